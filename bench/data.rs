@@ -0,0 +1,82 @@
+// Synthetic datasets shared by the criterion benchmarks. The same seed gives the same items on
+// every run, so results stay comparable between baselines.
+// Each benchmark uses only part of it.
+#![allow(dead_code)]
+
+use tascli::{
+    db::item::{
+        RECORD,
+        TASK,
+    },
+    Item,
+};
+
+const CATEGORIES: &[&str] = &[
+    "work", "home", "errands", "health", "finance", "reading", "garden", "travel", "music",
+    "family", "admin", "learning", "fitness", "cooking", "car", "taxes", "friends", "writing",
+    "side-project", "inbox",
+];
+
+const WORDS: &[&str] = &[
+    "buy", "milk", "call", "dentist", "review", "pull", "request", "write", "report", "pay",
+    "rent", "book", "flights", "clean", "garage", "plan", "sprint", "fix", "bug", "in", "parser",
+    "water", "plants", "send", "invoice", "to", "client", "read", "chapter", "update", "resume",
+    "renew", "passport", "prepare", "slides", "for", "meeting", "order", "groceries", "store",
+];
+
+pub const DAY: i64 = 86400;
+// A fixed clock, deadlines fall 60 days either side of it
+pub const NOW: i64 = 1_790_000_000;
+
+// Linear congruential generator, enough to spread the data without a dependency
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new() -> Self {
+        Rng(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % n
+    }
+}
+
+pub fn category(rng: &mut Rng) -> String {
+    CATEGORIES[rng.below(CATEGORIES.len())].to_string()
+}
+
+pub fn sentence(rng: &mut Rng, index: usize) -> String {
+    let count = 3 + rng.below(6);
+    let mut words: Vec<&str> = (0..count).map(|_| WORDS[rng.below(WORDS.len())]).collect();
+    let number = index.to_string();
+    words.push(&number);
+    words.join(" ")
+}
+
+/// Four tasks for every record, as in a typical db.
+pub fn items(count: usize) -> Vec<Item> {
+    let mut rng = Rng::new();
+    (0..count)
+        .map(|i| {
+            let category = category(&mut rng);
+            let content = sentence(&mut rng, i);
+            if i % 5 == 4 {
+                let created = NOW - rng.below(365) as i64 * DAY;
+                Item::with_create_time(RECORD.to_string(), category, content, created)
+            } else {
+                let deadline = NOW + (rng.below(120) as i64 - 60) * DAY;
+                Item::with_target_time(TASK.to_string(), category, content, Some(deadline))
+            }
+        })
+        .collect()
+}
+
+pub fn task_names(count: usize) -> Vec<String> {
+    let mut rng = Rng::new();
+    (0..count).map(|i| sentence(&mut rng, i)).collect()
+}
+
+pub fn categories(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("{}-{}", CATEGORIES[i % CATEGORIES.len()], i)).collect()
+}
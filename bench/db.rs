@@ -0,0 +1,120 @@
+mod data;
+
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BatchSize,
+    BenchmarkId,
+    Criterion,
+    Throughput,
+};
+use rusqlite::Connection;
+use tascli::{
+    actions::display::{
+        DisplayRow,
+        TableWriter,
+        DEFAULT_INDEX_WIDTH,
+        FIXED_WIDTH,
+    },
+    db::{
+        conn::{
+            atomically,
+            init_table,
+        },
+        crud::{
+            insert_item,
+            query_items,
+        },
+        item::TASK,
+    },
+    Item,
+    ItemQuery,
+    TascliError,
+};
+
+const SIZES: &[usize] = &[10_000, 100_000];
+// Ongoing, suspended and pending, the tasks `tascli list task` shows
+const OPEN_STATUSES: &[u8] = &[0, 4, 6];
+
+fn empty_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    init_table(&conn).unwrap();
+    conn
+}
+
+fn insert_all(conn: &Connection, items: &[Item]) {
+    atomically(conn, || -> Result<(), TascliError> {
+        for item in items {
+            insert_item(conn, item)?;
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
+// A db of `count` items where one task in five is done
+fn filled_db(count: usize) -> Connection {
+    let conn = empty_db();
+    insert_all(&conn, &data::items(count));
+    conn.execute("UPDATE items SET status = 1 WHERE action = 'task' AND id % 5 = 0", [])
+        .unwrap();
+    conn
+}
+
+fn open_tasks(conn: &Connection) -> Vec<Item> {
+    let query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUSES.to_vec())
+        .with_order_by("target_time");
+    query_items(conn, &query).unwrap()
+}
+
+fn bench_bulk_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_insert");
+    group.sample_size(10);
+    for &size in SIZES {
+        let items = data::items(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &items, |b, items| {
+            b.iter_batched(empty_db, |conn| insert_all(&conn, items), BatchSize::PerIteration)
+        });
+    }
+    group.finish();
+}
+
+fn bench_list_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_query");
+    group.sample_size(20);
+    for &size in SIZES {
+        let conn = filled_db(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| open_tasks(&conn)));
+    }
+    group.finish();
+}
+
+// Formatting and wrapping the rows of a listing, without writing them to the terminal
+fn bench_list_render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_render");
+    group.sample_size(20);
+    let table = TableWriter::with_width(false, DEFAULT_INDEX_WIDTH, FIXED_WIDTH, false);
+    for &size in SIZES {
+        let tasks = open_tasks(&filled_db(size));
+        group.throughput(Throughput::Elements(tasks.len() as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                tasks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, task)| {
+                        table.format_row(&DisplayRow::from_task((i + 1).to_string(), task)).len()
+                    })
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_bulk_insert, bench_list_query, bench_list_render);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+mod data;
+
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BenchmarkId,
+    Criterion,
+    Throughput,
+};
+use tascli::nlp::{
+    FuzzyMatcher,
+    PatternMatcher,
+};
+
+// Commands people type, the simple ones are matched without calling the api
+const INPUTS: &[&str] = &[
+    "add task buy milk tomorrow",
+    "task review pull request by friday 5pm",
+    "list tasks",
+    "show my tasks for today",
+    "done 3",
+    "mark 2 as done",
+    "delete 4",
+    "record went running 5km",
+    "list records from last week",
+    "what is overdue in work",
+    "remind me to call the dentist next tuesday at 3pm",
+    "move all my errands to the weekend unless they are urgent",
+];
+
+fn bench_pattern_match(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pattern_match");
+    group.throughput(Throughput::Elements(INPUTS.len() as u64));
+    group.bench_function("commands", |b| {
+        b.iter(|| INPUTS.iter().map(|input| PatternMatcher::match_input(input)).collect::<Vec<_>>())
+    });
+    group.finish();
+}
+
+fn bench_fuzzy_match(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fuzzy_match");
+    group.sample_size(20);
+    // A typo matches no category exactly, so every one is scored
+    let categories = data::categories(1_000);
+    group.bench_function("category/1000", |b| {
+        b.iter(|| FuzzyMatcher::match_category("wrok", &categories))
+    });
+    for size in [10_000, 100_000] {
+        let tasks = data::task_names(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("task", size), &tasks, |b, tasks| {
+            b.iter(|| FuzzyMatcher::match_task("grocries store order", tasks))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pattern_match, bench_fuzzy_match);
+criterion_main!(benches);
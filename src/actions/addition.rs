@@ -1,81 +1,189 @@
+use std::io;
+
 use rusqlite::Connection;
 
 use crate::{
-    actions::display,
+    actions::{
+        display,
+        modify::resolve_item,
+        project::check_deadline,
+        schedule::check_capacity,
+        template::{
+            fill_template,
+            FilledRecord,
+        },
+    },
     args::{
         parser::{
+            ItemRef,
             RecordCommand,
             TaskCommand,
         },
         timestr,
     },
+    clock,
+    config,
     db::{
-        crud::insert_item,
+        crud::{
+            get_item,
+            insert_item,
+        },
         item::{
             Item,
+            DEFAULT_CATEGORY,
             RECORD,
             TASK,
         },
+        tags::split_tags,
     },
+    error::TascliError,
+    events,
+    private,
+    tr,
 };
 
-pub fn handle_taskcmd(conn: &Connection, cmd: &TaskCommand) -> Result<(), String> {
-    let content = cmd.content.clone();
+pub fn handle_taskcmd(conn: &Connection, cmd: &TaskCommand) -> Result<(), TascliError> {
+    let (content, tags) = split_tags(&cmd.content);
+    if content.is_empty() {
+        return Err(TascliError::Invalid("A task needs a description besides its tags".to_string()));
+    }
+    let content = content_for(&content, cmd.private)?;
     let target_timestr = cmd.timestr.clone().unwrap_or_else(|| "today".to_string());
+    let parent = cmd.parent.as_ref().map(|parent| parent_task(conn, parent)).transpose()?;
+    // A subtask goes to the category of its parent
     let category: String = cmd
         .category
         .clone()
-        .unwrap_or_else(|| "default".to_string());
+        .or_else(|| parent.as_ref().map(|parent| parent.category.clone()))
+        .unwrap_or_else(|| DEFAULT_CATEGORY.to_string());
+    // Without a deadline, a task is due at the end of its category's SLA window
+    let target_time = match config::category_sla(&category).filter(|_| cmd.timestr.is_none()) {
+        Some(window) => Ok(timestr::window_deadline(window, clock::timestamp())
+            .map_err(|e| TascliError::Config(format!("SLA of {}: {}", category, e)))?),
+        None => timestr::to_unix_epoch(&target_timestr),
+    };
 
-    match timestr::to_unix_epoch(&target_timestr) {
+    match target_time {
         Ok(target_time) => {
-            let new_task =
+            check_deadline(conn, &category, target_time, cmd.strict)?;
+            check_capacity(conn, target_time)?;
+            let mut new_task =
                 Item::with_target_time(TASK.to_string(), category, content, Some(target_time));
-            insert_item(conn, &new_task).map_err(|e| e.to_string())?;
+            new_task.priority = cmd.priority.unwrap_or_default();
+            new_task.tags = tags;
+            new_task.parent_id = parent.and_then(|parent| parent.id);
+            new_task.id = Some(insert_item(conn, &new_task)?);
+            events::emit(conn, events::CREATE, &mut new_task);
 
-            display::print_bold("Inserted Task:");
+            display::print_bold(&tr!("inserted-task"));
             display::print_items(&[new_task], false, false);
             Ok(())
         }
         Err(_) => match timestr::parse_recurring_timestr(&target_timestr) {
+            Ok(_) if parent.is_some() => {
+                Err(TascliError::Invalid("A subtask cannot be recurring".to_string()))
+            }
             Ok(cron_schedule) => {
-                let new_recurring_task =
+                let mut new_recurring_task =
                     Item::create_recurring_task(category, content, cron_schedule, target_timestr);
-                insert_item(conn, &new_recurring_task).map_err(|e| e.to_string())?;
+                new_recurring_task.priority = cmd.priority.unwrap_or_default();
+                new_recurring_task.tags = tags;
+                new_recurring_task.id =
+                    Some(insert_item(conn, &new_recurring_task)?);
+                events::emit(conn, events::CREATE, &mut new_recurring_task);
 
-                display::print_bold("Inserted Recurring Task:");
+                display::print_bold(&tr!("inserted-recurring-task"));
                 display::print_items(&[new_recurring_task], false, false);
                 Ok(())
             }
-            Err(_) => Err(format!(
+            Err(_) => Err(TascliError::Parse(format!(
                 "Could not parse '{}' as a valid time or recurring schedule",
                 target_timestr
-            )),
+            ))),
         },
     }
 }
 
-pub fn handle_recordcmd(conn: &Connection, cmd: &RecordCommand) -> Result<(), String> {
-    let content = cmd.content.clone();
+// The task `--parent` refers to, records and recurring tasks have no subtasks
+fn parent_task(conn: &Connection, parent: &ItemRef) -> Result<Item, TascliError> {
+    let parent = get_item(conn, resolve_item(conn, parent)?)?;
+    if parent.action != TASK {
+        return Err(TascliError::Invalid(format!(
+            "Only tasks can have subtasks, this is a {}",
+            parent.action
+        )));
+    }
+    Ok(parent)
+}
+
+pub fn handle_recordcmd(conn: &Connection, cmd: &RecordCommand) -> Result<(), TascliError> {
+    // `tascli record workout` asks for the fields of the workout template
+    let template = config::record_template(&cmd.content);
+    let filled = match template {
+        Some(_) if cmd.private => {
+            return Err(TascliError::Invalid(
+                "Records made from a template cannot be private".to_string(),
+            ));
+        }
+        Some(template) => match fill_template(template, &mut io::stdin().lock())? {
+            Some(filled) => Some(filled),
+            None => {
+                display::print_bold("Record cancelled");
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    let content = match &filled {
+        Some(filled) => filled.content.clone(),
+        None => content_for(&cmd.content, cmd.private)?,
+    };
+    // Templated records go to the category named after the template
     let category: String = cmd
         .category
         .clone()
-        .unwrap_or_else(|| "default".to_string());
-    let new_record = match &cmd.timestr {
+        .or_else(|| template.map(|t| t.name.clone()))
+        .unwrap_or_else(|| DEFAULT_CATEGORY.to_string());
+    let mut new_record = match &cmd.timestr {
         Some(t) => {
             let create_time = timestr::to_unix_epoch(t)?;
             Item::with_create_time(RECORD.to_string(), category, content, create_time)
         }
         None => Item::new(RECORD.to_string(), category, content),
     };
+    new_record.value = cmd.value;
+    new_record.unit = cmd.unit.clone();
+    if let Some(FilledRecord {
+        fields,
+        value,
+        unit,
+        ..
+    }) = filled
+    {
+        new_record.fields = Some(fields);
+        // A --value given as well wins over the answers
+        if cmd.value.is_none() {
+            new_record.value = value;
+            new_record.unit = unit;
+        }
+    }
 
-    insert_item(conn, &new_record).map_err(|e| e.to_string())?;
+    new_record.id = Some(insert_item(conn, &new_record)?);
+    events::emit(conn, events::CREATE, &mut new_record);
 
-    display::print_bold("Inserted Record:");
+    display::print_bold(&tr!("inserted-record"));
     display::print_items(&[new_record], true, false);
     Ok(())
 }
 
+fn content_for(content: &str, is_private: bool) -> Result<String, TascliError> {
+    if is_private {
+        private::encrypt(content)
+    } else {
+        Ok(content.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,7 +195,11 @@ mod tests {
                 RECURRING_TASK,
             },
         },
-        tests::get_test_conn,
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_task,
+        },
     };
 
     #[test]
@@ -96,6 +208,10 @@ mod tests {
             content: String::from("complete testing of addition.rs"),
             category: None,
             timestr: None,
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         let (conn, _temp_file) = get_test_conn();
         handle_taskcmd(&conn, &tc).unwrap();
@@ -106,12 +222,65 @@ mod tests {
         assert_eq!(items[0].content, "complete testing of addition.rs");
     }
 
+    #[test]
+    fn test_tagged_task() {
+        let mut tc = TaskCommand {
+            content: String::from("buy milk +errands +Quick"),
+            category: None,
+            timestr: None,
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
+        };
+        let (conn, _temp_file) = get_test_conn();
+        handle_taskcmd(&conn, &tc).unwrap();
+        let items = query_items(&conn, &ItemQuery::new().with_tag("errands")).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].content, "buy milk");
+        assert_eq!(items[0].tags, ["errands", "quick"]);
+
+        tc.content = "+errands".to_string();
+        assert!(handle_taskcmd(&conn, &tc).is_err());
+    }
+
+    #[test]
+    fn test_subtask() {
+        let (conn, _temp_file) = get_test_conn();
+        let post = insert_task(&conn, "blog", "write post", "friday");
+        let mut tc = TaskCommand {
+            content: String::from("write intro"),
+            category: None,
+            timestr: None,
+            private: false,
+            strict: false,
+            priority: None,
+            parent: Some(ItemRef::Category("blog".to_string(), 1)),
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let subtasks = query_items(&conn, &ItemQuery::new().with_parent_id(post)).unwrap();
+        assert_eq!(subtasks.len(), 1);
+        assert_eq!(subtasks[0].content, "write intro");
+        assert_eq!(subtasks[0].category, "blog");
+
+        tc.timestr = Some("daily".to_string());
+        assert!(handle_taskcmd(&conn, &tc).is_err());
+        tc.timestr = None;
+        insert_record(&conn, "notes", "an idea", "today");
+        tc.parent = Some(ItemRef::Category("notes".to_string(), 1));
+        assert!(handle_taskcmd(&conn, &tc).is_err());
+    }
+
     #[test]
     fn test_filled_task() {
         let tc = TaskCommand {
             content: String::from("complete testing of addition.rs"),
             category: Some("fun".to_string()),
             timestr: Some("tomorrow".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         let (conn, _temp_file) = get_test_conn();
         handle_taskcmd(&conn, &tc).unwrap();
@@ -137,6 +306,9 @@ mod tests {
             content: String::from("100ML"),
             category: Some("feeding".to_string()),
             timestr: None,
+            private: false,
+            value: None,
+            unit: None,
         };
         let (conn, _temp_file) = get_test_conn();
         handle_recordcmd(&conn, &rc).unwrap();
@@ -153,6 +325,31 @@ mod tests {
         assert_eq!(items[0].content, "100ML");
     }
 
+    #[test]
+    fn test_record_with_value() {
+        let rc = RecordCommand {
+            content: String::from("weight"),
+            category: Some("health".to_string()),
+            timestr: None,
+            private: false,
+            value: Some(82.5),
+            unit: Some("kg".to_string()),
+        };
+        let (conn, _temp_file) = get_test_conn();
+        handle_recordcmd(&conn, &rc).unwrap();
+        handle_recordcmd(&conn, &RecordCommand { value: Some(84.0), ..rc }).unwrap();
+
+        let query = ItemQuery::new()
+            .with_action(RECORD)
+            .with_value_range(Some(80.0), Some(83.0));
+        let items = query_items(&conn, &query).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].value, Some(82.5));
+        assert_eq!(items[0].unit.as_deref(), Some("kg"));
+        let heavier = query_items(&conn, &query.with_value_range(Some(83.0), None)).unwrap();
+        assert_eq!(heavier[0].value, Some(84.0));
+    }
+
     #[test]
     fn test_recurring_task_patterns() {
         let (conn, _temp_file) = get_test_conn();
@@ -161,6 +358,10 @@ mod tests {
             content: String::from("Daily standup"),
             category: Some("work".to_string()),
             timestr: Some("Daily 9AM".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         handle_taskcmd(&conn, &daily).unwrap();
 
@@ -168,6 +369,10 @@ mod tests {
             content: String::from("Weekly meeting"),
             category: Some("meetings".to_string()),
             timestr: Some("Weekly Monday-Friday 2PM".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         handle_taskcmd(&conn, &weekly).unwrap();
 
@@ -175,6 +380,10 @@ mod tests {
             content: String::from("Monthly review"),
             category: Some("admin".to_string()),
             timestr: Some("Monthly 1st".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         handle_taskcmd(&conn, &monthly).unwrap();
 
@@ -206,6 +415,10 @@ mod tests {
             content: String::from("Finish report"),
             category: Some("work".to_string()),
             timestr: Some("tomorrow".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         handle_taskcmd(&conn, &regular_task).unwrap();
 
@@ -213,6 +426,10 @@ mod tests {
             content: String::from("Check emails"),
             category: Some("work".to_string()),
             timestr: Some("Daily 9AM".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         handle_taskcmd(&conn, &recurring_task).unwrap();
 
@@ -232,6 +449,10 @@ mod tests {
             content: String::from("Task"),
             category: None,
             timestr: Some("InvalidTimestr".to_string()),
+            private: false,
+            strict: false,
+            priority: None,
+            parent: None,
         };
         let (conn, _temp_file) = get_test_conn();
         let result = handle_taskcmd(&conn, &tc);
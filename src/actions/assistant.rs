@@ -0,0 +1,341 @@
+//! `tascli ai --non-interactive`, natural language commands that never prompt, for voice
+//! assistants and bots.
+
+use nanoserde::SerJson;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            capture_items,
+            print_yellow,
+            set_porcelain,
+            take_captured_items,
+        },
+        nlp::{
+            create_parser,
+            execute_parsed_command,
+            remember_clarification,
+            require_provider,
+            unmet_condition,
+        },
+    },
+    args::parser::NLPCommand,
+    db::{
+        crud::query_categories,
+        item::Item,
+    },
+    error::TascliError,
+    nlp::{
+        cancel,
+        parse_clarification,
+        ActionType,
+        ErrorCategory,
+        ErrorRecoveryEngine,
+        NLPConfig,
+        NLPError,
+        RecoveryResult,
+        LOW_CONFIDENCE,
+    },
+    private,
+};
+
+pub const EXECUTED: &str = "executed";
+pub const CLARIFICATION: &str = "clarification";
+pub const ERROR: &str = "error";
+
+/// The answer to a non-interactive command, printed as json with `--json`.
+#[derive(Debug, Default, SerJson)]
+pub struct Reply {
+    // executed, clarification or error
+    pub status: String,
+    pub input: String,
+    pub interpretation: Option<String>,
+    // The tascli commands the input was read as, in order
+    pub commands: Vec<ReplyCommand>,
+    // Items the commands printed
+    pub items: Vec<ReplyItem>,
+    pub clarification: Option<Clarification>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, SerJson)]
+pub struct ReplyCommand {
+    pub args: Vec<String>,
+    // False when it was not run because a clarification is needed first
+    pub ran: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, SerJson)]
+pub struct ReplyItem {
+    pub index: String,
+    pub uuid: String,
+    pub action: String,
+    pub category: String,
+    pub content: String,
+    pub status: u8,
+    pub target_time: Option<i64>,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// What to ask the user before the input can run.
+#[derive(Debug, PartialEq, SerJson)]
+pub struct Clarification {
+    // unclear, ambiguous, confirm_delete or low_confidence
+    pub reason: String,
+    pub question: String,
+    pub options: Vec<String>,
+}
+
+impl ReplyItem {
+    fn new(index: String, item: &Item) -> Self {
+        Self {
+            index,
+            uuid: item.uuid.clone(),
+            action: item.action.clone(),
+            category: item.category.clone(),
+            content: private::reveal(&item.content),
+            status: item.status,
+            target_time: item.target_time,
+            value: item.value,
+            unit: item.unit.clone(),
+        }
+    }
+}
+
+impl Clarification {
+    // Only input that was not understood is answered with a question, a provider that cannot
+    // be reached is an error
+    fn from_error(error: &NLPError, input: &str, categories: &[String]) -> Option<Self> {
+        let category = ErrorRecoveryEngine::categorize_error(error);
+        if category != ErrorCategory::Parse && category != ErrorCategory::Validation {
+            return None;
+        }
+        let (reason, question, options) =
+            match ErrorRecoveryEngine::handle_error(error, input, categories) {
+                RecoveryResult::ClarificationNeeded(request) => (
+                    "unclear",
+                    request.question,
+                    request.options.into_iter().map(|option| option.value).collect(),
+                ),
+                RecoveryResult::DisambiguationNeeded(disambiguation) => (
+                    "ambiguous",
+                    disambiguation.prompt,
+                    disambiguation.candidates.into_iter().map(|c| c.value).collect(),
+                ),
+                RecoveryResult::Suggestion(suggestions) => {
+                    ("unclear", error.to_string(), suggestions)
+                }
+                RecoveryResult::Guided(guide) => {
+                    ("unclear", error.to_string(), vec![guide.example])
+                }
+                RecoveryResult::Unrecoverable(_) => return None,
+            };
+        Some(Self {
+            reason: reason.to_string(),
+            question,
+            options,
+        })
+    }
+
+    // Deletions and interpretations corrected before are confirmed by the user, which a
+    // command that never prompts cannot do
+    fn before_running(command: &crate::nlp::NLPCommand, description: &str) -> Option<Self> {
+        let deletes = command.action == ActionType::Delete
+            || command.compound_commands.iter().flatten().any(|c| c.action == ActionType::Delete);
+        let reason = if deletes {
+            "confirm_delete"
+        } else if command.confidence.is_some_and(|confidence| confidence < LOW_CONFIDENCE) {
+            "low_confidence"
+        } else {
+            return None;
+        };
+        Some(Self {
+            reason: reason.to_string(),
+            question: format!("Did you mean: {}?", description),
+            options: Vec::new(),
+        })
+    }
+}
+
+/// Runs a natural language command without prompting. It either runs and prints what it did,
+/// or answers with the clarification it needs. With `--json` the answer is a json object on
+/// stdout and messages go to stderr.
+pub async fn answer(
+    conn: &Connection,
+    cmd: &NLPCommand,
+    nlp_config: &NLPConfig,
+) -> Result<(), TascliError> {
+    if cmd.json {
+        set_porcelain(true);
+        capture_items();
+    }
+    let (mut reply, error) = reply_to(conn, &cmd.description, nlp_config).await;
+    reply.items = take_captured_items()
+        .into_iter()
+        .map(|(index, item)| ReplyItem::new(index, &item))
+        .collect();
+    if cmd.json {
+        println!("{}", reply.serialize_json());
+    } else if let Some(clarification) = &reply.clarification {
+        print_yellow(&clarification.question);
+        for option in &clarification.options {
+            println!("  - {}", option);
+        }
+    }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+// The reply, and the error to exit with when it failed
+async fn reply_to(
+    conn: &Connection,
+    input: &str,
+    nlp_config: &NLPConfig,
+) -> (Reply, Option<TascliError>) {
+    let mut reply = Reply {
+        status: ERROR.to_string(),
+        input: input.to_string(),
+        ..Default::default()
+    };
+    let fail = |mut reply: Reply, error: TascliError| {
+        reply.message = Some(error.to_string());
+        (reply, Some(error))
+    };
+
+    if let Some((phrase, category)) = parse_clarification(input) {
+        return match remember_clarification(&phrase, &category) {
+            Ok(()) => {
+                reply.status = EXECUTED.to_string();
+                reply.message = Some(format!("'{}' means category {}", phrase, category));
+                (reply, None)
+            }
+            Err(error) => fail(reply, error),
+        };
+    }
+    if let Err(error) = require_provider(nlp_config) {
+        return fail(reply, error);
+    }
+
+    let categories = query_categories(conn).unwrap_or_default();
+    let parser = create_parser(conn, nlp_config, categories.clone()).await;
+    let (all_args, description, command) =
+        match cancel::abortable(parser.parse_to_compound_args_with_transparency(input)).await {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                if let Some(clarification) = Clarification::from_error(&error, input, &categories) {
+                    reply.status = CLARIFICATION.to_string();
+                    reply.clarification = Some(clarification);
+                    return (reply, None);
+                }
+                return fail(reply, error.into());
+            }
+        };
+    reply.commands = all_args
+        .into_iter()
+        .map(|args| ReplyCommand {
+            args,
+            ran: false,
+            error: None,
+        })
+        .collect();
+    if let Some(clarification) = Clarification::before_running(&command, &description) {
+        reply.interpretation = Some(description);
+        reply.status = CLARIFICATION.to_string();
+        reply.clarification = Some(clarification);
+        return (reply, None);
+    }
+    reply.interpretation = Some(description);
+    if !command.is_compound() {
+        match unmet_condition(conn, &command) {
+            Ok(Some(reasons)) => {
+                reply.status = EXECUTED.to_string();
+                reply.message = Some(format!("Skipped, the condition is not met: {}", reasons));
+                return (reply, None);
+            }
+            Ok(None) => {}
+            Err(error) => return fail(reply, error),
+        }
+    }
+
+    // Like compound commands in the terminal, a failed command does not stop the next
+    let mut first_error = None;
+    for reply_command in &mut reply.commands {
+        reply_command.ran = true;
+        if let Err(error) = execute_parsed_command(conn, &reply_command.args) {
+            reply_command.error = Some(error.to_string());
+            first_error.get_or_insert(error);
+        }
+    }
+    match first_error {
+        Some(error) => fail(reply, error),
+        None => {
+            if !command.is_compound() {
+                parser.record_category(&command).await;
+            }
+            parser.record_input(input).await;
+            reply.status = EXECUTED.to_string();
+            (reply, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clarification_before_running() {
+        let mut command = crate::nlp::NLPCommand {
+            action: ActionType::Task,
+            content: "buy milk".to_string(),
+            confidence: Some(0.95),
+            ..Default::default()
+        };
+        assert_eq!(Clarification::before_running(&command, "add task"), None);
+
+        command.confidence = Some(LOW_CONFIDENCE - 0.1);
+        let clarification = Clarification::before_running(&command, "add task").unwrap();
+        assert_eq!(clarification.reason, "low_confidence");
+        assert_eq!(clarification.question, "Did you mean: add task?");
+
+        command.confidence = Some(0.95);
+        command.compound_commands = Some(vec![crate::nlp::NLPCommand {
+            action: ActionType::Delete,
+            ..Default::default()
+        }]);
+        let clarification = Clarification::before_running(&command, "add and delete").unwrap();
+        assert_eq!(clarification.reason, "confirm_delete");
+    }
+
+    #[test]
+    fn test_clarification_from_error() {
+        let unclear = NLPError::ParseError("no action".to_string());
+        let clarification = Clarification::from_error(&unclear, "blah", &[]).unwrap();
+        assert!(!clarification.question.is_empty());
+
+        let offline = NLPError::RateLimited;
+        assert_eq!(Clarification::from_error(&offline, "add task", &[]), None);
+    }
+
+    #[test]
+    fn test_reply_json() {
+        let reply = Reply {
+            status: CLARIFICATION.to_string(),
+            input: "remove 4".to_string(),
+            clarification: Some(Clarification {
+                reason: "confirm_delete".to_string(),
+                question: "Did you mean: delete 4?".to_string(),
+                options: Vec::new(),
+            }),
+            ..Default::default()
+        };
+        let json = reply.serialize_json();
+        assert!(json.contains(r#""status":"clarification""#));
+        assert!(json.contains(r#""reason":"confirm_delete""#));
+    }
+}
@@ -0,0 +1,174 @@
+//! `tascli promote` turns a record into a task and `tascli log-done` a task into a record,
+//! keeping the id, category number and tags of the item.
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        inbox::file_as_task,
+        list::OPEN_STATUS_CODES,
+        modify::{
+            resolve_item,
+            resolve_or_pick,
+        },
+    },
+    args::{
+        parser::{
+            LogDoneCommand,
+            PromoteCommand,
+        },
+        timestr,
+    },
+    clock,
+    db::{
+        conn::atomically,
+        crud::{
+            get_item,
+            update_item,
+        },
+        item::{
+            Item,
+            RECORD,
+            RECURRING_TASK,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+        reminders,
+    },
+    error::TascliError,
+    events,
+};
+
+pub fn handle_promotecmd(conn: &Connection, cmd: &PromoteCommand) -> Result<(), TascliError> {
+    let row_id = resolve_item(conn, &cmd.index)?;
+    let item = get_item(conn, row_id)?;
+    let category = cmd.category.clone().unwrap_or_else(|| item.category.clone());
+    promote(conn, item, &category, cmd.timestr.as_deref().unwrap_or_default())?;
+
+    display::print_bold("Promoted to task:");
+    display::print_items(&[get_item(conn, row_id)?], false, false);
+    Ok(())
+}
+
+pub fn handle_logdonecmd(conn: &Connection, cmd: &LogDoneCommand) -> Result<(), TascliError> {
+    let row_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let item = get_item(conn, row_id)?;
+    let done_time = match &cmd.timestr {
+        Some(t) => timestr::to_unix_epoch(t)?,
+        None => clock::timestamp(),
+    };
+    let record = log_done(conn, item, done_time)?;
+
+    display::print_bold("Logged as record:");
+    display::print_items(&[record], true, false);
+    Ok(())
+}
+
+// Files the record as a task due at `timestr`, EOD when empty, as if it had been added
+fn promote(
+    conn: &Connection,
+    item: Item,
+    category: &str,
+    timestr: &str,
+) -> Result<(), TascliError> {
+    match item.action.as_str() {
+        RECORD => file_as_task(conn, item, category, timestr),
+        RECURRING_TASK_RECORD => Err(TascliError::Invalid(
+            "Completions of recurring tasks cannot be promoted".to_string(),
+        )),
+        action => Err(TascliError::Invalid(format!(
+            "Only records can be promoted, this is a {}",
+            action
+        ))),
+    }
+}
+
+// The task becomes a record made at `done_time`, without its deadline or reminders. Unlike
+// `tascli done` no completion record is added, the record is the task itself.
+fn log_done(conn: &Connection, mut item: Item, done_time: i64) -> Result<Item, TascliError> {
+    if item.action == RECURRING_TASK {
+        return Err(TascliError::Invalid(
+            "Recurring tasks are completed with `tascli done`, the next one is still due"
+                .to_string(),
+        ));
+    }
+    if item.action != TASK {
+        return Err(TascliError::Invalid(format!(
+            "Only tasks can be logged as done, this is a {}",
+            item.action
+        )));
+    }
+    if !OPEN_STATUS_CODES.contains(&item.status) {
+        return Err(TascliError::Invalid("Task is already closed".to_string()));
+    }
+
+    item.action = RECORD.to_string();
+    item.create_time = done_time;
+    item.set_target_time(None);
+    item.status = 0;
+    item.priority = 0;
+    let row_id = item.id.unwrap();
+    atomically(conn, || {
+        update_item(conn, &item)?;
+        reminders::delete_reminders(conn, row_id)
+    })?;
+    events::emit(conn, events::DONE, &mut item);
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::tags::set_tags,
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_task,
+            update_status,
+        },
+    };
+
+    #[test]
+    fn test_promote() {
+        let (conn, _temp_file) = get_test_conn();
+        let idea = insert_record(&conn, "ideas", "write a blog post", "today");
+        let item = get_item(&conn, idea).unwrap();
+        promote(&conn, item, "writing", "friday").unwrap();
+
+        let task = get_item(&conn, idea).unwrap();
+        assert_eq!(task.action, TASK);
+        assert_eq!(task.category, "writing");
+        assert_eq!(task.content, "write a blog post");
+        assert_eq!(task.target_time, Some(timestr::to_unix_epoch("friday").unwrap()));
+
+        let item = get_item(&conn, idea).unwrap();
+        assert!(promote(&conn, item, "writing", "").is_err());
+    }
+
+    #[test]
+    fn test_log_done() {
+        let (conn, _temp_file) = get_test_conn();
+        let call = insert_task(&conn, "home", "call the plumber", "tomorrow");
+        set_tags(&conn, call, &["errands".to_string()]).unwrap();
+        reminders::set_reminders(&conn, call, &[3600]).unwrap();
+        let done_time = timestr::to_unix_epoch("today 10:00").unwrap();
+        log_done(&conn, get_item(&conn, call).unwrap(), done_time).unwrap();
+
+        let record = get_item(&conn, call).unwrap();
+        assert_eq!(record.action, RECORD);
+        assert_eq!(record.create_time, done_time);
+        assert_eq!(record.target_time, None);
+        assert_eq!(record.content, "call the plumber");
+        assert_eq!(record.tags, ["errands"]);
+        assert!(reminders::get_reminders(&conn, call).unwrap().is_empty());
+
+        // Records and closed tasks stay as they are
+        assert!(log_done(&conn, record, done_time).is_err());
+        let closed = insert_task(&conn, "home", "fix the sink", "today");
+        update_status(&conn, closed, 1);
+        assert!(log_done(&conn, get_item(&conn, closed).unwrap(), done_time).is_err());
+        assert_eq!(get_item(&conn, closed).unwrap().action, TASK);
+    }
+}
@@ -0,0 +1,200 @@
+use chrono::{
+    DateTime,
+    Local,
+    TimeZone,
+    Timelike,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::{
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+    },
+    args::{
+        parser::DigestCommand,
+        timestr,
+    },
+    clock,
+    config::{
+        self,
+        current_user,
+    },
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+    integrations::email,
+    private,
+};
+
+pub struct Digest {
+    pub overdue: Vec<Item>,
+    pub due_today: Vec<Item>,
+}
+
+impl Digest {
+    pub fn is_empty(&self) -> bool {
+        self.overdue.is_empty() && self.due_today.is_empty()
+    }
+}
+
+pub fn handle_digestcmd(conn: &Connection, cmd: &DigestCommand) -> Result<(), TascliError> {
+    let now = clock::now();
+    let digest = build_digest(conn, cmd.category.as_deref(), now.timestamp())?;
+    if digest.is_empty() && cmd.skip_empty {
+        return Ok(());
+    }
+
+    let subject = format!(
+        "tascli digest: {} overdue, {} due today",
+        digest.overdue.len(),
+        digest.due_today.len()
+    );
+    let body = format_digest(&digest, now);
+
+    match &cmd.email {
+        Some(to) => {
+            let config = config::get_config()?;
+            email::send_email(&config.smtp, to, &subject, &body)?;
+            display::print_green(&format!("Digest sent to {}", to));
+        }
+        None => print!("{}", body),
+    }
+    Ok(())
+}
+
+pub fn build_digest(
+    conn: &Connection,
+    category: Option<&str>,
+    now: i64,
+) -> Result<Digest, TascliError> {
+    let end_of_today = timestr::to_unix_epoch("today")?;
+
+    let mut overdue_query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(now)
+        .with_order_by(TARGET_TIME_COL);
+    let mut today_query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_range(Some(now), Some(end_of_today))
+        .with_order_by(TARGET_TIME_COL);
+    if let Some(cat) = category {
+        overdue_query = overdue_query.with_category(cat);
+        today_query = today_query.with_category(cat);
+    }
+
+    Ok(Digest {
+        overdue: query_items(conn, &overdue_query)?,
+        due_today: query_items(conn, &today_query)?,
+    })
+}
+
+pub fn format_digest(digest: &Digest, now: DateTime<Local>) -> String {
+    let mut body = format!("tascli digest for {}\n", now.format("%A %Y/%m/%d"));
+    if digest.is_empty() {
+        body.push_str("\nNothing overdue or due today.\n");
+        return body;
+    }
+
+    body.push_str(&format!("\nOverdue ({}):\n", digest.overdue.len()));
+    for task in &digest.overdue {
+        body.push_str(&format_line(task, true));
+    }
+    body.push_str(&format!("\nDue today ({}):\n", digest.due_today.len()));
+    for task in &digest.due_today {
+        body.push_str(&format_line(task, false));
+    }
+    body
+}
+
+// Overdue tasks show their due date, tasks due today show the time unless due by end of day.
+fn format_line(task: &Item, show_date: bool) -> String {
+    let content = private::conceal(&task.content);
+    let content = content.lines().next().unwrap_or_default();
+    let due = match task.target_time.and_then(|t| Local.timestamp_opt(t, 0).single()) {
+        Some(dt) if show_date => format!(" (due {})", dt.format("%Y/%m/%d")),
+        Some(dt) if !(dt.hour() == 23 && dt.minute() == 59) => {
+            format!(" ({})", dt.format("%-I:%M%p"))
+        }
+        _ => String::new(),
+    };
+    format!("  - [{}] {}{}\n", task.category, content, due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+        pin_clock,
+        update_status,
+    };
+
+    #[test]
+    fn test_build_digest() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "overdue report", "yesterday");
+        insert_task(&conn, "home", "water plants", "today");
+        insert_task(&conn, "work", "future task", "tomorrow");
+        let done = insert_task(&conn, "work", "finished task", "yesterday");
+        update_status(&conn, done, 1);
+
+        let now = Local::now().timestamp();
+        let digest = build_digest(&conn, None, now).unwrap();
+        assert_eq!(digest.overdue.len(), 1);
+        assert_eq!(digest.overdue[0].content, "overdue report");
+        assert_eq!(digest.due_today.len(), 1);
+        assert_eq!(digest.due_today[0].content, "water plants");
+
+        let digest = build_digest(&conn, Some("home"), now).unwrap();
+        assert!(digest.overdue.is_empty());
+        assert_eq!(digest.due_today.len(), 1);
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now();
+        let digest = build_digest(&conn, None, now.timestamp()).unwrap();
+        assert!(format_digest(&digest, now).contains("Nothing overdue or due today."));
+
+        insert_task(&conn, "work", "overdue report\nwith details", "yesterday");
+        insert_task(&conn, "home", "water plants", "today");
+        let digest = build_digest(&conn, None, now.timestamp()).unwrap();
+        let body = format_digest(&digest, now);
+        assert!(body.contains("Overdue (1):\n  - [work] overdue report (due "));
+        assert!(body.contains("Due today (1):\n  - [home] water plants\n"));
+        assert!(!body.contains("with details"));
+    }
+
+    // The digest is the agenda of the day, compared with the snapshot next to this file
+    #[test]
+    fn test_digest_snapshot() {
+        let (conn, _temp_file) = get_test_conn();
+        pin_clock("2026/10/15 07:00");
+        insert_task(&conn, "work", "send the invoice", "2026/10/12");
+        insert_task(&conn, "home", "call the plumber", "yesterday 6PM");
+        insert_task(&conn, "work", "standup notes", "9:30AM");
+        insert_task(&conn, "home", "water plants", "today");
+        insert_task(&conn, "work", "plan the offsite", "tomorrow");
+        let done = insert_task(&conn, "work", "book the room", "today");
+        update_status(&conn, done, 1);
+
+        let digest = build_digest(&conn, None, clock::timestamp()).unwrap();
+        insta::assert_snapshot!(format_digest(&digest, clock::now()));
+    }
+}
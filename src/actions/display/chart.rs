@@ -0,0 +1,154 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::actions::display::{
+    is_plain,
+    print_bold,
+    terminal_width,
+};
+
+// print rows as a horizontal bar chart scaled to the terminal width.
+pub fn print_bar_chart(title: &str, rows: &[(String, f64)]) {
+    print_bold(title);
+    let lines = if is_plain() {
+        rows.iter()
+            .map(|(label, value)| format!("{}: {}", label, format_value(*value)))
+            .collect()
+    } else {
+        format_bar_chart(rows, terminal_width())
+    };
+    for line in lines {
+        println!("{}", line);
+    }
+    println!();
+}
+
+pub fn format_bar_chart(rows: &[(String, f64)], width: usize) -> Vec<String> {
+    let label_width = rows.iter().map(|(l, _)| l.width()).max().unwrap_or(0);
+    let values: Vec<String> = rows.iter().map(|(_, v)| format_value(*v)).collect();
+    let value_width = values.iter().map(String::len).max().unwrap_or(0);
+    let max = rows.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    // label, two spaces, bar, one space, value
+    let bar_width = width.saturating_sub(label_width + value_width + 3).max(1);
+
+    rows.iter()
+        .zip(values)
+        .map(|((label, value), valuestr)| {
+            let len = if max > 0.0 {
+                (value / max * bar_width as f64).round() as usize
+            } else {
+                0
+            };
+            let padding = label_width - label.width();
+            format!(
+                "{}{}  {} {}",
+                label,
+                " ".repeat(padding),
+                "█".repeat(len),
+                valuestr
+            )
+        })
+        .collect()
+}
+
+// print values as vertical columns, one per value, with a y axis on the left.
+pub fn print_column_chart(title: &str, values: &[usize], height: usize) {
+    print_bold(title);
+    if is_plain() {
+        let values: Vec<String> = values.iter().map(usize::to_string).collect();
+        println!("{}", values.join(", "));
+        return;
+    }
+    for line in format_column_chart(values, height) {
+        println!("{}", line);
+    }
+}
+
+pub fn format_column_chart(values: &[usize], height: usize) -> Vec<String> {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    let height = height.clamp(1, max);
+    let axis_width = max.to_string().len();
+
+    let mut lines: Vec<String> = (1..=height)
+        .rev()
+        .map(|row| {
+            // Smallest value that fills this row
+            let threshold = (row * max).div_ceil(height);
+            let label = if row == height || row == 1 {
+                threshold.to_string()
+            } else {
+                String::new()
+            };
+            let columns: String = values
+                .iter()
+                .map(|v| if *v >= threshold { '█' } else { ' ' })
+                .collect();
+            format!("{:>width$} │{}", label, columns.trim_end(), width = axis_width)
+        })
+        .collect();
+    lines.push(format!("{:>width$} └{}", 0, "─".repeat(values.len()), width = axis_width));
+    lines
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::display::FIXED_WIDTH;
+
+    #[test]
+    fn test_format_bar_chart() {
+        let rows = vec![
+            ("Mon".to_string(), 4.0),
+            ("Tuesday".to_string(), 2.0),
+            ("Wed".to_string(), 0.0),
+            ("Thu".to_string(), 1.5),
+        ];
+        let lines = format_bar_chart(&rows, 22);
+        assert_eq!(
+            lines,
+            vec![
+                "Mon      █████████ 4",
+                "Tuesday  █████ 2",
+                "Wed       0",
+                "Thu      ███ 1.5",
+            ]
+        );
+        assert!(format_bar_chart(&[], 80).is_empty());
+    }
+
+    #[test]
+    fn test_format_column_chart() {
+        assert_eq!(
+            format_column_chart(&[4, 3, 1, 0, 2], 4),
+            vec![
+                "4 │█",
+                "  │██",
+                "  │██  █",
+                "1 │███ █",
+                "0 └─────",
+            ]
+        );
+        // Taller than the largest value, one row per unit
+        assert_eq!(format_column_chart(&[1, 0], 10), vec!["1 │█", "0 └──"]);
+    }
+
+    // Charts of the reports at the fixed width, compared with the snapshot next to this file
+    #[test]
+    fn test_report_charts_snapshot() {
+        let rows = vec![
+            ("Ongoing (12 tasks)".to_string(), 3.5),
+            ("Suspended (2 tasks)".to_string(), 9.0),
+            ("Pending (4 tasks)".to_string(), 0.25),
+        ];
+        let mut lines = format_bar_chart(&rows, FIXED_WIDTH);
+        lines.extend(format_column_chart(&[9, 8, 8, 6, 5, 5, 3, 1, 0, 0], 5));
+        insta::assert_snapshot!(lines.join("\n"));
+    }
+}
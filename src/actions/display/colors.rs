@@ -0,0 +1,73 @@
+use crate::config::{
+    self,
+    ColorPalette,
+};
+
+/// Colors of the output as escape codes and 256 color numbers. Each palette keeps errors,
+/// successes and the heatmap levels apart for the eyes it is made for.
+#[derive(Debug, PartialEq)]
+pub struct Colors {
+    // Errors and overdue deadlines
+    pub alert: &'static str,
+    pub success: &'static str,
+    pub warning: &'static str,
+    // Heatmap cells from no completions to the busiest days
+    pub heat: [u8; 5],
+    // Heatmap cells of missed habits
+    pub missed: u8,
+}
+
+const DEFAULT: Colors = Colors {
+    alert: "91",
+    success: "92",
+    warning: "93",
+    heat: [238, 22, 28, 34, 46],
+    missed: 124,
+};
+
+// Blue against orange, which stay apart without green cones
+const DEUTERANOPIA: Colors = Colors {
+    alert: "38;5;208",
+    success: "38;5;39",
+    warning: "38;5;226",
+    heat: [238, 17, 19, 27, 39],
+    missed: 208,
+};
+
+// Reds look dark without red cones, alerts are a light orange
+const PROTANOPIA: Colors = Colors {
+    alert: "38;5;214",
+    success: "38;5;33",
+    warning: "38;5;229",
+    heat: [238, 23, 30, 37, 51],
+    missed: 214,
+};
+
+/// The colors of the configured palette.
+pub fn colors() -> &'static Colors {
+    palette_colors(config::color_palette())
+}
+
+fn palette_colors(palette: ColorPalette) -> &'static Colors {
+    match palette {
+        ColorPalette::Default => &DEFAULT,
+        ColorPalette::Deuteranopia => &DEUTERANOPIA,
+        ColorPalette::Protanopia => &PROTANOPIA,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_colors() {
+        assert_eq!(colors(), &DEFAULT);
+        let deuteranopia = palette_colors(ColorPalette::Deuteranopia);
+        // No red or green left to tell apart
+        assert_ne!(deuteranopia.alert, DEFAULT.alert);
+        assert_ne!(deuteranopia.success, DEFAULT.success);
+        assert_ne!(deuteranopia.heat, DEFAULT.heat);
+        assert_ne!(palette_colors(ColorPalette::Protanopia), deuteranopia);
+    }
+}
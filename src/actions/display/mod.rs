@@ -1,15 +1,53 @@
+mod chart;
+mod colors;
+mod porcelain;
 mod print;
 mod row;
 mod table;
 
 pub use crate::actions::display::{
+    chart::{
+        print_bar_chart,
+        print_column_chart,
+    },
+    colors::{
+        colors,
+        Colors,
+    },
+    porcelain::{
+        capture_items,
+        is_porcelain,
+        print_porcelain_item,
+        print_porcelain_row,
+        set_porcelain,
+        take_captured_items,
+        PORCELAIN_VERSION,
+    },
     print::{
+        is_deterministic,
+        is_plain,
         print_bold,
         print_green,
         print_items,
         print_red,
         print_yellow,
+        set_deterministic,
+        set_plain,
+        shows_emoji,
+        terminal_width,
+        FIXED_WIDTH,
+    },
+    row::{
+        format_value,
+        translate_status,
+        DisplayRow,
+    },
+    table::{
+        display_index,
+        index_width,
+        print_table,
+        tree_order,
+        TableWriter,
+        DEFAULT_INDEX_WIDTH,
     },
-    row::DisplayRow,
-    table::print_table,
 };
@@ -0,0 +1,151 @@
+use std::sync::{
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    Mutex,
+};
+
+use chrono::{
+    Local,
+    TimeZone,
+};
+
+use crate::{
+    actions::display::DisplayRow,
+    db::item::Item,
+    private,
+};
+
+/// Raised whenever a field is added, removed or changes meaning.
+pub const PORCELAIN_VERSION: u32 = 1;
+
+static PORCELAIN: AtomicBool = AtomicBool::new(false);
+static HEADER_PRINTED: AtomicBool = AtomicBool::new(false);
+// Items collected instead of printed, for callers that answer with json
+static CAPTURED: Mutex<Option<Vec<(String, Item)>>> = Mutex::new(None);
+
+// Untranslated, unlike the status shown in tables
+const STATUS_NAMES: [&str; 7] = [
+    "ongoing",
+    "done",
+    "cancelled",
+    "duplicate",
+    "suspended",
+    "removed",
+    "pending",
+];
+
+/// Output for scripts: one tab separated line per item on stdout, everything else on stderr.
+pub fn set_porcelain(porcelain: bool) {
+    PORCELAIN.store(porcelain, Ordering::Relaxed);
+}
+
+pub fn is_porcelain() -> bool {
+    PORCELAIN.load(Ordering::Relaxed)
+}
+
+/// Collects the porcelain items from now on instead of printing them.
+pub fn capture_items() {
+    *CAPTURED.lock().unwrap() = Some(Vec::new());
+}
+
+/// The items collected since `capture_items`, with their index, and stops collecting.
+pub fn take_captured_items() -> Vec<(String, Item)> {
+    CAPTURED.lock().unwrap().take().unwrap_or_default()
+}
+
+/// Prints an item as a porcelain line, after the version line for the first one.
+pub fn print_porcelain_item(index: &str, item: &Item) {
+    if let Some(captured) = CAPTURED.lock().unwrap().as_mut() {
+        captured.push((index.to_string(), item.clone()));
+        return;
+    }
+    print_header();
+    println!("{}", format_item(index, item));
+}
+
+/// Tables that do not list items print their columns as they are.
+pub fn print_porcelain_row(row: &DisplayRow) {
+    let fields = [&row.index, &row.category, &row.timestr, &row.content];
+    let fields: Vec<String> = fields.iter().map(|f| escape(f)).collect();
+    // Stdout is kept for the captured items
+    if CAPTURED.lock().unwrap().is_some() {
+        eprintln!("{}", fields.join("\t"));
+        return;
+    }
+    print_header();
+    println!("{}", fields.join("\t"));
+}
+
+fn print_header() {
+    if !HEADER_PRINTED.swap(true, Ordering::Relaxed) {
+        println!("# tascli porcelain {}", PORCELAIN_VERSION);
+    }
+}
+
+// index, uuid, type, category, status, deadline, created, value, unit, content
+fn format_item(index: &str, item: &Item) -> String {
+    let status = if item.action == "task" || item.action == "inbox" {
+        STATUS_NAMES.get(item.status as usize).copied().unwrap_or("unknown")
+    } else {
+        ""
+    };
+    let fields = [
+        index.to_string(),
+        item.uuid.clone(),
+        item.action.clone(),
+        item.category.clone(),
+        status.to_string(),
+        item.target_time.map(format_time).unwrap_or_default(),
+        format_time(item.create_time),
+        item.value.map(|v| v.to_string()).unwrap_or_default(),
+        item.unit.clone().unwrap_or_default(),
+        private::reveal(&item.content),
+    ];
+    let fields: Vec<String> = fields.iter().map(|f| escape(f)).collect();
+    fields.join("\t")
+}
+
+fn format_time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string())
+        .unwrap_or_default()
+}
+
+// Tabs and line breaks would split fields and lines
+fn escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::item::TASK;
+
+    #[test]
+    fn test_format_item() {
+        let mut task = Item::with_target_time(
+            TASK.to_string(),
+            "work".to_string(),
+            "line one\nline\ttwo".to_string(),
+            Some(0),
+        );
+        task.status = 6;
+        let line = format_item("3", &task);
+        let fields: Vec<&str> = line.split('\t').collect();
+        assert_eq!(fields.len(), 10);
+        assert_eq!(fields[0], "3");
+        assert_eq!(fields[1], task.uuid);
+        assert_eq!(&fields[2..5], ["task", "work", "pending"]);
+        assert_eq!(fields[5], format_time(0));
+        assert_eq!(&fields[7..9], ["", ""]);
+        assert_eq!(fields[9], "line one\\nline\\ttwo");
+    }
+}
@@ -1,11 +1,99 @@
+use std::{
+    env,
+    sync::atomic::{
+        AtomicBool,
+        Ordering,
+    },
+};
+
+use terminal_size::{
+    terminal_size,
+    Width,
+};
+
 use crate::{
     actions::display::{
+        colors,
+        display_index,
+        is_porcelain,
+        print_porcelain_item,
         print_table,
         DisplayRow,
     },
     db::item::Item,
+    tr,
 };
 
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Output for screen readers: no colors, grids or bar charts, tables become labeled lines.
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+/// Width tables and charts are laid out for when it cannot be detected or output is deterministic.
+pub const FIXED_WIDTH: usize = 120;
+
+/// Output that is the same on every terminal, for comparing runs: tables and charts are laid out
+/// for a fixed width and there are no colors.
+pub fn set_deterministic(deterministic: bool) {
+    DETERMINISTIC.store(deterministic, Ordering::Relaxed);
+}
+
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+/// Columns of the terminal, or the fixed width.
+pub fn terminal_width() -> usize {
+    match terminal_size() {
+        Some((Width(w), _)) if !is_deterministic() => w as usize,
+        _ => FIXED_WIDTH,
+    }
+}
+
+/// Whether the terminal can show emoji: a UTF-8 locale on a terminal other than the Linux
+/// console, or Windows Terminal. Deterministic output never uses them.
+pub fn shows_emoji() -> bool {
+    if is_deterministic() {
+        return false;
+    }
+    if cfg!(windows) {
+        return env::var_os("WT_SESSION").is_some();
+    }
+    // The first locale variable set decides, as for the character set of gettext
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty());
+    emoji_terminal(locale.as_deref(), env::var("TERM").ok().as_deref())
+}
+
+fn emoji_terminal(locale: Option<&str>, term: Option<&str>) -> bool {
+    let utf8 = locale.is_some_and(|l| {
+        let l = l.to_lowercase();
+        l.contains("utf-8") || l.contains("utf8")
+    });
+    utf8 && !matches!(term, Some("linux" | "dumb"))
+}
+
+fn print_styled(style: &str, text: &str) {
+    // Porcelain output keeps stdout for the items
+    if is_porcelain() {
+        eprintln!("{}", text);
+    } else if is_plain() || is_deterministic() {
+        println!("{}", text);
+    } else {
+        println!("\x1b[{}m{}\x1b[0m", style, text);
+    }
+}
+
 // For quick debug purposes
 #[allow(dead_code)]
 pub fn debug_print_items(header: &str, items: &[Item]) {
@@ -16,30 +104,38 @@ pub fn debug_print_items(header: &str, items: &[Item]) {
 }
 
 pub fn print_bold(text: &str) {
-    println!("\x1b[1m{}\x1b[0m", text);
+    print_styled("1", text);
 }
 
 pub fn print_red(text: &str) {
-    println!("\x1b[91m{}\x1b[0m", text);
+    print_styled(colors().alert, text);
 }
 
 pub fn print_green(text: &str) {
-    println!("\x1b[92m{}\x1b[0m", text);
+    print_styled(colors().success, text);
 }
 
+// Yellow is the only sign of a warning, plain output says so in words
 pub fn print_yellow(text: &str) {
-    println!("\x1b[93m{}\x1b[0m", text);
+    if is_plain() {
+        print_styled(colors().warning, &format!("{}: {}", tr!("warning"), text));
+    } else {
+        print_styled(colors().warning, text);
+    }
 }
 
 // print items in a table.
 pub fn print_items(items: &[Item], is_record: bool, is_list: bool) {
+    let index_of = |index: usize, item: &Item| display_index(is_list.then_some(index + 1), item);
+    if is_porcelain() {
+        for (index, item) in items.iter().enumerate() {
+            print_porcelain_item(&index_of(index, item).unwrap_or_default(), item);
+        }
+        return;
+    }
     let mut results: Vec<DisplayRow> = Vec::with_capacity(items.len());
     for (index, item) in items.iter().enumerate() {
-        let indexstr = if is_list {
-            format!("{}", index + 1)
-        } else {
-            "N/A".to_string()
-        };
+        let indexstr = index_of(index, item).unwrap_or_else(|| "N/A".to_string());
         if is_record {
             results.push(DisplayRow::from_record(indexstr, item));
         } else {
@@ -48,3 +144,18 @@ pub fn print_items(items: &[Item], is_record: bool, is_list: bool) {
     }
     print_table(&results, is_record);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emoji_terminal() {
+        assert!(emoji_terminal(Some("en_US.UTF-8"), Some("xterm-256color")));
+        assert!(emoji_terminal(Some("de_DE.utf8"), None));
+        assert!(!emoji_terminal(Some("C"), Some("xterm")));
+        assert!(!emoji_terminal(None, Some("xterm")));
+        // The Linux console has no glyphs for them
+        assert!(!emoji_terminal(Some("en_US.UTF-8"), Some("linux")));
+    }
+}
@@ -6,77 +6,247 @@ use chrono::{
     Weekday,
 };
 
-use crate::db::item::Item;
+use crate::{
+    actions::display::{
+        colors,
+        is_deterministic,
+        is_plain,
+        shows_emoji,
+    },
+    actions::list::OPEN_STATUS_CODES,
+    clock,
+    config,
+    db::item::{
+        Item,
+        RECURRING_TASK,
+    },
+    private,
+    tr,
+};
 
 pub struct DisplayRow {
     pub index: String,
     pub category: String,
+    // Escape code of the configured category color
+    pub category_color: Option<String>,
     pub content: String,
     pub timestr: String,
+    // Escape code of the deadline, overdue ones are in the alert color
+    pub timestr_color: Option<String>,
+    // Such as "+errands +quick", tables show a column for them when a row has any
+    pub tags: String,
+    // Levels of parents above a subtask in a tree listing, 0 at the top level
+    pub depth: usize,
 }
 
 impl DisplayRow {
     pub fn from_task(index: String, task: &Item) -> Self {
-        let mut category = task.category.clone();
-        let content = task.content.clone();
+        let (mut category, category_color) = styled_category(&task.category);
+        let mut content =
+            with_status_icon(task_status_icon(task), private::reveal(&task.content));
+        if task.priority > 0 {
+            content.push_str(&format!(" [{}]", translate_priority(task.priority)));
+        }
 
+        let mut timestr_color = None;
         let mut timestr = if task.action == "recurring_task" {
-            category.push_str(" (Recurring)");
+            category.push_str(&format!(" ({})", tr!("recurring")));
             task.human_schedule
                 .clone()
-                .unwrap_or_else(|| "No schedule".to_string())
+                .unwrap_or_else(|| tr!("no-schedule"))
         } else {
-            timestamp_to_display_string(task.target_time.unwrap(), false)
+            // Inbox items have no deadline until they are filed
+            task.target_time
+                .map(|t| timestamp_to_display_string(t, false, task.all_day))
+                .unwrap_or_default()
         };
 
         if task.status != 0 {
             let status_str = translate_status(task.status);
             timestr.push_str(&format!(" ({})", status_str));
         } else if task.recurring_interval_complete {
-            timestr.push_str(" (fulfilled)");
+            timestr.push_str(&format!(" ({})", tr!("fulfilled")));
+        } else if task.action == "task" {
+            let now = clock::timestamp();
+            if task.target_time.is_some_and(|t| t < now) {
+                // The status icon says so already
+                if !config::status_icons() {
+                    timestr.push_str(&overdue_marker());
+                }
+                timestr_color = Some(colors().alert.to_string()).filter(|_| !is_deterministic());
+            }
+            if let Some(stale_after) = config::stale_after() {
+                let stale = stale_marker(task.create_time, now, stale_after);
+                timestr.push_str(&stale.unwrap_or_default());
+            }
         }
 
         DisplayRow {
             index,
             category,
+            category_color,
             content,
             timestr,
+            timestr_color,
+            tags: format_tags(&task.tags),
+            depth: 0,
         }
     }
 
     pub fn from_record(index: String, record: &Item) -> Self {
-        let timestr = timestamp_to_display_string(record.create_time, true);
-        let mut category = record.category.clone();
-        let content = record.content.clone();
+        let timestr = timestamp_to_display_string(record.create_time, true, false);
+        let (mut category, category_color) = styled_category(&record.category);
+        let content = private::reveal(&record.content);
+        let mut content = with_status_icon(Some(StatusIcon::Record), content);
+        // The answers of templated records are in the content already
+        if let Some(value) = record.value
+            && record.fields.is_none()
+        {
+            content.push_str(&format!(" ({})", format_value(value, record.unit.as_deref())));
+        }
         if record.action == "recurring_task_record" {
-            category.push_str(" (Recurring)");
+            category.push_str(&format!(" ({})", tr!("recurring")));
         }
 
         DisplayRow {
             index,
             category,
+            category_color,
             content,
             timestr,
+            timestr_color: None,
+            tags: format_tags(&record.tags),
+            depth: 0,
+        }
+    }
+}
+
+// Icons rows start with when `status_icons` is on in config
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatusIcon {
+    Done,
+    Open,
+    Overdue,
+    Record,
+}
+
+impl StatusIcon {
+    // The emoji, or an ASCII sign for terminals that cannot show them
+    fn sign(self, emoji: bool) -> &'static str {
+        match (self, emoji) {
+            (StatusIcon::Done, true) => "✅",
+            (StatusIcon::Open, true) => "⏳",
+            (StatusIcon::Overdue, true) => "⚠️",
+            (StatusIcon::Record, true) => "📌",
+            (StatusIcon::Done, false) => "[x]",
+            (StatusIcon::Open, false) => "[ ]",
+            (StatusIcon::Overdue, false) => "[!]",
+            (StatusIcon::Record, false) => "[*]",
+        }
+    }
+}
+
+// Cancelled, duplicate and removed tasks have no icon, their status is spelled out
+fn task_status_icon(task: &Item) -> Option<StatusIcon> {
+    if task.status == 1 || task.recurring_interval_complete {
+        return Some(StatusIcon::Done);
+    }
+    if !OPEN_STATUS_CODES.contains(&task.status) {
+        return None;
+    }
+    let overdue = task.action != RECURRING_TASK
+        && task.target_time.is_some_and(|t| t < clock::timestamp());
+    Some(if overdue { StatusIcon::Overdue } else { StatusIcon::Open })
+}
+
+// Plain output leaves the icons out like the category icons, the status is in words there
+fn with_status_icon(icon: Option<StatusIcon>, content: String) -> String {
+    match icon {
+        Some(icon) if config::status_icons() && !is_plain() => {
+            format!("{} {}", icon.sign(shows_emoji()), content)
         }
+        _ => content,
+    }
+}
+
+// Overdue is told by a sign as well as by color, in words for plain output
+fn overdue_marker() -> String {
+    if is_plain() {
+        format!(" ({})", tr!("overdue"))
+    } else {
+        " ⚠".to_string()
+    }
+}
+
+// Days an ongoing task has been around, once that is longer than `stale_after` seconds
+fn stale_marker(create_time: i64, now: i64, stale_after: i64) -> Option<String> {
+    let age = now - create_time;
+    if age < stale_after {
+        return None;
+    }
+    let days = age / 86400;
+    if is_plain() {
+        Some(format!(" ({})", tr!("stale", days = days)))
+    } else {
+        Some(format!(" ⏳ {}d", days))
+    }
+}
+
+/// A record value with its unit, such as "82.5 kg".
+pub fn format_value(value: f64, unit: Option<&str>) -> String {
+    match unit {
+        Some(unit) if unit.chars().all(|c| !c.is_alphanumeric()) => format!("{}{}", value, unit),
+        Some(unit) => format!("{} {}", value, unit),
+        None => value.to_string(),
+    }
+}
+
+// The category behind its configured icon, with the escape code of its color.
+// Icons are left out of plain output, screen readers would read them out on every row.
+fn styled_category(category: &str) -> (String, Option<String>) {
+    let Some(style) = config::category_style(category) else {
+        return (category.to_string(), None);
+    };
+    let label = if style.icon.is_empty() || is_plain() {
+        category.to_string()
+    } else {
+        format!("{} {}", style.icon, category)
+    };
+    (label, ansi_color(&style.color).filter(|_| !is_deterministic()))
+}
+
+/// Escape code of a color name such as "red" or "bright blue", or of a 256 color number.
+pub fn ansi_color(color: &str) -> Option<String> {
+    let color = color.trim().to_lowercase();
+    if let Ok(number) = color.parse::<u8>() {
+        return Some(format!("38;5;{}", number));
     }
+    let (base, name) = match color.strip_prefix("bright ") {
+        Some(name) => (90, name),
+        None => (30, color.as_str()),
+    };
+    let offset = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"]
+        .iter()
+        .position(|c| *c == name)?;
+    Some((base + offset).to_string())
 }
 
-fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
+// All-day deadlines show the day only, timed ones the day and the time.
+fn timestamp_to_display_string(timestamp: i64, is_record: bool, all_day: bool) -> String {
     let dt = match Local.timestamp_opt(timestamp, 0) {
         chrono::LocalResult::Single(dt) => dt,
-        _ => return "Invalid timestamp".to_string(),
+        _ => return tr!("invalid-timestamp"),
     };
 
-    let now = Local::now();
-    let is_end_of_day = dt.hour() == 23 && dt.minute() == 59 && dt.second() == 59;
+    let now = clock::now();
 
     // If timestamp is today, use "Today" string.
     if dt.date_naive() == now.date_naive() {
-        if is_end_of_day {
-            return "Today".to_string();
+        if all_day {
+            return tr!("today");
         } else {
             let hour_format = format_hour(dt.hour(), dt.minute());
-            return format!("Today {}", hour_format);
+            return format!("{} {}", tr!("today"), hour_format);
         }
     }
 
@@ -105,41 +275,45 @@ fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
     // If timestamp is tomorrow
     let tomorrow = now.date_naive() + chrono::Duration::days(1);
     if dt.date_naive() == tomorrow {
-        if is_end_of_day {
-            return "Tomorrow".to_string();
+        if all_day {
+            return tr!("tomorrow");
         } else {
             let hour_format = format_hour(dt.hour(), dt.minute());
-            return format!("Tomorrow {}", hour_format);
+            return format!("{} {}", tr!("tomorrow"), hour_format);
         }
     }
 
     // If timestamp is within next 7 days
     if dt.date_naive() <= now.date_naive() + chrono::Duration::days(7) {
         let weekday = match dt.weekday() {
-            Weekday::Mon => "Monday",
-            Weekday::Tue => "Tuesday",
-            Weekday::Wed => "Wednesday",
-            Weekday::Thu => "Thursday",
-            Weekday::Fri => "Friday",
-            Weekday::Sat => "Saturday",
-            Weekday::Sun => "Sunday",
+            Weekday::Mon => tr!("weekday-mon"),
+            Weekday::Tue => tr!("weekday-tue"),
+            Weekday::Wed => tr!("weekday-wed"),
+            Weekday::Thu => tr!("weekday-thu"),
+            Weekday::Fri => tr!("weekday-fri"),
+            Weekday::Sat => tr!("weekday-sat"),
+            Weekday::Sun => tr!("weekday-sun"),
         };
 
         // Check if it's next week (different week number)
         let dt_week = dt.iso_week().week();
         let now_week = now.iso_week().week();
-        let prefix = if dt_week != now_week { "Next " } else { "" };
+        let day = if dt_week != now_week {
+            tr!("next-weekday", weekday = weekday)
+        } else {
+            weekday
+        };
 
-        if is_end_of_day {
-            return format!("{}{}", prefix, weekday);
+        if all_day {
+            return day;
         } else {
             let hour_format = format_hour(dt.hour(), dt.minute());
-            return format!("{}{} {}", prefix, weekday, hour_format);
+            return format!("{} {}", day, hour_format);
         }
     }
 
     // If timestamp is within the year
-    if is_end_of_day {
+    if all_day {
         format!("{}/{}", dt.month(), dt.day())
     } else {
         let hour_format = format_hour(dt.hour(), dt.minute());
@@ -159,15 +333,116 @@ fn format_hour(hour: u32, minute: u32) -> String {
     format!("{}:{:02}{}", hour12, minute, period)
 }
 
-fn translate_status(status: u8) -> String {
+pub fn translate_status(status: u8) -> String {
     match status {
-        0 => "ongoing".to_string(),
-        1 => "completed".to_string(),
-        2 => "cancelled".to_string(),
-        3 => "duplicate".to_string(),
-        4 => "suspended".to_string(),
-        5 => "removed".to_string(),
-        6 => "pending".to_string(),
-        _ => "unknown".to_string(),
+        0 => tr!("status-ongoing"),
+        1 => tr!("status-completed"),
+        2 => tr!("status-cancelled"),
+        3 => tr!("status-duplicate"),
+        4 => tr!("status-suspended"),
+        5 => tr!("status-removed"),
+        6 => tr!("status-pending"),
+        _ => tr!("status-unknown"),
+    }
+}
+
+fn format_tags(tags: &[String]) -> String {
+    tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" ")
+}
+
+pub fn translate_priority(priority: u8) -> String {
+    match priority {
+        1 => tr!("priority-low"),
+        2 => tr!("priority-medium"),
+        3 => tr!("priority-high"),
+        4 => tr!("priority-urgent"),
+        _ => tr!("status-unknown"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::item::TASK;
+
+    #[test]
+    fn test_overdue_task_row() {
+        let now = clock::timestamp();
+        let task = |target_time: i64| {
+            let item = Item::with_target_time(
+                TASK.to_string(),
+                "work".to_string(),
+                "report".to_string(),
+                Some(target_time),
+            );
+            DisplayRow::from_task("1".to_string(), &item)
+        };
+        let overdue = task(now - 3 * 86400);
+        assert!(overdue.timestr.ends_with(" ⚠"), "{}", overdue.timestr);
+        assert_eq!(overdue.timestr_color.as_deref(), Some(colors().alert));
+
+        let due = task(now + 3 * 86400);
+        assert!(!due.timestr.contains('⚠'), "{}", due.timestr);
+        assert_eq!(due.timestr_color, None);
+    }
+
+    #[test]
+    fn test_priority_row() {
+        let mut task = Item::with_target_time(
+            TASK.to_string(),
+            "work".to_string(),
+            "report".to_string(),
+            Some(clock::timestamp() + 86400),
+        );
+        assert_eq!(DisplayRow::from_task("1".to_string(), &task).content, "report");
+        task.priority = 3;
+        assert_eq!(DisplayRow::from_task("1".to_string(), &task).content, "report [high]");
+    }
+
+    #[test]
+    fn test_task_status_icon() {
+        let now = clock::timestamp();
+        let mut task = Item::with_target_time(
+            TASK.to_string(),
+            "work".to_string(),
+            "report".to_string(),
+            Some(now + 86400),
+        );
+        assert_eq!(task_status_icon(&task), Some(StatusIcon::Open));
+        task.target_time = Some(now - 86400);
+        assert_eq!(task_status_icon(&task), Some(StatusIcon::Overdue));
+        task.status = 1;
+        assert_eq!(task_status_icon(&task), Some(StatusIcon::Done));
+        task.status = 2;
+        assert_eq!(task_status_icon(&task), None);
+
+        assert_eq!(StatusIcon::Overdue.sign(true), "⚠️");
+        assert_eq!(StatusIcon::Overdue.sign(false), "[!]");
+        // Off unless set in config
+        assert_eq!(with_status_icon(Some(StatusIcon::Done), "report".to_string()), "report");
+    }
+
+    #[test]
+    fn test_stale_marker() {
+        let day = 86400;
+        assert_eq!(stale_marker(0, 29 * day, 30 * day), None);
+        assert_eq!(stale_marker(0, 45 * day + 100, 30 * day), Some(" ⏳ 45d".to_string()));
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(82.5, Some("kg")), "82.5 kg");
+        assert_eq!(format_value(40.0, Some("%")), "40%");
+        assert_eq!(format_value(3.0, None), "3");
+    }
+
+    #[test]
+    fn test_ansi_color() {
+        assert_eq!(ansi_color("red").as_deref(), Some("31"));
+        assert_eq!(ansi_color("Bright Blue").as_deref(), Some("94"));
+        assert_eq!(ansi_color("208").as_deref(), Some("38;5;208"));
+        assert_eq!(ansi_color("teal"), None);
+        assert_eq!(ansi_color("300"), None);
+        assert_eq!(ansi_color(""), None);
     }
 }
@@ -1,65 +1,219 @@
-use std::cmp;
-
-use terminal_size::{
-    terminal_size,
-    Width,
+use std::{
+    cmp,
+    collections::HashMap,
 };
+
 use unicode_width::{
     UnicodeWidthChar,
     UnicodeWidthStr,
 };
 
-use crate::actions::display::DisplayRow;
+use crate::{
+    actions::display::{
+        is_plain,
+        is_porcelain,
+        print_porcelain_row,
+        terminal_width,
+        DisplayRow,
+    },
+    config::{
+        self,
+        DisplayId,
+    },
+    db::item::Item,
+    tr,
+};
 
 pub fn print_table(rows: &[DisplayRow], is_record: bool) {
-    let terminal_width = if let Some((Width(w), _)) = terminal_size() {
-        w as usize
-    } else {
-        120 // Default if unable to detect
+    let show_tags = rows.iter().any(|row| !row.tags.is_empty());
+    let table = TableWriter::new(is_record, index_width(), show_tags);
+    for row in rows {
+        table.write_row(row);
+    }
+}
+
+pub const DEFAULT_INDEX_WIDTH: usize = 7;
+// Room for category numbers such as errands-12
+const CATEGORY_INDEX_WIDTH: usize = 15;
+// Taken from the content column when tags are shown
+const TAGS_WIDTH: usize = 16;
+
+/// Width of the index column for the configured display_id.
+pub fn index_width() -> usize {
+    match config::display_id() {
+        DisplayId::Category => CATEGORY_INDEX_WIDTH,
+        DisplayId::Index | DisplayId::Global => DEFAULT_INDEX_WIDTH,
+    }
+}
+
+/// Positions of the items in the order a tree shows them, each with its depth. Subtasks
+/// follow their parent, those whose parent is not among the items are at the top level.
+pub fn tree_order(items: &[Item]) -> Vec<(usize, usize)> {
+    let positions: HashMap<i64, usize> =
+        items.iter().enumerate().filter_map(|(i, item)| Some((item.id?, i))).collect();
+    let mut children = vec![Vec::new(); items.len()];
+    let mut roots = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match item.parent_id.and_then(|parent_id| positions.get(&parent_id)) {
+            Some(&parent) if parent != i => children[parent].push(i),
+            _ => roots.push(i),
+        }
+    }
+
+    let mut order = Vec::with_capacity(items.len());
+    let mut shown = vec![false; items.len()];
+    // Depth first, the stack holds what is left to show in reverse
+    let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|i| (i, 0)).collect();
+    while let Some((i, depth)) = stack.pop() {
+        if std::mem::replace(&mut shown[i], true) {
+            continue;
+        }
+        order.push((i, depth));
+        stack.extend(children[i].iter().rev().map(|&child| (child, depth + 1)));
+    }
+    // Parents of each other cannot be reached from the top level
+    order.extend((0..items.len()).filter(|&i| !shown[i]).map(|i| (i, 0)));
+    order
+}
+
+/// What the index column shows for an item: its category number or id when display_id asks
+/// for them, else its position in a listing. None outside listings.
+pub fn display_index(position: Option<usize>, item: &Item) -> Option<String> {
+    let number = match config::display_id() {
+        DisplayId::Index => None,
+        DisplayId::Category => item.category_ref(),
+        DisplayId::Global => item.id.map(|id| id.to_string()),
     };
+    number.or_else(|| position.map(|p| p.to_string()))
+}
 
-    // Define column widths
-    let index_width = 7;
-    let category_width = 20;
-    let timestr_width = 20;
-    let margin = 10;
-
-    // Calculate content width
-    // Total used: column widths + 5 delimiters (|) + margin
-    let content_width =
-        terminal_width.saturating_sub(index_width + category_width + timestr_width + 5 + margin);
-
-    let time_header = if is_record { "Created At" } else { "Deadline" };
-
-    let separator_width = terminal_width - margin + 4;
-
-    // Print table header
-    println!("{:-<width$}", "", width = separator_width);
-    println!(
-        "| {:<index_width$}| {:<category_width$}| {:<content_width$}| {:<timestr_width$}|",
-        "Index",
-        "Category",
-        "Content",
-        time_header,
-        index_width = index_width,
-        category_width = category_width,
-        content_width = content_width,
-        timestr_width = timestr_width
-    );
-    println!("{:-<width$}", "", width = separator_width);
+// Prints the header when created and each row as it is written,
+// column widths only depend on the terminal so rows can be streamed.
+pub struct TableWriter {
+    index_width: usize,
+    category_width: usize,
+    content_width: usize,
+    timestr_width: usize,
+    // 0 without a tags column
+    tags_width: usize,
+    separator_width: usize,
+    headers: [String; 4],
+    tags_header: String,
+    // Rows are printed as labeled lines when output is plain
+    labeled: bool,
+}
 
-    for row in rows {
-        let wrapped_index = wrap(&row.index, index_width);
-        let wrapped_category = wrap(&row.category, category_width);
-        let wrapped_content = wrap(&row.content, content_width);
-        let wrapped_timestr = wrap(&row.timestr, timestr_width);
+impl TableWriter {
+    pub fn new(is_record: bool, index_width: usize, show_tags: bool) -> Self {
+        let table = TableWriter::with_width(is_record, index_width, terminal_width(), show_tags);
+        if !table.labeled {
+            for line in table.format_header() {
+                println!("{}", line);
+            }
+        }
+        table
+    }
+
+    /// A table for the given terminal width that prints nothing until rows are written.
+    pub fn with_width(
+        is_record: bool,
+        index_width: usize,
+        terminal_width: usize,
+        show_tags: bool,
+    ) -> Self {
+        // Define column widths
+        let category_width = 20;
+        let timestr_width = 20;
+        let margin = 10;
+        // The tags column with its delimiter and space
+        let tags_width = if show_tags { TAGS_WIDTH } else { 0 };
+        let tags_room = if show_tags { TAGS_WIDTH + 2 } else { 0 };
+
+        // Calculate content width
+        // Total used: column widths + 5 delimiters (|) + margin
+        let content_width = terminal_width
+            .saturating_sub(index_width + category_width + timestr_width + 5 + margin + tags_room);
+
+        let time_header = if is_record { tr!("table-created-at") } else { tr!("table-deadline") };
+        let headers = [
+            tr!("table-index"),
+            tr!("table-category"),
+            tr!("table-content"),
+            time_header,
+        ];
+
+        TableWriter {
+            index_width,
+            category_width,
+            content_width,
+            timestr_width,
+            tags_width,
+            separator_width: terminal_width.saturating_sub(margin) + 4,
+            headers,
+            tags_header: tr!("table-tags"),
+            labeled: is_plain() || is_porcelain(),
+        }
+    }
+
+    /// The header lines `new` prints, between two separators.
+    pub fn format_header(&self) -> Vec<String> {
+        let separator = format!("{:-<width$}", "", width = self.separator_width);
+        let tags = match self.tags_width {
+            0 => String::new(),
+            width => format!("| {:<width$}", self.tags_header),
+        };
+        let header = format!(
+            "| {:<index_width$}| {:<category_width$}| {:<content_width$}{}| {:<timestr_width$}|",
+            self.headers[0],
+            self.headers[1],
+            self.headers[2],
+            tags,
+            self.headers[3],
+            index_width = self.index_width,
+            category_width = self.category_width,
+            content_width = self.content_width,
+            timestr_width = self.timestr_width
+        );
+        vec![separator.clone(), header, separator]
+    }
+
+    pub fn write_row(&self, row: &DisplayRow) {
+        if is_porcelain() {
+            print_porcelain_row(row);
+            return;
+        }
+        for line in self.format_row(row) {
+            println!("{}", line);
+        }
+    }
+
+    /// The lines `write_row` prints for a row, ending with the separator or blank line.
+    pub fn format_row(&self, row: &DisplayRow) -> Vec<String> {
+        if self.labeled {
+            let mut lines = format_labeled(row, &self.headers);
+            if !row.tags.is_empty() {
+                lines.push(format!("{}: {}", self.tags_header, row.tags));
+            }
+            lines.push(String::new());
+            return lines;
+        }
+
+        let wrapped_index = wrap(&row.index, self.index_width);
+        let wrapped_category = wrap(&row.category, self.category_width);
+        let wrapped_content = indent_tree(row.depth, |width| {
+            wrap(&row.content, self.content_width.saturating_sub(width))
+        });
+        let wrapped_timestr = wrap(&row.timestr, self.timestr_width);
+        let wrapped_tags = wrap(&row.tags, self.tags_width);
 
         // Find the maximum number of lines needed
         let max_lines = cmp::max(
             cmp::max(wrapped_index.len(), wrapped_category.len()),
             cmp::max(wrapped_content.len(), wrapped_timestr.len()),
-        );
+        )
+        .max(wrapped_tags.len());
 
+        let mut lines = Vec::with_capacity(max_lines + 1);
         for i in 0..max_lines {
             let index_line = if i < wrapped_index.len() {
                 &wrapped_index[i]
@@ -82,18 +236,66 @@ pub fn print_table(rows: &[DisplayRow], is_record: bool) {
                 ""
             };
 
-            println!(
-                "| {}| {}| {}| {}|",
-                pad_string(index_line, index_width),
-                pad_string(category_line, category_width),
-                pad_string(content_line, content_width),
-                pad_string(timestr_line, timestr_width)
-            );
+            // Colored after padding, escape codes take no room on screen
+            let mut category_cell = pad_string(category_line, self.category_width);
+            if let Some(color) = row.category_color.as_ref().filter(|_| !category_line.is_empty()) {
+                category_cell = format!("\x1b[{}m{}\x1b[0m", color, category_cell);
+            }
+            let mut timestr_cell = pad_string(timestr_line, self.timestr_width);
+            if let Some(color) = row.timestr_color.as_ref().filter(|_| !timestr_line.is_empty()) {
+                timestr_cell = format!("\x1b[{}m{}\x1b[0m", color, timestr_cell);
+            }
+            let tags_cell = match self.tags_width {
+                0 => String::new(),
+                width => {
+                    let tags_line = wrapped_tags.get(i).map(String::as_str).unwrap_or_default();
+                    format!("| {}", pad_string(tags_line, width))
+                }
+            };
+            lines.push(format!(
+                "| {}| {}| {}{}| {}|",
+                pad_string(index_line, self.index_width),
+                category_cell,
+                pad_string(content_line, self.content_width),
+                tags_cell,
+                timestr_cell
+            ));
         }
 
-        // Print separator between rows
-        println!("{:-<width$}", "", width = separator_width);
+        // Separator between rows
+        lines.push(format!("{:-<width$}", "", width = self.separator_width));
+        lines
+    }
+}
+
+// The lines of a subtask's content wrapped to the room left by its indent, the first one
+// marked with a branch under its parent
+fn indent_tree(depth: usize, wrap: impl FnOnce(usize) -> Vec<String>) -> Vec<String> {
+    if depth == 0 {
+        return wrap(0);
     }
+    let branch = format!("{}└ ", "  ".repeat(depth - 1));
+    let width = UnicodeWidthStr::width(branch.as_str());
+    wrap(width)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => format!("{}{}", branch, line),
+            _ => format!("{}{}", " ".repeat(width), line),
+        })
+        .collect()
+}
+
+// One "label: value" line per column, continuation lines of the content are indented.
+fn format_labeled(row: &DisplayRow, labels: &[String; 4]) -> Vec<String> {
+    let values = [&row.index, &row.category, &row.content, &row.timestr];
+    let mut lines = Vec::new();
+    for (label, value) in labels.iter().zip(values) {
+        let mut value_lines = value.lines();
+        lines.push(format!("{}: {}", label, value_lines.next().unwrap_or_default()));
+        lines.extend(value_lines.map(|line| format!("  {}", line)));
+    }
+    lines
 }
 
 fn pad_string(s: &str, width: usize) -> String {
@@ -190,6 +392,169 @@ fn wrap(text: &str, max_length: usize) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        actions::display::FIXED_WIDTH,
+        args::timestr::to_unix_epoch,
+        db::{
+            crud::get_item,
+            item::{
+                Item,
+                RECORD,
+                TASK,
+            },
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+            pin_clock,
+            update_status,
+        },
+    };
+
+    // Whole tables are compared with the snapshots next to this file. After an intended change,
+    // review them with `cargo insta review` or rewrite them with `INSTA_UPDATE=always`.
+    fn render(table: &TableWriter, rows: &[DisplayRow]) -> String {
+        let mut lines = table.format_header();
+        lines.extend(rows.iter().flat_map(|row| table.format_row(row)));
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_task_table_snapshot() {
+        let (conn, _temp_file) = get_test_conn();
+        pin_clock("2026/10/15 12:00");
+        let ids = [
+            insert_task(&conn, "work", "write the quarterly report with charts", "5PM"),
+            insert_task(&conn, "home", "water plants", "tomorrow"),
+            insert_task(&conn, "errands", "buy groceries", "yesterday"),
+            insert_task(&conn, "reading-list-for-the-long-winter", "你好世界 finish", "10/20 9AM"),
+        ];
+        update_status(&conn, ids[2], 1);
+        let rows: Vec<DisplayRow> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                DisplayRow::from_task((i + 1).to_string(), &get_item(&conn, *id).unwrap())
+            })
+            .collect();
+        let table = TableWriter::with_width(false, DEFAULT_INDEX_WIDTH, 90, false);
+        insta::assert_snapshot!(render(&table, &rows));
+    }
+
+    #[test]
+    fn test_record_table_snapshot() {
+        pin_clock("2026/10/15 12:00");
+        let created = |timestr: &str| to_unix_epoch(timestr).unwrap();
+        let records = [
+            Item::with_create_time(
+                RECORD.into(),
+                "health".into(),
+                "ran 5km".into(),
+                created("8AM"),
+            ),
+            Item::with_create_time(
+                RECORD.into(),
+                "work".into(),
+                "shipped the release\nand wrote the notes".into(),
+                created("2026/10/13 4:30PM"),
+            ),
+        ];
+        let rows: Vec<DisplayRow> = records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| DisplayRow::from_record((i + 1).to_string(), record))
+            .collect();
+        let table = TableWriter::with_width(true, DEFAULT_INDEX_WIDTH, FIXED_WIDTH, false);
+        insta::assert_snapshot!(render(&table, &rows));
+    }
+
+    #[test]
+    fn test_tags_column() {
+        let row = DisplayRow {
+            index: "1".to_string(),
+            category: "home".to_string(),
+            category_color: None,
+            content: "buy milk".to_string(),
+            timestr: "Today".to_string(),
+            timestr_color: None,
+            tags: "+errands +quick".to_string(),
+            depth: 0,
+        };
+        let table = TableWriter::with_width(false, DEFAULT_INDEX_WIDTH, 90, true);
+        let header = &table.format_header()[1];
+        let line = &table.format_row(&row)[0];
+        assert!(header.contains("| Tags "), "{}", header);
+        assert!(line.contains("| +errands +quick "), "{}", line);
+        // The column is taken from the content, rows stay as wide as without it
+        let without = TableWriter::with_width(false, DEFAULT_INDEX_WIDTH, 90, false);
+        assert_eq!(header.len(), without.format_header()[1].len());
+        assert_eq!(line.len(), without.format_row(&row)[0].len());
+    }
+
+    #[test]
+    fn test_subtask_row() {
+        let mut row = DisplayRow {
+            index: "3".to_string(),
+            category: "blog".to_string(),
+            category_color: None,
+            content: "write the intro".to_string(),
+            timestr: "Friday".to_string(),
+            timestr_color: None,
+            tags: String::new(),
+            depth: 2,
+        };
+        let table = TableWriter::with_width(false, DEFAULT_INDEX_WIDTH, 76, false);
+        let lines = table.format_row(&row);
+        assert!(lines[0].contains("|   └ write the "), "{}", lines[0]);
+        assert!(lines[1].contains("|     intro "), "{}", lines[1]);
+        let width = UnicodeWidthStr::width(lines[0].as_str());
+        row.depth = 0;
+        assert_eq!(width, UnicodeWidthStr::width(table.format_row(&row)[0].as_str()));
+    }
+
+    #[test]
+    fn test_tree_order() {
+        let task = |id: i64, parent_id: Option<i64>| {
+            let mut item = Item::new(TASK.into(), "blog".into(), id.to_string());
+            item.id = Some(id);
+            item.parent_id = parent_id;
+            item
+        };
+        // The parent of 4 is not listed, 5 comes after 3 and its subtask 2
+        let items = [
+            task(1, None),
+            task(2, Some(3)),
+            task(3, Some(1)),
+            task(4, Some(9)),
+            task(5, Some(1)),
+        ];
+        assert_eq!(tree_order(&items), [(0, 0), (2, 1), (1, 2), (4, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn test_format_labeled() {
+        let row = DisplayRow {
+            index: "2".to_string(),
+            category: "work".to_string(),
+            category_color: None,
+            content: "write report\nwith charts".to_string(),
+            timestr: "Today".to_string(),
+            timestr_color: None,
+            tags: String::new(),
+            depth: 0,
+        };
+        let labels = ["Index", "Category", "Content", "Deadline"].map(String::from);
+        assert_eq!(
+            format_labeled(&row, &labels),
+            vec![
+                "Index: 2",
+                "Category: work",
+                "Content: write report",
+                "  with charts",
+                "Deadline: Today",
+            ]
+        );
+    }
 
     #[test]
     fn test_wrap_function() {
@@ -0,0 +1,184 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{
+        self,
+        Write,
+    },
+    path::Path,
+};
+
+use clap::{
+    Command,
+    CommandFactory,
+};
+use clap_mangen::Man;
+
+use crate::{
+    actions::display,
+    args::parser::{
+        CliArgs,
+        GenDocsCommand,
+    },
+    error::TascliError,
+};
+
+const MARKDOWN_FILE: &str = "tascli.md";
+
+pub fn handle_gendocscmd(cmd: &GenDocsCommand) -> Result<(), TascliError> {
+    // Building fills in the bin names, e.g. `tascli list task`, used in usage and page names
+    let mut command = CliArgs::command();
+    command.build();
+
+    match (cmd.format.as_str(), &cmd.out_dir) {
+        ("man", Some(dir)) => {
+            fs::create_dir_all(dir)
+                .map_err(|e| TascliError::io(format!("Failed to create {}", dir), e))?;
+            clap_mangen::generate_to(command, dir)
+                .map_err(|e| TascliError::io(format!("Failed to write man pages to {}", dir), e))?;
+            display::print_green(&format!("Wrote man pages to {}", dir));
+        }
+        ("man", None) => {
+            Man::new(command)
+                .render(&mut io::stdout())
+                .map_err(|e| TascliError::io("Failed to print man page".to_string(), e))?;
+        }
+        (_, Some(dir)) => {
+            let path = Path::new(dir).join(MARKDOWN_FILE);
+            fs::create_dir_all(dir)
+                .and_then(|_| fs::write(&path, render_markdown(&mut command)))
+                .map_err(|e| TascliError::io(format!("Failed to write {}", path.display()), e))?;
+            display::print_green(&format!("Wrote {}", path.display()));
+        }
+        (_, None) => {
+            io::stdout()
+                .write_all(render_markdown(&mut command).as_bytes())
+                .map_err(|e| TascliError::io("Failed to print markdown".to_string(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// One markdown page with a section per command, in the order `--help` lists them.
+fn render_markdown(command: &mut Command) -> String {
+    let mut out = String::new();
+    write_command(&mut out, command, 1);
+    out
+}
+
+fn write_command(out: &mut String, command: &mut Command, depth: usize) {
+    let name = command
+        .get_bin_name()
+        .unwrap_or(command.get_name())
+        .to_string();
+    let _ = writeln!(out, "{} {}\n", "#".repeat(depth.min(6)), name);
+    if let Some(about) = command.get_long_about().or(command.get_about()) {
+        let _ = writeln!(out, "{}\n", about);
+    }
+    let _ = writeln!(out, "```\n{}\n```\n", command.render_usage().to_string().trim());
+
+    let args: Vec<String> = command
+        .get_arguments()
+        // Global options are listed once, under the top level command
+        .filter(|a| !a.is_hide_set() && (depth == 1 || !a.is_global_set()))
+        .map(|arg| {
+            let mut line = format!("- `{}`", arg_syntax(arg));
+            if let Some(help) = arg.get_long_help().or(arg.get_help()) {
+                let help = help.to_string().replace('\n', " ");
+                let _ = write!(line, ": {}", help);
+            }
+            // Flags have true and false as values, which says nothing
+            if !arg.get_action().takes_values() {
+                return line;
+            }
+            let possible: Vec<String> = arg
+                .get_possible_values()
+                .iter()
+                .filter(|v| !v.is_hide_set())
+                .map(|v| v.get_name().to_string())
+                .collect();
+            if !possible.is_empty() {
+                let _ = write!(line, " [possible values: {}]", possible.join(", "));
+            }
+            let defaults: Vec<String> = arg
+                .get_default_values()
+                .iter()
+                .map(|v| v.to_string_lossy().to_string())
+                .collect();
+            if !defaults.is_empty() {
+                let _ = write!(line, " [default: {}]", defaults.join(", "));
+            }
+            line
+        })
+        .collect();
+    if !args.is_empty() {
+        let _ = writeln!(out, "{}\n", args.join("\n"));
+    }
+
+    for sub in command.get_subcommands_mut() {
+        if sub.get_name() != "help" && !sub.is_hide_set() {
+            write_command(out, sub, depth + 1);
+        }
+    }
+}
+
+fn arg_syntax(arg: &clap::Arg) -> String {
+    let value = arg
+        .get_value_names()
+        .map(|names| {
+            names
+                .iter()
+                .map(|n| format!("<{}>", n))
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .unwrap_or_else(|| format!("<{}>", arg.get_id().as_str().to_uppercase()));
+    let flag = match (arg.get_short(), arg.get_long()) {
+        (Some(short), Some(long)) => format!("-{}, --{}", short, long),
+        (Some(short), None) => format!("-{}", short),
+        (None, Some(long)) => format!("--{}", long),
+        (None, None) => return value,
+    };
+    if arg.get_action().takes_values() {
+        format!("{} {}", flag, value)
+    } else {
+        flag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn built_command() -> Command {
+        let mut command = CliArgs::command();
+        command.build();
+        command
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = render_markdown(&mut built_command());
+        assert!(markdown.starts_with("# tascli\n"));
+        assert!(markdown.contains("\n## tascli task\n"));
+        assert!(markdown.contains("\n### tascli list task\n"));
+        assert!(markdown.contains("- `-c, --category <CATEGORY>`: category of the task"));
+        assert!(markdown.contains("- `<FORMAT>`: man for roff man pages"));
+        assert!(markdown.contains("[possible values: man, markdown]"));
+        assert!(markdown.contains("[default: 100]"));
+        assert!(!markdown.contains("## tascli help\n"));
+        assert_eq!(markdown.contains("## tascli help-topics\n"), cfg!(feature = "nlp"));
+        assert_eq!(markdown.matches("--log-level").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_man_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        clap_mangen::generate_to(built_command(), dir.path()).unwrap();
+
+        let page = fs::read_to_string(dir.path().join("tascli.1")).unwrap();
+        assert!(page.contains(".TH tascli 1"));
+        let list_task = fs::read_to_string(dir.path().join("tascli-list-task.1")).unwrap();
+        assert!(list_task.contains("category of the task"));
+    }
+}
@@ -0,0 +1,187 @@
+// The DeJson derive reads Option fields with an `if let` clippy would write with `?`
+#![allow(clippy::question_mark)]
+
+use std::fs;
+
+use nanoserde::{
+    DeJson,
+    SerJson,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    config::current_user,
+    db::{
+        crud::{
+            get_item,
+            query_items,
+        },
+        item::{
+            Item,
+            ItemQuery,
+        },
+    },
+    error::TascliError,
+};
+
+// Bumped when the format changes in a way older versions cannot restore
+pub(crate) const BACKUP_VERSION: u32 = 1;
+
+/// The items of a category, or of the whole db, as written by `tascli export --out`.
+#[derive(Debug, SerJson, DeJson)]
+pub struct Backup {
+    pub version: u32,
+    // The exported category, None for the whole db
+    pub category: Option<String>,
+    pub items: Vec<BackupItem>,
+}
+
+// An item without its row id, which differs between databases. Records of a recurring task
+// and subtasks point at their task by its uuid.
+#[derive(Debug, PartialEq, SerJson, DeJson)]
+pub struct BackupItem {
+    pub uuid: String,
+    pub action: String,
+    pub category: String,
+    pub content: String,
+    pub create_time: i64,
+    pub target_time: Option<i64>,
+    #[nserde(default)]
+    pub all_day: bool,
+    pub modify_time: Option<i64>,
+    #[nserde(default)]
+    pub status: u8,
+    pub cron_schedule: Option<String>,
+    pub human_schedule: Option<String>,
+    pub recurring_task_uuid: Option<String>,
+    pub good_until: Option<i64>,
+    pub owner: Option<String>,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    pub fields: Option<String>,
+    #[nserde(default)]
+    pub priority: u8,
+    #[nserde(default)]
+    pub tags: Vec<String>,
+    #[nserde(default)]
+    pub parent_uuid: Option<String>,
+}
+
+impl BackupItem {
+    /// The item to insert, `recurring_task_id` and `parent_id` are left for the restore to
+    /// fill in.
+    pub(crate) fn to_item(&self) -> Item {
+        let mut item = Item::new(self.action.clone(), self.category.clone(), self.content.clone());
+        item.uuid = self.uuid.clone();
+        item.create_time = self.create_time;
+        item.target_time = self.target_time;
+        item.all_day = self.all_day;
+        item.modify_time = self.modify_time;
+        item.status = self.status;
+        item.cron_schedule = self.cron_schedule.clone();
+        item.human_schedule = self.human_schedule.clone();
+        item.good_until = self.good_until;
+        item.owner = self.owner.clone();
+        item.value = self.value;
+        item.unit = self.unit.clone();
+        item.fields = self.fields.clone();
+        item.priority = self.priority;
+        item.tags = self.tags.clone();
+        item
+    }
+}
+
+/// Writes the items of `category`, or of the whole db, to a json file `tascli import
+/// --restore` reads back. Private items stay encrypted.
+pub fn export_backup(
+    conn: &Connection,
+    path: &str,
+    category: Option<&str>,
+) -> Result<(), TascliError> {
+    let backup = build_backup(conn, category)?;
+    fs::write(path, backup.serialize_json())
+        .map_err(|e| TascliError::io(format!("Failed to write {}", path), e))?;
+    display::print_green(&format!("Exported {} items to {}", backup.items.len(), path));
+    Ok(())
+}
+
+pub(crate) fn build_backup(conn: &Connection, category: Option<&str>) -> Result<Backup, TascliError> {
+    let mut query = ItemQuery::new().with_visible_to(current_user());
+    if let Some(category) = category {
+        query = query.with_category(category);
+    }
+    let items = query_items(conn, &query)?
+        .iter()
+        .map(|item| backup_item(conn, item))
+        .collect::<Result<Vec<BackupItem>, TascliError>>()?;
+    Ok(Backup {
+        version: BACKUP_VERSION,
+        category: category.map(String::from),
+        items,
+    })
+}
+
+fn backup_item(conn: &Connection, item: &Item) -> Result<BackupItem, TascliError> {
+    let recurring_task_uuid = match item.recurring_task_id {
+        Some(id) => Some(get_item(conn, id)?.uuid),
+        None => None,
+    };
+    let parent_uuid = match item.parent_id {
+        Some(id) => Some(get_item(conn, id)?.uuid),
+        None => None,
+    };
+    Ok(BackupItem {
+        uuid: item.uuid.clone(),
+        action: item.action.clone(),
+        category: item.category.clone(),
+        content: item.content.clone(),
+        create_time: item.create_time,
+        target_time: item.target_time,
+        all_day: item.all_day,
+        modify_time: item.modify_time,
+        status: item.status,
+        cron_schedule: item.cron_schedule.clone(),
+        human_schedule: item.human_schedule.clone(),
+        recurring_task_uuid,
+        good_until: item.good_until,
+        owner: item.owner.clone(),
+        value: item.value,
+        unit: item.unit.clone(),
+        fields: item.fields.clone(),
+        priority: item.priority,
+        tags: item.tags.clone(),
+        parent_uuid,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_recurring_record,
+        insert_recurring_task,
+        insert_task,
+    };
+
+    #[test]
+    fn test_build_backup() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "side", "ship the beta", "friday");
+        insert_task(&conn, "work", "report", "friday");
+        let review = insert_recurring_task(&conn, "side", "weekly review", "Daily 9AM");
+        insert_recurring_record(&conn, "side", "weekly review", review, 0);
+
+        let backup = build_backup(&conn, Some("side")).unwrap();
+        assert_eq!(backup.category.as_deref(), Some("side"));
+        assert_eq!(backup.items.len(), 3);
+        let review = &backup.items[1];
+        assert_eq!(backup.items[2].recurring_task_uuid.as_ref(), Some(&review.uuid));
+
+        let json = backup.serialize_json();
+        let read = Backup::deserialize_json(&json).unwrap();
+        assert_eq!(read.items, backup.items);
+        assert_eq!(build_backup(&conn, None).unwrap().items.len(), 4);
+    }
+}
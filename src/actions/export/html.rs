@@ -0,0 +1,390 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+};
+
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            self,
+            DisplayRow,
+        },
+        list::{
+            CREATE_TIME_COL,
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+    },
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 960px; color: #222; padding: 0 1rem; }
+h1 { margin-bottom: 0; }
+.subtitle { color: #777; margin-top: 0.25rem; }
+.cards { display: flex; gap: 1rem; margin: 1.5rem 0; }
+.card { flex: 1; border: 1px solid #ddd; border-radius: 8px; padding: 1rem; text-align: center; }
+.card .value { font-size: 2rem; font-weight: bold; }
+.card .label { color: #777; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2rem; }
+th, td { text-align: left; padding: 0.4rem 0.6rem; border-bottom: 1px solid #eee; vertical-align: top; }
+th { background: #f6f6f6; }
+td.content { white-space: pre-wrap; }
+tr.overdue td { color: #c0392b; }
+.chart { margin-bottom: 2rem; }
+.bar-row { display: flex; align-items: center; margin: 0.2rem 0; }
+.bar-label { width: 9rem; color: #555; }
+.bar { background: #4a90d9; height: 1rem; border-radius: 3px; margin-right: 0.5rem; }
+"#;
+
+struct Report {
+    generated: DateTime<Local>,
+    days: usize,
+    category: Option<String>,
+    open_tasks: Vec<Item>,
+    records: Vec<Item>,
+    completed: Vec<Item>,
+}
+
+pub fn export_html(
+    conn: &Connection,
+    path: &str,
+    category: Option<&str>,
+    days: usize,
+) -> Result<(), TascliError> {
+    let report = build_report(conn, category, current_user(), days, clock::now())?;
+    fs::write(path, render(&report))
+        .map_err(|e| TascliError::io(format!("Failed to write {}", path), e))?;
+    display::print_green(&format!(
+        "Exported {} open tasks and {} records to {}",
+        report.open_tasks.len(),
+        report.records.len(),
+        path
+    ));
+    Ok(())
+}
+
+fn build_report(
+    conn: &Connection,
+    category: Option<&str>,
+    user: Option<&str>,
+    days: usize,
+    now: DateTime<Local>,
+) -> Result<Report, TascliError> {
+    let since = (now - Duration::days(days as i64)).timestamp();
+
+    let open_tasks = query_items(
+        conn,
+        &base_query(category, user)
+            .with_action(TASK)
+            .with_statuses(OPEN_STATUS_CODES.to_vec())
+            .with_order_by(TARGET_TIME_COL),
+    )?;
+
+    let mut records = query_items(
+        conn,
+        &base_query(category, user)
+            .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+            .with_create_time_min(since)
+            .with_order_by(CREATE_TIME_COL),
+    )?;
+    records.reverse();
+
+    // Completion time is only tracked as the last modification of the task
+    let completed = query_items(
+        conn,
+        &base_query(category, user)
+            .with_action(TASK)
+            .with_statuses(vec![1]),
+    )?
+    .into_iter()
+    .filter(|t| t.modify_time.is_some_and(|m| m > since))
+    .collect();
+
+    Ok(Report {
+        generated: now,
+        days,
+        category: category.map(String::from),
+        open_tasks,
+        records,
+        completed,
+    })
+}
+
+fn base_query<'a>(category: Option<&'a str>, user: Option<&'a str>) -> ItemQuery<'a> {
+    let query = ItemQuery::new().with_visible_to(user);
+    match category {
+        Some(c) => query.with_category(c),
+        None => query,
+    }
+}
+
+fn render(report: &Report) -> String {
+    let now = report.generated.timestamp();
+    let overdue = report
+        .open_tasks
+        .iter()
+        .filter(|t| t.target_time.is_some_and(|tt| tt < now))
+        .count();
+    let title = match &report.category {
+        Some(c) => format!("tascli report: {}", c),
+        None => "tascli report".to_string(),
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>{}</title>\n<style>{}</style>\n",
+        escape_html(&title),
+        STYLE
+    ));
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&title)));
+    html.push_str(&format!(
+        "<p class=\"subtitle\">Generated {}, covering the last {} days</p>\n",
+        report.generated.format("%Y/%m/%d %H:%M"),
+        report.days
+    ));
+
+    html.push_str("<div class=\"cards\">\n");
+    for (value, label) in [
+        (report.open_tasks.len(), "open tasks"),
+        (overdue, "overdue"),
+        (report.completed.len(), "completed"),
+        (report.records.len(), "records"),
+    ] {
+        html.push_str(&format!(
+            "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>\n",
+            value, label
+        ));
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Open Tasks</h2>\n");
+    html.push_str(&render_table(&report.open_tasks, "Deadline", |t| {
+        let row = DisplayRow::from_task(String::new(), &concealed(t));
+        let is_overdue = t.target_time.is_some_and(|tt| tt < now);
+        (row, is_overdue)
+    }));
+
+    html.push_str(&format!("<h2>Records of the Last {} Days</h2>\n", report.days));
+    html.push_str(&render_table(&report.records, "Time", |r| {
+        (DisplayRow::from_record(String::new(), &concealed(r)), false)
+    }));
+
+    html.push_str("<h2>Stats</h2>\n");
+    html.push_str(&render_chart("Completed per day", &completed_per_day(report)));
+    html.push_str(&render_chart("Open tasks per category", &open_per_category(report)));
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// The export is meant to be emailed or published, private content stays hidden even while
+// the vault is unlocked
+fn concealed(item: &Item) -> Item {
+    Item {
+        content: private::conceal(&item.content),
+        ..item.clone()
+    }
+}
+
+fn render_table(
+    items: &[Item],
+    time_header: &str,
+    to_row: impl Fn(&Item) -> (DisplayRow, bool),
+) -> String {
+    if items.is_empty() {
+        return "<p>Nothing here.</p>\n".to_string();
+    }
+    let mut html = format!(
+        "<table>\n<tr><th>Category</th><th>Content</th><th>{}</th></tr>\n",
+        time_header
+    );
+    for item in items {
+        let (row, highlight) = to_row(item);
+        html.push_str(&format!(
+            "<tr{}><td>{}</td><td class=\"content\">{}</td><td>{}</td></tr>\n",
+            if highlight { " class=\"overdue\"" } else { "" },
+            escape_html(&row.category),
+            escape_html(&row.content),
+            escape_html(&row.timestr)
+        ));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn render_chart(title: &str, rows: &[(String, usize)]) -> String {
+    let max = rows.iter().map(|(_, n)| *n).max().unwrap_or(0).max(1);
+    let mut html = format!("<div class=\"chart\">\n<h3>{}</h3>\n", title);
+    for (label, count) in rows {
+        html.push_str(&format!(
+            "<div class=\"bar-row\"><span class=\"bar-label\">{}</span><span class=\"bar\" style=\"width: {}%\"></span>{}</div>\n",
+            escape_html(label),
+            count * 70 / max,
+            count
+        ));
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+fn completed_per_day(report: &Report) -> Vec<(String, usize)> {
+    let today = report.generated.date_naive();
+    let mut counts: BTreeMap<_, usize> = (0..report.days as i64)
+        .map(|d| (today - Duration::days(d), 0))
+        .collect();
+    for task in &report.completed {
+        if let Some(date) = task
+            .modify_time
+            .and_then(|m| Local.timestamp_opt(m, 0).single())
+            .map(|dt| dt.date_naive())
+        {
+            counts.entry(date).and_modify(|n| *n += 1);
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(date, n)| (date.format("%a %m/%d").to_string(), n))
+        .collect()
+}
+
+fn open_per_category(report: &Report) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for task in &report.open_tasks {
+        *counts.entry(&task.category).or_default() += 1;
+    }
+    let mut rows: Vec<(String, usize)> =
+        counts.into_iter().map(|(c, n)| (c.to_string(), n)).collect();
+    rows.sort_by_key(|r| std::cmp::Reverse(r.1));
+    rows
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::insert_item,
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_task,
+            update_status,
+        },
+    };
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<b>"fish" & 'chips'</b>"#),
+            "&lt;b&gt;&quot;fish&quot; &amp; &#39;chips&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_build_report() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "write report", "tomorrow");
+        insert_task(&conn, "home", "fix sink", "yesterday");
+        let done = insert_task(&conn, "work", "ship release", "today");
+        update_status(&conn, done, 1);
+        insert_record(&conn, "work", "standup notes", "today");
+        insert_record(&conn, "work", "old notes", "2020/01/01");
+
+        let report = build_report(&conn, None, None, 7, Local::now()).unwrap();
+        assert_eq!(report.open_tasks.len(), 2);
+        assert_eq!(report.open_tasks[0].content, "fix sink");
+        assert_eq!(report.records.len(), 1);
+        assert_eq!(report.completed.len(), 1);
+
+        let per_day = completed_per_day(&report);
+        assert_eq!(per_day.len(), 7);
+        assert_eq!(per_day.last().unwrap().1, 1);
+        assert_eq!(
+            open_per_category(&report),
+            vec![("home".to_string(), 1), ("work".to_string(), 1)]
+        );
+
+        let report = build_report(&conn, Some("home"), None, 7, Local::now()).unwrap();
+        assert_eq!(report.open_tasks.len(), 1);
+        assert!(report.records.is_empty());
+        assert!(report.completed.is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "review <script> PR", "yesterday");
+
+        let html = render(&build_report(&conn, None, None, 7, Local::now()).unwrap());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("review &lt;script&gt; PR"));
+        assert!(html.contains("<tr class=\"overdue\">"));
+        assert!(html.contains("<h3>Open tasks per category</h3>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_render_conceals_private() {
+        let (conn, _temp_file) = get_test_conn();
+        let content = private::encrypt("see the doctor").unwrap();
+        let id = insert_task(&conn, "health", "placeholder", "tomorrow");
+        conn.execute("UPDATE items SET content = ?1 WHERE id = ?2", (&content, id)).unwrap();
+        private::unlock(5).unwrap();
+        assert_eq!(private::reveal(&content), "see the doctor");
+
+        let html = render(&build_report(&conn, None, None, 7, Local::now()).unwrap());
+        assert!(!html.contains("doctor"));
+        assert!(!html.contains(private::PRIVATE_PREFIX));
+        assert!(html.contains("[private]"));
+    }
+
+    #[test]
+    fn test_build_report_visible_to() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "home", "water plants", "tomorrow");
+        let mut task = Item::with_target_time(
+            TASK.to_string(),
+            "home".to_string(),
+            "pick up parcel".to_string(),
+            Some(clock::timestamp() + 86400),
+        );
+        task.owner = Some("bob".to_string());
+        insert_item(&conn, &task).unwrap();
+
+        let html = render(&build_report(&conn, None, Some("alice"), 7, Local::now()).unwrap());
+        assert!(html.contains("water plants"));
+        assert!(!html.contains("pick up parcel"));
+        let report = build_report(&conn, None, Some("bob"), 7, Local::now()).unwrap();
+        assert_eq!(report.open_tasks.len(), 2);
+    }
+}
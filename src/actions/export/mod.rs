@@ -0,0 +1,21 @@
+pub(crate) mod backup;
+pub(crate) mod html;
+
+use rusqlite::Connection;
+
+use crate::{
+    args::parser::ExportCommand,
+    error::TascliError,
+};
+
+pub fn handle_exportcmd(conn: &Connection, cmd: &ExportCommand) -> Result<(), TascliError> {
+    if let Some(path) = &cmd.out {
+        return backup::export_backup(conn, path, cmd.category.as_deref());
+    }
+    match &cmd.html {
+        Some(path) => html::export_html(conn, path, cmd.category.as_deref(), cmd.days),
+        None => Err(TascliError::Invalid(
+            "Nothing to export, specify a target such as --html".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    io::{
+        self,
+        BufRead,
+        Write,
+    },
+    sync::mpsc::{
+        self,
+        Receiver,
+        RecvTimeoutError,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        modify::{
+            complete_item,
+            resolve_or_pick,
+        },
+    },
+    args::parser::FocusCommand,
+    db::{
+        crud::{
+            get_item,
+            insert_item,
+        },
+        item::{
+            Item,
+            RECORD,
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+    error::TascliError,
+    events,
+    private,
+};
+
+// Unit of the value of focus session records
+pub(crate) const MINUTES_UNIT: &str = "min";
+// How often plain output announces the time left, a ticking line is no use to screen readers
+const PLAIN_ANNOUNCE_MINUTES: u64 = 5;
+
+pub fn handle_focuscmd(conn: &Connection, cmd: &FocusCommand) -> Result<(), TascliError> {
+    let item_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let task = get_item(conn, item_id)?;
+    if task.action != TASK && task.action != RECURRING_TASK {
+        return Err(TascliError::Invalid("Focus sessions are for tasks".to_string()));
+    }
+
+    let content = private::reveal(&task.content);
+    display::print_bold(&format!(
+        "Focusing on {} for {} minutes, press enter to stop early",
+        content, cmd.minutes
+    ));
+    let lines = read_lines();
+    let elapsed = countdown(&content, Duration::from_secs(cmd.minutes * 60), &lines);
+    let minutes = elapsed.as_secs() / 60;
+    print!("\x07");
+    display::print_bold(&format!("Focus session over after {} minutes", minutes));
+    if minutes == 0 {
+        display::print_yellow("Sessions under a minute are not recorded");
+        return Ok(());
+    }
+    let note = ask(&lines, "Note for the session, enter to skip")?;
+    let done = ask(&lines, "Mark the task done? (y/N)")?.eq_ignore_ascii_case("y");
+    let record = finish_session(conn, task, minutes, &note, done)?;
+    display::print_items(&[record], true, false);
+    Ok(())
+}
+
+// Stdin read on its own thread, so the countdown can stop on enter and the prompts after it
+// read the same lines. The channel closes at the end of the input.
+fn read_lines() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+// Time actually spent, shorter than `length` when enter was pressed.
+fn countdown(content: &str, length: Duration, lines: &Receiver<String>) -> Duration {
+    let start = Instant::now();
+    let mut input_closed = false;
+    let mut announced = None;
+    while start.elapsed() < length {
+        let left = length - start.elapsed();
+        let minutes_left = left.as_secs().div_ceil(60);
+        if display::is_plain() {
+            let announce = minutes_left > 0 && minutes_left.is_multiple_of(PLAIN_ANNOUNCE_MINUTES);
+            if announce && announced != Some(minutes_left) {
+                println!("{} minutes left", minutes_left);
+                announced = Some(minutes_left);
+            }
+        } else {
+            print!("\r⏱ {:02}:{:02} {}", left.as_secs() / 60, left.as_secs() % 60, content);
+            let _ = io::stdout().flush();
+        }
+
+        let tick = left.min(Duration::from_secs(1));
+        if input_closed {
+            thread::sleep(tick);
+            continue;
+        }
+        match lines.recv_timeout(tick) {
+            Ok(_) => break,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => input_closed = true,
+        }
+    }
+    if !display::is_plain() {
+        println!();
+    }
+    start.elapsed().min(length)
+}
+
+// The trimmed answer, empty once the input is closed
+fn ask(lines: &Receiver<String>, question: &str) -> Result<String, TascliError> {
+    print!("{}: ", question);
+    io::stdout()
+        .flush()
+        .map_err(|e| TascliError::io("Failed to write prompt", e))?;
+    match lines.recv() {
+        Ok(answer) => Ok(answer.trim().to_string()),
+        Err(_) => {
+            println!();
+            Ok(String::new())
+        }
+    }
+}
+
+// Records the session as minutes spent on the task, with the note, and completes the task
+// when asked to.
+fn finish_session(
+    conn: &Connection,
+    task: Item,
+    minutes: u64,
+    note: &str,
+    done: bool,
+) -> Result<Item, TascliError> {
+    let content = if note.is_empty() {
+        task.content.clone()
+    } else {
+        private::edit(&task.content, |content| format!("{}: {}", content, note))?
+    };
+    let mut record = Item::new(RECORD.to_string(), task.category.clone(), content);
+    record.value = Some(minutes as f64);
+    record.unit = Some(MINUTES_UNIT.to_string());
+    record.id = Some(insert_item(conn, &record)?);
+    events::emit(conn, events::CREATE, &mut record);
+
+    if done {
+        complete_item(conn, task, 1, None)?;
+        display::print_green("Task completed");
+    }
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+    };
+
+    #[test]
+    fn test_finish_session() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "write report", "tomorrow");
+
+        let record = finish_session(&conn, get_item(&conn, id).unwrap(), 25, "", false).unwrap();
+        let record = get_item(&conn, record.id.unwrap()).unwrap();
+        assert_eq!(record.action, RECORD);
+        assert_eq!(record.category, "work");
+        assert_eq!(record.content, "write report");
+        assert_eq!((record.value, record.unit.as_deref()), (Some(25.0), Some(MINUTES_UNIT)));
+        assert_eq!(get_item(&conn, id).unwrap().status, 0);
+
+        let task = get_item(&conn, id).unwrap();
+        let record = finish_session(&conn, task, 10, "first draft", true).unwrap();
+        assert_eq!(record.content, "write report: first draft");
+        assert_eq!(get_item(&conn, id).unwrap().status, 1);
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::{
+        cron,
+        parser::ForecastCommand,
+    },
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+pub fn handle_forecastcmd(conn: &Connection, cmd: &ForecastCommand) -> Result<(), TascliError> {
+    let now = clock::now();
+    let days = count_per_day(conn, cmd.category.as_deref(), now, cmd.days)?;
+
+    let rows: Vec<(String, f64)> = days
+        .iter()
+        .map(|(date, count)| {
+            let mut label = date.format("%a %m/%d").to_string();
+            if *count > cmd.max {
+                label.push_str(" ⚠");
+            }
+            (label, *count as f64)
+        })
+        .collect();
+    display::print_bar_chart(&format!("Tasks due in the next {} days", cmd.days), &rows);
+
+    let overloaded = days.iter().filter(|(_, count)| *count > cmd.max).count();
+    if overloaded > 0 {
+        display::print_yellow(&format!(
+            "⚠ {} days have more than {} tasks due",
+            overloaded, cmd.max
+        ));
+    }
+    Ok(())
+}
+
+// Open tasks and recurring task occurrences due on each day, from today on.
+// Overdue tasks are not part of the forecast.
+pub(crate) fn count_per_day(
+    conn: &Connection,
+    category: Option<&str>,
+    now: DateTime<Local>,
+    days: usize,
+) -> Result<Vec<(NaiveDate, usize)>, TascliError> {
+    let today = now.date_naive();
+    let end = Local
+        .from_local_datetime(
+            &(today + Duration::days(days as i64))
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        )
+        .earliest()
+        .map(|dt| dt.timestamp() - 1)
+        .ok_or_else(|| "Invalid forecast range".to_string())?;
+
+    let mut tasks = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_range(Some(now.timestamp()), Some(end));
+    let mut recurring = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(RECURRING_TASK);
+    if let Some(c) = category {
+        tasks = tasks.with_category(c);
+        recurring = recurring.with_category(c);
+    }
+
+    let mut due_times: Vec<i64> = query_items(conn, &tasks)?
+        .iter()
+        .filter_map(|t| t.target_time)
+        .collect();
+    for task in query_items(conn, &recurring)? {
+        due_times.extend(recurring_occurrences(&task, now, end)?);
+    }
+
+    let mut counts: Vec<(NaiveDate, usize)> = (0..days as i64)
+        .map(|d| (today + Duration::days(d), 0))
+        .collect();
+    for time in due_times {
+        let Some(date) = Local.timestamp_opt(time, 0).single().map(|dt| dt.date_naive()) else {
+            continue;
+        };
+        let index = (date - today).num_days();
+        if let Some((_, count)) = usize::try_from(index).ok().and_then(|i| counts.get_mut(i)) {
+            *count += 1;
+        }
+    }
+    Ok(counts)
+}
+
+fn recurring_occurrences(
+    task: &Item,
+    now: DateTime<Local>,
+    end: i64,
+) -> Result<Vec<i64>, TascliError> {
+    match &task.cron_schedule {
+        Some(schedule) => {
+            cron::get_occurrences_between(schedule, now, end).map_err(TascliError::Parse)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::insert_item,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_count_per_day() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now();
+        let today = now.date_naive();
+        insert_task(&conn, "work", "today", "today");
+        insert_task(&conn, "work", "tomorrow", "tomorrow");
+        insert_task(&conn, "home", "tomorrow at home", "tomorrow");
+        insert_task(&conn, "work", "overdue", "2020/01/01");
+        let daily = Item::create_recurring_task(
+            "home".to_string(),
+            "stretch".to_string(),
+            "59 23 * * *".to_string(),
+            "daily".to_string(),
+        );
+        insert_item(&conn, &daily).unwrap();
+
+        let counts = count_per_day(&conn, None, now, 3).unwrap();
+        assert_eq!(
+            counts,
+            vec![
+                (today, 2),
+                (today + Duration::days(1), 3),
+                (today + Duration::days(2), 1),
+            ]
+        );
+
+        let counts = count_per_day(&conn, Some("work"), now, 3).unwrap();
+        let per_day: Vec<usize> = counts.iter().map(|(_, n)| *n).collect();
+        assert_eq!(per_day, vec![1, 1, 0]);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+    sync::LazyLock,
+};
+
+use regex::Regex;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+        modify::{
+            complete_item,
+            resolve_item,
+        },
+    },
+    args::parser::{
+        GitCommand,
+        InstallHookCommand,
+        ItemRef,
+        parse_item_ref,
+    },
+    db::{
+        crud::get_item,
+        item::{
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+// Same verbs GitHub accepts for closing issues, e.g. "Fixes tascli#3, closes tascli#a3f2b1c4"
+static REFERENCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?|done)\s*:?\s+tascli#([0-9a-f][0-9a-f-]*)")
+        .unwrap()
+});
+
+const HOOK_MARKER: &str = "# installed by tascli";
+const HOOK_SCRIPT: &str = r#"#!/bin/sh
+# installed by tascli
+# completes tasks referenced as "closes tascli#<index or id>" in the commit message
+tascli git post-commit
+"#;
+
+pub fn handle_gitcmd(conn: &Connection, cmd: GitCommand) -> Result<(), TascliError> {
+    match cmd {
+        GitCommand::InstallHook(cmd) => handle_installhookcmd(&cmd),
+        GitCommand::PostCommit => handle_postcommitcmd(conn),
+    }
+}
+
+fn handle_installhookcmd(cmd: &InstallHookCommand) -> Result<(), TascliError> {
+    let hooks_dir = PathBuf::from(run_git(&["rev-parse", "--git-path", "hooks"])?);
+    let hook_path = write_hook(&hooks_dir, cmd.force)?;
+    display::print_green(&format!("Installed post-commit hook at {}", hook_path.display()));
+    Ok(())
+}
+
+fn handle_postcommitcmd(conn: &Connection) -> Result<(), TascliError> {
+    let log = run_git(&["log", "-1", "--format=%h%n%B"])?;
+    let (hash, message) = log.split_once('\n').unwrap_or((&log, ""));
+    for item_ref in parse_references(message) {
+        // One bad reference should not keep the others from being completed
+        if let Err(e) = close_referenced(conn, &item_ref, hash) {
+            display::print_red(&format!("tascli#{}: {}", reference_name(&item_ref), e));
+        }
+    }
+    Ok(())
+}
+
+fn close_referenced(conn: &Connection, item_ref: &ItemRef, hash: &str) -> Result<(), TascliError> {
+    let row_id = resolve_item(conn, item_ref)?;
+    let item = get_item(conn, row_id)?;
+    if item.action != TASK && item.action != RECURRING_TASK {
+        return Err(TascliError::Invalid("Cannot complete a record".to_string()));
+    }
+    if item.action == TASK && !OPEN_STATUS_CODES.contains(&item.status) {
+        return Err(TascliError::Invalid("Task is already closed".to_string()));
+    }
+
+    let comment = format!("Closed by commit {}", hash);
+    let item = complete_item(conn, item, 1, Some(&comment))?;
+    display::print_bold(&format!("Completed tascli#{}:", reference_name(item_ref)));
+    display::print_items(&[item], false, false);
+    Ok(())
+}
+
+// Indices expire with the listing they came from, a uuid prefix keeps working in old commits
+fn parse_references(message: &str) -> Vec<ItemRef> {
+    let mut seen = HashSet::new();
+    REFERENCE_RE
+        .captures_iter(message)
+        .filter_map(|c| parse_item_ref(&c[1]).ok())
+        .filter(|item_ref| seen.insert(item_ref.clone()))
+        .collect()
+}
+
+fn reference_name(item_ref: &ItemRef) -> String {
+    match item_ref {
+        ItemRef::Index(index) => index.to_string(),
+        ItemRef::Category(category, seq) => format!("{}-{}", category, seq),
+        ItemRef::Id(prefix) => prefix.clone(),
+    }
+}
+
+fn write_hook(hooks_dir: &Path, force: bool) -> Result<PathBuf, TascliError> {
+    let hook_path = hooks_dir.join("post-commit");
+    if let Ok(existing) = fs::read_to_string(&hook_path)
+        && !existing.contains(HOOK_MARKER)
+        && !force
+    {
+        return Err(TascliError::Invalid(format!(
+            "{} already exists, use --force to replace it",
+            hook_path.display()
+        )));
+    }
+
+    fs::create_dir_all(hooks_dir)
+        .map_err(|e| TascliError::io(format!("Failed to create {}", hooks_dir.display()), e))?;
+    fs::write(&hook_path, HOOK_SCRIPT)
+        .map_err(|e| TascliError::io(format!("Failed to write {}", hook_path.display()), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| {
+                TascliError::io(format!("Failed to make {} executable", hook_path.display()), e)
+            })?;
+    }
+    Ok(hook_path)
+}
+
+fn run_git(args: &[&str]) -> Result<String, TascliError> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| TascliError::io("Failed to run git", e))?;
+    if !output.status.success() {
+        return Err(TascliError::Invalid(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{
+        db::cache,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_parse_references() {
+        assert_eq!(parse_references("Fix parser\n\ncloses tascli#2"), vec![ItemRef::Index(2)]);
+        assert_eq!(
+            parse_references("Fixes tascli#3, Resolved: tascli#10 and done tascli#3"),
+            vec![ItemRef::Index(3), ItemRef::Index(10)]
+        );
+        assert_eq!(
+            parse_references("closes tascli#A3F2B1C4 and tascli#a3f2b1c4"),
+            vec![ItemRef::Id("a3f2b1c4".to_string())]
+        );
+        assert_eq!(parse_references("see tascli#4"), Vec::<ItemRef>::new());
+        assert_eq!(parse_references("closes #4"), Vec::<ItemRef>::new());
+        assert_eq!(parse_references("closes tascli#0"), Vec::<ItemRef>::new());
+        assert_eq!(parse_references("closes tascli#abc"), Vec::<ItemRef>::new());
+    }
+
+    #[test]
+    fn test_close_referenced() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "ship the parser", "today");
+        cache::store(&conn, &[get_item(&conn, id).unwrap()]).unwrap();
+
+        close_referenced(&conn, &ItemRef::Index(1), "abc1234").unwrap();
+        let item = get_item(&conn, id).unwrap();
+        assert_eq!(item.status, 1);
+        assert_eq!(item.content, "ship the parser\nClosed by commit abc1234");
+
+        assert!(close_referenced(&conn, &ItemRef::Index(1), "def5678").is_err());
+        assert!(close_referenced(&conn, &ItemRef::Index(2), "def5678").is_err());
+    }
+
+    #[test]
+    fn test_close_referenced_by_id() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "ship the parser", "today");
+        let item = get_item(&conn, id).unwrap();
+
+        // No listing is needed, the cache may have expired long ago
+        let item_ref = ItemRef::Id(item.short_id().to_string());
+        close_referenced(&conn, &item_ref, "abc1234").unwrap();
+        assert_eq!(get_item(&conn, id).unwrap().status, 1);
+    }
+
+    #[test]
+    fn test_write_hook() {
+        let dir = TempDir::new().unwrap();
+        let hook_path = write_hook(dir.path(), false).unwrap();
+        assert_eq!(fs::read_to_string(&hook_path).unwrap(), HOOK_SCRIPT);
+        // Reinstalling over our own hook is fine
+        write_hook(dir.path(), false).unwrap();
+
+        fs::write(&hook_path, "#!/bin/sh\nmake lint\n").unwrap();
+        assert!(write_hook(dir.path(), false).is_err());
+        write_hook(dir.path(), true).unwrap();
+        assert_eq!(fs::read_to_string(&hook_path).unwrap(), HOOK_SCRIPT);
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        modify::complete_item,
+    },
+    args::{
+        cron,
+        parser::{
+            HabitAddCommand,
+            HabitCommand,
+            HabitDoneCommand,
+            HabitSkipCommand,
+        },
+        timestr,
+    },
+    config::current_user,
+    db::{
+        crud::{
+            insert_item,
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            RECURRING_TASK,
+            RECURRING_TASK_RECORD,
+        },
+    },
+    error::TascliError,
+    events,
+    private,
+};
+
+// Status of the record written for a skipped period
+pub(crate) const SKIPPED_STATUS: u8 = 2;
+
+/// What happened in one period of a habit, the time between two occurrences of its schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Done,
+    Skipped,
+    Missed,
+    // The current period, not missed until it is over
+    Open,
+}
+
+pub fn handle_habitcmd(conn: &Connection, cmd: HabitCommand) -> Result<(), TascliError> {
+    match cmd {
+        HabitCommand::Add(cmd) => handle_addcmd(conn, &cmd),
+        HabitCommand::Done(cmd) => handle_donecmd(conn, &cmd),
+        HabitCommand::Skip(cmd) => handle_skipcmd(conn, &cmd),
+    }
+}
+
+fn handle_addcmd(conn: &Connection, cmd: &HabitAddCommand) -> Result<(), TascliError> {
+    let cron_schedule = timestr::parse_recurring_timestr(&cmd.schedule)?;
+    let mut habit = Item::create_recurring_task(
+        cmd.category.clone(),
+        cmd.name.clone(),
+        cron_schedule,
+        cmd.schedule.clone(),
+    );
+    habit.id = Some(insert_item(conn, &habit)?);
+    events::emit(conn, events::CREATE, &mut habit);
+
+    display::print_bold("Added Habit:");
+    display::print_items(&[habit], false, false);
+    Ok(())
+}
+
+fn handle_donecmd(conn: &Connection, cmd: &HabitDoneCommand) -> Result<(), TascliError> {
+    let habit = find_habit(conn, &cmd.name)?;
+    let habit = complete_item(conn, habit, 1, cmd.comment.as_deref())?;
+    display::print_bold("Logged Habit:");
+    display::print_items(&[habit], false, false);
+    Ok(())
+}
+
+fn handle_skipcmd(conn: &Connection, cmd: &HabitSkipCommand) -> Result<(), TascliError> {
+    let habit = find_habit(conn, &cmd.name)?;
+    let cron_schedule = habit
+        .cron_schedule
+        .as_ref()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    let last_occurrence = cron::get_last_occurrence(cron_schedule)?;
+    let existing_records = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(RECURRING_TASK_RECORD)
+            .with_recurring_task_id(habit.id.unwrap())
+            .with_good_until_min(last_occurrence),
+    )?;
+    if !existing_records.is_empty() {
+        return Err(TascliError::Invalid(format!(
+            "'{}' is already logged or skipped for this period",
+            private::reveal(&habit.content)
+        )));
+    }
+
+    let mut skip_record = Item::create_recurring_record(
+        habit.category.clone(),
+        private::edit(&habit.content, |content| format!("Skipped Recurring Task: {}", content))?,
+        habit.id.unwrap(),
+        cron::get_next_occurrence(cron_schedule)?,
+    );
+    // Items are inserted as ongoing, the skip is recorded as a status change
+    skip_record.id = Some(insert_item(conn, &skip_record)?);
+    skip_record.status = SKIPPED_STATUS;
+    update_item(conn, &skip_record)?;
+    display::print_bold(&format!(
+        "Skipped '{}' for this period",
+        private::reveal(&habit.content)
+    ));
+    Ok(())
+}
+
+/// The recurring task named `name`, matched exactly and then by a unique part of its name.
+pub(crate) fn find_habit(conn: &Connection, name: &str) -> Result<Item, TascliError> {
+    let habits = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_visible_to(current_user())
+            .with_action(RECURRING_TASK),
+    )?;
+    let name = name.trim().to_lowercase();
+    if let Some(habit) = habits.iter().find(|h| h.content.to_lowercase() == name) {
+        return Ok(habit.clone());
+    }
+    let mut matches = habits
+        .into_iter()
+        .filter(|h| h.content.to_lowercase().contains(&name));
+    match (matches.next(), matches.next()) {
+        (Some(habit), None) => Ok(habit),
+        (None, _) => Err(TascliError::Invalid(format!("No habit named '{}'", name))),
+        (Some(_), Some(_)) => Err(TascliError::Invalid(format!(
+            "More than one habit matches '{}', use more of its name",
+            name
+        ))),
+    }
+}
+
+/// Every period of `habit` from its creation up to the current one, with the time each ends.
+pub(crate) fn habit_periods(
+    conn: &Connection,
+    habit: &Item,
+    now: i64,
+) -> Result<Vec<(i64, Period)>, TascliError> {
+    let cron_schedule = habit
+        .cron_schedule
+        .as_ref()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    let created = Local
+        .timestamp_opt(habit.create_time, 0)
+        .single()
+        .ok_or_else(|| "Invalid creation time".to_string())?;
+    let now_time = Local
+        .timestamp_opt(now, 0)
+        .single()
+        .ok_or_else(|| "Invalid time".to_string())?;
+    let mut ends = cron::get_occurrences_between(cron_schedule, created, now)?;
+    ends.push(cron::get_next_occurrence_from(cron_schedule, now_time)?);
+
+    let records = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(RECURRING_TASK_RECORD)
+            .with_recurring_task_id(habit.id.unwrap_or_default()),
+    )?;
+    let logged: Vec<(i64, u8)> = records
+        .iter()
+        .filter_map(|r| r.good_until.map(|until| (until, r.status)))
+        .collect();
+    Ok(classify_periods(&ends, &logged, now))
+}
+
+// A record belongs to the period its good_until falls in, skips only count
+// when nothing was logged in the same period.
+fn classify_periods(ends: &[i64], logged: &[(i64, u8)], now: i64) -> Vec<(i64, Period)> {
+    let mut start = i64::MIN;
+    ends.iter()
+        .map(|&end| {
+            let in_period: Vec<u8> = logged
+                .iter()
+                .filter(|(until, _)| *until > start && *until <= end)
+                .map(|(_, status)| *status)
+                .collect();
+            start = end;
+            let period = if in_period.iter().any(|s| *s != SKIPPED_STATUS) {
+                Period::Done
+            } else if !in_period.is_empty() {
+                Period::Skipped
+            } else if end > now {
+                Period::Open
+            } else {
+                Period::Missed
+            };
+            (end, period)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_recurring_task,
+    };
+
+    #[test]
+    fn test_classify_periods() {
+        let ends = [10, 20, 30, 40, 50];
+        let logged = [(10, 0), (25, SKIPPED_STATUS), (35, SKIPPED_STATUS), (40, 0)];
+        assert_eq!(
+            classify_periods(&ends, &logged, 45),
+            vec![
+                (10, Period::Done),
+                (20, Period::Missed),
+                (30, Period::Skipped),
+                (40, Period::Done),
+                (50, Period::Open),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_habit() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_recurring_task(&conn, "habit", "Meditate", "daily");
+        insert_recurring_task(&conn, "habit", "meditate longer", "daily");
+        insert_recurring_task(&conn, "habit", "read a book", "daily");
+
+        assert_eq!(find_habit(&conn, "meditate").unwrap().content, "Meditate");
+        assert_eq!(find_habit(&conn, "book").unwrap().content, "read a book");
+        assert!(find_habit(&conn, "medit").is_err(), "ambiguous");
+        assert!(find_habit(&conn, "run").is_err());
+    }
+
+    #[test]
+    fn test_skip_then_done_in_same_period() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_recurring_task(&conn, "habit", "meditate", "daily");
+        let skip = |name: &str| {
+            handle_skipcmd(
+                &conn,
+                &HabitSkipCommand {
+                    name: name.to_string(),
+                },
+            )
+        };
+
+        skip("meditate").unwrap();
+        assert!(skip("meditate").is_err(), "already skipped today");
+        let habit = find_habit(&conn, "meditate").unwrap();
+        assert!(complete_item(&conn, habit.clone(), 1, None).is_err());
+
+        let records = query_items(&conn, &ItemQuery::new().with_recurring_task_id(id)).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, SKIPPED_STATUS);
+        let periods = habit_periods(&conn, &habit, Local::now().timestamp()).unwrap();
+        assert_eq!(periods.last().unwrap().1, Period::Skipped);
+    }
+}
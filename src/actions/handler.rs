@@ -1,12 +1,40 @@
 use rusqlite::Connection;
 use clap::Parser;
+use tracing::debug;
 
 use crate::{
     actions::{
         addition,
+        convert,
+        digest,
+        docs,
+        export,
+        focus,
+        forecast,
+        git,
+        habit,
+        import,
+        inbox,
+        journal,
         list,
         modify,
-        nlp,
+        notify,
+        palette,
+        plan,
+        plugin,
+        private,
+        project,
+        publish,
+        query,
+        remind,
+        reminders,
+        report,
+        rules,
+        schedule,
+        show,
+        triage,
+        tutorial,
+        view,
         display::{print_yellow},
     },
     args::parser::{
@@ -14,23 +42,60 @@ use crate::{
         CliArgs,
         ListCommand,
     },
+    error::TascliError,
 };
 
-pub fn handle_commands(conn: &Connection, args: CliArgs) -> Result<(), String> {
+pub fn handle_commands(conn: &Connection, args: CliArgs) -> Result<(), TascliError> {
     // If we have a subcommand, handle it traditionally
     if let Some(action) = args.arguments {
+        debug!(?action, "dispatching command");
         return match action {
             Action::Task(cmd) => addition::handle_taskcmd(conn, &cmd),
             Action::Record(cmd) => addition::handle_recordcmd(conn, &cmd),
+            Action::In(cmd) => inbox::handle_incmd(conn, &cmd),
+            Action::Inbox(cmd) => inbox::handle_inboxcmd(conn, &cmd),
             Action::Done(cmd) => modify::handle_donecmd(conn, &cmd),
+            Action::Promote(cmd) => convert::handle_promotecmd(conn, &cmd),
+            Action::LogDone(cmd) => convert::handle_logdonecmd(conn, &cmd),
             Action::Delete(cmd) => modify::handle_deletecmd(conn, &cmd),
             Action::Update(cmd) => modify::handle_updatecmd(conn, &cmd),
+            Action::Remind(cmd) => remind::handle_remindcmd(conn, &cmd),
+            Action::Focus(cmd) => focus::handle_focuscmd(conn, &cmd),
+            Action::Journal(cmd) => journal::handle_journalcmd(conn, &cmd),
+            Action::Show(cmd) => show::handle_showcmd(conn, &cmd),
+            Action::Habit(cmd) => habit::handle_habitcmd(conn, cmd),
+            Action::Project(cmd) => project::handle_projectcmd(conn, &cmd),
             Action::List(list_cmd) => match list_cmd {
                 ListCommand::Task(cmd) => list::handle_listtasks(conn, cmd),
                 ListCommand::Record(cmd) => list::handle_listrecords(conn, cmd),
                 ListCommand::Show(cmd) => list::handle_showcontent(conn, cmd),
             },
-            Action::NLP(cmd) => nlp::handle_nlp_command(conn, &cmd),
+            #[cfg(feature = "nlp")]
+            Action::NLP(cmd) => crate::actions::nlp::handle_nlp_command(conn, &cmd),
+            Action::Digest(cmd) => digest::handle_digestcmd(conn, &cmd),
+            Action::Notify(cmd) => notify::handle_notifycmd(conn, cmd),
+            Action::Import(cmd) => import::handle_importcmd(conn, &cmd),
+            Action::Export(cmd) => export::handle_exportcmd(conn, &cmd),
+            Action::Publish(cmd) => publish::handle_publishcmd(conn, &cmd),
+            Action::Report(cmd) => report::handle_reportcmd(conn, cmd),
+            Action::Forecast(cmd) => forecast::handle_forecastcmd(conn, &cmd),
+            Action::Triage(cmd) => triage::handle_triagecmd(conn, &cmd),
+            Action::Plan(cmd) => plan::handle_plancmd(conn, &cmd),
+            Action::Schedule(cmd) => schedule::handle_schedulecmd(conn, &cmd),
+            Action::Git(cmd) => git::handle_gitcmd(conn, cmd),
+            Action::Reminders(cmd) => reminders::handle_reminderscmd(conn, cmd),
+            Action::Rules(cmd) => rules::handle_rulescmd(conn, cmd),
+            Action::View(cmd) => view::handle_viewcmd(conn, cmd),
+            Action::Query(cmd) => query::handle_querycmd(conn, &cmd),
+            Action::GenDocs(cmd) => docs::handle_gendocscmd(&cmd),
+            #[cfg(feature = "nlp")]
+            Action::HelpTopics(cmd) => crate::actions::help::handle_helptopicscmd(&cmd),
+            #[cfg(feature = "scripting")]
+            Action::Script(cmd) => crate::actions::scripts::handle_scriptcmd(conn, cmd),
+            Action::Unlock(cmd) => private::handle_unlockcmd(&cmd),
+            Action::Lock => private::handle_lockcmd(),
+            Action::Palette => palette::handle_palettecmd(conn),
+            Action::Tutorial => tutorial::handle_tutorialcmd(),
         };
     }
 
@@ -41,6 +106,21 @@ pub fn handle_commands(conn: &Connection, args: CliArgs) -> Result<(), String> {
         return Ok(());
     }
 
+    // Commands that are not built in may come from a `tascli-<command>` plugin on PATH
+    let plugin = std::env::var_os("PATH")
+        .and_then(|path_var| plugin::find_plugin(&args.raw_input[0], &path_var));
+    if let Some(plugin) = plugin {
+        debug!(plugin = %plugin.display(), "running plugin");
+        return plugin::run_plugin(&plugin, &args.raw_input[1..]);
+    }
+    #[cfg(feature = "scripting")]
+    if let Ok(config) = crate::config::get_config()
+        && let Some(script) = crate::scripting::find_command(&config, &args.raw_input[0])
+    {
+        debug!(script = %script.path, "running script command");
+        return crate::scripting::run_command(conn, script, &args.raw_input[1..]);
+    }
+
     // Join raw input into a single string
     let input = args.raw_input.join(" ");
 
@@ -62,18 +142,33 @@ pub fn handle_commands(conn: &Connection, args: CliArgs) -> Result<(), String> {
     }
 
     // Route through NLP by default
+    debug!(input = %input, "routing raw input through nlp");
     route_through_nlp(conn, &input)
 }
 
 /// Route input through NLP parser
-fn route_through_nlp(conn: &Connection, input: &str) -> Result<(), String> {
+#[cfg(feature = "nlp")]
+fn route_through_nlp(conn: &Connection, input: &str) -> Result<(), TascliError> {
     let nlp_cmd = crate::args::parser::NLPCommand {
         description: input.to_string(),
         show: false,
+        non_interactive: false,
+        json: false,
+        simulate: false,
+        parallel: false,
         config: None,
     };
 
-    nlp::handle_nlp_command(conn, &nlp_cmd)
+    crate::actions::nlp::handle_nlp_command(conn, &nlp_cmd)
+}
+
+/// Without NLP support only traditional commands are understood
+#[cfg(not(feature = "nlp"))]
+fn route_through_nlp(_conn: &Connection, input: &str) -> Result<(), TascliError> {
+    Err(TascliError::Parse(format!(
+        "'{}' is not a command, natural language input needs tascli built with the nlp feature",
+        input
+    )))
 }
 
 /// Check if input looks like a traditional command
@@ -85,7 +180,7 @@ fn looks_like_traditional_command(input: &str) -> bool {
 }
 
 /// Try to parse input as a traditional command
-fn try_traditional_parse(conn: &Connection, input: &str) -> Result<(), String> {
+fn try_traditional_parse(conn: &Connection, input: &str) -> Result<(), TascliError> {
     // Prepend "tascli" to simulate command invocation
     let cmd_args: Vec<&str> = std::iter::once("tascli")
         .chain(input.split_whitespace())
@@ -97,7 +192,7 @@ fn try_traditional_parse(conn: &Connection, input: &str) -> Result<(), String> {
             handle_commands(conn, parsed_args)
         },
         Err(e) => {
-            Err(format!("Failed to parse as traditional command: {}", e))
+            Err(TascliError::Parse(format!("Failed to parse as traditional command: {}", e)))
         }
     }
 }
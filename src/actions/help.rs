@@ -0,0 +1,68 @@
+//! `tascli help-topics`, the guides to natural language commands and their examples.
+
+use crate::{
+    actions::display::{
+        print_bold,
+        print_yellow,
+    },
+    args::parser::HelpTopicsCommand,
+    error::TascliError,
+    nlp::{
+        HelpSystem,
+        HelpTopic,
+    },
+};
+
+pub fn handle_helptopicscmd(cmd: &HelpTopicsCommand) -> Result<(), TascliError> {
+    match &cmd.search {
+        Some(query) => search_topics(query),
+        None => show_topic(cmd.topic.as_deref()),
+    }
+}
+
+/// Shows a topic, or the overview and the list of topics without one. `tascli nlp help`
+/// shows the same.
+pub fn show_topic(topic: Option<&str>) -> Result<(), TascliError> {
+    let Some(topic_str) = topic else {
+        HelpSystem::show_overview();
+        println!();
+        HelpSystem::list_topics();
+        return Ok(());
+    };
+    if let Some(help_topic) = HelpTopic::parse(topic_str) {
+        HelpSystem::show_help(help_topic);
+        return Ok(());
+    }
+
+    print_yellow(&format!("Unknown help topic: '{}'", topic_str));
+    println!();
+    let suggestions = HelpSystem::suggest_topic(topic_str);
+    if !suggestions.is_empty() {
+        print_yellow("Did you mean one of these?");
+        for suggestion in suggestions.iter().take(5) {
+            println!("  tascli help-topics {}", suggestion);
+        }
+    }
+    println!();
+    HelpSystem::list_topics();
+    Err(TascliError::Invalid(format!("Unknown help topic: '{}'", topic_str)))
+}
+
+// The matching lines under the topic they are from
+fn search_topics(query: &str) -> Result<(), TascliError> {
+    let results = HelpSystem::search(query);
+    if results.is_empty() {
+        print_yellow(&format!("No help topic mentions '{}'", query));
+        HelpSystem::list_topics();
+        return Ok(());
+    }
+    for (topic, lines) in results {
+        print_bold(&format!("{} - {}", topic.name(), topic.description()));
+        for line in lines {
+            println!("  {}", line);
+        }
+        println!();
+    }
+    println!("Read a whole topic with: tascli help-topics <topic>");
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    fs,
+};
+
+use nanoserde::DeJson;
+use rusqlite::Connection;
+
+use super::insert_imported;
+use crate::{
+    actions::{
+        display,
+        export::backup::{
+            Backup,
+            BackupItem,
+            BACKUP_VERSION,
+        },
+    },
+    db::crud::{
+        find_by_uuid_prefix,
+        update_item,
+    },
+    error::TascliError,
+};
+
+/// Restores the items of a `tascli export --out` file, into `category` when given. Items
+/// restored before are left as they are, so a restore can be repeated.
+pub fn restore_backup(
+    conn: &Connection,
+    path: &str,
+    category: Option<&str>,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", path), e))?;
+    let backup = Backup::deserialize_json(&json)
+        .map_err(|e| TascliError::Parse(format!("Not a tascli export: {}", e)))?;
+    if backup.version > BACKUP_VERSION {
+        return Err(TascliError::Parse(format!(
+            "{} was exported by a newer tascli, update to restore it",
+            path
+        )));
+    }
+
+    let (restored, skipped) = restore(conn, &backup.items, category, dry_run)?;
+    let verb = if dry_run { "Would restore" } else { "Restored" };
+    display::print_bold(&format!("{} {} items from {}", verb, restored, path));
+    if skipped > 0 {
+        display::print_yellow(&format!("Skipped {} items already in the database", skipped));
+    }
+    Ok(())
+}
+
+// The numbers of items restored and skipped
+fn restore(
+    conn: &Connection,
+    items: &[BackupItem],
+    category: Option<&str>,
+    dry_run: bool,
+) -> Result<(usize, usize), TascliError> {
+    // Recurring tasks go before their records, which need their new ids
+    let (tasks, records): (Vec<&BackupItem>, Vec<&BackupItem>) =
+        items.iter().partition(|item| item.recurring_task_uuid.is_none());
+    let mut ids: HashMap<&str, i64> = HashMap::new();
+    let mut restored = 0;
+    for backup_item in tasks.into_iter().chain(records) {
+        if let Some(&id) = find_by_uuid_prefix(conn, &backup_item.uuid, 1)?.first() {
+            ids.insert(&backup_item.uuid, id);
+            continue;
+        }
+        restored += 1;
+        if dry_run {
+            continue;
+        }
+        let mut item = backup_item.to_item();
+        if let Some(category) = category {
+            item.category = category.to_string();
+        }
+        if let Some(uuid) = &backup_item.recurring_task_uuid {
+            item.recurring_task_id = match ids.get(uuid.as_str()) {
+                Some(&id) => Some(id),
+                None => find_by_uuid_prefix(conn, uuid, 1)?.first().copied(),
+            };
+        }
+        // Parents were added before their subtasks, and come first in a backup
+        if let Some(uuid) = &backup_item.parent_uuid {
+            item.parent_id = match ids.get(uuid.as_str()) {
+                Some(&id) => Some(id),
+                None => find_by_uuid_prefix(conn, uuid, 1)?.first().copied(),
+            };
+        }
+        let item = insert_imported(conn, item)?;
+        // New items start out ongoing
+        if item.status != 0 {
+            update_item(conn, &item)?;
+        }
+        ids.insert(&backup_item.uuid, item.id.unwrap());
+    }
+    Ok((restored, items.len() - restored))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::export::backup::build_backup,
+        db::{
+            crud::{
+                get_item,
+                insert_item,
+                query_items,
+            },
+            item::{
+                Item,
+                ItemQuery,
+                TASK,
+            },
+        },
+        tests::{
+            get_test_conn,
+            insert_recurring_record,
+            insert_recurring_task,
+            insert_task,
+            update_status,
+        },
+    };
+
+    fn backup_items(conn: &Connection) -> Vec<BackupItem> {
+        build_backup(conn, None).unwrap().items
+    }
+
+    #[test]
+    fn test_restore() {
+        let (conn, _temp_file) = get_test_conn();
+        let beta = insert_task(&conn, "side", "ship the beta", "friday");
+        update_status(&conn, beta, 1);
+        let review = insert_recurring_task(&conn, "side", "weekly review", "Daily 9AM");
+        insert_recurring_record(&conn, "side", "weekly review", review, 0);
+        let items = backup_items(&conn);
+
+        let (other, _other_file) = get_test_conn();
+        assert_eq!(restore(&other, &items, Some("archive"), true).unwrap(), (3, 0));
+        assert!(query_items(&other, &ItemQuery::new()).unwrap().is_empty());
+
+        assert_eq!(restore(&other, &items, Some("archive"), false).unwrap(), (3, 0));
+        let restored = query_items(&other, &ItemQuery::new()).unwrap();
+        assert!(restored.iter().all(|item| item.category == "archive"));
+        assert_eq!(restored[0].uuid, items[0].uuid);
+        assert_eq!(restored[0].status, 1);
+        let record = restored.iter().find(|item| item.recurring_task_id.is_some()).unwrap();
+        let task = get_item(&other, record.recurring_task_id.unwrap()).unwrap();
+        assert_eq!(task.content, "weekly review");
+
+        // Restoring again finds everything in place
+        assert_eq!(restore(&other, &items, None, false).unwrap(), (0, 3));
+    }
+
+    #[test]
+    fn test_restore_subtasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let post = insert_task(&conn, "blog", "write post", "friday");
+        let mut intro = Item::new(TASK.to_string(), "blog".to_string(), "intro".to_string());
+        intro.parent_id = Some(post);
+        insert_item(&conn, &intro).unwrap();
+        let items = backup_items(&conn);
+        assert_eq!(items[1].parent_uuid.as_ref(), Some(&items[0].uuid));
+
+        let (other, _other_file) = get_test_conn();
+        restore(&other, &items, None, false).unwrap();
+        let restored = query_items(&other, &ItemQuery::new()).unwrap();
+        assert_eq!(restored[1].parent_id, restored[0].id);
+    }
+}
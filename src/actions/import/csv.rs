@@ -0,0 +1,368 @@
+use chrono::{
+    DateTime,
+    Local,
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use super::save_new;
+use crate::{
+    args::timestr,
+    db::item::{
+        Item,
+        TASK,
+    },
+    error::TascliError,
+};
+
+// Formats commonly exported by spreadsheets and other task managers,
+// tried after the formats tascli itself accepts.
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y/%m/%d %H:%M:%S",
+    "%m/%d/%Y %I:%M %p",
+    "%m/%d/%Y %H:%M",
+];
+const DATE_FORMATS: &[&str] = &[
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+    "%Y.%m.%d",
+    "%b %d, %Y",
+    "%B %d, %Y",
+    "%d %b %Y",
+    "%d %B %Y",
+    "%Y%m%d",
+];
+
+// Columns of the task csv export of Outlook (File > Open & Export > Import/Export)
+pub(super) const OUTLOOK_MAP: &str =
+    "content=Subject,deadline=Due Date,category=Categories,done=Status";
+// Values of a done column marking a row as finished, such as the Completed status of Outlook
+const DONE_VALUES: &[&str] = &["completed", "complete", "done", "true", "yes", "x", "1"];
+
+#[derive(Debug, PartialEq)]
+struct ColumnMap {
+    content: String,
+    deadline: Option<String>,
+    category: Option<String>,
+    done: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct ColumnIndices {
+    content: usize,
+    deadline: Option<usize>,
+    category: Option<usize>,
+    done: Option<usize>,
+}
+
+pub fn import_csv(
+    conn: &Connection,
+    path: &str,
+    map: Option<&str>,
+    category: Option<&str>,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", path), e.into()))?;
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| TascliError::io(format!("Failed to read header of {}", path), e.into()))?
+        .iter()
+        // Excel and Outlook start their exports with a byte order mark
+        .map(|h| h.trim_start_matches('\u{feff}').to_string())
+        .collect();
+    let columns = match map {
+        Some(spec) => resolve_columns(&parse_map(spec)?, &headers, true)?,
+        None => {
+            let defaults = ColumnMap {
+                content: "content".to_string(),
+                deadline: Some("deadline".to_string()),
+                category: Some("category".to_string()),
+                done: None,
+            };
+            resolve_columns(&defaults, &headers, false)?
+        }
+    };
+
+    // Every row is parsed before anything is saved, so a bad row leaves the db untouched
+    let mut items = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        // Data starts on line 2, after the header
+        let line = row + 2;
+        let record = record.map_err(|e| TascliError::Parse(format!("Line {}: {}", line, e)))?;
+        let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or("");
+
+        let content = field(Some(columns.content));
+        let done = field(columns.done);
+        if content.is_empty() || DONE_VALUES.iter().any(|v| done.eq_ignore_ascii_case(v)) {
+            continue;
+        }
+        // Outlook joins several categories with semicolons, the first one is used
+        let category = match (category, field(columns.category).split(';').next()) {
+            (Some(c), _) => c.to_string(),
+            (None, Some(c)) if !c.trim().is_empty() => c.trim().to_string(),
+            (None, _) => "default".to_string(),
+        };
+        let deadline = match field(columns.deadline) {
+            "" => timestr::to_unix_epoch("today")?,
+            d => parse_deadline(d)
+                .map_err(|e| TascliError::Parse(format!("Line {}: {}", line, e)))?,
+        };
+
+        let item = Item::with_target_time(
+            TASK.to_string(),
+            category,
+            content.to_string(),
+            Some(deadline),
+        );
+        // A row repeated in the file is imported once, as it is on a later import
+        if items
+            .iter()
+            .any(|i: &Item| i.category == item.category && i.content == item.content)
+        {
+            continue;
+        }
+        items.push(item);
+    }
+
+    save_new(conn, path, items, dry_run)
+}
+
+fn parse_map(spec: &str) -> Result<ColumnMap, TascliError> {
+    let mut content = None;
+    let mut deadline = None;
+    let mut category = None;
+    let mut done = None;
+    for pair in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        let (field, column) = pair
+            .split_once('=')
+            .ok_or_else(|| {
+                TascliError::Parse(format!(
+                    "Invalid mapping '{}', expected field=Column",
+                    pair.trim()
+                ))
+            })?;
+        let column = Some(column.trim().to_string());
+        match field.trim().to_lowercase().as_str() {
+            "content" => content = column,
+            "deadline" => deadline = column,
+            "category" => category = column,
+            "done" => done = column,
+            other => {
+                return Err(TascliError::Parse(format!(
+                    "Unknown field '{}', expected content, deadline, category or done",
+                    other
+                )))
+            }
+        }
+    }
+
+    Ok(ColumnMap {
+        content: content.ok_or_else(|| "The mapping needs a content column".to_string())?,
+        deadline,
+        category,
+        done,
+    })
+}
+
+// With strict set, every mapped column has to exist in the header.
+fn resolve_columns(
+    map: &ColumnMap,
+    headers: &[String],
+    strict: bool,
+) -> Result<ColumnIndices, TascliError> {
+    let find = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let content = find(&map.content).ok_or_else(|| {
+        format!(
+            "Column '{}' not found, available columns are: {}",
+            map.content,
+            headers.join(", ")
+        )
+    })?;
+
+    let optional = |name: &Option<String>| -> Result<Option<usize>, TascliError> {
+        match name.as_deref().map(|n| (n, find(n))) {
+            Some((n, None)) if strict => {
+                Err(TascliError::Parse(format!("Column '{}' not found", n)))
+            }
+            Some((_, index)) => Ok(index),
+            None => Ok(None),
+        }
+    };
+    Ok(ColumnIndices {
+        content,
+        deadline: optional(&map.deadline)?,
+        category: optional(&map.category)?,
+        done: optional(&map.done)?,
+    })
+}
+
+pub(super) fn parse_deadline(s: &str) -> Result<i64, TascliError> {
+    if let Ok(t) = timestr::to_unix_epoch(s) {
+        return Ok(t);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.timestamp());
+    }
+
+    let datetime = DATETIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            // Date only deadlines are due at the end of the day, as with tascli task
+            DATE_FORMATS
+                .iter()
+                .find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+                .map(|d| d.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()))
+        })
+        .ok_or_else(|| TascliError::Parse(format!("Couldn't parse '{}' as a deadline", s)))?;
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .ok_or_else(|| TascliError::Parse(format!("Couldn't parse '{}' as a deadline", s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::{
+        db::{
+            crud::query_items,
+            item::ItemQuery,
+        },
+        tests::get_test_conn,
+    };
+
+    #[test]
+    fn test_parse_map() {
+        assert_eq!(
+            parse_map("content=Title, deadline=Due,category=Project").unwrap(),
+            ColumnMap {
+                content: "Title".to_string(),
+                deadline: Some("Due".to_string()),
+                category: Some("Project".to_string()),
+                done: None,
+            }
+        );
+        assert_eq!(parse_map(OUTLOOK_MAP).unwrap().done, Some("Status".to_string()));
+        assert!(parse_map("deadline=Due").is_err());
+        assert!(parse_map("content").is_err());
+        assert!(parse_map("content=Title,owner=Who").is_err());
+    }
+
+    #[test]
+    fn test_parse_deadline() {
+        let end_of_day = |y, m, d| {
+            Local
+                .with_ymd_and_hms(y, m, d, 23, 59, 59)
+                .unwrap()
+                .timestamp()
+        };
+        assert_eq!(parse_deadline("2025-05-01").unwrap(), end_of_day(2025, 5, 1));
+        assert_eq!(parse_deadline("05/01/2025").unwrap(), end_of_day(2025, 5, 1));
+        assert_eq!(parse_deadline("01.05.2025").unwrap(), end_of_day(2025, 5, 1));
+        assert_eq!(parse_deadline("May 1, 2025").unwrap(), end_of_day(2025, 5, 1));
+        assert_eq!(parse_deadline("1 May 2025").unwrap(), end_of_day(2025, 5, 1));
+        assert_eq!(
+            parse_deadline("2025-05-01T09:30:00").unwrap(),
+            Local
+                .with_ymd_and_hms(2025, 5, 1, 9, 30, 0)
+                .unwrap()
+                .timestamp()
+        );
+        assert_eq!(parse_deadline("5/1/2025").unwrap(), end_of_day(2025, 5, 1));
+        assert_eq!(parse_deadline("2025-05-01T09:30:00.000Z").unwrap(), 1746091800);
+        assert!(parse_deadline("someday").is_err());
+    }
+
+    #[test]
+    fn test_import_csv() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut file = NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "Title,Due,Project\n\"Plan offsite, agenda\",2025-05-01,work\n\"Say \"\"hi\"\"\",,\n,2025-05-02,work\n"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+        let map = Some("content=Title,deadline=Due,category=Project");
+
+        import_csv(&conn, path, map, None, true).unwrap();
+        assert!(query_items(&conn, &ItemQuery::new()).unwrap().is_empty());
+
+        import_csv(&conn, path, map, None, false).unwrap();
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].content, "Plan offsite, agenda");
+        assert_eq!(tasks[0].category, "work");
+        assert_eq!(tasks[0].target_time, Some(parse_deadline("2025-05-01").unwrap()));
+        assert_eq!(tasks[1].content, "Say \"hi\"");
+        assert_eq!(tasks[1].category, "default");
+
+        // Already imported rows are skipped
+        import_csv(&conn, path, map, None, false).unwrap();
+        assert_eq!(
+            query_items(&conn, &ItemQuery::new().with_action(TASK))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert!(import_csv(&conn, path, Some("content=Name"), None, false).is_err());
+        assert!(import_csv(&conn, path, Some("content=Title,deadline=When"), None, false).is_err());
+        // Without a mapping, content/deadline/category headers are used
+        assert!(import_csv(&conn, path, None, None, false).is_err());
+    }
+
+    #[test]
+    fn test_import_repeated_row() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut file = NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "content,deadline,category\nbuy milk,,home\nbuy milk,,home\nbuy milk,,errands\n"
+        )
+        .unwrap();
+        import_csv(&conn, file.path().to_str().unwrap(), None, None, false).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        let categories: Vec<&str> = tasks.iter().map(|t| t.category.as_str()).collect();
+        assert_eq!(categories, ["home", "errands"]);
+    }
+
+    #[test]
+    fn test_import_outlook() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut file = NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "\u{feff}\"Subject\",\"Start Date\",\"Due Date\",\"Categories\",\"Status\"\n\
+             \"Quarterly review\",\"4/28/2025\",\"5/2/2025\",\"Work;Important\",\"In Progress\"\n\
+             \"Expense report\",,\"4/30/2025\",\"Work\",\"Completed\"\n\
+             \"Renew badge\",,,,\"Not Started\"\n"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        import_csv(&conn, path, Some(OUTLOOK_MAP), None, false).unwrap();
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        let imported: Vec<(&str, &str)> =
+            tasks.iter().map(|t| (t.category.as_str(), t.content.as_str())).collect();
+        assert_eq!(imported, vec![("Work", "Quarterly review"), ("default", "Renew badge")]);
+        assert_eq!(tasks[0].target_time, Some(parse_deadline("2025-05-02").unwrap()));
+    }
+}
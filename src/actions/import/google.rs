@@ -0,0 +1,167 @@
+use std::fs;
+
+use nanoserde::DeJson;
+use rusqlite::Connection;
+
+use super::{
+    csv::parse_deadline,
+    save_new,
+};
+use crate::{
+    args::timestr,
+    db::item::{
+        Item,
+        TASK,
+    },
+    error::TascliError,
+};
+
+// Tasks.json of a Google Takeout export, the fields not used here are skipped
+#[derive(DeJson)]
+struct Takeout {
+    #[nserde(default)]
+    items: Vec<TaskList>,
+}
+
+#[derive(DeJson)]
+struct TaskList {
+    #[nserde(default)]
+    title: String,
+    #[nserde(default)]
+    items: Vec<GoogleTask>,
+}
+
+#[derive(DeJson)]
+struct GoogleTask {
+    #[nserde(default)]
+    title: String,
+    #[nserde(default)]
+    notes: String,
+    // "needsAction" or "completed"
+    #[nserde(default)]
+    status: String,
+    // Google Tasks only keeps the date of a due date, given as midnight UTC
+    #[nserde(default)]
+    due: String,
+    #[nserde(default)]
+    deleted: bool,
+}
+
+pub fn import_google_takeout(
+    conn: &Connection,
+    path: &str,
+    category: Option<&str>,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", path), e))?;
+    save_new(conn, path, parse_takeout(&json, category)?, dry_run)
+}
+
+// Open tasks of every list, the list becoming the category unless one is given.
+// Completed and deleted tasks are left behind.
+fn parse_takeout(json: &str, category: Option<&str>) -> Result<Vec<Item>, TascliError> {
+    let takeout = Takeout::deserialize_json(json)
+        .map_err(|e| TascliError::Parse(format!("Not a Google Tasks export: {}", e)))?;
+    let mut items = Vec::new();
+    for list in &takeout.items {
+        let category = match (category, list.title.trim()) {
+            (Some(c), _) => c.to_string(),
+            (None, "") => "default".to_string(),
+            (None, title) => title.to_string(),
+        };
+        for task in &list.items {
+            let title = task.title.trim();
+            if title.is_empty() || task.deleted || task.status == "completed" {
+                continue;
+            }
+            let content = match task.notes.split_whitespace().collect::<Vec<_>>().join(" ") {
+                notes if notes.is_empty() => title.to_string(),
+                notes => format!("{} - {}", title, notes),
+            };
+            let deadline = match task.due.get(..10) {
+                Some(date) => parse_deadline(date)?,
+                None => timestr::to_unix_epoch("today")?,
+            };
+            items.push(Item::with_target_time(
+                TASK.to_string(),
+                category.clone(),
+                content,
+                Some(deadline),
+            ));
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::{
+        db::{
+            crud::query_items,
+            item::ItemQuery,
+        },
+        tests::get_test_conn,
+    };
+
+    const TAKEOUT: &str = r#"{
+        "kind": "tasks#taskLists",
+        "items": [{
+            "kind": "tasks#taskList",
+            "id": "MTIz",
+            "title": "Groceries",
+            "updated": "2026-10-01T08:00:00.000Z",
+            "items": [
+                {"kind": "tasks#task", "title": "Buy milk", "status": "needsAction",
+                 "due": "2026-10-20T00:00:00.000Z", "position": "00000000000000000000"},
+                {"kind": "tasks#task", "title": "Bake bread", "status": "completed",
+                 "completed": "2026-10-02T09:00:00.000Z"},
+                {"kind": "tasks#task", "title": "Old list", "status": "needsAction",
+                 "deleted": true}
+            ]
+        }, {
+            "kind": "tasks#taskList",
+            "title": "Work",
+            "items": [
+                {"kind": "tasks#task", "title": "Call Sam", "status": "needsAction",
+                 "notes": "about the\nbudget", "links": [{"type": "email"}]}
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn test_parse_takeout() {
+        let items = parse_takeout(TAKEOUT, None).unwrap();
+        let tasks: Vec<(&str, &str)> =
+            items.iter().map(|i| (i.category.as_str(), i.content.as_str())).collect();
+        assert_eq!(tasks, vec![("Groceries", "Buy milk"), ("Work", "Call Sam - about the budget")]);
+        assert_eq!(items[0].target_time, Some(parse_deadline("2026-10-20").unwrap()));
+        assert!(items[0].all_day);
+        assert_eq!(items[1].target_time, Some(timestr::to_unix_epoch("today").unwrap()));
+
+        let items = parse_takeout(TAKEOUT, Some("inbox")).unwrap();
+        assert!(items.iter().all(|i| i.category == "inbox"));
+        assert!(parse_takeout("[1, 2]", None).is_err());
+    }
+
+    #[test]
+    fn test_import_google_takeout() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", TAKEOUT).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        import_google_takeout(&conn, path, None, true).unwrap();
+        assert!(query_items(&conn, &ItemQuery::new()).unwrap().is_empty());
+        import_google_takeout(&conn, path, None, false).unwrap();
+        // Already imported tasks are skipped
+        import_google_takeout(&conn, path, None, false).unwrap();
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 2);
+    }
+}
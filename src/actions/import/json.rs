@@ -0,0 +1,330 @@
+use std::{
+    fs,
+    str::Chars,
+};
+
+use nanoserde::{
+    DeJson,
+    DeJsonErr,
+    DeJsonState,
+    DeJsonTok,
+};
+use rusqlite::Connection;
+
+use super::{
+    csv::parse_deadline,
+    save_new,
+};
+use crate::{
+    args::timestr,
+    db::item::{
+        Item,
+        TASK,
+    },
+    error::TascliError,
+};
+
+// Timestamps above this are taken as milliseconds, it is in 1973 as milliseconds
+// and in the year 5138 as seconds
+const MILLIS_THRESHOLD: f64 = 1e11;
+
+// Where the fields of a task are found in the json export of another app. Paths are keys
+// joined by dots, with numbers indexing arrays, such as "list.name".
+#[derive(Debug, Default, DeJson)]
+struct JsonMapping {
+    // Path of the array of tasks, empty when the file is the array
+    #[nserde(default)]
+    tasks: String,
+    content: String,
+    #[nserde(default)]
+    deadline: String,
+    #[nserde(default)]
+    category: String,
+    // "path" skips tasks where it is set, "path=value" the ones where it is that value
+    #[nserde(default)]
+    done: String,
+    #[nserde(default)]
+    lists: ListMapping,
+}
+
+// Looks up category names when tasks only hold the id of their list
+#[derive(Debug, Default, DeJson)]
+struct ListMapping {
+    // Path of the array of lists, left empty when the category is a name already
+    #[nserde(default)]
+    path: String,
+    #[nserde(default)]
+    id: String,
+    #[nserde(default)]
+    name: String,
+}
+
+// Any JSON value, as nanoserde only reads into known types
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl DeJson for Json {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let value = match s.tok {
+            DeJsonTok::Null => Json::Null,
+            DeJsonTok::Bool(b) => Json::Bool(b),
+            DeJsonTok::U64(_) | DeJsonTok::I64(_) | DeJsonTok::F64(_) => {
+                Json::Number(s.as_f64()?)
+            }
+            DeJsonTok::Str => Json::Str(s.as_string()?),
+            DeJsonTok::BlockOpen => return Ok(Json::Array(Vec::de_json(s, i)?)),
+            DeJsonTok::CurlyOpen => {
+                let mut fields = Vec::new();
+                s.curly_open(i)?;
+                while s.tok != DeJsonTok::CurlyClose {
+                    let key = String::de_json(s, i)?;
+                    s.colon(i)?;
+                    fields.push((key, Json::de_json(s, i)?));
+                    s.eat_comma_curly(i)?;
+                }
+                s.curly_close(i)?;
+                return Ok(Json::Object(fields));
+            }
+            _ => return Err(s.err_token("a json value")),
+        };
+        s.next_tok(i)?;
+        Ok(value)
+    }
+}
+
+impl Json {
+    fn get(&self, path: &str) -> Option<&Json> {
+        path.split('.').filter(|key| !key.is_empty()).try_fold(self, |value, key| match value {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            Json::Array(values) => values.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    // Scalars as text, empty for null, arrays and objects
+    fn text(&self) -> String {
+        match self {
+            Json::Bool(b) => b.to_string(),
+            Json::Number(n) => n.to_string(),
+            Json::Str(s) => s.trim().to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        !matches!(self, Json::Null | Json::Bool(false) | Json::Array(_) | Json::Object(_))
+            && !matches!(self.text().as_str(), "" | "0")
+    }
+}
+
+pub fn import_json(
+    conn: &Connection,
+    path: &str,
+    mapping_path: &str,
+    category: Option<&str>,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let mapping = fs::read_to_string(mapping_path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", mapping_path), e))?;
+    let mapping = JsonMapping::deserialize_json(&mapping)
+        .map_err(|e| TascliError::Parse(format!("Invalid mapping {}: {}", mapping_path, e)))?;
+    let json = fs::read_to_string(path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", path), e))?;
+    save_new(conn, path, parse_tasks(&json, &mapping, category)?, dry_run)
+}
+
+fn parse_tasks(
+    json: &str,
+    mapping: &JsonMapping,
+    category: Option<&str>,
+) -> Result<Vec<Item>, TascliError> {
+    let root = Json::deserialize_json(json)
+        .map_err(|e| TascliError::Parse(format!("Invalid json: {}", e)))?;
+    let Some(Json::Array(tasks)) = root.get(&mapping.tasks) else {
+        return Err(TascliError::Parse(format!(
+            "No array of tasks at '{}' of the json",
+            mapping.tasks
+        )));
+    };
+    let lists = match root.get(&mapping.lists.path) {
+        Some(Json::Array(lists)) if !mapping.lists.path.is_empty() => lists.as_slice(),
+        _ => &[],
+    };
+
+    let mut items = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let field = |path: &str| match path {
+            "" => None,
+            path => task.get(path),
+        };
+        let content = field(&mapping.content).map(Json::text).unwrap_or_default();
+        if content.is_empty() || is_done(task, &mapping.done) {
+            continue;
+        }
+        let list = field(&mapping.category).map(Json::text).unwrap_or_default();
+        let category = match category {
+            Some(c) => c.to_string(),
+            None => match list_name(lists, &mapping.lists, &list).unwrap_or(list) {
+                name if name.is_empty() => "default".to_string(),
+                name => name,
+            },
+        };
+        let deadline = match field(&mapping.deadline).filter(|d| d.is_set()) {
+            Some(due) => parse_json_deadline(due)
+                .map_err(|e| TascliError::Parse(format!("Task {}: {}", index + 1, e)))?,
+            None => timestr::to_unix_epoch("today")?,
+        };
+        items.push(Item::with_target_time(TASK.to_string(), category, content, Some(deadline)));
+    }
+    Ok(items)
+}
+
+fn is_done(task: &Json, done: &str) -> bool {
+    if done.is_empty() {
+        return false;
+    }
+    match done.split_once('=') {
+        Some((path, value)) => {
+            task.get(path.trim()).is_some_and(|v| v.text().eq_ignore_ascii_case(value.trim()))
+        }
+        None => task.get(done.trim()).is_some_and(Json::is_set),
+    }
+}
+
+fn list_name(lists: &[Json], mapping: &ListMapping, id: &str) -> Option<String> {
+    lists
+        .iter()
+        .find(|list| list.get(&mapping.id).is_some_and(|v| v.text() == id))
+        .and_then(|list| list.get(&mapping.name))
+        .map(Json::text)
+}
+
+// Unix timestamps in seconds or milliseconds, or any date csv imports accept
+fn parse_json_deadline(due: &Json) -> Result<i64, TascliError> {
+    let text = due.text();
+    let number = match due {
+        Json::Number(n) => Some(*n),
+        _ => text.parse::<f64>().ok().filter(|_| text.len() > 8),
+    };
+    match number {
+        Some(n) if n > MILLIS_THRESHOLD => Ok((n / 1000.0) as i64),
+        Some(n) => Ok(n as i64),
+        None => parse_deadline(&text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use chrono::{
+        Local,
+        TimeZone,
+    };
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::{
+        db::{
+            crud::query_items,
+            item::ItemQuery,
+        },
+        tests::get_test_conn,
+    };
+
+    fn mapping(json: &str) -> JsonMapping {
+        JsonMapping::deserialize_json(json).unwrap()
+    }
+
+    #[test]
+    fn test_json_get() {
+        let json = Json::deserialize_json(r#"{"a": {"b": [1, "two", null]}, "c": -1.5e1}"#)
+            .unwrap();
+        assert_eq!(json.get("a.b.1"), Some(&Json::Str("two".to_string())));
+        assert_eq!(json.get("a.b.0").map(Json::text), Some("1".to_string()));
+        assert_eq!(json.get("c"), Some(&Json::Number(-15.0)));
+        assert_eq!(json.get("a.b.3"), None);
+        assert_eq!(json.get("a.x"), None);
+        assert!(matches!(json.get(""), Some(Json::Object(_))));
+        assert!(!json.get("a.b.2").unwrap().is_set());
+    }
+
+    #[test]
+    fn test_parse_tasks_with_lists() {
+        // Tasks referring to their list by id, with millisecond timestamps
+        let export = r#"{
+            "lists": [{"id": "10", "name": "Errands"}, {"id": "11", "name": "Work"}],
+            "tasks": [
+                {"name": "Post parcel", "list_id": "10", "date_due": 1760000000000,
+                 "date_completed": null},
+                {"name": "Send invoice", "list_id": "11", "date_due": null,
+                 "date_completed": 1759000000000},
+                {"name": "Read book", "list_id": "99", "date_due": null}
+            ]
+        }"#;
+        let mapping = mapping(
+            r#"{"tasks": "tasks", "content": "name", "deadline": "date_due",
+                "category": "list_id", "done": "date_completed",
+                "lists": {"path": "lists", "id": "id", "name": "name"}}"#,
+        );
+        let items = parse_tasks(export, &mapping, None).unwrap();
+        let tasks: Vec<(&str, &str)> =
+            items.iter().map(|i| (i.category.as_str(), i.content.as_str())).collect();
+        assert_eq!(tasks, vec![("Errands", "Post parcel"), ("99", "Read book")]);
+        assert_eq!(items[0].target_time, Some(1760000000));
+        assert_eq!(items[1].target_time, Some(timestr::to_unix_epoch("today").unwrap()));
+    }
+
+    #[test]
+    fn test_parse_tasks_top_level_array() {
+        let export = r#"[
+            {"title": "Plan trip", "status": "UNCHECKED", "due": "2025-05-01T09:30:00",
+             "tags": ["home"]},
+            {"title": "Pay rent", "status": "CHECKED", "due": "2025-05-01"},
+            {"title": "", "status": "UNCHECKED"}
+        ]"#;
+        let mapping = mapping(
+            r#"{"content": "title", "deadline": "due", "category": "tags.0",
+                "done": "status=checked"}"#,
+        );
+        let items = parse_tasks(export, &mapping, None).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].category, "home");
+        assert_eq!(
+            items[0].target_time,
+            Some(Local.with_ymd_and_hms(2025, 5, 1, 9, 30, 0).unwrap().timestamp())
+        );
+        let items = parse_tasks(export, &mapping, Some("trips")).unwrap();
+        assert_eq!(items[0].category, "trips");
+
+        let wrong = JsonMapping { tasks: "tasks".to_string(), ..mapping };
+        assert!(parse_tasks(export, &wrong, None).is_err());
+        assert!(parse_tasks("{\"tasks\": [", &wrong, None).is_err());
+    }
+
+    #[test]
+    fn test_import_json() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut export = NamedTempFile::new().unwrap();
+        write!(export, r#"[{{"title": "Plan trip"}}, {{"title": "Pay rent"}}]"#).unwrap();
+        let mut mapping = NamedTempFile::new().unwrap();
+        write!(mapping, r#"{{"content": "title"}}"#).unwrap();
+        let export = export.path().to_str().unwrap();
+        let mapping = mapping.path().to_str().unwrap();
+
+        import_json(&conn, export, mapping, None, true).unwrap();
+        assert!(query_items(&conn, &ItemQuery::new()).unwrap().is_empty());
+        import_json(&conn, export, mapping, Some("home"), false).unwrap();
+        import_json(&conn, export, mapping, Some("home"), false).unwrap();
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(import_json(&conn, export, export, None, false).is_err());
+    }
+}
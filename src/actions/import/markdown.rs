@@ -0,0 +1,330 @@
+use std::{
+    fs,
+    path::Path,
+    sync::LazyLock,
+};
+
+use chrono::{
+    Local,
+    TimeZone,
+};
+use regex::Regex;
+use rusqlite::Connection;
+
+use super::{
+    find_existing,
+    insert_imported,
+};
+use crate::{
+    actions::display,
+    args::timestr,
+    clock,
+    config::write_atomic,
+    db::item::{
+        Item,
+        TASK,
+    },
+    error::TascliError,
+};
+
+// "- [ ] description", also matching "*" and "+" bullets, numbered lists,
+// and the "[/]" in progress marker used by Obsidian Tasks.
+static CHECKBOX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*(?:[-*+]|\d+[.)])\s+\[)([ /])(\]\s+)(.*?)(\r?\n)?$").unwrap()
+});
+
+static DATE_FIELD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(📅|⏳|🛫|➕|✅|❌)\u{FE0F}?\s*(\d{4}-\d{2}-\d{2})").unwrap()
+});
+
+static RECURRENCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"🔁\u{FE0F}?\s*([^📅⏳🛫➕✅❌⏫🔼🔽🔺⏬🆔⛔🏁#^]+)").unwrap()
+});
+
+static OTHER_FIELD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:(?:🆔|⛔|🏁)\u{FE0F}?\s*\S+|[⏫🔼🔽🔺⏬]\u{FE0F}?)").unwrap()
+});
+
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)#([\w/-]+)").unwrap());
+
+static BLOCK_ID_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+\^[\w-]+\s*$").unwrap());
+
+// Tasks plugin users commonly mark checkboxes with #task as a global filter,
+// it carries no meaning as a category.
+const IGNORED_TAG: &str = "task";
+
+#[derive(Debug, PartialEq)]
+struct MarkdownTask {
+    content: String,
+    tag: Option<String>,
+    due: Option<String>,
+    recurrence: Option<String>,
+}
+
+pub fn import_markdown(
+    conn: &Connection,
+    path: &str,
+    category: Option<&str>,
+    write_back: bool,
+) -> Result<(), TascliError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", path), e))?;
+    let mut lines: Vec<String> = source.split_inclusive('\n').map(String::from).collect();
+
+    let mut imported = Vec::new();
+    let mut ticked = 0;
+    for line in lines.iter_mut() {
+        let Some(task) = parse_task_line(line) else {
+            continue;
+        };
+        let category = category
+            .map(String::from)
+            .or_else(|| task.tag.clone())
+            .unwrap_or_else(|| "default".to_string());
+        let item = to_item(&task, category)?;
+
+        match find_existing(conn, &item)? {
+            None => imported.push(insert_imported(conn, item)?),
+            Some(existing) => {
+                if write_back && existing.action == TASK && existing.status == 1 {
+                    let done_time = existing
+                        .modify_time
+                        .unwrap_or_else(clock::timestamp);
+                    *line = tick_line(line, done_time);
+                    ticked += 1;
+                }
+            }
+        }
+    }
+
+    if ticked > 0 {
+        // The notes file is rewritten in full, a crash must not truncate it
+        write_atomic(Path::new(path), lines.concat().as_bytes())
+            .map_err(|e| TascliError::io(format!("Failed to write {}", path), e))?;
+        display::print_green(&format!("Ticked {} completed tasks in {}", ticked, path));
+    }
+    if imported.is_empty() {
+        display::print_bold(&format!("No new tasks found in {}", path));
+    } else {
+        display::print_bold(&format!("Imported {} tasks from {}:", imported.len(), path));
+        display::print_items(&imported, false, false);
+    }
+    Ok(())
+}
+
+fn parse_task_line(line: &str) -> Option<MarkdownTask> {
+    let caps = CHECKBOX_RE.captures(line)?;
+    let mut text = caps[4].to_string();
+
+    let mut due = None;
+    let mut scheduled = None;
+    for field in DATE_FIELD_RE.captures_iter(&caps[4]) {
+        match &field[1] {
+            "📅" => due = Some(field[2].to_string()),
+            "⏳" => scheduled = Some(field[2].to_string()),
+            _ => {}
+        }
+    }
+    text = DATE_FIELD_RE.replace_all(&text, "").to_string();
+
+    let recurrence = RECURRENCE_RE
+        .captures(&text)
+        .map(|c| c[1].trim().to_string());
+    text = RECURRENCE_RE.replace_all(&text, "").to_string();
+    text = OTHER_FIELD_RE.replace_all(&text, "").to_string();
+    text = BLOCK_ID_RE.replace(&text, "").to_string();
+
+    let tag = TAG_RE
+        .captures_iter(&text)
+        .map(|c| c[1].to_string())
+        .find(|t| t != IGNORED_TAG);
+    text = TAG_RE.replace_all(&text, "").to_string();
+
+    let content = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if content.is_empty() {
+        return None;
+    }
+    Some(MarkdownTask {
+        content,
+        tag,
+        due: due.or(scheduled),
+        recurrence,
+    })
+}
+
+fn to_item(task: &MarkdownTask, category: String) -> Result<Item, TascliError> {
+    // Obsidian writes recurrences as "every week on Monday", the leading
+    // "every" variants tascli understands are taken as is.
+    if let Some(recurrence) = &task.recurrence {
+        let human_schedule = recurrence.replace(" on ", " ");
+        if let Ok(cron_schedule) = timestr::parse_recurring_timestr(&human_schedule) {
+            return Ok(Item::create_recurring_task(
+                category,
+                task.content.clone(),
+                cron_schedule,
+                human_schedule,
+            ));
+        }
+    }
+
+    let target_timestr = task.due.as_deref().unwrap_or("today");
+    let target_time = timestr::to_unix_epoch(target_timestr)?;
+    Ok(Item::with_target_time(
+        TASK.to_string(),
+        category,
+        task.content.clone(),
+        Some(target_time),
+    ))
+}
+
+fn tick_line(line: &str, done_time: i64) -> String {
+    let Some(caps) = CHECKBOX_RE.captures(line) else {
+        return line.to_string();
+    };
+    let done_date = Local
+        .timestamp_opt(done_time, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+        .format("%Y-%m-%d");
+
+    // The done date goes before a trailing block id so links to the line keep working.
+    let text = &caps[4];
+    let (body, block_id) = match BLOCK_ID_RE.find(text) {
+        Some(m) => text.split_at(m.start()),
+        None => (text, ""),
+    };
+    format!(
+        "{}x{}{} ✅ {}{}{}",
+        &caps[1],
+        &caps[3],
+        body.trim_end(),
+        done_date,
+        block_id,
+        caps.get(5).map_or("", |m| m.as_str())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::{
+        db::{
+            crud::{
+                query_items,
+                update_item,
+            },
+            item::{
+                ItemQuery,
+                RECURRING_TASK,
+            },
+        },
+        tests::get_test_conn,
+    };
+
+    #[test]
+    fn test_parse_task_line() {
+        assert_eq!(
+            parse_task_line("- [ ] file taxes #finance 📅 2025-04-15 ⏫\n"),
+            Some(MarkdownTask {
+                content: "file taxes".to_string(),
+                tag: Some("finance".to_string()),
+                due: Some("2025-04-15".to_string()),
+                recurrence: None,
+            })
+        );
+        assert_eq!(
+            parse_task_line("  * [/] #task water plants 🔁 every week on Sunday ⏳ 2025-05-04 ^abc123"),
+            Some(MarkdownTask {
+                content: "water plants".to_string(),
+                tag: None,
+                due: Some("2025-05-04".to_string()),
+                recurrence: Some("every week on Sunday".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_task_line("1. [ ] call mom").map(|t| t.content),
+            Some("call mom".to_string())
+        );
+
+        assert_eq!(parse_task_line("- [x] already done ✅ 2025-04-01"), None);
+        assert_eq!(parse_task_line("- [-] cancelled"), None);
+        assert_eq!(parse_task_line("- [ ] "), None);
+        assert_eq!(parse_task_line("- plain bullet"), None);
+        assert_eq!(parse_task_line("[ ] no bullet"), None);
+    }
+
+    #[test]
+    fn test_to_item() {
+        let task = parse_task_line("- [ ] water plants 🔁 every Sunday").unwrap();
+        let item = to_item(&task, "home".to_string()).unwrap();
+        assert_eq!(item.action, RECURRING_TASK);
+        assert_eq!(item.cron_schedule.as_deref(), Some("59 23 * * 0"));
+
+        // Unsupported recurrences still import as a one-off task
+        let task = parse_task_line("- [ ] review 🔁 every 2 weeks 📅 2025-05-01").unwrap();
+        let item = to_item(&task, "work".to_string()).unwrap();
+        assert_eq!(item.action, TASK);
+        assert_eq!(
+            item.target_time,
+            Some(timestr::to_unix_epoch("2025-05-01").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_tick_line() {
+        let done = Local
+            .with_ymd_and_hms(2025, 4, 14, 10, 0, 0)
+            .unwrap()
+            .timestamp();
+        assert_eq!(
+            tick_line("- [ ] file taxes 📅 2025-04-15\n", done),
+            "- [x] file taxes 📅 2025-04-15 ✅ 2025-04-14\n"
+        );
+        assert_eq!(
+            tick_line("  - [/] draft post ^post1\r\n", done),
+            "  - [x] draft post ✅ 2025-04-14 ^post1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_import_markdown() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut notes = NamedTempFile::new().unwrap();
+        write!(
+            notes,
+            "# Todo\n\n- [ ] file taxes #finance 📅 2025-04-15\n- [x] old task\n- [ ] buy milk\nsome text\n"
+        )
+        .unwrap();
+        let path = notes.path().to_str().unwrap();
+
+        import_markdown(&conn, path, None, false).unwrap();
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].category, "finance");
+        assert_eq!(tasks[0].content, "file taxes");
+        assert_eq!(tasks[1].category, "default");
+        assert_eq!(tasks[1].content, "buy milk");
+
+        // Importing again does not duplicate, write back ticks completed tasks only
+        let mut done = tasks[0].clone();
+        done.status = 1;
+        update_item(&conn, &done).unwrap();
+        import_markdown(&conn, path, None, true).unwrap();
+        assert_eq!(
+            query_items(&conn, &ItemQuery::new().with_action(TASK))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let content = fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert!(lines[2].starts_with("- [x] file taxes #finance 📅 2025-04-15 ✅ "));
+        assert_eq!(lines[4], "- [ ] buy milk");
+    }
+}
@@ -0,0 +1,110 @@
+mod backup;
+mod csv;
+mod google;
+mod json;
+mod markdown;
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::parser::ImportCommand,
+    db::{
+        crud::{
+            insert_item,
+            query_items,
+        },
+        item::{
+            Item,
+            ItemQuery,
+        },
+        lock,
+    },
+    error::TascliError,
+    events,
+};
+
+pub fn handle_importcmd(conn: &Connection, cmd: &ImportCommand) -> Result<(), TascliError> {
+    // A large import takes a while, the daemon waits for it rather than act on half of it
+    lock::with_lock(conn, "import", || import(conn, cmd))
+}
+
+fn import(conn: &Connection, cmd: &ImportCommand) -> Result<(), TascliError> {
+    if let Some(path) = &cmd.markdown {
+        return markdown::import_markdown(conn, path, cmd.category.as_deref(), cmd.write_back);
+    }
+    if let Some(path) = &cmd.csv {
+        return csv::import_csv(
+            conn,
+            path,
+            cmd.map.as_deref(),
+            cmd.category.as_deref(),
+            cmd.dry_run,
+        );
+    }
+    if let Some(path) = &cmd.outlook {
+        let map = cmd.map.as_deref().unwrap_or(csv::OUTLOOK_MAP);
+        return csv::import_csv(conn, path, Some(map), cmd.category.as_deref(), cmd.dry_run);
+    }
+    if let (Some(path), Some(mapping)) = (&cmd.json, &cmd.mapping) {
+        return json::import_json(conn, path, mapping, cmd.category.as_deref(), cmd.dry_run);
+    }
+    if let Some(path) = &cmd.restore {
+        return backup::restore_backup(conn, path, cmd.category.as_deref(), cmd.dry_run);
+    }
+    if let Some(path) = &cmd.google_takeout {
+        return google::import_google_takeout(conn, path, cmd.category.as_deref(), cmd.dry_run);
+    }
+    Err(TascliError::Invalid(
+        "Nothing to import, specify a source such as --markdown or --csv".to_string(),
+    ))
+}
+
+// Imports are meant to be re-run against the same source,
+// so an item with identical action, category and content counts as already imported.
+pub(crate) fn find_existing(conn: &Connection, item: &Item) -> Result<Option<Item>, TascliError> {
+    let query = ItemQuery::new()
+        .with_action(&item.action)
+        .with_category(&item.category)
+        .with_content_like(&item.content);
+    let items = query_items(conn, &query)?;
+    Ok(items.into_iter().find(|i| i.content == item.content))
+}
+
+// Saves the tasks not imported before, or only shows them for a dry run
+fn save_new(
+    conn: &Connection,
+    path: &str,
+    items: Vec<Item>,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let mut new_items = Vec::new();
+    for item in items {
+        if find_existing(conn, &item)?.is_none() {
+            new_items.push(item);
+        }
+    }
+    if new_items.is_empty() {
+        display::print_bold(&format!("No new tasks found in {}", path));
+        return Ok(());
+    }
+    if dry_run {
+        display::print_bold(&format!("Would import {} tasks from {}:", new_items.len(), path));
+        display::print_items(&new_items, false, false);
+        return Ok(());
+    }
+
+    let imported = new_items
+        .into_iter()
+        .map(|item| insert_imported(conn, item))
+        .collect::<Result<Vec<Item>, TascliError>>()?;
+    display::print_bold(&format!("Imported {} tasks from {}:", imported.len(), path));
+    display::print_items(&imported, false, false);
+    Ok(())
+}
+
+pub(crate) fn insert_imported(conn: &Connection, mut item: Item) -> Result<Item, TascliError> {
+    item.id = Some(insert_item(conn, &item)?);
+    events::emit(conn, events::CREATE, &mut item);
+    Ok(item)
+}
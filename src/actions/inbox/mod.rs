@@ -0,0 +1,298 @@
+#[cfg(feature = "nlp")]
+mod refine;
+
+use std::io::{
+    self,
+    BufRead,
+};
+
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::{
+            ListingPrinter,
+            CREATE_TIME_COL,
+        },
+        project::check_deadline,
+        triage::prompt,
+    },
+    args::{
+        parser::{
+            InCommand,
+            InboxCommand,
+        },
+        timestr,
+    },
+    config::current_user,
+    db::{
+        crud::{
+            delete_item,
+            insert_item,
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            DEFAULT_CATEGORY,
+            INBOX,
+            RECORD,
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+    error::TascliError,
+    events,
+    private,
+};
+
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    tasks: usize,
+    records: usize,
+    deleted: usize,
+    skipped: usize,
+}
+
+// Capturing is kept instant: no rules, scripts or webhooks run until the item is filed.
+pub fn handle_incmd(conn: &Connection, cmd: &InCommand) -> Result<(), TascliError> {
+    let content = cmd.content.join(" ");
+    let item = Item::new(INBOX.to_string(), DEFAULT_CATEGORY.to_string(), content);
+    insert_item(conn, &item)?;
+    let count = query_items(conn, &inbox_query())?.len();
+    display::print_green(&format!("Captured, {} in the inbox", count));
+    Ok(())
+}
+
+pub fn handle_inboxcmd(conn: &Connection, cmd: &InboxCommand) -> Result<(), TascliError> {
+    #[cfg(feature = "nlp")]
+    if let Some(crate::args::parser::InboxSubcommand::Refine(refine)) = &cmd.command {
+        return refine::handle_refinecmd(conn, refine);
+    }
+    let items = query_items(conn, &inbox_query())?;
+    if items.is_empty() {
+        display::print_bold("Inbox is empty");
+        return Ok(());
+    }
+
+    if cmd.list {
+        let mut printer = ListingPrinter::new("Inbox:".to_string(), true, false);
+        for item in &items {
+            printer.print(item);
+        }
+        printer.finish(conn, usize::MAX)?;
+        return Ok(());
+    }
+
+    display::print_bold(&format!("{} items in the inbox", items.len()));
+    let summary = process(conn, items, &mut io::stdin().lock())?;
+    display::print_bold(&format!(
+        "Filed: {} tasks, {} records, {} deleted, {} skipped",
+        summary.tasks, summary.records, summary.deleted, summary.skipped
+    ));
+    Ok(())
+}
+
+pub(crate) fn inbox_query<'a>() -> ItemQuery<'a> {
+    ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(INBOX)
+        .with_order_by(CREATE_TIME_COL)
+}
+
+// Stops early on quit or at the end of the input, the items filed so far stay filed.
+fn process(
+    conn: &Connection,
+    items: Vec<Item>,
+    input: &mut impl BufRead,
+) -> Result<Summary, TascliError> {
+    let mut summary = Summary::default();
+    let total = items.len();
+    for (i, item) in items.into_iter().enumerate() {
+        println!("{} ({})", private::reveal(&item.content), format_time(item.create_time));
+        let question = format!(
+            "[{}/{}] (t)ask, (r)ecord, (d)elete, (s)kip, (q)uit",
+            i + 1,
+            total
+        );
+        loop {
+            let Some(key) = prompt(input, &question)? else {
+                return Ok(summary);
+            };
+            let key = key.to_lowercase();
+            match key.as_str() {
+                "t" | "r" => {
+                    let question = format!("Category [{}]", DEFAULT_CATEGORY);
+                    let Some(category) = prompt(input, &question)? else {
+                        return Ok(summary);
+                    };
+                    let filed = if key == "t" {
+                        let Some(timestr) = prompt(input, "Deadline or schedule [today]")? else {
+                            return Ok(summary);
+                        };
+                        file_as_task(conn, item.clone(), &category, &timestr)
+                    } else {
+                        file_as_record(conn, item.clone(), &category)
+                    };
+                    if let Err(e) = filed {
+                        display::print_red(&e.to_string());
+                        continue;
+                    }
+                    if key == "t" {
+                        summary.tasks += 1;
+                    } else {
+                        summary.records += 1;
+                    }
+                    display::print_green("Filed");
+                }
+                "d" => {
+                    delete_item(conn, item.id.unwrap_or_default())?;
+                    display::print_green("Deleted");
+                    summary.deleted += 1;
+                }
+                "s" | "" => summary.skipped += 1,
+                "q" => return Ok(summary),
+                _ => {
+                    display::print_yellow("Press t, r, d, s or q");
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+    Ok(summary)
+}
+
+pub(crate) fn file_as_task(
+    conn: &Connection,
+    mut item: Item,
+    category: &str,
+    timestr: &str,
+) -> Result<(), TascliError> {
+    let category = category_or_default(category);
+    let timestr = if timestr.is_empty() { "today" } else { timestr };
+    match timestr::to_unix_epoch(timestr) {
+        Ok(target_time) => {
+            check_deadline(conn, &category, target_time, false)?;
+            item.action = TASK.to_string();
+            item.set_target_time(Some(target_time));
+        }
+        Err(_) => {
+            let cron_schedule = timestr::parse_recurring_timestr(timestr).map_err(|_| {
+                TascliError::Parse(format!(
+                    "Could not parse '{}' as a valid time or recurring schedule",
+                    timestr
+                ))
+            })?;
+            item.action = RECURRING_TASK.to_string();
+            item.cron_schedule = Some(cron_schedule);
+            item.human_schedule = Some(timestr.to_string());
+        }
+    }
+    item.category = category;
+    file(conn, item)
+}
+
+pub(crate) fn file_as_record(
+    conn: &Connection,
+    mut item: Item,
+    category: &str,
+) -> Result<(), TascliError> {
+    item.action = RECORD.to_string();
+    item.category = category_or_default(category);
+    file(conn, item)
+}
+
+fn file(conn: &Connection, mut item: Item) -> Result<(), TascliError> {
+    update_item(conn, &item)?;
+    events::emit(conn, events::CREATE, &mut item);
+    Ok(())
+}
+
+fn category_or_default(category: &str) -> String {
+    if category.is_empty() {
+        DEFAULT_CATEGORY.to_string()
+    } else {
+        category.to_string()
+    }
+}
+
+fn format_time(time: i64) -> String {
+    Local
+        .timestamp_opt(time, 0)
+        .single()
+        .map(|dt| dt.format("%Y/%m/%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::get_test_conn,
+    };
+
+    fn capture(conn: &Connection, thoughts: &[&str]) -> Vec<Item> {
+        for thought in thoughts {
+            let cmd = InCommand {
+                content: thought.split(' ').map(String::from).collect(),
+            };
+            handle_incmd(conn, &cmd).unwrap();
+        }
+        query_items(conn, &inbox_query()).unwrap()
+    }
+
+    #[test]
+    fn test_capture() {
+        let (conn, _temp_file) = get_test_conn();
+        let items = capture(&conn, &["call the bank"]);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].content, "call the bank");
+        assert_eq!(items[0].category, DEFAULT_CATEGORY);
+        assert_eq!(items[0].target_time, None);
+
+        // inbox items stay out of task listings until filed
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert!(tasks.is_empty());
+    }
+
+    #[test]
+    fn test_process() {
+        let (conn, _temp_file) = get_test_conn();
+        let items = capture(&conn, &["call bank", "slept badly", "old idea", "plan trip", "gym"]);
+        // task with a bad deadline asked again, record, delete, skip, recurring task
+        let mut input =
+            Cursor::new("t\nwork\nsoonish\nt\nwork\ntomorrow\nr\n\nd\n\nT\nlife\nDaily\n");
+
+        let summary = process(&conn, items, &mut input).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                tasks: 2,
+                records: 1,
+                deleted: 1,
+                skipped: 1,
+            }
+        );
+        let task = get_item(&conn, 1).unwrap();
+        assert_eq!(task.action, TASK);
+        assert_eq!(task.category, "work");
+        assert_eq!(task.target_time, Some(timestr::to_unix_epoch("tomorrow").unwrap()));
+        let record = get_item(&conn, 2).unwrap();
+        assert_eq!((record.action.as_str(), record.category.as_str()), (RECORD, DEFAULT_CATEGORY));
+        assert!(get_item(&conn, 3).is_err());
+        assert_eq!(get_item(&conn, 4).unwrap().action, INBOX);
+        let habit = get_item(&conn, 5).unwrap();
+        assert_eq!(habit.action, RECURRING_TASK);
+        assert_eq!(habit.human_schedule.as_deref(), Some("Daily"));
+    }
+}
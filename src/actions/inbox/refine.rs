@@ -0,0 +1,240 @@
+use std::io;
+
+use rusqlite::Connection;
+
+use super::{
+    file_as_record,
+    file_as_task,
+    inbox_query,
+};
+use crate::{
+    actions::{
+        display::{
+            self,
+            print_table,
+            DisplayRow,
+        },
+        triage::prompt,
+    },
+    args::parser::RefineCommand,
+    config,
+    db::{
+        crud::{
+            query_categories,
+            query_items,
+        },
+        item::{
+            Item,
+            DEFAULT_CATEGORY,
+        },
+    },
+    error::TascliError,
+    nlp::{
+        ActionType,
+        NLPCommand,
+        NLPParser,
+    },
+    private,
+};
+
+// How NLP would file an inbox item
+#[derive(Debug, PartialEq)]
+struct Proposal {
+    content: String,
+    category: String,
+    // Deadline or schedule of a task, None for a record
+    when: Option<String>,
+}
+
+pub fn handle_refinecmd(conn: &Connection, cmd: &RefineCommand) -> Result<(), TascliError> {
+    let items = query_items(conn, &inbox_query())?;
+    if items.is_empty() {
+        display::print_bold("Inbox is empty");
+        return Ok(());
+    }
+    let nlp_config = config::get_nlp_config()?;
+    if !nlp_config.enabled || !nlp_config.has_provider() {
+        return Err(TascliError::Config(
+            "NLP needs to be enabled with an api key, see 'tascli nlp config show'".to_string(),
+        ));
+    }
+
+    let parser = NLPParser::with_categories(nlp_config, query_categories(conn)?);
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
+    let mut proposals = Vec::new();
+    let mut unsure = 0;
+    for item in items {
+        // Encrypted content would only confuse the parser, and should not leave the machine
+        if private::is_private(&item.content) {
+            unsure += 1;
+            continue;
+        }
+        match rt.block_on(parser.parse(&item.content)).ok().and_then(|c| propose(&item, c)) {
+            Some(proposal) => proposals.push((item, proposal)),
+            None => unsure += 1,
+        }
+    }
+    if unsure > 0 {
+        display::print_yellow(&format!("{} items have no proposal and stay in the inbox", unsure));
+    }
+    if proposals.is_empty() {
+        return Ok(());
+    }
+
+    display::print_bold("Proposed:");
+    let rows: Vec<DisplayRow> = proposals
+        .iter()
+        .enumerate()
+        .map(|(i, (_, proposal))| preview_row(i + 1, proposal))
+        .collect();
+    print_table(&rows, false);
+    if !cmd.yes {
+        let question = format!("File these {} items? (y/N)", proposals.len());
+        let answer = prompt(&mut io::stdin().lock(), &question)?.unwrap_or_default();
+        if !answer.eq_ignore_ascii_case("y") {
+            display::print_bold("Nothing filed");
+            return Ok(());
+        }
+    }
+
+    let filed = file_proposals(conn, proposals);
+    display::print_green(&format!("Filed {} items", filed));
+    Ok(())
+}
+
+// Only tasks and records are proposals, anything else the parser saw stays in the inbox
+fn propose(item: &Item, command: NLPCommand) -> Option<Proposal> {
+    let when = match command.action {
+        ActionType::Task => Some(command.schedule.or(command.deadline).unwrap_or_default()),
+        ActionType::Record => None,
+        _ => return None,
+    };
+    let content = if command.content.trim().is_empty() {
+        item.content.clone()
+    } else {
+        command.content
+    };
+    Some(Proposal {
+        content,
+        category: command.category.unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+        when,
+    })
+}
+
+fn preview_row(index: usize, proposal: &Proposal) -> DisplayRow {
+    let timestr = match proposal.when.as_deref() {
+        Some("") => "today".to_string(),
+        Some(when) => when.to_string(),
+        None => "(record)".to_string(),
+    };
+    DisplayRow {
+        index: index.to_string(),
+        category: proposal.category.clone(),
+        category_color: None,
+        content: proposal.content.clone(),
+        timestr,
+        timestr_color: None,
+        tags: String::new(),
+        depth: 0,
+    }
+}
+
+// Items whose proposal cannot be filed, such as an unknown deadline, stay in the inbox
+fn file_proposals(conn: &Connection, proposals: Vec<(Item, Proposal)>) -> usize {
+    let mut filed = 0;
+    for (mut item, proposal) in proposals {
+        item.content = proposal.content;
+        let result = match &proposal.when {
+            Some(when) => file_as_task(conn, item, &proposal.category, when),
+            None => file_as_record(conn, item, &proposal.category),
+        };
+        match result {
+            Ok(()) => filed += 1,
+            Err(e) => display::print_red(&e.to_string()),
+        }
+    }
+    filed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::inbox::handle_incmd,
+        args::parser::InCommand,
+        db::{
+            crud::get_item,
+            item::{
+                INBOX,
+                RECORD,
+                TASK,
+            },
+        },
+        tests::get_test_conn,
+    };
+
+    fn capture(conn: &Connection, thought: &str) -> Item {
+        let cmd = InCommand {
+            content: vec![thought.to_string()],
+        };
+        handle_incmd(conn, &cmd).unwrap();
+        query_items(conn, &inbox_query()).unwrap().pop().unwrap()
+    }
+
+    #[test]
+    fn test_propose() {
+        let (conn, _temp_file) = get_test_conn();
+        let item = capture(&conn, "call the bank friday");
+        let command = NLPCommand {
+            content: "call the bank".to_string(),
+            category: Some("errands".to_string()),
+            deadline: Some("friday".to_string()),
+            ..NLPCommand::default()
+        };
+        assert_eq!(
+            propose(&item, command),
+            Some(Proposal {
+                content: "call the bank".to_string(),
+                category: "errands".to_string(),
+                when: Some("friday".to_string()),
+            })
+        );
+
+        let command = NLPCommand {
+            action: ActionType::Record,
+            ..NLPCommand::default()
+        };
+        let proposal = propose(&item, command).unwrap();
+        assert_eq!(proposal.content, "call the bank friday");
+        assert_eq!(proposal.category, DEFAULT_CATEGORY);
+        assert_eq!(proposal.when, None);
+
+        let command = NLPCommand {
+            action: ActionType::List,
+            ..NLPCommand::default()
+        };
+        assert_eq!(propose(&item, command), None);
+    }
+
+    #[test]
+    fn test_file_proposals() {
+        let (conn, _temp_file) = get_test_conn();
+        let proposal = |content: &str, when: Option<&str>| Proposal {
+            content: content.to_string(),
+            category: "errands".to_string(),
+            when: when.map(String::from),
+        };
+        let proposals = vec![
+            (capture(&conn, "bank friday"), proposal("call the bank", Some("friday"))),
+            (capture(&conn, "ran 5k"), proposal("ran 5k", None)),
+            (capture(&conn, "plan trip"), proposal("plan trip", Some("someday"))),
+        ];
+
+        assert_eq!(file_proposals(&conn, proposals), 2);
+        let task = get_item(&conn, 1).unwrap();
+        assert_eq!((task.action.as_str(), task.content.as_str()), (TASK, "call the bank"));
+        assert_eq!(get_item(&conn, 2).unwrap().action, RECORD);
+        assert_eq!(get_item(&conn, 3).unwrap().action, INBOX);
+    }
+}
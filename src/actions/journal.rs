@@ -0,0 +1,295 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs,
+    path::Path,
+    process::Command,
+    sync::LazyLock,
+};
+
+use chrono::{
+    Local,
+    NaiveDate,
+    NaiveTime,
+    TimeZone,
+};
+use regex::Regex;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::CREATE_TIME_COL,
+    },
+    args::parser::JournalCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::{
+            delete_item,
+            insert_item,
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+        },
+    },
+    error::TascliError,
+    events,
+    private,
+};
+
+// "- 09:12 work: wrote the report ^12", only the content is required
+static ENTRY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[-*]\s+(?:(\d{1,2}:\d{2})\s+)?(?:([^\s:]+):\s+)?(.*?)(?:\s+\^(\d+))?\s*$")
+        .unwrap()
+});
+
+const HINT: &str = "<!-- Each \"- \" line is a record of the day. Edit or remove them, \
+add \"- category: content\" lines, optionally starting with a time such as 14:30. \
+The ^number at the end links a line to its record. -->";
+
+// A record line of the note, with the id of the record it came from
+#[derive(Debug, PartialEq)]
+struct Entry {
+    id: Option<i64>,
+    time: Option<NaiveTime>,
+    category: Option<String>,
+    content: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    added: usize,
+    updated: usize,
+    removed: usize,
+}
+
+pub fn handle_journalcmd(conn: &Connection, cmd: &JournalCommand) -> Result<(), TascliError> {
+    let today = clock::now().date_naive();
+    let records = day_records(conn, today)?;
+    // Encrypted records cannot be edited as text, they are left out and left alone
+    let (private, records): (Vec<Item>, Vec<Item>) =
+        records.into_iter().partition(|r| private::is_private(&r.content));
+    if !private.is_empty() {
+        display::print_yellow(&format!("{} private records are not in the note", private.len()));
+    }
+
+    let note = render(&records, today);
+    let path = env::temp_dir().join(format!("tascli-journal-{}.md", today.format("%Y-%m-%d")));
+    fs::write(&path, &note)
+        .map_err(|e| TascliError::io(format!("Failed to write {}", path.display()), e))?;
+    let edited = open_editor(&path).and_then(|()| {
+        fs::read_to_string(&path)
+            .map_err(|e| TascliError::io(format!("Failed to read {}", path.display()), e))
+    });
+    let _ = fs::remove_file(&path);
+    let edited = edited?;
+    if edited == note {
+        display::print_bold("Journal unchanged");
+        return Ok(());
+    }
+
+    let summary = apply(conn, records, &edited, &cmd.category, today)?;
+    display::print_green(&format!(
+        "Added {}, updated {} and removed {} records",
+        summary.added, summary.updated, summary.removed
+    ));
+    Ok(())
+}
+
+fn day_records(conn: &Connection, date: NaiveDate) -> Result<Vec<Item>, TascliError> {
+    let start = local_timestamp(date, NaiveTime::MIN);
+    let query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(RECORD)
+        .with_create_time_range(Some(start), Some(start + 24 * 60 * 60))
+        .with_order_by(CREATE_TIME_COL);
+    Ok(query_items(conn, &query)?)
+}
+
+fn local_timestamp(date: NaiveDate, time: NaiveTime) -> i64 {
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_default()
+}
+
+fn render(records: &[Item], date: NaiveDate) -> String {
+    let mut note = format!("# Journal {}\n\n{}\n\n", date.format("%A %Y/%m/%d"), HINT);
+    for record in records {
+        let time = Local
+            .timestamp_opt(record.create_time, 0)
+            .single()
+            .map(|dt| dt.format("%H:%M").to_string())
+            .unwrap_or_default();
+        note.push_str(&format!(
+            "- {} {}: {} ^{}\n",
+            time,
+            record.category,
+            record.content,
+            record.id.unwrap_or_default()
+        ));
+    }
+    note
+}
+
+// Lines that are not list items, such as the heading, are not records
+fn parse_note(note: &str) -> Vec<Entry> {
+    note.lines()
+        .filter_map(|line| ENTRY_RE.captures(line.trim()))
+        .filter(|caps| !caps[3].is_empty())
+        .map(|caps| Entry {
+            id: caps.get(4).and_then(|id| id.as_str().parse().ok()),
+            time: caps.get(1).and_then(|t| NaiveTime::parse_from_str(t.as_str(), "%H:%M").ok()),
+            category: caps.get(2).map(|c| c.as_str().to_string()),
+            content: caps[3].to_string(),
+        })
+        .collect()
+}
+
+// Records of the day missing from the note are deleted, changed lines update their record
+// and lines without a record become new records.
+fn apply(
+    conn: &Connection,
+    records: Vec<Item>,
+    note: &str,
+    default_category: &str,
+    date: NaiveDate,
+) -> Result<Summary, TascliError> {
+    let mut summary = Summary::default();
+    let mut records: HashMap<i64, Item> =
+        records.into_iter().filter_map(|r| Some((r.id?, r))).collect();
+    for entry in parse_note(note) {
+        let category = entry.category.unwrap_or_else(|| default_category.to_string());
+        let existing = entry.id.and_then(|id| records.remove(&id));
+        match existing {
+            Some(mut record) => {
+                if record.category == category && record.content == entry.content {
+                    continue;
+                }
+                record.category = category;
+                record.content = entry.content;
+                update_item(conn, &record)?;
+                summary.updated += 1;
+            }
+            None => {
+                let create_time = match entry.time {
+                    Some(time) => local_timestamp(date, time),
+                    None => clock::timestamp(),
+                };
+                let mut record = Item::with_create_time(
+                    RECORD.to_string(),
+                    category,
+                    entry.content,
+                    create_time,
+                );
+                record.id = Some(insert_item(conn, &record)?);
+                events::emit(conn, events::CREATE, &mut record);
+                summary.added += 1;
+            }
+        }
+    }
+    for (id, mut record) in records {
+        delete_item(conn, id)?;
+        events::emit(conn, events::DELETE, &mut record);
+        summary.removed += 1;
+    }
+    Ok(summary)
+}
+
+// $VISUAL, then $EDITOR, may carry arguments such as "code --wait"
+fn open_editor(path: &Path) -> Result<(), TascliError> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| TascliError::io(format!("Failed to run editor {}", program), e))?;
+    if !status.success() {
+        return Err(TascliError::Invalid(format!(
+            "Editor {} exited with {}, journal not saved",
+            program, status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::get_test_conn,
+    };
+
+    fn record(conn: &Connection, category: &str, content: &str, time: &str) -> Item {
+        let date = Local::now().date_naive();
+        let time = NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+        let item = Item::with_create_time(
+            RECORD.to_string(),
+            category.to_string(),
+            content.to_string(),
+            local_timestamp(date, time),
+        );
+        get_item(conn, insert_item(conn, &item).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_note() {
+        let note = "# Journal\n\n- 09:12 work: wrote report ^3\n* gym: ran 5k\n- quiet day\n-\n";
+        let entries = parse_note(note);
+        assert_eq!(
+            entries[0],
+            Entry {
+                id: Some(3),
+                time: NaiveTime::from_hms_opt(9, 12, 0),
+                category: Some("work".to_string()),
+                content: "wrote report".to_string(),
+            }
+        );
+        assert_eq!((entries[1].category.as_deref(), entries[1].id), (Some("gym"), None));
+        assert_eq!(entries[2].category, None);
+        assert_eq!(entries[2].content, "quiet day");
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_apply() {
+        let (conn, _temp_file) = get_test_conn();
+        let today = Local::now().date_naive();
+        record(&conn, "work", "wrote report", "09:00");
+        record(&conn, "gym", "ran 5k", "07:00");
+        record(&conn, "home", "fixed sink", "08:00");
+        let records = day_records(&conn, today).unwrap();
+        assert_eq!(records.len(), 3);
+
+        let mut note: String = render(&records, today)
+            .replace("ran 5k", "ran 10k")
+            .lines()
+            .filter(|line| !line.contains("fixed sink"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        note.push_str("- 14:30 read a chapter\n");
+        let summary = apply(&conn, records, &note, "journal", today).unwrap();
+        assert_eq!(summary, Summary { added: 1, updated: 1, removed: 1 });
+
+        let records = day_records(&conn, today).unwrap();
+        let lines: Vec<(&str, &str)> =
+            records.iter().map(|r| (r.category.as_str(), r.content.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![("gym", "ran 10k"), ("work", "wrote report"), ("journal", "read a chapter")]
+        );
+        let half_past_two = NaiveTime::from_hms_opt(14, 30, 0).unwrap();
+        assert_eq!(records[2].create_time, local_timestamp(today, half_past_two));
+    }
+}
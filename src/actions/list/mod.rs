@@ -6,18 +6,39 @@ use rusqlite::Connection;
 pub use tasks::handle_listtasks;
 
 use crate::{
-    args::parser::ShowContentCommand,
+    actions::{
+        display::{
+            self,
+            tree_order,
+            DisplayRow,
+            TableWriter,
+        },
+        modify::resolve_or_pick,
+    },
+    args::parser::{
+        ItemRef,
+        ShowContentCommand,
+    },
+    config::{
+        self,
+        DisplayId,
+    },
     db::{
         cache,
         crud::get_item,
         item::{
+            Item,
+            ItemQuery,
             Offset,
             RECORD,
             RECURRING_TASK,
             RECURRING_TASK_RECORD,
+            SHORT_ID_LEN,
             TASK,
         },
     },
+    error::TascliError,
+    private,
 };
 
 // Shared constants
@@ -26,24 +47,44 @@ pub(crate) const TARGET_TIME_COL: &str = "target_time";
 pub(crate) const OPEN_STATUS_CODES: &[u8] = &[0, 4, 6];
 pub(crate) const CLOSED_STATUS_CODES: &[u8] = &[1, 2, 3, 5];
 
-// Shared function for showing content
-pub fn handle_showcontent(conn: &Connection, cmd: ShowContentCommand) -> Result<(), String> {
-    if !cache::validate_cache(conn).map_err(|e| e.to_string())? {
-        return Err("No valid cache found. Please run a list command first.".to_string());
+// Shared category filter, an empty category asks for items created without one
+pub(crate) fn filter_category<'a>(
+    query: ItemQuery<'a>,
+    category: Option<&'a str>,
+    no_category: bool,
+) -> ItemQuery<'a> {
+    match category {
+        _ if no_category => query.with_uncategorized(),
+        Some("") => query.with_uncategorized(),
+        Some(c) => query.with_category(c),
+        None => query,
     }
+}
 
-    let item_id = match cache::read(conn, cmd.index as i64).map_err(|e| e.to_string())? {
-        Some(id) => id,
-        None => {
-            return Err(format!(
-                "Index {} not found in cache. Use a valid index from the previous list command.",
-                cmd.index
-            ))
+// Shared function for showing content
+pub fn handle_showcontent(conn: &Connection, cmd: ShowContentCommand) -> Result<(), TascliError> {
+    let item_id = match &cmd.index {
+        Some(ItemRef::Index(index)) if config::display_id() == DisplayId::Index => {
+            if !cache::validate_cache(conn)? {
+                return Err(TascliError::Invalid(
+                    "No valid cache found. Please run a list command first.".to_string(),
+                ));
+            }
+            match cache::read(conn, *index as i64)? {
+                Some(id) => id,
+                None => {
+                    return Err(TascliError::Invalid(format!(
+                        "Index {} not found in cache. Use a valid index from the previous list command.",
+                        index
+                    )))
+                }
+            }
         }
+        item_ref => resolve_or_pick(conn, item_ref.as_ref())?,
     };
 
-    let item = get_item(conn, item_id).map_err(|e| e.to_string())?;
-    println!("{}", item.content);
+    let item = get_item(conn, item_id)?;
+    println!("{}", private::reveal(&item.content));
     Ok(())
 }
 
@@ -70,3 +111,115 @@ pub(crate) fn handle_next_page(conn: &Connection) -> Offset {
     }
     Offset::None
 }
+
+// Prints a listing row by row as items are read, keeping only their ids for the cache.
+pub(crate) struct ListingPrinter {
+    title: String,
+    is_record: bool,
+    show_ids: bool,
+    show_tags: bool,
+    // Items held back until the listing is finished, to show subtasks under their parents
+    tree: Option<Vec<Item>>,
+    table: Option<TableWriter>,
+    ids: Vec<i64>,
+}
+
+impl ListingPrinter {
+    pub(crate) fn new(title: String, is_record: bool, show_ids: bool) -> Self {
+        ListingPrinter {
+            title,
+            is_record,
+            show_ids,
+            show_tags: false,
+            tree: None,
+            table: None,
+            ids: Vec::new(),
+        }
+    }
+
+    // A tags column, for databases with tags. Rows are printed as they are read, so
+    // whether one of them has tags is not known up front.
+    pub(crate) fn with_tags(mut self, show_tags: bool) -> Self {
+        self.show_tags = show_tags;
+        self
+    }
+
+    // Subtasks under their parents, for databases with subtasks. Rows are printed when the
+    // listing is finished then, a parent may be read after its subtasks.
+    pub(crate) fn with_tree(mut self, tree: bool) -> Self {
+        self.tree = tree.then(Vec::new);
+        self
+    }
+
+    // Nothing is printed for an empty listing, not even the header
+    fn table(&mut self) -> &TableWriter {
+        self.table.get_or_insert_with(|| {
+            display::print_bold(&self.title);
+            let index_width = if self.show_ids {
+                // index, space and the short id
+                display::index_width() + SHORT_ID_LEN + 1
+            } else {
+                display::index_width()
+            };
+            TableWriter::new(self.is_record, index_width, self.show_tags)
+        })
+    }
+
+    // A line above the rows that follow, such as the day of the records under it
+    pub(crate) fn print_heading(&mut self, heading: &str) {
+        self.table();
+        display::print_bold(heading);
+    }
+
+    pub(crate) fn print(&mut self, item: &Item) {
+        match &mut self.tree {
+            Some(items) => items.push(item.clone()),
+            None => self.print_row(item, 0),
+        }
+    }
+
+    fn print_row(&mut self, item: &Item, depth: usize) {
+        let position = self.ids.len() + 1;
+        let mut index = display::display_index(Some(position), item).unwrap_or_default();
+        if self.show_ids {
+            index = format!("{} {}", index, item.short_id());
+        }
+        let is_record = self.is_record;
+        let table = self.table();
+        if display::is_porcelain() {
+            display::print_porcelain_item(&index, item);
+        } else if is_record {
+            table.write_row(&DisplayRow::from_record(index, item));
+        } else {
+            let mut row = DisplayRow::from_task(index, item);
+            row.depth = depth;
+            table.write_row(&row);
+        }
+        self.ids.push(item.id.unwrap());
+    }
+
+    // Caches the printed indices, with a next page marker when the page was full.
+    pub(crate) fn finish(mut self, conn: &Connection, limit: usize) -> Result<usize, TascliError> {
+        // The next page goes on from the last item read, wherever the tree shows it
+        let mut next_index = None;
+        if let Some(items) = self.tree.take() {
+            for (i, depth) in tree_order(&items) {
+                self.print_row(&items[i], depth);
+                if i + 1 == items.len() {
+                    next_index = Some(self.ids.len());
+                }
+            }
+        }
+        if self.ids.is_empty() {
+            return Ok(0);
+        }
+        cache::clear(conn)?;
+        if self.ids.len() == limit {
+            let next_index = next_index.unwrap_or(self.ids.len());
+            cache::store_ids_with_next(conn, &self.ids, next_index)
+        } else {
+            cache::store_ids(conn, &self.ids)
+        }?;
+        Ok(self.ids.len())
+    }
+}
@@ -1,7 +1,16 @@
+use chrono::{
+    Datelike,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
 use rusqlite::Connection;
 
 use super::{
+    filter_category,
     handle_next_page,
+    ListingPrinter,
     CREATE_TIME_COL,
 };
 use crate::{
@@ -10,9 +19,12 @@ use crate::{
         parser::ListRecordCommand,
         timestr,
     },
+    config::current_user,
     db::{
-        cache,
-        crud::query_items,
+        crud::{
+            for_each_item,
+            query_items,
+        },
         item::{
             Item,
             ItemQuery,
@@ -20,43 +32,87 @@ use crate::{
             RECORD,
             RECURRING_TASK_RECORD,
         },
+        tags::has_tags,
     },
+    error::TascliError,
+    tr,
 };
 
-pub fn handle_listrecords(conn: &Connection, cmd: ListRecordCommand) -> Result<(), String> {
-    let records = match query_records(conn, &cmd) {
-        Ok(records) => records,
+pub fn handle_listrecords(conn: &Connection, cmd: ListRecordCommand) -> Result<(), TascliError> {
+    let record_query = match build_record_query(conn, &cmd) {
+        Ok(query) => query,
         Err(estr) => {
-            display::print_bold(&estr);
+            display::print_bold(&estr.to_string());
             return Ok(());
         }
     };
-    if records.is_empty() {
-        display::print_bold("No records found");
-        return Ok(());
-    }
 
-    cache::clear(conn).map_err(|e| e.to_string())?;
-    if records.len() == cmd.limit {
-        cache::store_with_next(conn, &records)
+    let mut printer = ListingPrinter::new(tr!("records-list"), true, cmd.ids)
+        .with_tags(has_tags(conn)?);
+    if let Some(group_by) = cmd.group_by.as_deref() {
+        let records = query_items(conn, &record_query)?;
+        for group in records.chunk_by(|a, b| same_group(a, b, group_by)) {
+            let start = group_start(group[0].create_time, group_by);
+            printer.print_heading(&group_heading(start, group_by, group.len()));
+            group.iter().for_each(|record| printer.print(record));
+        }
     } else {
-        cache::store(conn, &records)
+        for_each_item(conn, &record_query, |record| {
+            printer.print(&record);
+            Ok(())
+        })?;
     }
-    .map_err(|e| e.to_string())?;
 
-    display::print_bold("Records List:");
-    display::print_items(&records, true, true);
+    if printer.finish(conn, cmd.limit)? == 0 {
+        display::print_bold(&tr!("no-records-found"));
+    }
     Ok(())
 }
 
-fn query_records(conn: &Connection, cmd: &ListRecordCommand) -> Result<Vec<Item>, String> {
-    let mut record_query = ItemQuery::new().with_actions(vec![RECORD, RECURRING_TASK_RECORD]);
-    if let Some(cat) = &cmd.category {
-        record_query = record_query.with_category(cat);
+fn same_group(a: &Item, b: &Item, group_by: &str) -> bool {
+    group_start(a.create_time, group_by) == group_start(b.create_time, group_by)
+}
+
+// The day a record was made on, or the Monday of its week
+fn group_start(create_time: i64, group_by: &str) -> NaiveDate {
+    let day = Local
+        .timestamp_opt(create_time, 0)
+        .single()
+        .map(|t| t.date_naive())
+        .unwrap_or_default();
+    match group_by {
+        "week" => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+        _ => day,
+    }
+}
+
+// The date of the group with its number of records. A group cut off by --limit only counts
+// the records of this page.
+fn group_heading(start: NaiveDate, group_by: &str, count: usize) -> String {
+    let date = start.format("%Y/%m/%d").to_string();
+    match group_by {
+        "week" => tr!("records-week", date = date, count = count),
+        _ => tr!("records-day", date = date, count = count),
     }
+}
+
+fn build_record_query<'a>(
+    conn: &Connection,
+    cmd: &'a ListRecordCommand,
+) -> Result<ItemQuery<'a>, TascliError> {
+    let mut record_query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD]);
+    record_query = filter_category(record_query, cmd.category.as_deref(), cmd.no_category);
     if let Some(search_term) = &cmd.search {
         record_query = record_query.with_content_like(search_term);
     }
+    if let Some(tag) = &cmd.tag {
+        record_query = record_query.with_tag(tag.trim_start_matches('+'));
+    }
+    if cmd.value_min.is_some() || cmd.value_max.is_some() {
+        record_query = record_query.with_value_range(cmd.value_min, cmd.value_max);
+    }
     if let Some(days) = cmd.days {
         let cutoff_timestamp = timestr::days_before_to_unix_epoch(days);
         record_query = record_query.with_create_time_min(cutoff_timestamp);
@@ -75,36 +131,58 @@ fn query_records(conn: &Connection, cmd: &ListRecordCommand) -> Result<Vec<Item>
         offset = handle_next_page(conn);
         match offset {
             Offset::CreateTime(_) => {}
-            Offset::None => return Err("No next page available".to_string()),
-            _ => return Err("next page not meant for this call".to_string()),
+            Offset::None => return Err(TascliError::Invalid(tr!("no-next-page"))),
+            _ => return Err(TascliError::Invalid("next page not meant for this call".to_string())),
         }
     }
+    if let Some(page) = cmd.page {
+        record_query = record_query.with_skip((page - 1) * cmd.limit);
+    }
     record_query = record_query.with_offset(offset);
     record_query = record_query.with_limit(cmd.limit);
     record_query = record_query.with_order_by(CREATE_TIME_COL);
-    query_items(conn, &record_query).map_err(|e| e.to_string())
+    Ok(record_query)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{
-        get_test_conn,
-        insert_record,
-        insert_recurring_record,
-        insert_recurring_task,
+    use crate::{
+        db::{
+            cache,
+            crud::query_items,
+            item::Item,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_recurring_record,
+            insert_recurring_task,
+        },
     };
 
+    fn query_records(conn: &Connection, cmd: &ListRecordCommand) -> Result<Vec<Item>, TascliError> {
+        let record_query = build_record_query(conn, cmd)?;
+        Ok(query_items(conn, &record_query)?)
+    }
+
     impl ListRecordCommand {
         fn default_test() -> Self {
             ListRecordCommand {
                 category: None,
+                no_category: false,
                 days: None,
                 limit: 100,
                 starting_time: None,
                 ending_time: None,
                 next_page: false,
+                page: None,
                 search: None,
+                tag: None,
+                value_min: None,
+                value_max: None,
+                ids: false,
+                group_by: None,
             }
         }
 
@@ -138,6 +216,11 @@ mod tests {
             self
         }
 
+        fn with_page(mut self, page: usize) -> Self {
+            self.page = Some(page);
+            self
+        }
+
         fn with_next_page(mut self) -> Self {
             self.next_page = true;
             self
@@ -264,4 +347,68 @@ mod tests {
         cache::store(&conn, &results).unwrap();
         assert_eq!(results.len(), 0);
     }
+
+    #[test]
+    fn test_query_records_page() {
+        let (conn, _temp_file) = get_test_conn();
+        for i in 1..=5 {
+            insert_record(&conn, "test", &format!("R{}", i), &format!("2025/02/2{}", i));
+        }
+
+        let list_record = ListRecordCommand::default_test()
+            .with_starting_time("2025/02/01")
+            .with_limit(2);
+        let contents = |cmd: &ListRecordCommand| -> Vec<String> {
+            query_records(&conn, cmd)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.content)
+                .collect()
+        };
+        assert_eq!(contents(&list_record), vec!["R1", "R2"]);
+        let list_record = list_record.with_page(2);
+        assert_eq!(contents(&list_record), vec!["R3", "R4"]);
+        let list_record = list_record.with_page(3);
+        assert_eq!(contents(&list_record), vec!["R5"]);
+        let list_record = list_record.with_page(4);
+        assert!(contents(&list_record).is_empty());
+    }
+
+    #[test]
+    fn test_handle_listrecords_caches_page() {
+        let (conn, _temp_file) = get_test_conn();
+        let ids: Vec<i64> = (1..=3)
+            .map(|i| insert_record(&conn, "test", &format!("R{}", i), &format!("2025/02/2{}", i)))
+            .collect();
+
+        let cmd = ListRecordCommand::default_test()
+            .with_starting_time("2025/02/01")
+            .with_limit(2)
+            .with_page(2);
+        handle_listrecords(&conn, cmd).unwrap();
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(ids[2]));
+        assert_eq!(cache::read(&conn, 2).unwrap(), None);
+        assert_eq!(cache::get_next_index(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn test_group_records() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "journal", "ran 5k", "2025/03/10 7AM");
+        insert_record(&conn, "journal", "read", "2025/03/10 9PM");
+        insert_record(&conn, "journal", "swam", "2025/03/12 6PM");
+        let records = query_records(&conn, &ListRecordCommand::default_test()).unwrap();
+
+        let days: Vec<usize> =
+            records.chunk_by(|a, b| same_group(a, b, "day")).map(|g| g.len()).collect();
+        assert_eq!(days, vec![2, 1]);
+        let weeks = records.chunk_by(|a, b| same_group(a, b, "week")).count();
+        assert_eq!(weeks, 1);
+
+        // Weeks start on Monday
+        let start = group_start(records[2].create_time, "week");
+        assert_eq!(group_heading(start, "week", 3), "Week of 2025/03/10: 3 records");
+        let start = group_start(records[2].create_time, "day");
+        assert_eq!(group_heading(start, "day", 1), "2025/03/12: 1 record");
+    }
 }
@@ -1,8 +1,14 @@
-use chrono::Local;
+use chrono::{
+    Local,
+    NaiveDate,
+    TimeZone,
+};
 use rusqlite::Connection;
 
 use super::{
+    filter_category,
     handle_next_page,
+    ListingPrinter,
     CLOSED_STATUS_CODES,
     OPEN_STATUS_CODES,
     TARGET_TIME_COL,
@@ -14,9 +20,18 @@ use crate::{
         parser::ListTaskCommand,
         timestr,
     },
+    clock,
+    config::{
+        self,
+        current_user,
+        holidays,
+    },
     db::{
-        cache,
-        crud::query_items,
+        crud::{
+            for_each_item,
+            has_subtasks,
+            query_items,
+        },
         item::{
             Item,
             ItemQuery,
@@ -25,18 +40,28 @@ use crate::{
             RECURRING_TASK_RECORD,
             TASK,
         },
+        projects::get_projects,
+        tags::has_tags,
     },
+    error::TascliError,
+    tr,
 };
 
-pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), String> {
+pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), TascliError> {
+    if let Some(days) = cmd.at_risk {
+        return list_at_risk(conn, &cmd, days);
+    }
+    if cmd.due_on_holiday {
+        return list_due_on_holiday(conn, &cmd);
+    }
     let recurring_tasks = match query_recurring_tasks(conn, &cmd) {
         Ok(tasks) => tasks,
         Err(estr) => {
-            display::print_bold(&estr);
+            display::print_bold(&estr.to_string());
             return Ok(());
         }
     };
-    let recurring_hit_limit = recurring_tasks.len() == cmd.limit;
+    let recurring_hit_limit = cmd.page.is_none() && recurring_tasks.len() == cmd.limit;
 
     // Mark completion status for all recurring tasks
     let recurring_tasks = mark_recurring_task_by_completion(conn, recurring_tasks)?;
@@ -57,63 +82,182 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
     };
     let recurring_tasks = filter_recurring_task_by_time(recurring_tasks, &cmd)?;
 
-    let all_tasks = if recurring_hit_limit {
-        // If recurring tasks hit the limit, don't query regular tasks yet
-        // There might be more recurring tasks on the next page
-        recurring_tasks
+    // A numbered page is counted over recurring tasks first, then regular tasks,
+    // recurring tasks are few enough to be filtered in full.
+    let skip = cmd.page.map_or(0, |page| (page - 1) * cmd.limit);
+    let regular_skip = skip.saturating_sub(recurring_tasks.len());
+
+    let recurring_page: Vec<&Item> = recurring_tasks.iter().skip(skip).take(cmd.limit).collect();
+
+    // If recurring tasks hit the limit, don't query regular tasks yet
+    // There might be more recurring tasks on the next page
+    let remaining = cmd.limit - recurring_page.len();
+    let task_query = if recurring_hit_limit || remaining == 0 {
+        None
     } else {
-        // Recurring tasks didn't hit limit, safe to query and combine with regular tasks
-        let regular_tasks = match query_tasks(conn, &cmd) {
-            Ok(tasks) => tasks,
+        match build_task_query(conn, &cmd) {
+            Ok(query) => query.map(|q| q.with_limit(remaining).with_skip(regular_skip)),
             Err(estr) => {
-                display::print_bold(&estr);
+                display::print_bold(&estr.to_string());
                 return Ok(());
             }
-        };
+        }
+    };
 
-        // Combine both lists
-        let mut all_tasks = Vec::new();
-        all_tasks.extend(recurring_tasks);
-        all_tasks.extend(regular_tasks);
-        all_tasks.truncate(cmd.limit);
+    let mut printer = ListingPrinter::new(tr!("tasks-list"), false, cmd.ids)
+        .with_tags(has_tags(conn)?)
+        .with_tree(has_subtasks(conn)?);
+    for task in recurring_page {
+        printer.print(task);
+    }
+    if let Some(query) = task_query {
+        for_each_item(conn, &query, |task| {
+            printer.print(&task);
+            Ok(())
+        })?;
+    }
 
-        all_tasks
-    };
+    if printer.finish(conn, cmd.limit)? == 0 {
+        display::print_bold(&tr!("no-tasks-found"));
+    }
+    Ok(())
+}
 
-    if all_tasks.is_empty() {
-        display::print_bold("No tasks found");
-        return Ok(());
+fn list_at_risk(conn: &Connection, cmd: &ListTaskCommand, days: usize) -> Result<(), TascliError> {
+    let tasks = query_at_risk_tasks(conn, cmd, days)?;
+    let mut printer = ListingPrinter::new(tr!("tasks-list"), false, cmd.ids)
+        .with_tags(has_tags(conn)?)
+        .with_tree(has_subtasks(conn)?);
+    for task in &tasks {
+        printer.print(task);
+    }
+    if printer.finish(conn, cmd.limit)? == 0 {
+        display::print_bold(&tr!("no-tasks-found"));
     }
+    Ok(())
+}
 
-    cache::clear(conn).map_err(|e| e.to_string())?;
-    if all_tasks.len() == cmd.limit {
-        cache::store_with_next(conn, &all_tasks)
-    } else {
-        cache::store(conn, &all_tasks)
+// Open tasks of projects due within `days` of the project's end, overdue or not,
+// soonest deadline first.
+fn query_at_risk_tasks(
+    conn: &Connection,
+    cmd: &ListTaskCommand,
+    days: usize,
+) -> Result<Vec<Item>, TascliError> {
+    let mut tasks = Vec::new();
+    for project in get_projects(conn)? {
+        if cmd.category.as_ref().is_some_and(|c| *c != project.category) {
+            continue;
+        }
+        let mut query = ItemQuery::new()
+            .with_visible_to(current_user())
+            .with_action(TASK)
+            .with_category(&project.category)
+            .with_statuses(OPEN_STATUS_CODES.to_vec())
+            .with_target_time_min(project.end_time - days as i64 * 86400);
+        if let Some(search_term) = &cmd.search {
+            query = query.with_content_like(search_term);
+        }
+        if let Some(priority) = cmd.priority {
+            query = query.with_priority(priority);
+        }
+        if let Some(tag) = &cmd.tag {
+            query = query.with_tag(tag.trim_start_matches('+'));
+        }
+        tasks.extend(query_items(conn, &query)?);
     }
-    .map_err(|e| e.to_string())?;
+    tasks.sort_by_key(|t| t.target_time);
+    tasks.truncate(cmd.limit);
+    Ok(tasks)
+}
 
-    display::print_bold("Tasks List:");
-    display::print_items(&all_tasks, false, true);
+fn list_due_on_holiday(conn: &Connection, cmd: &ListTaskCommand) -> Result<(), TascliError> {
+    // Read here rather than through holidays::holidays() so a broken calendar is reported
+    let holidays = holidays::load_holidays()?;
+    if holidays.is_empty() {
+        display::print_bold("No holidays are set in config");
+        return Ok(());
+    }
+    let tasks = query_due_on_holiday(conn, cmd, &holidays)?;
+    if !tasks.is_empty() {
+        display::print_yellow(&format!("{} tasks are due on a holiday", tasks.len()));
+    }
+    let mut printer = ListingPrinter::new(tr!("tasks-list"), false, cmd.ids)
+        .with_tags(has_tags(conn)?)
+        .with_tree(has_subtasks(conn)?);
+    for task in &tasks {
+        printer.print(task);
+    }
+    if printer.finish(conn, cmd.limit)? == 0 {
+        display::print_bold(&tr!("no-tasks-found"));
+    }
     Ok(())
 }
 
+// Open tasks with a deadline on one of the sorted `holidays`, soonest first. Overdue ones
+// only with --overdue.
+fn query_due_on_holiday(
+    conn: &Connection,
+    cmd: &ListTaskCommand,
+    holidays: &[NaiveDate],
+) -> Result<Vec<Item>, TascliError> {
+    let mut query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_order_by(TARGET_TIME_COL);
+    if !cmd.overdue {
+        query = query.with_target_time_min(clock::timestamp());
+    }
+    query = filter_category(query, cmd.category.as_deref(), cmd.no_category);
+    query = filter_place(query, cmd.at.as_deref())?;
+    if let Some(search_term) = &cmd.search {
+        query = query.with_content_like(search_term);
+    }
+    if let Some(priority) = cmd.priority {
+        query = query.with_priority(priority);
+    }
+    if let Some(tag) = &cmd.tag {
+        query = query.with_tag(tag.trim_start_matches('+'));
+    }
+    let mut tasks = query_items(conn, &query)?;
+    tasks.retain(|t| {
+        t.target_time
+            .and_then(|t| Local.timestamp_opt(t, 0).single())
+            .is_some_and(|dt| holidays.binary_search(&dt.date_naive()).is_ok())
+    });
+    tasks.truncate(cmd.limit);
+    Ok(tasks)
+}
+
 // Some cmd query argument do not apply - moved to application layer.
 // Skip query for status because recurring tasks do not have status.
-fn query_recurring_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, String> {
-    let mut query = ItemQuery::new().with_action(RECURRING_TASK);
-    if let Some(cat) = &cmd.category {
-        query = query.with_category(cat);
+fn query_recurring_tasks(
+    conn: &Connection,
+    cmd: &ListTaskCommand,
+) -> Result<Vec<Item>, TascliError> {
+    // Recurring tasks come back on their own, they never go stale
+    if cmd.stale.is_some() {
+        return Ok(Vec::new());
     }
+    let mut query = ItemQuery::new().with_visible_to(current_user()).with_action(RECURRING_TASK);
+    query = filter_category(query, cmd.category.as_deref(), cmd.no_category);
+    query = filter_place(query, cmd.at.as_deref())?;
     if let Some(search_term) = &cmd.search {
         query = query.with_content_like(search_term);
     }
+    if let Some(priority) = cmd.priority {
+        query = query.with_priority(priority);
+    }
+    if let Some(tag) = &cmd.tag {
+        query = query.with_tag(tag.trim_start_matches('+'));
+    }
     let mut offset = Offset::None;
     if cmd.next_page {
         offset = handle_next_page(conn);
         match offset {
             Offset::Id(_) => {}
-            Offset::None => return Err("No next page available".to_string()),
+            Offset::None => return Err(TascliError::Invalid(tr!("no-next-page"))),
             _ => return Ok(Vec::new()), // Wrong offset type, skip recurring tasks query
         }
     }
@@ -125,14 +269,16 @@ fn query_recurring_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec
         _ => query = query.with_statuses(vec![cmd.status]),
     }
     query = query.with_offset(offset);
-    query = query.with_limit(cmd.limit);
-    query_items(conn, &query).map_err(|e| e.to_string())
+    if cmd.page.is_none() {
+        query = query.with_limit(cmd.limit);
+    }
+    Ok(query_items(conn, &query)?)
 }
 
 fn filter_recurring_task_by_time(
     recurring_tasks: Vec<Item>,
     cmd: &ListTaskCommand,
-) -> Result<Vec<Item>, String> {
+) -> Result<Vec<Item>, TascliError> {
     let mut filtered_tasks: Vec<Item> = Vec::new();
     let mut target_interval_end: Option<i64> = Option::None;
     if let Some(t) = &cmd.timestr {
@@ -158,7 +304,7 @@ fn filter_recurring_task_by_time(
 fn mark_recurring_task_by_completion(
     conn: &Connection,
     mut recurring_tasks: Vec<Item>,
-) -> Result<Vec<Item>, String> {
+) -> Result<Vec<Item>, TascliError> {
     for recurring_task in &mut recurring_tasks {
         let cron_schedule = recurring_task.cron_schedule.as_ref().unwrap();
         let last_occurrence = cron::get_last_occurrence(cron_schedule)?;
@@ -169,14 +315,28 @@ fn mark_recurring_task_by_completion(
             .with_action(RECURRING_TASK_RECORD)
             .with_recurring_task_id(recurring_task_id)
             .with_good_until_min(last_occurrence);
-        let records = query_items(conn, &record_query).map_err(|e| e.to_string())?;
+        let records = query_items(conn, &record_query)?;
         recurring_task.recurring_interval_complete = !records.is_empty();
     }
     Ok(recurring_tasks)
 }
 
-fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, String> {
-    let mut task_query = ItemQuery::new().with_action(TASK);
+fn filter_place<'a>(
+    query: ItemQuery<'a>,
+    place: Option<&str>,
+) -> Result<ItemQuery<'a>, TascliError> {
+    match place {
+        Some(place) => Ok(query.with_categories(config::place_categories(place)?)),
+        None => Ok(query),
+    }
+}
+
+// None when the next page continues past the regular tasks.
+fn build_task_query<'a>(
+    conn: &Connection,
+    cmd: &'a ListTaskCommand,
+) -> Result<Option<ItemQuery<'a>>, String> {
+    let mut task_query = ItemQuery::new().with_visible_to(current_user()).with_action(TASK);
     if let Some(t) = &cmd.timestr {
         let target_time_before = timestr::to_unix_epoch(t)?;
         task_query = task_query.with_target_time_max(target_time_before);
@@ -184,15 +344,22 @@ fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, St
         let cutoff_timestamp = timestr::days_after_to_unix_epoch(days);
         task_query = task_query.with_target_time_max(cutoff_timestamp);
     }
-    if !cmd.overdue {
-        task_query = task_query.with_target_time_min(Local::now().timestamp());
-    }
-    if let Some(cat) = &cmd.category {
-        task_query = task_query.with_category(cat);
+    if let Some(age) = cmd.stale {
+        task_query = task_query.with_create_time_max(clock::timestamp() - age);
+    } else if !cmd.overdue {
+        task_query = task_query.with_target_time_min(clock::timestamp());
     }
+    task_query = filter_category(task_query, cmd.category.as_deref(), cmd.no_category);
+    task_query = filter_place(task_query, cmd.at.as_deref()).map_err(|e| e.to_string())?;
     if let Some(search_term) = &cmd.search {
         task_query = task_query.with_content_like(search_term);
     }
+    if let Some(priority) = cmd.priority {
+        task_query = task_query.with_priority(priority);
+    }
+    if let Some(tag) = &cmd.tag {
+        task_query = task_query.with_tag(tag.trim_start_matches('+'));
+    }
 
     match cmd.status {
         // 255 status means we query all task items regardless of status.
@@ -211,37 +378,56 @@ fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, St
         match offset {
             Offset::TargetTime(_) => {}
             Offset::None => return Err("No next page available".to_string()),
-            _ => return Ok(Vec::new()), // Wrong offset type, skip regular tasks query
+            _ => return Ok(None), // Wrong offset type, skip regular tasks query
         }
     }
     task_query = task_query.with_offset(offset);
     task_query = task_query.with_limit(cmd.limit);
     task_query = task_query.with_order_by(TARGET_TIME_COL);
-    query_items(conn, &task_query).map_err(|e| e.to_string())
+    Ok(Some(task_query))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{
-        get_test_conn,
-        insert_recurring_record,
-        insert_recurring_task,
-        insert_task,
-        update_status,
+    use crate::{
+        db::cache,
+        tests::{
+            get_test_conn,
+            insert_recurring_record,
+            insert_recurring_task,
+            insert_task,
+            update_status,
+        },
     };
 
+    fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, TascliError> {
+        match build_task_query(conn, cmd)? {
+            Some(task_query) => Ok(query_items(conn, &task_query)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
     impl ListTaskCommand {
         fn default_test() -> Self {
             ListTaskCommand {
                 timestr: None,
                 category: None,
+                no_category: false,
                 days: None,
                 status: 0,
                 overdue: false,
                 limit: 100,
                 next_page: false,
+                page: None,
                 search: None,
+                priority: None,
+                tag: None,
+                ids: false,
+                at_risk: None,
+                stale: None,
+                at: None,
+                due_on_holiday: false,
             }
         }
 
@@ -270,6 +456,11 @@ mod tests {
             self
         }
 
+        fn with_page(mut self, page: usize) -> Self {
+            self.page = Some(page);
+            self
+        }
+
         fn with_search(mut self, search: &str) -> Self {
             self.search = Some(search.to_string());
             self
@@ -340,7 +531,7 @@ mod tests {
         assert_eq!(results.last().unwrap().content, "index 11PM");
 
         let results = query_tasks(&conn, &list_task_next);
-        assert_eq!(results.unwrap_err(), "No next page available".to_string());
+        assert_eq!(results.unwrap_err().to_string(), "No next page available");
     }
 
     #[test]
@@ -619,6 +810,49 @@ mod tests {
         assert!(cache::validate_cache(&conn).unwrap());
     }
 
+    #[test]
+    fn test_handle_listtasks_page() {
+        let (conn, _temp_file) = get_test_conn();
+        let recurring: Vec<i64> = (1..=3)
+            .map(|i| insert_recurring_task(&conn, "work", &format!("Recurring {}", i), "Daily 9AM"))
+            .collect();
+        let regular: Vec<i64> = (1..=5)
+            .map(|i| {
+                insert_task(
+                    &conn,
+                    "work",
+                    &format!("Regular {}", i),
+                    &format!("tomorrow {}PM", i),
+                )
+            })
+            .collect();
+        let cached = |conn: &Connection| -> Vec<i64> {
+            (1..=3)
+                .filter_map(|i| cache::read(conn, i).unwrap())
+                .collect()
+        };
+
+        let page = |page: usize| {
+            ListTaskCommand {
+                status: 255,
+                ..ListTaskCommand::default_test()
+            }
+            .with_limit(3)
+            .with_page(page)
+        };
+
+        handle_listtasks(&conn, page(1)).unwrap();
+        assert_eq!(cached(&conn), recurring);
+
+        handle_listtasks(&conn, page(2)).unwrap();
+        assert_eq!(cached(&conn), regular[..3]);
+        assert_eq!(cache::get_next_index(&conn).unwrap(), Some(3));
+
+        handle_listtasks(&conn, page(3)).unwrap();
+        assert_eq!(cached(&conn), regular[3..]);
+        assert_eq!(cache::get_next_index(&conn).unwrap(), None);
+    }
+
     #[test]
     fn test_search_functionality() {
         let (conn, _temp_file) = get_test_conn();
@@ -652,4 +886,111 @@ mod tests {
             assert_eq!(task.category, "work");
         }
     }
+
+    #[test]
+    fn test_at_risk_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let in_days = |days: i64| {
+            (Local::now() + chrono::Duration::days(days))
+                .format("%Y/%m/%d")
+                .to_string()
+        };
+        let early = insert_task(&conn, "release", "write docs", "today");
+        let late = insert_task(&conn, "release", "cut the release", &in_days(3));
+        let after = insert_task(&conn, "release", "announce", &in_days(7));
+        let done = insert_task(&conn, "release", "tag", &in_days(3));
+        update_status(&conn, done, 1);
+        insert_task(&conn, "chores", "laundry", &in_days(7));
+        let end = timestr::to_unix_epoch(&in_days(4)).unwrap();
+        crate::db::projects::set_project_end(&conn, "release", end).unwrap();
+
+        let ids = |days: usize| -> Vec<i64> {
+            query_at_risk_tasks(&conn, &ListTaskCommand::default_test(), days)
+                .unwrap()
+                .iter()
+                .filter_map(|t| t.id)
+                .collect()
+        };
+        assert_eq!(ids(2), vec![late, after]);
+        assert_eq!(ids(0), vec![after]);
+        assert_eq!(ids(10), vec![early, late, after]);
+    }
+
+    #[test]
+    fn test_no_category() {
+        let (conn, _temp_file) = get_test_conn();
+        let default = insert_task(&conn, "default", "sort me", "today");
+        let empty = insert_task(&conn, "", "me too", "today");
+        insert_task(&conn, "work", "already sorted", "today");
+        insert_recurring_task(&conn, "default", "water plants", "daily");
+
+        let ids = |cmd: &ListTaskCommand| -> Vec<i64> {
+            query_tasks(&conn, cmd)
+                .unwrap()
+                .iter()
+                .filter_map(|t| t.id)
+                .collect()
+        };
+        let mut no_category = ListTaskCommand::default_test().with_overdue(true);
+        no_category.no_category = true;
+        assert_eq!(ids(&no_category), vec![default, empty]);
+        let empty_category = ListTaskCommand::default_test()
+            .with_overdue(true)
+            .with_category("");
+        assert_eq!(ids(&empty_category), vec![default, empty]);
+        assert_eq!(query_recurring_tasks(&conn, &no_category).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_stale() {
+        let (conn, _temp_file) = get_test_conn();
+        let old = insert_task(&conn, "work", "rotting", "yesterday");
+        let closed = insert_task(&conn, "work", "finished long ago", "tomorrow");
+        update_status(&conn, closed, 1);
+        insert_task(&conn, "work", "fresh", "tomorrow");
+        insert_recurring_task(&conn, "work", "standup", "daily");
+        let created = Local::now().timestamp() - 40 * 86400;
+        let sql = "UPDATE items SET create_time = ?1 WHERE id IN (?2, ?3)";
+        conn.execute(sql, (created, old, closed)).unwrap();
+
+        let mut cmd = ListTaskCommand::default_test().with_status(254);
+        cmd.stale = Some(30 * 86400);
+        let ids: Vec<i64> = query_tasks(&conn, &cmd).unwrap().iter().filter_map(|t| t.id).collect();
+        assert_eq!(ids, vec![old]);
+        assert!(query_recurring_tasks(&conn, &cmd).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_due_on_holiday() {
+        let (conn, _temp_file) = get_test_conn();
+        let christmas = insert_task(&conn, "work", "ship release", "2099/12/25 10:00");
+        insert_task(&conn, "work", "plan next year", "2099/12/27");
+        let done = insert_task(&conn, "work", "send cards", "2099/12/25");
+        update_status(&conn, done, 1);
+        insert_task(&conn, "home", "bake", "2099/12/26");
+
+        let holidays = [
+            NaiveDate::from_ymd_opt(2099, 12, 25).unwrap(),
+            NaiveDate::from_ymd_opt(2099, 12, 26).unwrap(),
+        ];
+        let cmd = ListTaskCommand::default_test().with_category("work");
+        let tasks = query_due_on_holiday(&conn, &cmd, &holidays).unwrap();
+        assert_eq!(tasks.iter().filter_map(|t| t.id).collect::<Vec<_>>(), vec![christmas]);
+        let tasks = query_due_on_holiday(&conn, &ListTaskCommand::default_test(), &holidays);
+        assert_eq!(tasks.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_timed_before_all_day() {
+        let (conn, _temp_file) = get_test_conn();
+        let all_day = insert_task(&conn, "work", "report", "tomorrow");
+        let evening = insert_task(&conn, "work", "dinner", "tomorrow 7pm");
+        let morning = insert_task(&conn, "work", "standup", "tomorrow 9am");
+
+        let tasks = query_tasks(&conn, &ListTaskCommand::default_test()).unwrap();
+        let ids: Vec<i64> = tasks.iter().filter_map(|t| t.id).collect();
+        assert_eq!(ids, vec![morning, evening, all_day]);
+        let all_days: Vec<bool> = tasks.iter().map(|t| t.all_day).collect();
+        assert_eq!(all_days, vec![false, false, true]);
+    }
 }
@@ -1,6 +1,44 @@
 pub mod addition;
+#[cfg(feature = "nlp")]
+pub mod assistant;
+pub mod convert;
+pub mod digest;
+pub mod docs;
 pub mod display;
+pub mod export;
+pub mod focus;
+pub mod forecast;
+pub mod git;
+pub mod habit;
 pub mod handler;
+#[cfg(feature = "nlp")]
+pub mod help;
+pub mod import;
+pub mod inbox;
+pub mod journal;
 pub mod list;
 pub mod modify;
+#[cfg(feature = "nlp")]
 pub mod nlp;
+pub mod notify;
+pub mod palette;
+pub mod picker;
+pub mod plan;
+pub mod plugin;
+pub mod private;
+pub mod project;
+pub mod publish;
+pub mod query;
+pub mod remind;
+pub mod reminders;
+pub mod report;
+pub mod rollover;
+pub mod rules;
+pub mod schedule;
+#[cfg(feature = "scripting")]
+pub mod scripts;
+pub mod show;
+pub mod template;
+pub mod triage;
+pub mod tutorial;
+pub mod view;
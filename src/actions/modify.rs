@@ -1,25 +1,40 @@
 use std::{
     io,
-    io::Write,
+    io::{
+        IsTerminal,
+        Write,
+    },
 };
 
 use rusqlite::Connection;
 
 use crate::{
-    actions::display,
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+        picker,
+    },
     args::{
         cron,
         parser::{
             DeleteCommand,
             DoneCommand,
+            ItemRef,
             UpdateCommand,
         },
         timestr,
     },
+    config::{
+        self,
+        DisplayId,
+    },
     db::{
         cache,
+        conn::atomically,
         crud::{
             delete_item,
+            find_by_category_seq,
+            find_by_uuid_prefix,
             get_item,
             insert_item,
             query_items,
@@ -28,21 +43,86 @@ use crate::{
         item::{
             Item,
             ItemQuery,
+            INBOX,
             RECORD,
             RECURRING_TASK,
             RECURRING_TASK_RECORD,
+            TASK,
         },
+        reminders,
     },
+    error::TascliError,
+    events,
+    private,
+    tr,
 };
 
-pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), String> {
-    validate_cache(conn)?;
-    let row_id = get_rowid_from_cache(conn, cmd.index)?;
-    let status = cmd.status;
+pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), TascliError> {
+    let row_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let item = get_item(conn, row_id)?;
+    let item = complete_item(conn, item, cmd.status, cmd.comment.as_deref())?;
+
+    if item.action == RECURRING_TASK {
+        display::print_bold(&tr!("completed-recurring-task"));
+    } else {
+        display::print_bold(&tr!("completed-task"));
+    }
+    display::print_items(&[item], false, false);
+
+    // `done -s ongoing` reopens a task, its subtasks are not asked about then
+    if OPEN_STATUS_CODES.contains(&cmd.status) {
+        return Ok(());
+    }
+    let subtasks = open_subtasks(conn, row_id)?;
+    if !subtasks.is_empty() {
+        close_subtasks(conn, subtasks, cmd.status)?;
+    }
+    Ok(())
+}
+
+// Asks whether the open subtasks of a closed task are closed with the same status, they are
+// left open when the answer is no or there is no one to ask
+fn close_subtasks(conn: &Connection, subtasks: Vec<Item>, status: u8) -> Result<(), TascliError> {
+    let count = subtasks.len();
+    display::print_items(&subtasks, false, false);
+    let question = tr!("confirm-close-subtasks", count = count);
+    if !io::stdin().is_terminal() || !prompt_yes_no(&question) {
+        display::print_yellow(&tr!("subtasks-left-open", count = count));
+        return Ok(());
+    }
+    for subtask in subtasks {
+        complete_item(conn, subtask, status, None)?;
+    }
+    display::print_bold(&tr!("closed-subtasks", count = count));
+    Ok(())
+}
+
+// The open subtasks of a task, and theirs, parents before their subtasks
+fn open_subtasks(conn: &Connection, parent_id: i64) -> Result<Vec<Item>, TascliError> {
+    let mut open = Vec::new();
+    let mut parents = vec![parent_id];
+    while let Some(parent_id) = parents.pop() {
+        for subtask in query_items(conn, &ItemQuery::new().with_parent_id(parent_id))? {
+            // A closed subtask may still have open ones
+            parents.push(subtask.id.unwrap());
+            if OPEN_STATUS_CODES.contains(&subtask.status) {
+                open.push(subtask);
+            }
+        }
+    }
+    Ok(open)
+}
 
-    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+/// Closes a task with the given status and writes its completion record,
+/// recurring tasks are completed for the current iteration instead.
+pub fn complete_item(
+    conn: &Connection,
+    mut item: Item,
+    status: u8,
+    comment: Option<&str>,
+) -> Result<Item, TascliError> {
     if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
-        return Err("Cannot complete a record".to_string());
+        return Err(TascliError::Invalid(tr!("cannot-complete-record")));
     }
 
     if item.action == RECURRING_TASK {
@@ -59,22 +139,24 @@ pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), String
                 .with_action(RECURRING_TASK_RECORD)
                 .with_recurring_task_id(item.id.unwrap())
                 .with_good_until_min(last_occurrence),
-        )
-        .map_err(|e| format!("Failed to query existing records: {:?}", e))?;
+        )?;
 
         if !existing_records.is_empty() {
-            return Err(
+            return Err(TascliError::Invalid(
                 "This recurring task has already been completed for this iteration".to_string(),
-            );
+            ));
         }
 
         let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
 
-        let mut record_content = format!("Completed Recurring Task: {}", item.content);
-        if let Some(comment) = &cmd.comment {
-            record_content.push('\n');
-            record_content.push_str(comment);
-        }
+        let record_content = private::edit(&item.content, |content| {
+            let mut record_content = format!("Completed Recurring Task: {}", content);
+            if let Some(comment) = comment {
+                record_content.push('\n');
+                record_content.push_str(comment);
+            }
+            record_content
+        })?;
 
         let completion_record = Item::create_recurring_record(
             item.category.clone(),
@@ -82,68 +164,65 @@ pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), String
             item.id.unwrap(),
             next_occurrence,
         );
-        insert_item(conn, &completion_record)
-            .map_err(|e| format!("Failed to create completion record: {:?}", e))?;
-
-        display::print_bold("Completed Recurring Task:");
-        display::print_items(&[item], false, false);
-        return Ok(());
+        insert_item(conn, &completion_record)?;
+        events::emit(conn, events::DONE, &mut item);
+        return Ok(item);
     }
 
-    if let Some(comment) = &cmd.comment {
-        item.content.push('\n');
-        item.content.push_str(comment);
+    if let Some(comment) = comment {
+        item.content = private::edit(&item.content, |content| format!("{}\n{}", content, comment))?;
     }
 
-    let completion_content = format!("Completed Task: {}", item.content);
+    let completion_content = private::edit(&item.content, |content| {
+        format!("Completed Task: {}", content)
+    })?;
     let completion_record = Item::new(
         RECORD.to_string(),
         item.category.clone(),
         completion_content,
     );
-    insert_item(conn, &completion_record)
-        .map_err(|e| format!("Failed to create completion record: {:?}", e))?;
 
+    // The task is not closed without its completion record
     item.status = status;
-    update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
-    display::print_bold("Completed Task:");
-    display::print_items(&[item], false, false);
-    Ok(())
+    atomically(conn, || {
+        insert_item(conn, &completion_record)?;
+        update_item(conn, &item)
+    })?;
+    events::emit(conn, events::DONE, &mut item);
+    Ok(item)
 }
 
-pub fn handle_deletecmd(conn: &Connection, cmd: &DeleteCommand) -> Result<(), String> {
-    validate_cache(conn)?;
-    let row_id = get_rowid_from_cache(conn, cmd.index)?;
-    let item = get_item(conn, row_id).map_err(|e| format!("Failed to find item: {:?}", e))?;
+pub fn handle_deletecmd(conn: &Connection, cmd: &DeleteCommand) -> Result<(), TascliError> {
+    let row_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let mut item = get_item(conn, row_id)?;
     let item_type = item.action.clone();
     let is_record = item_type == RECORD || item_type == RECURRING_TASK_RECORD;
-    display::print_items(&[item], is_record, false);
-    let accept = prompt_yes_no(&format!(
-        "Are you sure you want to delete this {}? ",
-        &item_type
-    ));
+    display::print_items(std::slice::from_ref(&item), is_record, false);
+    let accept = prompt_yes_no(&tr!("confirm-delete", item_type = item_type.as_str()));
 
     if !accept {
-        return Err(format!("Not deleting the {}", &item_type));
+        return Err(TascliError::Invalid(tr!("not-deleting", item_type = item_type.as_str())));
     }
-    delete_item(conn, row_id).map_err(|e| format!("Failed to update item: {:?}", e))?;
-    display::print_bold("Deletion success");
+    delete_item(conn, row_id)?;
+    events::emit(conn, events::DELETE, &mut item);
+    display::print_bold(&tr!("deletion-success"));
     Ok(())
 }
 
-pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), String> {
-    validate_cache(conn)?;
-    let row_id = get_rowid_from_cache(conn, cmd.index)?;
-    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), TascliError> {
+    let row_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let mut item = get_item(conn, row_id)?;
 
     if item.action == RECURRING_TASK {
         if cmd.status.is_some() {
-            return Err("Cannot update status for recurring tasks".to_string());
+            return Err(TascliError::Invalid(
+                "Cannot update status for recurring tasks".to_string(),
+            ));
         }
         if cmd.add_content.is_some() {
-            return Err(
+            return Err(TascliError::Invalid(
                 "Cannot use add_content for recurring tasks, use content instead".to_string(),
-            );
+            ));
         }
 
         if let Some(schedule_str) = &cmd.target_time {
@@ -153,7 +232,7 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
                     item.human_schedule = Some(schedule_str.clone());
                 }
                 Err(_) => {
-                    return Err("Cannot parse schedule".to_string());
+                    return Err(TascliError::Parse("Cannot parse schedule".to_string()));
                 }
             }
         }
@@ -163,19 +242,26 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
         }
 
         if let Some(content) = &cmd.content {
-            item.content = content.clone();
+            item.content = private::edit(&item.content, |_| content.clone())?;
+        }
+        if cmd.private && !private::is_private(&item.content) {
+            item.content = private::encrypt(&item.content)?;
         }
 
-        update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
+        update_item(conn, &item)?;
 
-        display::print_bold("Updated Recurring Task:");
+        display::print_bold(&tr!("updated-recurring-task"));
         display::print_items(&[item], false, false);
         return Ok(());
     }
 
     if let Some(target) = &cmd.target_time {
         let target_time = timestr::to_unix_epoch(target)?;
-        item.target_time = Some(target_time);
+        item.set_target_time(Some(target_time));
+        // A deadline files an inbox item as a task
+        if item.action == INBOX {
+            item.action = TASK.to_string();
+        }
     }
 
     if let Some(category) = &cmd.category {
@@ -183,57 +269,126 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
     }
 
     if let Some(content) = &cmd.content {
-        item.content = content.clone();
+        item.content = private::edit(&item.content, |_| content.clone())?;
     }
 
     if let Some(add) = &cmd.add_content {
-        item.content.push('\n');
-        item.content.push_str(add);
+        item.content = private::edit(&item.content, |content| format!("{}\n{}", content, add))?;
+    }
+
+    if cmd.private && !private::is_private(&item.content) {
+        item.content = private::encrypt(&item.content)?;
     }
 
     if let Some(status) = cmd.status {
         item.status = status;
     }
 
-    update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
+    update_item(conn, &item)?;
+    // A moved deadline is reminded of again
+    if cmd.target_time.is_some() {
+        reminders::rearm_reminders(conn, row_id)?;
+    }
 
     let is_record = item.action == RECORD || item.action == RECURRING_TASK_RECORD;
-    let action = if is_record { "Record" } else { "Task" };
-    display::print_bold(&format!("Updated {}:", action));
+    let updated = if is_record { tr!("updated-record") } else { tr!("updated-task") };
+    display::print_bold(&updated);
     display::print_items(&[item], is_record, false);
     Ok(())
 }
 
-fn validate_cache(conn: &Connection) -> Result<(), String> {
+/// Row id of an item given by list index, which needs a valid cache, by category number
+/// or by id prefix.
+pub(crate) fn resolve_item(conn: &Connection, item_ref: &ItemRef) -> Result<i64, TascliError> {
+    resolve_item_as(conn, item_ref, config::display_id())
+}
+
+fn resolve_item_as(
+    conn: &Connection,
+    item_ref: &ItemRef,
+    display_id: DisplayId,
+) -> Result<i64, TascliError> {
+    match item_ref {
+        // Listings show the ids themselves
+        ItemRef::Index(id) if display_id == DisplayId::Global => match get_item(conn, *id as i64) {
+            Ok(_) => Ok(*id as i64),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                Err(TascliError::Invalid(tr!("item-not-found", item = *id as i64)))
+            }
+            Err(e) => Err(e.into()),
+        },
+        ItemRef::Index(index) => {
+            validate_cache(conn)?;
+            get_rowid_from_cache(conn, *index)
+        }
+        ItemRef::Category(category, seq) => match find_by_category_seq(conn, category, *seq)? {
+            Some(id) => Ok(id),
+            None => {
+                let item = format!("{}-{}", category, seq);
+                Err(TascliError::Invalid(tr!("item-not-found", item = item.as_str())))
+            }
+        },
+        ItemRef::Id(prefix) => match find_by_uuid_prefix(conn, prefix, 2)?.as_slice() {
+            [id] => Ok(*id),
+            [] => Err(TascliError::Invalid(tr!("id-not-found", id = prefix.as_str()))),
+            _ => Err(TascliError::Invalid(tr!("id-ambiguous", id = prefix.as_str()))),
+        },
+    }
+}
+
+/// Row id of the item given, or of an open task the user picks when none was given.
+pub(crate) fn resolve_or_pick(
+    conn: &Connection,
+    item_ref: Option<&ItemRef>,
+) -> Result<i64, TascliError> {
+    match item_ref {
+        Some(item_ref) => resolve_item(conn, item_ref),
+        None if io::stdin().is_terminal() => picker::pick_task(conn, &mut io::stdin().lock()),
+        None => Err(TascliError::Invalid(
+            "No item given, pass an index or an id when not run in a terminal".to_string(),
+        )),
+    }
+}
+
+pub(crate) fn validate_cache(conn: &Connection) -> Result<(), TascliError> {
     match cache::validate_cache(conn) {
         Ok(true) => Ok(()),
-        Ok(false) => Err("Cache is not valid, considering running list command first".to_string()),
-        Err(_) => Err("Cannot connect to cache".to_string()),
+        Ok(false) => Err(TascliError::Invalid(tr!("cache-invalid"))),
+        Err(_) => Err(TascliError::Invalid(tr!("cache-unavailable"))),
     }
 }
 
-fn get_rowid_from_cache(conn: &Connection, index: usize) -> Result<i64, String> {
+pub(crate) fn get_rowid_from_cache(conn: &Connection, index: usize) -> Result<i64, TascliError> {
     let index = index as i64;
-    match cache::read(conn, index).map_err(|e| format!("Failed to read cache table: {:?}", e))? {
+    match cache::read(conn, index)? {
         Some(id) => Ok(id),
-        None => Err(format!("index {} does not exist", index)),
+        None => Err(TascliError::Invalid(tr!("index-not-found", index = index))),
     }
 }
 
 fn prompt_yes_no(question: &str) -> bool {
-    print!("{} (y/n): ", question);
+    print!("{} {}: ", question, tr!("prompt-yes-no"));
     io::stdout().flush().unwrap();
 
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap();
 
-    matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+    let answer = input.trim().to_lowercase();
+    tr!("prompt-yes-answers")
+        .split(',')
+        .any(|yes| yes.trim() == answer)
 }
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
+
     use super::*;
     use crate::{
+        args::parser::{
+            Action,
+            CliArgs,
+        },
         db::{
             cache,
             crud::{
@@ -249,9 +404,74 @@ mod tests {
             get_test_conn,
             insert_recurring_task,
             insert_task,
+            update_status,
         },
     };
 
+    #[test]
+    fn test_resolve_item_by_id_prefix() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "finish report", "tomorrow");
+        let other = insert_task(&conn, "work", "review report", "tomorrow");
+        let set_uuid = |id: i64, uuid: &str| {
+            conn.execute("UPDATE items SET uuid = ?1 WHERE id = ?2", rusqlite::params![uuid, id])
+                .unwrap();
+        };
+        set_uuid(id, "a3f2b1c4-0000-4000-8000-000000000000");
+        set_uuid(other, "a3f29999-0000-4000-8000-000000000000");
+
+        // No listing is needed to refer to items by id
+        assert_eq!(resolve_item(&conn, &ItemRef::Id("a3f2b1".to_string())).unwrap(), id);
+        let err = resolve_item(&conn, &ItemRef::Id("a3f2".to_string())).unwrap_err();
+        assert!(err.to_string().contains("more than one"), "{}", err);
+        let err = resolve_item(&conn, &ItemRef::Id("ffff".to_string())).unwrap_err();
+        assert!(err.to_string().contains("no item id"), "{}", err);
+        assert!(resolve_item(&conn, &ItemRef::Index(1)).is_err());
+    }
+
+    #[test]
+    fn test_resolve_item_by_number() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "home", "laundry", "tomorrow");
+        let id = insert_task(&conn, "work", "finish report", "tomorrow");
+
+        let work_1 = ItemRef::Category("work".to_string(), 1);
+        assert_eq!(resolve_item(&conn, &work_1).unwrap(), id);
+        let err = resolve_item(&conn, &ItemRef::Category("work".to_string(), 2)).unwrap_err();
+        assert!(err.to_string().contains("no item"), "{}", err);
+
+        // Plain numbers are ids once listings show them
+        let global =
+            |index: usize| resolve_item_as(&conn, &ItemRef::Index(index), DisplayId::Global);
+        assert_eq!(global(id as usize).unwrap(), id);
+        assert!(global(3).is_err());
+        assert!(resolve_item_as(&conn, &ItemRef::Index(2), DisplayId::Category).is_err());
+    }
+
+    #[test]
+    fn test_item_ref_arguments() {
+        let index_of = |arg: &str| match CliArgs::try_parse_from(["tascli", "done", arg]) {
+            Ok(CliArgs { arguments: Some(Action::Done(cmd)), .. }) => Ok(cmd.index.unwrap()),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e.to_string()),
+        };
+        assert_eq!(index_of("3"), Ok(ItemRef::Index(3)));
+        assert_eq!(index_of("A3F2"), Ok(ItemRef::Id("a3f2".to_string())));
+        assert_eq!(index_of("12345678"), Ok(ItemRef::Id("12345678".to_string())));
+        assert_eq!(index_of("70000"), Ok(ItemRef::Index(70000)));
+        assert_eq!(index_of("work-12"), Ok(ItemRef::Category("work".to_string(), 12)));
+        assert_eq!(
+            index_of("side-project-3"),
+            Ok(ItemRef::Category("side-project".to_string(), 3))
+        );
+        // The start of a uuid up to its first dash
+        assert_eq!(index_of("a3f2b1c4-0"), Ok(ItemRef::Id("a3f2b1c4-0".to_string())));
+        assert!(index_of("-3").is_err());
+        assert!(index_of("0").is_err());
+        assert!(index_of("a3f").is_err());
+        assert!(index_of("report").is_err());
+    }
+
     #[test]
     fn test_handle_donecmd() {
         let (conn, _temp_file) = get_test_conn();
@@ -260,7 +480,7 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let done_cmd = DoneCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             status: 1,
             comment: None,
         };
@@ -275,7 +495,7 @@ mod tests {
         assert_eq!(records[0].category, "work");
 
         let done_cmd = DoneCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             status: 2,
             comment: None,
         };
@@ -287,6 +507,35 @@ mod tests {
         assert_eq!(records.len(), 2);
     }
 
+    #[test]
+    fn test_open_subtasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let post = insert_task(&conn, "blog", "write post", "friday");
+        let subtask = |parent_id: i64, content: &str| {
+            let mut item = Item::new(TASK.to_string(), "blog".to_string(), content.to_string());
+            item.parent_id = Some(parent_id);
+            insert_item(&conn, &item).unwrap()
+        };
+        let draft = subtask(post, "draft");
+        subtask(draft, "outline");
+        let intro = subtask(post, "intro");
+        update_status(&conn, draft, 1);
+
+        let contents: Vec<String> =
+            open_subtasks(&conn, post).unwrap().into_iter().map(|t| t.content).collect();
+        assert_eq!(contents, ["intro", "outline"]);
+
+        // Without a terminal to ask, completing the parent leaves them open
+        let done_cmd = DoneCommand {
+            index: Some(ItemRef::Category("blog".to_string(), 1)),
+            status: 1,
+            comment: None,
+        };
+        handle_donecmd(&conn, &done_cmd).unwrap();
+        assert_eq!(get_item(&conn, post).unwrap().status, 1);
+        assert_eq!(get_item(&conn, intro).unwrap().status, 0);
+    }
+
     #[test]
     fn test_handle_donecmd_with_comment() {
         let (conn, _temp_file) = get_test_conn();
@@ -295,7 +544,7 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let done_cmd = DoneCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             status: 1,
             comment: Some("Added extra analysis section".to_string()),
         };
@@ -327,24 +576,26 @@ mod tests {
         let item_id = cache::read(&conn, 1).unwrap().unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: None,
             category: None,
             content: Some("reorganize garage thoroughly".to_string()),
             add_content: None,
             status: None,
+            private: false,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.content, "reorganize garage thoroughly");
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: None,
             category: None,
             content: None,
             add_content: Some("move stuff to basement".to_string()),
             status: None,
+            private: false,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
@@ -354,24 +605,26 @@ mod tests {
         );
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: None,
             category: None,
             content: None,
             add_content: None,
             status: Some(3),
+            private: false,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.status, 3);
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: Some("eow".to_string()),
             category: Some("chore".to_string()),
             content: None,
             add_content: None,
             status: None,
+            private: false,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let got_item = get_item(&conn, item_id).unwrap();
@@ -386,7 +639,7 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let done_cmd = DoneCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             status: 1,
             comment: Some("Discussed sprint goals".to_string()),
         };
@@ -405,14 +658,14 @@ mod tests {
         assert!(records[0].good_until.is_some());
 
         let done_cmd2 = DoneCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             status: 1,
             comment: None,
         };
         let result = handle_donecmd(&conn, &done_cmd2);
         assert!(result.is_err());
         assert_eq!(
-            result.unwrap_err(),
+            result.unwrap_err().to_string(),
             "This recurring task has already been completed for this iteration"
         );
 
@@ -429,12 +682,13 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: None,
             category: Some("meetings".to_string()),
             content: Some("Daily team sync".to_string()),
             add_content: None,
             status: None,
+            private: false,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_ok());
@@ -445,12 +699,13 @@ mod tests {
 
         // Test updating schedule
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: Some("Daily 3PM".to_string()),
             category: None,
             content: None,
             add_content: None,
             status: None,
+            private: false,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_ok());
@@ -459,32 +714,34 @@ mod tests {
         assert_eq!(updated_item.human_schedule, Some("Daily 3PM".to_string()));
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: None,
             category: None,
             content: None,
             add_content: None,
             status: Some(1),
+            private: false,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
         assert_eq!(
-            result.unwrap_err(),
+            result.unwrap_err().to_string(),
             "Cannot update status for recurring tasks"
         );
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: None,
             category: None,
             content: None,
             add_content: Some("extra notes".to_string()),
             status: None,
+            private: false,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
         assert_eq!(
-            result.unwrap_err(),
+            result.unwrap_err().to_string(),
             "Cannot use add_content for recurring tasks, use content instead"
         );
     }
@@ -499,16 +756,17 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: Some("Daily 9AM".to_string()),
             category: None,
             content: None,
             add_content: None,
             status: None,
+            private: false,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Couldn't parse"));
+        assert!(result.unwrap_err().to_string().contains("Couldn't parse"));
 
         // Test blocking recurring task to regular conversion
         cache::clear(&conn).unwrap();
@@ -517,12 +775,13 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            index: Some(ItemRef::Index(1)),
             target_time: Some("tomorrow".to_string()),
             category: None,
             content: None,
             add_content: None,
             status: None,
+            private: false,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
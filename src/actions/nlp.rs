@@ -1,64 +1,120 @@
 //! Natural language processing action handlers
 
+use std::sync::Arc;
+
 use rusqlite::Connection;
 
 use crate::{
     actions::{
+        assistant::answer,
+        help,
         display::{print_green, print_yellow, print_red},
+        notify::automations::{
+            handle_automate,
+            handle_delete_automation,
+            handle_list_automations,
+        },
+        triage::prompt,
     },
     args::parser::{
         NLPCommand,
         NLPConfigCommand,
     },
     config,
+    db::{
+        conn::atomically,
+        crud::{
+            category_counts,
+            query_categories,
+        },
+        tags::all_tags,
+    },
+    error::TascliError,
+    events,
     nlp::{
-        NLPParser, SequentialExecutor, CompoundExecutionMode,
+        NLPParser, SequentialExecutor, SequentialContext, CompoundExecutionMode,
+        ConditionEvaluator, ConditionalExecutor,
         PreviewManager, commands_to_previews, ConfirmationResult,
-        SuggestionEngine, SuggestionRequest,
+        SuggestionEngine, AutoCompleter,
         ErrorRecoveryEngine,
         LearningEngine, LearningStats, format_action,
-        PersonalizationEngine, get_user_id,
+        PersonalizationEngine, get_user_id, parse_clarification,
         ActionType,
         show_interpretation, show_compound_interpretation, show_interpretation_compact,
-        HelpSystem, HelpTopic,
+        LOW_CONFIDENCE,
+        CommandMapper,
+        ExecutionSummary,
+        NLPError,
+        cancel,
+        prepare_parallel,
     },
 };
 
-pub fn handle_nlp_command(conn: &Connection, cmd: &NLPCommand) -> Result<(), String> {
+// Past inputs offered as completions
+const HISTORY_COMPLETIONS: usize = 50;
+
+pub fn handle_nlp_command(conn: &Connection, cmd: &NLPCommand) -> Result<(), TascliError> {
     // Handle configuration commands first
     if let Some(config_cmd) = &cmd.config {
-        return handle_nlp_config(config_cmd);
+        return handle_nlp_config(conn, config_cmd);
     }
 
     // Parse natural language command
     let rt = tokio::runtime::Runtime::new()
-        .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+        .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
 
     rt.block_on(async {
         // Get NLP configuration
-        let nlp_config = config::get_nlp_config()
-            .map_err(|e| format!("Failed to get NLP config: {}", e))?;
+        let nlp_config = config::get_nlp_config()?;
 
         if !nlp_config.enabled {
-            return Err("NLP is disabled. Use 'tascli nlp config enable' to enable it.".to_string());
+            return Err(TascliError::Config("NLP is disabled. Use 'tascli nlp config enable' to enable it.".to_string()));
         }
 
-        if nlp_config.api_key.is_none() {
-            return Err("OpenAI API key not configured. Use 'tascli nlp config set-key <api_key>' to set it.".to_string());
+        // Ctrl-C drops the request to the provider, or stops compound commands and rolls
+        // them back
+        cancel::reset();
+        tokio::spawn(cancel::listen());
+
+        if cmd.non_interactive {
+            return answer(conn, cmd, &nlp_config).await;
         }
 
-        // Create parser
-        let parser = NLPParser::new(nlp_config.clone());
+        // "by gym I mean category health" is remembered rather than run
+        if let Some((phrase, category)) = parse_clarification(&cmd.description) {
+            return remember_clarification(&phrase, &category);
+        }
 
-        // Initialize personalization engine
-        let user_id = get_user_id();
-        if let Ok(personalization_db_path) = config::get_personalization_db_path() {
-            let _ = parser.init_personalization(&personalization_db_path, user_id).await;
+        require_provider(&nlp_config)?;
+        let categories = query_categories(conn).unwrap_or_default();
+        let parser = Arc::new(create_parser(conn, &nlp_config, categories.clone()).await);
+
+        // Clauses go to the provider at once and run in order as one compound command
+        if cmd.parallel {
+            let (commands, description) = parse_clauses(&parser, &cmd.description).await?;
+            if nlp_config.show_transparency {
+                let all_args: Vec<Vec<String>> =
+                    commands.iter().map(CommandMapper::to_tascli_args).collect();
+                show_compound_interpretation(&cmd.description, &all_args, &description);
+            }
+            let mode = CompoundExecutionMode::Parallel;
+            handle_compound_command(conn, &commands, &mode, cmd.show, &nlp_config)?;
+            parser.record_input(&cmd.description).await;
+            return Ok(());
         }
 
         // Parse the natural language command, checking for compound commands
-        match parser.parse_to_compound_args_with_transparency(&cmd.description).await {
+        let parsed =
+            cancel::abortable(parser.parse_to_compound_args_with_transparency(&cmd.description));
+        match parsed.await {
             Ok((all_args, description, nlp_command)) => {
+                if cmd.simulate {
+                    return simulate(conn, &cmd.description, &nlp_command);
+                }
+
+                // Interpretations like this one were corrected before, ask before running it
+                let nlp_config = confirm_if_unsure(&nlp_config, &nlp_command);
+
                 // Show interpretation transparency if enabled
                 if nlp_config.show_transparency {
                     if all_args.len() > 1 {
@@ -71,106 +127,247 @@ pub fn handle_nlp_command(conn: &Connection, cmd: &NLPCommand) -> Result<(), Str
                 // Check if this is a compound command
                 if all_args.len() > 1 {
                     // Handle compound command
-                    handle_compound_command(conn, &all_args, &description, cmd.show, &nlp_config)
+                    let commands = convert_args_to_commands(&all_args);
+                    let mode = CompoundExecutionMode::ContinueOnError;
+                    handle_compound_command(conn, &commands, &mode, cmd.show, &nlp_config)?;
+                    parser.record_input(&cmd.description).await;
+                    Ok(())
                 } else {
-                    // Handle single command
-                    handle_single_command(conn, &all_args[0], &description, cmd.show, &nlp_config)
+                    // Propose the category of the last few added items when the preview asks
+                    let asks = (cmd.show || nlp_config.preview_enabled) && !nlp_config.auto_confirm;
+                    let context_category =
+                        if asks { parser.context_category(&nlp_command).await } else { None };
+                    let ran = handle_single_command(
+                        conn,
+                        &nlp_command,
+                        context_category.as_deref(),
+                        cmd.show,
+                        &nlp_config,
+                    )?;
+                    if let Some(ran) = ran {
+                        parser.record_category(&ran).await;
+                        parser.record_input(&cmd.description).await;
+                    }
+                    Ok(())
                 }
             },
+            Err(NLPError::Cancelled) => Err(NLPError::Cancelled.into()),
             Err(e) => {
                 // Use error recovery to provide helpful suggestions
                 print_red(&format!("Failed to parse natural language command: {}", e));
 
-                // Try to get available categories for context
-                let available_categories: Vec<String> = match crate::db::crud::query_items(
-                    conn,
-                    &crate::db::item::ItemQuery::new()
-                ) {
-                    Ok(items) => {
-                        let mut cats: std::collections::HashSet<String> = std::collections::HashSet::new();
-                        for item in items {
-                            if !item.category.is_empty() {
-                                cats.insert(item.category);
-                            }
-                        }
-                        cats.into_iter().collect()
-                    },
-                    Err(_) => Vec::new(),
-                };
-
                 // Generate and display recovery options with help suggestions
-                let recovery_result = ErrorRecoveryEngine::handle_error(&e, &cmd.description, &available_categories);
+                let recovery_result =
+                    ErrorRecoveryEngine::handle_error(&e, &cmd.description, &categories);
                 ErrorRecoveryEngine::display_recovery_with_help(&recovery_result, &cmd.description);
 
-                Err(e.to_string())
+                Err(e.into())
             }
         }
     })
 }
 
-/// Handle single command with preview
+pub(super) fn require_provider(nlp_config: &crate::nlp::NLPConfig) -> Result<(), TascliError> {
+    if !nlp_config.has_provider() {
+        return Err(TascliError::Config(
+            "OpenAI API key not configured. Use 'tascli nlp config set-key <api_key>' to set it."
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// A parser with the personalization and learning data, knowing the categories lets simple
+// commands skip the api and knowing the tags corrects mistyped ones
+pub(super) async fn create_parser(
+    conn: &Connection,
+    nlp_config: &crate::nlp::NLPConfig,
+    categories: Vec<String>,
+) -> NLPParser {
+    let parser = NLPParser::with_categories(nlp_config.clone(), categories);
+    parser.update_tags(all_tags(conn).unwrap_or_default()).await;
+    let user_id = get_user_id();
+    if let Ok(personalization_db_path) = config::get_personalization_db_path() {
+        let _ = parser.init_personalization(&personalization_db_path, user_id).await;
+    }
+    if let Ok(learning_db_path) = config::get_learning_db_path() {
+        let _ = parser.init_learning(&learning_db_path).await;
+    }
+    parser
+}
+
+// Completions from the inputs that ran before and the categories in use, the most used first
+fn auto_completer(conn: &Connection) -> AutoCompleter {
+    let categories = category_counts(conn)
+        .map(|counts| counts.into_iter().map(|(category, _)| category).collect())
+        .unwrap_or_default();
+    let history = personalization_engine()
+        .ok()
+        .and_then(|engine| engine.command_history(HISTORY_COMPLETIONS).ok())
+        .unwrap_or_default();
+    AutoCompleter::from_history(categories, history)
+}
+
+fn personalization_engine() -> Result<PersonalizationEngine, TascliError> {
+    let personalization_db_path = config::get_personalization_db_path()?;
+    PersonalizationEngine::with_db(&personalization_db_path, get_user_id())
+        .map_err(|e| TascliError::Invalid(format!("Failed to access database: {}", e)))
+}
+
+// Store the category the user said a phrase stands for, used from then on
+pub(super) fn remember_clarification(phrase: &str, category: &str) -> Result<(), TascliError> {
+    personalization_engine()?.remember_clarification(phrase, category)
+        .map_err(|e| TascliError::Invalid(format!("Failed to remember clarification: {}", e)))?;
+    print_green(&format!("Got it, '{}' means category {} from now on.", phrase, category));
+    println!("Forget it with: tascli nlp config forget-clarification '{}'", phrase);
+    Ok(())
+}
+
+// The config with the preview turned on when the command was parsed with low confidence
+fn confirm_if_unsure(
+    nlp_config: &crate::nlp::NLPConfig,
+    nlp_command: &crate::nlp::NLPCommand,
+) -> crate::nlp::NLPConfig {
+    let mut nlp_config = nlp_config.clone();
+    if nlp_command.confidence.is_some_and(|confidence| confidence < LOW_CONFIDENCE) {
+        print_yellow("Commands like this were corrected before, please check it before it runs.");
+        nlp_config.preview_enabled = true;
+        nlp_config.auto_confirm = false;
+    }
+    nlp_config
+}
+
+// Report whether the conditions hold and which commands would run, running none
+fn simulate(
+    conn: &Connection,
+    input: &str,
+    nlp_command: &crate::nlp::NLPCommand,
+) -> Result<(), TascliError> {
+    let simulated = ConditionalExecutor::new(false)
+        .simulate(conn, nlp_command)
+        .map_err(TascliError::Invalid)?;
+    println!("Simulating: {}", input);
+    for (index, command) in simulated.iter().enumerate() {
+        let number = if simulated.len() > 1 { format!("{}. ", index + 1) } else { String::new() };
+        match &command.condition {
+            Some(trace) => {
+                let outcome = if trace.met { "met" } else { "not met" };
+                println!("  {}Condition {}: {}", number, outcome, trace.reasons.join(", "));
+            }
+            None => println!("  {}No condition", number),
+        }
+        let args: Vec<String> = command.args.iter()
+            .map(|arg| if arg.contains(' ') { format!("\"{}\"", arg) } else { arg.clone() })
+            .collect();
+        let line = format!("tascli {}", args.join(" "));
+        if command.runs {
+            print_green(&format!("     Would run: {}", line));
+        } else {
+            print_yellow(&format!("     Would skip: {}", line));
+        }
+    }
+    println!("Nothing was run.");
+    Ok(())
+}
+
+// Why the condition of a command does not hold, None when it has none or it holds
+pub(super) fn unmet_condition(
+    conn: &Connection,
+    nlp_cmd: &crate::nlp::NLPCommand,
+) -> Result<Option<String>, TascliError> {
+    let Some(condition) = &nlp_cmd.condition else {
+        return Ok(None);
+    };
+    let trace = ConditionEvaluator::new()
+        .explain(condition, conn, &SequentialContext::default())
+        .map_err(TascliError::Invalid)?;
+    Ok((!trace.met).then(|| trace.reasons.join(", ")))
+}
+
+/// Handle single command with preview, the command that ran when it was not cancelled
 fn handle_single_command(
     conn: &Connection,
-    args: &[String],
-    description: &str,
+    nlp_cmd: &crate::nlp::NLPCommand,
+    context_category: Option<&str>,
     force_show: bool,
     nlp_config: &crate::nlp::NLPConfig,
-) -> Result<(), String> {
+) -> Result<Option<crate::nlp::NLPCommand>, TascliError> {
+    if let Some(reasons) = unmet_condition(conn, nlp_cmd)? {
+        print_yellow(&format!("Skipped, the condition is not met: {}", reasons));
+        return Ok(None);
+    }
+
     // Create preview manager
     let preview_enabled = force_show || nlp_config.preview_enabled;
     let preview_manager = PreviewManager::new(preview_enabled, nlp_config.auto_confirm);
 
-    // Convert args to NLPCommand for preview
-    let nlp_cmd = convert_args_to_nlp_command(args);
+    let mut nlp_cmd = nlp_cmd.clone();
+    if let Some(category) = context_category {
+        nlp_cmd.category = Some(category.to_string());
+    }
 
     // Create preview
-    let preview = crate::nlp::PreviewCommand::from_nlp_command(&nlp_cmd, 0);
+    let mut preview = crate::nlp::PreviewCommand::from_nlp_command(&nlp_cmd, 0);
+    if let Some(category) = context_category {
+        preview = preview.with_context_category(category);
+    }
 
     // Show preview and get confirmation
     match preview_manager.preview_command(&preview)? {
-        ConfirmationResult::Confirmed => {
-            execute_parsed_command(conn, args)
-        },
+        ConfirmationResult::Confirmed => {},
         ConfirmationResult::Cancelled => {
             print_yellow("Command cancelled.");
-            Ok(())
+            return Ok(None);
         },
         ConfirmationResult::Edit => {
             print_yellow("Edit functionality not yet implemented. Command cancelled.");
-            Ok(())
+            return Ok(None);
+        },
+        ConfirmationResult::ChangeCategory => {
+            let answer = prompt(&mut std::io::stdin().lock(), "Category (empty for none)")?;
+            let Some(category) = answer else {
+                print_yellow("Command cancelled.");
+                return Ok(None);
+            };
+            nlp_cmd.category = Some(category).filter(|c| !c.is_empty());
         },
     }
+    // Ctrl-C at the prompt
+    if cancel::requested() {
+        print_yellow("Cancelled, nothing was run.");
+        return Ok(None);
+    }
+    execute_parsed_command(conn, &CommandMapper::to_tascli_args(&nlp_cmd))?;
+    Ok(Some(nlp_cmd))
 }
 
 /// Handle compound commands (multiple commands in one input)
 fn handle_compound_command(
     conn: &Connection,
-    all_args: &[Vec<String>],
-    description: &str,
+    commands: &[crate::nlp::NLPCommand],
+    execution_mode: &CompoundExecutionMode,
     force_show: bool,
     nlp_config: &crate::nlp::NLPConfig,
-) -> Result<(), String> {
-    // Convert args to NLPCommands for SequentialExecutor
-    let commands = convert_args_to_commands(all_args);
-
+) -> Result<(), TascliError> {
     // Create preview manager
     let preview_enabled = force_show || nlp_config.preview_enabled;
     let preview_manager = PreviewManager::new(preview_enabled, nlp_config.auto_confirm);
 
     // Create previews
-    let previews = commands_to_previews(&commands);
+    let previews = commands_to_previews(commands);
 
     // Show preview and get confirmation
-    match preview_manager.preview_compound(&previews, &CompoundExecutionMode::ContinueOnError)? {
+    match preview_manager.preview_compound(&previews, execution_mode)? {
         ConfirmationResult::Confirmed => {
             // Execute the compound command
-            execute_compound_commands(conn, &commands, &preview_manager)
+            execute_compound_commands(conn, commands, execution_mode, &preview_manager)
         },
         ConfirmationResult::Cancelled => {
             print_yellow("Commands cancelled.");
             Ok(())
         },
-        ConfirmationResult::Edit => {
+        // Categories are only proposed for single commands
+        ConfirmationResult::Edit | ConfirmationResult::ChangeCategory => {
             print_yellow("Edit functionality not yet implemented. Commands cancelled.");
             Ok(())
         },
@@ -181,14 +378,30 @@ fn handle_compound_command(
 fn execute_compound_commands(
     conn: &Connection,
     commands: &[crate::nlp::NLPCommand],
+    execution_mode: &CompoundExecutionMode,
     preview_manager: &PreviewManager,
-) -> Result<(), String> {
+) -> Result<(), TascliError> {
     // Create executor
     let executor = SequentialExecutor::new(false, true); // Continue on error, verbose
-    let execution_mode = CompoundExecutionMode::ContinueOnError;
 
-    // Disable internal preview since we already showed it
-    let result = executor.execute_compound(conn, commands, &execution_mode, false);
+    // The commands are applied together, so the ones that ran before Ctrl-C are rolled back.
+    // Their events wait for the commit, a rolled back run is never announced.
+    let mut cancelled = None;
+    let (result, held) = events::hold(|| atomically(conn, || {
+        // Disable internal preview since we already showed it
+        let summary = executor.execute_compound(conn, commands, execution_mode, false)
+            .map_err(TascliError::Invalid)?;
+        if summary.cancelled {
+            cancelled = Some(summary);
+            return Err(NLPError::Cancelled.into());
+        }
+        Ok(summary)
+    }));
+    events::release(conn, held);
+    if let Some(summary) = cancelled {
+        print_cancelled(commands, &summary);
+        return Err(NLPError::Cancelled.into());
+    }
 
     match result {
         Ok(summary) => {
@@ -216,6 +429,54 @@ fn execute_compound_commands(
     }
 }
 
+// What ran before Ctrl-C and was rolled back, and what did not run
+fn print_cancelled(commands: &[crate::nlp::NLPCommand], summary: &ExecutionSummary) {
+    print_yellow("Cancelled, nothing was applied.");
+    for (index, command) in commands.iter().enumerate() {
+        let outcome = if index < summary.results.len() { "rolled back" } else { "not run" };
+        let description = CommandMapper::describe_command(command);
+        println!("  Command {}: {} ({})", index + 1, description, outcome);
+    }
+}
+
+// Interprets each clause of a parallel command on a request of its own, up to MAX_PARALLEL at
+// once. Nothing runs when one of them cannot be interpreted.
+async fn parse_clauses(
+    parser: &Arc<NLPParser>,
+    input: &str,
+) -> Result<(Vec<crate::nlp::NLPCommand>, String), TascliError> {
+    let clauses = split_clauses(input);
+    if clauses.is_empty() {
+        return Err(TascliError::Invalid("Nothing to run".to_string()));
+    }
+    let steps = clauses
+        .iter()
+        .map(|clause| {
+            let parser = parser.clone();
+            let clause = clause.to_string();
+            async move { parser.parse(&clause).await }
+        })
+        .collect();
+
+    let mut commands = Vec::new();
+    let mut descriptions = Vec::new();
+    for (clause, parsed) in clauses.iter().zip(prepare_parallel(steps).await?) {
+        let mut command = parsed.map_err(|e| {
+            TascliError::Invalid(format!("Failed to interpret '{}': {}", clause, e))
+        })?;
+        descriptions.push(CommandMapper::describe_compound_command(&command));
+        let follow_ups = command.compound_commands.take().unwrap_or_default();
+        commands.push(command);
+        commands.extend(follow_ups);
+    }
+    Ok((commands, descriptions.join("; ")))
+}
+
+// The clauses of a parallel command are separated by ';' or new lines
+fn split_clauses(input: &str) -> Vec<&str> {
+    input.split([';', '\n']).map(str::trim).filter(|clause| !clause.is_empty()).collect()
+}
+
 /// Convert CLI args back to NLPCommands (simplified for compatibility)
 fn convert_args_to_commands(all_args: &[Vec<String>]) -> Vec<crate::nlp::NLPCommand> {
     all_args.iter().map(|args| convert_args_to_nlp_command(args)).collect()
@@ -254,7 +515,10 @@ fn convert_args_to_nlp_command(args: &[String]) -> crate::nlp::NLPCommand {
     }
 }
 
-fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
+fn handle_nlp_config(
+    conn: &Connection,
+    config_cmd: &NLPConfigCommand,
+) -> Result<(), TascliError> {
     match config_cmd {
         NLPConfigCommand::Enable => {
             let mut nlp_config = config::get_nlp_config()
@@ -301,6 +565,9 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             println!("  Context window: {}", nlp_config.context_window);
             println!("  Max API calls/minute: {}", nlp_config.max_api_calls_per_minute);
             println!("  API base URL: {}", nlp_config.api_base_url);
+            if let Some(fallback) = &nlp_config.fallback {
+                println!("  Fallback: {} ({})", fallback.api_base_url, fallback.model);
+            }
             println!("  Preview enabled: {}", nlp_config.preview_enabled);
             println!("  Auto-confirm: {}", nlp_config.auto_confirm);
             println!("  Show transparency: {}", nlp_config.show_transparency);
@@ -351,15 +618,8 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
         },
 
         NLPConfigCommand::Suggest { input } => {
-            // Get suggestions for the input
-            let request = SuggestionRequest {
-                input: input.clone(),
-                cursor_position: input.len(),
-                recent_commands: Vec::new(),
-                available_categories: Vec::new(),
-            };
-
-            let result = SuggestionEngine::suggest(&request);
+            // Get suggestions for the input, ranked by history and categories
+            let result = auto_completer(conn).suggest(input);
 
             // Show validation status
             if result.is_valid {
@@ -375,6 +635,14 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             Ok(())
         },
 
+        NLPConfigCommand::Complete { input } => {
+            // One per line, for shell completion scripts
+            for completion in auto_completer(conn).complete(input) {
+                println!("{}", completion);
+            }
+            Ok(())
+        },
+
         NLPConfigCommand::Patterns => {
             let patterns = SuggestionEngine::command_patterns();
 
@@ -396,7 +664,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             // Get learning statistics
             let learning_db_path = config::get_learning_db_path()?;
             let rt = tokio::runtime::Runtime::new()
-                .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+                .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
 
             rt.block_on(async {
                 let engine = LearningEngine::with_db(&learning_db_path);
@@ -426,7 +694,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                     }
                     Err(e) => {
                         print_red(&format!("Failed to access learning database: {}", e));
-                        Err(format!("Failed to access learning database: {}", e))
+                        Err(TascliError::Invalid(format!("Failed to access learning database: {}", e)))
                     }
                 }
             })
@@ -435,19 +703,19 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
         NLPConfigCommand::ClearLearning => {
             let learning_db_path = config::get_learning_db_path()?;
             let rt = tokio::runtime::Runtime::new()
-                .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+                .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
 
             rt.block_on(async {
                 let engine = LearningEngine::with_db(&learning_db_path);
                 match engine {
                     Ok(engine) => {
                         engine.clear()
-                            .map_err(|e| format!("Failed to clear learning data: {}", e))?;
+                            .map_err(|e| TascliError::Invalid(format!("Failed to clear learning data: {}", e)))?;
                         print_green("All learned corrections have been cleared.");
                         Ok(())
                     }
                     Err(e) => {
-                        Err(format!("Failed to access learning database: {}", e))
+                        Err(TascliError::Invalid(format!("Failed to access learning database: {}", e)))
                     }
                 }
             })
@@ -456,7 +724,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
         NLPConfigCommand::Learn { original, action, content, category } => {
             let learning_db_path = config::get_learning_db_path()?;
             let rt = tokio::runtime::Runtime::new()
-                .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+                .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
 
             rt.block_on(async {
                 let engine = LearningEngine::with_db(&learning_db_path);
@@ -470,7 +738,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                             "delete" | "remove" => ActionType::Delete,
                             "list" | "show" => ActionType::List,
                             "record" => ActionType::Record,
-                            _ => return Err(format!("Unknown action: {}", action)),
+                            _ => return Err(TascliError::Parse(format!("Unknown action: {}", action))),
                         };
 
                         let intended_command = crate::nlp::NLPCommand {
@@ -481,13 +749,13 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                         };
 
                         engine.learn_from_correction(&original, &intended_command)
-                            .map_err(|e| format!("Failed to store correction: {}", e))?;
+                            .map_err(|e| TascliError::Invalid(format!("Failed to store correction: {}", e)))?;
 
                         print_green(&format!("Learned: '{}' -> {} {}", original, action_type, content));
                         Ok(())
                     }
                     Err(e) => {
-                        Err(format!("Failed to access learning database: {}", e))
+                        Err(TascliError::Invalid(format!("Failed to access learning database: {}", e)))
                     }
                 }
             })
@@ -543,12 +811,12 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                         Ok(())
                     } else {
                         print_red("Failed to get personalization statistics.");
-                        Err("Failed to get statistics".to_string())
+                        Err(TascliError::Invalid("Failed to get statistics".to_string()))
                     }
                 }
                 Err(e) => {
                     print_red(&format!("Failed to access personalization database: {}", e));
-                    Err(format!("Failed to access personalization database: {}", e))
+                    Err(TascliError::Invalid(format!("Failed to access personalization database: {}", e)))
                 }
             }
         },
@@ -561,12 +829,12 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             match engine {
                 Ok(engine) => {
                     engine.clear()
-                        .map_err(|e| format!("Failed to clear personalization data: {}", e))?;
+                        .map_err(|e| TascliError::Invalid(format!("Failed to clear personalization data: {}", e)))?;
                     print_green("All personalization data has been reset.");
                     Ok(())
                 }
                 Err(e) => {
-                    Err(format!("Failed to access personalization database: {}", e))
+                    Err(TascliError::Invalid(format!("Failed to access personalization database: {}", e)))
                 }
             }
         },
@@ -579,14 +847,14 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             match engine {
                 Ok(engine) => {
                     let data = engine.export()
-                        .map_err(|e| format!("Failed to export personalization data: {}", e))?;
+                        .map_err(|e| TascliError::Invalid(format!("Failed to export personalization data: {}", e)))?;
 
                     println!("{}", data);
                     print_yellow("\nCopy this JSON to backup your personalization data.");
                     Ok(())
                 }
                 Err(e) => {
-                    Err(format!("Failed to access personalization database: {}", e))
+                    Err(TascliError::Invalid(format!("Failed to access personalization database: {}", e)))
                 }
             }
         },
@@ -612,7 +880,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                         "delete" | "remove" => ActionType::Delete,
                         "list" | "show" => ActionType::List,
                         "record" => ActionType::Record,
-                        _ => return Err(format!("Unknown action: {}", action)),
+                        _ => return Err(TascliError::Parse(format!("Unknown action: {}", action))),
                     };
 
                     let command = crate::nlp::NLPCommand {
@@ -623,14 +891,14 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                     };
 
                     engine.create_shortcut(&shortcut, &command)
-                        .map_err(|e| format!("Failed to create shortcut: {}", e))?;
+                        .map_err(|e| TascliError::Invalid(format!("Failed to create shortcut: {}", e)))?;
 
                     print_green(&format!("Created shortcut '{}' -> {} {}", shortcut, action_type, content));
                     print_yellow(&format!("Use: tascli nlp '{}'", shortcut));
                     Ok(())
                 }
                 Err(e) => {
-                    Err(format!("Failed to access personalization database: {}", e))
+                    Err(TascliError::Invalid(format!("Failed to access personalization database: {}", e)))
                 }
             }
         },
@@ -643,7 +911,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             match engine {
                 Ok(engine) => {
                     let shortcuts = engine.get_shortcuts()
-                        .map_err(|e| format!("Failed to get shortcuts: {}", e))?;
+                        .map_err(|e| TascliError::Invalid(format!("Failed to get shortcuts: {}", e)))?;
 
                     if shortcuts.is_empty() {
                         print_yellow("No shortcuts created yet.");
@@ -676,7 +944,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                     Ok(())
                 }
                 Err(e) => {
-                    Err(format!("Failed to access personalization database: {}", e))
+                    Err(TascliError::Invalid(format!("Failed to access personalization database: {}", e)))
                 }
             }
         },
@@ -686,7 +954,7 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
             let user_id = get_user_id();
 
             let db = crate::nlp::PersonalizationDB::new(&personalization_db_path, user_id)
-                .map_err(|e| format!("Failed to access database: {}", e))?;
+                .map_err(|e| TascliError::Invalid(format!("Failed to access database: {}", e)))?;
 
             // Delete the shortcut using direct SQL
             match db.conn.execute(
@@ -702,10 +970,47 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
                         Ok(())
                     }
                 }
-                Err(e) => Err(format!("Failed to delete shortcut: {}", e)),
+                Err(e) => Err(TascliError::Invalid(format!("Failed to delete shortcut: {}", e))),
             }
         },
 
+        NLPConfigCommand::ListClarifications => {
+            let clarifications = personalization_engine()?.clarifications()
+                .map_err(|e| TascliError::Invalid(format!("Failed to get clarifications: {}", e)))?;
+            if clarifications.is_empty() {
+                print_yellow("No clarifications remembered yet.");
+                println!("Remember a phrase with: tascli nlp 'by gym I mean category health'");
+                return Ok(());
+            }
+            for (phrase, category) in clarifications {
+                println!("  '{}' -> category {}", phrase, category);
+            }
+            Ok(())
+        },
+
+        NLPConfigCommand::ForgetClarification { phrase } => {
+            let forgotten = personalization_engine()?.forget_clarification(phrase).map_err(|e| {
+                TascliError::Invalid(format!("Failed to forget clarification: {}", e))
+            })?;
+            if forgotten {
+                print_green(&format!("Forgot what '{}' means.", phrase));
+            } else {
+                print_yellow(&format!("No clarification for '{}'.", phrase));
+            }
+            Ok(())
+        },
+
+        NLPConfigCommand::Automate { name, automation } => {
+            let nlp_config = config::get_nlp_config()?;
+            let rt = tokio::runtime::Runtime::new()
+                .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
+            rt.block_on(handle_automate(conn, name, automation, &nlp_config))
+        },
+
+        NLPConfigCommand::ListAutomations => handle_list_automations(conn),
+
+        NLPConfigCommand::DeleteAutomation { name } => handle_delete_automation(conn, name),
+
         NLPConfigCommand::EnableTransparency => {
             let mut nlp_config = config::get_nlp_config()
                 .unwrap_or_default();
@@ -725,34 +1030,37 @@ fn handle_nlp_config(config_cmd: &NLPConfigCommand) -> Result<(), String> {
         },
 
         NLPConfigCommand::Help { topic } => {
-            handle_nlp_help(topic.as_deref())
+            help::show_topic(topic.as_deref())
         },
 
         NLPConfigCommand::Interactive { no_transparency, no_context } => {
-            handle_nlp_interactive(*no_transparency, *no_context)
+            handle_nlp_interactive(auto_completer(conn), *no_transparency, *no_context)
         },
     }
 }
 
 /// Handle NLP interactive mode
-fn handle_nlp_interactive(no_transparency: bool, no_context: bool) -> Result<(), String> {
+fn handle_nlp_interactive(
+    completer: AutoCompleter,
+    no_transparency: bool,
+    no_context: bool,
+) -> Result<(), TascliError> {
     use std::sync::Arc;
     use tokio::sync::Mutex;
 
     let rt = tokio::runtime::Runtime::new()
-        .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+        .map_err(|e| TascliError::io("Failed to create async runtime", e))?;
 
     rt.block_on(async {
         // Get NLP configuration
-        let nlp_config = config::get_nlp_config()
-            .map_err(|e| format!("Failed to get NLP config: {}", e))?;
+        let nlp_config = config::get_nlp_config()?;
 
         if !nlp_config.enabled {
-            return Err("NLP is disabled. Use 'tascli nlp config enable' to enable it.".to_string());
+            return Err(TascliError::Config("NLP is disabled. Use 'tascli nlp config enable' to enable it.".to_string()));
         }
 
-        if nlp_config.api_key.is_none() {
-            return Err("OpenAI API key not configured. Use 'tascli nlp config set-key <api_key>' to set it.".to_string());
+        if !nlp_config.has_provider() {
+            return Err(TascliError::Config("OpenAI API key not configured. Use 'tascli nlp config set-key <api_key>' to set it.".to_string()));
         }
 
         // Create parser
@@ -775,52 +1083,19 @@ fn handle_nlp_interactive(no_transparency: bool, no_context: bool) -> Result<(),
         let mut interactive_mode = crate::nlp::create_interactive_mode(
             parser,
             Some(interactive_config),
-        );
+        ).with_completer(completer);
 
-        interactive_mode.run().await
-            .map_err(|e| e.to_string())
+        interactive_mode.run().await?;
+        Ok(())
     })
 }
 
-/// Handle NLP help command
-fn handle_nlp_help(topic: Option<&str>) -> Result<(), String> {
-    match topic {
-        None => {
-            // No topic specified, show overview and list topics
-            HelpSystem::show_overview();
-            println!();
-            HelpSystem::list_topics();
-            Ok(())
-        },
-        Some(topic_str) => {
-            match HelpTopic::from_str(topic_str) {
-                Some(help_topic) => {
-                    HelpSystem::show_help(help_topic);
-                    Ok(())
-                },
-                None => {
-                    // Topic not found, suggest similar topics
-                    print_yellow(&format!("Unknown help topic: '{}'", topic_str));
-                    println!();
-                    let suggestions = HelpSystem::suggest_topic(topic_str);
-                    if !suggestions.is_empty() {
-                        print_yellow("Did you mean one of these?");
-                        for suggestion in suggestions.iter().take(5) {
-                            println!("  tascli nlp help {}", suggestion);
-                        }
-                    }
-                    println!();
-                    HelpSystem::list_topics();
-                    Err(format!("Unknown help topic: '{}'", topic_str))
-                }
-            }
-        }
-    }
-}
-
-fn execute_parsed_command(conn: &Connection, args: &[String]) -> Result<(), String> {
+pub(super) fn execute_parsed_command(
+    conn: &Connection,
+    args: &[String],
+) -> Result<(), TascliError> {
     if args.is_empty() {
-        return Err("No command to execute".to_string());
+        return Err(TascliError::Invalid("No command to execute".to_string()));
     }
 
     // Parse and execute the command using the existing CLI infrastructure
@@ -838,7 +1113,7 @@ fn execute_parsed_command(conn: &Connection, args: &[String]) -> Result<(), Stri
 
     // Parse the command
     let parsed_args = CliArgs::try_parse_from(cmd_args)
-        .map_err(|e| format!("Failed to parse generated command: {}", e))?;
+        .map_err(|e| TascliError::Parse(format!("Failed to parse generated command: {}", e)))?;
 
     // Execute using existing handler
     super::handler::handle_commands(conn, parsed_args)
@@ -852,8 +1127,71 @@ mod tests {
     fn test_nlp_config_enable() {
         // This would require setting up a test config
         // For now, just test that the function doesn't panic
-        let result = handle_nlp_config(&NLPConfigCommand::Enable);
+        let (conn, _temp_file) = crate::tests::get_test_conn();
+        let result = handle_nlp_config(&conn, &NLPConfigCommand::Enable);
         // In a real test, we'd mock the config system
         println!("Result: {:?}", result);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rolled_back_compound_emits_nothing() {
+        let (conn, _temp_file) = crate::tests::get_test_conn();
+        let commands: Vec<crate::nlp::NLPCommand> = ["water plants", "call mom"]
+            .iter()
+            .map(|content| crate::nlp::NLPCommand {
+                action: ActionType::Task,
+                content: content.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let executor = SequentialExecutor::new(false, false);
+        let mode = CompoundExecutionMode::ContinueOnError;
+        let run = |cancel: bool| {
+            events::hold(|| {
+                atomically(&conn, || {
+                    let summary = executor
+                        .execute_compound(&conn, &commands, &mode, false)
+                        .map_err(TascliError::Invalid)?;
+                    assert!(summary.is_complete_success(), "{:?}", summary.results);
+                    // As when Ctrl-C stops the run after its commands
+                    if cancel {
+                        return Err(TascliError::from(NLPError::Cancelled));
+                    }
+                    Ok(summary)
+                })
+            })
+        };
+        let count = || -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0)).unwrap()
+        };
+
+        let (result, held) = run(true);
+        assert!(result.is_err());
+        assert!(held.is_empty());
+        assert_eq!(count(), 0);
+
+        let (result, held) = run(false);
+        assert!(result.is_ok());
+        assert_eq!(held.len(), 2);
+        assert_eq!(count(), 2);
+    }
+
+    #[test]
+    fn test_split_clauses() {
+        assert_eq!(
+            split_clauses("buy milk; call mom tomorrow\nlog 5km run;;"),
+            ["buy milk", "call mom tomorrow", "log 5km run"]
+        );
+        assert!(split_clauses(" ; ").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_parse_clauses() {
+        // Matched by patterns, no request leaves the machine
+        let parser = Arc::new(NLPParser::new(crate::nlp::NLPConfig::default()));
+        let (commands, _) =
+            parse_clauses(&parser, "add task buy milk; add task call mom").await.unwrap();
+        let contents: Vec<&str> = commands.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, ["buy milk", "call mom"]);
+    }
+}
@@ -0,0 +1,305 @@
+//! Natural language commands saved with `tascli nlp config automate` and run on a schedule
+//! by `tascli notify reports`, such as "every weekday at 9: if inbox has tasks then notify me".
+
+use std::sync::LazyLock;
+
+use chrono::{
+    DateTime,
+    Local,
+};
+use regex::Regex;
+use rusqlite::Connection;
+
+use super::reports::is_due;
+use crate::{
+    actions::{
+        display,
+        nlp::{
+            create_parser,
+            execute_parsed_command,
+        },
+    },
+    args::timestr::parse_recurring_timestr,
+    clock,
+    config::{
+        self,
+        Config,
+    },
+    db::{
+        automations::{
+            delete_automation,
+            get_automations,
+            save_automation,
+            Automation,
+        },
+        crud::query_categories,
+        state::set_state,
+    },
+    error::TascliError,
+    integrations::chat,
+    nlp::{
+        schema::{
+            decode_command,
+            encode_command,
+        },
+        ConditionalExecutor,
+        NLPConfig,
+        SimulatedCommand,
+    },
+};
+
+// "then notify me" at the end, or only "notify me", the condition is what gets parsed
+static NOTIFY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(.+?),?\s+then\s+)?(?:notify|alert|ping|tell)\s+me\.?$").unwrap()
+});
+
+/// Saves an automation from "<schedule>: <command>", replacing one with the same name.
+pub async fn handle_automate(
+    conn: &Connection,
+    name: &str,
+    text: &str,
+    nlp_config: &NLPConfig,
+) -> Result<(), TascliError> {
+    let Some((schedule, input)) = text.split_once(": ") else {
+        return Err(TascliError::Parse(
+            "Expected a schedule and a command, as in \
+             \"every weekday at 9: if inbox has tasks then notify me\""
+                .to_string(),
+        ));
+    };
+    let schedule = schedule_timestr(schedule);
+    parse_recurring_timestr(&schedule)
+        .map_err(|e| TascliError::Parse(format!("Schedule '{}': {}", schedule, e)))?;
+
+    let input = input.trim();
+    let (to_parse, notify) = match NOTIFY_RE.captures(input) {
+        // The condition needs a command to parse, the list never runs
+        Some(caps) => (caps.get(1).map(|c| format!("{} then list", c.as_str())), true),
+        None => (Some(input.to_string()), false),
+    };
+    let command = match to_parse {
+        Some(to_parse) => {
+            let categories = query_categories(conn).unwrap_or_default();
+            create_parser(conn, nlp_config, categories).await.parse(&to_parse).await?
+        }
+        None => crate::nlp::NLPCommand::default(),
+    };
+
+    let automation = Automation {
+        name: name.to_string(),
+        schedule,
+        input: input.to_string(),
+        command: encode_command(&command)?.to_string(),
+        notify,
+    };
+    save_automation(conn, &automation)?;
+    // The next occurrence is the first run, not one that passed before it was saved
+    set_state(conn, &state_key(&automation), &clock::now().timestamp().to_string())?;
+    display::print_green(&format!(
+        "Saved automation {}, runs {}: {}",
+        automation.name, automation.schedule, automation.input
+    ));
+    Ok(())
+}
+
+pub fn handle_list_automations(conn: &Connection) -> Result<(), TascliError> {
+    let automations = get_automations(conn)?;
+    if automations.is_empty() {
+        display::print_yellow("No automations saved yet.");
+        println!(
+            "Save one with: tascli nlp config automate inbox \
+             'every weekday at 9: if inbox has tasks then notify me'"
+        );
+        return Ok(());
+    }
+    for automation in automations {
+        display::print_bold(&format!("{} ({})", automation.name, automation.schedule));
+        println!("  {}", automation.input);
+    }
+    Ok(())
+}
+
+pub fn handle_delete_automation(conn: &Connection, name: &str) -> Result<(), TascliError> {
+    if delete_automation(conn, name)? {
+        display::print_green(&format!("Deleted automation {}", name));
+    } else {
+        display::print_yellow(&format!("No automation named {}", name));
+    }
+    Ok(())
+}
+
+/// Runs the automations whose schedule came, or all of them with `all`. With `dry_run` it
+/// prints what they would do.
+pub fn run_automations(
+    conn: &Connection,
+    now: DateTime<Local>,
+    all: bool,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let config = config::get_config()?;
+    let mut failures = 0;
+    for automation in get_automations(conn)? {
+        let key = state_key(&automation);
+        if !all && !is_due(conn, &key, &automation.schedule, now)? {
+            continue;
+        }
+        if let Err(e) = run_automation(conn, &config, &automation, dry_run) {
+            display::print_red(&format!("Automation {} failed: {}", automation.name, e));
+            failures += 1;
+        }
+        // Not retried, a command that ran halfway would add its tasks twice
+        if !dry_run {
+            set_state(conn, &key, &now.timestamp().to_string())?;
+        }
+    }
+
+    if failures > 0 {
+        return Err(TascliError::Invalid(format!("{} automations failed", failures)));
+    }
+    Ok(())
+}
+
+fn run_automation(
+    conn: &Connection,
+    config: &Config,
+    automation: &Automation,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let value = serde_json::from_str(&automation.command)
+        .map_err(|e| TascliError::Parse(format!("Stored command is not json: {}", e)))?;
+    let command = decode_command(value)?;
+    // Commands that run are taken to succeed, for conditions on the previous one
+    let simulated = ConditionalExecutor::new(false)
+        .simulate(conn, &command)
+        .map_err(TascliError::Invalid)?;
+
+    if automation.notify {
+        return match notification(&automation.name, &simulated) {
+            Some(text) => notify(config, &text, dry_run),
+            None => Ok(()),
+        };
+    }
+    for command in simulated.iter().filter(|c| c.runs) {
+        if dry_run {
+            println!("{}: would run tascli {}", automation.name, command.args.join(" "));
+        } else {
+            execute_parsed_command(conn, &command.args)?;
+        }
+    }
+    Ok(())
+}
+
+// None when the condition does not hold
+fn notification(name: &str, simulated: &[SimulatedCommand]) -> Option<String> {
+    let first = simulated.first()?;
+    match &first.condition {
+        Some(trace) if trace.met => Some(format!("🔔 {}: {}", name, trace.reasons.join(", "))),
+        Some(_) => None,
+        None => Some(format!("🔔 {}", name)),
+    }
+}
+
+// To the chat channels, printed when none are configured
+fn notify(config: &Config, text: &str, dry_run: bool) -> Result<(), TascliError> {
+    if config.chat.is_empty() || dry_run {
+        println!("{}", text);
+        return Ok(());
+    }
+    let mut failures = 0;
+    for channel in &config.chat {
+        if let Err(e) = chat::post_message(channel, text) {
+            display::print_red(&format!("Failed to post to {}: {}", channel.kind, e));
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        return Err(TascliError::Invalid(format!("{} chat notifications failed", failures)));
+    }
+    Ok(())
+}
+
+// Runs are remembered per automation and schedule, like reports
+fn state_key(automation: &Automation) -> String {
+    format!("automation:{}:{}", automation.name, automation.schedule)
+}
+
+// Schedules read as in "every weekday at 9", for the recurring forms such as
+// "weekly Monday-Friday 9:00"
+fn schedule_timestr(schedule: &str) -> String {
+    let lower = schedule.trim().to_lowercase();
+    let Some(rest) = lower.strip_prefix("every ") else {
+        return schedule.trim().to_string();
+    };
+    let (when, time) = match rest.split_once(" at ") {
+        Some((when, time)) => (when.trim(), time.trim()),
+        None => (rest.trim(), ""),
+    };
+    let when = match when {
+        "day" => "daily".to_string(),
+        "weekday" | "weekdays" => "weekly Monday-Friday".to_string(),
+        "weekend" => "weekly Saturday-Sunday".to_string(),
+        "month" => "monthly".to_string(),
+        "year" => "yearly".to_string(),
+        day => format!("weekly {}", day),
+    };
+    // A bare hour is on the 24 hour clock
+    let time = match time.parse::<u32>() {
+        Ok(hour) => format!("{}:00", hour),
+        Err(_) => time.to_uppercase(),
+    };
+    format!("{} {}", when, time).trim_end().to_string()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nlp::{
+            ActionType,
+            ConditionBuilder,
+            NLPCommand,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_schedule_timestr() {
+        assert_eq!(schedule_timestr("every weekday at 9"), "weekly Monday-Friday 9:00");
+        assert_eq!(schedule_timestr("every day at 7am"), "daily 7AM");
+        assert_eq!(schedule_timestr("every friday at 17:30"), "weekly friday 17:30");
+        assert_eq!(schedule_timestr("every month"), "monthly");
+        assert_eq!(schedule_timestr("daily 9AM"), "daily 9AM");
+        assert!(parse_recurring_timestr(&schedule_timestr("every weekday at 9")).is_ok());
+    }
+
+    #[test]
+    fn test_notify_re() {
+        let caps = NOTIFY_RE.captures("if inbox has tasks then notify me").unwrap();
+        assert_eq!(&caps[1], "if inbox has tasks");
+        assert!(NOTIFY_RE.captures("notify me").unwrap().get(1).is_none());
+        assert!(NOTIFY_RE.captures("remind me to call mom").is_none());
+    }
+
+    #[test]
+    fn test_notification() {
+        let (conn, _temp_file) = get_test_conn();
+        let command = NLPCommand {
+            action: ActionType::List,
+            condition: Some(ConditionBuilder::category_has_tasks("inbox")),
+            ..Default::default()
+        };
+        let simulate = || ConditionalExecutor::new(false).simulate(&conn, &command).unwrap();
+        assert_eq!(notification("inbox", &simulate()), None);
+
+        insert_task(&conn, "inbox", "sort mail", "today");
+        let text = notification("inbox", &simulate()).unwrap();
+        assert!(text.starts_with("🔔 inbox: "), "{}", text);
+        let unconditional = NLPCommand::default();
+        let simulated = ConditionalExecutor::new(false).simulate(&conn, &unconditional).unwrap();
+        assert_eq!(notification("standup", &simulated).as_deref(), Some("🔔 standup"));
+    }
+}
@@ -0,0 +1,246 @@
+#[cfg(feature = "nlp")]
+pub(super) mod automations;
+mod reports;
+
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        digest,
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::{
+        NotifyCommand,
+        NotifyOverdueCommand,
+        NotifyRemindersCommand,
+    },
+    clock,
+    config,
+    db::{
+        item::Item,
+        reminders,
+    },
+    error::TascliError,
+    integrations::chat,
+    private,
+};
+
+pub fn handle_notifycmd(conn: &Connection, cmd: NotifyCommand) -> Result<(), TascliError> {
+    match cmd {
+        NotifyCommand::Overdue(cmd) => handle_overduecmd(conn, &cmd),
+        NotifyCommand::Reminders(cmd) => handle_reminderscmd(conn, &cmd),
+        NotifyCommand::Reports(cmd) => reports::handle_reportscmd(conn, &cmd),
+    }
+}
+
+fn handle_overduecmd(conn: &Connection, cmd: &NotifyOverdueCommand) -> Result<(), TascliError> {
+    let config = config::get_config()?;
+    if config.chat.is_empty() {
+        return Err(TascliError::Config(
+            "No chat channels configured, add a \"chat\" section to config".to_string(),
+        ));
+    }
+
+    let overdue = digest::build_digest(conn, None, clock::timestamp())?.overdue;
+    let mut failures = 0;
+    for channel in &config.chat {
+        let tasks: Vec<&Item> = overdue
+            .iter()
+            .filter(|t| chat::handles_category(channel, &t.category))
+            .collect();
+        if tasks.is_empty() {
+            continue;
+        }
+
+        let text = format_overdue_alert(&tasks);
+        if cmd.dry_run {
+            display::print_bold(&format!("{} {}:", channel.kind, channel.url));
+            println!("{}", text);
+            continue;
+        }
+        match chat::post_message(channel, &text) {
+            Ok(()) => display::print_green(&format!(
+                "Posted {} overdue tasks to {}",
+                tasks.len(),
+                channel.kind
+            )),
+            Err(e) => {
+                display::print_red(&format!("Failed to post to {}: {}", channel.kind, e));
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(TascliError::Invalid(format!("{} chat notifications failed", failures)));
+    }
+    Ok(())
+}
+
+fn handle_reminderscmd(conn: &Connection, cmd: &NotifyRemindersCommand) -> Result<(), TascliError> {
+    let config = config::get_config()?;
+    let now = clock::timestamp();
+    let due = reminders::due_reminders(conn, now, OPEN_STATUS_CODES)?;
+
+    // A late run sends one reminder per task, the earlier lead times are marked sent with it
+    let mut tasks: Vec<&Item> = Vec::new();
+    for (_, task) in &due {
+        if !tasks.iter().any(|t| t.id == task.id) {
+            tasks.push(task);
+        }
+    }
+
+    let mut failures = 0;
+    if config.chat.is_empty() {
+        for task in &tasks {
+            println!("{}", format_reminder(task, now));
+        }
+    }
+    for channel in &config.chat {
+        let lines: Vec<String> = tasks
+            .iter()
+            .filter(|t| chat::handles_category(channel, &t.category))
+            .map(|t| format_reminder(t, now))
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+
+        let text = lines.join("\n");
+        if cmd.dry_run {
+            display::print_bold(&format!("{} {}:", channel.kind, channel.url));
+            println!("{}", text);
+            continue;
+        }
+        match chat::post_message(channel, &text) {
+            Ok(()) => display::print_green(&format!(
+                "Posted {} reminders to {}",
+                lines.len(),
+                channel.kind
+            )),
+            Err(e) => {
+                display::print_red(&format!("Failed to post to {}: {}", channel.kind, e));
+                failures += 1;
+            }
+        }
+    }
+
+    if cmd.dry_run {
+        return Ok(());
+    }
+    // Nothing is marked sent so the next run tries again
+    if failures > 0 {
+        return Err(TascliError::Invalid(format!("{} chat notifications failed", failures)));
+    }
+    for (reminder, _) in &due {
+        reminders::mark_sent(conn, reminder.id, now)?;
+    }
+    Ok(())
+}
+
+fn format_reminder(task: &Item, now: i64) -> String {
+    let target_time = task.target_time.unwrap_or(now);
+    let left = target_time - now;
+    let (amount, unit) = if left >= 86400 {
+        (left / 86400, "d")
+    } else if left >= 3600 {
+        (left / 3600, "h")
+    } else {
+        (left.max(60) / 60, "m")
+    };
+    let due = Local
+        .timestamp_opt(target_time, 0)
+        .single()
+        .map(|dt| format!(" ({})", dt.format("%Y/%m/%d %H:%M")))
+        .unwrap_or_default();
+    format!(
+        "⏰ [{}] {} is due in {}{}{}",
+        task.category,
+        private::conceal(&task.content).lines().next().unwrap_or_default(),
+        amount,
+        unit,
+        due
+    )
+}
+
+fn format_overdue_alert(tasks: &[&Item]) -> String {
+    let mut text = format!("⚠️ {} overdue tasks:", tasks.len());
+    for task in tasks {
+        let due = task
+            .target_time
+            .and_then(|t| Local.timestamp_opt(t, 0).single())
+            .map(|dt| format!(" (due {})", dt.format("%Y/%m/%d")))
+            .unwrap_or_default();
+        text.push_str(&format!(
+            "\n• [{}] {}{}",
+            task.category,
+            private::conceal(&task.content).lines().next().unwrap_or_default(),
+            due
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::item::TASK;
+
+    #[test]
+    fn test_format_overdue_alert() {
+        let due = Local
+            .with_ymd_and_hms(2025, 3, 14, 23, 59, 59)
+            .unwrap()
+            .timestamp();
+        let first = Item::with_target_time(
+            TASK.to_string(),
+            "work".to_string(),
+            "send invoice\nto client".to_string(),
+            Some(due),
+        );
+        let second = Item::with_target_time(
+            TASK.to_string(),
+            "home".to_string(),
+            "fix sink".to_string(),
+            Some(due),
+        );
+
+        let text = format_overdue_alert(&[&first, &second]);
+        assert_eq!(
+            text,
+            "⚠️ 2 overdue tasks:\n• [work] send invoice (due 2025/03/14)\n• [home] fix sink (due 2025/03/14)"
+        );
+    }
+
+    #[test]
+    fn test_format_reminder() {
+        let due = Local
+            .with_ymd_and_hms(2025, 3, 14, 17, 0, 0)
+            .unwrap()
+            .timestamp();
+        let task = Item::with_target_time(
+            TASK.to_string(),
+            "work".to_string(),
+            "send invoice\nto client".to_string(),
+            Some(due),
+        );
+
+        assert_eq!(
+            format_reminder(&task, due - 86400 - 60),
+            "⏰ [work] send invoice is due in 1d (2025/03/14 17:00)"
+        );
+        assert_eq!(
+            format_reminder(&task, due - 3 * 3600 + 1),
+            "⏰ [work] send invoice is due in 2h (2025/03/14 17:00)"
+        );
+        assert_eq!(
+            format_reminder(&task, due - 10),
+            "⏰ [work] send invoice is due in 1m (2025/03/14 17:00)"
+        );
+    }
+}
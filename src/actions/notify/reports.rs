@@ -0,0 +1,319 @@
+use std::{
+    thread,
+    time::Duration,
+};
+
+use chrono::{
+    DateTime,
+    Local,
+    TimeZone,
+};
+use nanoserde::SerJson;
+use rusqlite::Connection;
+
+use super::format_overdue_alert;
+use crate::{
+    actions::{
+        digest::{
+            build_digest,
+            format_digest,
+        },
+        display,
+        report::summary_between,
+    },
+    args::{
+        cron,
+        parser::NotifyReportsCommand,
+        timestr::parse_recurring_timestr,
+    },
+    clock,
+    config::{
+        self,
+        Config,
+        ReportConfig,
+    },
+    db::{
+        automations::get_automations,
+        item::Item,
+        lock,
+        state::{
+            get_state,
+            set_state,
+        },
+    },
+    error::TascliError,
+    integrations::{
+        chat,
+        email,
+        webhook,
+    },
+};
+
+// Webhooks get reports when they list this event
+const REPORT_EVENT: &str = "report";
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+#[derive(Debug, SerJson)]
+struct ReportEvent {
+    event: String,
+    timestamp: i64,
+    report: String,
+    category: String,
+    text: String,
+}
+
+pub fn handle_reportscmd(conn: &Connection, cmd: &NotifyReportsCommand) -> Result<(), TascliError> {
+    if !cmd.daemon {
+        return run_due(conn, clock::now(), cmd.all, cmd.dry_run);
+    }
+    display::print_bold("Checking the schedules every minute, Ctrl-C to stop");
+    loop {
+        // An import running meanwhile is waited for, its tasks would be reported half done
+        if let Some(holder) = lock::holder(conn)? {
+            display::print_bold(&format!("Waiting for {} in another process", holder.operation));
+            thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+            continue;
+        }
+        // A failed delivery is retried on the next check
+        if let Err(e) = run_due(conn, clock::now(), false, cmd.dry_run) {
+            display::print_red(&e.to_string());
+        }
+        thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+    }
+}
+
+// A failed report does not hold up the automations
+fn run_due(
+    conn: &Connection,
+    now: DateTime<Local>,
+    all: bool,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let reports = send_reports(conn, now, all, dry_run);
+    run_automations(conn, now, all, dry_run)?;
+    reports
+}
+
+// Automations are saved with `tascli nlp config automate`
+#[cfg(feature = "nlp")]
+fn run_automations(
+    conn: &Connection,
+    now: DateTime<Local>,
+    all: bool,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    super::automations::run_automations(conn, now, all, dry_run)
+}
+
+#[cfg(not(feature = "nlp"))]
+fn run_automations(
+    _conn: &Connection,
+    _now: DateTime<Local>,
+    _all: bool,
+    _dry_run: bool,
+) -> Result<(), TascliError> {
+    Ok(())
+}
+
+fn send_reports(
+    conn: &Connection,
+    now: DateTime<Local>,
+    all: bool,
+    dry_run: bool,
+) -> Result<(), TascliError> {
+    let config = config::get_config()?;
+    if config.reports.is_empty() {
+        // Running only automations is fine
+        if !get_automations(conn)?.is_empty() {
+            return Ok(());
+        }
+        return Err(TascliError::Config(
+            "No reports configured, add a \"reports\" section to config".to_string(),
+        ));
+    }
+
+    let mut failures = 0;
+    for report in &config.reports {
+        let key = state_key(report);
+        if !all && !is_due(conn, &key, &report.schedule, now)? {
+            continue;
+        }
+        let Some(text) = build_report(conn, report, now)? else {
+            if !dry_run {
+                set_state(conn, &key, &now.timestamp().to_string())?;
+            }
+            continue;
+        };
+        if dry_run {
+            display::print_bold(&format!("{} ({}):", report.report, report.schedule));
+            println!("{}", text);
+            continue;
+        }
+        match deliver(&config, report, &text, now) {
+            0 => set_state(conn, &key, &now.timestamp().to_string())?,
+            failed => failures += failed,
+        }
+    }
+
+    if failures > 0 {
+        return Err(TascliError::Invalid(format!("{} report deliveries failed", failures)));
+    }
+    Ok(())
+}
+
+// Runs are remembered per report, so editing its schedule or category starts it afresh
+fn state_key(report: &ReportConfig) -> String {
+    format!("report:{}:{}:{}", report.report, report.schedule, report.category)
+}
+
+// Due when the schedule had an occurrence since the last run. The first check only starts the
+// clock, a report added at 8AM with a 7AM schedule waits for the next morning.
+pub(super) fn is_due(
+    conn: &Connection,
+    key: &str,
+    schedule: &str,
+    now: DateTime<Local>,
+) -> Result<bool, TascliError> {
+    let cron_schedule = parse_recurring_timestr(schedule)
+        .map_err(|e| TascliError::Config(format!("Schedule '{}': {}", schedule, e)))?;
+    let last_run = get_state(conn, key)?
+        .and_then(|t| t.parse::<i64>().ok())
+        .and_then(|t| Local.timestamp_opt(t, 0).single());
+    let Some(last_run) = last_run else {
+        set_state(conn, key, &now.timestamp().to_string())?;
+        return Ok(false);
+    };
+    let occurrences = cron::get_occurrences_between(&cron_schedule, last_run, now.timestamp())
+        .map_err(TascliError::Parse)?;
+    Ok(!occurrences.is_empty())
+}
+
+// None when there is nothing to report, as with no overdue tasks
+fn build_report(
+    conn: &Connection,
+    report: &ReportConfig,
+    now: DateTime<Local>,
+) -> Result<Option<String>, TascliError> {
+    let category = Some(report.category.as_str()).filter(|c| !c.is_empty());
+    match report.report.as_str() {
+        "digest" => {
+            let digest = build_digest(conn, category, now.timestamp())?;
+            Ok(Some(format_digest(&digest, now)))
+        }
+        "overdue" => {
+            let overdue = build_digest(conn, category, now.timestamp())?.overdue;
+            let tasks: Vec<&Item> = overdue.iter().collect();
+            Ok((!tasks.is_empty()).then(|| format_overdue_alert(&tasks)))
+        }
+        "weekly" => {
+            let end = now.timestamp();
+            let title = format!("Summary of the week to {}", now.format("%A %Y/%m/%d"));
+            Ok(Some(summary_between(conn, end - 7 * 86400, end + 1, &title, category)?))
+        }
+        other => Err(TascliError::Config(format!(
+            "Unknown report '{}', expected digest, overdue or weekly",
+            other
+        ))),
+    }
+}
+
+// Sends the report to its channels, returning how many failed
+fn deliver(config: &Config, report: &ReportConfig, text: &str, now: DateTime<Local>) -> usize {
+    let name = &report.report;
+    let mut failures = 0;
+    let mut fail = |target: &str, e: String| {
+        display::print_red(&format!("Failed to send the {} report to {}: {}", name, target, e));
+        failures += 1;
+    };
+
+    if !report.email.is_empty() {
+        let subject = text.lines().next().unwrap_or(name);
+        match email::send_email(&config.smtp, &report.email, subject, text) {
+            Ok(()) => {
+                display::print_green(&format!("Sent the {} report to {}", name, report.email))
+            }
+            Err(e) => fail(&report.email, e),
+        }
+    }
+    if report.chat {
+        let channels = config
+            .chat
+            .iter()
+            .filter(|c| report.category.is_empty() || chat::handles_category(c, &report.category));
+        for channel in channels {
+            match chat::post_message(channel, text) {
+                Ok(()) => {
+                    display::print_green(&format!("Posted the {} report to {}", name, channel.kind))
+                }
+                Err(e) => fail(&channel.kind, e),
+            }
+        }
+    }
+    if report.webhook {
+        let body = ReportEvent {
+            event: REPORT_EVENT.to_string(),
+            timestamp: now.timestamp(),
+            report: report.report.clone(),
+            category: report.category.clone(),
+            text: text.to_string(),
+        }
+        .serialize_json();
+        // Only hooks asking for reports, the others expect item events
+        let hooks = config
+            .webhooks
+            .iter()
+            .filter(|h| h.events.iter().any(|e| e.eq_ignore_ascii_case(REPORT_EVENT)));
+        for hook in hooks {
+            if let Err(e) = webhook::send_webhook(hook, &body) {
+                fail(&hook.url, e);
+            }
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+    };
+
+    #[test]
+    fn test_is_due() {
+        let (conn, _temp_file) = get_test_conn();
+        let morning = Local.with_ymd_and_hms(2026, 10, 12, 8, 0, 0).unwrap();
+        let key = "report:digest:daily 7AM:";
+        // The first check only records the time
+        assert!(!is_due(&conn, key, "daily 7AM", morning).unwrap());
+        assert!(!is_due(&conn, key, "daily 7AM", morning + Duration::hours(12)).unwrap());
+        assert!(is_due(&conn, key, "daily 7AM", morning + Duration::hours(23)).unwrap());
+        set_state(&conn, key, &(morning + Duration::hours(23)).timestamp().to_string()).unwrap();
+        assert!(!is_due(&conn, key, "daily 7AM", morning + Duration::hours(24)).unwrap());
+        assert!(is_due(&conn, key, "whenever", morning).is_err());
+    }
+
+    #[test]
+    fn test_build_report() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now();
+        let report = |name: &str| ReportConfig {
+            report: name.to_string(),
+            schedule: "daily 7AM".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(build_report(&conn, &report("overdue"), now).unwrap(), None);
+        insert_task(&conn, "work", "overdue report", "yesterday");
+
+        let overdue = build_report(&conn, &report("overdue"), now).unwrap().unwrap();
+        assert!(overdue.contains("[work] overdue report"), "{}", overdue);
+        let digest = build_report(&conn, &report("digest"), now).unwrap().unwrap();
+        assert!(digest.contains("Overdue (1):"), "{}", digest);
+        let weekly = build_report(&conn, &report("weekly"), now).unwrap().unwrap();
+        assert!(weekly.starts_with("Summary of the week to "), "{}", weekly);
+        assert!(build_report(&conn, &report("quarterly"), now).is_err());
+    }
+}
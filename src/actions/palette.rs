@@ -0,0 +1,192 @@
+use std::io::{
+    self,
+    BufRead,
+};
+
+use clap::{
+    Arg,
+    ArgAction,
+    Command,
+    CommandFactory,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        handler::handle_commands,
+        picker::choose,
+        triage::prompt,
+        view::{
+            join_args,
+            parse_args,
+        },
+    },
+    args::parser::CliArgs,
+    db::views::get_views,
+    error::TascliError,
+};
+
+// A command or saved view offered by the palette
+#[derive(Debug)]
+struct Entry {
+    // The command line it starts, such as ["list", "task"]
+    args: Vec<String>,
+    about: String,
+    // Arguments asked for before it runs
+    required: Vec<Required>,
+}
+
+#[derive(Debug)]
+struct Required {
+    name: String,
+    help: String,
+    // The flag before the answer, None for a positional argument
+    flag: Option<String>,
+    // Several values, one per word of the answer
+    multiple: bool,
+}
+
+pub fn handle_palettecmd(conn: &Connection) -> Result<(), TascliError> {
+    // The input is released before the command runs, which may ask questions of its own
+    let Some(args) = ask_command_line(conn, &mut io::stdin().lock())? else {
+        return Ok(());
+    };
+    display::print_bold(&format!("tascli {}", join_args(&args)));
+    handle_commands(conn, parse_args(&args)?)
+}
+
+// The command line of the picked entry with the answers to its questions, None when cancelled
+fn ask_command_line(
+    conn: &Connection,
+    input: &mut impl BufRead,
+) -> Result<Option<Vec<String>>, TascliError> {
+    let entries = entries(conn)?;
+    let text = |entry: &Entry| format!("{} {}", entry.args.join(" "), entry.about);
+    let label = |entry: &Entry| format!("{:<24} {}", entry.args.join(" "), entry.about);
+    let Some(entry) = choose(&entries, text, label, input)? else {
+        return Ok(None);
+    };
+
+    let mut args = entry.args.clone();
+    for required in &entry.required {
+        let question = match required.help.as_str() {
+            "" => required.name.clone(),
+            help => format!("{} ({})", required.name, help),
+        };
+        let answer = loop {
+            match prompt(input, &question)? {
+                None => return Ok(None),
+                Some(answer) if answer.is_empty() => continue,
+                Some(answer) => break answer,
+            }
+        };
+        args.extend(required.flag.clone());
+        if required.multiple {
+            args.extend(answer.split_whitespace().map(String::from));
+        } else {
+            args.push(answer);
+        }
+    }
+    let more = prompt(input, "More arguments, Enter for none")?.unwrap_or_default();
+    args.extend(more.split_whitespace().map(String::from));
+    Ok(Some(args))
+}
+
+// Every command, then the saved views
+fn entries(conn: &Connection) -> Result<Vec<Entry>, TascliError> {
+    let mut entries = Vec::new();
+    collect_commands(&CliArgs::command(), &mut Vec::new(), &mut entries);
+    for view in get_views(conn)? {
+        entries.push(Entry {
+            args: vec!["view".to_string(), view.name],
+            about: join_args(&view.args),
+            required: Vec::new(),
+        });
+    }
+    Ok(entries)
+}
+
+// The commands without subcommands of their own, such as "list task"
+fn collect_commands(command: &Command, path: &mut Vec<String>, entries: &mut Vec<Entry>) {
+    let subcommands = command
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set() && !matches!(c.get_name(), "help" | "palette"));
+    for subcommand in subcommands {
+        path.push(subcommand.get_name().to_string());
+        if subcommand.has_subcommands() {
+            collect_commands(subcommand, path, entries);
+        } else {
+            entries.push(Entry {
+                args: path.clone(),
+                about: subcommand.get_about().map(|a| a.to_string()).unwrap_or_default(),
+                required: subcommand
+                    .get_arguments()
+                    .filter(|a| a.is_required_set() && !a.is_global_set())
+                    .map(required_arg)
+                    .collect(),
+            });
+        }
+        path.pop();
+    }
+}
+
+fn required_arg(arg: &Arg) -> Required {
+    Required {
+        name: arg.get_id().to_string(),
+        help: arg.get_help().map(|h| h.to_string()).unwrap_or_default(),
+        flag: arg
+            .get_long()
+            .map(|long| format!("--{}", long))
+            .or_else(|| arg.get_short().map(|short| format!("-{}", short))),
+        multiple: matches!(arg.get_action(), ArgAction::Append),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        db::views::save_view,
+        tests::get_test_conn,
+    };
+
+    fn answer(conn: &Connection, lines: &str) -> Option<Vec<String>> {
+        ask_command_line(conn, &mut Cursor::new(lines.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_entries() {
+        let (conn, _temp_file) = get_test_conn();
+        save_view(&conn, "inbox", &["list".to_string(), "task".to_string()]).unwrap();
+        let entries = entries(&conn).unwrap();
+        let find = |args: &[&str]| entries.iter().find(|e| e.args == args);
+
+        let task = find(&["task"]).unwrap();
+        assert_eq!(task.about, "add task");
+        let required: Vec<&str> = task.required.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(required, vec!["content"]);
+        assert!(find(&["list", "task"]).is_some());
+        // Only the commands themselves, not the groups they belong to
+        assert!(find(&["list"]).is_none());
+        assert!(find(&["palette"]).is_none());
+        assert_eq!(find(&["view", "inbox"]).unwrap().about, "list task");
+    }
+
+    #[test]
+    fn test_ask_command_line() {
+        let (conn, _temp_file) = get_test_conn();
+        let args = answer(&conn, "add task\n1\nwater the plants\n-c home friday\n").unwrap();
+        assert_eq!(args, vec!["task", "water the plants", "-c", "home", "friday"]);
+        assert!(parse_args(&args).is_ok());
+
+        // An empty answer to a required argument asks again
+        let args = answer(&conn, "view save\n1\nweekly\n\nlist task -c work\n\n").unwrap();
+        assert_eq!(args, vec!["view", "save", "weekly", "list", "task", "-c", "work"]);
+
+        assert_eq!(answer(&conn, "\n"), None);
+        assert_eq!(answer(&conn, "add task\n1\n"), None);
+    }
+}
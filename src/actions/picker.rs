@@ -0,0 +1,216 @@
+use std::io::BufRead;
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            self,
+            DisplayRow,
+        },
+        list::{
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+        triage::prompt,
+    },
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+// Tasks shown at a time, typing more of the name narrows them down
+const SHOWN: usize = 10;
+
+// Scores of a fuzzy match, consecutive letters and the starts of words count more
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 16;
+const WORD_START_BONUS: i64 = 8;
+const GAP_PENALTY: i64 = 1;
+
+/// Row id of an open task picked from a list the user filters by typing, for commands given
+/// no item.
+pub(crate) fn pick_task(conn: &Connection, input: &mut impl BufRead) -> Result<i64, TascliError> {
+    let query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_order_by(TARGET_TIME_COL);
+    let tasks = query_items(conn, &query)?;
+    if tasks.is_empty() {
+        return Err(TascliError::Invalid("No open tasks to pick from".to_string()));
+    }
+    match choose(&tasks, task_text, format_task, input)? {
+        Some(task) => Ok(task.id.unwrap()),
+        None => Err(TascliError::Invalid("No task picked".to_string())),
+    }
+}
+
+/// One of `choices` picked by the user, None when they cancel with an empty line. Each line
+/// typed filters the choices by their `text`, a number picks one of the shown ones.
+pub(crate) fn choose<'a, T>(
+    choices: &'a [T],
+    text: impl Fn(&T) -> String,
+    label: impl Fn(&T) -> String,
+    input: &mut impl BufRead,
+) -> Result<Option<&'a T>, TascliError> {
+    let mut matches: Vec<&T> = choices.iter().collect();
+    loop {
+        let shown = &matches[..matches.len().min(SHOWN)];
+        for (number, choice) in shown.iter().enumerate() {
+            println!("{:>3}  {}", number + 1, label(choice));
+        }
+        if matches.len() > shown.len() {
+            println!("     ...");
+        }
+        let answer = prompt(input, "Type to filter, a number to pick")?.unwrap_or_default();
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(number) = answer.parse::<usize>()
+            && let Some(choice) = number.checked_sub(1).and_then(|i| shown.get(i))
+        {
+            return Ok(Some(choice));
+        }
+        let filtered = filter(choices, &text, &answer);
+        if filtered.is_empty() {
+            display::print_yellow(&format!("Nothing matches '{}'", answer));
+            continue;
+        }
+        matches = filtered;
+    }
+}
+
+fn task_text(task: &Item) -> String {
+    format!("{} {}", task.category, private::reveal(&task.content))
+}
+
+fn format_task(task: &Item) -> String {
+    let row = DisplayRow::from_task(String::new(), task);
+    format!("[{}] {}  {}", task.category, row.content, row.timestr)
+}
+
+// Choices matching every word of the query, best match first
+fn filter<'a, T>(choices: &'a [T], text: impl Fn(&T) -> String, query: &str) -> Vec<&'a T> {
+    let mut scored: Vec<(i64, &T)> = choices
+        .iter()
+        .filter_map(|choice| {
+            let text = text(choice);
+            query
+                .split_whitespace()
+                .map(|word| fuzzy_score(word, &text))
+                .sum::<Option<i64>>()
+                .map(|score| (score, choice))
+        })
+        .collect();
+    // Stable, so equal scores keep the order they came in
+    scored.sort_by_key(|(score, _)| -score);
+    scored.into_iter().map(|(_, choice)| choice).collect()
+}
+
+// Score of the letters of `pattern` found in order in `text` ignoring case, None when some
+// are missing. The best of the matches starting at each occurrence of the first letter.
+fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let first = *pattern.first()?;
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| score_from(&pattern, &text, start))
+        .max()
+}
+
+// Each letter after the first takes its next occurrence
+fn score_from(pattern: &[char], text: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut next = start;
+    for &c in pattern {
+        let found = next + text[next..].iter().position(|&t| t == c)?;
+        score += MATCH_SCORE;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        match previous {
+            Some(p) if p + 1 == found => score += CONSECUTIVE_BONUS,
+            Some(p) => score -= GAP_PENALTY * (found - p - 1) as i64,
+            None => {}
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+        update_status,
+    };
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("rpt", "work write report").is_some());
+        assert_eq!(fuzzy_score("tpr", "work report"), None);
+        assert_eq!(fuzzy_score("x", ""), None);
+        // Consecutive letters at the start of a word beat scattered ones
+        let start = fuzzy_score("rep", "work report").unwrap();
+        let scattered = fuzzy_score("rep", "work rather cheap").unwrap();
+        assert!(start > scattered, "{} {}", start, scattered);
+        assert_eq!(fuzzy_score("REP", "work report"), Some(start));
+    }
+
+    #[test]
+    fn test_filter() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "home", "pay rent", "today");
+        insert_task(&conn, "work", "prepare report", "tomorrow");
+        insert_task(&conn, "work", "reply to mail", "today");
+        let query = ItemQuery::new().with_action(TASK).with_order_by(TARGET_TIME_COL);
+        let tasks = query_items(&conn, &query).unwrap();
+
+        let contents = |query: &str| -> Vec<String> {
+            filter(&tasks, task_text, query).iter().map(|t| t.content.clone()).collect()
+        };
+        assert_eq!(contents("report"), vec!["prepare report"]);
+        assert_eq!(contents("work re"), vec!["reply to mail", "prepare report"]);
+        assert!(contents("holiday").is_empty());
+    }
+
+    #[test]
+    fn test_pick_task() {
+        let (conn, _temp_file) = get_test_conn();
+        let rent = insert_task(&conn, "home", "pay rent", "today");
+        let report = insert_task(&conn, "work", "prepare report", "tomorrow");
+        let done = insert_task(&conn, "work", "report on q3", "today");
+        update_status(&conn, done, 1);
+
+        // Open tasks by deadline
+        assert_eq!(pick_task(&conn, &mut Cursor::new("1\n")).unwrap(), rent);
+        // A filter without a match keeps the tasks shown
+        let mut input = Cursor::new("zzz\nreport\n1\n");
+        assert_eq!(pick_task(&conn, &mut input).unwrap(), report);
+        assert!(pick_task(&conn, &mut Cursor::new("\n")).is_err());
+        assert!(pick_task(&conn, &mut Cursor::new("")).is_err());
+    }
+
+    #[test]
+    fn test_pick_task_without_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let err = pick_task(&conn, &mut Cursor::new("1\n")).unwrap_err();
+        assert!(err.to_string().contains("No open tasks"), "{}", err);
+    }
+}
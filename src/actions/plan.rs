@@ -0,0 +1,311 @@
+use std::io::{
+    self,
+    BufRead,
+};
+
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        forecast::count_per_day,
+        inbox::{
+            file_as_task,
+            inbox_query,
+        },
+        list::{
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+        triage::{
+            prompt,
+            reschedule,
+        },
+    },
+    args::{
+        parser::{
+            PlanCommand,
+            PlanWeekCommand,
+        },
+        timestr,
+    },
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            INBOX,
+            TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+const WEEK_DAYS: usize = 7;
+// Width of the unplanned column, the week is printed to its right
+const LEFT_WIDTH: usize = 48;
+
+// An overdue task or inbox item, with the index of the day it was planned for
+struct Entry {
+    item: Item,
+    planned: Option<usize>,
+}
+
+// A day of the week being planned, with the number of tasks due on it
+struct Day {
+    date: NaiveDate,
+    due: usize,
+}
+
+pub fn handle_plancmd(conn: &Connection, cmd: &PlanCommand) -> Result<(), TascliError> {
+    match cmd {
+        PlanCommand::Week(cmd) => handle_weekcmd(conn, cmd),
+    }
+}
+
+fn handle_weekcmd(conn: &Connection, cmd: &PlanWeekCommand) -> Result<(), TascliError> {
+    let now = clock::now();
+    let items = unplanned(conn, cmd.category.as_deref(), now.timestamp())?;
+    if items.is_empty() {
+        display::print_bold("Nothing to plan, no overdue tasks or inbox items");
+        return Ok(());
+    }
+
+    let mut entries: Vec<Entry> =
+        items.into_iter().map(|item| Entry { item, planned: None }).collect();
+    let mut days: Vec<Day> = count_per_day(conn, cmd.category.as_deref(), now, WEEK_DAYS)?
+        .into_iter()
+        .map(|(date, due)| Day { date, due })
+        .collect();
+    let planned = plan(conn, &mut entries, &mut days, &mut io::stdin().lock())?;
+    display::print_bold(&format!("Planned {} of {}", planned, entries.len()));
+    Ok(())
+}
+
+// Overdue open tasks, oldest first, then inbox items, which have no category yet.
+pub(crate) fn unplanned(
+    conn: &Connection,
+    category: Option<&str>,
+    now: i64,
+) -> Result<Vec<Item>, TascliError> {
+    let mut query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(now)
+        .with_order_by(TARGET_TIME_COL);
+    if let Some(c) = category {
+        query = query.with_category(c);
+    }
+    let mut items = query_items(conn, &query)?;
+    if category.is_none() {
+        items.extend(query_items(conn, &inbox_query())?);
+    }
+    Ok(items)
+}
+
+// Ends on quit, an empty answer or the end of the input, returning how many entries got a day.
+fn plan(
+    conn: &Connection,
+    entries: &mut [Entry],
+    days: &mut [Day],
+    input: &mut impl BufRead,
+) -> Result<usize, TascliError> {
+    loop {
+        print_board(entries, days);
+        let question = "Numbers and a day such as 1 3 fr, (q)uit";
+        let answer = match prompt(input, question)? {
+            Some(answer) if answer.is_empty() || answer.eq_ignore_ascii_case("q") => break,
+            Some(answer) => answer,
+            None => break,
+        };
+        let (numbers, day) = match parse_assignment(&answer, entries.len(), days) {
+            Ok(assignment) => assignment,
+            Err(e) => {
+                display::print_yellow(&e);
+                continue;
+            }
+        };
+        let timestr = days[day].date.format("%Y/%m/%d").to_string();
+        for number in numbers {
+            let entry = &mut entries[number - 1];
+            if let Err(e) = assign(conn, &mut entry.item, &timestr) {
+                display::print_red(&e.to_string());
+                continue;
+            }
+            if let Some(previous) = entry.planned.replace(day) {
+                days[previous].due -= 1;
+            }
+            days[day].due += 1;
+        }
+    }
+    Ok(entries.iter().filter(|e| e.planned.is_some()).count())
+}
+
+// Inbox items are filed as tasks of their category, tasks move to the day.
+pub(crate) fn assign(conn: &Connection, item: &mut Item, timestr: &str) -> Result<(), TascliError> {
+    if item.action == INBOX {
+        file_as_task(conn, item.clone(), &item.category, timestr)?;
+        item.action = TASK.to_string();
+    } else {
+        reschedule(conn, item.clone(), timestr)?;
+    }
+    item.set_target_time(Some(timestr::to_unix_epoch(timestr)?));
+    Ok(())
+}
+
+// "1 3 fr" gives entries 1 and 3 and the index of the coming Friday.
+fn parse_assignment(
+    answer: &str,
+    count: usize,
+    days: &[Day],
+) -> Result<(Vec<usize>, usize), String> {
+    let words: Vec<&str> = answer.split_whitespace().collect();
+    let Some((day_key, numbers)) = words.split_last() else {
+        return Err("Give numbers and a day, such as 1 3 fr".to_string());
+    };
+    let day_key = day_key.to_lowercase();
+    let day = days
+        .iter()
+        .position(|d| day_key.len() >= 2 && day_name(d).starts_with(&day_key))
+        .ok_or_else(|| format!("'{}' is not a day of the week, use mo, tu, we ...", day_key))?;
+    if numbers.is_empty() {
+        return Err(format!("Give the numbers to plan for {}", day_key));
+    }
+    let numbers = numbers
+        .iter()
+        .map(|n| match n.parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n),
+            _ => Err(format!("'{}' is not a number from 1 to {}", n, count)),
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    Ok((numbers, day))
+}
+
+fn day_name(day: &Day) -> String {
+    day.date.format("%A").to_string().to_lowercase()
+}
+
+fn print_board(entries: &[Entry], days: &[Day]) {
+    let left: Vec<String> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let label = format!("{}. {}", i + 1, private::reveal(&entry.item.content));
+            let note = match entry.planned {
+                Some(day) => format!(" → {}", &day_name(&days[day])[..2]),
+                None if entry.item.action == INBOX => " (inbox)".to_string(),
+                None => String::new(),
+            };
+            format!("{}{}", truncate(&label, LEFT_WIDTH - note.chars().count()), note)
+        })
+        .collect();
+    let right: Vec<String> = days
+        .iter()
+        .map(|day| {
+            let key = &day_name(day)[..2];
+            format!("{} {}  {} due", key, day.date.format("%m/%d"), day.due)
+        })
+        .collect();
+
+    if display::is_plain() {
+        println!("Unplanned:");
+        left.iter().for_each(|line| println!("{}", line));
+        println!("This week:");
+        right.iter().for_each(|line| println!("{}", line));
+        return;
+    }
+    display::print_bold(&format!("{:<width$} | This week", "Unplanned", width = LEFT_WIDTH));
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map(String::as_str).unwrap_or_default();
+        let r = right.get(i).map(String::as_str).unwrap_or_default();
+        let padding = LEFT_WIDTH.saturating_sub(l.chars().count());
+        println!("{}{} | {}", l, " ".repeat(padding), r);
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        actions::inbox::handle_incmd,
+        args::parser::InCommand,
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    fn week() -> Vec<Day> {
+        // Monday 2025/03/17 to Sunday 2025/03/23
+        let monday = NaiveDate::from_ymd_opt(2025, 3, 17).unwrap();
+        (0..WEEK_DAYS as i64)
+            .map(|d| Day {
+                date: monday + Duration::days(d),
+                due: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        let days = week();
+        assert_eq!(parse_assignment("1 3 fr", 3, &days), Ok((vec![1, 3], 4)));
+        assert_eq!(parse_assignment("2 Sunday", 3, &days), Ok((vec![2], 6)));
+        assert!(parse_assignment("4 fr", 3, &days).is_err());
+        assert!(parse_assignment("1 f", 3, &days).is_err());
+        assert!(parse_assignment("fr", 3, &days).is_err());
+        assert!(parse_assignment("1 someday", 3, &days).is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        let (conn, _temp_file) = get_test_conn();
+        let overdue = insert_task(&conn, "work", "send invoice", "yesterday");
+        handle_incmd(&conn, &InCommand { content: vec!["call bank".to_string()] }).unwrap();
+        let mut items = vec![get_item(&conn, overdue).unwrap()];
+        items.extend(query_items(&conn, &inbox_query()).unwrap());
+        let mut entries: Vec<Entry> =
+            items.into_iter().map(|item| Entry { item, planned: None }).collect();
+        let today = clock::now().date_naive();
+        let mut days: Vec<Day> = (0..WEEK_DAYS as i64)
+            .map(|d| Day {
+                date: today + Duration::days(d),
+                due: 0,
+            })
+            .collect();
+        let tomorrow = &day_name(&days[1])[..2];
+        let in_two_days = &day_name(&days[2])[..2];
+        // both tomorrow, a typo, then the invoice moved again
+        let answers = format!("1 2 {}\n1 xx\n1 {}\n", tomorrow, in_two_days);
+
+        let planned = plan(&conn, &mut entries, &mut days, &mut Cursor::new(answers)).unwrap();
+        assert_eq!(planned, 2);
+        assert_eq!((days[1].due, days[2].due), (1, 1));
+        let deadline = |days: i64| {
+            let date = (today + Duration::days(days)).format("%Y/%m/%d").to_string();
+            Some(timestr::to_unix_epoch(&date).unwrap())
+        };
+        assert_eq!(get_item(&conn, overdue).unwrap().target_time, deadline(2));
+        let filed = get_item(&conn, 2).unwrap();
+        assert_eq!((filed.action.as_str(), filed.target_time), (TASK, deadline(1)));
+    }
+}
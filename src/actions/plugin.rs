@@ -0,0 +1,141 @@
+use std::{
+    env,
+    ffi::OsStr,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+use nanoserde::SerJson;
+
+use crate::{
+    config,
+    error::TascliError,
+};
+
+const PLUGIN_PREFIX: &str = "tascli-";
+
+/// Passed to plugins as json in TASCLI_CONTEXT
+#[derive(SerJson)]
+struct PluginContext {
+    version: String,
+    db_path: String,
+    config_path: String,
+    args: Vec<String>,
+}
+
+/// Finds the executable `tascli-<name>` in the directories of `path_var`, like git does
+/// for its external commands. Names that could escape the directory are never looked up.
+pub fn find_plugin(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    let valid_name = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return None;
+    }
+    let file_name = format!("{}{}{}", PLUGIN_PREFIX, name, env::consts::EXE_SUFFIX);
+    env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Runs the plugin with the remaining arguments, with the db path in TASCLI_DB_PATH
+/// and the json context in TASCLI_CONTEXT. On unix the plugin replaces this process.
+pub fn run_plugin(plugin: &Path, args: &[String]) -> Result<(), TascliError> {
+    let db_path = config::get_data_path()?;
+    let context = PluginContext {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        db_path: db_path.display().to_string(),
+        config_path: config::get_config_path()?.display().to_string(),
+        args: args.to_vec(),
+    };
+
+    let mut command = Command::new(plugin);
+    command
+        .args(args)
+        .env("TASCLI_DB_PATH", &db_path)
+        .env("TASCLI_CONTEXT", context.serialize_json());
+    let run_err = |e| TascliError::io(format!("Failed to run {}", plugin.display()), e);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        // exec only returns on failure
+        Err(run_err(command.exec()))
+    }
+    #[cfg(not(unix))]
+    {
+        let status = command.status().map_err(run_err)?;
+        std::process::exit(status.code().unwrap_or(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn write_plugin(dir: &Path, name: &str, executable: bool) -> PathBuf {
+        let path = dir.join(format!("{}{}{}", PLUGIN_PREFIX, name, env::consts::EXE_SUFFIX));
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = if executable { 0o755 } else { 0o644 };
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn test_find_plugin_searches_path_in_order() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let expected = write_plugin(first.path(), "sync", true);
+        write_plugin(second.path(), "sync", true);
+        let stats = write_plugin(second.path(), "stats", true);
+        let path_var = env::join_paths([first.path(), second.path()]).unwrap();
+
+        assert_eq!(find_plugin("sync", &path_var), Some(expected));
+        assert_eq!(find_plugin("stats", &path_var), Some(stats));
+        assert_eq!(find_plugin("missing", &path_var), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_find_plugin_skips_non_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        write_plugin(dir.path(), "sync", false);
+        assert_eq!(find_plugin("sync", dir.path().as_os_str()), None);
+    }
+
+    #[test]
+    fn test_find_plugin_rejects_unsafe_names() {
+        let dir = tempfile::tempdir().unwrap();
+        write_plugin(dir.path(), "sync", true);
+        for name in ["", "../sync", "sync now", "-sync", "add task to review PRs"] {
+            assert_eq!(find_plugin(name, dir.path().as_os_str()), None, "{}", name);
+        }
+    }
+}
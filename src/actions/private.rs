@@ -0,0 +1,29 @@
+use chrono::{
+    Local,
+    TimeZone,
+};
+
+use crate::{
+    actions::display,
+    args::parser::UnlockCommand,
+    error::TascliError,
+    private,
+    tr,
+};
+
+pub fn handle_unlockcmd(cmd: &UnlockCommand) -> Result<(), TascliError> {
+    let until = private::unlock(cmd.minutes)?;
+    let time = Local
+        .timestamp_opt(until, 0)
+        .single()
+        .map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_default();
+    display::print_green(&tr!("unlocked-until", time = time));
+    Ok(())
+}
+
+pub fn handle_lockcmd() -> Result<(), TascliError> {
+    private::lock()?;
+    display::print_green(&tr!("locked"));
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::{
+        parser::ProjectCommand,
+        timestr,
+    },
+    db::projects::{
+        clear_project_end,
+        get_project,
+        get_projects,
+        set_project_end,
+        Project,
+    },
+    error::TascliError,
+};
+
+pub fn handle_projectcmd(conn: &Connection, cmd: &ProjectCommand) -> Result<(), TascliError> {
+    let Some(category) = &cmd.category else {
+        let projects = get_projects(conn)?;
+        if projects.is_empty() {
+            display::print_bold("No projects with an end date");
+        }
+        for project in projects {
+            println!("{}", describe(&project));
+        }
+        return Ok(());
+    };
+
+    if cmd.clear {
+        clear_project_end(conn, category)?;
+    } else if let Some(end) = &cmd.end {
+        set_project_end(conn, category, timestr::to_unix_epoch(end)?)?;
+    }
+    match get_project(conn, category)? {
+        Some(project) => display::print_bold(&describe(&project)),
+        None => display::print_bold(&format!("{} has no end date", category)),
+    }
+    Ok(())
+}
+
+/// Checks a task deadline against the end of its category's project,
+/// warning about a later deadline or refusing it when `strict`.
+pub fn check_deadline(
+    conn: &Connection,
+    category: &str,
+    target_time: i64,
+    strict: bool,
+) -> Result<(), TascliError> {
+    let Some(project) = get_project(conn, category)? else {
+        return Ok(());
+    };
+    if target_time <= project.end_time {
+        return Ok(());
+    }
+    let message = format!(
+        "Deadline {} is after the end of project {} on {}",
+        format_date(target_time),
+        project.category,
+        format_date(project.end_time)
+    );
+    if strict {
+        return Err(TascliError::Invalid(message));
+    }
+    display::print_yellow(&message);
+    Ok(())
+}
+
+fn describe(project: &Project) -> String {
+    format!("{} ends {}", project.category, format_date(project.end_time))
+}
+
+fn format_date(time: i64) -> String {
+    Local
+        .timestamp_opt(time, 0)
+        .single()
+        .map(|dt| dt.format("%Y/%m/%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_check_deadline() {
+        let (conn, _temp_file) = get_test_conn();
+        assert!(check_deadline(&conn, "release", 5000, true).is_ok(), "no project");
+
+        set_project_end(&conn, "release", 4000).unwrap();
+        assert!(check_deadline(&conn, "release", 4000, true).is_ok());
+        assert!(check_deadline(&conn, "release", 5000, false).is_ok());
+        let err = check_deadline(&conn, "release", 5000, true).unwrap_err();
+        assert!(err.to_string().contains("after the end of project release"), "{}", err);
+    }
+}
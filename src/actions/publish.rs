@@ -0,0 +1,291 @@
+use std::path::Path;
+
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        export::html::escape_html,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::PublishCommand,
+    clock,
+    config::{
+        current_user,
+        write_atomic,
+    },
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 720px; color: #222; padding: 0 1rem; }
+.subtitle { color: #777; }
+li { margin: 0.3rem 0; }
+.meta { color: #777; }
+"#;
+
+enum Format {
+    Markdown,
+    Html,
+}
+
+// What a public status page shows of a task, owners, ids and private content are left out
+struct Entry {
+    content: String,
+    note: String,
+}
+
+struct StatusPage {
+    title: String,
+    generated: DateTime<Local>,
+    days: usize,
+    open: Vec<Entry>,
+    done: Vec<Entry>,
+}
+
+pub fn handle_publishcmd(conn: &Connection, cmd: &PublishCommand) -> Result<(), TascliError> {
+    let format = format_of(&cmd.out)?;
+    let title = match &cmd.title {
+        Some(title) => title.clone(),
+        None => format!("{} status", cmd.categories.join(", ")),
+    };
+    let page = build_page(conn, &cmd.categories, title, cmd.days, clock::now())?;
+    let contents = match format {
+        Format::Markdown => render_markdown(&page),
+        Format::Html => render_html(&page),
+    };
+    // Regenerated from cron while a web server may be reading it
+    write_atomic(Path::new(&cmd.out), contents.as_bytes())
+        .map_err(|e| TascliError::io(format!("Failed to write {}", cmd.out), e))?;
+    display::print_green(&format!(
+        "Published {} open and {} done tasks to {}",
+        page.open.len(),
+        page.done.len(),
+        cmd.out
+    ));
+    Ok(())
+}
+
+fn format_of(path: &str) -> Result<Format, TascliError> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "md" | "markdown" => Ok(Format::Markdown),
+        "html" | "htm" => Ok(Format::Html),
+        _ => Err(TascliError::Invalid(format!(
+            "Cannot tell the format of {}, use a .md or .html file",
+            path
+        ))),
+    }
+}
+
+fn build_page(
+    conn: &Connection,
+    categories: &[String],
+    title: String,
+    days: usize,
+    now: DateTime<Local>,
+) -> Result<StatusPage, TascliError> {
+    let since = (now - Duration::days(days as i64)).timestamp();
+    let mut open = Vec::new();
+    let mut done = Vec::new();
+    for category in categories {
+        let query = || {
+            ItemQuery::new()
+                .with_visible_to(current_user())
+                .with_action(TASK)
+                .with_category(category)
+        };
+        open.extend(query_items(conn, &query().with_statuses(OPEN_STATUS_CODES.to_vec()))?);
+        // Completion time is only tracked as the last modification of the task
+        done.extend(
+            query_items(conn, &query().with_statuses(vec![1]))?
+                .into_iter()
+                .filter(|t| t.modify_time.is_some_and(|m| m > since)),
+        );
+    }
+    // Encrypted content has no public form, not even as "[private]"
+    open.retain(|t| !private::is_private(&t.content));
+    done.retain(|t| !private::is_private(&t.content));
+    open.sort_by_key(|t| t.target_time);
+    done.sort_by_key(|t| std::cmp::Reverse(t.modify_time));
+
+    Ok(StatusPage {
+        title,
+        generated: now,
+        days,
+        open: open.iter().map(open_entry).collect(),
+        done: done.iter().map(done_entry).collect(),
+    })
+}
+
+fn open_entry(task: &Item) -> Entry {
+    let mut note = match task.target_time {
+        Some(t) => format!("due {}", format_date(t)),
+        None => String::new(),
+    };
+    let status = match task.status {
+        4 => "on hold",
+        6 => "pending",
+        _ => "",
+    };
+    if !status.is_empty() {
+        note = if note.is_empty() {
+            status.to_string()
+        } else {
+            format!("{}, {}", note, status)
+        };
+    }
+    Entry {
+        content: task.content.clone(),
+        note,
+    }
+}
+
+fn done_entry(task: &Item) -> Entry {
+    Entry {
+        content: task.content.clone(),
+        note: task
+            .modify_time
+            .map(|t| format!("done {}", format_date(t)))
+            .unwrap_or_default(),
+    }
+}
+
+fn format_date(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%Y/%m/%d").to_string())
+        .unwrap_or_default()
+}
+
+fn render_markdown(page: &StatusPage) -> String {
+    let mut md = format!(
+        "# {}\n\nUpdated {}\n\n",
+        page.title,
+        page.generated.format("%Y/%m/%d %H:%M")
+    );
+    for (heading, entries) in [
+        ("Open".to_string(), &page.open),
+        (format!("Done in the Last {} Days", page.days), &page.done),
+    ] {
+        md.push_str(&format!("## {}\n\n", heading));
+        if entries.is_empty() {
+            md.push_str("Nothing here.\n\n");
+            continue;
+        }
+        for entry in entries {
+            match entry.note.as_str() {
+                "" => md.push_str(&format!("- {}\n", entry.content)),
+                note => md.push_str(&format!("- {} ({})\n", entry.content, note)),
+            }
+        }
+        md.push('\n');
+    }
+    md
+}
+
+fn render_html(page: &StatusPage) -> String {
+    let title = escape_html(&page.title);
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n<style>{}</style>\n", title, STYLE));
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", title));
+    html.push_str(&format!(
+        "<p class=\"subtitle\">Updated {}</p>\n",
+        page.generated.format("%Y/%m/%d %H:%M")
+    ));
+    for (heading, entries) in [
+        ("Open".to_string(), &page.open),
+        (format!("Done in the Last {} Days", page.days), &page.done),
+    ] {
+        html.push_str(&format!("<h2>{}</h2>\n", heading));
+        if entries.is_empty() {
+            html.push_str("<p>Nothing here.</p>\n");
+            continue;
+        }
+        html.push_str("<ul>\n");
+        for entry in entries {
+            html.push_str(&format!("<li>{}", escape_html(&entry.content)));
+            if !entry.note.is_empty() {
+                let note = escape_html(&entry.note);
+                html.push_str(&format!(" <span class=\"meta\">{}</span>", note));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+        update_status,
+    };
+
+    #[test]
+    fn test_build_page() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "open source", "release 1.2", "2099/01/02");
+        let pending = insert_task(&conn, "open source", "review <PR>", "2099/01/01");
+        update_status(&conn, pending, 6);
+        let done = insert_task(&conn, "open source", "fix docs", "today");
+        update_status(&conn, done, 1);
+        insert_task(&conn, "work", "quarterly plan", "tomorrow");
+        let secret = insert_task(&conn, "open source", "secret", "tomorrow");
+        conn.execute(
+            "UPDATE items SET content = ?1 WHERE id = ?2",
+            rusqlite::params![format!("{}abc", private::PRIVATE_PREFIX), secret],
+        )
+        .unwrap();
+
+        let categories = vec!["open source".to_string()];
+        let page = build_page(&conn, &categories, "OSS".to_string(), 14, Local::now()).unwrap();
+        let open: Vec<(&str, &str)> =
+            page.open.iter().map(|e| (e.content.as_str(), e.note.as_str())).collect();
+        assert_eq!(
+            open,
+            vec![("review <PR>", "due 2099/01/01, pending"), ("release 1.2", "due 2099/01/02")]
+        );
+        assert_eq!(page.done.len(), 1);
+        assert!(page.done[0].note.starts_with("done "));
+
+        let md = render_markdown(&page);
+        assert!(md.starts_with("# OSS\n"));
+        assert!(md.contains("- release 1.2 (due 2099/01/02)\n"));
+        let html = render_html(&page);
+        assert!(html.contains("<li>review &lt;PR&gt; <span class=\"meta\">"));
+        assert!(!html.contains("secret") && !html.contains("quarterly"));
+    }
+
+    #[test]
+    fn test_format_of() {
+        assert!(matches!(format_of("status.md"), Ok(Format::Markdown)));
+        assert!(matches!(format_of("site/STATUS.HTML"), Ok(Format::Html)));
+        assert!(format_of("status.txt").is_err());
+    }
+}
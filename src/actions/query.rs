@@ -0,0 +1,222 @@
+use nanoserde::SerJson;
+use rusqlite::{
+    types::ValueRef,
+    Connection,
+};
+
+use crate::{
+    actions::display,
+    args::parser::QueryCommand,
+    error::TascliError,
+};
+
+// The column names and the cells of each row of a query
+struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Cell {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(usize),
+}
+
+impl Cell {
+    fn from_value(value: ValueRef) -> Self {
+        match value {
+            ValueRef::Null => Cell::Null,
+            ValueRef::Integer(i) => Cell::Integer(i),
+            ValueRef::Real(r) => Cell::Real(r),
+            ValueRef::Text(t) => Cell::Text(String::from_utf8_lossy(t).into_owned()),
+            ValueRef::Blob(b) => Cell::Blob(b.len()),
+        }
+    }
+
+    // One line in a table, newlines of multi-line content become spaces
+    fn to_text(&self) -> String {
+        match self {
+            Cell::Null => String::new(),
+            Cell::Integer(i) => i.to_string(),
+            Cell::Real(r) => r.to_string(),
+            Cell::Text(t) => t.split_whitespace().collect::<Vec<_>>().join(" "),
+            Cell::Blob(len) => format!("<{} bytes>", len),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        match self {
+            Cell::Null => "null".to_string(),
+            Cell::Integer(i) => i.to_string(),
+            Cell::Real(r) if r.is_finite() => r.to_string(),
+            Cell::Real(_) => "null".to_string(),
+            Cell::Text(t) => t.serialize_json(),
+            Cell::Blob(len) => format!("<{} bytes>", len).serialize_json(),
+        }
+    }
+}
+
+pub fn handle_querycmd(conn: &Connection, cmd: &QueryCommand) -> Result<(), TascliError> {
+    let result = run_query(conn, &cmd.sql)?;
+    if cmd.json {
+        println!("{}", format_json(&result));
+    } else if result.rows.is_empty() {
+        display::print_bold("No rows");
+    } else if display::is_plain() {
+        format_labeled(&result).iter().for_each(|line| println!("{}", line));
+    } else {
+        format_table(&result).iter().for_each(|line| println!("{}", line));
+    }
+    Ok(())
+}
+
+// Runs a single SELECT with the connection switched to query_only, so nothing the statement
+// does can write to the db
+fn run_query(conn: &Connection, sql: &str) -> Result<QueryResult, TascliError> {
+    let keyword = sql.split_whitespace().next().unwrap_or_default();
+    if !keyword.eq_ignore_ascii_case("select") && !keyword.eq_ignore_ascii_case("with") {
+        return Err(TascliError::Invalid("Only SELECT queries can be run".to_string()));
+    }
+    conn.pragma_update(None, "query_only", true)?;
+    let result = read_rows(conn, sql);
+    conn.pragma_update(None, "query_only", false)?;
+    result
+}
+
+fn read_rows(conn: &Connection, sql: &str) -> Result<QueryResult, TascliError> {
+    // Preparing fails for several statements separated by ';'
+    let mut stmt = conn.prepare(sql)?;
+    if !stmt.readonly() {
+        return Err(TascliError::Invalid("Only SELECT queries can be run".to_string()));
+    }
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = Vec::new();
+    let mut results = stmt.query([])?;
+    while let Some(row) = results.next()? {
+        let cells = (0..columns.len())
+            .map(|i| row.get_ref(i).map(Cell::from_value))
+            .collect::<Result<Vec<Cell>, rusqlite::Error>>()?;
+        rows.push(cells);
+    }
+    Ok(QueryResult { columns, rows })
+}
+
+fn format_table(result: &QueryResult) -> Vec<String> {
+    let text: Vec<Vec<String>> = result
+        .rows
+        .iter()
+        .map(|row| row.iter().map(Cell::to_text).collect())
+        .collect();
+    let widths: Vec<usize> = result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            text.iter()
+                .map(|row| row[i].chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let mut lines = vec![line(&result.columns), line(&separator)];
+    lines.extend(text.iter().map(|row| line(row)));
+    lines
+}
+
+// A "column: value" line per cell and a blank line after each row, for screen readers
+fn format_labeled(result: &QueryResult) -> Vec<String> {
+    let mut lines = Vec::new();
+    for row in &result.rows {
+        for (column, cell) in result.columns.iter().zip(row) {
+            lines.push(format!("{}: {}", column, cell.to_text()));
+        }
+        lines.push(String::new());
+    }
+    lines
+}
+
+fn format_json(result: &QueryResult) -> String {
+    let objects: Vec<String> = result
+        .rows
+        .iter()
+        .map(|row| {
+            let fields: Vec<String> = result
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| format!("{}:{}", column.serialize_json(), cell.to_json()))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        })
+        .collect();
+    format!("[{}]", objects.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+    };
+
+    #[test]
+    fn test_run_query() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "report", "today");
+        insert_task(&conn, "work", "call", "today");
+        insert_task(&conn, "home", "laundry", "today");
+
+        let sql = "SELECT category, COUNT(*) AS tasks, NULL AS note FROM items
+                   GROUP BY category ORDER BY category";
+        let result = run_query(&conn, sql).unwrap();
+        assert_eq!(result.columns, vec!["category", "tasks", "note"]);
+        assert_eq!(result.rows[1][0], Cell::Text("work".to_string()));
+        assert_eq!(result.rows[1][1], Cell::Integer(2));
+
+        assert_eq!(
+            format_table(&result),
+            vec![
+                "category  tasks  note",
+                "--------  -----  ----",
+                "home      1",
+                "work      2",
+            ]
+        );
+        let json = format_json(&result);
+        assert!(json.starts_with(r#"[{"category":"home","tasks":1,"note":null},"#), "{}", json);
+    }
+
+    #[test]
+    fn test_only_select() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "report", "today");
+        for sql in [
+            "DELETE FROM items",
+            "  update items SET content = ''",
+            "SELECT 1; DELETE FROM items",
+            "WITH gone AS (SELECT 1) DELETE FROM items",
+            "PRAGMA user_version = 0",
+        ] {
+            assert!(run_query(&conn, sql).is_err(), "{}", sql);
+        }
+        let count = run_query(&conn, "SELECT COUNT(*) FROM items").unwrap();
+        assert_eq!(count.rows[0][0], Cell::Integer(1));
+        // Writes work again afterwards
+        insert_task(&conn, "work", "call", "today");
+    }
+}
@@ -0,0 +1,78 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        modify::resolve_or_pick,
+    },
+    args::{
+        parser::RemindCommand,
+        timestr::format_duration,
+    },
+    db::{
+        crud::get_item,
+        item::TASK,
+        reminders::{
+            get_reminders,
+            set_reminders,
+            Reminder,
+        },
+    },
+    error::TascliError,
+};
+
+pub fn handle_remindcmd(conn: &Connection, cmd: &RemindCommand) -> Result<(), TascliError> {
+    let item_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let item = get_item(conn, item_id)?;
+    // Lead times count back from the deadline, recurring tasks and records have none
+    if item.action != TASK {
+        return Err(TascliError::Invalid(
+            "Reminders can only be set on tasks with a deadline".to_string(),
+        ));
+    }
+
+    if cmd.clear {
+        set_reminders(conn, item_id, &[])?;
+    } else if !cmd.lead_times.is_empty() {
+        set_reminders(conn, item_id, &cmd.lead_times)?;
+    }
+
+    display::print_items(&[item], false, false);
+    let reminders = get_reminders(conn, item_id)?;
+    if reminders.is_empty() {
+        display::print_bold("No reminders");
+    } else {
+        display::print_bold(&format!("Reminders: {}", describe(&reminders)));
+    }
+    Ok(())
+}
+
+pub(crate) fn describe(reminders: &[Reminder]) -> String {
+    reminders
+        .iter()
+        .map(|r| {
+            let sent = if r.sent_time.is_some() { " (sent)" } else { "" };
+            format!("{} before{}", format_duration(r.lead_seconds), sent)
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let reminder = |lead_seconds: i64, sent_time: Option<i64>| Reminder {
+            id: 1,
+            item_id: 1,
+            lead_seconds,
+            sent_time,
+        };
+        assert_eq!(
+            describe(&[reminder(86400, Some(1000)), reminder(3600, None)]),
+            "1d before (sent), 1h before"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+        modify::complete_item,
+    },
+    args::parser::{
+        RemindersCommand,
+        RemindersSyncCommand,
+    },
+    db::{
+        crud::{
+            get_item,
+            query_items,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+    integrations::reminders::{
+        self,
+        NewReminder,
+        Reminder,
+    },
+    private,
+};
+
+// Reminders are linked back to their task through this marker in the notes.
+const LINK_PREFIX: &str = "tascli:";
+
+#[derive(Debug, Default, PartialEq)]
+struct SyncPlan {
+    create: Vec<NewReminder>,
+    complete_reminders: Vec<String>,
+    complete_tasks: Vec<i64>,
+}
+
+pub fn handle_reminderscmd(conn: &Connection, cmd: RemindersCommand) -> Result<(), TascliError> {
+    match cmd {
+        RemindersCommand::Sync(cmd) => handle_synccmd(conn, &cmd),
+    }
+}
+
+fn handle_synccmd(conn: &Connection, cmd: &RemindersSyncCommand) -> Result<(), TascliError> {
+    let existing = reminders::fetch_reminders(&cmd.list)?;
+    let plan = plan_sync(conn, &existing, cmd.category.as_deref())?;
+
+    let mut completed = Vec::new();
+    for id in &plan.complete_tasks {
+        let item = get_item(conn, *id)?;
+        completed.push(complete_item(conn, item, 1, Some("Completed in Reminders"))?);
+    }
+    reminders::apply_changes(&cmd.list, &plan.create, &plan.complete_reminders)?;
+
+    display::print_green(&format!(
+        "Pushed {} tasks to '{}', checked off {} reminders",
+        plan.create.len(),
+        cmd.list,
+        plan.complete_reminders.len()
+    ));
+    if !completed.is_empty() {
+        display::print_bold("Completed Tasks:");
+        display::print_items(&completed, false, false);
+    }
+    Ok(())
+}
+
+fn plan_sync(
+    conn: &Connection,
+    existing: &[Reminder],
+    category: Option<&str>,
+) -> Result<SyncPlan, TascliError> {
+    let mut plan = SyncPlan::default();
+    let mut linked = HashSet::new();
+
+    for reminder in existing {
+        let Some(id) = linked_task_id(&reminder.body) else {
+            continue;
+        };
+        linked.insert(id);
+        // The task may have been deleted since, its reminder is left alone
+        let Ok(item) = get_item(conn, id) else {
+            continue;
+        };
+        let is_open = OPEN_STATUS_CODES.contains(&item.status);
+        if reminder.completed && is_open {
+            plan.complete_tasks.push(id);
+        } else if !reminder.completed && !is_open {
+            plan.complete_reminders.push(reminder.id.clone());
+        }
+    }
+
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec());
+    if let Some(c) = category {
+        query = query.with_category(c);
+    }
+    let tasks = query_items(conn, &query)?;
+    for task in tasks {
+        let (Some(id), Some(target_time)) = (task.id, task.target_time) else {
+            continue;
+        };
+        if !linked.contains(&id) {
+            plan.create.push(to_reminder(&task, id, target_time));
+        }
+    }
+    Ok(plan)
+}
+
+fn to_reminder(task: &Item, id: i64, target_time: i64) -> NewReminder {
+    NewReminder {
+        name: format!(
+            "[{}] {}",
+            task.category,
+            private::conceal(&task.content).lines().next().unwrap_or_default()
+        ),
+        body: format!("{}{}", LINK_PREFIX, id),
+        due: target_time * 1000,
+    }
+}
+
+fn linked_task_id(body: &str) -> Option<i64> {
+    body.lines()
+        .find_map(|line| line.trim().strip_prefix(LINK_PREFIX))
+        .and_then(|id| id.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+        update_status,
+    };
+
+    fn reminder(id: &str, body: &str, completed: bool) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            name: String::new(),
+            body: body.to_string(),
+            completed,
+        }
+    }
+
+    #[test]
+    fn test_linked_task_id() {
+        assert_eq!(linked_task_id("tascli:42"), Some(42));
+        assert_eq!(linked_task_id("some notes\n tascli:7 "), Some(7));
+        assert_eq!(linked_task_id("tascli:abc"), None);
+        assert_eq!(linked_task_id(""), None);
+    }
+
+    #[test]
+    fn test_plan_sync() {
+        let (conn, _temp_file) = get_test_conn();
+        let unlinked = insert_task(&conn, "work", "write report", "tomorrow");
+        let done_in_reminders = insert_task(&conn, "home", "buy milk", "today");
+        let done_in_tascli = insert_task(&conn, "home", "fix sink", "today");
+        let unchanged = insert_task(&conn, "home", "water plants", "today");
+        update_status(&conn, done_in_tascli, 1);
+
+        let existing = vec![
+            reminder("A", &format!("tascli:{}", done_in_reminders), true),
+            reminder("B", &format!("tascli:{}", done_in_tascli), false),
+            reminder("C", &format!("tascli:{}", unchanged), false),
+            reminder("D", "not from tascli", false),
+        ];
+        let plan = plan_sync(&conn, &existing, None).unwrap();
+        assert_eq!(plan.complete_tasks, vec![done_in_reminders]);
+        assert_eq!(plan.complete_reminders, vec!["B".to_string()]);
+        assert_eq!(plan.create.len(), 1);
+        assert_eq!(plan.create[0].name, "[work] write report");
+        assert_eq!(plan.create[0].body, format!("tascli:{}", unlinked));
+        assert_eq!(
+            plan.create[0].due,
+            get_item(&conn, unlinked).unwrap().target_time.unwrap() * 1000
+        );
+
+        let plan = plan_sync(&conn, &[], Some("home")).unwrap();
+        assert_eq!(plan.create.len(), 2);
+    }
+}
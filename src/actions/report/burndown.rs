@@ -0,0 +1,195 @@
+use chrono::{
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::BurndownCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        history::{
+            get_status_history,
+            StatusChange,
+        },
+        item::{
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+const CHART_HEIGHT: usize = 12;
+
+pub fn handle_burndowncmd(conn: &Connection, cmd: &BurndownCommand) -> Result<(), TascliError> {
+    let tasks = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_visible_to(current_user())
+            .with_action(TASK)
+            .with_category(&cmd.category),
+    )?;
+    if tasks.is_empty() {
+        return Err(TascliError::Invalid(format!("No tasks found in category '{}'", cmd.category)));
+    }
+    let ids: Vec<i64> = tasks.iter().filter_map(|t| t.id).collect();
+    let history = get_status_history(conn, &ids)?;
+
+    let today = clock::now().date_naive();
+    let burndown = remaining_per_day(&history, today);
+
+    if let Some(path) = &cmd.csv {
+        write_csv(path, &burndown)?;
+        display::print_green(&format!("Wrote {} days to {}", burndown.len(), path));
+    }
+
+    let (first, _) = burndown[0];
+    let remaining = burndown.last().map_or(0, |(_, n)| *n);
+    let width = display::terminal_width().saturating_sub(10).max(10);
+    let values = sample(&burndown, width);
+    display::print_column_chart(
+        &format!(
+            "Burndown of {}: {} open tasks remaining, {} to {}",
+            cmd.category,
+            remaining,
+            first.format("%Y/%m/%d"),
+            today.format("%Y/%m/%d")
+        ),
+        &values,
+        CHART_HEIGHT,
+    );
+    Ok(())
+}
+
+// Open tasks at the end of each day, from the day the first task was created to today.
+// History is ordered per item from oldest to newest.
+fn remaining_per_day(history: &[StatusChange], today: NaiveDate) -> Vec<(NaiveDate, usize)> {
+    let date_of = |t: i64| Local.timestamp_opt(t, 0).single().map(|dt| dt.date_naive());
+    let first = history
+        .iter()
+        .filter_map(|c| date_of(c.change_time))
+        .min()
+        .unwrap_or(today)
+        .min(today);
+
+    let days = (today - first).num_days() as usize + 1;
+    // +1 when a task opens on a day, -1 when it closes
+    let mut deltas = vec![0i64; days];
+    for (i, change) in history.iter().enumerate() {
+        let was_open = i > 0
+            && history[i - 1].item_id == change.item_id
+            && OPEN_STATUS_CODES.contains(&history[i - 1].status);
+        let is_open = OPEN_STATUS_CODES.contains(&change.status);
+        let delta = match (was_open, is_open) {
+            (false, true) => 1,
+            (true, false) => -1,
+            _ => continue,
+        };
+        if let Some(date) = date_of(change.change_time) {
+            let index = (date - first).num_days().clamp(0, days as i64 - 1) as usize;
+            deltas[index] += delta;
+        }
+    }
+
+    let mut open = 0;
+    deltas
+        .iter()
+        .enumerate()
+        .map(|(d, delta)| {
+            open += delta;
+            (first + Duration::days(d as i64), open.max(0) as usize)
+        })
+        .collect()
+}
+
+// Long projects are squeezed to fit the terminal by keeping the last day of each step.
+fn sample(burndown: &[(NaiveDate, usize)], width: usize) -> Vec<usize> {
+    let step = burndown.len().div_ceil(width).max(1);
+    burndown
+        .chunks(step)
+        .map(|chunk| chunk.last().map_or(0, |(_, n)| *n))
+        .collect()
+}
+
+fn write_csv(path: &str, burndown: &[(NaiveDate, usize)]) -> Result<(), TascliError> {
+    let write_err = |e: csv::Error| TascliError::io(format!("Failed to write {}", path), e.into());
+    let mut writer = csv::Writer::from_path(path).map_err(write_err)?;
+    writer.write_record(["date", "remaining"]).map_err(write_err)?;
+    for (date, remaining) in burndown {
+        writer
+            .write_record([date.format("%Y-%m-%d").to_string(), remaining.to_string()])
+            .map_err(write_err)?;
+    }
+    writer.flush().map_err(|e| TascliError::io(format!("Failed to write {}", path), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    fn change(item_id: i64, status: u8, day: u32) -> StatusChange {
+        StatusChange {
+            item_id,
+            status,
+            change_time: Local
+                .with_ymd_and_hms(2025, 3, day, 12, 0, 0)
+                .unwrap()
+                .timestamp(),
+        }
+    }
+
+    #[test]
+    fn test_remaining_per_day() {
+        let history = vec![
+            change(1, 0, 1),
+            change(1, 1, 3),
+            change(2, 0, 1),
+            // suspended is still open, reopened after cancelling
+            change(2, 4, 2),
+            change(2, 2, 4),
+            change(2, 0, 5),
+            change(3, 0, 2),
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 3, 6).unwrap();
+        let remaining: Vec<usize> = remaining_per_day(&history, today)
+            .iter()
+            .map(|(_, n)| *n)
+            .collect();
+        assert_eq!(remaining, vec![2, 3, 2, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_sample() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let burndown: Vec<(NaiveDate, usize)> = (0..5)
+            .map(|d| (start + Duration::days(d), 5 - d as usize))
+            .collect();
+        assert_eq!(sample(&burndown, 10), vec![5, 4, 3, 2, 1]);
+        assert_eq!(sample(&burndown, 2), vec![3, 1]);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        write_csv(path, &[(day, 3), (day + Duration::days(1), 2)]).unwrap();
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "date,remaining\n2025-03-01,3\n2025-03-02,2\n"
+        );
+    }
+}
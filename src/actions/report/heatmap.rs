@@ -0,0 +1,333 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use chrono::{
+    Datelike,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        habit::{
+            find_habit,
+            habit_periods,
+            Period,
+        },
+    },
+    args::parser::HeatmapCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        history::get_status_history,
+        item::{
+            ItemQuery,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+const MAX_WEEKS: usize = 53;
+// Width of the weekday labels in front of the grid
+const LABEL_WIDTH: usize = 4;
+// Levels from no completions to the busiest days, colored by the palette
+const LEVELS: usize = 5;
+const CELL: &str = "■";
+// Days a habit was expected but not logged, a sign of their own as well as a color
+const MISSED_CELL: &str = "×";
+
+pub fn handle_heatmapcmd(conn: &Connection, cmd: &HeatmapCommand) -> Result<(), TascliError> {
+    let today = clock::now().date_naive();
+    let weeks = (display::terminal_width().saturating_sub(LABEL_WIDTH) / 2).clamp(1, MAX_WEEKS);
+    let since = Local
+        .from_local_datetime(&first_day(today, weeks).and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_default();
+
+    let mut done_tasks = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(vec![1]);
+    // Skipped habits are written as cancelled records, they are not completions
+    let mut recurring = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(RECURRING_TASK_RECORD)
+        .with_statuses(vec![0])
+        .with_create_time_min(since);
+    if let Some(c) = &cmd.category {
+        done_tasks = done_tasks.with_category(c);
+        recurring = recurring.with_category(c);
+    }
+
+    // Tasks are completed when they entered the done status,
+    // recurring tasks when their completion record was written
+    let mut completion_times: Vec<i64> = Vec::new();
+    let mut missed = HashSet::new();
+    if let Some(name) = &cmd.habit {
+        let habit = find_habit(conn, name)?;
+        recurring = recurring.with_recurring_task_id(habit.id.unwrap_or_default());
+        missed = habit_periods(conn, &habit, clock::timestamp())?
+            .into_iter()
+            .filter(|(_, period)| *period == Period::Missed)
+            .filter_map(|(end, _)| Local.timestamp_opt(end - 1, 0).single())
+            .map(|dt| dt.date_naive())
+            .collect();
+    } else {
+        let ids: Vec<i64> = query_items(conn, &done_tasks)?
+            .iter()
+            .filter_map(|t| t.id)
+            .collect();
+        completion_times = get_status_history(conn, &ids)?
+            .into_iter()
+            .filter(|c| c.status == 1 && c.change_time >= since)
+            .map(|c| c.change_time)
+            .collect();
+    }
+    completion_times.extend(
+        query_items(conn, &recurring)?
+            .iter()
+            .map(|r| r.create_time),
+    );
+
+    let counts = count_per_day(&completion_times);
+    display::print_bold(&format!(
+        "{} completions in the last {} weeks",
+        completion_times.len(),
+        weeks
+    ));
+    let lines = if display::is_plain() {
+        list_days(&counts, &missed, today, weeks)
+    } else {
+        render_heatmap(&counts, &missed, today, weeks)
+    };
+    for line in lines {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn count_per_day(times: &[i64]) -> HashMap<NaiveDate, usize> {
+    let mut counts = HashMap::new();
+    for time in times {
+        if let Some(dt) = Local.timestamp_opt(*time, 0).single() {
+            *counts.entry(dt.date_naive()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+// Monday of the first week shown
+fn first_day(today: NaiveDate, weeks: usize) -> NaiveDate {
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    this_monday - Duration::weeks(weeks as i64 - 1)
+}
+
+// Level 0 is no completions, the rest split the busiest day into quarters
+fn level(count: usize, max: usize) -> usize {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    (count * 4).div_ceil(max).clamp(1, 4)
+}
+
+fn colored_cell(level: usize) -> String {
+    cell(display::colors().heat[level], CELL)
+}
+
+fn missed_cell() -> String {
+    cell(display::colors().missed, MISSED_CELL)
+}
+
+fn cell(color: u8, sign: &str) -> String {
+    format!("\x1b[38;5;{}m{}\x1b[0m", color, sign)
+}
+
+// The days with completions or missed habits, oldest first, for output without the colored grid
+fn list_days(
+    counts: &HashMap<NaiveDate, usize>,
+    missed: &HashSet<NaiveDate>,
+    today: NaiveDate,
+    weeks: usize,
+) -> Vec<String> {
+    let start = first_day(today, weeks);
+    let mut days: Vec<(NaiveDate, String)> = counts
+        .iter()
+        .map(|(date, count)| (*date, count.to_string()))
+        .chain(
+            missed
+                .iter()
+                .filter(|date| !counts.contains_key(date))
+                .map(|date| (*date, "missed".to_string())),
+        )
+        .filter(|(date, _)| *date >= start && *date <= today)
+        .collect();
+    days.sort();
+    days.iter()
+        .map(|(date, count)| format!("{}: {}", date.format("%a %Y/%m/%d"), count))
+        .collect()
+}
+
+fn render_heatmap(
+    counts: &HashMap<NaiveDate, usize>,
+    missed: &HashSet<NaiveDate>,
+    today: NaiveDate,
+    weeks: usize,
+) -> Vec<String> {
+    let start = first_day(today, weeks);
+    let max = counts
+        .iter()
+        .filter(|(date, _)| **date >= start && **date <= today)
+        .map(|(_, n)| *n)
+        .max()
+        .unwrap_or(0);
+
+    // Month names above the first week starting in that month
+    let mut months = " ".repeat(LABEL_WIDTH);
+    let mut week = 0;
+    while week < weeks {
+        let monday = start + Duration::weeks(week as i64);
+        if week == 0 || monday.day() <= 7 {
+            let name = monday.format("%b").to_string();
+            months.push_str(&name);
+            months.push(' ');
+            // the name spans two weeks
+            week += 2;
+        } else {
+            months.push_str("  ");
+            week += 1;
+        }
+    }
+    let mut lines = vec![months.trim_end().to_string()];
+
+    for (day, label) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        let mut line = format!("{:<width$}", label, width = LABEL_WIDTH);
+        for week in 0..weeks {
+            let date = start + Duration::days(week as i64 * 7 + day as i64);
+            if date > today {
+                break;
+            }
+            let count = counts.get(&date).copied().unwrap_or(0);
+            if count == 0 && missed.contains(&date) {
+                line.push_str(&missed_cell());
+            } else {
+                line.push_str(&colored_cell(level(count, max)));
+            }
+            line.push(' ');
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    let legend = (0..LEVELS)
+        .map(colored_cell)
+        .collect::<Vec<String>>()
+        .join(" ");
+    let mut legend = format!("{}Less {} More", " ".repeat(LABEL_WIDTH), legend);
+    if !missed.is_empty() {
+        legend.push_str(&format!("  {} Missed", missed_cell()));
+    }
+    lines.push(legend);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(0, 10), 0);
+        assert_eq!(level(1, 10), 1);
+        assert_eq!(level(3, 10), 2);
+        assert_eq!(level(6, 10), 3);
+        assert_eq!(level(10, 10), 4);
+        assert_eq!(level(1, 1), 4);
+    }
+
+    #[test]
+    fn test_first_day() {
+        // Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 3, 19).unwrap();
+        assert_eq!(first_day(today, 1), NaiveDate::from_ymd_opt(2025, 3, 17).unwrap());
+        assert_eq!(first_day(today, 3), NaiveDate::from_ymd_opt(2025, 3, 3).unwrap());
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 19).unwrap();
+        let counts = HashMap::from([
+            (NaiveDate::from_ymd_opt(2025, 3, 17).unwrap(), 4),
+            (NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(), 1),
+            // Outside of the grid
+            (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 40),
+        ]);
+        let lines = render_heatmap(&counts, &HashSet::new(), today, 3);
+
+        // months, seven weekdays, legend
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "    Mar");
+        // Monday row has three weeks, the busiest day at the top level
+        assert!(lines[1].starts_with("Mon "));
+        assert_eq!(lines[1].matches(CELL).count(), 3);
+        assert!(lines[1].ends_with(&colored_cell(4)));
+        assert!(lines[2].contains(&colored_cell(1)));
+        // Thursday of the current week is still to come
+        assert_eq!(lines[4].matches(CELL).count(), 2);
+    }
+
+    #[test]
+    fn test_list_days() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 19).unwrap();
+        let counts = HashMap::from([
+            (NaiveDate::from_ymd_opt(2025, 3, 17).unwrap(), 4),
+            (NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(), 1),
+            (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 40),
+        ]);
+        assert_eq!(
+            list_days(&counts, &HashSet::new(), today, 3),
+            vec!["Tue 2025/03/04: 1", "Mon 2025/03/17: 4"]
+        );
+    }
+
+    #[test]
+    fn test_missed_days() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 19).unwrap();
+        let counts = HashMap::from([(NaiveDate::from_ymd_opt(2025, 3, 17).unwrap(), 1)]);
+        let missed = HashSet::from([
+            NaiveDate::from_ymd_opt(2025, 3, 18).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 17).unwrap(),
+        ]);
+
+        let lines = render_heatmap(&counts, &missed, today, 1);
+        assert_eq!(lines[1], format!("Mon {}", colored_cell(4)));
+        assert_eq!(lines[2], format!("    {}", missed_cell()));
+        assert!(lines[8].ends_with("Missed"));
+        assert_eq!(
+            list_days(&counts, &missed, today, 1),
+            vec!["Mon 2025/03/17: 1", "Tue 2025/03/18: missed"]
+        );
+    }
+
+    #[test]
+    fn test_count_per_day() {
+        let morning = Local
+            .with_ymd_and_hms(2025, 3, 17, 9, 0, 0)
+            .unwrap()
+            .timestamp();
+        let evening = Local
+            .with_ymd_and_hms(2025, 3, 17, 21, 0, 0)
+            .unwrap()
+            .timestamp();
+        let counts = count_per_day(&[morning, evening]);
+        assert_eq!(counts[&NaiveDate::from_ymd_opt(2025, 3, 17).unwrap()], 2);
+    }
+}
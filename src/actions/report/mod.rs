@@ -0,0 +1,36 @@
+mod burndown;
+mod heatmap;
+mod monthly;
+mod overdue;
+mod productivity;
+mod records;
+mod sla;
+mod status;
+mod streak;
+mod switches;
+mod velocity;
+
+pub use monthly::summary_between;
+
+use rusqlite::Connection;
+
+use crate::{
+    args::parser::ReportCommand,
+    error::TascliError,
+};
+
+pub fn handle_reportcmd(conn: &Connection, cmd: ReportCommand) -> Result<(), TascliError> {
+    match cmd {
+        ReportCommand::Productivity(cmd) => productivity::handle_productivitycmd(conn, &cmd),
+        ReportCommand::Status(cmd) => status::handle_statuscmd(conn, &cmd),
+        ReportCommand::Heatmap(cmd) => heatmap::handle_heatmapcmd(conn, &cmd),
+        ReportCommand::Overdue(cmd) => overdue::handle_overduecmd(conn, &cmd),
+        ReportCommand::Monthly(cmd) => monthly::handle_monthlycmd(conn, &cmd),
+        ReportCommand::Burndown(cmd) => burndown::handle_burndowncmd(conn, &cmd),
+        ReportCommand::Records(cmd) => records::handle_recordscmd(conn, &cmd),
+        ReportCommand::Velocity(cmd) => velocity::handle_velocitycmd(conn, &cmd),
+        ReportCommand::Streak(cmd) => streak::handle_streakcmd(conn, &cmd),
+        ReportCommand::Sla(cmd) => sla::handle_slacmd(conn, &cmd),
+        ReportCommand::Switches(cmd) => switches::handle_switchescmd(conn, &cmd),
+    }
+}
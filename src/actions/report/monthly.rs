@@ -0,0 +1,315 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+};
+
+use chrono::{
+    Datelike,
+    Local,
+    Months,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::parser::MonthlyReportCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        history::get_status_history,
+        item::{
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+// Prefixes of the records written by `tascli done`
+const TASK_COMPLETION_PREFIX: &str = "Completed Task: ";
+const RECURRING_COMPLETION_PREFIX: &str = "Completed Recurring Task: ";
+
+#[derive(Debug, PartialEq)]
+struct Entry {
+    time: i64,
+    content: String,
+}
+
+#[derive(Debug, Default)]
+struct Summary {
+    completed: BTreeMap<String, Vec<Entry>>,
+    records: BTreeMap<String, Vec<Entry>>,
+}
+
+pub fn handle_monthlycmd(conn: &Connection, cmd: &MonthlyReportCommand) -> Result<(), TascliError> {
+    let first_day = match &cmd.month {
+        Some(m) => parse_month(m)?,
+        None => clock::now().date_naive().with_day(1).unwrap(),
+    };
+    let summary = build_summary(conn, first_day, cmd.category.as_deref())?;
+    let text = render(&summary, first_day, cmd.format == "markdown");
+
+    match &cmd.output {
+        Some(path) => {
+            fs::write(path, text)
+                .map_err(|e| TascliError::io(format!("Failed to write {}", path), e))?;
+            display::print_green(&format!("Wrote the monthly summary to {}", path));
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+fn parse_month(s: &str) -> Result<NaiveDate, TascliError> {
+    NaiveDate::parse_from_str(&format!("{}/01", s.replace('-', "/")), "%Y/%m/%d")
+        .map_err(|_| {
+            TascliError::Parse(format!("Couldn't parse '{}' as a month, expected YYYY-MM", s))
+        })
+}
+
+fn month_range(first_day: NaiveDate) -> (i64, i64) {
+    let to_epoch = |d: NaiveDate| {
+        Local
+            .from_local_datetime(&d.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .map(|dt| dt.timestamp())
+            .unwrap_or_default()
+    };
+    let next_month = first_day + Months::new(1);
+    (to_epoch(first_day), to_epoch(next_month))
+}
+
+/// Plain text summary of the tasks completed and records made from `start` until `end`.
+pub fn summary_between(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+    title: &str,
+    category: Option<&str>,
+) -> Result<String, TascliError> {
+    let summary = summarize(conn, start, end, category)?;
+    Ok(render_titled(&summary, title, false))
+}
+
+fn build_summary(
+    conn: &Connection,
+    first_day: NaiveDate,
+    category: Option<&str>,
+) -> Result<Summary, TascliError> {
+    let (start, end) = month_range(first_day);
+    summarize(conn, start, end, category)
+}
+
+fn summarize(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+    category: Option<&str>,
+) -> Result<Summary, TascliError> {
+    let in_range = |t: i64| t >= start && t < end;
+    let mut summary = Summary::default();
+
+    let mut done = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(vec![1]);
+    let mut records = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_create_time_range(Some(start - 1), Some(end - 1));
+    if let Some(c) = category {
+        done = done.with_category(c);
+        records = records.with_category(c);
+    }
+
+    // Tasks were completed when they entered the done status
+    let tasks = query_items(conn, &done)?;
+    let ids: Vec<i64> = tasks.iter().filter_map(|t| t.id).collect();
+    let history = get_status_history(conn, &ids)?;
+    for task in &tasks {
+        let done_at = history
+            .iter()
+            .filter(|c| Some(c.item_id) == task.id && c.status == 1)
+            .map(|c| c.change_time)
+            .next_back();
+        if let Some(time) = done_at.filter(|t| in_range(*t)) {
+            summary
+                .completed
+                .entry(task.category.clone())
+                .or_default()
+                .push(Entry {
+                    time,
+                    content: first_line(&task.content),
+                });
+        }
+    }
+
+    for record in query_items(conn, &records)? {
+        // Completion records repeat completed tasks, recurring ones are only known through them
+        if record.content.starts_with(TASK_COMPLETION_PREFIX) {
+            continue;
+        }
+        match record.content.strip_prefix(RECURRING_COMPLETION_PREFIX) {
+            Some(content) if record.action == RECURRING_TASK_RECORD => summary
+                .completed
+                .entry(record.category.clone())
+                .or_default()
+                .push(Entry {
+                    time: record.create_time,
+                    content: first_line(content),
+                }),
+            _ => summary
+                .records
+                .entry(record.category.clone())
+                .or_default()
+                .push(Entry {
+                    time: record.create_time,
+                    content: first_line(&record.content),
+                }),
+        }
+    }
+
+    for entries in summary
+        .completed
+        .values_mut()
+        .chain(summary.records.values_mut())
+    {
+        entries.sort_by_key(|e| e.time);
+    }
+    Ok(summary)
+}
+
+// The report is meant to be shared, private content stays hidden even while unlocked
+fn first_line(content: &str) -> String {
+    private::conceal(content).lines().next().unwrap_or_default().to_string()
+}
+
+fn render(summary: &Summary, first_day: NaiveDate, markdown: bool) -> String {
+    render_titled(summary, &format!("Summary of {}", first_day.format("%B %Y")), markdown)
+}
+
+fn render_titled(summary: &Summary, title: &str, markdown: bool) -> String {
+    let count = |section: &BTreeMap<String, Vec<Entry>>| -> usize {
+        section.values().map(Vec::len).sum()
+    };
+    let (h1, h2, h3, bullet) = if markdown {
+        ("# ", "## ", "### ", "- ")
+    } else {
+        ("", "", "", "  ")
+    };
+
+    let mut out = format!("{}{}\n\n", h1, title);
+    out.push_str(&format!(
+        "{} tasks completed and {} records made.\n",
+        count(&summary.completed),
+        count(&summary.records)
+    ));
+
+    for (title, section) in [("Completed", &summary.completed), ("Records", &summary.records)] {
+        if section.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n{}{}\n", h2, title));
+        for (category, entries) in section {
+            out.push_str(&format!("\n{}{} ({})\n\n", h3, category, entries.len()));
+            for entry in entries {
+                let date = Local
+                    .timestamp_opt(entry.time, 0)
+                    .single()
+                    .map(|dt| dt.format("%m/%d").to_string())
+                    .unwrap_or_default();
+                out.push_str(&format!("{}{} {}\n", bullet, date, entry.content));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::{
+            get_item,
+            update_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!(
+            parse_month("2025-03").unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+        assert_eq!(
+            parse_month("2025/12").unwrap(),
+            NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()
+        );
+        assert!(parse_month("2025-13").is_err());
+        assert!(parse_month("march").is_err());
+    }
+
+    #[test]
+    fn test_build_summary() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "ship release\nwith notes", "today");
+        let mut task = get_item(&conn, id).unwrap();
+        task.status = 1;
+        update_item(&conn, &task).unwrap();
+        insert_task(&conn, "work", "still open", "today");
+        insert_record(&conn, "journal", "met the new team", "today");
+        insert_record(&conn, "work", "Completed Task: ship release", "today");
+        insert_record(&conn, "journal", "last year", "2020/01/01");
+
+        let this_month = Local::now().date_naive().with_day(1).unwrap();
+        let summary = build_summary(&conn, this_month, None).unwrap();
+        assert_eq!(summary.completed["work"].len(), 1);
+        assert_eq!(summary.completed["work"][0].content, "ship release");
+        assert_eq!(summary.records.len(), 1);
+        assert_eq!(summary.records["journal"][0].content, "met the new team");
+
+        let summary = build_summary(&conn, this_month, Some("journal")).unwrap();
+        assert!(summary.completed.is_empty());
+
+        let summary = build_summary(&conn, parse_month("2020-01").unwrap(), None).unwrap();
+        assert!(summary.completed.is_empty());
+        assert_eq!(summary.records["journal"].len(), 1);
+    }
+
+    #[test]
+    fn test_render() {
+        let time = Local
+            .with_ymd_and_hms(2025, 3, 14, 10, 0, 0)
+            .unwrap()
+            .timestamp();
+        let mut summary = Summary::default();
+        summary.completed.insert(
+            "work".to_string(),
+            vec![Entry {
+                time,
+                content: "ship release".to_string(),
+            }],
+        );
+        let march = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        assert_eq!(
+            render(&summary, march, true),
+            "# Summary of March 2025\n\n1 tasks completed and 0 records made.\n\n## Completed\n\n### work (1)\n\n- 03/14 ship release\n"
+        );
+        assert_eq!(
+            render(&summary, march, false),
+            "Summary of March 2025\n\n1 tasks completed and 0 records made.\n\nCompleted\n\nwork (1)\n\n  03/14 ship release\n"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::{
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+    },
+    args::parser::OverdueReportCommand,
+    clock,
+    config::current_user,
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+// Lower bound in days late of each bucket
+const BUCKETS: [(i64, &str); 4] = [(1, "1-3d"), (4, "4-7d"), (8, "8-30d"), (31, "30d+")];
+
+pub fn handle_overduecmd(conn: &Connection, cmd: &OverdueReportCommand) -> Result<(), TascliError> {
+    let now = clock::timestamp();
+    let mut query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(now)
+        .with_order_by(TARGET_TIME_COL);
+    if let Some(c) = &cmd.category {
+        query = query.with_category(c);
+    }
+    let overdue = query_items(conn, &query)?;
+    if overdue.is_empty() {
+        display::print_bold("No overdue tasks");
+        return Ok(());
+    }
+
+    display::print_bold(&format!("{} overdue tasks by days late:", overdue.len()));
+    for line in format_table(&bucket_by_category(&overdue, now)) {
+        println!("{}", line);
+    }
+    println!();
+
+    // Ordered by deadline, so the most overdue come first,
+    // cached so they can be completed or updated by index
+    let worst: Vec<Item> = overdue.into_iter().take(cmd.limit).collect();
+    cache::clear(conn)?;
+    cache::store(conn, &worst)?;
+    display::print_bold("Most Overdue:");
+    display::print_items(&worst, false, true);
+    Ok(())
+}
+
+fn days_late(task: &Item, now: i64) -> i64 {
+    let seconds = now - task.target_time.unwrap_or(now);
+    // Any part of a day counts as a day late
+    (seconds + 86399) / 86400
+}
+
+fn bucket_index(days_late: i64) -> usize {
+    BUCKETS
+        .iter()
+        .rposition(|(min, _)| days_late >= *min)
+        .unwrap_or(0)
+}
+
+fn bucket_by_category(tasks: &[Item], now: i64) -> BTreeMap<String, [usize; 4]> {
+    let mut buckets: BTreeMap<String, [usize; 4]> = BTreeMap::new();
+    for task in tasks {
+        let bucket = bucket_index(days_late(task, now));
+        buckets.entry(task.category.clone()).or_default()[bucket] += 1;
+    }
+    buckets
+}
+
+fn format_table(buckets: &BTreeMap<String, [usize; 4]>) -> Vec<String> {
+    let category_width = buckets
+        .keys()
+        .map(|c| c.chars().count())
+        .chain(["Category".len(), "Total".len()])
+        .max()
+        .unwrap_or(0);
+    let row = |label: &str, counts: &[usize; 4]| {
+        let cells: String = counts.iter().map(|n| format!("{:>7}", n)).collect();
+        format!(
+            "{:<width$}{}{:>7}",
+            label,
+            cells,
+            counts.iter().sum::<usize>(),
+            width = category_width
+        )
+    };
+
+    let header: String = BUCKETS.iter().map(|(_, name)| format!("{:>7}", name)).collect();
+    let mut lines = vec![format!(
+        "{:<width$}{}{:>7}",
+        "Category",
+        header,
+        "Total",
+        width = category_width
+    )];
+    let mut totals = [0; 4];
+    for (category, counts) in buckets {
+        lines.push(row(category, counts));
+        for (total, n) in totals.iter_mut().zip(counts) {
+            *total += n;
+        }
+    }
+    lines.push(row("Total", &totals));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn overdue_task(category: &str, target_time: i64) -> Item {
+        Item::with_target_time(
+            TASK.to_string(),
+            category.to_string(),
+            "task".to_string(),
+            Some(target_time),
+        )
+    }
+
+    #[test]
+    fn test_bucket_index() {
+        assert_eq!(bucket_index(1), 0);
+        assert_eq!(bucket_index(3), 0);
+        assert_eq!(bucket_index(4), 1);
+        assert_eq!(bucket_index(7), 1);
+        assert_eq!(bucket_index(8), 2);
+        assert_eq!(bucket_index(30), 2);
+        assert_eq!(bucket_index(31), 3);
+        assert_eq!(bucket_index(400), 3);
+    }
+
+    #[test]
+    fn test_bucket_by_category() {
+        let now = 1000 * DAY;
+        let tasks = vec![
+            overdue_task("work", now - 60),
+            overdue_task("work", now - 5 * DAY),
+            overdue_task("work", now - 45 * DAY),
+            overdue_task("home", now - 10 * DAY),
+        ];
+        assert_eq!(days_late(&tasks[0], now), 1);
+
+        let buckets = bucket_by_category(&tasks, now);
+        assert_eq!(buckets["work"], [1, 1, 0, 1]);
+        assert_eq!(buckets["home"], [0, 0, 1, 0]);
+
+        assert_eq!(
+            format_table(&buckets),
+            vec![
+                "Category   1-3d   4-7d  8-30d   30d+  Total",
+                "home          0      0      1      0      1",
+                "work          1      1      0      1      3",
+                "Total         1      1      1      1      4",
+            ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use chrono::{
+    DateTime,
+    Datelike,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+    Timelike,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::ProductivityCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Debug, Default, PartialEq)]
+struct WeekTrend {
+    start: NaiveDate,
+    on_time: usize,
+    late: usize,
+}
+
+#[derive(Debug, Default)]
+struct ProductivityStats {
+    completed: usize,
+    by_weekday: [usize; 7],
+    by_hour: [usize; 24],
+    average_age_days: Option<f64>,
+    weeks: Vec<WeekTrend>,
+    overdue_now: usize,
+}
+
+pub fn handle_productivitycmd(
+    conn: &Connection,
+    cmd: &ProductivityCommand,
+) -> Result<(), TascliError> {
+    let now = clock::now();
+    let since = (now - Duration::days(cmd.days as i64)).timestamp();
+
+    let mut done = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(vec![1]);
+    let mut open = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(now.timestamp());
+    if let Some(c) = &cmd.category {
+        done = done.with_category(c);
+        open = open.with_category(c);
+    }
+
+    // Completion time is only tracked as the last modification of the task
+    let completed: Vec<Item> = query_items(conn, &done)?
+        .into_iter()
+        .filter(|t| t.modify_time.is_some_and(|m| m > since))
+        .collect();
+    let overdue_now = query_items(conn, &open)?.len();
+
+    let stats = compute_stats(&completed, overdue_now, cmd.days, now);
+    print_stats(&stats, cmd.days);
+    Ok(())
+}
+
+fn compute_stats(
+    completed: &[Item],
+    overdue_now: usize,
+    days: usize,
+    now: DateTime<Local>,
+) -> ProductivityStats {
+    let first_day = now.date_naive() - Duration::days(days as i64 - 1);
+    let first_monday =
+        first_day - Duration::days(first_day.weekday().num_days_from_monday() as i64);
+    let mut weeks: Vec<WeekTrend> = (0..)
+        .map(|w| first_monday + Duration::weeks(w))
+        .take_while(|start| *start <= now.date_naive())
+        .map(|start| WeekTrend {
+            start,
+            ..Default::default()
+        })
+        .collect();
+
+    let mut stats = ProductivityStats {
+        overdue_now,
+        ..Default::default()
+    };
+    let mut total_age = 0;
+    for task in completed {
+        let Some(done_at) = task
+            .modify_time
+            .and_then(|m| Local.timestamp_opt(m, 0).single())
+        else {
+            continue;
+        };
+        stats.completed += 1;
+        stats.by_weekday[done_at.weekday().num_days_from_monday() as usize] += 1;
+        stats.by_hour[done_at.hour() as usize] += 1;
+        total_age += done_at.timestamp() - task.create_time;
+
+        let week = (done_at.date_naive() - first_monday).num_weeks() as usize;
+        if let Some(trend) = weeks.get_mut(week) {
+            if task.target_time.is_some_and(|t| t < done_at.timestamp()) {
+                trend.late += 1;
+            } else {
+                trend.on_time += 1;
+            }
+        }
+    }
+
+    if stats.completed > 0 {
+        stats.average_age_days = Some(total_age as f64 / stats.completed as f64 / 86400.0);
+    }
+    stats.weeks = weeks;
+    stats
+}
+
+fn print_stats(stats: &ProductivityStats, days: usize) {
+    display::print_bold(&format!(
+        "Completed {} tasks in the last {} days, {} open tasks are overdue",
+        stats.completed, days, stats.overdue_now
+    ));
+    match stats.average_age_days {
+        Some(age) => println!("Average task age at completion: {:.1} days\n", age),
+        None => {
+            println!();
+            return;
+        }
+    }
+
+    let weekdays: Vec<(String, f64)> = WEEKDAYS
+        .iter()
+        .zip(stats.by_weekday)
+        .map(|(d, n)| (d.to_string(), n as f64))
+        .collect();
+    display::print_bar_chart("Completions per weekday", &weekdays);
+
+    // Only the range of hours with completions, a full day is mostly empty rows
+    let active = |&h: &usize| stats.by_hour[h] > 0;
+    if let (Some(first), Some(last)) = ((0..24).find(active), (0..24).rev().find(active)) {
+        let hours: Vec<(String, f64)> = (first..=last)
+            .map(|h| (format!("{:02}:00", h), stats.by_hour[h] as f64))
+            .collect();
+        display::print_bar_chart("Completions per hour", &hours);
+    }
+
+    let weeks: Vec<(String, f64)> = stats
+        .weeks
+        .iter()
+        .map(|w| {
+            let total = w.on_time + w.late;
+            let late_percent = if total > 0 {
+                (w.late * 100) as f64 / total as f64
+            } else {
+                0.0
+            };
+            (
+                format!("week of {}", w.start.format("%m/%d")),
+                late_percent.round(),
+            )
+        })
+        .collect();
+    display::print_bar_chart("Completed after deadline per week (%)", &weeks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed_task(
+        created: DateTime<Local>,
+        deadline: DateTime<Local>,
+        done: DateTime<Local>,
+    ) -> Item {
+        let mut task = Item::with_create_time(
+            TASK.to_string(),
+            "work".to_string(),
+            "task".to_string(),
+            created.timestamp(),
+        );
+        task.target_time = Some(deadline.timestamp());
+        task.modify_time = Some(done.timestamp());
+        task.status = 1;
+        task
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let at = |d, h| Local.with_ymd_and_hms(2025, 3, d, h, 0, 0).unwrap();
+        // Wednesday 2025/03/19
+        let now = at(19, 18);
+        let completed = vec![
+            // Monday 10:00, two days old, on time
+            completed_task(at(15, 10), at(17, 23), at(17, 10)),
+            // Monday 14:00, one day old, late
+            completed_task(at(16, 14), at(16, 23), at(17, 14)),
+            // Thursday of the previous week, late
+            completed_task(at(13, 10), at(12, 23), at(13, 10)),
+        ];
+
+        let stats = compute_stats(&completed, 4, 14, now);
+        assert_eq!(stats.completed, 3);
+        assert_eq!(stats.overdue_now, 4);
+        assert_eq!(stats.by_weekday, [2, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(stats.by_hour[10], 2);
+        assert_eq!(stats.by_hour[14], 1);
+        assert!((stats.average_age_days.unwrap() - 1.0).abs() < 1e-9);
+
+        // 14 days back from 03/19 starts on Thursday 03/06, so weeks start 03/03
+        let starts: Vec<NaiveDate> = stats.weeks.iter().map(|w| w.start).collect();
+        assert_eq!(
+            starts,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 3, 17).unwrap(),
+            ]
+        );
+        assert_eq!((stats.weeks[1].on_time, stats.weeks[1].late), (0, 1));
+        assert_eq!((stats.weeks[2].on_time, stats.weeks[2].late), (1, 1));
+    }
+
+    #[test]
+    fn test_compute_stats_empty() {
+        let stats = compute_stats(&[], 0, 7, Local::now());
+        assert_eq!(stats.completed, 0);
+        assert_eq!(stats.average_age_days, None);
+    }
+}
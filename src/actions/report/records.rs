@@ -0,0 +1,300 @@
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+};
+
+use chrono::{
+    DateTime,
+    Datelike,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use regex::Regex;
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::parser::RecordsReportCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+        },
+    },
+    error::TascliError,
+};
+
+// The first number of a record and the unit right after it, as in "ran 5km" or "slept 7.5 h".
+static VALUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\w.])(\d+(?:\.\d+)?)\s?([a-zA-Z]+\b|%)?").unwrap()
+});
+
+// Prefix of the records written by `tascli done`
+const TASK_COMPLETION_PREFIX: &str = "Completed Task: ";
+
+#[derive(Debug, Default, PartialEq)]
+struct WeekRecords {
+    start: NaiveDate,
+    count: usize,
+    values: Vec<f64>,
+}
+
+#[derive(Debug, PartialEq)]
+struct RecordTrend {
+    weeks: Vec<WeekRecords>,
+    unit: Option<String>,
+}
+
+pub fn handle_recordscmd(conn: &Connection, cmd: &RecordsReportCommand) -> Result<(), TascliError> {
+    let now = clock::now();
+    let weeks = cmd.weeks.max(1);
+    let first_monday = first_monday(now, weeks);
+    let since = Local
+        .from_local_datetime(&first_monday.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_default();
+
+    let mut query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(RECORD)
+        .with_create_time_min(since);
+    if let Some(c) = &cmd.category {
+        query = query.with_category(c);
+    }
+    if let Some(s) = &cmd.search {
+        query = query.with_content_like(s);
+    }
+    let records: Vec<Item> = query_items(conn, &query)?
+        .into_iter()
+        .filter(|r| !r.content.starts_with(TASK_COMPLETION_PREFIX))
+        .collect();
+
+    let trend = compute_trend(&records, cmd.field.as_deref(), first_monday, now);
+    print_trend(&trend, weeks);
+    Ok(())
+}
+
+fn first_monday(now: DateTime<Local>, weeks: usize) -> NaiveDate {
+    let this_monday =
+        now.date_naive() - Duration::days(now.weekday().num_days_from_monday() as i64);
+    this_monday - Duration::weeks(weeks as i64 - 1)
+}
+
+// The answer to `field` of a templated record, the value logged with --value, otherwise the
+// first number in the content
+fn record_value(record: &Item, field: Option<&str>) -> Option<(f64, Option<String>)> {
+    if let Some(field) = field {
+        return record.field(field).and_then(extract_value);
+    }
+    match record.value {
+        Some(value) => Some((value, record.unit.as_ref().map(|u| u.to_lowercase()))),
+        None => extract_value(&record.content),
+    }
+}
+
+fn extract_value(content: &str) -> Option<(f64, Option<String>)> {
+    let caps = VALUE_RE.captures(content)?;
+    let value = caps[1].parse().ok()?;
+    Some((value, caps.get(2).map(|u| u.as_str().to_lowercase())))
+}
+
+fn compute_trend(
+    records: &[Item],
+    field: Option<&str>,
+    first_monday: NaiveDate,
+    now: DateTime<Local>,
+) -> RecordTrend {
+    let mut weeks: Vec<WeekRecords> = (0..)
+        .map(|w| first_monday + Duration::weeks(w))
+        .take_while(|start| *start <= now.date_naive())
+        .map(|start| WeekRecords {
+            start,
+            ..Default::default()
+        })
+        .collect();
+
+    // Only values with the most common unit are comparable, "ran 5km" and "ran 30 min" are not
+    let values: Vec<Option<(f64, Option<String>)>> =
+        records.iter().map(|r| record_value(r, field)).collect();
+    let mut unit_counts: HashMap<&Option<String>, usize> = HashMap::new();
+    for (_, unit) in values.iter().flatten() {
+        *unit_counts.entry(unit).or_default() += 1;
+    }
+    let unit = unit_counts
+        .into_iter()
+        .max_by(|(a, x), (b, y)| x.cmp(y).then_with(|| b.cmp(a)))
+        .map(|(unit, _)| unit.clone());
+
+    for (record, value) in records.iter().zip(&values) {
+        let Some(created) = Local.timestamp_opt(record.create_time, 0).single() else {
+            continue;
+        };
+        let week = (created.date_naive() - first_monday).num_days().div_euclid(7);
+        let Some(bucket) = usize::try_from(week).ok().and_then(|w| weeks.get_mut(w)) else {
+            continue;
+        };
+        bucket.count += 1;
+        if let Some((value, value_unit)) = value
+            && unit.as_ref() == Some(value_unit)
+        {
+            bucket.values.push(*value);
+        }
+    }
+
+    RecordTrend {
+        weeks,
+        unit: unit.flatten(),
+    }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn print_trend(trend: &RecordTrend, weeks: usize) {
+    let total: usize = trend.weeks.iter().map(|w| w.count).sum();
+    display::print_bold(&format!("{} records in the last {} weeks\n", total, weeks));
+    if total == 0 {
+        return;
+    }
+
+    let label = |w: &WeekRecords| format!("week of {}", w.start.format("%m/%d"));
+    let counts: Vec<(String, f64)> = trend
+        .weeks
+        .iter()
+        .map(|w| (label(w), w.count as f64))
+        .collect();
+    display::print_bar_chart("Records per week", &counts);
+
+    let all_values: Vec<f64> = trend
+        .weeks
+        .iter()
+        .flat_map(|w| w.values.iter().copied())
+        .collect();
+    let Some(overall) = average(&all_values) else {
+        return;
+    };
+    let unit = trend
+        .unit
+        .as_ref()
+        .map(|u| format!(" {}", u))
+        .unwrap_or_default();
+    let sums: Vec<(String, f64)> = trend
+        .weeks
+        .iter()
+        .map(|w| (label(w), w.values.iter().sum()))
+        .collect();
+    display::print_bar_chart(&format!("Total{} per week", unit), &sums);
+    let averages: Vec<(String, f64)> = trend
+        .weeks
+        .iter()
+        .map(|w| (label(w), average(&w.values).unwrap_or_default()))
+        .collect();
+    display::print_bar_chart(&format!("Average{} per record", unit), &averages);
+
+    // Compare the recent half of the period with the earlier half
+    let (earlier, recent) = trend.weeks.split_at(trend.weeks.len() / 2);
+    let half_average = |weeks: &[WeekRecords]| {
+        average(
+            &weeks
+                .iter()
+                .flat_map(|w| w.values.iter().copied())
+                .collect::<Vec<f64>>(),
+        )
+    };
+    println!("Average per record: {:.1}{}", overall, unit);
+    if let (Some(before), Some(after)) = (half_average(earlier), half_average(recent))
+        && before > 0.0
+    {
+        println!(
+            "Trend: {:+.0}% in the last {} weeks compared to the {} before",
+            (after - before) / before * 100.0,
+            recent.len(),
+            earlier.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(content: &str, created: DateTime<Local>) -> Item {
+        Item::with_create_time(
+            RECORD.to_string(),
+            "health".to_string(),
+            content.to_string(),
+            created.timestamp(),
+        )
+    }
+
+    #[test]
+    fn test_extract_value() {
+        assert_eq!(extract_value("ran 5km"), Some((5.0, Some("km".to_string()))));
+        assert_eq!(
+            extract_value("slept 7.5 Hours, felt fine"),
+            Some((7.5, Some("hours".to_string())))
+        );
+        assert_eq!(extract_value("weight 81.2"), Some((81.2, None)));
+        assert_eq!(extract_value("battery at 40%"), Some((40.0, Some("%".to_string()))));
+        assert_eq!(extract_value("upgraded to v2"), None);
+        assert_eq!(extract_value("went for a walk"), None);
+    }
+
+    #[test]
+    fn test_record_value_prefers_logged_value() {
+        let at = Local.with_ymd_and_hms(2025, 3, 3, 8, 0, 0).unwrap();
+        let mut weight = record("weight after 2 runs", at);
+        assert_eq!(record_value(&weight, None), Some((2.0, Some("runs".to_string()))));
+        weight.value = Some(82.5);
+        weight.unit = Some("KG".to_string());
+        assert_eq!(record_value(&weight, None), Some((82.5, Some("kg".to_string()))));
+    }
+
+    #[test]
+    fn test_record_value_of_field() {
+        let at = Local.with_ymd_and_hms(2025, 3, 3, 8, 0, 0).unwrap();
+        let mut workout = record("run for 30 min, 5.2 km", at);
+        workout.value = Some(30.0);
+        workout.fields = Some("type=run\nduration=30 min\ndistance=5.2 km".to_string());
+        let distance = Some((5.2, Some("km".to_string())));
+        assert_eq!(record_value(&workout, Some("distance")), distance);
+        assert_eq!(record_value(&workout, Some("pace")), None);
+    }
+
+    #[test]
+    fn test_compute_trend() {
+        let at = |d| Local.with_ymd_and_hms(2025, 3, d, 8, 0, 0).unwrap();
+        // Wednesday 2025/03/19
+        let now = at(19);
+        let first_monday = first_monday(now, 3);
+        assert_eq!(first_monday, NaiveDate::from_ymd_opt(2025, 3, 3).unwrap());
+
+        let records = vec![
+            record("ran 5km", at(3)),
+            record("ran 3 km", at(9)),
+            record("ran 30 min", at(11)),
+            record("rest day", at(12)),
+            record("ran 6km", at(18)),
+            record("ran 4km", at(19)),
+            // Before the period
+            record("ran 9km", at(2)),
+        ];
+        let trend = compute_trend(&records, None, first_monday, now);
+        assert_eq!(trend.unit.as_deref(), Some("km"));
+        let counts: Vec<usize> = trend.weeks.iter().map(|w| w.count).collect();
+        assert_eq!(counts, vec![2, 2, 2]);
+        let values: Vec<Vec<f64>> = trend.weeks.iter().map(|w| w.values.clone()).collect();
+        assert_eq!(values, vec![vec![5.0, 3.0], vec![], vec![6.0, 4.0]]);
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::display::{
+        self,
+        print_table,
+        DisplayRow,
+    },
+    args::{
+        parser::SlaReportCommand,
+        timestr::window_deadline,
+    },
+    clock,
+    config::{
+        self,
+        current_user,
+    },
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+// Done and open tasks, cancelled, duplicate and removed ones were never going to be done
+const COUNTED_STATUS_CODES: [u8; 4] = [0, 1, 4, 6];
+
+// A task done after the end of its window, or still open past it
+struct Breach {
+    task: Item,
+    due: i64,
+    late: i64,
+}
+
+struct CategorySla {
+    category: String,
+    window: String,
+    total: usize,
+    breaches: Vec<Breach>,
+}
+
+pub fn handle_slacmd(conn: &Connection, cmd: &SlaReportCommand) -> Result<(), TascliError> {
+    let mut slas = config::category_slas();
+    if let Some(category) = &cmd.category {
+        slas.retain(|(name, _)| name == category);
+    }
+    if slas.is_empty() {
+        display::print_bold("No categories have an sla in config");
+        return Ok(());
+    }
+    let now = clock::timestamp();
+    let report = check_slas(conn, &slas, now - cmd.days as i64 * 86400, now)?;
+
+    display::print_bold(&format!("SLAs of tasks added in the last {} days:", cmd.days));
+    for sla in &report {
+        let met = sla.total - sla.breaches.len();
+        let share = (met * 100).checked_div(sla.total).unwrap_or(100);
+        println!(
+            "{} ({}): {} tasks, {} breached, {}% met",
+            sla.category,
+            sla.window,
+            sla.total,
+            sla.breaches.len(),
+            share
+        );
+    }
+    let breaches: Vec<Breach> = report.into_iter().flat_map(|sla| sla.breaches).collect();
+    if breaches.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    display::print_bold("Breaches:");
+    let tasks: Vec<Item> = breaches.iter().map(|b| b.task.clone()).collect();
+    cache::clear(conn)?;
+    cache::store(conn, &tasks)?;
+    let rows: Vec<DisplayRow> = breaches
+        .iter()
+        .enumerate()
+        .map(|(i, breach)| DisplayRow {
+            index: (i + 1).to_string(),
+            category: breach.task.category.clone(),
+            category_color: None,
+            content: private::reveal(&breach.task.content),
+            timestr: describe(breach),
+            timestr_color: None,
+            tags: String::new(),
+            depth: 0,
+        })
+        .collect();
+    print_table(&rows, false);
+    Ok(())
+}
+
+// Tasks of each category added since `since`, with the ones that missed their window, the
+// latest first. Completion time is only tracked as the last modification of a task.
+fn check_slas(
+    conn: &Connection,
+    slas: &[(String, String)],
+    since: i64,
+    now: i64,
+) -> Result<Vec<CategorySla>, TascliError> {
+    let mut report = Vec::new();
+    for (category, window) in slas {
+        let query = ItemQuery::new()
+            .with_visible_to(current_user())
+            .with_action(TASK)
+            .with_category(category)
+            .with_statuses(COUNTED_STATUS_CODES.to_vec())
+            .with_create_time_min(since);
+        let tasks = query_items(conn, &query)?;
+        let mut breaches = Vec::new();
+        for task in &tasks {
+            let due = window_deadline(window, task.create_time)
+                .map_err(|e| TascliError::Config(format!("SLA of {}: {}", category, e)))?;
+            let finished = if task.status == 1 { task.modify_time } else { None };
+            let late = finished.unwrap_or(now) - due;
+            if late > 0 {
+                breaches.push(Breach { task: task.clone(), due, late });
+            }
+        }
+        breaches.sort_by_key(|b| std::cmp::Reverse(b.late));
+        report.push(CategorySla {
+            category: category.clone(),
+            window: window.clone(),
+            total: tasks.len(),
+            breaches,
+        });
+    }
+    Ok(report)
+}
+
+fn describe(breach: &Breach) -> String {
+    let due = Local
+        .timestamp_opt(breach.due, 0)
+        .single()
+        .map(|dt| dt.format("%m/%d %H:%M").to_string())
+        .unwrap_or_default();
+    let late = if breach.late >= 86400 {
+        format!("{}d", breach.late / 86400)
+    } else {
+        format!("{}h", (breach.late + 3599) / 3600)
+    };
+    let state = if breach.task.status == 1 { "done" } else { "open" };
+    format!("{} {} late, due {}", state, late, due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+        update_status,
+    };
+
+    #[test]
+    fn test_check_slas() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now().timestamp();
+        let quick = insert_task(&conn, "support", "reset password", "today");
+        let slow = insert_task(&conn, "support", "refund order", "today");
+        let open = insert_task(&conn, "support", "broken login", "today");
+        let fresh = insert_task(&conn, "support", "new ticket", "today");
+        let cancelled = insert_task(&conn, "support", "spam", "today");
+        update_status(&conn, cancelled, 2);
+        insert_task(&conn, "work", "quarterly plan", "today");
+        let set_times = |id: i64, created: i64, modified: i64| {
+            conn.execute(
+                "UPDATE items SET create_time = ?1, modify_time = ?2 WHERE id = ?3",
+                (now - created * 3600, now - modified * 3600, id),
+            )
+            .unwrap();
+        };
+        update_status(&conn, quick, 1);
+        update_status(&conn, slow, 1);
+        set_times(open, 60, 60);
+        set_times(cancelled, 100, 100);
+        set_times(quick, 100, 90);
+        set_times(slow, 100, 10);
+
+        let slas = vec![("support".to_string(), "24h".to_string())];
+        let report = check_slas(&conn, &slas, now - 30 * 86400, now).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].total, 4);
+        let breached: Vec<(i64, i64)> = report[0]
+            .breaches
+            .iter()
+            .map(|b| (b.task.id.unwrap(), b.late / 3600))
+            .collect();
+        // Done 66 hours late, still open 36 hours after the window closed
+        assert_eq!(breached, vec![(slow, 66), (open, 36)]);
+        assert!(describe(&report[0].breaches[0]).starts_with("done 2d late, due "));
+        assert!(!breached.iter().any(|(id, _)| *id == fresh || *id == quick));
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::StatusReportCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        history::{
+            get_status_history,
+            StatusChange,
+        },
+        item::{
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+const ALL_CATEGORIES: &str = "all categories";
+
+// Seconds spent in a status and how many tasks spent any time in it
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct StatusTime {
+    seconds: i64,
+    tasks: usize,
+}
+
+type StatusTimes = BTreeMap<String, BTreeMap<u8, StatusTime>>;
+
+pub fn handle_statuscmd(conn: &Connection, cmd: &StatusReportCommand) -> Result<(), TascliError> {
+    let now = clock::timestamp();
+    let since = now - cmd.days as i64 * 86400;
+
+    let mut query = ItemQuery::new().with_visible_to(current_user()).with_action(TASK);
+    if let Some(c) = &cmd.category {
+        query = query.with_category(c);
+    }
+    let tasks = query_items(conn, &query)?;
+    let categories: HashMap<i64, String> = tasks
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.category)))
+        .collect();
+    let ids: Vec<i64> = categories.keys().copied().collect();
+    let history = get_status_history(conn, &ids)?;
+
+    let times = time_in_status(&history, &categories, since, now);
+    if times.is_empty() {
+        display::print_bold(&format!("No open task time in the last {} days", cmd.days));
+        return Ok(());
+    }
+
+    display::print_bold(&format!(
+        "Average days in each status over the last {} days\n",
+        cmd.days
+    ));
+    for (category, statuses) in &times {
+        let rows: Vec<(String, f64)> = statuses
+            .iter()
+            .map(|(status, time)| {
+                (
+                    format!("{} ({} tasks)", display::translate_status(*status), time.tasks),
+                    time.seconds as f64 / time.tasks as f64 / 86400.0,
+                )
+            })
+            .collect();
+        display::print_bar_chart(category, &rows);
+    }
+    Ok(())
+}
+
+// Time spent in open statuses within [since, now], per category and in total.
+// Closed statuses are where tasks end up, the time after closing is not counted.
+fn time_in_status(
+    history: &[StatusChange],
+    categories: &HashMap<i64, String>,
+    since: i64,
+    now: i64,
+) -> StatusTimes {
+    let mut times = StatusTimes::new();
+    let mut counted = HashSet::new();
+    for (i, change) in history.iter().enumerate() {
+        if !OPEN_STATUS_CODES.contains(&change.status) {
+            continue;
+        }
+        let Some(category) = categories.get(&change.item_id) else {
+            continue;
+        };
+        let end = match history.get(i + 1) {
+            Some(next) if next.item_id == change.item_id => next.change_time,
+            _ => now,
+        };
+        let seconds = end.min(now) - change.change_time.max(since);
+        if seconds <= 0 {
+            continue;
+        }
+
+        // A task counts once per status even if it re-entered it
+        let first_time = counted.insert((change.item_id, change.status));
+        for key in [category.as_str(), ALL_CATEGORIES] {
+            let time = times
+                .entry(key.to_string())
+                .or_default()
+                .entry(change.status)
+                .or_default();
+            time.seconds += seconds;
+            if first_time {
+                time.tasks += 1;
+            }
+        }
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn change(item_id: i64, status: u8, day: i64) -> StatusChange {
+        StatusChange {
+            item_id,
+            status,
+            change_time: day * DAY,
+        }
+    }
+
+    #[test]
+    fn test_time_in_status() {
+        let categories = HashMap::from([
+            (1, "work".to_string()),
+            (2, "work".to_string()),
+            (3, "home".to_string()),
+        ]);
+        let history = vec![
+            // ongoing 2 days, pending 3 days, done
+            change(1, 0, 10),
+            change(1, 6, 12),
+            change(1, 1, 15),
+            // ongoing since before the window, still open
+            change(2, 0, 5),
+            // ongoing, suspended, ongoing again, done
+            change(3, 0, 16),
+            change(3, 4, 17),
+            change(3, 0, 18),
+            change(3, 1, 19),
+        ];
+
+        let times = time_in_status(&history, &categories, 8 * DAY, 20 * DAY);
+        let work = &times["work"];
+        assert_eq!(work[&0], StatusTime { seconds: (2 + 12) * DAY, tasks: 2 });
+        assert_eq!(work[&6], StatusTime { seconds: 3 * DAY, tasks: 1 });
+        let home = &times["home"];
+        assert_eq!(home[&0], StatusTime { seconds: 2 * DAY, tasks: 1 });
+        assert_eq!(home[&4], StatusTime { seconds: DAY, tasks: 1 });
+        assert_eq!(times[ALL_CATEGORIES][&0], StatusTime { seconds: 16 * DAY, tasks: 3 });
+        assert!(!work.contains_key(&1));
+    }
+
+    #[test]
+    fn test_time_in_status_outside_window() {
+        let categories = HashMap::from([(1, "work".to_string())]);
+        let history = vec![change(1, 0, 1), change(1, 1, 2)];
+        assert!(time_in_status(&history, &categories, 5 * DAY, 10 * DAY).is_empty());
+    }
+}
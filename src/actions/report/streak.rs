@@ -0,0 +1,148 @@
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        habit::{
+            habit_periods,
+            Period,
+        },
+    },
+    args::parser::StreakCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            ItemQuery,
+            RECURRING_TASK,
+        },
+    },
+    error::TascliError,
+    private,
+};
+
+pub fn handle_streakcmd(conn: &Connection, cmd: &StreakCommand) -> Result<(), TascliError> {
+    let now = clock::timestamp();
+    let mut query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(RECURRING_TASK)
+        .with_statuses(vec![0]);
+    if let Some(c) = &cmd.category {
+        query = query.with_category(c);
+    }
+    let habits = query_items(conn, &query)?;
+    if habits.is_empty() {
+        display::print_bold("No habits to report");
+        return Ok(());
+    }
+
+    for habit in &habits {
+        let periods = habit_periods(conn, habit, now)?;
+        let recent = &periods[periods.len().saturating_sub(cmd.periods.max(1))..];
+        let done = recent.iter().filter(|(_, p)| *p == Period::Done).count();
+        let expected = recent.iter().filter(|(_, p)| *p != Period::Open).count();
+        display::print_bold(&format!(
+            "{} ({}): streak {}, best {}, {} of {} done",
+            private::reveal(&habit.content),
+            habit.human_schedule.as_deref().unwrap_or_default(),
+            current_streak(&periods),
+            best_streak(&periods),
+            done,
+            expected
+        ));
+        if display::is_plain() {
+            let missed: Vec<String> = recent
+                .iter()
+                .filter(|(_, p)| *p == Period::Missed)
+                .filter_map(|(end, _)| Local.timestamp_opt(end - 1, 0).single())
+                .map(|dt| dt.format("%Y/%m/%d").to_string())
+                .collect();
+            if !missed.is_empty() {
+                println!("Missed: {}", missed.join(", "));
+            }
+        } else {
+            println!("{}", pattern(recent));
+        }
+    }
+    Ok(())
+}
+
+// Done periods in a row up to now, skips pass over without counting and
+// the open period only counts once it is done.
+fn current_streak(periods: &[(i64, Period)]) -> usize {
+    let mut streak = 0;
+    for (_, period) in periods.iter().rev() {
+        match period {
+            Period::Done => streak += 1,
+            Period::Missed => break,
+            Period::Skipped | Period::Open => {}
+        }
+    }
+    streak
+}
+
+fn best_streak(periods: &[(i64, Period)]) -> usize {
+    let mut best = 0;
+    let mut streak = 0;
+    for (_, period) in periods {
+        match period {
+            Period::Done => {
+                streak += 1;
+                best = best.max(streak);
+            }
+            Period::Missed => streak = 0,
+            Period::Skipped | Period::Open => {}
+        }
+    }
+    best
+}
+
+// One mark per period, oldest first
+fn pattern(periods: &[(i64, Period)]) -> String {
+    periods
+        .iter()
+        .map(|(_, period)| match period {
+            Period::Done => '✓',
+            Period::Missed => '✗',
+            Period::Skipped => '-',
+            Period::Open => '·',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Period::*;
+
+    fn periods(list: &[Period]) -> Vec<(i64, Period)> {
+        list.iter()
+            .enumerate()
+            .map(|(i, p)| (i as i64, *p))
+            .collect()
+    }
+
+    #[test]
+    fn test_current_streak() {
+        assert_eq!(current_streak(&periods(&[Done, Missed, Done, Skipped, Done, Open])), 2);
+        assert_eq!(current_streak(&periods(&[Done, Done, Missed, Open])), 0);
+        assert_eq!(current_streak(&periods(&[Done, Done, Done])), 3);
+        assert_eq!(current_streak(&[]), 0);
+    }
+
+    #[test]
+    fn test_best_streak() {
+        assert_eq!(best_streak(&periods(&[Done, Done, Skipped, Done, Missed, Done, Open])), 3);
+        assert_eq!(best_streak(&periods(&[Missed, Skipped, Open])), 0);
+    }
+
+    #[test]
+    fn test_pattern() {
+        assert_eq!(pattern(&periods(&[Done, Missed, Skipped, Open])), "✓✗-·");
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{
+    Local,
+    NaiveDate,
+    TimeZone,
+    Timelike,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        focus::MINUTES_UNIT,
+    },
+    args::{
+        parser::SwitchesReportCommand,
+        timestr,
+    },
+    config::current_user,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+        },
+    },
+    error::TascliError,
+};
+
+// Sessions in one category with no more than this between them make one focus block
+const MAX_BREAK: i64 = 15 * 60;
+
+// Time spent on one category without switching to another
+struct Block {
+    category: String,
+    start: i64,
+    end: i64,
+    sessions: usize,
+}
+
+pub fn handle_switchescmd(
+    conn: &Connection,
+    cmd: &SwitchesReportCommand,
+) -> Result<(), TascliError> {
+    let date = timestr::parse_flexible_timestr(&cmd.date)?.date();
+    let sessions = day_sessions(conn, date)?;
+    let blocks = focus_blocks(&sessions);
+    let title = date.format("%a %Y/%m/%d");
+    if blocks.is_empty() {
+        display::print_bold(&format!("No focus sessions on {}", title));
+        return Ok(());
+    }
+
+    let tracked: i64 =
+        sessions.iter().map(|s| (s.value.unwrap_or_default() * 60.0) as i64).sum();
+    let switches = switch_times(&blocks);
+    display::print_bold(&format!(
+        "{}: {} focus sessions, {} tracked",
+        title,
+        sessions.len(),
+        format_span(tracked)
+    ));
+    let rate = switches.len() as f64 / (tracked as f64 / 3600.0).max(1.0);
+    println!("Context switches: {}, {:.1} per hour tracked", switches.len(), rate);
+    if let Some(longest) = blocks.iter().max_by_key(|b| b.end - b.start) {
+        println!(
+            "Longest focus block: {} {}-{} ({})",
+            longest.category,
+            format_clock(longest.start),
+            format_clock(longest.end),
+            format_span(longest.end - longest.start)
+        );
+    }
+    if switches.is_empty() {
+        return Ok(());
+    }
+
+    let mut per_hour = [0usize; 24];
+    for time in &switches {
+        if let Some(dt) = Local.timestamp_opt(*time, 0).single() {
+            per_hour[dt.hour() as usize] += 1;
+        }
+    }
+    let first = per_hour.iter().position(|n| *n > 0).unwrap_or(0);
+    let last = per_hour.iter().rposition(|n| *n > 0).unwrap_or(23);
+    let rows: Vec<(String, f64)> = (first..=last)
+        .map(|hour| (format!("{:02}:00", hour), per_hour[hour] as f64))
+        .collect();
+    println!();
+    display::print_bar_chart("Switches per hour", &rows);
+    Ok(())
+}
+
+// Focus session records of a day, a session ends when its record was written
+fn day_sessions(conn: &Connection, date: NaiveDate) -> Result<Vec<Item>, TascliError> {
+    let start = Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_default();
+    let query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(RECORD)
+        .with_create_time_range(Some(start), Some(start + 24 * 60 * 60));
+    Ok(query_items(conn, &query)?
+        .into_iter()
+        .filter(|r| r.unit.as_deref() == Some(MINUTES_UNIT) && r.value.is_some())
+        .collect())
+}
+
+// Back to back sessions of one category are merged, in the order they started
+fn focus_blocks(sessions: &[Item]) -> Vec<Block> {
+    let mut spans: Vec<(i64, i64, &str)> = sessions
+        .iter()
+        .map(|s| {
+            let minutes = s.value.unwrap_or_default() as i64;
+            (s.create_time - minutes * 60, s.create_time, s.category.as_str())
+        })
+        .collect();
+    spans.sort();
+    let mut blocks: Vec<Block> = Vec::new();
+    for (start, end, category) in spans {
+        match blocks.last_mut() {
+            Some(last) if last.category == category && start - last.end <= MAX_BREAK => {
+                last.end = last.end.max(end);
+                last.sessions += 1;
+            }
+            _ => blocks.push(Block {
+                category: category.to_string(),
+                start,
+                end,
+                sessions: 1,
+            }),
+        }
+    }
+    blocks
+}
+
+// Starts of the blocks that follow a block of another category
+fn switch_times(blocks: &[Block]) -> Vec<i64> {
+    blocks
+        .windows(2)
+        .filter(|pair| pair[0].category != pair[1].category)
+        .map(|pair| pair[1].start)
+        .collect()
+}
+
+fn format_clock(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn format_span(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A session of `minutes` ending `end` minutes into the day
+    fn session(category: &str, end: i64, minutes: f64) -> Item {
+        let mut record = Item::with_create_time(
+            RECORD.to_string(),
+            category.to_string(),
+            "session".to_string(),
+            end * 60,
+        );
+        record.value = Some(minutes);
+        record.unit = Some(MINUTES_UNIT.to_string());
+        record
+    }
+
+    #[test]
+    fn test_focus_blocks() {
+        let sessions = vec![
+            session("work", 560, 25.0),
+            session("work", 590, 25.0),
+            session("mail", 600, 10.0),
+            session("work", 700, 25.0),
+            // Same category after a long break, a new block but not a switch
+            session("work", 800, 25.0),
+        ];
+        let blocks = focus_blocks(&sessions);
+        let spans: Vec<(&str, i64, i64, usize)> = blocks
+            .iter()
+            .map(|b| (b.category.as_str(), b.start / 60, b.end / 60, b.sessions))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("work", 535, 590, 2),
+                ("mail", 590, 600, 1),
+                ("work", 675, 700, 1),
+                ("work", 775, 800, 1),
+            ]
+        );
+        let switches: Vec<i64> = switch_times(&blocks).iter().map(|t| t / 60).collect();
+        assert_eq!(switches, vec![590, 675]);
+        assert_eq!(format_span(55 * 60), "55m");
+        assert_eq!(format_span(100 * 60), "1h 40m");
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::VelocityCommand,
+    clock,
+    config::current_user,
+    db::{
+        crud::query_items,
+        history::get_status_history,
+        item::{
+            ItemQuery,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+#[derive(Debug, Default, PartialEq)]
+struct Velocity {
+    completed: usize,
+    open: usize,
+}
+
+impl Velocity {
+    fn per_week(&self, weeks: usize) -> f64 {
+        self.completed as f64 / weeks.max(1) as f64
+    }
+
+    // None when nothing was completed, the backlog is not moving.
+    fn eta(&self, weeks: usize, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let pace = self.per_week(weeks);
+        if pace == 0.0 {
+            return None;
+        }
+        let days = (self.open as f64 / pace * 7.0).ceil() as i64;
+        Some(now + Duration::days(days))
+    }
+}
+
+pub fn handle_velocitycmd(conn: &Connection, cmd: &VelocityCommand) -> Result<(), TascliError> {
+    let now = clock::now();
+    let weeks = cmd.weeks.max(1);
+    let since = (now - Duration::weeks(weeks as i64)).timestamp();
+
+    let mut done = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(vec![1]);
+    let mut open = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec());
+    if let Some(c) = &cmd.category {
+        done = done.with_category(c);
+        open = open.with_category(c);
+    }
+
+    let mut velocities: BTreeMap<String, Velocity> = BTreeMap::new();
+    // Tasks were completed when they last entered the done status
+    let tasks = query_items(conn, &done)?;
+    let ids: Vec<i64> = tasks.iter().filter_map(|t| t.id).collect();
+    let history = get_status_history(conn, &ids)?;
+    for task in &tasks {
+        let done_at = history
+            .iter()
+            .filter(|c| Some(c.item_id) == task.id && c.status == 1)
+            .map(|c| c.change_time)
+            .next_back();
+        if done_at.is_some_and(|t| t > since) {
+            velocities
+                .entry(task.category.clone())
+                .or_default()
+                .completed += 1;
+        }
+    }
+    for task in query_items(conn, &open)? {
+        velocities.entry(task.category).or_default().open += 1;
+    }
+
+    if velocities.is_empty() {
+        display::print_bold("No tasks to report");
+        return Ok(());
+    }
+    display::print_bold(&format!(
+        "Completions per week over the last {} weeks:",
+        weeks
+    ));
+    for line in format_table(&velocities, weeks, cmd.min_open, now) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn format_table(
+    velocities: &BTreeMap<String, Velocity>,
+    weeks: usize,
+    min_open: usize,
+    now: DateTime<Local>,
+) -> Vec<String> {
+    let category_width = velocities
+        .keys()
+        .map(|c| c.chars().count())
+        .chain(["Category".len()])
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![format!(
+        "{:<width$}{:>9}{:>7}  Estimate",
+        "Category",
+        "Done/wk",
+        "Open",
+        width = category_width
+    )];
+    for (category, velocity) in velocities {
+        let estimate = if velocity.open < min_open {
+            String::new()
+        } else {
+            match velocity.eta(weeks, now) {
+                Some(eta) => format!("at current pace, done by {}", eta.format("%Y/%m/%d")),
+                None => "no completions, not moving".to_string(),
+            }
+        };
+        lines.push(
+            format!(
+                "{:<width$}{:>9.1}{:>7}  {}",
+                category,
+                velocity.per_week(weeks),
+                velocity.open,
+                estimate,
+                width = category_width
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+        update_status,
+    };
+
+    #[test]
+    fn test_eta() {
+        let now = Local.with_ymd_and_hms(2025, 3, 19, 9, 0, 0).unwrap();
+        let velocity = Velocity {
+            completed: 8,
+            open: 10,
+        };
+        assert_eq!(velocity.per_week(4), 2.0);
+        // 5 weeks of work left
+        assert_eq!(velocity.eta(4, now), Some(now + Duration::days(35)));
+
+        let stalled = Velocity {
+            completed: 0,
+            open: 10,
+        };
+        assert_eq!(stalled.eta(4, now), None);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let now = Local.with_ymd_and_hms(2025, 3, 19, 9, 0, 0).unwrap();
+        let mut velocities = BTreeMap::new();
+        velocities.insert(
+            "home".to_string(),
+            Velocity {
+                completed: 1,
+                open: 2,
+            },
+        );
+        velocities.insert(
+            "work".to_string(),
+            Velocity {
+                completed: 4,
+                open: 12,
+            },
+        );
+        velocities.insert(
+            "someday".to_string(),
+            Velocity {
+                completed: 0,
+                open: 30,
+            },
+        );
+        assert_eq!(
+            format_table(&velocities, 2, 10, now),
+            vec![
+                "Category  Done/wk   Open  Estimate",
+                "home          0.5      2",
+                "someday       0.0     30  no completions, not moving",
+                "work          2.0     12  at current pace, done by 2025/04/30",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_handle_velocitycmd() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "open task", "tomorrow");
+        let done = insert_task(&conn, "work", "done task", "today");
+        update_status(&conn, done, 1);
+
+        let cmd = VelocityCommand {
+            weeks: 4,
+            category: None,
+            min_open: 1,
+        };
+        handle_velocitycmd(&conn, &cmd).unwrap();
+    }
+}
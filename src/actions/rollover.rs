@@ -0,0 +1,84 @@
+use std::io::{
+    self,
+    IsTerminal,
+};
+
+use chrono::{
+    DateTime,
+    Local,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        digest::build_digest,
+        display,
+    },
+    clock,
+    config,
+    db::state::{
+        get_state,
+        set_state,
+    },
+    error::TascliError,
+};
+
+// State key of the day tascli last ran on
+const LAST_RUN_DAY: &str = "last_run_day";
+
+/// On the first run of each day, prints how many overdue tasks were carried over and how many
+/// are due today, when `rollover_banner` is on in config. Output that is not a terminal, such
+/// as cron jobs, leaves the banner for the next interactive run.
+pub fn print_banner(conn: &Connection) {
+    if !config::get_config().is_ok_and(|c| c.rollover_banner)
+        || !io::stdout().is_terminal()
+        || display::is_porcelain()
+    {
+        return;
+    }
+    match rollover_banner(conn, clock::now()) {
+        Ok(Some(banner)) => display::print_bold(&banner),
+        Ok(None) => {}
+        Err(e) => display::print_yellow(&format!("Daily summary failed: {}", e)),
+    }
+}
+
+// None when tascli already ran today
+fn rollover_banner(conn: &Connection, now: DateTime<Local>) -> Result<Option<String>, TascliError> {
+    let today = now.format("%Y-%m-%d").to_string();
+    if get_state(conn, LAST_RUN_DAY)?.as_deref() == Some(today.as_str()) {
+        return Ok(None);
+    }
+    set_state(conn, LAST_RUN_DAY, &today)?;
+    let digest = build_digest(conn, None, now.timestamp())?;
+    Ok(Some(format!(
+        "Carried over: {} overdue, due today: {}",
+        digest.overdue.len(),
+        digest.due_today.len()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+    };
+
+    #[test]
+    fn test_rollover_banner() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "late report", "yesterday");
+        insert_task(&conn, "work", "call back", "today");
+        insert_task(&conn, "work", "plan", "tomorrow");
+        let now = Local::now();
+
+        let banner = rollover_banner(&conn, now).unwrap();
+        assert_eq!(banner.as_deref(), Some("Carried over: 1 overdue, due today: 1"));
+        assert_eq!(rollover_banner(&conn, now).unwrap(), None);
+        assert!(rollover_banner(&conn, now + Duration::days(1)).unwrap().is_some());
+    }
+}
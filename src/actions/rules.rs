@@ -0,0 +1,47 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::parser::RulesCommand,
+    clock,
+    config,
+    error::TascliError,
+    rules,
+};
+
+pub fn handle_rulescmd(conn: &Connection, cmd: RulesCommand) -> Result<(), TascliError> {
+    let config = config::get_config()?;
+    if config.rules.is_empty() {
+        return Err(TascliError::Config(
+            "No rules configured, add a \"rules\" list to config".to_string(),
+        ));
+    }
+
+    match cmd {
+        RulesCommand::List => {
+            let mut invalid = 0;
+            for text in &config.rules {
+                match rules::parse_rule(text) {
+                    Ok(_) => display::print_green(&format!("✓ {}", text)),
+                    Err(e) => {
+                        display::print_red(&format!("✗ {}: {}", text, e));
+                        invalid += 1;
+                    }
+                }
+            }
+            if invalid > 0 {
+                return Err(TascliError::Config(format!(
+                    "{} of {} rules are invalid",
+                    invalid,
+                    config.rules.len()
+                )));
+            }
+            Ok(())
+        }
+        RulesCommand::Run => {
+            let fired = rules::run_overdue_rules(conn, &config, clock::timestamp())?;
+            display::print_bold(&format!("Overdue rules fired {} times", fired));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::io;
+
+use chrono::{
+    DateTime,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            self,
+            print_table,
+            DisplayRow,
+        },
+        forecast::count_per_day,
+        plan::{
+            assign,
+            unplanned,
+        },
+        triage::prompt,
+    },
+    args::parser::ScheduleCommand,
+    clock,
+    config::{
+        self,
+        holidays,
+    },
+    db::item::Item,
+    error::TascliError,
+    private,
+};
+
+// Days after a full deadline day searched for one with room
+const LOOKAHEAD_DAYS: usize = 30;
+
+pub fn handle_schedulecmd(conn: &Connection, cmd: &ScheduleCommand) -> Result<(), TascliError> {
+    let now = clock::now();
+    let capacity = cmd.capacity.unwrap_or_else(config::daily_capacity);
+    let mut days = count_per_day(conn, cmd.category.as_deref(), now, cmd.days)?;
+    let items = unplanned(conn, cmd.category.as_deref(), now.timestamp())?;
+    if !cmd.auto {
+        print_load(&days, capacity);
+        if !items.is_empty() {
+            display::print_bold(&format!(
+                "{} overdue tasks and inbox items, `tascli schedule --auto` spreads them out",
+                items.len()
+            ));
+        }
+        return Ok(());
+    }
+    if items.is_empty() {
+        display::print_bold("Nothing to schedule, no overdue tasks or inbox items");
+        return Ok(());
+    }
+
+    // Nothing is moved onto a day off
+    days.retain(|(date, _)| !holidays::is_holiday(*date));
+    let slots = distribute(items.len(), &mut days, capacity);
+    let proposal: Vec<(Item, NaiveDate)> = items
+        .into_iter()
+        .zip(&slots)
+        .filter_map(|(item, slot)| Some((item, days[(*slot)?].0)))
+        .collect();
+    let full = slots.len() - proposal.len();
+    if full > 0 {
+        display::print_yellow(&format!(
+            "{} items stay unscheduled, the next {} days are full at {} tasks a day",
+            full, cmd.days, capacity
+        ));
+    }
+    if proposal.is_empty() {
+        return Ok(());
+    }
+
+    display::print_bold("Proposed:");
+    let rows: Vec<DisplayRow> = proposal
+        .iter()
+        .enumerate()
+        .map(|(i, (item, date))| DisplayRow {
+            index: (i + 1).to_string(),
+            category: item.category.clone(),
+            category_color: None,
+            content: private::reveal(&item.content),
+            timestr: date.format("%a %Y/%m/%d").to_string(),
+            timestr_color: None,
+            tags: String::new(),
+            depth: 0,
+        })
+        .collect();
+    print_table(&rows, false);
+    if !cmd.yes {
+        let question = format!("Schedule these {} items? (y/N)", proposal.len());
+        let answer = prompt(&mut io::stdin().lock(), &question)?.unwrap_or_default();
+        if !answer.eq_ignore_ascii_case("y") {
+            display::print_bold("Nothing scheduled");
+            return Ok(());
+        }
+    }
+
+    let mut scheduled = 0;
+    for (mut item, date) in proposal {
+        match assign(conn, &mut item, &date.format("%Y/%m/%d").to_string()) {
+            Ok(()) => scheduled += 1,
+            Err(e) => display::print_red(&e.to_string()),
+        }
+    }
+    display::print_green(&format!("Scheduled {} items", scheduled));
+    Ok(())
+}
+
+// The earliest day below capacity for each of `count` items in turn, None once all days are
+// full. The loads of the days grow with the items given to them.
+fn distribute(
+    count: usize,
+    days: &mut [(NaiveDate, usize)],
+    capacity: usize,
+) -> Vec<Option<usize>> {
+    (0..count)
+        .map(|_| {
+            let day = days.iter().position(|(_, load)| *load < capacity)?;
+            days[day].1 += 1;
+            Some(day)
+        })
+        .collect()
+}
+
+/// Warns when the day of a new deadline already has a full day of tasks due,
+/// naming the next day with room.
+pub fn check_capacity(conn: &Connection, target_time: i64) -> Result<(), TascliError> {
+    let warning = capacity_warning(conn, target_time, config::daily_capacity(), clock::now())?;
+    if let Some(warning) = warning {
+        display::print_yellow(&warning);
+    }
+    Ok(())
+}
+
+fn capacity_warning(
+    conn: &Connection,
+    target_time: i64,
+    capacity: usize,
+    now: DateTime<Local>,
+) -> Result<Option<String>, TascliError> {
+    let Some(deadline) = Local.timestamp_opt(target_time, 0).single() else {
+        return Ok(None);
+    };
+    if deadline < now {
+        return Ok(None);
+    }
+    // Counted from the start of the deadline's day, or from now for a deadline today
+    let start = Local
+        .from_local_datetime(&deadline.date_naive().and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .unwrap_or(now)
+        .max(now);
+    let days = count_per_day(conn, None, start, LOOKAHEAD_DAYS + 1)?;
+    let (date, load) = days[0];
+    if load < capacity {
+        return Ok(None);
+    }
+    let next = days.iter().find(|(day, load)| *load < capacity && !holidays::is_holiday(*day));
+    let next = match next {
+        Some((free, _)) => format!("{} is the next day with room", free.format("%a %Y/%m/%d")),
+        None => format!("the {} days after it are full too", LOOKAHEAD_DAYS),
+    };
+    Ok(Some(format!(
+        "{} already has {} tasks due, {}",
+        date.format("%a %Y/%m/%d"),
+        load,
+        next
+    )))
+}
+
+fn print_load(days: &[(NaiveDate, usize)], capacity: usize) {
+    let rows: Vec<(String, f64)> = days
+        .iter()
+        .map(|(date, load)| {
+            let mut label = date.format("%a %m/%d").to_string();
+            if holidays::is_holiday(*date) {
+                label.push_str(" (holiday)");
+            } else if *load >= capacity {
+                label.push_str(" (full)");
+            }
+            (label, *load as f64)
+        })
+        .collect();
+    display::print_bar_chart(&format!("Tasks due per day, {} fit in a day", capacity), &rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        args::timestr,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_distribute() {
+        let today = Local::now().date_naive();
+        let mut days: Vec<(NaiveDate, usize)> = [2, 0, 3]
+            .iter()
+            .enumerate()
+            .map(|(d, load)| (today + Duration::days(d as i64), *load))
+            .collect();
+
+        let slots = distribute(5, &mut days, 3);
+        assert_eq!(slots, vec![Some(0), Some(1), Some(1), Some(1), None]);
+        assert_eq!(days.iter().map(|(_, n)| *n).collect::<Vec<_>>(), vec![3, 3, 3]);
+    }
+
+    #[test]
+    fn test_capacity_warning() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now();
+        let day = |d: i64| (now.date_naive() + Duration::days(d)).format("%Y/%m/%d").to_string();
+        insert_task(&conn, "work", "draft", &day(1));
+        insert_task(&conn, "home", "groceries", &day(1));
+        insert_task(&conn, "work", "review", &day(2));
+        let deadline = |d: i64| timestr::to_unix_epoch(&day(d)).unwrap();
+
+        assert_eq!(capacity_warning(&conn, deadline(1), 3, now).unwrap(), None);
+        let warning = capacity_warning(&conn, deadline(1), 2, now).unwrap().unwrap();
+        assert!(warning.contains("already has 2 tasks due"), "{}", warning);
+        let day_after = (now.date_naive() + Duration::days(2)).format("%a %Y/%m/%d");
+        assert!(warning.ends_with(&format!("{} is the next day with room", day_after)));
+        let warning = capacity_warning(&conn, deadline(1), 1, now).unwrap().unwrap();
+        assert!(warning.contains(&day(3)), "{}", warning);
+        assert_eq!(capacity_warning(&conn, deadline(-1), 1, now).unwrap(), None);
+    }
+}
@@ -0,0 +1,43 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::parser::ScriptCommand,
+    config,
+    error::TascliError,
+    scripting,
+};
+
+pub fn handle_scriptcmd(conn: &Connection, cmd: ScriptCommand) -> Result<(), TascliError> {
+    let config = config::get_config()?;
+    if config.scripts.is_empty() {
+        return Err(TascliError::Config(
+            "No scripts configured, add a \"scripts\" list to config".to_string(),
+        ));
+    }
+
+    match cmd {
+        ScriptCommand::List => {
+            for script in &config.scripts {
+                let command = if script.command.is_empty() {
+                    String::new()
+                } else {
+                    format!(" as `tascli {}`", script.command)
+                };
+                display::print_bold(&format!(
+                    "{} on [{}]{}",
+                    script.path,
+                    script.events.join(", "),
+                    command
+                ));
+            }
+            Ok(())
+        }
+        ScriptCommand::Run { name, args } => {
+            let script = scripting::find_command(&config, &name).ok_or_else(|| {
+                TascliError::Config(format!("No script is registered as command '{}'", name))
+            })?;
+            scripting::run_command(conn, script, &args)
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::{
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            self,
+            format_value,
+            translate_status,
+        },
+        modify::resolve_or_pick,
+        remind,
+    },
+    args::parser::ShowCommand,
+    config::current_user,
+    db::{
+        crud::{
+            get_item,
+            query_items,
+        },
+        history::get_status_history,
+        item::{
+            Item,
+            ItemQuery,
+            INBOX,
+            RECORD,
+            RECURRING_TASK,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+        projects::get_project,
+        reminders::get_reminders,
+    },
+    error::TascliError,
+    private,
+};
+
+// Completions of a recurring task listed by show, older ones are only counted
+const RECENT_COMPLETIONS: usize = 5;
+
+pub fn handle_showcmd(conn: &Connection, cmd: &ShowCommand) -> Result<(), TascliError> {
+    let item_id = resolve_or_pick(conn, cmd.index.as_ref())?;
+    let item = get_item(conn, item_id)?;
+    let user = current_user();
+    // Items of other users of a shared database stay out of sight, as in listings
+    if let (Some(user), Some(owner)) = (user, item.owner.as_deref())
+        && owner != user
+    {
+        return Err(TascliError::Invalid(format!("The item belongs to {}", owner)));
+    }
+    display::print_bold(&private::reveal(&item.content));
+    let lines = details(conn, &item, user)?;
+    let width = lines.iter().map(|(label, _)| label.len()).max().unwrap_or_default() + 2;
+    for (label, value) in lines {
+        let label = if label.is_empty() { String::new() } else { format!("{}:", label) };
+        println!("{:<width$}{}", label, value, width = width);
+    }
+    Ok(())
+}
+
+// Labeled lines about the item, an empty label continues the line above
+fn details(
+    conn: &Connection,
+    item: &Item,
+    user: Option<&str>,
+) -> Result<Vec<(&'static str, String)>, TascliError> {
+    let mut lines = vec![
+        ("Id", item.uuid.clone()),
+        ("Type", kind(&item.action).to_string()),
+        ("Category", item.category.clone()),
+    ];
+    match item.action.as_str() {
+        TASK => {
+            lines.push(("Status", translate_status(item.status)));
+            if let Some(target_time) = item.target_time {
+                let deadline = if item.all_day {
+                    format!("{}, all day", format_date(target_time))
+                } else {
+                    format_time(target_time)
+                };
+                lines.push(("Deadline", deadline));
+            }
+        }
+        RECURRING_TASK => {
+            lines.push(("Status", translate_status(item.status)));
+            let schedule = item.human_schedule.clone().unwrap_or_default();
+            lines.push(("Schedule", schedule));
+        }
+        _ => {}
+    }
+    if let Some(value) = item.value {
+        lines.push(("Value", format_value(value, item.unit.as_deref())));
+    }
+    let answers = item.field_answers();
+    if !answers.is_empty() {
+        let fields: Vec<String> = answers.iter().map(|(f, a)| format!("{}: {}", f, a)).collect();
+        lines.push(("Fields", fields.join(", ")));
+    }
+    if let Some(owner) = &item.owner {
+        lines.push(("Owner", owner.clone()));
+    }
+    lines.push(("Created", format_time(item.create_time)));
+    if let Some(modify_time) = item.modify_time {
+        lines.push(("Modified", format_time(modify_time)));
+    }
+    if let Some(project) = get_project(conn, &item.category)? {
+        lines.push(("Project end", format_time(project.end_time)));
+    }
+
+    let item_id = item.id.unwrap_or_default();
+    let reminders = get_reminders(conn, item_id)?;
+    if !reminders.is_empty() {
+        lines.push(("Reminders", remind::describe(&reminders)));
+    }
+    if let Some(task_id) = item.recurring_task_id {
+        let task = get_item(conn, task_id)?;
+        let line = format!("{} ({})", private::reveal(&task.content), task.short_id());
+        lines.push(("Recurring task", line));
+        if item.status == 2 {
+            lines.push(("Status", "skipped".to_string()));
+        }
+    }
+    if item.action == RECURRING_TASK {
+        lines.extend(completions(conn, item_id, user)?);
+    }
+
+    let history = get_status_history(conn, &[item_id])?;
+    for (i, change) in history.iter().enumerate() {
+        let label = if i == 0 { "History" } else { "" };
+        let status = translate_status(change.status);
+        lines.push((label, format!("{} {}", format_time(change.change_time), status)));
+    }
+    Ok(lines)
+}
+
+fn completions(
+    conn: &Connection,
+    task_id: i64,
+    user: Option<&str>,
+) -> Result<Vec<(&'static str, String)>, TascliError> {
+    let query = ItemQuery::new()
+        .with_visible_to(user)
+        .with_action(RECURRING_TASK_RECORD)
+        .with_recurring_task_id(task_id)
+        .with_order_by("create_time");
+    let records = query_items(conn, &query)?;
+    if records.is_empty() {
+        return Ok(vec![("Completions", "none".to_string())]);
+    }
+    let mut lines = vec![("Completions", records.len().to_string())];
+    for record in records.iter().rev().take(RECENT_COMPLETIONS) {
+        let skipped = if record.status == 2 { " (skipped)" } else { "" };
+        lines.push(("", format!("{}{}", format_time(record.create_time), skipped)));
+    }
+    Ok(lines)
+}
+
+fn kind(action: &str) -> &'static str {
+    match action {
+        TASK => "task",
+        RECORD => "record",
+        RECURRING_TASK => "recurring task",
+        RECURRING_TASK_RECORD => "recurring task record",
+        INBOX => "inbox item",
+        _ => "unknown",
+    }
+}
+
+fn format_date(time: i64) -> String {
+    Local
+        .timestamp_opt(time, 0)
+        .single()
+        .map(|dt| dt.format("%Y/%m/%d").to_string())
+        .unwrap_or_default()
+}
+
+fn format_time(time: i64) -> String {
+    Local
+        .timestamp_opt(time, 0)
+        .single()
+        .map(|dt| dt.format("%Y/%m/%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            projects::set_project_end,
+            reminders::set_reminders,
+        },
+        tests::{
+            get_test_conn,
+            insert_recurring_record,
+            insert_recurring_task,
+            insert_task,
+            update_status,
+        },
+    };
+
+    fn labels(lines: &[(&'static str, String)]) -> Vec<&'static str> {
+        lines.iter().map(|(label, _)| *label).collect()
+    }
+
+    #[test]
+    fn test_details_task() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "release", "write notes", "tomorrow");
+        set_reminders(&conn, id, &[3600]).unwrap();
+        set_project_end(&conn, "release", 4000).unwrap();
+        update_status(&conn, id, 1);
+
+        let lines = details(&conn, &get_item(&conn, id).unwrap(), None).unwrap();
+        assert_eq!(
+            labels(&lines),
+            vec![
+                "Id",
+                "Type",
+                "Category",
+                "Status",
+                "Deadline",
+                "Created",
+                "Modified",
+                "Project end",
+                "Reminders",
+                "History",
+                "",
+            ]
+        );
+        assert_eq!(lines[3].1, translate_status(1));
+        assert_eq!(lines[8].1, "1h before");
+        assert!(lines[10].1.ends_with(&translate_status(1)), "{}", lines[10].1);
+    }
+
+    #[test]
+    fn test_details_recurring() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_recurring_task(&conn, "life", "write diary", "Daily");
+        let record_id = insert_recurring_record(&conn, "life", "write diary", task_id, 4000);
+
+        let task = get_item(&conn, task_id).unwrap();
+        let lines = details(&conn, &task, None).unwrap();
+        assert!(lines.contains(&("Schedule", "Daily".to_string())));
+        assert!(lines.contains(&("Completions", "1".to_string())));
+
+        let lines = details(&conn, &get_item(&conn, record_id).unwrap(), None).unwrap();
+        assert!(lines.contains(&("Type", "recurring task record".to_string())));
+        let parent = format!("write diary ({})", task.short_id());
+        assert!(lines.contains(&("Recurring task", parent)));
+
+        // Completions by other users of the database are not counted
+        let bobs = insert_recurring_record(&conn, "life", "write diary", task_id, 5000);
+        conn.execute("UPDATE items SET owner = 'bob' WHERE id = ?1", [bobs]).unwrap();
+        let lines = details(&conn, &task, Some("alice")).unwrap();
+        assert!(lines.contains(&("Completions", "1".to_string())));
+        let lines = details(&conn, &task, None).unwrap();
+        assert!(lines.contains(&("Completions", "2".to_string())));
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    io::BufRead,
+    sync::LazyLock,
+};
+
+use regex::{
+    Captures,
+    Regex,
+};
+
+use crate::{
+    actions::{
+        display,
+        triage::prompt,
+    },
+    config::RecordTemplateConfig,
+    error::TascliError,
+};
+
+// "{type}" or "{distance:km}"
+static FIELD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{(\w+)(?::([^{}]*))?\}").unwrap());
+
+// A field of a record template, one with a unit asks for a number
+#[derive(Debug, PartialEq)]
+struct Field {
+    name: String,
+    unit: Option<String>,
+}
+
+/// A record filled in from a template.
+#[derive(Debug, PartialEq)]
+pub(crate) struct FilledRecord {
+    pub content: String,
+    // One "name=answer" per answered field, as stored with the record
+    pub fields: String,
+    // The answer to the first number field, for the trend report
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// Asks for each field of `template`, None when the input is closed before all are answered.
+/// An empty answer leaves a field out.
+pub(crate) fn fill_template(
+    template: &RecordTemplateConfig,
+    input: &mut impl BufRead,
+) -> Result<Option<FilledRecord>, TascliError> {
+    let fields = parse_fields(&template.template);
+    let mut answers = Vec::new();
+    for field in &fields {
+        let Some(answer) = ask_field(field, input)? else {
+            return Ok(None);
+        };
+        answers.push(answer);
+    }
+    Ok(Some(fill(&template.template, &fields, &answers)))
+}
+
+fn parse_fields(template: &str) -> Vec<Field> {
+    FIELD_RE
+        .captures_iter(template)
+        .map(|caps| Field {
+            name: caps[1].to_string(),
+            unit: caps.get(2).map(|u| u.as_str().trim().to_string()),
+        })
+        .collect()
+}
+
+// Number fields are asked again until the answer is a number
+fn ask_field(field: &Field, input: &mut impl BufRead) -> Result<Option<String>, TascliError> {
+    let question = match &field.unit {
+        Some(unit) if !unit.is_empty() => format!("{} ({})", field.name, unit),
+        _ => field.name.clone(),
+    };
+    loop {
+        let Some(answer) = prompt(input, &question)? else {
+            return Ok(None);
+        };
+        if field.unit.is_none() || answer.is_empty() || answer.parse::<f64>().is_ok() {
+            return Ok(Some(answer));
+        }
+        display::print_yellow(&format!("{} takes a number, such as 5 or 2.5", field.name));
+    }
+}
+
+fn fill(template: &str, fields: &[Field], answers: &[String]) -> FilledRecord {
+    // An answer with the unit of its field, such as "5 km"
+    let with_unit = |field: &Field, answer: &str| match field.unit.as_deref() {
+        Some(unit) if !answer.is_empty() && !unit.is_empty() => format!("{} {}", answer, unit),
+        _ => answer.to_string(),
+    };
+    let mut position = 0;
+    let content = FIELD_RE.replace_all(template, |_: &Captures| {
+        let filled = with_unit(&fields[position], &answers[position]);
+        position += 1;
+        filled
+    });
+
+    let answered: Vec<(&Field, &String)> =
+        fields.iter().zip(answers).filter(|(_, answer)| !answer.is_empty()).collect();
+    let fields_text: Vec<String> = answered
+        .iter()
+        .map(|(field, answer)| format!("{}={}", field.name, with_unit(field, answer)))
+        .collect();
+    let first_number = answered
+        .iter()
+        .find_map(|(field, answer)| Some((*field, answer.parse::<f64>().ok()?)));
+
+    FilledRecord {
+        // Left out fields leave no double spaces behind
+        content: content.split_whitespace().collect::<Vec<_>>().join(" "),
+        fields: fields_text.join("\n"),
+        value: first_number.map(|(_, value)| value),
+        unit: first_number
+            .and_then(|(field, _)| field.unit.clone())
+            .filter(|unit| !unit.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn workout() -> RecordTemplateConfig {
+        RecordTemplateConfig {
+            name: "workout".to_string(),
+            template: "{type} for {duration:min}, {distance:km}".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let fields = parse_fields(&workout().template);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["type", "duration", "distance"]);
+        assert_eq!(fields[0].unit, None);
+        assert_eq!(fields[2].unit.as_deref(), Some("km"));
+    }
+
+    #[test]
+    fn test_fill_template() {
+        let mut input = Cursor::new("run\nhalf an hour\n30\n5.2\n");
+        let filled = fill_template(&workout(), &mut input).unwrap().unwrap();
+        assert_eq!(filled.content, "run for 30 min, 5.2 km");
+        assert_eq!(filled.fields, "type=run\nduration=30 min\ndistance=5.2 km");
+        assert_eq!(filled.value, Some(30.0));
+        assert_eq!(filled.unit.as_deref(), Some("min"));
+
+        // Fields answered with an empty line are left out
+        let mut input = Cursor::new("swim\n\n1.5\n");
+        let filled = fill_template(&workout(), &mut input).unwrap().unwrap();
+        assert_eq!(filled.content, "swim for , 1.5 km");
+        assert_eq!(filled.fields, "type=swim\ndistance=1.5 km");
+        assert_eq!(filled.value, Some(1.5));
+
+        assert_eq!(fill_template(&workout(), &mut Cursor::new("run\n")).unwrap(), None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    io,
+    io::{
+        BufRead,
+        Write,
+    },
+};
+
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::{
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+        modify::complete_item,
+        project::check_deadline,
+    },
+    args::{
+        parser::TriageCommand,
+        timestr,
+    },
+    clock,
+    config::{
+        current_user,
+        holidays,
+    },
+    db::{
+        crud::{
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+        reminders,
+    },
+    error::TascliError,
+};
+
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    done: usize,
+    rescheduled: usize,
+    cancelled: usize,
+    skipped: usize,
+}
+
+pub fn handle_triagecmd(conn: &Connection, cmd: &TriageCommand) -> Result<(), TascliError> {
+    let mut query = ItemQuery::new()
+        .with_visible_to(current_user())
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(clock::timestamp())
+        .with_order_by(TARGET_TIME_COL);
+    if let Some(c) = &cmd.category {
+        query = query.with_category(c);
+    }
+    let overdue = query_items(conn, &query)?;
+    if overdue.is_empty() {
+        display::print_bold("No overdue tasks");
+        return Ok(());
+    }
+
+    display::print_bold(&format!("{} overdue tasks to triage", overdue.len()));
+    let suggestion = suggest_timestr(clock::now().date_naive());
+    let summary = triage(conn, overdue, &suggestion, &mut io::stdin().lock())?;
+    display::print_bold(&format!(
+        "Triaged: {} done, {} rescheduled, {} cancelled, {} skipped",
+        summary.done, summary.rescheduled, summary.cancelled, summary.skipped
+    ));
+    Ok(())
+}
+
+// Stops early on quit or at the end of the input, the tasks handled so far stay handled.
+fn triage(
+    conn: &Connection,
+    tasks: Vec<Item>,
+    suggestion: &str,
+    input: &mut impl BufRead,
+) -> Result<Summary, TascliError> {
+    let mut summary = Summary::default();
+    let total = tasks.len();
+    for (i, task) in tasks.into_iter().enumerate() {
+        display::print_items(std::slice::from_ref(&task), false, false);
+        let question = format!(
+            "[{}/{}] (d)one, (r)eschedule to {}, (c)ancel, (s)kip, (q)uit",
+            i + 1,
+            total,
+            suggestion
+        );
+        loop {
+            let Some(key) = prompt(input, &question)? else {
+                return Ok(summary);
+            };
+            match key.to_lowercase().as_str() {
+                "d" => {
+                    complete_item(conn, task.clone(), 1, None)?;
+                    display::print_green("Completed");
+                    summary.done += 1;
+                }
+                "c" => {
+                    complete_item(conn, task.clone(), 2, None)?;
+                    display::print_green("Cancelled");
+                    summary.cancelled += 1;
+                }
+                "r" => {
+                    let Some(answer) = prompt(input, &format!("New deadline [{}]", suggestion))?
+                    else {
+                        return Ok(summary);
+                    };
+                    let timestr = if answer.is_empty() { suggestion } else { &answer };
+                    if let Err(e) = reschedule(conn, task.clone(), timestr) {
+                        display::print_red(&e.to_string());
+                        continue;
+                    }
+                    display::print_green(&format!("Rescheduled to {}", timestr));
+                    summary.rescheduled += 1;
+                }
+                "s" | "" => summary.skipped += 1,
+                "q" => return Ok(summary),
+                _ => {
+                    display::print_yellow("Press d, r, c, s or q");
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+    Ok(summary)
+}
+
+pub(crate) fn reschedule(conn: &Connection, mut task: Item, timestr: &str) -> Result<(), TascliError> {
+    let target_time = timestr::to_unix_epoch(timestr)?;
+    check_deadline(conn, &task.category, target_time, false)?;
+    task.set_target_time(Some(target_time));
+    update_item(conn, &task)?;
+    reminders::rearm_reminders(conn, task.id.unwrap_or_default())?;
+    Ok(())
+}
+
+// The next business day, so rescheduled tasks do not pile up on weekends and holidays
+fn suggest_timestr(today: NaiveDate) -> String {
+    let next = holidays::next_business_day(today);
+    match (next - today).num_days() {
+        1 => "tomorrow".to_string(),
+        // A weekday name is the coming one
+        2..=6 => next.format("%A").to_string().to_lowercase(),
+        _ => next.format("%Y/%m/%d").to_string(),
+    }
+}
+
+// The trimmed answer, None once the input is closed
+pub(crate) fn prompt(input: &mut impl BufRead, question: &str) -> Result<Option<String>, TascliError> {
+    print!("{}: ", question);
+    io::stdout()
+        .flush()
+        .map_err(|e| TascliError::io("Failed to write prompt", e))?;
+    let mut answer = String::new();
+    let read = input
+        .read_line(&mut answer)
+        .map_err(|e| TascliError::io("Failed to read answer", e))?;
+    if read == 0 {
+        println!();
+        return Ok(None);
+    }
+    Ok(Some(answer.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    fn overdue_tasks(conn: &Connection, contents: &[&str]) -> Vec<Item> {
+        contents
+            .iter()
+            .map(|content| {
+                let id = insert_task(conn, "work", content, "yesterday");
+                get_item(conn, id).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_triage() {
+        let (conn, _temp_file) = get_test_conn();
+        let tasks = overdue_tasks(&conn, &["send invoice", "fix bug", "call bank", "plan trip"]);
+        // done, unknown key then reschedule to the suggestion, cancel, skip
+        let mut input = Cursor::new("d\nx\nr\n\nC\ns\n");
+
+        let summary = triage(&conn, tasks, "tomorrow", &mut input).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                done: 1,
+                rescheduled: 1,
+                cancelled: 1,
+                skipped: 1,
+            }
+        );
+        assert_eq!(get_item(&conn, 1).unwrap().status, 1);
+        assert_eq!(
+            get_item(&conn, 2).unwrap().target_time,
+            Some(timestr::to_unix_epoch("tomorrow").unwrap())
+        );
+        assert_eq!(get_item(&conn, 3).unwrap().status, 2);
+        assert_eq!(get_item(&conn, 4).unwrap().status, 0);
+    }
+
+    #[test]
+    fn test_triage_stops_on_quit_and_end_of_input() {
+        let (conn, _temp_file) = get_test_conn();
+        let tasks = overdue_tasks(&conn, &["send invoice", "fix bug"]);
+        let summary = triage(&conn, tasks, "tomorrow", &mut Cursor::new("q\nd\n")).unwrap();
+        assert_eq!(summary, Summary::default());
+
+        let tasks = overdue_tasks(&conn, &["call bank", "plan trip"]);
+        // a deadline that cannot be parsed is asked for again
+        let mut input = Cursor::new("r\nsoonish\nr\nmonday\n");
+        let summary = triage(&conn, tasks, "tomorrow", &mut input).unwrap();
+        assert_eq!(summary.rescheduled, 1);
+        assert_eq!(get_item(&conn, 4).unwrap().status, 0);
+    }
+
+    #[test]
+    fn test_suggest_timestr() {
+        // Wednesday, Friday and Saturday
+        assert_eq!(suggest_timestr(NaiveDate::from_ymd_opt(2025, 3, 19).unwrap()), "tomorrow");
+        assert_eq!(suggest_timestr(NaiveDate::from_ymd_opt(2025, 3, 21).unwrap()), "monday");
+        assert_eq!(suggest_timestr(NaiveDate::from_ymd_opt(2025, 3, 22).unwrap()), "monday");
+    }
+}
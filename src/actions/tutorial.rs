@@ -0,0 +1,249 @@
+//! `tascli tutorial`, a walk through adding, listing, completing and rescheduling tasks in a
+//! sandbox db that is thrown away at the end.
+
+use std::io::{
+    self,
+    BufRead,
+};
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        handler::handle_commands,
+        triage::prompt,
+        view::parse_args,
+    },
+    args::parser::{
+        Action,
+        ListCommand,
+    },
+    db::{
+        conn::connect_in_memory,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            DEFAULT_CATEGORY,
+            TASK,
+        },
+    },
+    error::TascliError,
+    events,
+};
+
+// A step is done once a command it expects has run and the sandbox shows it
+struct Step {
+    title: &'static str,
+    explanation: &'static str,
+    example: &'static str,
+    expects: fn(&Action) -> bool,
+    // Said when the command typed is not one the step expects
+    hint: &'static str,
+    done: fn(&[Item]) -> bool,
+}
+
+const STEPS: [Step; 5] = [
+    Step {
+        title: "Add a task",
+        explanation: "A task has a description and a deadline, such as today, friday or 12/24.",
+        example: "task \"buy milk\" tomorrow",
+        expects: |action| matches!(action, Action::Task(_)),
+        hint: "Tasks are added with `task`",
+        done: |tasks| !tasks.is_empty(),
+    },
+    Step {
+        title: "Add a task to a category",
+        explanation: "Categories group tasks, -c puts a task in one.",
+        example: "task -c work \"send report\" friday",
+        expects: |action| matches!(action, Action::Task(cmd) if cmd.category.is_some()),
+        hint: "Add a task with -c and the name of a category",
+        done: |tasks| tasks.iter().any(|t| t.category != DEFAULT_CATEGORY),
+    },
+    Step {
+        title: "List your tasks",
+        explanation: "Lists number the tasks, other commands refer to a task by its number.",
+        example: "list task",
+        expects: |action| matches!(action, Action::List(ListCommand::Task(_))),
+        hint: "Tasks are listed with `list task`",
+        done: |_| true,
+    },
+    Step {
+        title: "Complete a task",
+        explanation: "`done` completes a task by its number in the last list.",
+        example: "done 1",
+        expects: |action| matches!(action, Action::Done(cmd) if cmd.index.is_some()),
+        hint: "Complete a task with `done` and its number in the list",
+        done: |tasks| tasks.iter().any(|t| t.status == 1),
+    },
+    Step {
+        title: "Reschedule a task",
+        explanation: "`update -t` moves the deadline of a task, the numbers of the last list \
+                      still hold.",
+        example: "update 2 -t monday",
+        expects: |action| {
+            matches!(
+                action,
+                Action::Update(cmd) if cmd.index.is_some() && cmd.target_time.is_some()
+            )
+        },
+        hint: "Reschedule with `update`, the number of a task and -t with its new deadline",
+        done: |tasks| tasks.iter().any(|t| t.status == 0 && t.modify_time.is_some()),
+    },
+];
+
+pub fn handle_tutorialcmd() -> Result<(), TascliError> {
+    let sandbox = connect_in_memory()?;
+    // Webhooks, chat channels and rules are not told about the sandbox
+    events::set_muted(true);
+    display::print_bold("Welcome to tascli! Nothing you do here touches your own tasks.");
+    println!("Type the commands without `tascli`, `skip` moves on and `quit` stops.");
+    let finished = run_tutorial(&sandbox, &mut io::stdin().lock())?;
+    println!();
+    if finished == STEPS.len() {
+        display::print_green("That is the basics, your own tasks are as they were.");
+        println!("`tascli palette` finds every other command, `tascli --help` lists them all.");
+    } else {
+        display::print_bold("Tutorial stopped, your own tasks are as they were.");
+    }
+    Ok(())
+}
+
+// The number of steps done, it stops early on quit or at the end of the input
+fn run_tutorial(sandbox: &Connection, input: &mut impl BufRead) -> Result<usize, TascliError> {
+    let mut finished = 0;
+    for (i, step) in STEPS.iter().enumerate() {
+        println!();
+        display::print_bold(&format!("{}/{}: {}", i + 1, STEPS.len(), step.title));
+        println!("{}", step.explanation);
+        println!("Try: {}", step.example);
+        loop {
+            let Some(line) = prompt(input, "tascli")? else {
+                return Ok(finished);
+            };
+            match line.as_str() {
+                "quit" | "q" => return Ok(finished),
+                "skip" => break,
+                "" => {
+                    println!("Try: {}", step.example);
+                    continue;
+                }
+                _ => {}
+            }
+            if run_step(sandbox, step, &line)? {
+                display::print_green("Well done!");
+                finished += 1;
+                break;
+            }
+        }
+    }
+    Ok(finished)
+}
+
+// Whether the step is done after running the command line typed
+fn run_step(sandbox: &Connection, step: &Step, line: &str) -> Result<bool, TascliError> {
+    let mut words = split_words(line);
+    if words.first().is_some_and(|w| w == "tascli") {
+        words.remove(0);
+    }
+    let args = match parse_args(&words) {
+        Ok(args) => args,
+        Err(e) => {
+            display::print_red(&e.to_string());
+            return Ok(false);
+        }
+    };
+    // Other commands are not run, they may ask questions of their own
+    if !args.arguments.as_ref().is_some_and(step.expects) {
+        display::print_yellow(&format!("{}, such as: {}", step.hint, step.example));
+        return Ok(false);
+    }
+    if let Err(e) = handle_commands(sandbox, args) {
+        display::print_red(&e.to_string());
+        return Ok(false);
+    }
+
+    let tasks = query_items(sandbox, &ItemQuery::new().with_action(TASK))?;
+    if !(step.done)(&tasks) {
+        display::print_yellow(&format!("{}, such as: {}", step.hint, step.example));
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+// Words of a command line, quotes keep words together as in a shell
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            None => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_run_tutorial() {
+        let sandbox = connect_in_memory().unwrap();
+        // Commands other than the step's and empty lines do not count
+        let mut input = Cursor::new(
+            "task \"buy milk\" tomorrow\n\
+             list task\n\
+             \n\
+             tascli task -c work 'send report' tomorrow\n\
+             list task\n\
+             delete 1\n\
+             done 1\n\
+             update 2 -t monday\n",
+        );
+        assert_eq!(run_tutorial(&sandbox, &mut input).unwrap(), 5);
+
+        let tasks = query_items(&sandbox, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks.iter().filter(|t| t.status == 1).count(), 1);
+    }
+
+    #[test]
+    fn test_run_tutorial_stops() {
+        let sandbox = connect_in_memory().unwrap();
+        let mut input = Cursor::new("skip\ntask -c home \"water plants\" today\nquit\n");
+        assert_eq!(run_tutorial(&sandbox, &mut input).unwrap(), 1);
+        assert_eq!(run_tutorial(&sandbox, &mut Cursor::new("")).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("task \"buy milk\" tomorrow"), ["task", "buy milk", "tomorrow"]);
+        let words = split_words("  update 2 -t 'next friday' ");
+        assert_eq!(words, ["update", "2", "-t", "next friday"]);
+        assert_eq!(split_words("task \"\""), ["task", ""]);
+    }
+}
@@ -0,0 +1,126 @@
+use clap::Parser;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        handler::handle_commands,
+    },
+    args::parser::{
+        Action,
+        CliArgs,
+        ViewCommand,
+    },
+    db::views::{
+        delete_view,
+        get_view,
+        get_views,
+        save_view,
+    },
+    error::TascliError,
+};
+
+pub fn handle_viewcmd(conn: &Connection, cmd: ViewCommand) -> Result<(), TascliError> {
+    match cmd {
+        ViewCommand::Save { name, args } => {
+            parse_view(&args)?;
+            save_view(conn, &name, &args)?;
+            display::print_bold(&format!("Saved view {}: {}", name, join_args(&args)));
+            Ok(())
+        }
+        ViewCommand::List => {
+            let views = get_views(conn)?;
+            if views.is_empty() {
+                display::print_bold("No saved views, add one with `tascli view save`");
+            }
+            for view in views {
+                println!("{}: {}", view.name, join_args(&view.args));
+            }
+            Ok(())
+        }
+        ViewCommand::Delete { name } => {
+            if !delete_view(conn, &name)? {
+                return Err(TascliError::Invalid(format!("No view named {}", name)));
+            }
+            display::print_bold(&format!("Deleted view {}", name));
+            Ok(())
+        }
+        ViewCommand::Run(args) => {
+            let (name, extra) = args
+                .split_first()
+                .ok_or_else(|| TascliError::Invalid("Missing view name".to_string()))?;
+            let view = get_view(conn, name)?
+                .ok_or_else(|| TascliError::Invalid(format!("No view named {}", name)))?;
+            let mut view_args = view.args;
+            view_args.extend(extra.iter().cloned());
+            handle_commands(conn, parse_view(&view_args)?)
+        }
+    }
+}
+
+// A view is any command other than a view, which could run itself forever
+fn parse_view(args: &[String]) -> Result<CliArgs, TascliError> {
+    let parsed = parse_args(args)?;
+    match parsed.arguments {
+        None => Err(TascliError::Invalid(
+            "A view needs a command such as `list task`".to_string(),
+        )),
+        Some(Action::View(_)) => Err(TascliError::Invalid(
+            "A view cannot run another view".to_string(),
+        )),
+        Some(_) => Ok(parsed),
+    }
+}
+
+/// The command line `args`, given without "tascli", as parsed by clap.
+pub(crate) fn parse_args(args: &[String]) -> Result<CliArgs, TascliError> {
+    let command_line = std::iter::once("tascli").chain(args.iter().map(String::as_str));
+    CliArgs::try_parse_from(command_line).map_err(|e| {
+        // The first line says what is wrong, the rest is usage
+        let message = e.to_string();
+        let reason = message.lines().next().unwrap_or_default();
+        TascliError::Parse(reason.trim_start_matches("error: ").to_string())
+    })
+}
+
+// Quotes the arguments that would not survive being pasted back into a shell
+pub(crate) fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let needs_quotes = arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'');
+            if !arg.is_empty() && !needs_quotes {
+                arg.clone()
+            } else {
+                format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_view() {
+        assert!(parse_view(&args(&["list", "task", "--no-category", "-s", "ongoing"])).is_ok());
+        assert!(parse_view(&args(&["list", "task", "--no-such-flag"])).is_err());
+        assert!(parse_view(&args(&["view", "inbox"])).is_err());
+        assert!(parse_view(&args(&["view", "list"])).is_err());
+        assert!(parse_view(&args(&["--plain"])).is_err());
+    }
+
+    #[test]
+    fn test_join_args() {
+        assert_eq!(
+            join_args(&args(&["list", "task", "--search", "weekly sync", ""])),
+            r#"list task --search "weekly sync" """#
+        );
+        assert_eq!(join_args(&args(&["say \"hi\""])), r#""say \"hi\"""#);
+    }
+}
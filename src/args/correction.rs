@@ -0,0 +1,201 @@
+//! Corrections of mistyped subcommands and flags, such as `tascli lst task --overdeu`,
+//! found with the fuzzy matcher natural language commands use for categories.
+
+use clap::{
+    Arg,
+    Command,
+};
+
+use crate::nlp::FuzzyMatcher;
+
+// Least similarity for a word to be corrected, as for categories
+const MIN_SIMILARITY: f64 = 0.6;
+// Least similarity of every corrected word for the correction to run without asking
+const CERTAIN: f64 = 0.7;
+
+/// Arguments with the mistyped subcommands and long flags replaced by the closest ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    pub args: Vec<String>,
+    // Similarity of the least similar corrected word, 1.0 for the same word
+    pub confidence: f64,
+}
+
+impl Correction {
+    /// Whether every corrected word is close enough to run without asking
+    pub fn is_certain(&self) -> bool {
+        self.confidence >= CERTAIN
+    }
+
+    /// The corrected command line, as it would be typed
+    pub fn command_line(&self) -> String {
+        let args = self.args.iter().map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            }
+        });
+        std::iter::once("tascli".to_string()).chain(args).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Corrects `args`, without the program name, against the subcommands and long flags of
+/// `command`. None when nothing needed correcting or a flag is like none of them.
+pub fn correct(mut command: Command, args: &[String]) -> Option<Correction> {
+    // Adds --help and the global flags to every subcommand
+    command.build();
+    let mut current = &command;
+    let mut corrected = Vec::with_capacity(args.len());
+    let mut confidence = 1.0f64;
+    // Past the subcommands, words are values and left as they are
+    let mut in_values = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            corrected.push(arg.clone());
+            corrected.extend(args.cloned());
+            break;
+        }
+        if let Some(flag) = arg.strip_prefix("--") {
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (flag, None),
+            };
+            let (long, score) = closest(name, &long_flags(current))?;
+            confidence = confidence.min(score);
+            let takes_value = find_long(current, &long).is_some_and(takes_value);
+            match value {
+                Some(value) => corrected.push(format!("--{}={}", long, value)),
+                None => corrected.push(format!("--{}", long)),
+            }
+            if takes_value && value.is_none() {
+                corrected.extend(args.next().cloned());
+            }
+            continue;
+        }
+        if arg.len() > 1 && arg.starts_with('-') {
+            // Short flags are too short to tell a typo from another flag
+            corrected.push(arg.clone());
+            let short = arg.chars().nth(1);
+            let takes_value = current
+                .get_arguments()
+                .find(|a| a.get_short().is_some() && a.get_short() == short)
+                .is_some_and(takes_value);
+            if arg.chars().count() == 2 && takes_value {
+                corrected.extend(args.next().cloned());
+            }
+            continue;
+        }
+        if in_values
+            || !current.has_subcommands()
+            || current.is_allow_external_subcommands_set()
+        {
+            in_values = true;
+            corrected.push(arg.clone());
+            continue;
+        }
+        let found = match current.find_subcommand(arg) {
+            Some(subcommand) => Some((subcommand, 1.0)),
+            None => closest(arg, &subcommand_names(current))
+                .and_then(|(name, score)| Some((current.find_subcommand(&name)?, score))),
+        };
+        match found {
+            Some((subcommand, score)) => {
+                confidence = confidence.min(score);
+                // Aliases are kept as typed
+                let name = if score < 1.0 { subcommand.get_name() } else { arg.as_str() };
+                corrected.push(name.to_string());
+                current = subcommand;
+            }
+            None => {
+                // Words that are not subcommands are values, such as natural language input
+                in_values = true;
+                corrected.push(arg.clone());
+            }
+        }
+    }
+
+    (confidence < 1.0).then_some(Correction {
+        args: corrected,
+        confidence,
+    })
+}
+
+// The word itself when it is known, otherwise the most similar one
+fn closest(word: &str, known: &[String]) -> Option<(String, f64)> {
+    if known.iter().any(|k| k == word) {
+        return Some((word.to_string(), 1.0));
+    }
+    FuzzyMatcher::find_all_matches(word, known, MIN_SIMILARITY).into_iter().next()
+}
+
+fn long_flags(command: &Command) -> Vec<String> {
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .flat_map(|arg| arg.get_long_and_visible_aliases().unwrap_or_default())
+        .map(str::to_string)
+        .collect()
+}
+
+fn find_long<'a>(command: &'a Command, long: &str) -> Option<&'a Arg> {
+    command.get_arguments().find(|arg| {
+        arg.get_long_and_visible_aliases().is_some_and(|longs| longs.contains(&long))
+    })
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_action().takes_values()
+}
+
+fn subcommand_names(command: &Command) -> Vec<String> {
+    command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .flat_map(|subcommand| {
+            std::iter::once(subcommand.get_name()).chain(subcommand.get_visible_aliases())
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::args::parser::CliArgs;
+
+    fn correct_args(args: &str) -> Option<Correction> {
+        let args: Vec<String> = args.split_whitespace().map(str::to_string).collect();
+        correct(CliArgs::command(), &args)
+    }
+
+    #[test]
+    fn test_correct() {
+        let correction = correct_args("list tsk --overdeu").unwrap();
+        assert_eq!(correction.command_line(), "tascli list task --overdue");
+        assert!(correction.is_certain());
+
+        // Values of flags and positional arguments are left alone
+        let correction = correct_args("list task -c wrok today --serch=mlk").unwrap();
+        assert_eq!(correction.command_line(), "tascli list task -c wrok today --search=mlk");
+        let correction = correct_args("list task --catgory wrok").unwrap();
+        assert_eq!(correction.args, ["list", "task", "--category", "wrok"]);
+
+        assert_eq!(correct_args("list task --overdue"), None);
+        assert_eq!(correct_args("list task --xyzzy"), None);
+        assert_eq!(correct_args("buy milk tomorrow"), None);
+    }
+
+    #[test]
+    fn test_command_line_quotes() {
+        let correction = Correction {
+            args: vec!["task".to_string(), "buy milk".to_string()],
+            confidence: 0.8,
+        };
+        assert_eq!(correction.command_line(), "tascli task \"buy milk\"");
+    }
+}
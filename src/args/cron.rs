@@ -6,16 +6,43 @@ use chrono::{
     Timelike,
 };
 
+use crate::clock;
+
 // Parse a cron string and return the next or last occurrence timestamp
 // The cron implementation is specific to this project
 // avoiding additional dependency while implementing specific
 // subset of cron functionalities.
 pub fn get_next_occurrence(cron_str: &str) -> Result<i64, String> {
-    get_occurrence_from(cron_str, Local::now(), true)
+    get_occurrence_from(cron_str, clock::now(), true)
 }
 
 pub fn get_last_occurrence(cron_str: &str) -> Result<i64, String> {
-    get_occurrence_from(cron_str, Local::now(), false)
+    get_occurrence_from(cron_str, clock::now(), false)
+}
+
+pub fn get_next_occurrence_from(cron_str: &str, from: chrono::DateTime<Local>) -> Result<i64, String> {
+    get_occurrence_from(cron_str, from, true)
+}
+
+// All occurrences after `from` up to and including `until`
+pub fn get_occurrences_between(
+    cron_str: &str,
+    from: chrono::DateTime<Local>,
+    until: i64,
+) -> Result<Vec<i64>, String> {
+    let mut occurrences = Vec::new();
+    let mut current = from;
+    loop {
+        let next = get_occurrence_from(cron_str, current, true)?;
+        if next > until {
+            return Ok(occurrences);
+        }
+        occurrences.push(next);
+        current = Local
+            .timestamp_opt(next, 0)
+            .single()
+            .ok_or_else(|| format!("Invalid occurrence of {}", cron_str))?;
+    }
 }
 
 // Underlying implementation to allow for testing
@@ -248,6 +275,23 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_occurrences_between() {
+        let at = |d, h| Local.with_ymd_and_hms(2024, 3, d, h, 0, 0).unwrap();
+        // Friday 03/15 to Friday 03/22, weekdays at 9
+        let occurrences =
+            get_occurrences_between("0 9 * * 1-5", at(15, 10), at(22, 9).timestamp()).unwrap();
+        let expected: Vec<i64> = [18, 19, 20, 21, 22]
+            .iter()
+            .map(|d| at(*d, 9).timestamp())
+            .collect();
+        assert_eq!(occurrences, expected);
+
+        assert!(get_occurrences_between("0 9 1 * *", at(2, 0), at(20, 0).timestamp())
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_next_occurrence() {
         let test_cases = vec![
@@ -1,3 +1,5 @@
+#[cfg(feature = "nlp")]
+pub mod correction;
 pub mod cron;
 pub mod parser;
 pub mod timestr;
@@ -1,14 +1,28 @@
 use clap::{
+    ArgGroup,
     Args,
     Parser,
     Subcommand,
 };
-use crate::args::timestr::{parse_flexible_timestr, parse_recurring_timestr};
+use crate::{
+    args::timestr::{
+        parse_duration,
+        parse_flexible_timestr,
+        parse_lead_time,
+        parse_recurring_timestr,
+    },
+    db::item::{
+        PRIORITIES,
+        SHORT_ID_LEN,
+    },
+    logging::LOG_LEVELS,
+};
 
 /// a simple CLI tool for tracking tasks and records from terminal
 ///
 /// data is stored at ~/.local/share/tascli/tascli.db,
 /// or where defined in config at ~/.config/tascli/config.json
+/// (%APPDATA%\tascli on Windows)
 #[derive(Debug, Parser)]
 #[command(author, version)]
 pub struct CliArgs {
@@ -16,6 +30,43 @@ pub struct CliArgs {
     #[arg(short, long, global = false, default_value_t = false)]
     pub no_nlp: bool,
 
+    /// Print errors as a json object on stderr, for scripts
+    #[arg(long, global = true)]
+    pub json_errors: bool,
+
+    /// Log level for diagnostics written to stderr or --log-file
+    #[arg(
+        long,
+        global = true,
+        default_value = "off",
+        value_parser = clap::builder::PossibleValuesParser::new(LOG_LEVELS)
+    )]
+    pub log_level: String,
+
+    /// Append logs to this file instead of stderr
+    #[arg(long, global = true)]
+    pub log_file: Option<String>,
+
+    /// Screen reader friendly output, labeled lines instead of tables and no colors
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Stable tab separated lines of items for scripts, messages go to stderr
+    #[arg(long, global = true)]
+    pub porcelain: bool,
+
+    /// Same output on every terminal, a fixed width of 120 columns and no colors
+    #[arg(long, global = true)]
+    pub deterministic: bool,
+
+    /// Person sharing the database, new items are theirs and listings hide other people's items
+    #[arg(long, global = true)]
+    pub user: Option<String>,
+
+    /// Run as if it were this time, such as "2026-03-01 09:00", for debugging
+    #[arg(long, global = true)]
+    pub now: Option<String>,
+
     #[command(subcommand)]
     pub arguments: Option<Action>,
 
@@ -30,22 +81,101 @@ pub enum Action {
     Task(TaskCommand),
     /// add record
     Record(RecordCommand),
+    /// capture a thought to the inbox, without a category or deadline
+    In(InCommand),
+    /// file the captured thoughts as tasks or records, one by one
+    Inbox(InboxCommand),
     /// shortcut to complete tasks
     Done(DoneCommand),
+    /// turn a record into a task, for a noted idea to act on
+    Promote(PromoteCommand),
+    /// turn a task that is already done into a record of it, kept with the other records
+    LogDone(LogDoneCommand),
     /// update task and record entries.
     Update(UpdateCommand),
+    /// everything about an item: deadline, reminders, status history and completions
+    Show(ShowCommand),
+    /// set reminders ahead of a task's deadline, sent by `tascli notify reminders`
+    Remind(RemindCommand),
+    /// count down a focus session on a task, recorded as minutes spent on it
+    Focus(FocusCommand),
+    /// edit today's records in $EDITOR as a markdown note, saved back as records
+    Journal(JournalCommand),
+    /// add, log or skip habits, recurring tasks with streaks in `tascli report streak`
+    #[command(subcommand)]
+    Habit(HabitCommand),
+    /// show or set the end date of a category run as a project
+    Project(ProjectCommand),
     /// delete task or record
     Delete(DeleteCommand),
     /// list tasks or records
     #[command(subcommand)]
     List(ListCommand),
     /// use natural language to create commands
+    #[cfg(feature = "nlp")]
+    #[command(visible_alias = "ai")]
     NLP(NLPCommand),
+    /// print or email a summary of overdue and due today tasks,
+    /// designed to be run from cron
+    Digest(DigestCommand),
+    /// send notifications to the chat channels in config
+    #[command(subcommand)]
+    Notify(NotifyCommand),
+    /// import tasks from other tools
+    Import(ImportCommand),
+    /// export tasks and records to a file
+    Export(ExportCommand),
+    /// write a public status page of the tasks in some categories, to rerun from cron
+    Publish(PublishCommand),
+    /// statistics about tasks and records
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// tasks due each of the coming days, flagging overloaded days
+    Forecast(ForecastCommand),
+    /// walk through overdue tasks one by one to complete, reschedule or cancel them
+    Triage(TriageCommand),
+    /// give overdue tasks and inbox items a day of the coming week
+    #[command(subcommand)]
+    Plan(PlanCommand),
+    /// tasks due per day against the daily capacity, --auto spreads out overdue tasks
+    /// and inbox items over the days with room
+    Schedule(ScheduleCommand),
+    /// complete tasks from git commit messages
+    #[command(subcommand)]
+    Git(GitCommand),
+    /// sync tasks with Apple Reminders, macOS only
+    #[command(subcommand)]
+    Reminders(RemindersCommand),
+    /// check or run the automation rules in config
+    #[command(subcommand)]
+    Rules(RulesCommand),
+    /// saved searches, run a saved command line by name with `tascli view <name>`
+    #[command(subcommand)]
+    View(ViewCommand),
+    /// run a read-only SQL SELECT against the db, for questions the list filters cannot answer
+    Query(QueryCommand),
+    /// generate man pages or a markdown reference from this help text
+    GenDocs(GenDocsCommand),
+    /// guides to natural language commands with examples, by topic or searched
+    #[cfg(feature = "nlp")]
+    HelpTopics(HelpTopicsCommand),
+    /// list or run the scripts in config
+    #[cfg(feature = "scripting")]
+    #[command(subcommand)]
+    Script(ScriptCommand),
+    /// show the content of private items for a while
+    Unlock(UnlockCommand),
+    /// hide the content of private items again
+    Lock,
+    /// find a command or saved view by typing part of its name, then run it
+    Palette,
+    /// learn to add, list, complete and reschedule tasks step by step, in a sandbox
+    Tutorial,
 }
 
 #[derive(Debug, Args)]
 pub struct TaskCommand {
-    /// description of the task
+    /// description of the task, words such as +errands in it are tags
     #[arg(value_parser = |s: &str| syntax_helper("task", s))]
     pub content: String,
     /// time the task is due for completion, default to EOD,
@@ -55,6 +185,20 @@ pub struct TaskCommand {
     /// category of the task
     #[arg(short, long)]
     pub category: Option<String>,
+    /// encrypt the content, lists show [private] until `tascli unlock`
+    #[arg(long)]
+    pub private: bool,
+    /// refuse a deadline after the end of the category's project instead of warning
+    #[arg(long)]
+    pub strict: bool,
+    /// how pressing the task is, low|medium|high|urgent,
+    /// tasks due at the same time list the most pressing first
+    #[arg(short, long, value_parser = parse_priority)]
+    pub priority: Option<u8>,
+    /// index, category number or start of the id of the task this is a subtask of,
+    /// its category is used unless -c is given
+    #[arg(long, value_parser = parse_item_ref)]
+    pub parent: Option<ItemRef>,
 }
 
 #[derive(Debug, Args)]
@@ -69,13 +213,23 @@ pub struct RecordCommand {
     /// default to current time
     #[arg(short = 't', long = "time", value_parser = validate_timestr)]
     pub timestr: Option<String>,
+    /// encrypt the content, lists show [private] until `tascli unlock`
+    #[arg(long)]
+    pub private: bool,
+    /// a measurement logged with the record, e.g. 82.5
+    #[arg(long, allow_negative_numbers = true)]
+    pub value: Option<f64>,
+    /// unit of the value, e.g. kg
+    #[arg(long, requires = "value")]
+    pub unit: Option<String>,
 }
 
 #[derive(Debug, Args)]
 pub struct DoneCommand {
-    /// index from previous list command
-    #[arg(value_parser = validate_index)]
-    pub index: usize,
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
     /// optional status, default to done.
     #[arg(short, long, value_parser = parse_status, default_value_t = 1)]
     pub status: u8,
@@ -84,18 +238,46 @@ pub struct DoneCommand {
     pub comment: Option<String>,
 }
 
+#[derive(Debug, Args)]
+pub struct PromoteCommand {
+    /// index of the record from previous list command, category number such as work-12,
+    /// or start of the id
+    #[arg(value_parser = parse_item_ref)]
+    pub index: ItemRef,
+    /// time the task is due for completion, default to EOD,
+    /// a schedule makes it a recurring task
+    #[arg(value_parser = validate_timestr)]
+    pub timestr: Option<String>,
+    /// category of the task, default to the record's
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct LogDoneCommand {
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
+    /// time the task was done, default to current time
+    #[arg(short = 't', long = "time", value_parser = validate_timestr)]
+    pub timestr: Option<String>,
+}
+
 #[derive(Debug, Args)]
 pub struct DeleteCommand {
-    /// index from previous list command
-    #[arg(value_parser = validate_index)]
-    pub index: usize,
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
 }
 
 #[derive(Debug, Args)]
 pub struct UpdateCommand {
-    /// index from previous list command
-    #[arg(value_parser = validate_index)]
-    pub index: usize,
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
     /// update the target time of task,
     /// or event time of record,
     /// or schedule of a recurring task
@@ -113,7 +295,104 @@ pub struct UpdateCommand {
     /// update status of the tasks,
     /// accept ongoing|done|cancelled|duplicate|suspended|pending
     #[arg(short, long, value_parser = parse_status)]
-    pub status: Option<u8>
+    pub status: Option<u8>,
+    /// encrypt the content of the task/record
+    #[arg(long)]
+    pub private: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct UnlockCommand {
+    /// minutes until private content is hidden again
+    #[arg(short, long, default_value_t = 15)]
+    pub minutes: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct RemindCommand {
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
+    /// how long before the deadline to remind, e.g. "1d before" or "30m before",
+    /// repeat for several reminders. Shows the reminders when not given
+    #[arg(long = "at", value_parser = parse_lead_time)]
+    pub lead_times: Vec<i64>,
+    /// remove all reminders of the task
+    #[arg(long, conflicts_with = "lead_times")]
+    pub clear: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct FocusCommand {
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
+    /// length of the session
+    #[arg(
+        short,
+        long,
+        default_value_t = 25,
+        value_parser = clap::value_parser!(u64).range(1..=600)
+    )]
+    pub minutes: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct JournalCommand {
+    /// category of new lines written without one
+    #[arg(short, long, default_value = "journal")]
+    pub category: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectCommand {
+    /// category of the project, all projects are shown when omitted
+    pub category: Option<String>,
+    /// the project's end date, task deadlines after it are warned about
+    #[arg(long, value_parser = validate_timestr, requires = "category")]
+    pub end: Option<String>,
+    /// remove the project's end date
+    #[arg(long, conflicts_with = "end", requires = "category")]
+    pub clear: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HabitCommand {
+    /// add a habit, expected every day or on its schedule
+    Add(HabitAddCommand),
+    /// log the habit for the current day or period
+    Done(HabitDoneCommand),
+    /// skip the current day or period without breaking the streak
+    Skip(HabitSkipCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct HabitAddCommand {
+    /// name of the habit, e.g. meditate
+    pub name: String,
+    /// how often the habit is expected, e.g. daily or "weekly monday"
+    #[arg(default_value = "daily", value_parser = validate_schedule)]
+    pub schedule: String,
+    /// category of the habit
+    #[arg(short, long, default_value = "habit")]
+    pub category: String,
+}
+
+#[derive(Debug, Args)]
+pub struct HabitDoneCommand {
+    /// name of the habit, or a unique part of it
+    pub name: String,
+    /// add comment to the logged entry
+    #[arg(short, long)]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct HabitSkipCommand {
+    /// name of the habit, or a unique part of it
+    pub name: String,
 }
 
 #[derive(Debug, Subcommand)]
@@ -137,6 +416,9 @@ pub struct ListTaskCommand {
     /// category of the task
     #[arg(short, long)]
     pub category: Option<String>,
+    /// only tasks created without a category, same as --category ""
+    #[arg(long, conflicts_with = "category")]
+    pub no_category: bool,
     /// days in the future for tasks to list - mutually exclusive with timestr
     #[arg(short, long, conflicts_with = "timestr")]
     pub days: Option<usize>,
@@ -149,15 +431,39 @@ pub struct ListTaskCommand {
     /// but were not closed, these tasks are not returned by default
     #[arg(short, long, default_value_t = false)]
     pub overdue: bool,
-    /// limit the amount of tasks returned
-    #[arg(short, long, default_value_t = 100, value_parser = validate_limit)]
+    /// limit the amount of tasks returned, this is also the size of a page
+    #[arg(short, long, visible_alias = "page-size", default_value_t = 100, value_parser = validate_limit)]
     pub limit: usize,
     /// next page if the previous list command reached limit
     #[arg(short, long, default_value_t = false)]
     pub next_page: bool,
+    /// jump to this page of tasks, starting at 1
+    #[arg(long, value_parser = validate_page, conflicts_with = "next_page")]
+    pub page: Option<usize>,
     /// search for tasks containing this text in their content
     #[arg(long)]
     pub search: Option<String>,
+    /// only tasks of this priority, low|medium|high|urgent
+    #[arg(short, long, value_parser = parse_priority)]
+    pub priority: Option<u8>,
+    /// only tasks with this tag, the + in front is optional
+    #[arg(long)]
+    pub tag: Option<String>,
+    /// show the short id of each item next to its index, it can be used in place of the index
+    #[arg(long)]
+    pub ids: bool,
+    /// only open tasks due within this many days of their project's end, or after it
+    #[arg(long, conflicts_with_all = ["timestr", "days", "next_page", "page"])]
+    pub at_risk: Option<usize>,
+    /// only tasks created longer ago than this, such as 30d or 2w, overdue ones included
+    #[arg(long, value_parser = parse_duration)]
+    pub stale: Option<i64>,
+    /// only tasks in the categories of this place from config, such as office
+    #[arg(long, conflicts_with_all = ["category", "no_category"])]
+    pub at: Option<String>,
+    /// only open tasks due on a holiday from config, which may need another day
+    #[arg(long, conflicts_with_all = ["timestr", "days", "next_page", "page", "at_risk", "stale"])]
+    pub due_on_holiday: bool,
 }
 
 #[derive(Debug, Args)]
@@ -165,13 +471,16 @@ pub struct ListRecordCommand {
     /// category of the record
     #[arg(short, long)]
     pub category: Option<String>,
+    /// only records created without a category, same as --category ""
+    #[arg(long, conflicts_with = "category")]
+    pub no_category: bool,
     /// days of records to retrieve,
     /// e.g. 1 shows record made in the last 24 hours,
     /// value of 7 would show record made in the past week
-    #[arg(short, long, conflicts_with_all = ["starting_date", "ending_date"])]
+    #[arg(short, long, conflicts_with_all = ["starting_time", "ending_time"])]
     pub days: Option<usize>,
-    /// limit the amount of records returned
-    #[arg(short, long, default_value_t = 100, value_parser = validate_limit)]
+    /// limit the amount of records returned, this is also the size of a page
+    #[arg(short, long, visible_alias = "page-size", default_value_t = 100, value_parser = validate_limit)]
     pub limit: usize,
     /// list the record starting from this time,
     /// if this is date only, then it is non-inclusive
@@ -184,30 +493,545 @@ pub struct ListRecordCommand {
     /// next page if the previous list command reached limit
     #[arg(short, long, default_value_t = false)]
     pub next_page: bool,
+    /// jump to this page of records, starting at 1
+    #[arg(long, value_parser = validate_page, conflicts_with = "next_page")]
+    pub page: Option<usize>,
     /// search for records containing this text in their content
     #[arg(long)]
     pub search: Option<String>,
+    /// only records with this tag, the + in front is optional
+    #[arg(long)]
+    pub tag: Option<String>,
+    /// only records with a value of at least this
+    #[arg(long, allow_negative_numbers = true)]
+    pub value_min: Option<f64>,
+    /// only records with a value of at most this
+    #[arg(long, allow_negative_numbers = true)]
+    pub value_max: Option<f64>,
+    /// show the short id of each item next to its index, it can be used in place of the index
+    #[arg(long)]
+    pub ids: bool,
+    /// list the records under a heading for each day or week, with the number of records
+    #[arg(long, value_parser = ["day", "week"])]
+    pub group_by: Option<String>,
 }
 
 #[derive(Debug, Args)]
 pub struct ShowContentCommand {
-    /// index from previous list command
-    #[arg(value_parser = validate_index)]
-    pub index: usize,
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
+}
+
+#[derive(Debug, Args)]
+pub struct InCommand {
+    /// the thought, quoting it is optional
+    #[arg(required = true, trailing_var_arg = true)]
+    pub content: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct InboxCommand {
+    /// only list the inbox, its indices work with show, update and delete
+    #[arg(short, long)]
+    pub list: bool,
+    #[command(subcommand)]
+    pub command: Option<InboxSubcommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum InboxSubcommand {
+    /// let NLP propose a category and deadline for every item, and file them all at once
+    #[cfg(feature = "nlp")]
+    Refine(RefineCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct RefineCommand {
+    /// file the proposals without asking
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowCommand {
+    /// index from previous list command, category number such as work-12, or start of the id.
+    /// Picked from the open tasks when left out
+    #[arg(value_parser = parse_item_ref)]
+    pub index: Option<ItemRef>,
+}
+
+#[derive(Debug, Args)]
+pub struct DigestCommand {
+    /// send the digest to this address using smtp settings from config,
+    /// the digest is printed to stdout when omitted
+    #[arg(short, long)]
+    pub email: Option<String>,
+    /// only summarize tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// do not print or send anything when nothing is overdue or due today
+    #[arg(long, default_value_t = false)]
+    pub skip_empty: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NotifyCommand {
+    /// post overdue tasks to chat channels,
+    /// designed to be run from cron
+    Overdue(NotifyOverdueCommand),
+    /// send the task reminders that are due, to chat channels or printed
+    /// when none are configured, designed to be run from cron every few minutes
+    Reminders(NotifyRemindersCommand),
+    /// send the reports configured in config that are due by their schedule and run the
+    /// automations, designed to be run from cron every few minutes or with --daemon
+    Reports(NotifyReportsCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyOverdueCommand {
+    /// print the alerts instead of posting them
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyRemindersCommand {
+    /// print the reminders without sending them or marking them sent
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyReportsCommand {
+    /// print the reports instead of sending them
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+    /// send every configured report now, whatever its schedule
+    #[arg(long, default_value_t = false)]
+    pub all: bool,
+    /// keep running and check the schedules every minute
+    #[arg(long, default_value_t = false, conflicts_with = "all")]
+    pub daemon: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("source").required(true)))]
+pub struct ImportCommand {
+    /// markdown file with "- [ ] task" checkboxes,
+    /// Obsidian Tasks fields like 📅 2025-05-01 are understood
+    #[arg(long, group = "source")]
+    pub markdown: Option<String>,
+    /// csv file with a header row
+    #[arg(long, group = "source")]
+    pub csv: Option<String>,
+    /// csv of tasks exported from Outlook, completed ones are left out
+    #[arg(long, group = "source")]
+    pub outlook: Option<String>,
+    /// Tasks.json of a Google Takeout export, each list becomes a category
+    #[arg(long, group = "source")]
+    pub google_takeout: Option<String>,
+    /// json export of another app, read with the field mapping of --mapping
+    #[arg(long, group = "source", requires = "mapping")]
+    pub json: Option<String>,
+    /// file written by `tascli export --out`, items restored before are skipped
+    #[arg(long, group = "source", conflicts_with = "map")]
+    pub restore: Option<String>,
+    /// json file naming where the task fields are in the --json export, see the README
+    #[arg(long, requires = "json")]
+    pub mapping: Option<String>,
+    /// csv columns to use, e.g. "content=Title,deadline=Due,category=Project,done=Status",
+    /// default to columns named content, deadline and category, or the Outlook ones
+    #[arg(long, conflicts_with_all = ["markdown", "google_takeout", "json"])]
+    pub map: Option<String>,
+    /// category for all imported tasks, otherwise taken from the first #tag
+    /// of a markdown line, the mapped csv or json field, the Google Tasks list
+    /// or the exported category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// tick the checkboxes of imported tasks that have been done since
+    #[arg(long, default_value_t = false, requires = "markdown")]
+    pub write_back: bool,
+    /// preview the tasks that would be imported without saving them
+    #[arg(long, default_value_t = false, conflicts_with = "markdown")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("target").required(true)))]
+pub struct ExportCommand {
+    /// self-contained html report with open tasks, recent records and stats
+    #[arg(long, group = "target")]
+    pub html: Option<String>,
+    /// json file with every item, `tascli import --restore` reads it back
+    #[arg(long, group = "target")]
+    pub out: Option<String>,
+    /// only export items of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// days of records and completions to include
+    #[arg(short, long, default_value_t = 14)]
+    pub days: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct QueryCommand {
+    /// a single SELECT statement, such as "SELECT category, COUNT(*) FROM items GROUP BY 1"
+    pub sql: String,
+    /// print the rows as a json array of objects
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Debug, Args)]
+pub struct PublishCommand {
+    /// category to publish, repeat for several
+    #[arg(short, long = "category", required = true)]
+    pub categories: Vec<String>,
+    /// file to write, markdown for .md and html for .html
+    #[arg(short, long)]
+    pub out: String,
+    /// heading of the page, defaults to the categories
+    #[arg(long)]
+    pub title: Option<String>,
+    /// days of completed tasks to include
+    #[arg(short, long, default_value_t = 14)]
+    pub days: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct ForecastCommand {
+    /// days ahead to forecast, starting today
+    #[arg(short, long, default_value_t = 14)]
+    pub days: usize,
+    /// only forecast tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// days with more tasks due than this are flagged as overloaded
+    #[arg(short, long, default_value_t = 5)]
+    pub max: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct ScheduleCommand {
+    /// give overdue tasks and inbox items the earliest day below capacity, after a preview
+    #[arg(long)]
+    pub auto: bool,
+    /// only schedule tasks of this category, inbox items are left out
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// days ahead to schedule into, starting today
+    #[arg(short, long, default_value_t = 14)]
+    pub days: usize,
+    /// tasks a day can take, daily_capacity in config or 5 when not given
+    #[arg(long)]
+    pub capacity: Option<usize>,
+    /// apply without asking
+    #[arg(short, long, requires = "auto")]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TriageCommand {
+    /// only triage overdue tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PlanCommand {
+    /// overdue tasks and inbox items next to the coming 7 days, assigned with "1 3 fr"
+    Week(PlanWeekCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct PlanWeekCommand {
+    /// only plan overdue tasks of this category, inbox items are left out
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// completions per weekday and hour, task age at completion and overdue trends
+    Productivity(ProductivityCommand),
+    /// average time tasks spend in each open status, per category
+    Status(StatusReportCommand),
+    /// yearly grid of completed tasks per day
+    Heatmap(HeatmapCommand),
+    /// overdue tasks bucketed by how late they are, per category,
+    /// followed by the most overdue tasks
+    Overdue(OverdueReportCommand),
+    /// shareable summary of a month's completed tasks and records
+    Monthly(MonthlyReportCommand),
+    /// remaining open tasks of a category per day since its first task
+    Burndown(BurndownCommand),
+    /// records per week, with the trend of numbers logged in them
+    Records(RecordsReportCommand),
+    /// completions per week per category, with an estimated finish
+    /// date for large backlogs at the current pace
+    Velocity(VelocityCommand),
+    /// current and best streaks of habits, with the days they were missed
+    Streak(StreakCommand),
+    /// tasks of categories with an sla in config that missed it, done late or still open
+    Sla(SlaReportCommand),
+    /// how fragmented a day of focus sessions was: context switches between categories
+    /// per hour and the longest focus block
+    Switches(SwitchesReportCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct ProductivityCommand {
+    /// days of completed tasks to analyze
+    #[arg(short, long, default_value_t = 30)]
+    pub days: usize,
+    /// only analyze tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct StatusReportCommand {
+    /// days of status history to analyze
+    #[arg(short, long, default_value_t = 90)]
+    pub days: usize,
+    /// only analyze tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct HeatmapCommand {
+    /// only count completions of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// only count this habit, showing the days it was missed
+    #[arg(long, conflicts_with = "category")]
+    pub habit: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OverdueReportCommand {
+    /// only report overdue tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// how many of the most overdue tasks to list
+    #[arg(short, long, default_value_t = 10, value_parser = validate_limit)]
+    pub limit: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct MonthlyReportCommand {
+    /// month to summarize as YYYY-MM, default to the current month
+    #[arg(short, long)]
+    pub month: Option<String>,
+    /// output format
+    #[arg(short, long, default_value = "markdown", value_parser = ["markdown", "text"])]
+    pub format: String,
+    /// only summarize this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// write the summary to this file instead of printing it
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct BurndownCommand {
+    /// category of the project
+    #[arg(short, long)]
+    pub category: String,
+    /// also write the daily counts to this csv file
+    #[arg(long)]
+    pub csv: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RecordsReportCommand {
+    /// weeks of records to analyze
+    #[arg(short, long, default_value_t = 12)]
+    pub weeks: usize,
+    /// only analyze records of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// only analyze records containing this text
+    #[arg(short, long)]
+    pub search: Option<String>,
+    /// chart this field of records made from a template, such as distance
+    #[arg(short, long)]
+    pub field: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct VelocityCommand {
+    /// weeks of completed tasks the pace is averaged over
+    #[arg(short, long, default_value_t = 8)]
+    pub weeks: usize,
+    /// only report this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// open tasks from which a backlog gets an estimate
+    #[arg(long, default_value_t = 10)]
+    pub min_open: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct StreakCommand {
+    /// only report habits of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// how many of the latest days or periods to show
+    #[arg(short, long, default_value_t = 30)]
+    pub periods: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct SlaReportCommand {
+    /// days of added tasks to check
+    #[arg(short, long, default_value_t = 30)]
+    pub days: usize,
+    /// only report this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct SwitchesReportCommand {
+    /// day to report, default to today
+    #[arg(short, long, default_value = "today")]
+    pub date: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GitCommand {
+    /// install a post-commit hook in the current repository,
+    /// commits with "closes tascli#<index>" then complete the task
+    InstallHook(InstallHookCommand),
+    /// complete the tasks referenced by the last commit,
+    /// this is what the installed hook runs
+    #[command(hide = true)]
+    PostCommit,
+}
+
+#[derive(Debug, Args)]
+pub struct InstallHookCommand {
+    /// replace an existing post-commit hook
+    #[arg(short, long, default_value_t = false)]
+    pub force: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RemindersCommand {
+    /// push open tasks into a Reminders list,
+    /// and complete tasks whose reminder was checked off
+    Sync(RemindersSyncCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct RemindersSyncCommand {
+    /// name of the Reminders list, created when missing
+    #[arg(short, long, default_value = "tascli")]
+    pub list: String,
+    /// only push tasks of this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct GenDocsCommand {
+    /// man for roff man pages, markdown for a single reference page
+    #[arg(value_parser = ["man", "markdown"])]
+    pub format: String,
+    /// write the pages into this directory instead of printing the top level page
+    #[arg(short, long)]
+    pub out_dir: Option<String>,
+}
+
+#[cfg(feature = "nlp")]
+#[derive(Debug, Args)]
+pub struct HelpTopicsCommand {
+    /// overview, queries, compound, conditions, examples, patterns or all
+    pub topic: Option<String>,
+    /// show the lines of every topic containing these words, such as "overdue"
+    #[arg(short, long, conflicts_with = "topic")]
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ViewCommand {
+    /// save a command line under a name, e.g. `view save inbox -- list task --no-category`
+    Save {
+        /// name of the view
+        name: String,
+        /// the command and its arguments, after --
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        args: Vec<String>,
+    },
+    /// show the saved views
+    List,
+    /// remove a saved view
+    Delete {
+        /// name of the view
+        name: String,
+    },
+    /// run the saved view, extra arguments are appended to it
+    #[command(external_subcommand)]
+    Run(Vec<String>),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RulesCommand {
+    /// show the configured rules and whether they are valid
+    List,
+    /// run the overdue rules against open tasks,
+    /// designed to be run from cron
+    Run,
+}
+
+#[cfg(feature = "scripting")]
+#[derive(Debug, Subcommand)]
+pub enum ScriptCommand {
+    /// show the configured scripts with their events and command
+    List,
+    /// run the script registered as a command
+    Run {
+        /// command name of the script
+        name: String,
+        /// arguments passed to the script as `args`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[cfg(feature = "nlp")]
+#[derive(Debug, Args)]
+// `nlp help` is the natural language help below, not clap's
+#[command(disable_help_subcommand = true)]
 pub struct NLPCommand {
     /// natural language command description
     pub description: String,
     /// show the interpreted command before executing
     #[arg(short, long, default_value_t = false)]
     pub show: bool,
+    /// never prompt, answer with a clarification instead of asking, for voice assistants and bots
+    #[arg(long, conflicts_with = "show")]
+    pub non_interactive: bool,
+    /// print the result or clarification as a json object on stdout
+    #[arg(long, requires = "non_interactive")]
+    pub json: bool,
+    /// show whether the conditions hold and which commands would run, without running them
+    #[arg(long, conflicts_with_all = ["show", "non_interactive"])]
+    pub simulate: bool,
+    /// interpret the clauses separated by ';' at once, then run them in order as one command
+    #[arg(long, conflicts_with_all = ["non_interactive", "simulate"])]
+    pub parallel: bool,
     /// configuration commands for NLP
     #[command(subcommand)]
     pub config: Option<NLPConfigCommand>,
 }
 
+#[cfg(feature = "nlp")]
 #[derive(Debug, Subcommand)]
 pub enum NLPConfigCommand {
     /// enable NLP functionality
@@ -236,6 +1060,11 @@ pub enum NLPConfigCommand {
         /// partial input to get suggestions for
         input: String,
     },
+    /// print completions for natural language input, one per line, for shell completion
+    Complete {
+        /// partial input to complete
+        input: String,
+    },
     /// show available command patterns
     Patterns,
     /// show learning statistics
@@ -250,7 +1079,7 @@ pub enum NLPConfigCommand {
         #[arg(short, long)]
         action: String,
         /// The intended content
-        #[arg(short, long)]
+        #[arg(long)]
         content: String,
         /// The intended category (optional)
         #[arg(short, long)]
@@ -275,7 +1104,7 @@ pub enum NLPConfigCommand {
         #[arg(short, long)]
         action: String,
         /// The intended content
-        #[arg(short, long)]
+        #[arg(long)]
         content: String,
         /// The intended category (optional)
         #[arg(short, long)]
@@ -288,6 +1117,28 @@ pub enum NLPConfigCommand {
         /// Shortcut name to delete
         shortcut: String,
     },
+    /// list the categories remembered for phrases ("by gym I mean category health")
+    ListClarifications,
+    /// forget the category remembered for a phrase
+    ForgetClarification {
+        /// Phrase to forget
+        phrase: String,
+    },
+    /// save a command run on a schedule by `tascli notify reports`,
+    /// as in "every weekday at 9: if inbox has tasks then notify me"
+    Automate {
+        /// Name of the automation, saving it again replaces it
+        name: String,
+        /// Schedule and command separated by ": "
+        automation: String,
+    },
+    /// list the saved automations
+    ListAutomations,
+    /// delete a saved automation
+    DeleteAutomation {
+        /// Name of the automation to delete
+        name: String,
+    },
     /// enable NLP interpretation transparency
     EnableTransparency,
     /// disable NLP interpretation transparency
@@ -303,7 +1154,7 @@ pub enum NLPConfigCommand {
         #[arg(short, long, default_value_t = false)]
         no_transparency: bool,
         /// disable context display on startup
-        #[arg(short, long, default_value_t = false)]
+        #[arg(long, default_value_t = false)]
         no_context: bool,
     },
 }
@@ -329,14 +1180,59 @@ fn validate_limit(s: &str) -> Result<usize, String> {
     Ok(limit)
 }
 
+fn validate_page(s: &str) -> Result<usize, String> {
+    let page: usize = s.parse().map_err(|_| "Page must be a number".to_string())?;
+    if page == 0 {
+        return Err("Page must be greater than 0".to_string());
+    }
+    Ok(page)
+}
+
+/// An item on the command line, by index in the last listing, by category and number such as
+/// work-12, or by a prefix of its uuid. With display_id set to global indices are ids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ItemRef {
+    Index(usize),
+    Category(String, i64),
+    Id(String),
+}
+
+// Id prefixes shorter than this match too many items to be useful
+const MIN_ID_PREFIX_LEN: usize = 4;
+
+// Short numbers are indices, any other value is taken as an id prefix, "12345678" is the
+// prefix of a uuid. A name and a number joined by '-' is a category number, unless the name
+// is the first 8 hex digits of a uuid.
+pub(crate) fn parse_item_ref(s: &str) -> Result<ItemRef, String> {
+    if s.len() < SHORT_ID_LEN && s.chars().all(|c| c.is_ascii_digit()) {
+        return validate_index(s).map(ItemRef::Index);
+    }
+    if let Some((category, seq)) = s.rsplit_once('-')
+        && !category.is_empty()
+        && !(category.len() == SHORT_ID_LEN && category.chars().all(|c| c.is_ascii_hexdigit()))
+        && seq.chars().all(|c| c.is_ascii_digit())
+        && let Ok(seq) = seq.parse::<i64>()
+    {
+        return Ok(ItemRef::Category(category.to_string(), seq));
+    }
+    let prefix = s.to_ascii_lowercase();
+    if prefix.len() < MIN_ID_PREFIX_LEN
+        || !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+    {
+        return Err(format!(
+            "'{}' is neither an index nor an id, ids are at least {} hex digits like a3f2",
+            s, MIN_ID_PREFIX_LEN
+        ));
+    }
+    Ok(ItemRef::Id(prefix))
+}
+
+// Not capped, an index is an id when display_id is global
 fn validate_index(s: &str) -> Result<usize, String> {
     let index: usize = s.parse().map_err(|_| "Index must be a number".to_string())?;
     if index == 0 {
         return Err("Index must be greater than 0".to_string());
     }
-    if index > 65536 {
-        return Err("Index cannot exceed 65536".to_string());
-    }
     Ok(index)
 }
 
@@ -352,7 +1248,24 @@ fn validate_timestr(s: &str) -> Result<String, String> {
     }
 }
 
-fn parse_status(s: &str) -> Result<u8, String> {
+fn validate_schedule(s: &str) -> Result<String, String> {
+    parse_recurring_timestr(s).map(|_| s.to_string())
+}
+
+/// Priorities are stored from 1 for low to 4 for urgent, 0 is none
+pub(crate) fn parse_priority(s: &str) -> Result<u8, String> {
+    let s = s.to_lowercase();
+    if s == "none" {
+        return Ok(0);
+    }
+    PRIORITIES
+        .iter()
+        .position(|p| *p == s)
+        .map(|i| i as u8 + 1)
+        .ok_or_else(|| format!("Invalid priority: '{}'. Expected low|medium|high|urgent", s))
+}
+
+pub(crate) fn parse_status(s: &str) -> Result<u8, String> {
     match s.to_lowercase().as_str() {
         "ongoing" => Ok(0),
         "done" | "complete" | "completed" => Ok(1),
@@ -1,5 +1,6 @@
 use chrono::{
     Datelike,
+    Days,
     Duration,
     Local,
     NaiveDate,
@@ -10,18 +11,34 @@ use chrono::{
     Weekday,
 };
 
+use crate::{
+    clock,
+    config::{
+        self,
+        holidays,
+    },
+};
+
 pub fn days_before_to_unix_epoch(d: usize) -> i64 {
-    let now = Local::now();
+    let now = clock::now();
     let past_date = now - Duration::days(d as i64);
     past_date.timestamp()
 }
 
 pub fn days_after_to_unix_epoch(d: usize) -> i64 {
-    let now = Local::now();
+    let now = clock::now();
     let future_date = now + Duration::days(d as i64);
     future_date.timestamp()
 }
 
+/// Whether a deadline is at 23:59:59 local time, where deadlines given as a date only land.
+pub fn is_end_of_day(timestamp: i64) -> bool {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .is_some_and(|dt| dt.hour() == 23 && dt.minute() == 59 && dt.second() == 59)
+}
+
 pub fn to_unix_epoch(s: &str) -> Result<i64, String> {
     let dt = parse_flexible_timestr(s)?;
     Local
@@ -32,8 +49,13 @@ pub fn to_unix_epoch(s: &str) -> Result<i64, String> {
 }
 
 pub fn parse_flexible_timestr(s: &str) -> Result<NaiveDateTime, String> {
-    let s = s.trim();
-    let now = Local::now().naive_local();
+    parse_timestr_at(s, clock::now().naive_local())
+}
+
+/// Parse a time string relative to the given local time rather than the clock.
+pub(crate) fn parse_timestr_at(s: &str, now: NaiveDateTime) -> Result<NaiveDateTime, String> {
+    let s = shorthand(s.trim());
+    let s = s.as_str();
 
     // Default time when only date is specified (end of day)
     let default_time = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
@@ -84,9 +106,24 @@ fn parse_date_portion(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
         "sunday" | "eow" | "week" => return Ok(next_weekday(today, Weekday::Sun)),
         "year" | "eoy" => return Ok(today.with_month(12).unwrap().with_day(31).unwrap()),
         "month" | "eom" => return Ok(last_day_of_month(today)),
+        "quarter" | "eoq" => return Ok(end_of_quarter(today, None, config::fiscal_year_start())),
+        "fy" | "eofy" => return Ok(end_of_fiscal_year(today, config::fiscal_year_start())),
+        "nbd" | "workday" => return Ok(holidays::next_business_day(today)),
+        "q1" | "q2" | "q3" | "q4" => {
+            let quarter = s[1..].parse().unwrap();
+            return Ok(end_of_quarter(today, Some(quarter), config::fiscal_year_start()));
+        }
         _ => {}
     }
 
+    // "+3d", a number of days from today
+    let offset = s.strip_prefix('+').and_then(|d| d.strip_suffix('d'));
+    if let Some(days) = offset.and_then(|d| d.parse::<u32>().ok()) {
+        return today
+            .checked_add_days(Days::new(days as u64))
+            .ok_or_else(|| format!("'{}' is too far ahead", s));
+    }
+
     let full_date_formats = [
         "%Y/%m/%d", // 2025/06/12
         "%Y-%m-%d", // 2025-06-12
@@ -151,16 +188,60 @@ fn parse_time_portion(s: &str) -> Result<NaiveTime, String> {
 }
 
 fn last_day_of_month(date: NaiveDate) -> NaiveDate {
-    let next_month = if date.month() == 12 {
-        date.with_year(date.year() + 1)
-            .unwrap()
-            .with_month(1)
-            .unwrap()
+    // From the first of the month, the 31st has no counterpart in shorter months
+    add_months(date.year(), date.month(), 1) - Duration::days(1)
+}
+
+// "end of Q3 5PM" reads as "q3 5pm", "end of the fiscal year" as "fy" and
+// "next business day" as "nbd"
+fn shorthand(s: &str) -> String {
+    let lower = s.to_lowercase();
+    if let Some(time) = lower.strip_prefix("next business day") {
+        return format!("nbd{}", time);
+    }
+    let Some(rest) = lower.strip_prefix("end of ") else {
+        return s.to_string();
+    };
+    let rest = rest.strip_prefix("the ").unwrap_or(rest);
+    match rest.strip_prefix("fiscal year") {
+        Some(time) => format!("fy{}", time),
+        None => rest.to_string(),
+    }
+}
+
+// First day of the month `months` after the given year and month
+fn add_months(year: i32, month: u32, months: u32) -> NaiveDate {
+    let total = year * 12 + (month - 1) as i32 + months as i32;
+    NaiveDate::from_ymd_opt(total.div_euclid(12), total.rem_euclid(12) as u32 + 1, 1).unwrap()
+}
+
+// First day of the fiscal year the date falls in
+fn fiscal_year_of(date: NaiveDate, fiscal_start: u32) -> NaiveDate {
+    let year = if date.month() >= fiscal_start {
+        date.year()
     } else {
-        date.with_month(date.month() + 1).unwrap()
+        date.year() - 1
     };
-    let first_of_next = next_month.with_day(1).unwrap();
-    first_of_next - Duration::days(1)
+    NaiveDate::from_ymd_opt(year, fiscal_start, 1).unwrap()
+}
+
+fn end_of_fiscal_year(date: NaiveDate, fiscal_start: u32) -> NaiveDate {
+    let start = fiscal_year_of(date, fiscal_start);
+    add_months(start.year(), start.month(), 12) - Duration::days(1)
+}
+
+// Last day of the current fiscal quarter, or of the given quarter (1 to 4) when it is still
+// ahead or running, else of that quarter in the next fiscal year
+fn end_of_quarter(date: NaiveDate, quarter: Option<u32>, fiscal_start: u32) -> NaiveDate {
+    let start = fiscal_year_of(date, fiscal_start);
+    let current = (date.month() + 12 - fiscal_start) % 12 / 3 + 1;
+    let quarter = quarter.unwrap_or(current);
+    let end = add_months(start.year(), start.month(), quarter * 3) - Duration::days(1);
+    if end < date {
+        add_months(start.year(), start.month(), quarter * 3 + 12) - Duration::days(1)
+    } else {
+        end
+    }
 }
 
 fn next_weekday(from_date: NaiveDate, weekday: Weekday) -> NaiveDate {
@@ -374,9 +455,71 @@ fn parse_month_day(s: &str) -> Result<(u32, u32), String> {
     Ok((month, day))
 }
 
+// Durations such as 30m, 12h, 7d or 2w
+pub fn parse_duration(s: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid duration '{}', expected e.g. 12h, 7d or 2w", s);
+    let (number, unit) = s.split_at(s.len().saturating_sub(1));
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => return Err(invalid()),
+    };
+    Ok(number * seconds)
+}
+
+/// The deadline `window` after `from`. Windows are durations such as 48h or 3d, or business
+/// days such as 2bd, which end with the last of those days.
+pub fn window_deadline(window: &str, from: i64) -> Result<i64, String> {
+    let compact: String = window.split_whitespace().collect::<String>().to_lowercase();
+    let business = ["businessdays", "businessday", "bd"]
+        .iter()
+        .find_map(|suffix| compact.strip_suffix(suffix));
+    let Some(days) = business else {
+        return Ok(from + parse_duration(&compact)?);
+    };
+    let days: u32 = days
+        .parse()
+        .map_err(|_| format!("invalid window '{}', expected e.g. 2bd, 48h or 3d", window))?;
+    let start = Local
+        .timestamp_opt(from, 0)
+        .single()
+        .ok_or_else(|| format!("invalid start time {}", from))?;
+    let mut date = start.date_naive();
+    for _ in 0..days {
+        date = holidays::next_business_day(date);
+    }
+    to_unix_epoch(&date.format("%Y/%m/%d").to_string())
+}
+
+// Reminder lead times such as "1d before" or "30m", in seconds
+pub fn parse_lead_time(s: &str) -> Result<i64, String> {
+    let s = s.trim();
+    let duration = s.strip_suffix("before").unwrap_or(s).trim();
+    match parse_duration(duration)? {
+        0 => Err(format!("lead time '{}' must be more than zero", s)),
+        seconds if seconds < 0 => Err(format!("lead time '{}' cannot be negative", s)),
+        seconds => Ok(seconds),
+    }
+}
+
+// The largest unit that divides the duration evenly, the reverse of parse_duration
+pub fn format_duration(seconds: i64) -> String {
+    let unit = [("w", 7 * 86400), ("d", 86400), ("h", 3600)]
+        .into_iter()
+        .find(|(_, size)| seconds % size == 0);
+    match unit {
+        Some((unit, size)) => format!("{}{}", seconds / size, unit),
+        None => format!("{}m", seconds / 60),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -397,6 +540,14 @@ mod tests {
             "friday",
             "friday 3PM",
             "3/24",
+            "eoq",
+            "Q3",
+            "end of Q3",
+            "end of the quarter 5PM",
+            "end of fiscal year",
+            "nbd",
+            "next business day 9AM",
+            "+3d",
         ];
 
         for input in valid_inputs {
@@ -417,6 +568,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_quarters() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(end_of_quarter(date(2025, 5, 10), None, 1), date(2025, 6, 30));
+        assert_eq!(end_of_quarter(date(2025, 5, 10), Some(3), 1), date(2025, 9, 30));
+        // Q1 is over, the next one is meant
+        assert_eq!(end_of_quarter(date(2025, 5, 10), Some(1), 1), date(2026, 3, 31));
+        // Fiscal year from July: Q1 is Jul-Sep, Q3 is Jan-Mar
+        assert_eq!(end_of_quarter(date(2025, 8, 1), None, 7), date(2025, 9, 30));
+        assert_eq!(end_of_quarter(date(2025, 8, 1), Some(3), 7), date(2026, 3, 31));
+        assert_eq!(end_of_quarter(date(2026, 2, 1), None, 7), date(2026, 3, 31));
+        assert_eq!(end_of_fiscal_year(date(2026, 2, 1), 7), date(2026, 6, 30));
+        assert_eq!(end_of_fiscal_year(date(2025, 12, 31), 1), date(2025, 12, 31));
+        assert_eq!(end_of_fiscal_year(date(2025, 10, 1), 10), date(2026, 9, 30));
+
+        assert_eq!(shorthand("end of Q3 5PM"), "q3 5pm");
+        assert_eq!(shorthand("end of the fiscal year"), "fy");
+        assert_eq!(shorthand("Q3"), "Q3");
+        assert_eq!(shorthand("Next business day 9AM"), "nbd 9am");
+        let q3 = parse_flexible_timestr("end of Q3").unwrap();
+        assert_eq!(q3.time(), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+        assert_eq!((q3.month(), q3.day()), (9, 30));
+    }
+
+    #[test]
+    fn test_window_deadline() {
+        // Thursday 2026/10/15 at 10:00
+        let from = to_unix_epoch("2026/10/15 10:00").unwrap();
+        assert_eq!(window_deadline("48h", from), Ok(from + 48 * 3600));
+        assert_eq!(window_deadline("2bd", from), to_unix_epoch("2026/10/19"));
+        assert_eq!(window_deadline("1 business day", from), to_unix_epoch("2026/10/16"));
+        assert!(window_deadline("soon", from).is_err());
+        assert!(window_deadline("xbd", from).is_err());
+    }
+
     #[test]
     fn test_invalid_inputs() {
         // Collection of inputs that should fail to parse
@@ -545,4 +731,143 @@ mod tests {
         assert!(parse_recurring_timestr("Every 32nd of the Month").is_err());
         assert!(parse_recurring_timestr("Every 2/30").is_err()); // Invalid date
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m").unwrap(), 1800);
+        assert_eq!(parse_duration("12h").unwrap(), 43200);
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7").is_err());
+    }
+
+    #[test]
+    fn test_lead_times() {
+        assert_eq!(parse_lead_time("1d before").unwrap(), 86400);
+        assert_eq!(parse_lead_time(" 1h  before ").unwrap(), 3600);
+        assert_eq!(parse_lead_time("30m").unwrap(), 1800);
+        assert!(parse_lead_time("0h before").is_err());
+        assert!(parse_lead_time("-1h before").is_err());
+        assert!(parse_lead_time("before").is_err());
+        assert!(parse_lead_time("1d after").is_err());
+
+        assert_eq!(format_duration(86400), "1d");
+        assert_eq!(format_duration(2 * 7 * 86400), "2w");
+        assert_eq!(format_duration(90 * 60), "90m");
+        assert_eq!(format_duration(36 * 3600), "36h");
+    }
+
+    // Property tests against chrono as the oracle, with "now" drawn at random so that month
+    // ends, leap days and every weekday get their turn
+    fn dates() -> impl Strategy<Value = NaiveDate> {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+        (0i64..47_000).prop_map(move |d| epoch + Duration::days(d))
+    }
+
+    fn times() -> impl Strategy<Value = NaiveTime> {
+        (0u32..24, 0u32..60).prop_map(|(h, m)| NaiveTime::from_hms_opt(h, m, 0).unwrap())
+    }
+
+    fn nows() -> impl Strategy<Value = NaiveDateTime> {
+        (dates(), 0u32..86_400).prop_map(|(date, seconds)| {
+            date.and_time(NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0).unwrap())
+        })
+    }
+
+    fn end_of(date: NaiveDate) -> NaiveDateTime {
+        date.and_hms_opt(23, 59, 59).unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_full_dates(
+            date in dates(),
+            now in nows(),
+            format in prop::sample::select(
+                vec!["%Y/%m/%d", "%Y-%m-%d", "%m/%d/%Y", "%m-%d-%Y", "%-m/%-d/%Y"],
+            ),
+        ) {
+            let input = date.format(format).to_string();
+            prop_assert_eq!(parse_timestr_at(&input, now), Ok(end_of(date)), "{}", input);
+        }
+
+        #[test]
+        fn prop_dates_with_times(date in dates(), time in times(), now in nows()) {
+            let expected = date.and_time(time);
+            let mut formats = vec!["%Y-%m-%d %H:%M", "%Y/%m/%d %-I:%M%p"];
+            if time.minute() == 0 {
+                formats.push("%m/%d/%Y %-I%P");
+            }
+            for format in formats {
+                let input = expected.format(format).to_string();
+                prop_assert_eq!(parse_timestr_at(&input, now), Ok(expected), "{}", input);
+            }
+        }
+
+        #[test]
+        fn prop_times_are_today(time in times(), now in nows()) {
+            let input = time.format("%H:%M").to_string();
+            prop_assert_eq!(parse_timestr_at(&input, now), Ok(now.date().and_time(time)));
+        }
+
+        #[test]
+        fn prop_month_day_in_current_year(date in dates(), now in nows()) {
+            let input = date.format("%-m/%-d").to_string();
+            let expected = NaiveDate::from_ymd_opt(now.year(), date.month(), date.day());
+            // February 29th outside a leap year is an error
+            prop_assert_eq!(parse_timestr_at(&input, now).ok(), expected.map(end_of));
+        }
+
+        #[test]
+        fn prop_weekdays_are_ahead(
+            day in prop::sample::select(
+                vec!["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"],
+            ),
+            now in nows(),
+        ) {
+            let parsed = parse_timestr_at(day, now).unwrap();
+            let ahead = (parsed.date() - now.date()).num_days();
+            prop_assert!((1..=7).contains(&ahead), "{} is {} days ahead", day, ahead);
+            prop_assert_eq!(parsed.weekday().to_string().to_lowercase(), &day[..3]);
+            prop_assert_eq!(parsed.time(), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+        }
+
+        #[test]
+        fn prop_period_ends(now in nows()) {
+            let today = now.date();
+            let month_end = parse_timestr_at("eom", now).unwrap().date();
+            prop_assert_eq!((month_end.year(), month_end.month()), (today.year(), today.month()));
+            prop_assert_eq!((month_end + Duration::days(1)).day(), 1);
+            let year_end = parse_timestr_at("eoy", now).unwrap().date();
+            prop_assert_eq!(year_end, NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap());
+            let quarter_end = parse_timestr_at("eoq", now).unwrap().date();
+            prop_assert!(quarter_end >= month_end && quarter_end.month().is_multiple_of(3));
+            prop_assert!((quarter_end - today).num_days() < 92);
+        }
+
+        #[test]
+        fn prop_day_offsets(days in 0u64..3650, now in nows()) {
+            let expected = end_of(now.date() + Days::new(days));
+            prop_assert_eq!(parse_timestr_at(&format!("+{}d", days), now), Ok(expected));
+        }
+
+        #[test]
+        fn prop_invalid_dates(year in 1970i32..2100, month in 13u32..100, day in 32u32..100) {
+            let now = Local::now().naive_local();
+            let month_out = format!("{}-{:02}-{:02}", year, month, day % 28 + 1);
+            prop_assert!(parse_timestr_at(&month_out, now).is_err(), "{}", month_out);
+            let day_out = format!("{}/{:02}/{}", year, month % 12 + 1, day);
+            prop_assert!(parse_timestr_at(&day_out, now).is_err(), "{}", day_out);
+        }
+
+        #[test]
+        fn prop_dates_end_the_local_day(date in dates()) {
+            let epoch = to_unix_epoch(&date.format("%Y-%m-%d").to_string()).unwrap();
+            prop_assert!(is_end_of_day(epoch));
+        }
+
+        #[test]
+        fn prop_never_panics(input in "\\PC{0,24}", now in nows()) {
+            let _ = parse_timestr_at(&input, now);
+        }
+    }
 }
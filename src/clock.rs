@@ -0,0 +1,55 @@
+use std::cell::Cell;
+
+use chrono::{
+    DateTime,
+    Local,
+    TimeZone,
+};
+
+thread_local! {
+    // Unix timestamp the clock is pinned to, by --now or a test
+    static PINNED: Cell<Option<i64>> = const { Cell::new(None) };
+}
+
+/// Pins the clock of the current thread to a unix timestamp, None goes back to the system clock.
+/// Everything date dependent reads the time from here, so a pinned clock makes "today",
+/// overdue checks and reports the same on every run.
+pub fn set_now(timestamp: Option<i64>) {
+    PINNED.with(|pinned| pinned.set(timestamp));
+}
+
+/// The current local time, or the pinned one.
+pub fn now() -> DateTime<Local> {
+    PINNED
+        .with(Cell::get)
+        .and_then(|timestamp| Local.timestamp_opt(timestamp, 0).single())
+        .unwrap_or_else(Local::now)
+}
+
+/// The current unix timestamp, or the pinned one.
+pub fn timestamp() -> i64 {
+    now().timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::timestr,
+        db::item::Item,
+    };
+
+    #[test]
+    fn test_pinned_clock() {
+        let noon = timestr::to_unix_epoch("2026/10/15 12:00").unwrap();
+        set_now(Some(noon));
+        assert_eq!(timestamp(), noon);
+        assert_eq!(now().timestamp(), noon);
+        // Relative times and new items follow it
+        assert_eq!(timestr::to_unix_epoch("tomorrow 9AM"), timestr::to_unix_epoch("2026/10/16 9AM"));
+        let item = Item::new("task".to_string(), "work".to_string(), "pinned".to_string());
+        assert_eq!(item.create_time, noon);
+        set_now(None);
+        assert!((timestamp() - Local::now().timestamp()).abs() < 5);
+    }
+}
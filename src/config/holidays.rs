@@ -0,0 +1,164 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use chrono::{
+    Datelike,
+    Duration,
+    NaiveDate,
+    Weekday,
+};
+
+use super::{
+    expand_path,
+    get_config,
+};
+use crate::error::TascliError;
+
+static HOLIDAYS: OnceLock<Vec<NaiveDate>> = OnceLock::new();
+// Longest event of a calendar taken as days off, longer ones are not holidays
+const MAX_EVENT_DAYS: i64 = 31;
+
+/// The configured holidays, left empty when the calendar cannot be read.
+/// `load_holidays` reports what is wrong with it.
+pub fn holidays() -> &'static [NaiveDate] {
+    HOLIDAYS.get_or_init(|| load_holidays().unwrap_or_default())
+}
+
+/// Reads the holidays of the config, dates such as "2026-12-25" and paths of .ics calendars.
+pub fn load_holidays() -> Result<Vec<NaiveDate>, TascliError> {
+    let mut dates = Vec::new();
+    for entry in get_config()?.holidays {
+        let entry = entry.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(entry, "%Y-%m-%d") {
+            dates.push(date);
+            continue;
+        }
+        let path = resolve_path(entry)?;
+        let calendar = fs::read_to_string(&path)
+            .map_err(|e| TascliError::io(format!("Failed to read holidays {}", entry), e))?;
+        dates.extend(parse_ics(&calendar));
+    }
+    dates.sort();
+    dates.dedup();
+    Ok(dates)
+}
+
+fn resolve_path(path: &str) -> Result<PathBuf, TascliError> {
+    expand_path(path)?.ok_or_else(|| {
+        TascliError::Config(format!(
+            "holidays must be dates such as 2026-12-25 or absolute or home relative calendar \
+             paths, it cannot be '{}'",
+            path
+        ))
+    })
+}
+
+pub fn is_holiday(date: NaiveDate) -> bool {
+    holidays().binary_search(&date).is_ok()
+}
+
+/// Weekdays that are not holidays.
+pub fn is_business_day(date: NaiveDate) -> bool {
+    is_workday(date, holidays())
+}
+
+/// The first business day after `date`.
+pub fn next_business_day(date: NaiveDate) -> NaiveDate {
+    next_workday(date, holidays())
+}
+
+fn is_workday(date: NaiveDate, holidays: &[NaiveDate]) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && holidays.binary_search(&date).is_err()
+}
+
+fn next_workday(date: NaiveDate, holidays: &[NaiveDate]) -> NaiveDate {
+    let mut next = date + Duration::days(1);
+    while !is_workday(next, holidays) {
+        next += Duration::days(1);
+    }
+    next
+}
+
+// The days covered by the events of an iCalendar file. All-day events end the day before
+// their DTEND, timed ones on the day of it.
+fn parse_ics(calendar: &str) -> Vec<NaiveDate> {
+    // Long lines are folded onto lines starting with a space
+    let unfolded = calendar.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut dates = Vec::new();
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<(NaiveDate, bool)> = None;
+    for line in unfolded.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.split(';').next().unwrap_or_default();
+        let date = value.get(..8).and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok());
+        match name {
+            "BEGIN" if value == "VEVENT" => (start, end) = (None, None),
+            "DTSTART" => start = date,
+            "DTEND" => end = date.map(|d| (d, value.len() == 8)),
+            "END" if value == "VEVENT" => {
+                let Some(first) = start else {
+                    continue;
+                };
+                let last = match end {
+                    Some((day, true)) => day - Duration::days(1),
+                    Some((day, false)) => day,
+                    None => first,
+                };
+                let days = (last - first).num_days();
+                if (0..MAX_EVENT_DAYS).contains(&days) {
+                    dates.extend((0..=days).map(|d| first + Duration::days(d)));
+                }
+            }
+            _ => {}
+        }
+    }
+    dates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ics() {
+        let calendar = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20261225\r\n\
+            DTEND;VALUE=DATE:20261227\r\n\
+            SUMMARY:Christmas\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20270101\r\n\
+            SUMMARY:New Year's\r\n\
+            \x20Day\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20260704T000000Z\r\n\
+            DTEND:20260704T235959Z\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            parse_ics(calendar),
+            vec![date(2026, 12, 25), date(2026, 12, 26), date(2027, 1, 1), date(2026, 7, 4)]
+        );
+    }
+
+    #[test]
+    fn test_next_workday() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // Friday to Monday, Tuesday to Wednesday
+        assert_eq!(next_workday(date(2026, 10, 16), &[]), date(2026, 10, 19));
+        assert_eq!(next_workday(date(2026, 10, 20), &[]), date(2026, 10, 21));
+        // Christmas and Boxing Day on Friday and Monday
+        let holidays = [date(2026, 12, 25), date(2026, 12, 28)];
+        assert_eq!(next_workday(date(2026, 12, 24), &holidays), date(2026, 12, 29));
+        assert!(!is_workday(date(2026, 12, 25), &holidays));
+        assert!(!is_workday(date(2026, 10, 17), &[]));
+    }
+}
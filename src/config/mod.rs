@@ -1,13 +1,42 @@
 use std::{
+    env,
     fs,
-    path::PathBuf,
+    io::{
+        self,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::OnceLock,
 };
 
 use nanoserde::{DeJson, SerJson};
 
+use crate::{
+    args::timestr::parse_duration,
+    error::TascliError,
+};
+
+pub mod holidays;
+
 const DB_NAME: &str = "tascli.db";
+const CONFIG_NAME: &str = "config.json";
 const DEFAULT_DATA_DIR: &[&str] = &[".local", "share", "tascli"];
-const CONFIG_PATH: &[&str] = &[".config", "tascli", "config.json"];
+const DEFAULT_CONFIG_DIR: &[&str] = &[".config", "tascli"];
+const CONFIG_DIR_ENV: &str = "TASCLI_CONFIG_DIR";
+
+static CURRENT_USER: OnceLock<Option<String>> = OnceLock::new();
+static CATEGORY_STYLES: OnceLock<Vec<CategoryConfig>> = OnceLock::new();
+static RECORD_TEMPLATES: OnceLock<Vec<RecordTemplateConfig>> = OnceLock::new();
+static STALE_AFTER: OnceLock<Option<i64>> = OnceLock::new();
+static DISPLAY_ID: OnceLock<DisplayId> = OnceLock::new();
+static COLOR_PALETTE: OnceLock<ColorPalette> = OnceLock::new();
+static STATUS_ICONS: OnceLock<bool> = OnceLock::new();
+// Age of an ongoing task before listings mark it as stale, unless configured
+const DEFAULT_STALE_AFTER: i64 = 30 * 86400;
+const DEFAULT_DAILY_CAPACITY: usize = 5;
 
 #[derive(Default, DeJson, SerJson)]
 pub struct Config {
@@ -17,6 +46,206 @@ pub struct Config {
     /// NLP configuration settings
     #[nserde(default)]
     pub nlp: NLPConfigSection,
+    /// SMTP settings used by `tascli digest --email`
+    #[nserde(default)]
+    pub smtp: SmtpConfigSection,
+    /// Webhooks notified when items are created, completed or deleted
+    #[nserde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Slack or Discord channels receiving completions and overdue alerts
+    #[nserde(default)]
+    pub chat: Vec<ChatConfig>,
+    /// Reports sent on a schedule by `tascli notify reports`
+    #[nserde(default)]
+    pub reports: Vec<ReportConfig>,
+    /// Automation rules such as "when task in category finance is created then set deadline eom"
+    #[nserde(default)]
+    pub rules: Vec<String>,
+    /// Rhai scripts run on events or as custom commands
+    #[nserde(default)]
+    pub scripts: Vec<ScriptConfig>,
+    /// Language of the output such as "de", LC_ALL, LC_MESSAGES or LANG when empty
+    #[nserde(default)]
+    pub language: String,
+    /// Always use the --plain output
+    #[nserde(default)]
+    pub plain: bool,
+    /// Print the overdue tasks carried over and the tasks due today on the first run of a day
+    #[nserde(default)]
+    pub rollover_banner: bool,
+    /// Default for --user, everything is shared when empty
+    #[nserde(default)]
+    pub user: String,
+    /// Colors and icons of categories in listings
+    #[nserde(default)]
+    pub categories: Vec<CategoryConfig>,
+    /// Age such as "30d" or "2w" before ongoing tasks are marked stale in listings,
+    /// "off" for never, 30 days when empty
+    #[nserde(default)]
+    pub stale_after: String,
+    /// Tasks a day can take before `tascli schedule` moves on to the next day, 5 when 0
+    #[nserde(default)]
+    pub daily_capacity: usize,
+    /// Named places such as "office", listing the categories of tasks done there
+    #[nserde(default)]
+    pub places: Vec<PlaceConfig>,
+    /// Month the fiscal year starts in, 1 to 12, for "eoq", "q3" and "eofy" deadlines.
+    /// January when 0
+    #[nserde(default)]
+    pub fiscal_year_start: usize,
+    /// Days off skipped by business day math, dates such as "2026-12-25" or .ics calendars
+    #[nserde(default)]
+    pub holidays: Vec<String>,
+    /// What listings number items by: "index" in the listing (the default), "category"
+    /// such as work-12, or "global" for the ids that never change
+    #[nserde(default)]
+    pub display_id: String,
+    /// Colors of the output: "default", or "deuteranopia" or "protanopia" for colors told
+    /// apart with red-green color blindness
+    #[nserde(default)]
+    pub palette: String,
+    /// Start listed rows with an icon of their status, ASCII signs such as [x] on terminals
+    /// that cannot show emoji
+    #[nserde(default)]
+    pub status_icons: bool,
+    /// Records such as "workout" that `tascli record workout` asks the fields of
+    #[nserde(default)]
+    pub record_templates: Vec<RecordTemplateConfig>,
+    /// Run commands with a mistyped subcommand or flag as corrected, when the correction is
+    /// certain, instead of only suggesting it
+    #[nserde(default)]
+    pub auto_correct: bool,
+}
+
+/// How listings number items, and what a plain number given on the command line means.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayId {
+    Index,
+    Category,
+    Global,
+}
+
+/// The configured colors of the output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorPalette {
+    Default,
+    Deuteranopia,
+    Protanopia,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct PlaceConfig {
+    #[nserde(default)]
+    pub name: String,
+    #[nserde(default)]
+    pub categories: Vec<String>,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct RecordTemplateConfig {
+    #[nserde(default)]
+    pub name: String,
+    /// The content with a {field} for each answer, {field:unit} asks for a number such as
+    /// "{type} for {duration:min}, {distance:km}"
+    #[nserde(default)]
+    pub template: String,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct CategoryConfig {
+    #[nserde(default)]
+    pub name: String,
+    /// A color name such as "red" or "bright blue", or a 256 color number such as "208"
+    #[nserde(default)]
+    pub color: String,
+    /// Shown in front of the category, such as an emoji
+    #[nserde(default)]
+    pub icon: String,
+    /// Completion window of its tasks such as "2bd" (business days), "48h" or "3d",
+    /// new tasks without a deadline are due at its end
+    #[nserde(default)]
+    pub sla: String,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct ScriptConfig {
+    /// Path of the script, absolute or starting with '~'
+    #[nserde(default)]
+    pub path: String,
+    /// Events that run the script: create, done, delete
+    #[nserde(default)]
+    pub events: Vec<String>,
+    /// Runs the script as `tascli <command>`, not a command when empty
+    #[nserde(default)]
+    pub command: String,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct WebhookConfig {
+    #[nserde(default)]
+    pub url: String,
+    /// Shared secret used to sign the payload with HMAC-SHA256, unsigned when empty
+    #[nserde(default)]
+    pub secret: String,
+    /// Events to send: create, done, delete. All events are sent when empty
+    #[nserde(default)]
+    pub events: Vec<String>,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct ChatConfig {
+    /// slack or discord
+    #[nserde(default)]
+    pub kind: String,
+    /// Incoming webhook url of the channel
+    #[nserde(default)]
+    pub url: String,
+    /// Only notify about items in these categories, all categories when empty
+    #[nserde(default)]
+    pub categories: Vec<String>,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct ReportConfig {
+    /// digest, overdue or weekly
+    #[nserde(default)]
+    pub report: String,
+    /// When to send it, such as "daily 7AM" or "weekly Monday 9AM"
+    #[nserde(default)]
+    pub schedule: String,
+    /// Only cover tasks of this category, all categories when empty
+    #[nserde(default)]
+    pub category: String,
+    /// Address the report is emailed to with the smtp settings, not emailed when empty
+    #[nserde(default)]
+    pub email: String,
+    /// Post the report to the chat channels
+    #[nserde(default)]
+    pub chat: bool,
+    /// Post the report to the webhooks listing "report" in their events
+    #[nserde(default)]
+    pub webhook: bool,
+}
+
+#[derive(Default, DeJson, SerJson)]
+pub struct SmtpConfigSection {
+    /// SMTP server host, e.g. smtp.gmail.com
+    #[nserde(default)]
+    pub host: String,
+    /// SMTP server port, defaults to 587 for starttls, 465 for tls and 25 for none
+    #[nserde(default)]
+    pub port: u16,
+    /// Username for SMTP authentication, authentication is skipped when empty
+    #[nserde(default)]
+    pub username: String,
+    #[nserde(default)]
+    pub password: String,
+    /// Sender address, defaults to username
+    #[nserde(default)]
+    pub from: String,
+    /// Connection security: starttls (default), tls or none
+    #[nserde(default)]
+    pub security: String,
 }
 
 #[derive(DeJson, SerJson)]
@@ -57,6 +286,22 @@ pub struct NLPConfigSection {
     /// Whether to show NLP interpretation transparency
     #[nserde(default)]
     pub show_transparency: bool,
+    /// Provider used when the one above fails or has no key, off without an api_base_url
+    #[nserde(default)]
+    pub fallback: NLPFallbackSection,
+}
+
+/// A second OpenAI compatible provider, such as Ollama at http://localhost:11434/v1.
+#[derive(Default, DeJson, SerJson)]
+pub struct NLPFallbackSection {
+    #[nserde(default)]
+    pub api_base_url: String,
+    /// Can stay empty for local servers, which take any key
+    #[nserde(default)]
+    pub api_key: String,
+    /// The model of the main provider when empty
+    #[nserde(default)]
+    pub model: String,
 }
 
 impl Default for NLPConfigSection {
@@ -74,24 +319,49 @@ impl Default for NLPConfigSection {
             preview_enabled: true,
             auto_confirm: false,
             show_transparency: true,
+            fallback: NLPFallbackSection::default(),
         }
     }
 }
 
-pub fn get_data_path() -> Result<PathBuf, String> {
-    let home_dir = home::home_dir().ok_or_else(|| String::from("cannot find home directory"))?;
-    let data_dir = match get_config_data_dir(home_dir.clone()) {
+fn home_dir() -> Result<PathBuf, TascliError> {
+    home::home_dir().ok_or_else(|| TascliError::Config(String::from("cannot find home directory")))
+}
+
+// Windows keeps config and data under %APPDATA%, unless an older version already made the
+// directories in the home folder. The data gets a folder of its own there, away from the key
+// of private items next to the config.
+#[cfg_attr(not(windows), allow(unused_variables))]
+fn default_dir(unix: &[&str], windows: &[&str]) -> Result<PathBuf, TascliError> {
+    let dir = unix.iter().fold(home_dir()?, |p, d| p.join(d));
+    #[cfg(windows)]
+    if !dir.exists()
+        && let Some(appdata) = dirs_next::config_dir()
+    {
+        return Ok(windows.iter().fold(appdata, |p, d| p.join(d)));
+    }
+    Ok(dir)
+}
+
+// The configured data directory, or the default one, created if missing
+fn data_dir() -> Result<PathBuf, TascliError> {
+    let data_dir = match get_config_data_dir() {
         Some(dir_path) => str_to_pathbuf(dir_path)?,
-        None => DEFAULT_DATA_DIR.iter().fold(home_dir, |p, d| p.join(d)),
+        None => default_dir(DEFAULT_DATA_DIR, &["tascli", "data"])?,
     };
-    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
-    Ok(data_dir.join(DB_NAME))
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| TascliError::io("Failed to create data directory", e))?;
+    Ok(data_dir)
+}
+
+pub fn get_data_path() -> Result<PathBuf, TascliError> {
+    Ok(data_dir()?.join(DB_NAME))
 }
 
 // Quick passthrough for reading config file
 // If config file do not exist, return quickly
-fn get_config_data_dir(home_dir: PathBuf) -> Option<String> {
-    let config_path = CONFIG_PATH.iter().fold(home_dir, |p, d| p.join(d));
+fn get_config_data_dir() -> Option<String> {
+    let config_path = get_config_path().ok()?;
     if !config_path.exists() {
         return None;
     }
@@ -110,25 +380,48 @@ fn get_config_data_dir(home_dir: PathBuf) -> Option<String> {
     }
 }
 
-fn str_to_pathbuf(dir_path: String) -> Result<PathBuf, String> {
-    if dir_path.starts_with("~") {
-        // We have already executed home_dir previously
-        let mut path_buf = home::home_dir().unwrap();
-        if dir_path.len() > 2 && dir_path.starts_with("~/") {
-            path_buf.push(&dir_path[2..]);
-        }
-        Ok(path_buf)
-    } else if dir_path.starts_with("/") {
-        Ok(PathBuf::from(dir_path))
+/// Expands a path from config that is home relative, such as "~/tascli" or "~\tascli", or
+/// absolute, such as "/data/tascli" or "C:\tascli". None for relative paths.
+pub fn expand_path(path: &str) -> Result<Option<PathBuf>, TascliError> {
+    let home_relative = path
+        .strip_prefix('~')
+        .filter(|rest| rest.is_empty() || rest.starts_with(['/', '\\']));
+    if let Some(rest) = home_relative {
+        // Either separator, config files get written on one system and read on another
+        let parts = rest.split(['/', '\\']).filter(|part| !part.is_empty());
+        return Ok(Some(parts.fold(home_dir()?, |p, part| p.join(part))));
+    }
+    let absolute = PathBuf::from(path);
+    if absolute.is_absolute() || path.starts_with(['/', '\\']) {
+        Ok(Some(absolute))
     } else {
-        Err(format!("data directory must be absolute or home relative, and start with '~' or '/', it cannot be {}", dir_path))
+        Ok(None)
     }
 }
 
+fn str_to_pathbuf(dir_path: String) -> Result<PathBuf, TascliError> {
+    expand_path(&dir_path)?.ok_or_else(|| {
+        TascliError::Config(format!(
+            "data directory must be absolute or home relative, such as '~/tascli', '/data/tascli' \
+             or 'C:\\tascli', it cannot be {}",
+            dir_path
+        ))
+    })
+}
+
+/// Path of the config file, whether or not it exists. `TASCLI_CONFIG_DIR` moves it, along
+/// with the key of private items, to another directory.
+pub fn get_config_path() -> Result<PathBuf, TascliError> {
+    let dir = match env::var_os(CONFIG_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => default_dir(DEFAULT_CONFIG_DIR, &["tascli"])?,
+    };
+    Ok(dir.join(CONFIG_NAME))
+}
+
 /// Get the full configuration from the config file
-pub fn get_config() -> Result<Config, String> {
-    let home_dir = home::home_dir().ok_or_else(|| String::from("cannot find home directory"))?;
-    let config_path = CONFIG_PATH.iter().fold(home_dir, |p, d| p.join(d));
+pub fn get_config() -> Result<Config, TascliError> {
+    let config_path = get_config_path()?;
 
     if !config_path.exists() {
         // Return default config if file doesn't exist
@@ -136,38 +429,191 @@ pub fn get_config() -> Result<Config, String> {
     }
 
     let config_content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config file: {}", e))?;
+        .map_err(|e| TascliError::io("Failed to read config file", e))?;
 
     let config: Config = DeJson::deserialize_json(&config_content)
-        .map_err(|e| format!("Failed to parse config file: {}", e))?;
+        .map_err(|e| TascliError::Config(format!("Failed to parse config file: {}", e)))?;
 
     Ok(config)
 }
 
+/// Uses `name` instead of the configured user for the rest of the run.
+pub fn set_user(name: &str) {
+    let _ = CURRENT_USER.set(Some(name.trim().to_string()).filter(|n| !n.is_empty()));
+}
+
+/// The person using a shared database, from --user or the config. None when items are shared.
+pub fn current_user() -> Option<&'static str> {
+    CURRENT_USER
+        .get_or_init(|| {
+            get_config()
+                .ok()
+                .map(|c| c.user.trim().to_string())
+                .filter(|n| !n.is_empty())
+        })
+        .as_deref()
+}
+
+/// The configured color and icon of `category`, if any.
+pub fn category_style(category: &str) -> Option<&'static CategoryConfig> {
+    CATEGORY_STYLES
+        .get_or_init(|| {
+            get_config().map(|c| c.categories).unwrap_or_default()
+        })
+        .iter()
+        .find(|style| style.name == category)
+}
+
+/// The record template called `name` in config, if any.
+pub fn record_template(name: &str) -> Option<&'static RecordTemplateConfig> {
+    RECORD_TEMPLATES
+        .get_or_init(|| {
+            get_config().map(|c| c.record_templates).unwrap_or_default()
+        })
+        .iter()
+        .find(|template| template.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// The completion window of `category`, if it has one.
+pub fn category_sla(category: &str) -> Option<&'static str> {
+    category_style(category)
+        .map(|style| style.sla.trim())
+        .filter(|sla| !sla.is_empty())
+}
+
+/// Categories with a completion window and their windows.
+pub fn category_slas() -> Vec<(String, String)> {
+    get_config()
+        .map(|c| c.categories)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|style| !style.sla.trim().is_empty())
+        .map(|style| (style.name, style.sla.trim().to_string()))
+        .collect()
+}
+
+/// Seconds before an ongoing task is marked as stale, None when the marker is turned off.
+pub fn stale_after() -> Option<i64> {
+    *STALE_AFTER.get_or_init(|| {
+        let setting = get_config().map(|c| c.stale_after).unwrap_or_default();
+        match setting.trim() {
+            "off" => None,
+            setting => Some(parse_duration(setting).unwrap_or(DEFAULT_STALE_AFTER)),
+        }
+    })
+}
+
+/// Tasks due on one day before the day counts as full.
+pub fn daily_capacity() -> usize {
+    let capacity = get_config().map(|c| c.daily_capacity).unwrap_or_default();
+    if capacity == 0 {
+        DEFAULT_DAILY_CAPACITY
+    } else {
+        capacity
+    }
+}
+
+/// Month the fiscal year starts in, 1 for January.
+pub fn fiscal_year_start() -> u32 {
+    let month = get_config().map(|c| c.fiscal_year_start).unwrap_or_default();
+    match month {
+        1..=12 => month as u32,
+        _ => 1,
+    }
+}
+
+/// The configured numbering of listings, the listing index unless set.
+pub fn display_id() -> DisplayId {
+    *DISPLAY_ID.get_or_init(|| {
+        let setting = get_config().map(|c| c.display_id).unwrap_or_default();
+        parse_display_id(&setting)
+    })
+}
+
+fn parse_display_id(setting: &str) -> DisplayId {
+    match setting.trim().to_lowercase().as_str() {
+        "category" => DisplayId::Category,
+        "global" => DisplayId::Global,
+        _ => DisplayId::Index,
+    }
+}
+
+/// The configured palette, the default one unless set.
+pub fn color_palette() -> ColorPalette {
+    *COLOR_PALETTE.get_or_init(|| {
+        let setting = get_config().map(|c| c.palette).unwrap_or_default();
+        parse_color_palette(&setting)
+    })
+}
+
+fn parse_color_palette(setting: &str) -> ColorPalette {
+    match setting.trim().to_lowercase().as_str() {
+        "deuteranopia" => ColorPalette::Deuteranopia,
+        "protanopia" => ColorPalette::Protanopia,
+        _ => ColorPalette::Default,
+    }
+}
+
+/// Whether listed rows start with an icon of their status.
+pub fn status_icons() -> bool {
+    *STATUS_ICONS.get_or_init(|| get_config().is_ok_and(|c| c.status_icons))
+}
+
+/// Categories of the named place in config, for `list task --at`.
+pub fn place_categories(name: &str) -> Result<Vec<String>, TascliError> {
+    find_place(&get_config()?.places, name)
+}
+
+fn find_place(places: &[PlaceConfig], name: &str) -> Result<Vec<String>, TascliError> {
+    if let Some(place) = places.iter().find(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Ok(place.categories.clone());
+    }
+    let known: Vec<&str> = places.iter().map(|p| p.name.as_str()).collect();
+    Err(TascliError::Config(if known.is_empty() {
+        format!("No place named {}, places are set in config", name)
+    } else {
+        format!("No place named {}, known places: {}", name, known.join(", "))
+    }))
+}
+
 /// Save configuration to the config file
-pub fn save_config(config: &Config) -> Result<(), String> {
-    let home_dir = home::home_dir().ok_or_else(|| String::from("cannot find home directory"))?;
-    let config_path = CONFIG_PATH.iter().fold(home_dir, |p, d| p.join(d));
+#[cfg(feature = "nlp")]
+pub fn save_config(config: &Config) -> Result<(), TascliError> {
+    let config_path = get_config_path()?;
 
     // Create config directory if it doesn't exist
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            .map_err(|e| TascliError::io("Failed to create config directory", e))?;
     }
 
     let config_json = config.serialize_json();
-    fs::write(&config_path, config_json)
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    write_atomic(&config_path, config_json.as_bytes())
+        .map_err(|e| TascliError::io("Failed to write config file", e))?;
 
     Ok(())
 }
 
+/// Writes to a temporary file next to `path` and renames it over `path`,
+/// so a crash leaves either the old or the new content, never a truncated file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
+
 /// Get just the NLP configuration
-pub fn get_nlp_config() -> Result<crate::nlp::NLPConfig, String> {
+#[cfg(feature = "nlp")]
+pub fn get_nlp_config() -> Result<crate::nlp::NLPConfig, TascliError> {
     let config = get_config()?;
     let nlp_section = config.nlp;
 
-    Ok(crate::nlp::NLPConfig {
+    let mut nlp_config = crate::nlp::NLPConfig {
         enabled: nlp_section.enabled,
         api_key: if nlp_section.api_key.is_empty() { None } else { Some(nlp_section.api_key) },
         model: nlp_section.model,
@@ -180,12 +626,41 @@ pub fn get_nlp_config() -> Result<crate::nlp::NLPConfig, String> {
         preview_enabled: nlp_section.preview_enabled,
         auto_confirm: nlp_section.auto_confirm,
         show_transparency: nlp_section.show_transparency,
-    })
+        fallback: None,
+    };
+    nlp_config.fallback = fallback_nlp_config(&nlp_config, nlp_section.fallback);
+    Ok(nlp_config)
+}
+
+// The main settings with the provider swapped for the fallback one
+#[cfg(feature = "nlp")]
+fn fallback_nlp_config(
+    main: &crate::nlp::NLPConfig,
+    section: NLPFallbackSection,
+) -> Option<Box<crate::nlp::NLPConfig>> {
+    if section.api_base_url.is_empty() {
+        return None;
+    }
+    let mut fallback = main.clone();
+    fallback.api_base_url = section.api_base_url;
+    // The client refuses to send a request without a key
+    fallback.api_key = Some(if section.api_key.is_empty() {
+        "local".to_string()
+    } else {
+        section.api_key
+    });
+    if !section.model.is_empty() {
+        fallback.model = section.model;
+    }
+    Some(Box::new(fallback))
 }
 
 /// Update NLP configuration
-pub fn update_nlp_config(nlp_config: &crate::nlp::NLPConfig) -> Result<(), String> {
+#[cfg(feature = "nlp")]
+pub fn update_nlp_config(nlp_config: &crate::nlp::NLPConfig) -> Result<(), TascliError> {
     let mut config = get_config()?;
+    // The fallback is only set in the config file
+    let fallback = std::mem::take(&mut config.nlp.fallback);
 
     config.nlp = NLPConfigSection {
         enabled: nlp_config.enabled,
@@ -200,37 +675,90 @@ pub fn update_nlp_config(nlp_config: &crate::nlp::NLPConfig) -> Result<(), Strin
         preview_enabled: nlp_config.preview_enabled,
         auto_confirm: nlp_config.auto_confirm,
         show_transparency: nlp_config.show_transparency,
+        fallback,
     };
 
     save_config(&config)
 }
 
 /// Get the learning database path
-pub fn get_learning_db_path() -> Result<std::path::PathBuf, String> {
-    let home_dir = home::home_dir().ok_or_else(|| String::from("cannot find home directory"))?;
-    let data_dir = match get_config_data_dir(home_dir.clone()) {
-        Some(dir_path) => str_to_pathbuf(dir_path)?,
-        None => DEFAULT_DATA_DIR.iter().fold(home_dir, |p, d| p.join(d)),
-    };
-    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
-    Ok(data_dir.join("nlp_learning.db"))
+#[cfg(feature = "nlp")]
+pub fn get_learning_db_path() -> Result<std::path::PathBuf, TascliError> {
+    Ok(data_dir()?.join("nlp_learning.db"))
 }
 
 /// Get the personalization database path
-pub fn get_personalization_db_path() -> Result<std::path::PathBuf, String> {
-    let home_dir = home::home_dir().ok_or_else(|| String::from("cannot find home directory"))?;
-    let data_dir = match get_config_data_dir(home_dir.clone()) {
-        Some(dir_path) => str_to_pathbuf(dir_path)?,
-        None => DEFAULT_DATA_DIR.iter().fold(home_dir, |p, d| p.join(d)),
-    };
-    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
-    Ok(data_dir.join("nlp_personalization.db"))
+#[cfg(feature = "nlp")]
+pub fn get_personalization_db_path() -> Result<std::path::PathBuf, TascliError> {
+    Ok(data_dir()?.join("nlp_personalization.db"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_find_place() {
+        let places = vec![PlaceConfig {
+            name: "office".to_string(),
+            categories: vec!["work".to_string(), "meetings".to_string()],
+        }];
+        assert_eq!(find_place(&places, "Office").unwrap(), vec!["work", "meetings"]);
+        let err = find_place(&places, "gym").unwrap_err().to_string();
+        assert!(err.contains("known places: office"), "{}", err);
+        assert!(find_place(&[], "gym").is_err());
+    }
+
+    #[test]
+    fn test_parse_display_id() {
+        assert_eq!(parse_display_id(""), DisplayId::Index);
+        assert_eq!(parse_display_id("Category"), DisplayId::Category);
+        assert_eq!(parse_display_id(" global "), DisplayId::Global);
+        assert_eq!(parse_display_id("uuid"), DisplayId::Index);
+    }
+
+    #[cfg(feature = "nlp")]
+    #[test]
+    fn test_fallback_nlp_config() {
+        let main = crate::nlp::NLPConfig {
+            api_key: Some("sk-test".to_string()),
+            timeout_seconds: 10,
+            ..Default::default()
+        };
+        assert!(fallback_nlp_config(&main, NLPFallbackSection::default()).is_none());
+
+        let section = NLPFallbackSection {
+            api_base_url: "http://localhost:11434/v1".to_string(),
+            model: "llama3.2".to_string(),
+            ..Default::default()
+        };
+        let fallback = fallback_nlp_config(&main, section).unwrap();
+        assert_eq!(fallback.api_base_url, "http://localhost:11434/v1");
+        assert_eq!(fallback.model, "llama3.2");
+        assert_eq!(fallback.api_key.as_deref(), Some("local"));
+        assert_eq!(fallback.timeout_seconds, 10);
+    }
+
+    #[test]
+    fn test_parse_color_palette() {
+        assert_eq!(parse_color_palette(""), ColorPalette::Default);
+        assert_eq!(parse_color_palette("Deuteranopia"), ColorPalette::Deuteranopia);
+        assert_eq!(parse_color_palette("protanopia "), ColorPalette::Protanopia);
+        assert_eq!(parse_color_palette("rainbow"), ColorPalette::Default);
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!dir.path().join("config.json.tmp").exists());
+    }
+
     #[test]
     fn test_str_to_pathbuf_with_tilde() {
         // Test with just "~"
@@ -250,6 +778,21 @@ mod tests {
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
+            .to_string()
             .contains("must be absolute or home relative"));
     }
+
+    #[test]
+    fn test_expand_path() {
+        let home = home::home_dir().unwrap();
+        let expand = |path: &str| expand_path(path).unwrap();
+        assert_eq!(expand("~\\some\\path"), Some(home.join("some").join("path")));
+        assert_eq!(expand("~/some\\path/"), Some(home.join("some").join("path")));
+        assert_eq!(expand("/data/tascli"), Some(PathBuf::from("/data/tascli")));
+        assert_eq!(expand("\\\\server\\share"), Some(PathBuf::from("\\\\server\\share")));
+        assert_eq!(expand("tascli\\data"), None);
+        assert_eq!(expand("~tascli"), None);
+        #[cfg(windows)]
+        assert_eq!(expand("C:\\tascli"), Some(PathBuf::from("C:\\tascli")));
+    }
 }
@@ -0,0 +1,105 @@
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    Result,
+    Row,
+};
+
+/// A natural language command run on a schedule by `tascli notify reports`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Automation {
+    pub name: String,
+    // Recurring schedule as in recurring tasks, such as "weekly Monday-Friday 9:00"
+    pub schedule: String,
+    // The command as the user wrote it
+    pub input: String,
+    // The parsed command, as versioned json
+    pub command: String,
+    // Notifies when the condition holds instead of running the command
+    pub notify: bool,
+}
+
+fn automation_from_row(row: &Row) -> Result<Automation> {
+    Ok(Automation {
+        name: row.get(0)?,
+        schedule: row.get(1)?,
+        input: row.get(2)?,
+        command: row.get(3)?,
+        notify: row.get(4)?,
+    })
+}
+
+pub fn save_automation(conn: &Connection, automation: &Automation) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO automations (name, schedule, input, command, notify)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(name) DO UPDATE SET schedule = excluded.schedule,
+         input = excluded.input, command = excluded.command, notify = excluded.notify",
+    )?
+    .execute(params![
+        automation.name,
+        automation.schedule,
+        automation.input,
+        automation.command,
+        automation.notify
+    ])?;
+    Ok(())
+}
+
+pub fn get_automation(conn: &Connection, name: &str) -> Result<Option<Automation>> {
+    conn.prepare_cached(
+        "SELECT name, schedule, input, command, notify FROM automations WHERE name = ?1",
+    )?
+    .query_row(params![name], automation_from_row)
+    .optional()
+}
+
+pub fn get_automations(conn: &Connection) -> Result<Vec<Automation>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT name, schedule, input, command, notify FROM automations ORDER BY name",
+    )?;
+    stmt.query_map([], automation_from_row)?.collect()
+}
+
+// False when there was no such automation
+pub fn delete_automation(conn: &Connection, name: &str) -> Result<bool> {
+    let deleted = conn
+        .prepare_cached("DELETE FROM automations WHERE name = ?1")?
+        .execute(params![name])?;
+    Ok(deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_automations() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut inbox = Automation {
+            name: "inbox".to_string(),
+            schedule: "weekly Monday-Friday 9:00".to_string(),
+            input: "if inbox has tasks then notify me".to_string(),
+            command: r#"{"action":"list"}"#.to_string(),
+            notify: true,
+        };
+        save_automation(&conn, &inbox).unwrap();
+        let standup = Automation {
+            name: "standup".to_string(),
+            notify: false,
+            ..inbox.clone()
+        };
+        save_automation(&conn, &standup).unwrap();
+        assert_eq!(get_automation(&conn, "inbox").unwrap(), Some(inbox.clone()));
+
+        inbox.schedule = "daily 8:00".to_string();
+        save_automation(&conn, &inbox).unwrap();
+        assert_eq!(get_automations(&conn).unwrap(), vec![inbox, standup]);
+
+        assert!(delete_automation(&conn, "inbox").unwrap());
+        assert!(!delete_automation(&conn, "inbox").unwrap());
+        assert_eq!(get_automation(&conn, "inbox").unwrap(), None);
+    }
+}
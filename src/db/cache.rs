@@ -12,6 +12,12 @@ use rusqlite::{
 use crate::db::item::Item;
 
 pub fn store(conn: &Connection, items: &[Item]) -> Result<()> {
+    let ids: Vec<i64> = items.iter().map(|item| item.id.unwrap()).collect();
+    store_ids(conn, &ids)
+}
+
+// For listings that were streamed, where only the ids were kept.
+pub fn store_ids(conn: &Connection, ids: &[i64]) -> Result<()> {
     let current_time = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("Time went backwards")
@@ -21,22 +27,34 @@ pub fn store(conn: &Connection, items: &[Item]) -> Result<()> {
     // For invalidations after some time.
     let mut kv: Vec<(i64, i64)> = vec![(0, current_time)];
 
-    let items_kv: Vec<(i64, i64)> = items
+    let items_kv: Vec<(i64, i64)> = ids
         .iter()
         .enumerate()
-        .map(|(index, item)| ((index + 1) as i64, item.id.unwrap()))
+        .map(|(index, id)| ((index + 1) as i64, *id))
         .collect();
 
     kv.extend(items_kv);
     store_kv(conn, kv)
 }
 
-// add a next token marker
+// add a next token marker, listings use store_ids_with_next
+#[cfg(test)]
 pub fn store_with_next(conn: &Connection, items: &[Item]) -> Result<()> {
     store(conn, items)?;
+    mark_next(conn, items.len())
+}
+
+// The next page goes on from the item at `next_index`, the last one unless the listing was
+// shown in another order than read
+pub fn store_ids_with_next(conn: &Connection, ids: &[i64], next_index: usize) -> Result<()> {
+    store_ids(conn, ids)?;
+    mark_next(conn, next_index)
+}
+
+fn mark_next(conn: &Connection, last_index: usize) -> Result<()> {
     conn.execute(
         "INSERT OR REPLACE INTO cache (key, value) VALUES (?1, ?2)",
-        [-1, items.len() as i64],
+        [-1, last_index as i64],
     )?;
     Ok(())
 }
@@ -72,11 +90,8 @@ fn store_kv(conn: &Connection, kv: Vec<(i64, i64)>) -> Result<()> {
 }
 
 pub fn read(conn: &Connection, index: i64) -> Result<Option<i64>> {
-    let result = conn.query_row(
-        "SELECT value FROM cache WHERE key = ?1",
-        params![index],
-        |row| row.get(0),
-    );
+    let mut stmt = conn.prepare_cached("SELECT value FROM cache WHERE key = ?1")?;
+    let result = stmt.query_row(params![index], |row| row.get(0));
 
     match result {
         Ok(value) => Ok(Some(value)),
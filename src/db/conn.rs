@@ -1,10 +1,29 @@
+use std::{
+    thread,
+    time::Duration,
+};
+
 use rusqlite::Connection;
+use uuid::Uuid;
 
-use crate::config::get_data_path;
+use crate::{
+    config::get_data_path,
+    error::TascliError,
+};
 
 // Going forward, all schema changes require toggling
 // this DB_VERSION to a higher number.
-const SCHEMA_VERSION: i32 = 2;
+const SCHEMA_VERSION: i32 = 17;
+
+// Prepared statements kept per connection, enough for the fixed crud
+// statements plus the query shapes of a listing or report.
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+// Another process writing holds the db for milliseconds, a wait doubling from 5ms up to
+// 250ms gives up after about 10 seconds
+const BUSY_FIRST_WAIT_MS: u64 = 5;
+const BUSY_MAX_WAIT_MS: u64 = 250;
+const BUSY_RETRIES: i32 = 45;
 
 pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
     let current_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
@@ -13,6 +32,11 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
         return Ok(());
     }
 
+    // A migration interrupted halfway would otherwise be rerun against a partial schema
+    atomically(conn, || migrate(conn, current_version))
+}
+
+fn migrate(conn: &Connection, current_version: i32) -> Result<(), rusqlite::Error> {
     // Single polymorphic table
     // Supports task, record, recurring_task, recurring_task_record
     // distinguished via field "action"
@@ -23,6 +47,7 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS items (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
+            uuid TEXT,
             action TEXT NOT NULL,
             category TEXT NOT NULL,
             content TEXT NOT NULL,
@@ -33,7 +58,15 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
             cron_schedule TEXT,
             human_schedule TEXT,
             recurring_task_id INTEGER,
-            good_until INTEGER
+            good_until INTEGER,
+            owner TEXT,
+            value REAL,
+            unit TEXT,
+            all_day INTEGER NOT NULL DEFAULT 0,
+            seq INTEGER,
+            fields TEXT,
+            priority INTEGER NOT NULL DEFAULT 0,
+            parent_id INTEGER
         )",
         [],
     )?;
@@ -83,25 +116,264 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
         [],
     )?;
 
+    // Status transitions of items, one row per status an item entered
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS status_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL,
+            status INTEGER NOT NULL,
+            change_time INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_status_history_item_id ON status_history(item_id)",
+        [],
+    )?;
+
+    // Migrate from version 2 to 3 - seed the history from what items already know,
+    // the creation and, for items no longer ongoing, the last modification.
+    if current_version < 3 && current_version > 0 {
+        conn.execute(
+            "INSERT INTO status_history (item_id, status, change_time)
+             SELECT id, 0, create_time FROM items",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO status_history (item_id, status, change_time)
+             SELECT id, status, COALESCE(modify_time, create_time) FROM items WHERE status != 0",
+            [],
+        )?;
+    }
+
+    // Migrate from version 3 to 4 - give every item a uuid
+    if current_version < 4 && current_version > 0 {
+        if !has_column(conn, "items", "uuid")? {
+            conn.execute("ALTER TABLE items ADD COLUMN uuid TEXT", [])?;
+        }
+        let ids = conn
+            .prepare("SELECT id FROM items WHERE uuid IS NULL")?
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<i64>, rusqlite::Error>>()?;
+        for id in ids {
+            conn.execute(
+                "UPDATE items SET uuid = ?1 WHERE id = ?2",
+                rusqlite::params![Uuid::new_v4().to_string(), id],
+            )?;
+        }
+    }
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_uuid ON items(uuid)",
+        [],
+    )?;
+
+    // Migrate from version 4 to 5 - items of people sharing the database, null for shared items
+    if current_version < 5 && current_version > 0 && !has_column(conn, "items", "owner")? {
+        conn.execute("ALTER TABLE items ADD COLUMN owner TEXT", [])?;
+    }
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_owner ON items(owner)", [])?;
+
+    // Reminders of tasks, one row per lead time, sent_time is set once the reminder went out
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL,
+            lead_seconds INTEGER NOT NULL,
+            sent_time INTEGER,
+            UNIQUE (item_id, lead_seconds)
+        )",
+        [],
+    )?;
+
+    // End dates of categories run as projects, deadlines of their tasks are checked against it
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS projects (
+            category TEXT PRIMARY KEY,
+            end_time INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Migrate from version 8 to 9 - numeric values of records such as "weight 82.5 kg"
+    if current_version < 9 && current_version > 0 && !has_column(conn, "items", "value")? {
+        conn.execute("ALTER TABLE items ADD COLUMN value REAL", [])?;
+        conn.execute("ALTER TABLE items ADD COLUMN unit TEXT", [])?;
+    }
+
+    // Migrate from version 9 to 10 - deadlines given as a date only, which were
+    // always stored as the last second of their day
+    if current_version < 10 && current_version > 0 && !has_column(conn, "items", "all_day")? {
+        conn.execute(
+            "ALTER TABLE items ADD COLUMN all_day INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+        conn.execute(
+            "UPDATE items SET all_day = 1 WHERE target_time IS NOT NULL
+             AND strftime('%H:%M:%S', target_time, 'unixepoch', 'localtime') = '23:59:59'",
+            [],
+        )?;
+    }
+
+    // Migrate to version 12 - number the items of each category in the order they were added
+    if current_version < 12 && current_version > 0 && !has_column(conn, "items", "seq")? {
+        conn.execute("ALTER TABLE items ADD COLUMN seq INTEGER", [])?;
+        conn.execute(
+            "UPDATE items SET seq = (SELECT COUNT(*) FROM items AS earlier
+             WHERE earlier.category = items.category AND earlier.id <= items.id)",
+            [],
+        )?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_category_seq ON items(category, seq)",
+        [],
+    )?;
+
+    // Migrate to version 13 - the answers to the fields of record templates
+    if current_version < 13 && current_version > 0 && !has_column(conn, "items", "fields")? {
+        conn.execute("ALTER TABLE items ADD COLUMN fields TEXT", [])?;
+    }
+
+    // Migrate to version 15 - priorities of tasks, 0 for none
+    if current_version < 15 && current_version > 0 && !has_column(conn, "items", "priority")? {
+        conn.execute(
+            "ALTER TABLE items ADD COLUMN priority INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+
+    // Migrate to version 17 - the task a subtask belongs to
+    if current_version < 17 && current_version > 0 && !has_column(conn, "items", "parent_id")? {
+        conn.execute("ALTER TABLE items ADD COLUMN parent_id INTEGER", [])?;
+    }
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_parent_id ON items(parent_id)", [])?;
+
+    // Saved command lines, args is a json array of the arguments after `tascli`
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS views (
+            name TEXT PRIMARY KEY,
+            args TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Values kept between runs, such as the day of the last run
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS state (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Free-form tags of items, in lowercase
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_tags (
+            item_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (item_id, tag)
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_item_tags_tag ON item_tags(tag)", [])?;
+
+    // Natural language commands run on a schedule, command is the parsed command as json
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS automations (
+            name TEXT PRIMARY KEY,
+            schedule TEXT NOT NULL,
+            input TEXT NOT NULL,
+            command TEXT NOT NULL,
+            notify INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
     conn.execute(&format!("PRAGMA user_version = {SCHEMA_VERSION}"), [])?;
 
     Ok(())
 }
 
-pub fn connect() -> Result<Connection, String> {
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>("name"))?
+        .collect::<Result<Vec<String>, rusqlite::Error>>()?;
+    Ok(names.iter().any(|name| name == column))
+}
+
+/// Runs `f` inside a savepoint so its statements are applied together or not at all.
+/// Savepoints nest, so callers may already be inside a transaction.
+pub fn atomically<T, E>(conn: &Connection, f: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+where
+    E: From<rusqlite::Error>,
+{
+    // The outermost takes the write lock up front, a deferred transaction reading first
+    // could not wait for another writer and would fail with SQLITE_BUSY right away
+    let outermost = conn.is_autocommit();
+    conn.execute_batch(if outermost { "BEGIN IMMEDIATE" } else { "SAVEPOINT tascli" })?;
+    match f() {
+        Ok(value) => {
+            conn.execute_batch(if outermost { "COMMIT" } else { "RELEASE tascli" })?;
+            Ok(value)
+        }
+        Err(e) => {
+            conn.execute_batch(if outermost {
+                "ROLLBACK"
+            } else {
+                "ROLLBACK TO tascli; RELEASE tascli"
+            })?;
+            Err(e)
+        }
+    }
+}
+
+// Called by sqlite while another connection holds the lock it needs, the daemon, the repl
+// or a command run meanwhile. Returning false fails the statement with SQLITE_BUSY.
+fn retry_busy(attempt: i32) -> bool {
+    if attempt >= BUSY_RETRIES {
+        return false;
+    }
+    thread::sleep(Duration::from_millis(busy_wait_ms(attempt)));
+    true
+}
+
+fn busy_wait_ms(attempt: i32) -> u64 {
+    BUSY_FIRST_WAIT_MS.saturating_mul(1 << attempt.clamp(0, 16)).min(BUSY_MAX_WAIT_MS)
+}
+
+pub fn connect() -> Result<Connection, TascliError> {
     let db_path = get_data_path()?;
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
-    init_table(&conn).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path)?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    conn.busy_handler(Some(retry_busy))?;
+    init_table(&conn)?;
 
     Ok(conn)
 }
 
+/// An empty db that lives as long as the connection, for a sandbox such as the tutorial's.
+pub fn connect_in_memory() -> Result<Connection, TascliError> {
+    let conn = Connection::open_in_memory()?;
+    init_table(&conn)?;
+    Ok(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use rusqlite::Row;
 
     use super::*;
-    use crate::tests::get_test_conn;
+    use crate::{
+        db::{
+            crud::get_item,
+            history::get_status_history,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+            update_status,
+        },
+    };
 
     #[test]
     fn test_init_table() {
@@ -178,4 +450,183 @@ mod tests {
             .unwrap();
         assert_eq!(SCHEMA_VERSION, final_version);
     }
+
+    #[test]
+    fn test_migrate_status_history() {
+        let (conn, _temp_file) = get_test_conn();
+        let open_id = insert_task(&conn, "work", "open task", "today");
+        let done_id = insert_task(&conn, "work", "done task", "today");
+        update_status(&conn, done_id, 1);
+
+        // Pretend the items were written before the history table existed
+        conn.execute("DELETE FROM status_history", []).unwrap();
+        conn.execute("PRAGMA user_version = 2", []).unwrap();
+        init_table(&conn).unwrap();
+
+        let history = get_status_history(&conn, &[open_id, done_id]).unwrap();
+        let statuses: Vec<(i64, u8)> = history.iter().map(|c| (c.item_id, c.status)).collect();
+        assert_eq!(statuses, vec![(open_id, 0), (done_id, 0), (done_id, 1)]);
+    }
+
+    #[test]
+    fn test_migrate_uuid() {
+        let (conn, _temp_file) = get_test_conn();
+        let first = insert_task(&conn, "work", "first", "today");
+        let second = insert_task(&conn, "work", "second", "today");
+
+        // Pretend the items were written before they had uuids
+        conn.execute_batch("DROP INDEX idx_uuid; ALTER TABLE items DROP COLUMN uuid; PRAGMA user_version = 3")
+            .unwrap();
+        init_table(&conn).unwrap();
+
+        let uuid = |id: i64| get_item(&conn, id).unwrap().uuid;
+        assert_eq!(uuid(first).len(), 36);
+        assert_ne!(uuid(first), uuid(second));
+    }
+
+    #[test]
+    fn test_migrate_owner() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "home", "groceries", "today");
+
+        conn.execute_batch("DROP INDEX idx_owner; ALTER TABLE items DROP COLUMN owner; PRAGMA user_version = 4")
+            .unwrap();
+        init_table(&conn).unwrap();
+
+        assert!(has_column(&conn, "items", "owner").unwrap());
+        assert_eq!(get_item(&conn, id).unwrap().owner, None);
+    }
+
+    #[test]
+    fn test_migrate_value() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "home", "groceries", "today");
+
+        conn.execute_batch(
+            "ALTER TABLE items DROP COLUMN value; ALTER TABLE items DROP COLUMN unit;
+             PRAGMA user_version = 8",
+        )
+        .unwrap();
+        init_table(&conn).unwrap();
+
+        assert!(has_column(&conn, "items", "unit").unwrap());
+        assert_eq!(get_item(&conn, id).unwrap().value, None);
+    }
+
+    #[test]
+    fn test_migrate_all_day() {
+        let (conn, _temp_file) = get_test_conn();
+        let all_day = insert_task(&conn, "work", "report", "tomorrow");
+        let timed = insert_task(&conn, "work", "call", "tomorrow 3pm");
+
+        conn.execute_batch("ALTER TABLE items DROP COLUMN all_day; PRAGMA user_version = 9")
+            .unwrap();
+        init_table(&conn).unwrap();
+
+        assert!(get_item(&conn, all_day).unwrap().all_day);
+        assert!(!get_item(&conn, timed).unwrap().all_day);
+    }
+
+    #[test]
+    fn test_migrate_seq() {
+        let (conn, _temp_file) = get_test_conn();
+        let first = insert_task(&conn, "work", "report", "tomorrow");
+        let home = insert_task(&conn, "home", "laundry", "tomorrow");
+        let second = insert_task(&conn, "work", "call", "tomorrow");
+
+        conn.execute_batch(
+            "DROP INDEX idx_category_seq;
+             ALTER TABLE items DROP COLUMN seq;
+             PRAGMA user_version = 11",
+        )
+        .unwrap();
+        init_table(&conn).unwrap();
+
+        assert_eq!(get_item(&conn, first).unwrap().seq, Some(1));
+        assert_eq!(get_item(&conn, home).unwrap().seq, Some(1));
+        assert_eq!(get_item(&conn, second).unwrap().seq, Some(2));
+    }
+
+    #[test]
+    fn test_migrate_priority() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "report", "tomorrow");
+
+        conn.execute_batch("ALTER TABLE items DROP COLUMN priority; PRAGMA user_version = 14")
+            .unwrap();
+        init_table(&conn).unwrap();
+
+        assert!(has_column(&conn, "items", "priority").unwrap());
+        assert_eq!(get_item(&conn, id).unwrap().priority, 0);
+    }
+
+    #[test]
+    fn test_migrate_parent() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "report", "tomorrow");
+
+        conn.execute_batch(
+            "DROP INDEX idx_parent_id; ALTER TABLE items DROP COLUMN parent_id;
+             PRAGMA user_version = 16",
+        )
+        .unwrap();
+        init_table(&conn).unwrap();
+
+        assert!(has_column(&conn, "items", "parent_id").unwrap());
+        assert_eq!(get_item(&conn, id).unwrap().parent_id, None);
+    }
+
+    #[test]
+    fn test_atomically_rolls_back_on_error() {
+        let (conn, _temp_file) = get_test_conn();
+        let kept_id = insert_task(&conn, "work", "kept", "today");
+
+        let result: Result<(), rusqlite::Error> = atomically(&conn, || {
+            insert_task(&conn, "work", "rolled back", "today");
+            atomically(&conn, || -> Result<(), rusqlite::Error> {
+                conn.execute("DELETE FROM items WHERE id = ?1", [kept_id])?;
+                Ok(())
+            })?;
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        });
+        assert!(result.is_err());
+
+        let contents: Vec<String> = conn
+            .prepare("SELECT content FROM items")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(contents, vec!["kept"]);
+    }
+
+    #[test]
+    fn test_busy_wait_backs_off() {
+        assert_eq!(busy_wait_ms(0), 5);
+        assert_eq!(busy_wait_ms(3), 40);
+        assert_eq!(busy_wait_ms(10), BUSY_MAX_WAIT_MS);
+        assert!(!retry_busy(BUSY_RETRIES));
+    }
+
+    #[test]
+    fn test_atomically_waits_for_other_writer() {
+        let (conn, temp_file) = get_test_conn();
+        conn.busy_handler(Some(retry_busy)).unwrap();
+        let other = Connection::open(temp_file.path()).unwrap();
+        other.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            other.execute_batch("COMMIT").unwrap();
+        });
+        // Held by the other connection for the first 100ms
+        atomically(&conn, || -> Result<(), rusqlite::Error> {
+            insert_task(&conn, "work", "after the other writer", "today");
+            Ok(())
+        })
+        .unwrap();
+        writer.join().unwrap();
+        assert_eq!(get_item(&conn, 1).unwrap().content, "after the other writer");
+    }
 }
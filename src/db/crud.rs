@@ -1,28 +1,43 @@
-use std::time::{
-    SystemTime,
-    UNIX_EPOCH,
-};
-
 use rusqlite::{
     params,
     params_from_iter,
     Connection,
+    OptionalExtension,
     Result,
 };
-
-use crate::db::item::{
-    Item,
-    ItemQuery,
-    Offset,
+use tracing::debug;
+
+use crate::{
+    clock,
+    db::{
+        conn::atomically,
+        history,
+        item::{
+            Item,
+            ItemQuery,
+            Offset,
+            DEFAULT_CATEGORY,
+        },
+        reminders,
+        tags::{
+            self,
+            TAGS_COLUMN,
+        },
+    },
 };
 
 const VALID_ORDER_COLUMNS: &[&str] = &["id", "create_time", "target_time"];
 
 pub fn insert_item(conn: &Connection, item: &Item) -> Result<i64> {
-    conn.execute(
-        "INSERT INTO items (action, category, content, create_time, target_time, cron_schedule, human_schedule, recurring_task_id, good_until)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-        params![
+    // The item and its first history entry are written together
+    let id = atomically(conn, || -> Result<i64> {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO items (uuid, action, category, content, create_time, target_time, cron_schedule, human_schedule, recurring_task_id, good_until, owner, value, unit, all_day, fields, priority, parent_id, seq)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                (SELECT COALESCE(MAX(seq), 0) + 1 FROM items WHERE category = ?3))",
+        )?;
+        stmt.execute(params![
+            item.uuid,
             item.action,
             item.category,
             item.content,
@@ -31,32 +46,57 @@ pub fn insert_item(conn: &Connection, item: &Item) -> Result<i64> {
             item.cron_schedule,
             item.human_schedule,
             item.recurring_task_id,
-            item.good_until
-        ],
-    )?;
-
-    Ok(conn.last_insert_rowid())
+            item.good_until,
+            item.owner,
+            item.value,
+            item.unit,
+            item.all_day,
+            item.fields,
+            item.priority,
+            item.parent_id
+        ])?;
+
+        let id = conn.last_insert_rowid();
+        history::record_created(conn, id, item.create_time)?;
+        if !item.tags.is_empty() {
+            tags::set_tags(conn, id, &item.tags)?;
+        }
+        Ok(id)
+    })?;
+    debug!(id, action = item.action, "inserted item");
+    Ok(id)
 }
 
+// Tags are left as they are, tags::set_tags changes them
 pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
-
-    conn.execute(
-        "UPDATE items SET
-            category = ?1,
-            content = ?2,
-            target_time = ?3,
-            modify_time = ?4,
-            status = ?5,
-            cron_schedule = ?6,
-            human_schedule = ?7,
-            recurring_task_id = ?8,
-            good_until = ?9
-        WHERE id = ?10",
-        params![
+    let now = clock::timestamp();
+
+    atomically(conn, || -> Result<()> {
+        if let Some(id) = item.id {
+            history::record_status_change(conn, id, item.status, now)?;
+        }
+        let mut stmt = conn.prepare_cached(
+            "UPDATE items SET
+                category = ?1,
+                content = ?2,
+                target_time = ?3,
+                modify_time = ?4,
+                status = ?5,
+                cron_schedule = ?6,
+                human_schedule = ?7,
+                recurring_task_id = ?8,
+                good_until = ?9,
+                value = ?10,
+                unit = ?11,
+                action = ?12,
+                all_day = ?13,
+                priority = ?14,
+                create_time = ?15,
+                seq = CASE WHEN category = ?1 THEN seq
+                    ELSE (SELECT COALESCE(MAX(seq), 0) + 1 FROM items WHERE category = ?1) END
+            WHERE id = ?16",
+        )?;
+        stmt.execute(params![
             item.category,
             item.content,
             item.target_time,
@@ -66,25 +106,86 @@ pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
             item.human_schedule,
             item.recurring_task_id,
             item.good_until,
+            item.value,
+            item.unit,
+            item.action,
+            item.all_day,
+            item.priority,
+            item.create_time,
             item.id
-        ],
-    )?;
+        ])?;
+        Ok(())
+    })?;
+    debug!(id = item.id, status = item.status, "updated item");
 
     Ok(())
 }
 
 pub fn get_item(conn: &Connection, item_id: i64) -> Result<Item> {
-    let item = conn.query_row(
-        "SELECT * FROM items WHERE id = ?1",
-        params![item_id],
-        Item::from_row,
-    )?;
+    let mut stmt =
+        conn.prepare_cached(&format!("SELECT *, {} FROM items WHERE id = ?1", TAGS_COLUMN))?;
+    let item = stmt.query_row(params![item_id], Item::from_row)?;
 
     Ok(item)
 }
 
+/// Ids of up to `limit` items whose uuid starts with `prefix`.
+pub fn find_by_uuid_prefix(conn: &Connection, prefix: &str, limit: usize) -> Result<Vec<i64>> {
+    // GLOB is case sensitive and uses the uuid index, prefixes only contain hex digits and '-'
+    let mut stmt = conn.prepare_cached("SELECT id FROM items WHERE uuid GLOB ?1 || '*' LIMIT ?2")?;
+    let ids = stmt
+        .query_map(params![prefix, limit as i64], |row| row.get(0))?
+        .collect::<Result<Vec<i64>>>()?;
+    Ok(ids)
+}
+
+/// Id of the item numbered `seq` in `category`.
+pub fn find_by_category_seq(conn: &Connection, category: &str, seq: i64) -> Result<Option<i64>> {
+    conn.prepare_cached("SELECT id FROM items WHERE category = ?1 AND seq = ?2")?
+        .query_row(params![category, seq], |row| row.get(0))
+        .optional()
+}
+
+/// The categories in use, in alphabetical order.
+pub fn query_categories(conn: &Connection) -> Result<Vec<String>> {
+    conn.prepare_cached(
+        "SELECT DISTINCT category FROM items WHERE category != '' ORDER BY category",
+    )?
+    .query_map([], |row| row.get(0))?
+    .collect()
+}
+
+/// Categories with their number of items, the most used first.
+pub fn category_counts(conn: &Connection) -> Result<Vec<(String, i64)>> {
+    conn.prepare_cached(
+        "SELECT category, COUNT(*) AS count FROM items WHERE category != ''
+         GROUP BY category ORDER BY count DESC, category",
+    )?
+    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+    .collect()
+}
+
+/// Whether any item is a subtask, listings are only shown as trees then.
+pub fn has_subtasks(conn: &Connection) -> Result<bool> {
+    let found = conn
+        .prepare_cached("SELECT 1 FROM items WHERE parent_id IS NOT NULL LIMIT 1")?
+        .query_row([], |_| Ok(()))
+        .optional()?;
+    Ok(found.is_some())
+}
+
 pub fn delete_item(conn: &Connection, item_id: i64) -> Result<()> {
-    conn.execute("DELETE FROM items WHERE id = ?1", params![item_id])?;
+    atomically(conn, || {
+        conn.prepare_cached("DELETE FROM items WHERE id = ?1")?
+            .execute(params![item_id])?;
+        history::delete_history(conn, item_id)?;
+        tags::delete_tags(conn, item_id)?;
+        // Subtasks of a deleted task are left as tasks of their own
+        conn.prepare_cached("UPDATE items SET parent_id = NULL WHERE parent_id = ?1")?
+            .execute(params![item_id])?;
+        reminders::delete_reminders(conn, item_id)
+    })?;
+    debug!(id = item_id, "deleted item");
 
     Ok(())
 }
@@ -93,6 +194,34 @@ pub fn query_items(
     conn: &Connection,
     item_query: &ItemQuery,
 ) -> Result<Vec<Item>, rusqlite::Error> {
+    let mut items = Vec::new();
+    for_each_item(conn, item_query, |item| {
+        items.push(item);
+        Ok(())
+    })?;
+
+    Ok(items)
+}
+
+// Hands rows to f as they are read, so large listings are never held in memory.
+pub fn for_each_item<F>(conn: &Connection, item_query: &ItemQuery, mut f: F) -> Result<()>
+where
+    F: FnMut(Item) -> Result<()>,
+{
+    let (querystr, params) = build_query(item_query)?;
+    debug!(sql = %querystr, ?params, "query items");
+
+    // Listing pages and per recurring task lookups repeat the same query shapes
+    let mut stmt = conn.prepare_cached(&querystr)?;
+    let mut rows = stmt.query(params_from_iter(params))?;
+    while let Some(row) = rows.next()? {
+        f(Item::from_row(row)?)?;
+    }
+
+    Ok(())
+}
+
+fn build_query(item_query: &ItemQuery) -> Result<(String, Vec<String>)> {
     let mut conditions: Vec<String> = Vec::new();
     let mut params: Vec<String> = Vec::new();
 
@@ -115,6 +244,17 @@ pub fn query_items(
         params.push(c.to_string());
     }
 
+    if let Some(categories) = &item_query.categories {
+        let placeholders = vec!["?"; categories.len()].join(", ");
+        conditions.push(format!("category IN ({})", placeholders));
+        params.extend(categories.iter().cloned());
+    }
+
+    if item_query.uncategorized {
+        conditions.push("category IN (?, '')".to_string());
+        params.push(DEFAULT_CATEGORY.to_string());
+    }
+
     if let Some(content) = item_query.content_like {
         conditions.push("content LIKE ?".to_string());
         params.push(format!("%{}%", content));
@@ -160,11 +300,42 @@ pub fn query_items(
         params.push(gu_max.to_string());
     }
 
+    if let Some(v_min) = item_query.value_min {
+        conditions.push("value >= ?".to_string());
+        params.push(v_min.to_string());
+    }
+
+    if let Some(v_max) = item_query.value_max {
+        conditions.push("value <= ?".to_string());
+        params.push(v_max.to_string());
+    }
+
+    if let Some(priority) = item_query.priority {
+        conditions.push("priority = ?".to_string());
+        params.push(priority.to_string());
+    }
+
+    if let Some(tag) = item_query.tag {
+        conditions.push("id IN (SELECT item_id FROM item_tags WHERE tag = ?)".to_string());
+        params.push(tag.to_lowercase());
+    }
+
+    if let Some(parent_id) = item_query.parent_id {
+        conditions.push("parent_id = ?".to_string());
+        params.push(parent_id.to_string());
+    }
+
     if let Some(rt_id) = item_query.recurring_task_id {
         conditions.push("recurring_task_id = ?".to_string());
         params.push(rt_id.to_string());
     }
 
+    // Shared items have no owner and are visible to everyone
+    if let Some(user) = item_query.visible_to {
+        conditions.push("(owner = ? OR owner IS NULL)".to_string());
+        params.push(user.to_string());
+    }
+
     if let Some(cc) = &item_query.statuses {
         let status_list = cc
             .iter()
@@ -179,7 +350,7 @@ pub fn query_items(
         params.push(rowid.to_string());
     }
 
-    let mut querystr = String::from("SELECT * FROM items");
+    let mut querystr = format!("SELECT *, {} FROM items", TAGS_COLUMN);
     if !conditions.is_empty() {
         querystr.push_str(" WHERE ");
         querystr.push_str(&conditions.join(" AND "));
@@ -198,22 +369,26 @@ pub fn query_items(
         )));
     }
     querystr.push_str(&format!(" ORDER BY {} ASC", order_column));
+    // Tasks due at the same time, such as all day ones, the most pressing first
+    if order_column == "target_time" {
+        querystr.push_str(", priority DESC");
+    }
 
     if let Some(limit) = item_query.limit {
         querystr.push_str(" LIMIT ?");
         params.push(limit.to_string());
     }
 
-    let mut stmt = conn.prepare(&querystr)?;
-
-    let item_iter = stmt.query_map(params_from_iter(params), Item::from_row)?;
-
-    let mut items = Vec::new();
-    for item_result in item_iter {
-        items.push(item_result?);
+    if let Some(skip) = item_query.skip {
+        // OFFSET is only valid after a LIMIT, -1 means no limit
+        if item_query.limit.is_none() {
+            querystr.push_str(" LIMIT -1");
+        }
+        querystr.push_str(" OFFSET ?");
+        params.push(skip.to_string());
     }
 
-    Ok(items)
+    Ok((querystr, params))
 }
 
 #[cfg(test)]
@@ -244,6 +419,20 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_query_categories() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "report", "today");
+        insert_task(&conn, "home", "laundry", "today");
+        insert_record(&conn, "work", "called the bank", "today");
+        insert_task(&conn, "", "something", "today");
+        assert_eq!(query_categories(&conn).unwrap(), vec!["home", "work"]);
+        assert_eq!(
+            category_counts(&conn).unwrap(),
+            vec![("work".to_string(), 2), ("home".to_string(), 1)]
+        );
+    }
+
     #[test]
     fn test_insert_item() {
         let (conn, _temp_file) = get_test_conn();
@@ -310,6 +499,26 @@ mod tests {
         assert_eq!(updated_recurring.human_schedule, original_human);
     }
 
+    #[test]
+    fn test_category_seq() {
+        let (conn, _temp_file) = get_test_conn();
+        let first = insert_task(&conn, "work", "report", "today");
+        insert_task(&conn, "home", "laundry", "today");
+        let second = insert_task(&conn, "work", "call", "today");
+        assert_eq!(get_item(&conn, second).unwrap().category_ref(), Some("work-2".to_string()));
+        assert_eq!(find_by_category_seq(&conn, "work", 1).unwrap(), Some(first));
+        assert_eq!(find_by_category_seq(&conn, "work", 3).unwrap(), None);
+
+        // Moved items are numbered last in their new category, edits keep the number
+        let mut item = get_item(&conn, first).unwrap();
+        item.category = "home".to_string();
+        update_item(&conn, &item).unwrap();
+        item.content = "yearly report".to_string();
+        update_item(&conn, &item).unwrap();
+        assert_eq!(get_item(&conn, first).unwrap().category_ref(), Some("home-2".to_string()));
+        assert_eq!(find_by_category_seq(&conn, "work", 1).unwrap(), None);
+    }
+
     #[test]
     fn test_delete_item() {
         let (conn, _temp_file) = get_test_conn();
@@ -365,6 +574,14 @@ mod tests {
         let all_items = query_items(&conn, &ItemQuery::new()).unwrap();
         assert_eq!(all_items.len(), 8);
 
+        let categories = vec!["work".to_string(), "life".to_string(), "gym".to_string()];
+        let item_query = ItemQuery::new().with_categories(categories);
+        assert_eq!(query_items(&conn, &item_query).unwrap().len(), 8);
+        let item_query = ItemQuery::new().with_categories(vec!["life".to_string()]);
+        assert_eq!(query_items(&conn, &item_query).unwrap().len(), 3);
+        let item_query = ItemQuery::new().with_categories(Vec::new());
+        assert!(query_items(&conn, &item_query).unwrap().is_empty());
+
         let task_items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
         assert_eq!(task_items.len(), 8);
 
@@ -571,6 +788,49 @@ mod tests {
         assert_eq!(result.last().unwrap().content, "task2");
     }
 
+    #[test]
+    fn test_query_priority() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "none", "tomorrow");
+        let urgent = insert_task(&conn, "work", "urgent", "tomorrow");
+        let high = insert_task(&conn, "work", "high", "tomorrow");
+        for (id, priority) in [(urgent, 4), (high, 3)] {
+            let mut item = get_item(&conn, id).unwrap();
+            item.priority = priority;
+            update_item(&conn, &item).unwrap();
+        }
+        assert_eq!(get_item(&conn, high).unwrap().priority_name(), Some("high"));
+
+        let query = ItemQuery::new().with_action(TASK).with_order_by("target_time");
+        let contents: Vec<String> =
+            query_items(&conn, &query).unwrap().into_iter().map(|t| t.content).collect();
+        assert_eq!(contents, ["urgent", "high", "none"]);
+
+        let high_only = query_items(&conn, &ItemQuery::new().with_priority(3)).unwrap();
+        assert_eq!(high_only.len(), 1);
+        assert_eq!(high_only[0].id, Some(high));
+    }
+
+    #[test]
+    fn test_subtasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let post = insert_task(&conn, "blog", "write post", "friday");
+        assert!(!has_subtasks(&conn).unwrap());
+        let mut intro = get_test_item(TASK, "blog", "intro");
+        intro.parent_id = Some(post);
+        let intro = insert_item(&conn, &intro).unwrap();
+
+        assert!(has_subtasks(&conn).unwrap());
+        let subtasks = query_items(&conn, &ItemQuery::new().with_parent_id(post)).unwrap();
+        assert_eq!(subtasks.len(), 1);
+        assert_eq!(subtasks[0].id, Some(intro));
+
+        // Deleting the parent leaves the subtask on its own
+        delete_item(&conn, post).unwrap();
+        assert_eq!(get_item(&conn, intro).unwrap().parent_id, None);
+        assert!(!has_subtasks(&conn).unwrap());
+    }
+
     #[test]
     fn test_query_content_like() {
         let (conn, _temp_file) = get_test_conn();
@@ -771,4 +1031,51 @@ mod tests {
             assert!(record.good_until.unwrap() > 1500);
         }
     }
+
+    #[test]
+    fn test_query_visible_to() {
+        let (conn, _temp_file) = get_test_conn();
+        let owners = [("shared", None), ("alice's", Some("alice")), ("bob's", Some("bob"))];
+        for (content, owner) in owners {
+            let mut item = get_test_item(TASK, "home", content);
+            item.owner = owner.map(String::from);
+            insert_item(&conn, &item).unwrap();
+        }
+
+        let contents = |user: Option<&str>| -> Vec<String> {
+            query_items(&conn, &ItemQuery::new().with_visible_to(user))
+                .unwrap()
+                .into_iter()
+                .map(|item| item.content)
+                .collect()
+        };
+        assert_eq!(contents(Some("alice")), vec!["shared", "alice's"]);
+        assert_eq!(contents(Some("carol")), vec!["shared"]);
+        assert_eq!(contents(None).len(), 3);
+        assert_eq!(get_item(&conn, 2).unwrap().owner.as_deref(), Some("alice"));
+    }
+
+    // Run with: cargo test --release bench_bulk_crud -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_bulk_crud() {
+        let (conn, _temp_file) = get_test_conn();
+        let count = 5_000;
+
+        let start = std::time::Instant::now();
+        let tx = conn.unchecked_transaction().unwrap();
+        for i in 0..count {
+            let id = insert_item(&tx, &get_test_item("task", "work", &format!("task {}", i))).unwrap();
+            let mut item = get_item(&tx, id).unwrap();
+            item.status = 1;
+            update_item(&tx, &item).unwrap();
+            query_items(&tx, &ItemQuery::new().with_action(TASK).with_limit(1)).unwrap();
+        }
+        tx.commit().unwrap();
+        let elapsed = start.elapsed();
+        println!(
+            "insert, get, update and query: {:.1}µs per item",
+            elapsed.as_secs_f64() * 1e6 / count as f64
+        );
+    }
 }
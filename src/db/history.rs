@@ -0,0 +1,108 @@
+use rusqlite::{
+    params,
+    Connection,
+    Result,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    pub item_id: i64,
+    pub status: u8,
+    pub change_time: i64,
+}
+
+// Every item enters status 0 when it is created.
+pub fn record_created(conn: &Connection, item_id: i64, create_time: i64) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO status_history (item_id, status, change_time) VALUES (?1, 0, ?2)",
+    )?
+    .execute(params![item_id, create_time])?;
+    Ok(())
+}
+
+// Must run before the item itself is updated, it compares against the stored status.
+pub fn record_status_change(
+    conn: &Connection,
+    item_id: i64,
+    status: u8,
+    change_time: i64,
+) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO status_history (item_id, status, change_time)
+         SELECT id, ?2, ?3 FROM items WHERE id = ?1 AND status != ?2",
+    )?
+    .execute(params![item_id, status, change_time])?;
+    Ok(())
+}
+
+pub fn delete_history(conn: &Connection, item_id: i64) -> Result<()> {
+    conn.prepare_cached("DELETE FROM status_history WHERE item_id = ?1")?
+        .execute(params![item_id])?;
+    Ok(())
+}
+
+// Status changes of the given items, ordered per item from oldest to newest.
+pub fn get_status_history(conn: &Connection, item_ids: &[i64]) -> Result<Vec<StatusChange>> {
+    if item_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let id_list = item_ids
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT item_id, status, change_time FROM status_history
+         WHERE item_id IN ({}) ORDER BY item_id, change_time, id",
+        id_list
+    ))?;
+    let changes = stmt
+        .query_map([], |row| {
+            Ok(StatusChange {
+                item_id: row.get(0)?,
+                status: row.get(1)?,
+                change_time: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<StatusChange>>>()?;
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::{
+            delete_item,
+            get_item,
+            update_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_status_history() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "write report", "today");
+        let create_time = get_item(&conn, id).unwrap().create_time;
+
+        let mut task = get_item(&conn, id).unwrap();
+        task.content.push_str(" for Q3");
+        update_item(&conn, &task).unwrap();
+        task.status = 6;
+        update_item(&conn, &task).unwrap();
+        task.status = 1;
+        update_item(&conn, &task).unwrap();
+
+        let history = get_status_history(&conn, &[id]).unwrap();
+        let statuses: Vec<u8> = history.iter().map(|c| c.status).collect();
+        assert_eq!(statuses, vec![0, 6, 1]);
+        assert_eq!(history[0].change_time, create_time);
+
+        delete_item(&conn, id).unwrap();
+        assert!(get_status_history(&conn, &[id]).unwrap().is_empty());
+    }
+}
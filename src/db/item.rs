@@ -1,22 +1,29 @@
-use std::time::{
-    SystemTime,
-    UNIX_EPOCH,
+use rusqlite::Row;
+use uuid::Uuid;
+
+use crate::{
+    args::timestr::is_end_of_day,
+    clock,
 };
 
-use rusqlite::Row;
+// Length of the id prefix shown in listings
+pub const SHORT_ID_LEN: usize = 8;
 
 #[derive(Debug, Clone)]
 pub struct Item {
     // Optional id field as when item is first created at runtime it
     // has not received an id from the db yet.
     pub id: Option<i64>,
+    // Random and never changed, unlike the row id it is stable across exports and syncs
+    pub uuid: String,
     pub action: String,
     pub category: String,
     pub content: String,
     pub create_time: i64,
     // This field is dedicated for tasks (deadline)
     pub target_time: Option<i64>,
-    #[allow(dead_code)]
+    // A deadline given as a date only, due by the end of that day rather than at a time
+    pub all_day: bool,
     pub modify_time: Option<i64>,
     pub status: u8,
     // cron and human schedule are specific to recurring tasks.
@@ -26,6 +33,21 @@ pub struct Item {
     // these records are generated when a recurring task is "done"
     pub recurring_task_id: Option<i64>,
     pub good_until: Option<i64>,
+    // Person the item belongs to when several share the database, None for shared items
+    pub owner: Option<String>,
+    // Measurement logged with a record, such as 82.5 with unit kg
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    // Number of the item within its category, given by the db when inserted or moved
+    pub seq: Option<i64>,
+    // Answers to the fields of a record template, one "name=answer" per line
+    pub fields: Option<String>,
+    // Index into PRIORITIES plus one, 0 when the task has no priority
+    pub priority: u8,
+    // Sorted, kept in the item_tags table
+    pub tags: Vec<String>,
+    // Row id of the task this one is a subtask of
+    pub parent_id: Option<i64>,
     // Runtime-only field applicable to recurring task, not persisted to db
     // Computed at application layer indicating if a recurring_task is completed.
     pub recurring_interval_complete: bool,
@@ -35,27 +57,40 @@ pub const TASK: &str = "task";
 pub const RECORD: &str = "record";
 pub const RECURRING_TASK: &str = "recurring_task";
 pub const RECURRING_TASK_RECORD: &str = "recurring_task_record";
+// Undated thoughts captured with `tascli in`, filed as tasks or records from `tascli inbox`
+pub const INBOX: &str = "inbox";
+/// Category of items created without one.
+pub const DEFAULT_CATEGORY: &str = "default";
+/// Priorities of tasks from lowest to highest, stored as their position plus one.
+pub const PRIORITIES: [&str; 4] = ["low", "medium", "high", "urgent"];
 
 impl Item {
     pub fn new(action: String, category: String, content: String) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        let now = clock::timestamp();
 
         Self {
             id: None,
+            uuid: Uuid::new_v4().to_string(),
             action,
             category,
             content,
             create_time: now,
             target_time: None,
+            all_day: false,
             modify_time: None,
             status: 0,
             cron_schedule: None,
             human_schedule: None,
             recurring_task_id: None,
             good_until: None,
+            owner: crate::config::current_user().map(String::from),
+            value: None,
+            unit: None,
+            seq: None,
+            fields: None,
+            priority: 0,
+            tags: Vec::new(),
+            parent_id: None,
             recurring_interval_complete: false,
         }
     }
@@ -67,7 +102,7 @@ impl Item {
         target_time: Option<i64>,
     ) -> Self {
         let mut item = Self::new(action, category, content);
-        item.target_time = target_time;
+        item.set_target_time(target_time);
         item
     }
 
@@ -107,30 +142,85 @@ impl Item {
         item
     }
 
+    /// Sets the deadline, a deadline at the end of its day is an all-day one.
+    pub fn set_target_time(&mut self, target_time: Option<i64>) {
+        self.target_time = target_time;
+        self.all_day = target_time.is_some_and(is_end_of_day);
+    }
+
+    /// The start of the uuid, enough to tell items apart in practice.
+    pub fn short_id(&self) -> &str {
+        self.uuid.get(..SHORT_ID_LEN).unwrap_or(&self.uuid)
+    }
+
+    /// The category and the number of the item in it, such as "work-12".
+    pub fn category_ref(&self) -> Option<String> {
+        self.seq.map(|seq| format!("{}-{}", self.category, seq))
+    }
+
+    /// Name of the priority, None when the task has none.
+    pub fn priority_name(&self) -> Option<&'static str> {
+        PRIORITIES.get(usize::from(self.priority).checked_sub(1)?).copied()
+    }
+
+    /// The fields of a record made from a template with their answers, in template order.
+    pub fn field_answers(&self) -> Vec<(&str, &str)> {
+        let fields = self.fields.as_deref().unwrap_or_default();
+        fields.lines().filter_map(|line| line.split_once('=')).collect()
+    }
+
+    /// The answer to the template field `name`, such as "5 km".
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.field_answers()
+            .into_iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, answer)| answer)
+    }
+
     pub fn from_row(row: &Row) -> Result<Self, rusqlite::Error> {
         Ok(Self {
             id: row.get("id")?,
+            uuid: row.get("uuid")?,
             action: row.get("action")?,
             category: row.get("category")?,
             content: row.get("content")?,
             create_time: row.get("create_time")?,
             target_time: row.get("target_time")?,
+            all_day: row.get("all_day")?,
             modify_time: row.get("modify_time")?,
             status: row.get("status")?,
             cron_schedule: row.get("cron_schedule")?,
             human_schedule: row.get("human_schedule")?,
             recurring_task_id: row.get("recurring_task_id")?,
             good_until: row.get("good_until")?,
+            owner: row.get("owner")?,
+            value: row.get("value")?,
+            unit: row.get("unit")?,
+            seq: row.get("seq")?,
+            fields: row.get("fields")?,
+            priority: row.get("priority")?,
+            tags: parse_tags(row.get("tags")?),
+            parent_id: row.get("parent_id")?,
             recurring_interval_complete: false,
         })
     }
 }
 
+// The tags column of a row, sorted
+fn parse_tags(tags: Option<String>) -> Vec<String> {
+    let mut tags: Vec<String> =
+        tags.unwrap_or_default().split_whitespace().map(str::to_string).collect();
+    tags.sort();
+    tags
+}
+
 // Query Struct for querying items from db
 #[derive(Debug)]
 pub struct ItemQuery<'a> {
     pub actions: Option<Vec<&'a str>>,
     pub category: Option<&'a str>,
+    pub categories: Option<Vec<String>>,
+    pub uncategorized: bool,
     pub content_like: Option<&'a str>,
     pub create_time_min: Option<i64>,
     pub create_time_max: Option<i64>,
@@ -138,9 +228,16 @@ pub struct ItemQuery<'a> {
     pub target_time_max: Option<i64>,
     pub good_until_min: Option<i64>,
     pub good_until_max: Option<i64>,
+    pub value_min: Option<f64>,
+    pub value_max: Option<f64>,
+    pub priority: Option<u8>,
+    pub tag: Option<&'a str>,
+    pub parent_id: Option<i64>,
     pub recurring_task_id: Option<i64>,
+    pub visible_to: Option<&'a str>,
     pub statuses: Option<Vec<u8>>,
     pub limit: Option<usize>,
+    pub skip: Option<usize>,
     pub offset: Offset,
     pub order_by: Option<&'a str>,
 }
@@ -160,6 +257,8 @@ impl<'a> ItemQuery<'a> {
         ItemQuery {
             actions: None,
             category: None,
+            categories: None,
+            uncategorized: false,
             content_like: None,
             create_time_min: None,
             create_time_max: None,
@@ -167,9 +266,16 @@ impl<'a> ItemQuery<'a> {
             target_time_max: None,
             good_until_min: None,
             good_until_max: None,
+            value_min: None,
+            value_max: None,
+            priority: None,
+            tag: None,
+            parent_id: None,
             recurring_task_id: None,
+            visible_to: None,
             statuses: None,
             limit: None,
+            skip: None,
             offset: Offset::None,
             order_by: None,
         }
@@ -190,6 +296,18 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    // Items in any of the categories, none match an empty list
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        self.categories = Some(categories);
+        self
+    }
+
+    // Items in the default category, or in an empty one
+    pub fn with_uncategorized(mut self) -> Self {
+        self.uncategorized = true;
+        self
+    }
+
     pub fn with_content_like(mut self, content: &'a str) -> Self {
         self.content_like = Some(content);
         self
@@ -237,6 +355,12 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    // Rows to pass over before the first one returned, for numbered pages
+    pub fn with_skip(mut self, skip: usize) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+
     pub fn with_offset(mut self, offset: Offset) -> Self {
         self.offset = offset;
         self
@@ -263,14 +387,55 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    // Only items with a value in the range, both ends included
+    pub fn with_value_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.value_min = min;
+        self.value_max = max;
+        self
+    }
+
+    // Only tasks of this priority, 0 for the ones without
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: &'a str) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    // Subtasks of the task
+    pub fn with_parent_id(mut self, parent_id: i64) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
     pub fn with_recurring_task_id(mut self, recurring_task_id: i64) -> Self {
         self.recurring_task_id = Some(recurring_task_id);
         self
     }
+
+    // Only items of `user` and shared items, everything when None
+    pub fn with_visible_to(mut self, user: Option<&'a str>) -> Self {
+        self.visible_to = user;
+        self
+    }
+}
+
+impl Default for ItemQuery<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::{
+        SystemTime,
+        UNIX_EPOCH,
+    };
+
     use super::*;
 
     #[test]
@@ -314,6 +479,16 @@ mod tests {
         assert_eq!(item.category, "category");
         assert_eq!(item.content, "content");
         assert_eq!(item.target_time, Some(target_time));
+        assert!(!item.all_day);
+
+        let end_of_day = crate::args::timestr::to_unix_epoch("tomorrow").unwrap();
+        let item = Item::with_target_time(
+            "action".to_string(),
+            "category".to_string(),
+            "content".to_string(),
+            Some(end_of_day),
+        );
+        assert!(item.all_day);
     }
 
     #[test]
@@ -0,0 +1,183 @@
+use std::{
+    process,
+    thread,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use rusqlite::{
+    params,
+    Connection,
+};
+
+use crate::{
+    db::{
+        conn::atomically,
+        state::{
+            get_state,
+            set_state,
+        },
+    },
+    error::TascliError,
+};
+
+const LOCK_KEY: &str = "lock";
+// A process killed while holding the lock never releases it, it is taken over after this
+const STALE_SECONDS: i64 = 600;
+const WAIT_SECONDS: u64 = 30;
+const POLL_MS: u64 = 200;
+
+// Who holds the lock, stored as "operation pid since"
+#[derive(Debug, PartialEq)]
+pub struct LockHolder {
+    pub operation: String,
+    pub pid: u32,
+    pub since: i64,
+}
+
+impl LockHolder {
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split(' ');
+        Some(LockHolder {
+            operation: parts.next()?.to_string(),
+            pid: parts.next()?.parse().ok()?,
+            since: parts.next()?.parse().ok()?,
+        })
+    }
+
+    fn to_value(&self) -> String {
+        format!("{} {} {}", self.operation, self.pid, self.since)
+    }
+}
+
+/// Runs a long operation such as an import holding the advisory lock of the db, waiting
+/// while another process runs one. Single writes are not held back by it.
+pub fn with_lock<T>(
+    conn: &Connection,
+    operation: &str,
+    f: impl FnOnce() -> Result<T, TascliError>,
+) -> Result<T, TascliError> {
+    acquire(conn, operation, Duration::from_secs(WAIT_SECONDS))?;
+    let result = f();
+    release(conn)?;
+    result
+}
+
+/// The long operation another process is running, the daemon skips its checks meanwhile.
+pub fn holder(conn: &Connection) -> Result<Option<LockHolder>, TascliError> {
+    let holder = get_state(conn, LOCK_KEY)?.and_then(|v| LockHolder::parse(&v));
+    Ok(holder.filter(|h| h.pid != process::id() && !is_stale(h)))
+}
+
+fn acquire(conn: &Connection, operation: &str, wait: Duration) -> Result<(), TascliError> {
+    let mut waited = Duration::ZERO;
+    loop {
+        // Checked and taken in one transaction, two processes cannot both find it free
+        let taken = atomically(conn, || -> Result<Option<LockHolder>, TascliError> {
+            if let Some(holder) = holder(conn)? {
+                return Ok(Some(holder));
+            }
+            let ours = LockHolder {
+                operation: operation.to_string(),
+                pid: process::id(),
+                since: unix_now(),
+            };
+            set_state(conn, LOCK_KEY, &ours.to_value())?;
+            Ok(None)
+        })?;
+        let Some(holder) = taken else {
+            return Ok(());
+        };
+        if waited >= wait {
+            return Err(TascliError::Invalid(format!(
+                "Another tascli process (pid {}) is running {}, try again when it is done",
+                holder.pid, holder.operation
+            )));
+        }
+        thread::sleep(Duration::from_millis(POLL_MS));
+        waited += Duration::from_millis(POLL_MS);
+    }
+}
+
+// Only our own lock is released, a stale one taken over meanwhile belongs to its new holder
+fn release(conn: &Connection) -> Result<(), TascliError> {
+    let ours = get_state(conn, LOCK_KEY)?
+        .and_then(|v| LockHolder::parse(&v))
+        .is_some_and(|h| h.pid == process::id());
+    if ours {
+        conn.execute("DELETE FROM state WHERE key = ?1", params![LOCK_KEY])?;
+    }
+    Ok(())
+}
+
+fn is_stale(holder: &LockHolder) -> bool {
+    unix_now() - holder.since > STALE_SECONDS
+}
+
+// The real time even when the clock is pinned, other processes read it
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    fn hold_elsewhere(conn: &Connection, since: i64) {
+        let other = LockHolder {
+            operation: "import".to_string(),
+            pid: process::id() + 1,
+            since,
+        };
+        set_state(conn, LOCK_KEY, &other.to_value()).unwrap();
+    }
+
+    #[test]
+    fn test_with_lock() {
+        let (conn, _temp_file) = get_test_conn();
+        let operation = with_lock(&conn, "import", || {
+            let value = get_state(&conn, LOCK_KEY)?.unwrap();
+            Ok(LockHolder::parse(&value).unwrap().operation)
+        })
+        .unwrap();
+        assert_eq!(operation, "import");
+        assert_eq!(get_state(&conn, LOCK_KEY).unwrap(), None);
+
+        // Released on failure as well
+        let failed: Result<(), TascliError> =
+            with_lock(&conn, "import", || Err(TascliError::Invalid("failed".to_string())));
+        assert!(failed.is_err());
+        assert_eq!(get_state(&conn, LOCK_KEY).unwrap(), None);
+    }
+
+    #[test]
+    fn test_held_by_other_process() {
+        let (conn, _temp_file) = get_test_conn();
+        hold_elsewhere(&conn, unix_now());
+        assert_eq!(holder(&conn).unwrap().unwrap().operation, "import");
+
+        let err = acquire(&conn, "import", Duration::ZERO).unwrap_err();
+        assert!(err.to_string().contains("is running import"), "{}", err);
+        // Not ours to release
+        release(&conn).unwrap();
+        assert!(holder(&conn).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_stale_lock_taken_over() {
+        let (conn, _temp_file) = get_test_conn();
+        hold_elsewhere(&conn, unix_now() - STALE_SECONDS - 1);
+        assert_eq!(holder(&conn).unwrap(), None);
+
+        acquire(&conn, "import", Duration::ZERO).unwrap();
+        let value = get_state(&conn, LOCK_KEY).unwrap().unwrap();
+        assert_eq!(LockHolder::parse(&value).unwrap().pid, process::id());
+    }
+}
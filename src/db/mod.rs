@@ -1,4 +1,12 @@
+pub mod automations;
 pub mod cache;
 pub mod conn;
 pub mod crud;
+pub mod history;
 pub mod item;
+pub mod lock;
+pub mod projects;
+pub mod reminders;
+pub mod state;
+pub mod tags;
+pub mod views;
@@ -0,0 +1,80 @@
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    Result,
+};
+
+/// A category with an end date, deadlines of its tasks should fall before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub category: String,
+    pub end_time: i64,
+}
+
+pub fn set_project_end(conn: &Connection, category: &str, end_time: i64) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO projects (category, end_time) VALUES (?1, ?2)
+         ON CONFLICT(category) DO UPDATE SET end_time = excluded.end_time",
+    )?
+    .execute(params![category, end_time])?;
+    Ok(())
+}
+
+pub fn clear_project_end(conn: &Connection, category: &str) -> Result<()> {
+    conn.prepare_cached("DELETE FROM projects WHERE category = ?1")?
+        .execute(params![category])?;
+    Ok(())
+}
+
+pub fn get_project(conn: &Connection, category: &str) -> Result<Option<Project>> {
+    conn.prepare_cached("SELECT category, end_time FROM projects WHERE category = ?1")?
+        .query_row(params![category], |row| {
+            Ok(Project {
+                category: row.get(0)?,
+                end_time: row.get(1)?,
+            })
+        })
+        .optional()
+}
+
+// All projects, ending soonest first.
+pub fn get_projects(conn: &Connection) -> Result<Vec<Project>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT category, end_time FROM projects ORDER BY end_time, category")?;
+    let projects = stmt
+        .query_map([], |row| {
+            Ok(Project {
+                category: row.get(0)?,
+                end_time: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<Project>>>()?;
+    Ok(projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_project_end() {
+        let (conn, _temp_file) = get_test_conn();
+        assert_eq!(get_project(&conn, "release").unwrap(), None);
+
+        set_project_end(&conn, "release", 2000).unwrap();
+        set_project_end(&conn, "offsite", 1000).unwrap();
+        set_project_end(&conn, "release", 3000).unwrap();
+        assert_eq!(get_project(&conn, "release").unwrap().unwrap().end_time, 3000);
+        let categories: Vec<String> = get_projects(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.category)
+            .collect();
+        assert_eq!(categories, vec!["offsite", "release"]);
+
+        clear_project_end(&conn, "release").unwrap();
+        assert_eq!(get_project(&conn, "release").unwrap(), None);
+    }
+}
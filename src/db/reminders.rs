@@ -0,0 +1,196 @@
+use rusqlite::{
+    params,
+    Connection,
+    Result,
+};
+
+use crate::db::{
+    conn::atomically,
+    item::Item,
+    tags::TAGS_COLUMN,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reminder {
+    pub id: i64,
+    pub item_id: i64,
+    pub lead_seconds: i64,
+    pub sent_time: Option<i64>,
+}
+
+// Replaces the lead times of an item, reminders that were already sent stay sent.
+pub fn set_reminders(conn: &Connection, item_id: i64, lead_seconds: &[i64]) -> Result<()> {
+    atomically(conn, || {
+        let keep = lead_seconds
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        conn.execute(
+            &format!(
+                "DELETE FROM reminders WHERE item_id = ?1 AND lead_seconds NOT IN ({})",
+                keep
+            ),
+            params![item_id],
+        )?;
+        let mut stmt = conn.prepare_cached(
+            "INSERT OR IGNORE INTO reminders (item_id, lead_seconds) VALUES (?1, ?2)",
+        )?;
+        for lead in lead_seconds {
+            stmt.execute(params![item_id, lead])?;
+        }
+        Ok(())
+    })
+}
+
+// Reminders of an item, earliest first.
+pub fn get_reminders(conn: &Connection, item_id: i64) -> Result<Vec<Reminder>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, item_id, lead_seconds, sent_time FROM reminders
+         WHERE item_id = ?1 ORDER BY lead_seconds DESC",
+    )?;
+    let reminders = stmt
+        .query_map(params![item_id], |row| {
+            Ok(Reminder {
+                id: row.get(0)?,
+                item_id: row.get(1)?,
+                lead_seconds: row.get(2)?,
+                sent_time: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<Reminder>>>()?;
+    Ok(reminders)
+}
+
+pub fn delete_reminders(conn: &Connection, item_id: i64) -> Result<()> {
+    conn.prepare_cached("DELETE FROM reminders WHERE item_id = ?1")?
+        .execute(params![item_id])?;
+    Ok(())
+}
+
+// Sends the reminders again, for a deadline that moved.
+pub fn rearm_reminders(conn: &Connection, item_id: i64) -> Result<()> {
+    conn.prepare_cached("UPDATE reminders SET sent_time = NULL WHERE item_id = ?1")?
+        .execute(params![item_id])?;
+    Ok(())
+}
+
+// Unsent reminders whose time has come, with their tasks ordered by deadline.
+// Tasks that are closed or already overdue get no reminders, overdue alerts cover those.
+pub fn due_reminders(
+    conn: &Connection,
+    now: i64,
+    open_statuses: &[u8],
+) -> Result<Vec<(Reminder, Item)>> {
+    let status_list = open_statuses
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT reminders.id AS reminder_id, lead_seconds, sent_time, items.*, {}
+         FROM reminders JOIN items ON items.id = reminders.item_id
+         WHERE sent_time IS NULL AND items.status IN ({})
+           AND items.target_time - lead_seconds <= ?1 AND items.target_time > ?1
+         ORDER BY items.target_time, lead_seconds DESC",
+        TAGS_COLUMN, status_list
+    ))?;
+    let due = stmt
+        .query_map(params![now], |row| {
+            let item = Item::from_row(row)?;
+            let reminder = Reminder {
+                id: row.get("reminder_id")?,
+                item_id: item.id.unwrap_or_default(),
+                lead_seconds: row.get("lead_seconds")?,
+                sent_time: row.get("sent_time")?,
+            };
+            Ok((reminder, item))
+        })?
+        .collect::<Result<Vec<(Reminder, Item)>>>()?;
+    Ok(due)
+}
+
+pub fn mark_sent(conn: &Connection, reminder_id: i64, sent_time: i64) -> Result<()> {
+    conn.prepare_cached("UPDATE reminders SET sent_time = ?2 WHERE id = ?1")?
+        .execute(params![reminder_id, sent_time])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::{
+            delete_item,
+            get_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+            update_status,
+        },
+    };
+
+    const OPEN: &[u8] = &[0, 4, 6];
+
+    fn leads(conn: &Connection, item_id: i64) -> Vec<i64> {
+        get_reminders(conn, item_id)
+            .unwrap()
+            .iter()
+            .map(|r| r.lead_seconds)
+            .collect()
+    }
+
+    #[test]
+    fn test_set_reminders_replaces_lead_times() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "send invoice", "tomorrow");
+
+        set_reminders(&conn, id, &[3600, 86400]).unwrap();
+        assert_eq!(leads(&conn, id), vec![86400, 3600]);
+
+        let hour = get_reminders(&conn, id).unwrap()[1].clone();
+        mark_sent(&conn, hour.id, 1000).unwrap();
+        set_reminders(&conn, id, &[3600, 1800]).unwrap();
+        let reminders = get_reminders(&conn, id).unwrap();
+        assert_eq!(leads(&conn, id), vec![3600, 1800]);
+        assert_eq!(reminders[0].sent_time, Some(1000));
+
+        rearm_reminders(&conn, id).unwrap();
+        assert!(get_reminders(&conn, id).unwrap().iter().all(|r| r.sent_time.is_none()));
+
+        set_reminders(&conn, id, &[]).unwrap();
+        assert!(leads(&conn, id).is_empty());
+    }
+
+    #[test]
+    fn test_due_reminders() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "send invoice", "tomorrow");
+        let closed = insert_task(&conn, "work", "pay rent", "tomorrow");
+        update_status(&conn, closed, 1);
+        let due = get_item(&conn, id).unwrap().target_time.unwrap();
+        set_reminders(&conn, id, &[86400, 3600]).unwrap();
+        set_reminders(&conn, closed, &[86400]).unwrap();
+
+        let lead_times = |now: i64| -> Vec<i64> {
+            due_reminders(&conn, now, OPEN)
+                .unwrap()
+                .iter()
+                .map(|(reminder, _)| reminder.lead_seconds)
+                .collect()
+        };
+        assert!(lead_times(due - 86401).is_empty());
+        assert_eq!(lead_times(due - 86400), vec![86400]);
+        assert_eq!(lead_times(due - 60), vec![86400, 3600]);
+        assert!(lead_times(due).is_empty(), "overdue tasks get no reminders");
+
+        let (reminder, item) = due_reminders(&conn, due - 86400, OPEN).unwrap().remove(0);
+        assert_eq!(item.content, "send invoice");
+        mark_sent(&conn, reminder.id, due - 86400).unwrap();
+        assert_eq!(lead_times(due - 60), vec![3600]);
+
+        delete_item(&conn, id).unwrap();
+        assert!(get_reminders(&conn, id).unwrap().is_empty());
+    }
+}
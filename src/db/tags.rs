@@ -0,0 +1,132 @@
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    Result,
+};
+
+/// The tags of each item read from `items`, separated by spaces, null without tags.
+pub const TAGS_COLUMN: &str =
+    "(SELECT group_concat(tag, ' ') FROM item_tags WHERE item_id = items.id) AS tags";
+
+// Replaces the tags of an item.
+pub fn set_tags(conn: &Connection, item_id: i64, tags: &[String]) -> Result<()> {
+    delete_tags(conn, item_id)?;
+    let mut stmt =
+        conn.prepare_cached("INSERT OR IGNORE INTO item_tags (item_id, tag) VALUES (?1, ?2)")?;
+    for tag in tags {
+        stmt.execute(params![item_id, tag])?;
+    }
+    Ok(())
+}
+
+pub fn delete_tags(conn: &Connection, item_id: i64) -> Result<()> {
+    conn.prepare_cached("DELETE FROM item_tags WHERE item_id = ?1")?
+        .execute(params![item_id])?;
+    Ok(())
+}
+
+// Every tag in use, the most used first.
+pub fn all_tags(conn: &Connection) -> Result<Vec<String>> {
+    conn.prepare_cached(
+        "SELECT tag FROM item_tags GROUP BY tag ORDER BY COUNT(*) DESC, tag",
+    )?
+    .query_map([], |row| row.get(0))?
+    .collect()
+}
+
+pub fn has_tags(conn: &Connection) -> Result<bool> {
+    let found = conn
+        .prepare_cached("SELECT 1 FROM item_tags LIMIT 1")?
+        .query_row([], |_| Ok(()))
+        .optional()?;
+    Ok(found.is_some())
+}
+
+/// The content and its tags in lowercase. The `+tag` words at the end are removed from the
+/// content, one in the middle keeps its word without the `+`. Words such as `+1` are kept, a
+/// tag starts with a letter.
+pub fn split_tags(content: &str) -> (String, Vec<String>) {
+    let as_tag = |word: &str| word.strip_prefix('+').and_then(normalize_tag);
+    let words: Vec<&str> = content.split(' ').collect();
+    let mut tags: Vec<String> = Vec::new();
+    for tag in words.iter().filter_map(|word| as_tag(word)) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    let mut end = words.len();
+    while end > 0 && (words[end - 1].is_empty() || as_tag(words[end - 1]).is_some()) {
+        end -= 1;
+    }
+    let kept: Vec<&str> = words[..end]
+        .iter()
+        .map(|word| if as_tag(word).is_some() { &word[1..] } else { word })
+        .collect();
+    (kept.join(" ").trim().to_string(), tags)
+}
+
+/// A tag as stored, lowercase letters, digits, dashes and underscores starting with a letter.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim_start_matches('+').to_lowercase();
+    let valid = tag.starts_with(char::is_alphabetic)
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            crud::{
+                get_item,
+                query_items,
+            },
+            item::ItemQuery,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_set_tags() {
+        let (conn, _temp_file) = get_test_conn();
+        assert!(!has_tags(&conn).unwrap());
+        let milk = insert_task(&conn, "home", "buy milk", "today");
+        let report = insert_task(&conn, "work", "send report", "today");
+        set_tags(&conn, milk, &["errands".to_string(), "quick".to_string()]).unwrap();
+        set_tags(&conn, report, &["quick".to_string()]).unwrap();
+
+        assert!(has_tags(&conn).unwrap());
+        assert_eq!(all_tags(&conn).unwrap(), ["quick", "errands"]);
+        assert_eq!(get_item(&conn, milk).unwrap().tags, ["errands", "quick"]);
+        assert_eq!(query_items(&conn, &ItemQuery::new().with_tag("Quick")).unwrap().len(), 2);
+
+        set_tags(&conn, milk, &[]).unwrap();
+        assert!(get_item(&conn, milk).unwrap().tags.is_empty());
+        assert_eq!(all_tags(&conn).unwrap(), ["quick"]);
+    }
+
+    #[test]
+    fn test_split_tags() {
+        let (content, tags) = split_tags("buy milk +Errands +quick +errands");
+        assert_eq!(content, "buy milk");
+        assert_eq!(tags, ["errands", "quick"]);
+
+        let (content, tags) = split_tags("+1 the proposal, +review");
+        assert_eq!(content, "+1 the proposal,");
+        assert_eq!(tags, ["review"]);
+
+        let (content, tags) = split_tags("fix +bug in parser +urgent");
+        assert_eq!(content, "fix bug in parser");
+        assert_eq!(tags, ["bug", "urgent"]);
+
+        assert_eq!(normalize_tag("+Q3-plan"), Some("q3-plan".to_string()));
+        assert_eq!(normalize_tag("3d"), None);
+        assert_eq!(normalize_tag("a.b"), None);
+    }
+}
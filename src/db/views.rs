@@ -0,0 +1,86 @@
+use nanoserde::{
+    DeJson,
+    SerJson,
+};
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    Result,
+};
+
+/// A saved command line run by name with `tascli view <name>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+// Arguments are kept as a json array so quoting survives the round trip
+fn view_from_row(name: String, args: String) -> Result<View> {
+    let args = Vec::<String>::deserialize_json(&args).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(View { name, args })
+}
+
+pub fn save_view(conn: &Connection, name: &str, args: &[String]) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO views (name, args) VALUES (?1, ?2)
+         ON CONFLICT(name) DO UPDATE SET args = excluded.args",
+    )?
+    .execute(params![name, args.to_vec().serialize_json()])?;
+    Ok(())
+}
+
+pub fn get_view(conn: &Connection, name: &str) -> Result<Option<View>> {
+    let row = conn
+        .prepare_cached("SELECT name, args FROM views WHERE name = ?1")?
+        .query_row(params![name], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?;
+    row.map(|(name, args)| view_from_row(name, args)).transpose()
+}
+
+pub fn get_views(conn: &Connection) -> Result<Vec<View>> {
+    let mut stmt = conn.prepare_cached("SELECT name, args FROM views ORDER BY name")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<(String, String)>>>()?;
+    rows.into_iter()
+        .map(|(name, args)| view_from_row(name, args))
+        .collect()
+}
+
+// False when there was no such view
+pub fn delete_view(conn: &Connection, name: &str) -> Result<bool> {
+    let deleted = conn
+        .prepare_cached("DELETE FROM views WHERE name = ?1")?
+        .execute(params![name])?;
+    Ok(deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_views() {
+        let (conn, _temp_file) = get_test_conn();
+        let args: Vec<String> = ["list", "task", "--search", "weekly \"sync\""]
+            .map(String::from)
+            .to_vec();
+        save_view(&conn, "sync", &args).unwrap();
+        save_view(&conn, "inbox", &["list".to_string(), "task".to_string()]).unwrap();
+        assert_eq!(get_view(&conn, "sync").unwrap().unwrap().args, args);
+
+        save_view(&conn, "sync", &["list".to_string(), "record".to_string()]).unwrap();
+        let names: Vec<String> = get_views(&conn).unwrap().into_iter().map(|v| v.name).collect();
+        assert_eq!(names, vec!["inbox", "sync"]);
+        assert_eq!(get_view(&conn, "sync").unwrap().unwrap().args, vec!["list", "record"]);
+
+        assert!(delete_view(&conn, "sync").unwrap());
+        assert!(!delete_view(&conn, "sync").unwrap());
+        assert_eq!(get_view(&conn, "sync").unwrap(), None);
+    }
+}
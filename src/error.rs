@@ -0,0 +1,128 @@
+use nanoserde::SerJson;
+
+/// Errors surfaced by commands, each kind exits with its own code.
+#[derive(Debug, thiserror::Error)]
+pub enum TascliError {
+    /// sqlite failures
+    #[error("{0}")]
+    Db(#[from] rusqlite::Error),
+    /// missing or malformed config file
+    #[error("{0}")]
+    Config(String),
+    /// file system failures, with what was being done at the time
+    #[error("{message}: {source}")]
+    Io {
+        message: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// time strings, indices, csv and other user input that cannot be understood
+    #[error("{0}")]
+    Parse(String),
+    /// natural language processing failures
+    #[cfg(feature = "nlp")]
+    #[error("{0}")]
+    Nlp(#[from] crate::nlp::NLPError),
+    /// requests that cannot be carried out, such as an index missing from the cache
+    #[error("{0}")]
+    Invalid(String),
+}
+
+#[derive(SerJson)]
+struct ErrorOutput {
+    kind: String,
+    message: String,
+    exit_code: i32,
+}
+
+impl TascliError {
+    pub fn io(message: impl Into<String>, source: std::io::Error) -> Self {
+        TascliError::Io {
+            message: message.into(),
+            source,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TascliError::Db(_) => "db",
+            TascliError::Config(_) => "config",
+            TascliError::Io { .. } => "io",
+            TascliError::Parse(_) => "parse",
+            #[cfg(feature = "nlp")]
+            TascliError::Nlp(_) => "nlp",
+            TascliError::Invalid(_) => "invalid",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            TascliError::Invalid(_) => 1,
+            TascliError::Parse(_) => 2,
+            TascliError::Config(_) => 3,
+            TascliError::Db(_) => 4,
+            TascliError::Io { .. } => 5,
+            #[cfg(feature = "nlp")]
+            TascliError::Nlp(_) => 6,
+        }
+    }
+
+    /// Single line json for scripts, `{"kind":"db","message":"...","exit_code":4}`
+    pub fn to_json(&self) -> String {
+        ErrorOutput {
+            kind: self.kind().to_string(),
+            message: self.to_string(),
+            exit_code: self.exit_code(),
+        }
+        .serialize_json()
+    }
+}
+
+impl From<String> for TascliError {
+    fn from(message: String) -> Self {
+        TascliError::Invalid(message)
+    }
+}
+
+impl From<&str> for TascliError {
+    fn from(message: &str) -> Self {
+        TascliError::Invalid(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_db_error_keeps_source() {
+        let err: TascliError = rusqlite::Error::QueryReturnedNoRows.into();
+        assert_eq!(err.kind(), "db");
+        assert_eq!(err.exit_code(), 4);
+        assert_eq!(err.to_string(), "Query returned no rows");
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn test_io_error_message() {
+        let source = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = TascliError::io("Failed to read a.csv", source);
+        assert_eq!(err.to_string(), "Failed to read a.csv: no such file");
+        assert_eq!(err.exit_code(), 5);
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let err = TascliError::Parse("Invalid time \"soon\"".to_string());
+        assert_eq!(
+            err.to_json(),
+            r#"{"kind":"parse","message":"Invalid time \"soon\"","exit_code":2}"#
+        );
+        let err: TascliError = "Index 3 not found".into();
+        assert_eq!(err.kind(), "invalid");
+        assert_eq!(err.exit_code(), 1);
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    cell::RefCell,
+    sync::atomic::{
+        AtomicBool,
+        Ordering,
+    },
+};
+
+use nanoserde::SerJson;
+use rusqlite::Connection;
+
+use crate::{
+    actions::display::print_yellow,
+    clock,
+    config::{
+        self,
+        Config,
+    },
+    db::item::Item,
+    integrations::{
+        chat,
+        webhook,
+    },
+    private,
+    rules,
+};
+
+pub const CREATE: &str = "create";
+pub const DONE: &str = "done";
+pub const DELETE: &str = "delete";
+
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Events emitted while `hold` runs, None when they are delivered right away
+    static HELD: RefCell<Option<Vec<HeldEvent>>> = const { RefCell::new(None) };
+}
+
+/// An event held back by `hold` until the change it is about is committed.
+pub struct HeldEvent {
+    event: String,
+    item: Item,
+}
+
+#[derive(Debug, SerJson)]
+pub struct Event {
+    pub event: String,
+    pub timestamp: i64,
+    pub item: EventItem,
+}
+
+#[derive(Debug, SerJson)]
+pub struct EventItem {
+    pub id: Option<i64>,
+    pub uuid: String,
+    pub action: String,
+    pub category: String,
+    pub content: String,
+    pub status: u8,
+    pub create_time: i64,
+    pub target_time: Option<i64>,
+    pub human_schedule: Option<String>,
+    pub owner: Option<String>,
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+}
+
+impl Event {
+    pub fn new(event: &str, item: &Item) -> Self {
+        let now = clock::timestamp();
+
+        Self {
+            event: event.to_string(),
+            timestamp: now,
+            item: EventItem {
+                id: item.id,
+                uuid: item.uuid.clone(),
+                action: item.action.clone(),
+                category: item.category.clone(),
+                // Payloads leave the machine, private content is never decrypted for them
+                content: private::conceal(&item.content),
+                status: item.status,
+                create_time: item.create_time,
+                target_time: item.target_time,
+                human_schedule: item.human_schedule.clone(),
+                owner: item.owner.clone(),
+                value: item.value,
+                unit: item.unit.clone(),
+            },
+        }
+    }
+}
+
+/// Stops events from being emitted, for changes to a sandbox such as the tutorial's.
+pub fn set_muted(muted: bool) {
+    MUTED.store(muted, Ordering::Relaxed);
+}
+
+/// Runs `f` holding back the events it emits, they are returned when it succeeds and dropped
+/// when it fails. Wrapped around a transaction, nothing is announced before the commit, and
+/// rules and scripts writing on connections of their own do not wait on its lock.
+pub fn hold<T, E>(f: impl FnOnce() -> Result<T, E>) -> (Result<T, E>, Vec<HeldEvent>) {
+    let outer = HELD.replace(Some(Vec::new()));
+    let result = f();
+    let held = HELD.replace(outer).unwrap_or_default();
+    match result {
+        Ok(_) => (result, held),
+        Err(_) => (result, Vec::new()),
+    }
+}
+
+/// Emits the events held back by `hold`, in the order they happened.
+pub fn release(conn: &Connection, held: Vec<HeldEvent>) {
+    for HeldEvent { event, mut item } in held {
+        emit(conn, &event, &mut item);
+    }
+}
+
+// Run the automation rules and scripts for an event, then deliver it to every configured sink.
+// Rule and delivery failures are reported but never fail the command that emitted them.
+pub fn emit(conn: &Connection, event: &str, item: &mut Item) {
+    if MUTED.load(Ordering::Relaxed) {
+        return;
+    }
+    let held = HELD.with_borrow_mut(|held| {
+        held.as_mut().map(|held| {
+            held.push(HeldEvent {
+                event: event.to_string(),
+                item: item.clone(),
+            })
+        })
+    });
+    if held.is_some() {
+        return;
+    }
+    let config = match config::get_config() {
+        Ok(config) => config,
+        Err(_) => return,
+    };
+    deliver(conn, &config, event, item);
+}
+
+fn deliver(conn: &Connection, config: &Config, event: &str, item: &mut Item) {
+    rules::apply_event_rules(conn, config, event, item);
+    #[cfg(feature = "scripting")]
+    crate::scripting::run_event_scripts(conn, config, event, item);
+
+    if !config.webhooks.is_empty() {
+        let body = Event::new(event, item).serialize_json();
+        for hook in config.webhooks.iter().filter(|h| webhook::subscribes_to(h, event)) {
+            if let Err(e) = webhook::send_webhook(hook, &body) {
+                print_yellow(&format!("Webhook {} failed: {}", hook.url, e));
+            }
+        }
+    }
+
+    // Chat channels are only told about completions, overdue alerts are sent by `tascli notify`.
+    if event == DONE {
+        let text = format!(
+            "✅ Completed [{}] {}",
+            item.category,
+            private::conceal(&item.content).lines().next().unwrap_or_default()
+        );
+        for channel in config.chat.iter().filter(|c| chat::handles_category(c, &item.category)) {
+            if let Err(e) = chat::post_message(channel, &text) {
+                print_yellow(&format!("Chat notification to {} failed: {}", channel.kind, e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{
+            Read,
+            Write,
+        },
+        net::TcpListener,
+        thread::{
+            self,
+            JoinHandle,
+        },
+    };
+
+    use super::*;
+    use crate::config::{
+        ChatConfig,
+        WebhookConfig,
+    };
+
+    // Answers one request with 200 and hands back its body
+    fn serve_once() -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                assert!(n > 0, "connection closed before the body was sent");
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .to_lowercase()
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length:")?.trim().parse().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                            .unwrap();
+                        return body.to_string();
+                    }
+                }
+            }
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_event_payload() {
+        let mut item = Item::with_target_time(
+            "task".to_string(),
+            "work".to_string(),
+            "write \"report\"".to_string(),
+            Some(1700000000),
+        );
+        item.id = Some(7);
+
+        let json = Event::new(DONE, &item).serialize_json();
+        assert!(json.contains(r#""event":"done""#));
+        assert!(json.contains(r#""id":7"#));
+        assert!(json.contains(r#""category":"work""#));
+        assert!(json.contains(r#""content":"write \"report\"""#));
+        assert!(json.contains(r#""target_time":1700000000"#));
+
+        item.content = format!("{}00ff", private::PRIVATE_PREFIX);
+        let json = Event::new(DONE, &item).serialize_json();
+        assert!(json.contains(r#""content":"[private]""#), "{}", json);
+    }
+
+    #[test]
+    fn test_hold() {
+        let (conn, _temp_file) = crate::tests::get_test_conn();
+        let mut item =
+            Item::new("task".to_string(), "home".to_string(), "water plants".to_string());
+        let (result, held) = hold(|| {
+            emit(&conn, CREATE, &mut item);
+            emit(&conn, DONE, &mut item);
+            Ok::<_, String>(())
+        });
+        assert!(result.is_ok());
+        let events: Vec<&str> = held.iter().map(|held| held.event.as_str()).collect();
+        assert_eq!(events, [CREATE, DONE]);
+
+        let (result, held) = hold(|| {
+            emit(&conn, CREATE, &mut item);
+            Err::<(), _>("rolled back".to_string())
+        });
+        assert!(result.is_err());
+        assert!(held.is_empty());
+
+        // Events of a nested run are held by the outer one
+        let (_, held) = hold(|| {
+            let (_, inner) = hold(|| {
+                emit(&conn, DELETE, &mut item);
+                Ok::<_, String>(())
+            });
+            release(&conn, inner);
+            Ok::<_, String>(())
+        });
+        assert_eq!(held.len(), 1);
+        assert!(HELD.with_borrow(Option::is_none));
+    }
+
+    #[cfg(feature = "nlp")]
+    #[test]
+    fn test_deliver_inside_runtime() {
+        let (conn, _temp_file) = crate::tests::get_test_conn();
+        let (url, server) = serve_once();
+        let config = Config {
+            webhooks: vec![WebhookConfig {
+                url,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut item =
+            Item::new("task".to_string(), "home".to_string(), "water plants".to_string());
+
+        // NLP commands change items from inside their runtime
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async { deliver(&conn, &config, CREATE, &mut item) });
+        assert!(server.join().unwrap().contains(r#""content":"water plants""#));
+    }
+
+    #[cfg(feature = "nlp")]
+    #[test]
+    fn test_deliver_chat_inside_runtime() {
+        let (conn, _temp_file) = crate::tests::get_test_conn();
+        let (url, server) = serve_once();
+        let config = Config {
+            chat: vec![ChatConfig {
+                kind: "slack".to_string(),
+                url,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut item =
+            Item::new("task".to_string(), "home".to_string(), "water plants".to_string());
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async { deliver(&conn, &config, DONE, &mut item) });
+        assert_eq!(server.join().unwrap(), r#"{"text":"✅ Completed [home] water plants"}"#);
+    }
+}
@@ -0,0 +1,97 @@
+# Listing tables
+table-index = Nr.
+table-category = Kategorie
+table-content = Inhalt
+table-deadline = Fällig
+table-tags = Tags
+table-created-at = Erstellt
+tasks-list = Aufgaben:
+records-list = Einträge:
+records-day = { $date }: { $count ->
+    [one] 1 Eintrag
+   *[other] { $count } Einträge
+}
+records-week = Woche ab { $date }: { $count ->
+    [one] 1 Eintrag
+   *[other] { $count } Einträge
+}
+no-tasks-found = Keine Aufgaben gefunden
+no-records-found = Keine Einträge gefunden
+no-next-page = Keine weitere Seite vorhanden
+
+# Dates and statuses in listings
+today = Heute
+tomorrow = Morgen
+next-weekday = Nächster { $weekday }
+weekday-mon = Montag
+weekday-tue = Dienstag
+weekday-wed = Mittwoch
+weekday-thu = Donnerstag
+weekday-fri = Freitag
+weekday-sat = Samstag
+weekday-sun = Sonntag
+invalid-timestamp = Ungültiger Zeitstempel
+no-schedule = Kein Zeitplan
+private-content = [privat]
+recurring = Wiederkehrend
+fulfilled = erledigt für diesen Zeitraum
+stale = seit { $days } Tagen offen
+overdue = überfällig
+status-ongoing = offen
+status-completed = erledigt
+status-cancelled = abgebrochen
+status-duplicate = Duplikat
+status-suspended = pausiert
+status-removed = entfernt
+status-pending = ausstehend
+status-unknown = unbekannt
+priority-low = niedrig
+priority-medium = mittel
+priority-high = hoch
+priority-urgent = dringend
+
+# Command results
+inserted-task = Aufgabe angelegt:
+inserted-recurring-task = Wiederkehrende Aufgabe angelegt:
+inserted-record = Eintrag angelegt:
+completed-task = Aufgabe erledigt:
+completed-recurring-task = Wiederkehrende Aufgabe erledigt:
+updated-task = Aufgabe geändert:
+updated-recurring-task = Wiederkehrende Aufgabe geändert:
+updated-record = Eintrag geändert:
+deletion-success = Erfolgreich gelöscht
+subtasks-left-open = { $count ->
+    [one] 1 Unteraufgabe bleibt offen
+   *[other] { $count } Unteraufgaben bleiben offen
+}
+closed-subtasks = { $count ->
+    [one] 1 Unteraufgabe geschlossen
+   *[other] { $count } Unteraufgaben geschlossen
+}
+unlocked-until = Private Inhalte werden bis { $time } angezeigt, tascli lock verbirgt sie früher
+locked = Private Inhalte sind verborgen
+
+# Prompts, answers are matched case insensitively
+confirm-delete = Soll dieser Eintrag ({ $item_type }) wirklich gelöscht werden?
+confirm-close-subtasks = { $count ->
+    [one] Die Unteraufgabe ist noch offen, soll sie auch geschlossen werden?
+   *[other] { $count } Unteraufgaben sind noch offen, sollen sie auch geschlossen werden?
+}
+prompt-yes-no = (j/n)
+prompt-yes-answers = j, ja, y, yes
+
+# Errors
+warning = Warnung
+error = Fehler
+error-connecting-db = Fehler beim Öffnen der Datenbank
+did-you-mean = Meinten Sie `{ $command }`?
+running-corrected = Führe `{ $command }` aus
+not-deleting = { $item_type } wird nicht gelöscht
+cannot-complete-record = Ein Eintrag kann nicht erledigt werden
+cache-invalid = Der Cache ist ungültig, bitte zuerst einen list Befehl ausführen
+cache-unavailable = Keine Verbindung zum Cache
+id-not-found = keine Id beginnt mit { $id }
+id-ambiguous = mehr als eine Id beginnt mit { $id }, bitte mehr Zeichen angeben
+item-not-found = es gibt keinen Eintrag { $item }
+index-not-found = Nummer { $index } existiert nicht
+private-undecryptable = privater Inhalt kann mit dem lokalen Schlüssel nicht entschlüsselt werden
@@ -0,0 +1,97 @@
+# Listing tables
+table-index = Index
+table-category = Category
+table-content = Content
+table-deadline = Deadline
+table-tags = Tags
+table-created-at = Created At
+tasks-list = Tasks List:
+records-list = Records List:
+records-day = { $date }: { $count ->
+    [one] 1 record
+   *[other] { $count } records
+}
+records-week = Week of { $date }: { $count ->
+    [one] 1 record
+   *[other] { $count } records
+}
+no-tasks-found = No tasks found
+no-records-found = No records found
+no-next-page = No next page available
+
+# Dates and statuses in listings
+today = Today
+tomorrow = Tomorrow
+next-weekday = Next { $weekday }
+weekday-mon = Monday
+weekday-tue = Tuesday
+weekday-wed = Wednesday
+weekday-thu = Thursday
+weekday-fri = Friday
+weekday-sat = Saturday
+weekday-sun = Sunday
+invalid-timestamp = Invalid timestamp
+no-schedule = No schedule
+private-content = [private]
+recurring = Recurring
+fulfilled = fulfilled
+stale = stale { $days }d
+overdue = overdue
+status-ongoing = ongoing
+status-completed = completed
+status-cancelled = cancelled
+status-duplicate = duplicate
+status-suspended = suspended
+status-removed = removed
+status-pending = pending
+status-unknown = unknown
+priority-low = low
+priority-medium = medium
+priority-high = high
+priority-urgent = urgent
+
+# Command results
+inserted-task = Inserted Task:
+inserted-recurring-task = Inserted Recurring Task:
+inserted-record = Inserted Record:
+completed-task = Completed Task:
+completed-recurring-task = Completed Recurring Task:
+updated-task = Updated Task:
+updated-recurring-task = Updated Recurring Task:
+updated-record = Updated Record:
+deletion-success = Deletion success
+subtasks-left-open = { $count ->
+    [one] 1 subtask is left open
+   *[other] { $count } subtasks are left open
+}
+closed-subtasks = { $count ->
+    [one] Closed 1 subtask
+   *[other] Closed { $count } subtasks
+}
+unlocked-until = Private content is shown until { $time }, run tascli lock to hide it sooner
+locked = Private content is hidden
+
+# Prompts, answers are matched case insensitively
+confirm-delete = Are you sure you want to delete this { $item_type }?
+confirm-close-subtasks = { $count ->
+    [one] Its subtask is still open, close it as well?
+   *[other] Its { $count } subtasks are still open, close them as well?
+}
+prompt-yes-no = (y/n)
+prompt-yes-answers = y, yes
+
+# Errors
+warning = Warning
+error = Error
+error-connecting-db = Error connecting to db file
+did-you-mean = Did you mean `{ $command }`?
+running-corrected = Running `{ $command }`
+not-deleting = Not deleting the { $item_type }
+cannot-complete-record = Cannot complete a record
+cache-invalid = Cache is not valid, considering running list command first
+cache-unavailable = Cannot connect to cache
+id-not-found = no item id starts with { $id }
+id-ambiguous = more than one item id starts with { $id }, add more characters
+item-not-found = there is no item { $item }
+index-not-found = index { $index } does not exist
+private-undecryptable = private content cannot be decrypted with the local key
@@ -0,0 +1,164 @@
+use std::{
+    env,
+    sync::LazyLock,
+};
+
+pub use fluent_bundle::FluentArgs;
+use fluent_bundle::{
+    concurrent::FluentBundle,
+    FluentResource,
+};
+use unic_langid::LanguageIdentifier;
+
+use crate::config;
+
+const DEFAULT_LANGUAGE: &str = "en";
+
+// Bundled locales, the first is the fallback for untranslated messages
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("locales/en.ftl")),
+    ("de", include_str!("locales/de.ftl")),
+];
+
+// Checked in this order when config sets no language, as gettext does
+const LOCALE_ENV_VARS: &[&str] = &["LC_ALL", "LC_MESSAGES", "LANG"];
+
+type Bundle = FluentBundle<FluentResource>;
+
+static FALLBACK: LazyLock<Bundle> = LazyLock::new(|| load_bundle(DEFAULT_LANGUAGE));
+static BUNDLE: LazyLock<Bundle> = LazyLock::new(|| load_bundle(current_language()));
+
+/// Formats a message in the user's language, e.g. `tr!("index-not-found", index = 3)`.
+#[macro_export]
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::tr($id, None)
+    };
+    ($id:expr, $($key:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::i18n::FluentArgs::new();
+        $(args.set(stringify!($key), $value);)+
+        $crate::i18n::tr($id, Some(&args))
+    }};
+}
+
+/// Message `id` in the configured language, English when it has no translation.
+pub fn tr(id: &str, args: Option<&FluentArgs>) -> String {
+    format(&BUNDLE, id, args)
+        .or_else(|| format(&FALLBACK, id, args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn format(bundle: &Bundle, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    Some(bundle.format_pattern(pattern, args, &mut errors).into_owned())
+}
+
+fn current_language() -> &'static str {
+    // Tests compare against the English strings whatever the machine's locale is
+    if cfg!(test) {
+        return DEFAULT_LANGUAGE;
+    }
+    let configured = config::get_config()
+        .map(|c| c.language)
+        .unwrap_or_default();
+    let from_env = LOCALE_ENV_VARS.iter().filter_map(|var| env::var(var).ok());
+    resolve_language(std::iter::once(configured).chain(from_env))
+}
+
+/// The bundled language for the first non-empty setting, such as "de", "de_DE.UTF-8" or "de-AT".
+/// Languages that are not bundled, and the "C" locale, get English.
+fn resolve_language(settings: impl IntoIterator<Item = String>) -> &'static str {
+    let Some(setting) = settings.into_iter().find(|s| !s.trim().is_empty()) else {
+        return DEFAULT_LANGUAGE;
+    };
+    let language = setting
+        .trim()
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    LOCALES
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| *code == language)
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+fn load_bundle(language: &str) -> Bundle {
+    let source = LOCALES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map_or(LOCALES[0].1, |(_, source)| *source);
+    let langid: LanguageIdentifier = language.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // The unicode isolation marks around arguments show up as garbage in some terminals
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("invalid {} locale: {:?}", language, errors));
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|errors| panic!("duplicate messages in {} locale: {:?}", language, errors));
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_ids(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter(|line| !line.starts_with(['#', ' ']))
+            .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_locales_translate_every_message() {
+        let english = message_ids(LOCALES[0].1);
+        for (code, source) in &LOCALES[1..] {
+            let translated = message_ids(source);
+            for id in &english {
+                assert!(translated.contains(id), "{} is missing {}", code, id);
+            }
+            for id in &translated {
+                assert!(english.contains(id), "{} has unknown message {}", code, id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_with_args() {
+        let mut args = FluentArgs::new();
+        args.set("index", 3);
+        assert_eq!(
+            format(&load_bundle("de"), "index-not-found", Some(&args)).unwrap(),
+            "Nummer 3 existiert nicht"
+        );
+        assert_eq!(
+            format(&load_bundle("en"), "index-not-found", Some(&args)).unwrap(),
+            "index 3 does not exist"
+        );
+        assert_eq!(format(&load_bundle("en"), "no-such-message", None), None);
+    }
+
+    #[test]
+    fn test_tr_falls_back_to_id() {
+        assert_eq!(crate::tr!("no-tasks-found"), "No tasks found");
+        assert_eq!(crate::tr!("next-weekday", weekday = "Friday"), "Next Friday");
+        assert_eq!(crate::tr!("no-such-message"), "no-such-message");
+    }
+
+    #[test]
+    fn test_resolve_language() {
+        let resolve = |settings: &[&str]| resolve_language(settings.iter().map(|s| s.to_string()));
+        assert_eq!(resolve(&["de"]), "de");
+        assert_eq!(resolve(&["", "de_DE.UTF-8"]), "de");
+        assert_eq!(resolve(&["de-AT"]), "de");
+        assert_eq!(resolve(&["en", "de_DE.UTF-8"]), "en");
+        assert_eq!(resolve(&["fr_FR.UTF-8", "de_DE.UTF-8"]), "en");
+        assert_eq!(resolve(&["C"]), "en");
+        assert_eq!(resolve(&[]), "en");
+    }
+}
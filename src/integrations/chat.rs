@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use nanoserde::SerJson;
+
+use crate::{
+    config::ChatConfig,
+    integrations::off_runtime,
+};
+
+const TIMEOUT_SECONDS: u64 = 5;
+
+#[derive(SerJson)]
+struct SlackMessage {
+    text: String,
+}
+
+#[derive(SerJson)]
+struct DiscordMessage {
+    content: String,
+}
+
+pub fn handles_category(channel: &ChatConfig, category: &str) -> bool {
+    channel.categories.is_empty()
+        || channel
+            .categories
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(category))
+}
+
+pub fn post_message(channel: &ChatConfig, text: &str) -> Result<(), String> {
+    let body = message_body(&channel.kind, text)?;
+    off_runtime(|| post(channel, body))
+}
+
+fn post(channel: &ChatConfig, body: String) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECONDS))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let response = client
+        .post(&channel.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("server responded with {}", response.status()));
+    }
+    Ok(())
+}
+
+fn message_body(kind: &str, text: &str) -> Result<String, String> {
+    match kind.to_lowercase().as_str() {
+        "slack" => Ok(SlackMessage {
+            text: text.to_string(),
+        }
+        .serialize_json()),
+        "discord" => Ok(DiscordMessage {
+            content: text.to_string(),
+        }
+        .serialize_json()),
+        other => Err(format!(
+            "Unknown chat kind '{}', expected slack or discord",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_body() {
+        assert_eq!(
+            message_body("slack", "hello").unwrap(),
+            r#"{"text":"hello"}"#
+        );
+        assert_eq!(
+            message_body("Discord", "hello").unwrap(),
+            r#"{"content":"hello"}"#
+        );
+        assert!(message_body("teams", "hello").is_err());
+    }
+
+    #[test]
+    fn test_handles_category() {
+        let mut channel = ChatConfig {
+            kind: "slack".to_string(),
+            url: "http://localhost/hook".to_string(),
+            categories: Vec::new(),
+        };
+        assert!(handles_category(&channel, "work"));
+
+        channel.categories = vec!["Work".to_string()];
+        assert!(handles_category(&channel, "work"));
+        assert!(!handles_category(&channel, "home"));
+    }
+}
@@ -0,0 +1,119 @@
+use lettre::{
+    message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+    Message,
+    SmtpTransport,
+    Transport,
+};
+
+use crate::config::SmtpConfigSection;
+
+const SECURITY_STARTTLS: &str = "starttls";
+const SECURITY_TLS: &str = "tls";
+const SECURITY_NONE: &str = "none";
+
+pub fn send_email(
+    smtp: &SmtpConfigSection,
+    to: &str,
+    subject: &str,
+    body: &str,
+) -> Result<(), String> {
+    if smtp.host.is_empty() {
+        return Err("SMTP host is not configured, add an \"smtp\" section to config".to_string());
+    }
+    let from = if smtp.from.is_empty() {
+        &smtp.username
+    } else {
+        &smtp.from
+    };
+    if from.is_empty() {
+        return Err("SMTP sender is not configured, set \"from\" or \"username\"".to_string());
+    }
+
+    let message = Message::builder()
+        .from(
+            from.parse()
+                .map_err(|e| format!("Invalid sender address '{}': {}", from, e))?,
+        )
+        .to(to
+            .parse()
+            .map_err(|e| format!("Invalid recipient address '{}': {}", to, e))?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(body.to_string())
+        .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    let security = security_mode(&smtp.security)?;
+    let builder = match security {
+        SECURITY_TLS => SmtpTransport::relay(&smtp.host),
+        SECURITY_NONE => Ok(SmtpTransport::builder_dangerous(&smtp.host)),
+        _ => SmtpTransport::starttls_relay(&smtp.host),
+    }
+    .map_err(|e| format!("Failed to configure SMTP transport: {}", e))?;
+
+    let mut builder = builder.port(resolve_port(smtp.port, security));
+    if !smtp.username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            smtp.username.clone(),
+            smtp.password.clone(),
+        ));
+    }
+
+    builder
+        .build()
+        .send(&message)
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    Ok(())
+}
+
+fn security_mode(security: &str) -> Result<&'static str, String> {
+    match security.to_lowercase().as_str() {
+        "" | SECURITY_STARTTLS => Ok(SECURITY_STARTTLS),
+        SECURITY_TLS | "ssl" => Ok(SECURITY_TLS),
+        SECURITY_NONE => Ok(SECURITY_NONE),
+        other => Err(format!(
+            "Invalid SMTP security '{}', expected starttls, tls or none",
+            other
+        )),
+    }
+}
+
+fn resolve_port(port: u16, security: &str) -> u16 {
+    if port != 0 {
+        return port;
+    }
+    match security {
+        SECURITY_TLS => 465,
+        SECURITY_NONE => 25,
+        _ => 587,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_mode() {
+        assert_eq!(security_mode("").unwrap(), SECURITY_STARTTLS);
+        assert_eq!(security_mode("STARTTLS").unwrap(), SECURITY_STARTTLS);
+        assert_eq!(security_mode("ssl").unwrap(), SECURITY_TLS);
+        assert_eq!(security_mode("none").unwrap(), SECURITY_NONE);
+        assert!(security_mode("plaintext").is_err());
+    }
+
+    #[test]
+    fn test_resolve_port() {
+        assert_eq!(resolve_port(0, SECURITY_STARTTLS), 587);
+        assert_eq!(resolve_port(0, SECURITY_TLS), 465);
+        assert_eq!(resolve_port(0, SECURITY_NONE), 25);
+        assert_eq!(resolve_port(2525, SECURITY_TLS), 2525);
+    }
+
+    #[test]
+    fn test_send_email_requires_host() {
+        let smtp = SmtpConfigSection::default();
+        let result = send_email(&smtp, "me@example.com", "subject", "body");
+        assert!(result.unwrap_err().contains("SMTP host is not configured"));
+    }
+}
@@ -0,0 +1,15 @@
+pub mod chat;
+pub mod email;
+pub mod reminders;
+pub mod webhook;
+
+use std::{
+    panic,
+    thread,
+};
+
+// The blocking reqwest client runs and drops a runtime of its own, which panics when done on a
+// thread inside the tokio runtime of NLP commands. Requests are sent from a thread of their own.
+pub(crate) fn off_runtime<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|s| s.spawn(f).join()).unwrap_or_else(|e| panic::resume_unwind(e))
+}
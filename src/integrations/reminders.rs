@@ -0,0 +1,127 @@
+use std::process::Command;
+
+use nanoserde::{
+    DeJson,
+    SerJson,
+};
+
+#[derive(Debug, Clone, PartialEq, DeJson)]
+pub struct Reminder {
+    pub id: String,
+    pub name: String,
+    pub body: String,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, SerJson)]
+pub struct NewReminder {
+    pub name: String,
+    pub body: String,
+    // milliseconds since epoch, as expected by the JavaScript Date constructor
+    pub due: i64,
+}
+
+// Looks up the list by name and creates it on first use.
+const LIST_PRELUDE: &str = r#"
+const app = Application("Reminders");
+const matches = app.lists.whose({ name: LIST_NAME })();
+let list = matches[0];
+if (!list) {
+    list = app.List({ name: LIST_NAME });
+    app.lists.push(list);
+    list = app.lists.whose({ name: LIST_NAME })()[0];
+}
+"#;
+
+pub fn fetch_reminders(list: &str) -> Result<Vec<Reminder>, String> {
+    let output = run_jxa(&fetch_script(list))?;
+    Vec::<Reminder>::deserialize_json(&output)
+        .map_err(|e| format!("Unexpected output from Reminders: {}", e))
+}
+
+pub fn apply_changes(
+    list: &str,
+    create: &[NewReminder],
+    complete: &[String],
+) -> Result<(), String> {
+    if create.is_empty() && complete.is_empty() {
+        return Ok(());
+    }
+    run_jxa(&apply_script(list, create, complete)).map(|_| ())
+}
+
+fn fetch_script(list: &str) -> String {
+    format!(
+        "const LIST_NAME = {};{}JSON.stringify(list.reminders().map(r => ({{ id: r.id(), name: r.name(), body: r.body() || \"\", completed: r.completed() }})));",
+        list.to_string().serialize_json(),
+        LIST_PRELUDE
+    )
+}
+
+fn apply_script(list: &str, create: &[NewReminder], complete: &[String]) -> String {
+    format!(
+        "const LIST_NAME = {};{}\
+         for (const r of {}) {{ list.reminders.push(app.Reminder({{ name: r.name, body: r.body, dueDate: new Date(r.due) }})); }}\n\
+         for (const id of {}) {{ app.reminders.byId(id).completed = true; }}\n\"ok\";",
+        list.to_string().serialize_json(),
+        LIST_PRELUDE,
+        create.serialize_json(),
+        complete.serialize_json()
+    )
+}
+
+// Reminders is scripted through JavaScript for Automation,
+// which avoids linking against EventKit.
+fn run_jxa(script: &str) -> Result<String, String> {
+    if !cfg!(target_os = "macos") {
+        return Err("Apple Reminders sync is only available on macOS".to_string());
+    }
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", script])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Reminders script failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_escape_input() {
+        let script = fetch_script(r#"my "list""#);
+        assert!(script.starts_with(r#"const LIST_NAME = "my \"list\"";"#));
+
+        let script = apply_script(
+            "tascli",
+            &[NewReminder {
+                name: "call \"Bob\"".to_string(),
+                body: "tascli:1".to_string(),
+                due: 1000,
+            }],
+            &["x-apple-reminder://A".to_string()],
+        );
+        assert!(script.contains(r#"[{"name":"call \"Bob\"","body":"tascli:1","due":1000}]"#));
+        assert!(script.contains(r#"["x-apple-reminder://A"]"#));
+    }
+
+    #[test]
+    fn test_parse_reminders() {
+        let output = r#"[{"id":"x-apple-reminder://A","name":"call Bob","body":"tascli:3","completed":true}]"#;
+        assert_eq!(
+            Vec::<Reminder>::deserialize_json(output).unwrap(),
+            vec![Reminder {
+                id: "x-apple-reminder://A".to_string(),
+                name: "call Bob".to_string(),
+                body: "tascli:3".to_string(),
+                completed: true,
+            }]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use hmac::{
+    Hmac,
+    Mac,
+};
+use sha2::Sha256;
+
+use crate::{
+    config::WebhookConfig,
+    integrations::off_runtime,
+};
+
+const SIGNATURE_HEADER: &str = "X-Tascli-Signature";
+const TIMEOUT_SECONDS: u64 = 5;
+
+pub fn subscribes_to(hook: &WebhookConfig, event: &str) -> bool {
+    hook.events.is_empty() || hook.events.iter().any(|e| e.eq_ignore_ascii_case(event))
+}
+
+pub fn send_webhook(hook: &WebhookConfig, body: &str) -> Result<(), String> {
+    off_runtime(|| send(hook, body))
+}
+
+fn send(hook: &WebhookConfig, body: &str) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECONDS))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut request = client
+        .post(&hook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string());
+    if !hook.secret.is_empty() {
+        request = request.header(SIGNATURE_HEADER, sign(&hook.secret, body));
+    }
+
+    let response = request.send().map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("server responded with {}", response.status()));
+    }
+    Ok(())
+}
+
+// Signature in the form of "sha256=<hex digest>", as used by GitHub webhooks.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any size");
+    mac.update(body.as_bytes());
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_subscribes_to() {
+        let mut hook = WebhookConfig {
+            url: "http://localhost/hook".to_string(),
+            ..Default::default()
+        };
+        assert!(subscribes_to(&hook, "create"));
+        assert!(subscribes_to(&hook, "delete"));
+
+        hook.events = vec!["Done".to_string()];
+        assert!(subscribes_to(&hook, "done"));
+        assert!(!subscribes_to(&hook, "create"));
+    }
+}
@@ -0,0 +1,51 @@
+//! tascli as a library, for GUI wrappers, editor plugins and bots that want
+//! to embed it instead of shelling out.
+//!
+//! Tasks and records are both [`Item`]s, told apart by their `action`.
+//! [`db`] is the storage layer over the sqlite file, and [`actions::handler`]
+//! runs the same commands as the binary from parsed [`CliArgs`].
+//!
+//! ```no_run
+//! use tascli::{
+//!     db::{
+//!         conn,
+//!         crud,
+//!         item::TASK,
+//!     },
+//!     Item,
+//!     ItemQuery,
+//! };
+//!
+//! let conn = conn::connect()?;
+//! let id = crud::insert_item(&conn, &Item::new(TASK.to_string(), "work".into(), "ship it".into()))?;
+//! let open = crud::query_items(&conn, &ItemQuery::new().with_action(TASK))?;
+//! assert!(open.iter().any(|item| item.id == Some(id)));
+//! # Ok::<(), tascli::TascliError>(())
+//! ```
+
+pub mod actions;
+pub mod args;
+pub mod clock;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod events;
+pub mod i18n;
+pub mod integrations;
+pub mod logging;
+#[cfg(feature = "nlp")]
+pub mod nlp;
+pub mod private;
+pub mod rules;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+
+pub use args::parser::CliArgs;
+pub use db::item::{
+    Item,
+    ItemQuery,
+};
+pub use error::TascliError;
+
+#[cfg(test)]
+pub mod tests;
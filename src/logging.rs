@@ -0,0 +1,51 @@
+use std::{
+    fs::OpenOptions,
+    str::FromStr,
+    sync::Mutex,
+};
+
+use tracing::level_filters::LevelFilter;
+
+use crate::error::TascliError;
+
+pub const LOG_LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+
+/// Sends tracing events at `level` and above to stderr, or appended to `log_file`.
+/// Logging is off by default so command output stays unchanged.
+pub fn init(level: &str, log_file: Option<&str>) -> Result<(), TascliError> {
+    let level = LevelFilter::from_str(level)
+        .map_err(|_| TascliError::Parse(format!("Invalid log level '{}'", level)))?;
+    if level == LevelFilter::OFF {
+        return Ok(());
+    }
+
+    let builder = tracing_subscriber::fmt().with_max_level(level);
+    match log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| TascliError::io(format!("Failed to open log file {}", path), e))?;
+            builder.with_ansi(false).with_writer(Mutex::new(file)).init();
+        }
+        None => builder.with_writer(std::io::stderr).init(),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_rejects_unknown_level() {
+        let err = init("verbose", None).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid log level 'verbose'");
+    }
+
+    #[test]
+    fn test_init_off_is_noop() {
+        assert!(init("off", Some("/nonexistent/dir/tascli.log")).is_ok());
+    }
+}
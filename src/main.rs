@@ -1,30 +1,94 @@
-mod actions;
-mod args;
-mod config;
-mod db;
-mod nlp;
-
 use std::process::exit;
 
-use actions::display::print_red;
-use args::parser::CliArgs;
+#[cfg(feature = "nlp")]
+use clap::{
+    error::ErrorKind,
+    CommandFactory,
+};
 use clap::Parser;
+#[cfg(feature = "nlp")]
+use tascli::args::correction::correct;
+use tascli::{
+    actions::{
+        self,
+        display::{
+            print_red,
+            set_deterministic,
+            set_plain,
+            set_porcelain,
+        },
+    },
+    args::timestr,
+    clock,
+    config,
+    db,
+    logging,
+    tr,
+    CliArgs,
+    TascliError,
+};
 
 fn main() {
-    let cli_args = CliArgs::parse();
+    let cli_args = parse_args();
+    // Colors in cmd and PowerShell, consoles older than Windows 10 show the escape codes as is
+    #[cfg(windows)]
+    let _ = enable_ansi_support::enable_ansi_support();
+    let json_errors = cli_args.json_errors;
+    set_plain(cli_args.plain || cli_args.porcelain || config::get_config().is_ok_and(|c| c.plain));
+    set_porcelain(cli_args.porcelain);
+    set_deterministic(cli_args.deterministic);
+    if let Some(user) = &cli_args.user {
+        config::set_user(user);
+    }
+    if let Some(now) = &cli_args.now {
+        match timestr::to_unix_epoch(now) {
+            Ok(timestamp) => clock::set_now(Some(timestamp)),
+            Err(e) => report_error(&tr!("error"), TascliError::Parse(e), json_errors),
+        }
+    }
+    if let Err(err) = logging::init(&cli_args.log_level, cli_args.log_file.as_deref()) {
+        report_error(&tr!("error"), err, json_errors)
+    }
     let conn = match db::conn::connect() {
         Ok(conn) => conn,
-        Err(err) => {
-            print_red(&format!("Error connecting to db file: {}", err));
-            exit(1)
-        }
+        Err(err) => report_error(&tr!("error-connecting-db"), err, json_errors),
     };
-    let result = actions::handler::handle_commands(&conn, cli_args);
-    if result.is_err() {
-        print_red(&format!("Error: {}", result.unwrap_err()));
-        exit(1)
+    actions::rollover::print_banner(&conn);
+    if let Err(err) = actions::handler::handle_commands(&conn, cli_args) {
+        report_error(&tr!("error"), err, json_errors)
     }
 }
 
-#[cfg(test)]
-pub mod tests;
+// Arguments that do not parse exit with clap's error, a mistyped subcommand or flag also
+// suggests the command meant, or runs it with auto_correct in config
+fn parse_args() -> CliArgs {
+    let err = match CliArgs::try_parse() {
+        Ok(cli_args) => return cli_args,
+        Err(err) => err,
+    };
+    #[cfg(feature = "nlp")]
+    if matches!(err.kind(), ErrorKind::InvalidSubcommand | ErrorKind::UnknownArgument) {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if let Some(correction) = correct(CliArgs::command(), &args) {
+            let command = correction.command_line();
+            if correction.is_certain() && config::get_config().is_ok_and(|c| c.auto_correct) {
+                eprintln!("{}", tr!("running-corrected", command = command.as_str()));
+                let args = std::iter::once("tascli".to_string()).chain(correction.args);
+                return CliArgs::try_parse_from(args).unwrap_or_else(|err| err.exit());
+            }
+            let _ = err.print();
+            eprintln!("\n{}", tr!("did-you-mean", command = command.as_str()));
+            exit(err.exit_code());
+        }
+    }
+    err.exit()
+}
+
+fn report_error(prefix: &str, err: TascliError, json_errors: bool) -> ! {
+    if json_errors {
+        eprintln!("{}", err.to_json());
+    } else {
+        print_red(&format!("{}: {}", prefix, err));
+    }
+    exit(err.exit_code())
+}
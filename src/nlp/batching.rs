@@ -434,7 +434,7 @@ fn execute_parsed_command(
     let parsed_args = CliArgs::try_parse_from(cmd_args)
         .map_err(|e| format!("Failed to parse generated command: {}", e))?;
 
-    crate::actions::handler::handle_commands(conn, parsed_args)
+    crate::actions::handler::handle_commands(conn, parsed_args).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
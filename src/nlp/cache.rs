@@ -5,15 +5,17 @@
 //! in SQLite with SHA256 hashes as keys.
 
 use super::types::*;
+use super::schema::{decode_command, encode_command};
 use sha2::{Sha256, Digest};
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 use rusqlite::params;
 
 /// Cache entry for storing NLP responses
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct CacheEntry {
-    /// The parsed command
-    command: NLPCommand,
+    /// The parsed command, tagged with its schema version
+    command: serde_json::Value,
     /// Timestamp when cached (Unix seconds)
     cached_at: i64,
     /// Number of times this entry was accessed
@@ -22,7 +24,8 @@ struct CacheEntry {
 
 /// Persistent cache for NLP responses
 pub struct ResponseCache {
-    conn: rusqlite::Connection,
+    // Behind a lock so the client can be shared by the requests of a parallel compound command
+    conn: Mutex<rusqlite::Connection>,
     ttl_seconds: i64,
 }
 
@@ -52,7 +55,7 @@ impl ResponseCache {
         ).map_err(|e| NLPError::ConfigError(format!("Failed to create index: {}", e)))?;
 
         Ok(Self {
-            conn,
+            conn: Mutex::new(conn),
             ttl_seconds: 7 * 24 * 3600, // 7 days default TTL
         })
     }
@@ -64,6 +67,11 @@ impl ResponseCache {
         Ok(cache)
     }
 
+    fn conn(&self) -> MutexGuard<'_, rusqlite::Connection> {
+        // A panic mid-statement leaves nothing half written, the connection is still usable
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Generate cache key hash from input
     fn hash_input(&self, input: &str) -> String {
         // Normalize: trim, lowercase, and collapse multiple spaces
@@ -84,7 +92,7 @@ impl ResponseCache {
             .ok()?
             .as_secs() as i64;
 
-        let result = self.conn.query_row(
+        let result = self.conn().query_row(
             "SELECT response_data, cached_at, access_count FROM nlp_responses WHERE hash = ?1",
             params![hash.as_str()],
             |row| {
@@ -100,7 +108,7 @@ impl ResponseCache {
                 // Check if expired
                 if now - cached_at > self.ttl_seconds {
                     // Remove expired entry
-                    let _ = self.conn.execute(
+                    let _ = self.conn().execute(
                         "DELETE FROM nlp_responses WHERE hash = ?1",
                         params![hash.as_str()],
                     );
@@ -108,16 +116,15 @@ impl ResponseCache {
                 }
 
                 // Update access stats
-                let _ = self.conn.execute(
+                let _ = self.conn().execute(
                     "UPDATE nlp_responses SET last_accessed = ?1, access_count = ?2 WHERE hash = ?3",
                     params![now, i64::from(access_count + 1), hash.as_str()],
                 );
 
-                // Deserialize command
-                match serde_json::from_slice::<CacheEntry>(&data) {
-                    Ok(entry) => Some(entry.command),
-                    Err(_) => None,
-                }
+                // Deserialize command, migrating entries of older schema versions
+                serde_json::from_slice::<CacheEntry>(&data)
+                    .ok()
+                    .and_then(|entry| decode_command(entry.command).ok())
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => None,
             Err(_) => None,
@@ -133,7 +140,7 @@ impl ResponseCache {
             .as_secs() as i64;
 
         let entry = CacheEntry {
-            command: command.clone(),
+            command: encode_command(command)?,
             cached_at: now,
             access_count: 1,
         };
@@ -141,7 +148,7 @@ impl ResponseCache {
         let data = serde_json::to_vec(&entry)
             .map_err(|e| NLPError::SerializationError(serde_json::Error::from(e)))?;
 
-        self.conn.execute(
+        self.conn().execute(
             "INSERT OR REPLACE INTO nlp_responses (hash, input, response_data, cached_at, last_accessed, access_count)
              VALUES (?1, ?2, ?3, ?4, ?5, 1)",
             params![hash.as_str(), input, data, now, now],
@@ -152,7 +159,7 @@ impl ResponseCache {
 
     /// Clear all entries from the cache
     pub fn clear(&self) -> Result<(), NLPError> {
-        self.conn.execute("DELETE FROM nlp_responses", [])
+        self.conn().execute("DELETE FROM nlp_responses", [])
             .map_err(|e| NLPError::ConfigError(format!("Failed to clear cache: {}", e)))?;
         Ok(())
     }
@@ -166,7 +173,7 @@ impl ResponseCache {
 
         let cutoff = now - self.ttl_seconds;
 
-        let deleted = self.conn.execute(
+        let deleted = self.conn().execute(
             "DELETE FROM nlp_responses WHERE cached_at < ?1",
             [cutoff],
         ).map_err(|e| NLPError::ConfigError(format!("Failed to cleanup cache: {}", e)))?;
@@ -176,13 +183,13 @@ impl ResponseCache {
 
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
-        let total = self.conn.query_row(
+        let total = self.conn().query_row(
             "SELECT COUNT(*) FROM nlp_responses",
             [],
             |row| row.get::<_, i64>(0)
         ).unwrap_or(0);
 
-        let total_size = self.conn.query_row(
+        let total_size = self.conn().query_row(
             "SELECT SUM(LENGTH(response_data)) FROM nlp_responses",
             [],
             |row| row.get::<_, i64>(0)
@@ -195,13 +202,13 @@ impl ResponseCache {
 
         let cutoff = now - self.ttl_seconds;
 
-        let expired = self.conn.query_row(
+        let expired = self.conn().query_row(
             "SELECT COUNT(*) FROM nlp_responses WHERE cached_at < ?1",
             [cutoff],
             |row| row.get::<_, i64>(0)
         ).unwrap_or(0);
 
-        let total_accesses = self.conn.query_row(
+        let total_accesses = self.conn().query_row(
             "SELECT SUM(access_count) FROM nlp_responses",
             [],
             |row| row.get::<_, i64>(0)
@@ -280,6 +287,32 @@ mod tests {
         assert_eq!(cmd.category, Some("work".to_string()));
     }
 
+    #[test]
+    fn test_cache_reads_unversioned_entry() {
+        let (cache, _temp) = create_test_cache();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        // An entry written before the schema was versioned, without the filter maps
+        let data = serde_json::json!({
+            "command": {"action": "task", "content": "old task"},
+            "cached_at": now,
+            "access_count": 1,
+        });
+        cache.conn().execute(
+            "INSERT INTO nlp_responses (hash, input, response_data, cached_at, last_accessed)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![cache.hash_input("add old task"), "add old task",
+                    serde_json::to_vec(&data).unwrap(), now],
+        ).unwrap();
+
+        let cmd = cache.get("add old task").unwrap();
+        assert_eq!(cmd.content, "old task");
+        assert!(cmd.filters.is_empty());
+    }
+
     #[test]
     fn test_cache_normalization() {
         let (cache, _temp) = create_test_cache();
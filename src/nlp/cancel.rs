@@ -0,0 +1,85 @@
+//! Ctrl-C during natural language commands
+//!
+//! While a natural language command runs, the first Ctrl-C asks it to stop: a request to the
+//! provider is dropped, and compound commands stop before their next command so their changes
+//! can be rolled back. A second Ctrl-C quits right away.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use super::types::{NLPError, NLPResult};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// How often a request in flight checks for Ctrl-C
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// The exit code of a process stopped by SIGINT
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Whether Ctrl-C was pressed since the command started
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Ask the running command to stop, as Ctrl-C does
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Forget an earlier request, for the next command
+pub fn reset() {
+    REQUESTED.store(false, Ordering::SeqCst);
+}
+
+/// Turn Ctrl-C into a cancellation request for the rest of the process. Spawned on the runtime
+/// running the command.
+pub async fn listen() {
+    while tokio::signal::ctrl_c().await.is_ok() {
+        if requested() {
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        }
+        request();
+        eprintln!("Cancelling, press Ctrl-C again to quit");
+    }
+}
+
+/// Run `future` unless a cancellation is requested first, then it is dropped mid-flight
+pub async fn abortable<T>(future: impl Future<Output = NLPResult<T>>) -> NLPResult<T> {
+    abortable_by(&REQUESTED, future).await
+}
+
+pub(super) async fn abortable_by<T>(
+    flag: &AtomicBool,
+    future: impl Future<Output = NLPResult<T>>,
+) -> NLPResult<T> {
+    let cancelled = async {
+        while !flag.load(Ordering::SeqCst) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    };
+    tokio::select! {
+        result = future => result,
+        _ = cancelled => Err(NLPError::Cancelled),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_abortable() {
+        // A flag of its own, the process wide one would cancel the other tests
+        let flag = AtomicBool::new(false);
+        assert_eq!(abortable_by(&flag, async { Ok(1) }).await.unwrap(), 1);
+
+        let pending = async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        };
+        flag.store(true, Ordering::SeqCst);
+        assert!(matches!(abortable_by(&flag, pending).await, Err(NLPError::Cancelled)));
+    }
+}
@@ -2,11 +2,13 @@
 
 use super::types::*;
 use super::cache::ResponseCache;
+use super::schema::{decode_command, SCHEMA_VERSION};
 use reqwest::Client;
 use serde_json::{json, Value};
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
 use std::path::Path;
+use tracing::{debug, instrument};
 
 pub struct OpenAIClient {
     client: Client,
@@ -109,10 +111,12 @@ impl OpenAIClient {
     }
 
     /// Parse natural language input into a structured command
+    #[instrument(skip(self), fields(model = %self.config.model))]
     pub async fn parse_command(&mut self, input: &str) -> NLPResult<NLPCommand> {
         // Check persistent cache first if enabled
         if let Some(ref cache) = self.persistent_cache {
             if let Some(cached) = cache.get(input) {
+                debug!("nlp cache hit");
                 return Ok(cached);
             }
         }
@@ -139,6 +143,11 @@ impl OpenAIClient {
                 "parameters": {
                     "type": "object",
                     "properties": {
+                        "schema_version": {
+                            "type": "integer",
+                            "enum": [SCHEMA_VERSION],
+                            "description": "Version of this command schema"
+                        },
                         "action": {
                             "type": "string",
                             "enum": ["task", "record", "done", "update", "delete", "list"],
@@ -229,6 +238,8 @@ Rules:
 2. Extract relevant information like content, category, deadlines, schedules
 3. For time expressions, convert them to tascli's format:
    - Relative dates: "today", "tomorrow", "yesterday", "eom", "eoy"
+   - Quarters and fiscal year: "eoq" (end of this quarter), "q3" (end of Q3), "eofy"
+   - Next business day, skipping weekends and holidays: "nbd"
    - Relative time offsets: "+7d" (7 days from now), "+30d" (30 days from now)
    - Relative time in hours/minutes: extract the numeric value for later processing
    - Dates: "YYYY-MM-DD", "MM/DD", "MM/DD/YYYY"
@@ -297,6 +308,7 @@ Examples:
             }
         });
 
+        let started = Instant::now();
         let response = self.client
             .post(&format!("{}/responses", self.config.api_base_url))
             .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
@@ -312,6 +324,11 @@ Examples:
                     NLPError::NetworkError(e)
                 }
             })?;
+        debug!(
+            status = %response.status(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "nlp api response"
+        );
 
         if response.status() == 401 {
             return Err(NLPError::InvalidAPIKey);
@@ -355,7 +372,7 @@ Examples:
                 if let Some(function) = tool_call.get("function") {
                     if let Some("parse_task_command") = function.get("name").and_then(|n| n.as_str()) {
                         if let Some(arguments) = function.get("arguments") {
-                            let command: NLPCommand = serde_json::from_value(arguments.clone())?;
+                            let command = decode_command(arguments.clone())?;
                             // Cache the successful response
                             if let Some(ref cache) = self.persistent_cache {
                                 let _ = cache.put(input, &command);
@@ -391,6 +408,13 @@ Examples:
         conversation_summary: &[HashMap<String, String>],
         known_categories: &[String],
     ) -> NLPResult<NLPCommand> {
+        self.reserve_request().await?;
+        self.request_command(input, context_str, conversation_summary, known_categories).await
+    }
+
+    /// Count a request against the rate limit, waiting when it is reached. Only this needs
+    /// the client to itself, the requests of a parallel compound command are sent at once.
+    pub async fn reserve_request(&mut self) -> NLPResult<()> {
         if !self.config.enabled {
             return Err(NLPError::ConfigError("NLP is not enabled".to_string()));
         }
@@ -404,7 +428,18 @@ Examples:
         }
 
         self.check_rate_limit().await;
+        Ok(())
+    }
 
+    /// Send a context-aware parse request reserved by `reserve_request`
+    #[instrument(skip_all, fields(model = %self.config.model))]
+    pub async fn request_command(
+        &self,
+        input: &str,
+        context_str: &str,
+        conversation_summary: &[HashMap<String, String>],
+        known_categories: &[String],
+    ) -> NLPResult<NLPCommand> {
         // Build context-aware system prompt
         let mut system_prompt = r#"You are a task management assistant that converts natural language into structured commands for tascli CLI tool.
 
@@ -413,6 +448,8 @@ Rules:
 2. Extract relevant information like content, category, deadlines, schedules
 3. For time expressions, convert them to tascli's format:
    - Relative dates: "today", "tomorrow", "yesterday", "eom", "eoy"
+   - Quarters and fiscal year: "eoq" (end of this quarter), "q3" (end of Q3), "eofy"
+   - Next business day, skipping weekends and holidays: "nbd"
    - Relative time offsets: "+7d" (7 days from now), "+30d" (30 days from now)
    - Relative time in hours/minutes: extract the numeric value for later processing
    - Dates: "YYYY-MM-DD", "MM/DD", "MM/DD/YYYY"
@@ -484,6 +521,11 @@ For follow-up commands, use context:
                 "parameters": {
                     "type": "object",
                     "properties": {
+                        "schema_version": {
+                            "type": "integer",
+                            "enum": [SCHEMA_VERSION],
+                            "description": "Version of this command schema"
+                        },
                         "action": {
                             "type": "string",
                             "enum": ["task", "record", "done", "update", "delete", "list"],
@@ -600,6 +642,7 @@ For follow-up commands, use context:
             }
         });
 
+        let started = Instant::now();
         let response = self.client
             .post(&format!("{}/responses", self.config.api_base_url))
             .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
@@ -615,6 +658,11 @@ For follow-up commands, use context:
                     NLPError::NetworkError(e)
                 }
             })?;
+        debug!(
+            status = %response.status(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "nlp api response"
+        );
 
         if response.status() == 401 {
             return Err(NLPError::InvalidAPIKey);
@@ -658,7 +706,7 @@ For follow-up commands, use context:
                 if let Some(function) = tool_call.get("function") {
                     if let Some("parse_task_command") = function.get("name").and_then(|n| n.as_str()) {
                         if let Some(arguments) = function.get("arguments") {
-                            let command: NLPCommand = serde_json::from_value(arguments.clone())?;
+                            let command = decode_command(arguments.clone())?;
                             return Ok(command);
                         }
                     }
@@ -23,12 +23,39 @@ impl ConditionEvaluator {
         condition: &Condition,
         conn: &Connection,
         context: &SequentialContext,
+    ) -> Result<bool, String> {
+        Ok(self.explain(condition, conn, context)?.met)
+    }
+
+    /// Evaluate a condition, with the reasons for the outcome
+    pub fn explain(
+        &self,
+        condition: &Condition,
+        conn: &Connection,
+        context: &SequentialContext,
+    ) -> Result<ConditionTrace, String> {
+        let mut reasons = Vec::new();
+        let met = self.evaluate_traced(condition, conn, context, &mut reasons)?;
+        Ok(ConditionTrace { met, reasons })
+    }
+
+    // Only the expressions that decided the outcome add a reason, And and Or stop at the first
+    fn evaluate_traced(
+        &self,
+        condition: &Condition,
+        conn: &Connection,
+        context: &SequentialContext,
+        reasons: &mut Vec<String>,
     ) -> Result<bool, String> {
         match condition {
-            Condition::Single(expr) => self.evaluate_expression(expr, conn, context),
+            Condition::Single(expr) => {
+                let (met, reason) = self.evaluate_expression(expr, conn, context)?;
+                reasons.push(reason);
+                Ok(met)
+            },
             Condition::And(conditions) => {
                 for cond in conditions {
-                    if !self.evaluate(cond, conn, context)? {
+                    if !self.evaluate_traced(cond, conn, context, reasons)? {
                         return Ok(false);
                     }
                 }
@@ -36,95 +63,101 @@ impl ConditionEvaluator {
             },
             Condition::Or(conditions) => {
                 for cond in conditions {
-                    if self.evaluate(cond, conn, context)? {
+                    if self.evaluate_traced(cond, conn, context, reasons)? {
                         return Ok(true);
                     }
                 }
                 Ok(false)
             },
             Condition::Not(cond) => {
-                Ok(!self.evaluate(cond, conn, context)?)
+                Ok(!self.evaluate_traced(cond, conn, context, reasons)?)
             },
         }
     }
 
-    /// Evaluate a single condition expression
+    /// Evaluate a single condition expression, with what it found
     fn evaluate_expression(
         &self,
         expr: &ConditionExpression,
         conn: &Connection,
         context: &SequentialContext,
-    ) -> Result<bool, String> {
+    ) -> Result<(bool, String), String> {
         match expr {
             ConditionExpression::TaskExists { content } => {
-                self.task_exists(conn, content)
+                let count = self.matching_count(conn, content)?;
+                Ok((count > 0, format!("{} match '{}'", items(count), content)))
             },
 
             ConditionExpression::TaskCount { operator, value } => {
                 let count = self.get_task_count(conn)?;
-                self.compare_numbers(count, *operator, *value)
+                let met = self.compare_numbers(count, *operator, *value)?;
+                Ok((met, format!("there are {}, needs {} {}", items(count.into()), symbol(*operator), value)))
             },
 
             ConditionExpression::CategoryHasTasks { category } => {
-                self.category_has_tasks(conn, category)
+                let count = self.category_count(conn, category)?;
+                Ok((count > 0, format!("category '{}' has {}", category, items(count))))
             },
 
             ConditionExpression::CategoryEmpty { category } => {
-                Ok(!self.category_has_tasks(conn, category)?)
+                let count = self.category_count(conn, category)?;
+                Ok((count == 0, format!("category '{}' has {}", category, items(count))))
             },
 
-            ConditionExpression::PreviousSuccess => {
-                Ok(context.previous_results
-                    .last()
-                    .map_or(false, |r| r.success))
-            },
-
-            ConditionExpression::PreviousFailed => {
-                Ok(context.previous_results
-                    .last()
-                    .map_or(false, |r| !r.success))
+            ConditionExpression::PreviousSuccess | ConditionExpression::PreviousFailed => {
+                let Some(previous) = context.previous_results.last() else {
+                    return Ok((false, "there is no previous command".to_string()));
+                };
+                let met = previous.success == matches!(expr, ConditionExpression::PreviousSuccess);
+                let outcome = if previous.success { "succeeded" } else { "failed" };
+                Ok((met, format!("the previous command {}", outcome)))
             },
 
             ConditionExpression::TimeCondition { operator, hour, minute } => {
-                let now = chrono::Local::now();
+                let now = crate::clock::now();
                 let current_hour = now.hour() as i32;
                 let current_min = now.minute() as i32;
 
                 let current_value = current_hour * 60 + current_min;
                 let target_value = hour.unwrap_or(0) * 60 + minute.unwrap_or(0);
 
-                self.compare_numbers(current_value, *operator, target_value)
+                let met = self.compare_numbers(current_value, *operator, target_value)?;
+                Ok((met, format!(
+                    "it is {:02}:{:02}, needs {} {:02}:{:02}",
+                    current_hour, current_min, symbol(*operator), hour.unwrap_or(0), minute.unwrap_or(0)
+                )))
             },
 
             ConditionExpression::DayOfWeek { days } => {
-                let now = chrono::Local::now();
-                let current_day = now.format("%A").to_string().to_lowercase();
-                Ok(days.iter()
-                    .any(|d| d.to_lowercase() == current_day))
+                let current_day = crate::clock::now().format("%A").to_string().to_lowercase();
+                let met = days.iter()
+                    .any(|d| d.to_lowercase() == current_day);
+                Ok((met, format!("today is {}, needs {}", current_day, days.join(" or ").to_lowercase())))
             },
 
             ConditionExpression::VariableEquals { name, value } => {
-                Ok(context.get_var(name)
-                    .map_or(false, |v| v == value))
+                Ok(match context.get_var(name) {
+                    Some(v) => (v == value, format!("{} is '{}', needs '{}'", name, v, value)),
+                    None => (false, format!("{} is not set", name)),
+                })
             },
 
             ConditionExpression::VariableExists { name } => {
-                Ok(context.get_var(name).is_some())
+                let set = context.get_var(name).is_some();
+                Ok((set, format!("{} is {}", name, if set { "set" } else { "not set" })))
             },
         }
     }
 
-    /// Check if a task exists matching the content
-    fn task_exists(&self, conn: &Connection, content: &str) -> Result<bool, String> {
+    /// Count the items whose content matches
+    fn matching_count(&self, conn: &Connection, content: &str) -> Result<i64, String> {
         let pattern = format!("%{}%", content);
 
-        let count: i64 = conn.query_row(
+        conn.query_row(
             "SELECT COUNT(*) FROM items WHERE content LIKE ? AND status != 'cancelled'",
             [&pattern],
             |row| row.get(0),
-        ).map_err(|e| format!("Database error: {}", e))?;
-
-        Ok(count > 0)
+        ).map_err(|e| format!("Database error: {}", e))
     }
 
     /// Get the total count of non-cancelled tasks
@@ -138,15 +171,13 @@ impl ConditionEvaluator {
         Ok(count as i32)
     }
 
-    /// Check if a category has any tasks
-    fn category_has_tasks(&self, conn: &Connection, category: &str) -> Result<bool, String> {
-        let count: i64 = conn.query_row(
+    /// Count the items of a category
+    fn category_count(&self, conn: &Connection, category: &str) -> Result<i64, String> {
+        conn.query_row(
             "SELECT COUNT(*) FROM items WHERE category = ? AND status != 'cancelled'",
             [category],
             |row| row.get(0),
-        ).map_err(|e| format!("Database error: {}", e))?;
-
-        Ok(count > 0)
+        ).map_err(|e| format!("Database error: {}", e))
     }
 
     /// Compare two numbers using the given operator
@@ -162,6 +193,30 @@ impl ConditionEvaluator {
     }
 }
 
+fn items(count: i64) -> String {
+    if count == 1 { "1 item".to_string() } else { format!("{} items", count) }
+}
+
+fn symbol(operator: ComparisonOperator) -> &'static str {
+    match operator {
+        ComparisonOperator::Equal => "=",
+        ComparisonOperator::NotEqual => "!=",
+        ComparisonOperator::GreaterThan => ">",
+        ComparisonOperator::LessThan => "<",
+        ComparisonOperator::GreaterOrEqual => ">=",
+        ComparisonOperator::LessOrEqual => "<=",
+    }
+}
+
+/// The outcome of a condition and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionTrace {
+    /// Whether the condition holds
+    pub met: bool,
+    /// What the deciding expressions found, such as "category 'work' has 0 items"
+    pub reasons: Vec<String>,
+}
+
 impl Default for ConditionEvaluator {
     fn default() -> Self {
         Self::new()
@@ -305,6 +360,39 @@ impl ConditionalExecutor {
         })
     }
 
+    /// What running a command and its compound commands would do, without running any.
+    /// Commands that would run are taken to succeed, for conditions on the previous one.
+    pub fn simulate(
+        &self,
+        conn: &Connection,
+        command: &NLPCommand,
+    ) -> Result<Vec<SimulatedCommand>, String> {
+        use super::mapper::CommandMapper;
+        let mut context = SequentialContext::default();
+        let mut simulated = Vec::new();
+        let commands = std::iter::once(command).chain(command.compound_commands.iter().flatten());
+        for (index, command) in commands.enumerate() {
+            let condition = command.condition.as_ref()
+                .map(|condition| self.evaluator.explain(condition, conn, &context))
+                .transpose()?;
+            let runs = condition.as_ref().is_none_or(|trace| trace.met);
+            if runs {
+                context.update_with_result(&CommandExecutionResult {
+                    index,
+                    success: true,
+                    error: None,
+                    output: Some(self.extract_output(command)),
+                });
+            }
+            simulated.push(SimulatedCommand {
+                args: CommandMapper::to_tascli_args(command),
+                condition,
+                runs,
+            });
+        }
+        Ok(simulated)
+    }
+
     /// Execute a single command
     fn execute_single(
         &self,
@@ -345,6 +433,17 @@ pub struct ConditionalExecutionResult {
     pub output: Option<CommandOutput>,
 }
 
+/// A command as a simulation would run it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedCommand {
+    /// The tascli arguments of the command
+    pub args: Vec<String>,
+    /// Its condition, None when it has none
+    pub condition: Option<ConditionTrace>,
+    /// Whether it would run
+    pub runs: bool,
+}
+
 /// Result of branch execution
 #[derive(Debug, Clone)]
 pub struct BranchExecutionResult {
@@ -379,7 +478,7 @@ fn execute_parsed_command(conn: &Connection, args: &[String]) -> Result<(), Stri
     let parsed_args = CliArgs::try_parse_from(cmd_args)
         .map_err(|e| format!("Failed to parse generated command: {}", e))?;
 
-    crate::actions::handler::handle_commands(conn, parsed_args)
+    crate::actions::handler::handle_commands(conn, parsed_args).map_err(|e| e.to_string())
 }
 
 /// Helper functions for building conditions
@@ -708,4 +807,54 @@ mod tests {
         assert!(!result.condition_met);
         assert!(result.executed_commands.is_empty());
     }
+
+    #[test]
+    fn test_simulate() {
+        let (conn, _temp) = crate::tests::get_test_conn();
+        crate::tests::insert_task(&conn, "work", "review", "today");
+
+        let evaluator = ConditionEvaluator::new();
+        let context = SequentialContext::default();
+        let trace = evaluator
+            .explain(&ConditionBuilder::category_empty("work"), &conn, &context)
+            .unwrap();
+        assert!(!trace.met);
+        assert_eq!(trace.reasons, vec!["category 'work' has 1 item".to_string()]);
+
+        // Or stops at the first condition that holds
+        let either = ConditionBuilder::or(vec![
+            ConditionBuilder::category_empty("home"),
+            ConditionBuilder::category_empty("work"),
+        ]);
+        let trace = evaluator.explain(&either, &conn, &context).unwrap();
+        assert!(trace.met);
+        assert_eq!(trace.reasons, vec!["category 'home' has 0 items".to_string()]);
+
+        // The second command runs when the first would have
+        let command = NLPCommand {
+            action: ActionType::Task,
+            content: "plan next sprint".to_string(),
+            condition: Some(ConditionBuilder::category_empty("home")),
+            compound_commands: Some(vec![NLPCommand {
+                action: ActionType::List,
+                condition: Some(ConditionBuilder::previous_success()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        let simulated = ConditionalExecutor::new(false).simulate(&conn, &command).unwrap();
+        assert_eq!(simulated.len(), 2);
+        assert!(simulated[0].runs);
+        assert_eq!(simulated[0].args[..2], ["task".to_string(), "plan next sprint".to_string()]);
+        assert!(simulated[1].runs);
+        assert_eq!(
+            simulated[1].condition.as_ref().unwrap().reasons,
+            vec!["the previous command succeeded".to_string()]
+        );
+
+        // Nothing was added
+        assert!(evaluator.explain(&ConditionBuilder::category_empty("home"), &conn, &context)
+            .unwrap()
+            .met);
+    }
 }
@@ -3,10 +3,11 @@
 use super::types::*;
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::LazyLock;
 use regex::Regex;
-use chrono::Datelike;
-use chrono::Timelike;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Timelike};
 use std::fmt;
+use crate::{clock, config::holidays};
 
 /// Context information about previous commands and state
 #[derive(Debug, Clone)]
@@ -19,6 +20,8 @@ pub struct CommandContext {
     pub last_content: Option<String>,
     /// Known categories from the database
     pub known_categories: Vec<String>,
+    /// Tags in use in the database
+    pub known_tags: Vec<String>,
     /// Recent task contents
     pub recent_tasks: Vec<String>,
     /// Maximum history size
@@ -43,6 +46,7 @@ impl Default for CommandContext {
             last_category: None,
             last_content: None,
             known_categories: Vec::new(),
+            known_tags: Vec::new(),
             recent_tasks: Vec::new(),
             max_history_size: 50,
         }
@@ -117,6 +121,11 @@ impl CommandContext {
         self.known_categories = categories;
     }
 
+    /// Update known tags
+    pub fn update_tags(&mut self, tags: Vec<String>) {
+        self.known_tags = tags;
+    }
+
     /// Clear old history entries (older than specified seconds)
     pub fn clear_old_entries(&mut self, max_age_seconds: i64) {
         let now = SystemTime::now()
@@ -213,35 +222,54 @@ impl TimeContext {
 
     /// Get current timestamp
     pub fn now(&self) -> i64 {
-        self.current_time.unwrap_or_else(|| {
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64
-        })
+        self.current_time.unwrap_or_else(clock::timestamp)
+    }
+
+    /// Timezone the calendar math is done in, UTC unless an offset is set
+    fn offset(&self) -> FixedOffset {
+        self.timezone_offset
+            .and_then(FixedOffset::east_opt)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// Convert a timestamp to a date and time in this context's timezone
+    pub fn to_datetime(&self, ts: i64) -> DateTime<FixedOffset> {
+        let utc = DateTime::from_timestamp(ts, 0).unwrap_or_default();
+        utc.with_timezone(&self.offset())
+    }
+
+    /// Get the current date
+    pub fn today(&self) -> NaiveDate {
+        self.to_datetime(self.now()).date_naive()
+    }
+
+    /// Get the timestamp of midnight at the start of a date
+    fn start_of(&self, date: NaiveDate) -> i64 {
+        // A fixed offset has no gaps or overlaps, midnight always exists
+        self.offset()
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .unwrap()
+            .timestamp()
     }
 
     /// Get start of today (midnight)
     pub fn start_of_today(&self) -> i64 {
-        let now = self.now();
-        let day_in_seconds = 86400;
-        (now / day_in_seconds) * day_in_seconds
+        self.start_of(self.today())
     }
 
     /// Get start of tomorrow
     pub fn start_of_tomorrow(&self) -> i64 {
-        self.start_of_today() + 86400
+        self.start_of(self.today() + Duration::days(1))
     }
 
     /// Get start of yesterday
     pub fn start_of_yesterday(&self) -> i64 {
-        self.start_of_today() - 86400
+        self.start_of(self.today() - Duration::days(1))
     }
 
     /// Get day of week (0 = Sunday, 1 = Monday, etc.)
     pub fn day_of_week(&self) -> u8 {
-        let now = self.now();
-        ((now / 86400 + 4) % 7) as u8 // Unix epoch was Thursday
+        self.today().weekday().num_days_from_sunday() as u8
     }
 
     /// Get days until a specific weekday (0 = Sunday, 1 = Monday, etc.)
@@ -256,45 +284,25 @@ impl TimeContext {
 
     /// Get start of next Monday
     pub fn next_monday(&self) -> i64 {
-        self.start_of_today() + self.days_until_weekday(1) * 86400
+        self.start_of(self.today() + Duration::days(self.days_until_weekday(1)))
     }
 
     /// Get timestamp for "this week" (start of week, assuming Monday)
     pub fn start_of_week(&self) -> i64 {
-        self.start_of_today() - ((self.day_of_week() + 6) % 7) as i64 * 86400
+        let today = self.today();
+        self.start_of(today - Duration::days(today.weekday().num_days_from_monday() as i64))
     }
 
-    /// Get timestamp for "end of month"
+    /// Get timestamp for "end of month", the last second of its last day
     pub fn end_of_month(&self) -> i64 {
-        let now = self.now();
-        // Get days in current month (simplified - doesn't handle leap years perfectly)
-        let days_in_month = match Self::month_from_timestamp(now) {
-            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
-            4 | 6 | 9 | 11 => 30,
-            2 => 28, // Simplified
-            _ => 30,
-        };
-        let start_of_month = (now / (86400 * 30)) * (86400 * 30);
-        start_of_month + days_in_month as i64 * 86400 - 1
-    }
-
-    /// Get month from timestamp (1-12)
-    fn month_from_timestamp(ts: i64) -> u32 {
-        let days_since_epoch = ts / 86400;
-        let years_since_epoch = days_since_epoch / 365;
-        let day_of_year = (days_since_epoch % 365) as u32;
-        // Simplified - actual implementation would account for leap years
-        let days_per_month = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-        let mut day_count = 0;
-        let mut month = 0;
-        for (i, &days) in days_per_month.iter().enumerate() {
-            day_count += days;
-            if day_of_year < day_count {
-                month = i as u32 + 1;
-                break;
-            }
+        let today = self.today();
+        let first_of_next_month = if today.month() == 12 {
+            NaiveDate::from_ymd_opt(today.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(today.year(), today.month() + 1, 1)
         }
-        month
+        .unwrap();
+        self.start_of(first_of_next_month) - 1
     }
 }
 
@@ -341,6 +349,24 @@ impl FuzzyMatcher {
         best_match
     }
 
+    /// Find the known tag meant by a tag, written with or without + or #
+    pub fn match_tag(input: &str, known_tags: &[String]) -> Option<String> {
+        let input_lower = input.trim_start_matches(['+', '#']).to_lowercase();
+        if input_lower.is_empty() {
+            return None;
+        }
+        if let Some(tag) = known_tags.iter().find(|tag| tag.to_lowercase() == input_lower) {
+            return Some(tag.clone());
+        }
+        // Tags are short, a contained one is not taken for the one meant
+        known_tags
+            .iter()
+            .map(|tag| (tag, Self::similarity_score(&input_lower, &tag.to_lowercase())))
+            .filter(|(_, score)| *score >= 0.6)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(tag, _)| tag.clone())
+    }
+
     /// Find matching task content using fuzzy matching
     pub fn match_task(input: &str, known_tasks: &[String]) -> Option<String> {
         let input_lower = input.to_lowercase();
@@ -497,6 +523,95 @@ pub enum DeadlineSource {
     Default,
 }
 
+// === Deadline Inference Patterns ===
+// Compiled once, deadline inference runs on every NLP input.
+
+fn compile_all(patterns: &[&str]) -> Vec<Regex> {
+    patterns.iter().map(|p| Regex::new(p).unwrap()).collect()
+}
+
+// "by friday", "due tomorrow", "deadline is monday", ...
+static EXPLICIT_DEADLINE_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile_all(&[
+        r"(?i)by\s+(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod|eow|eom|eoq|eoy|eofy)",
+        r"(?i)(?:by|due)\s+(?:the\s+)?(next\s+business\s+day)\b",
+        r"(?i)(?:by|due|before)\s+(?:the\s+)?end\s+of\s+(?:the\s+)?(q[1-4]|quarter|fiscal\s+year|month|year)\b",
+        r"(?i)due\s+(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
+        r"(?i)deadline\s+(?:is\s+)?(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
+        r"(?i)before\s+(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod)",
+        r"(?i)on\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
+        r"(?i)next\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
+        r"(?i)for\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
+    ])
+});
+
+// "by 5PM", "due at 3:30", "deadline at 9am"
+static EXPLICIT_TIME_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile_all(&[
+        r"(?i)by\s+(\d{1,2}(?::\d{2})?(?:\s*(?:am|pm|a\.m\.|p\.m\.))?)",
+        r"(?i)due\s+(?:at\s+)?(\d{1,2}(?::\d{2})?(?:\s*(?:am|pm|a\.m\.|p\.m\.))?)",
+        r"(?i)deadline\s+(?:at\s+)?(\d{1,2}(?::\d{2})?(?:\s*(?:am|pm|a\.m\.|p\.m\.))?)",
+    ])
+});
+
+// "in X time_unit", with the seconds per unit
+static RELATIVE_TIME_RES: LazyLock<Vec<(Regex, i64)>> = LazyLock::new(|| {
+    [
+        (r"in\s+(\d+)\s+seconds?", 1),
+        (r"in\s+(\d+)\s+minutes?", 60),
+        (r"in\s+(\d+)\s+hours?", 3600),
+        (r"in\s+(\d+)\s+days?", 86400),
+        (r"in\s+(\d+)\s+weeks?", 604800),
+    ]
+    .into_iter()
+    .map(|(p, seconds)| (Regex::new(p).unwrap(), seconds))
+    .collect()
+});
+
+// "next week"/"next month" as tascli-compatible +Xd offsets
+static NEXT_PERIOD_RES: LazyLock<Vec<(Regex, &str)>> = LazyLock::new(|| {
+    [
+        (r"next\s+week", "+7d"),
+        (r"next\s+month", "+30d"),
+        (r"next\s+year", "+365d"),
+    ]
+    .into_iter()
+    .map(|(p, deadline)| (Regex::new(p).unwrap(), deadline))
+    .collect()
+});
+
+// Urgency words, with the deadline and confidence they imply
+static URGENCY_RES: LazyLock<Vec<(Regex, &str, f64)>> = LazyLock::new(|| {
+    [
+        (r"(?i)\burgent(?:ly)?\b", "today", 0.70),
+        (r"(?i)\basap\b|\bas soon as possible\b", "today", 0.65),
+        (r"(?i)\bimmediately\b|\bright now\b", "today", 0.75),
+        (r"(?i)\bsoon\b", "tomorrow", 0.50),
+        (r"(?i)\bthis week\b", "eow", 0.60),
+        (r"(?i)\boverdue\b", "yesterday", 0.80),
+    ]
+    .into_iter()
+    .map(|(p, deadline, confidence)| (Regex::new(p).unwrap(), deadline, confidence))
+    .collect()
+});
+
+static AMBIGUOUS_DEADLINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\blater\b|\bsometime\b|\beventually\b|\bsomeday\b").unwrap()
+});
+
+static TIME_PHRASE_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile_all(&[
+        r"(?i)\bin\s+\d+\s+(?:seconds?|minutes?|hours?|days?|weeks?|months?|years?)\b",
+        r"(?i)\bby\s+(?:today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod|eow|eoq|eofy)\b",
+        r"(?i)\bend\s+of\s+(?:the\s+)?(?:q[1-4]|quarter|fiscal\s+year)\b",
+        r"(?i)\bdue\s+(?:today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday)\b",
+        r"(?i)\bnext\s+(?:week|month|year|monday|tuesday|wednesday|thursday|friday|saturday|sunday)\b",
+        r"(?i)\bthis\s+(?:week|weekend|month)\b",
+        r"(?i)\bat\s+\d{1,2}(?::\d{2})?\s*(?:am|pm)?\b",
+        r"(?i)\b\d{1,2}:\d{2}\s*(?:am|pm)?\b",
+    ])
+});
+
 /// Intelligent deadline inference from natural language
 pub struct DeadlineInference;
 
@@ -533,50 +648,26 @@ impl DeadlineInference {
     /// Infer explicit deadlines (mentioned dates/times)
     fn infer_explicit_deadline(input: &str, _time_context: &TimeContext) -> Option<InferredDeadline> {
         // Common deadline indicator words (case-insensitive)
-        let deadline_patterns = [
-            r"(?i)by\s+(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod|eow|eom|eoy)",
-            r"(?i)due\s+(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
-            r"(?i)deadline\s+(?:is\s+)?(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
-            r"(?i)before\s+(today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod)",
-            r"(?i)on\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
-            r"(?i)next\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
-            r"(?i)for\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)",
-        ];
-
-        for pattern in &deadline_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if let Some(caps) = re.captures(input) {
-                    if let Some(match_str) = caps.get(1) {
-                        return Some(InferredDeadline {
-                            deadline: Self::normalize_deadline_keyword(match_str.as_str()),
-                            confidence: 0.95,
-                            is_explicit: true,
-                            source: DeadlineSource::Explicit,
-                        });
-                    }
-                }
+        for re in EXPLICIT_DEADLINE_RES.iter() {
+            if let Some(match_str) = re.captures(input).and_then(|caps| caps.get(1)) {
+                return Some(InferredDeadline {
+                    deadline: Self::normalize_deadline_keyword(match_str.as_str()),
+                    confidence: 0.95,
+                    is_explicit: true,
+                    source: DeadlineSource::Explicit,
+                });
             }
         }
 
         // Check for time-specific deadlines (e.g., "by 5PM", "due at 3:30")
-        let time_patterns = [
-            r"(?i)by\s+(\d{1,2}(?::\d{2})?(?:\s*(?:am|pm|a\.m\.|p\.m\.))?)",
-            r"(?i)due\s+(?:at\s+)?(\d{1,2}(?::\d{2})?(?:\s*(?:am|pm|a\.m\.|p\.m\.))?)",
-            r"(?i)deadline\s+(?:at\s+)?(\d{1,2}(?::\d{2})?(?:\s*(?:am|pm|a\.m\.|p\.m\.))?)",
-        ];
-
-        for pattern in &time_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if let Some(caps) = re.captures(input) {
-                    if let Some(match_str) = caps.get(1) {
-                        return Some(InferredDeadline {
-                            deadline: format!("today {}", match_str.as_str()),
-                            confidence: 0.90,
-                            is_explicit: true,
-                            source: DeadlineSource::Explicit,
-                        });
-                    }
-                }
+        for re in EXPLICIT_TIME_RES.iter() {
+            if let Some(match_str) = re.captures(input).and_then(|caps| caps.get(1)) {
+                return Some(InferredDeadline {
+                    deadline: format!("today {}", Self::normalize_clock_time(match_str.as_str())),
+                    confidence: 0.90,
+                    is_explicit: true,
+                    source: DeadlineSource::Explicit,
+                });
             }
         }
 
@@ -586,49 +677,31 @@ impl DeadlineInference {
     /// Infer relative time deadlines (e.g., "in 2 hours", "next week")
     fn infer_relative_deadline(input: &str, time_context: &TimeContext) -> Option<InferredDeadline> {
         // "in X time_unit" patterns
-        let relative_patterns = [
-            (r"in\s+(\d+)\s+seconds?", 1, "second"),
-            (r"in\s+(\d+)\s+minutes?", 60, "minute"),
-            (r"in\s+(\d+)\s+hours?", 3600, "hour"),
-            (r"in\s+(\d+)\s+days?", 86400, "day"),
-            (r"in\s+(\d+)\s+weeks?", 604800, "week"),
-        ];
-
-        for (pattern, seconds_per_unit, unit_name) in &relative_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if let Some(caps) = re.captures(input) {
-                    if let Some(match_str) = caps.get(1) {
-                        if let Ok(amount) = match_str.as_str().parse::<i64>() {
-                            let total_seconds = amount * seconds_per_unit;
-                            return Some(InferredDeadline {
-                                deadline: Self::format_relative_deadline(total_seconds, time_context),
-                                confidence: 0.85,
-                                is_explicit: true,
-                                source: DeadlineSource::RelativeTime,
-                            });
-                        }
-                    }
-                }
+        for (re, seconds_per_unit) in RELATIVE_TIME_RES.iter() {
+            let amount = re
+                .captures(input)
+                .and_then(|caps| caps.get(1))
+                .and_then(|m| m.as_str().parse::<i64>().ok());
+            if let Some(amount) = amount {
+                let total_seconds = amount * seconds_per_unit;
+                return Some(InferredDeadline {
+                    deadline: Self::format_relative_deadline(total_seconds, time_context),
+                    confidence: 0.85,
+                    is_explicit: true,
+                    source: DeadlineSource::RelativeTime,
+                });
             }
         }
 
         // "next week"/"next month" patterns - use tascli-compatible +Xd format
-        let next_patterns = [
-            (r"next\s+week", "+7d"),
-            (r"next\s+month", "+30d"),
-            (r"next\s+year", "+365d"),
-        ];
-
-        for (pattern, description) in &next_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if re.is_match(input) {
-                    return Some(InferredDeadline {
-                        deadline: description.to_string(),
-                        confidence: 0.80,
-                        is_explicit: true,
-                        source: DeadlineSource::RelativeTime,
-                    });
-                }
+        for (re, description) in NEXT_PERIOD_RES.iter() {
+            if re.is_match(input) {
+                return Some(InferredDeadline {
+                    deadline: description.to_string(),
+                    confidence: 0.80,
+                    is_explicit: true,
+                    source: DeadlineSource::RelativeTime,
+                });
             }
         }
 
@@ -637,25 +710,14 @@ impl DeadlineInference {
 
     /// Infer deadline from urgency indicators
     fn infer_urgency_deadline(input: &str, _time_context: &TimeContext) -> Option<InferredDeadline> {
-        let urgency_mappings: [(&str, &str, f64); 6] = [
-            (r"(?i)\burgent(?:ly)?\b", "today", 0.70),
-            (r"(?i)\basap\b|\bas soon as possible\b", "today", 0.65),
-            (r"(?i)\bimmediately\b|\bright now\b", "today", 0.75),
-            (r"(?i)\bsoon\b", "tomorrow", 0.50),
-            (r"(?i)\bthis week\b", "eow", 0.60),
-            (r"(?i)\boverdue\b", "yesterday", 0.80),
-        ];
-
-        for (pattern, deadline, confidence) in &urgency_mappings {
-            if let Ok(re) = Regex::new(pattern) {
-                if re.is_match(input) {
-                    return Some(InferredDeadline {
-                        deadline: deadline.to_string(),
-                        confidence: *confidence,
-                        is_explicit: false,
-                        source: DeadlineSource::Urgency,
-                    });
-                }
+        for (re, deadline, confidence) in URGENCY_RES.iter() {
+            if re.is_match(input) {
+                return Some(InferredDeadline {
+                    deadline: deadline.to_string(),
+                    confidence: *confidence,
+                    is_explicit: false,
+                    source: DeadlineSource::Urgency,
+                });
             }
         }
 
@@ -680,11 +742,11 @@ impl DeadlineInference {
 
             // Personal (often more flexible)
             ("personal", "week", 0.30),
-            ("errand", "weekend", 0.40),
+            ("errand", "saturday", 0.40),
 
             // Shopping/chores (often this week)
             ("shopping", "week", 0.35),
-            ("chore", "weekend", 0.35),
+            ("chore", "saturday", 0.35),
 
             // Learning/reading (often longer term)
             ("learning", "month", 0.30),
@@ -712,10 +774,23 @@ impl DeadlineInference {
             "eow" | "week" => "sunday".to_string(),
             "eom" => "month".to_string(),
             "eoy" => "year".to_string(),
+            "quarter" => "eoq".to_string(),
+            fiscal if fiscal.starts_with("fiscal") => "eofy".to_string(),
+            business if business.starts_with("next") => "nbd".to_string(),
             other => other.to_string(),
         }
     }
 
+    /// Normalize a clock time to one the time parser reads, "5 p.m." as "5pm" and "9" as "9:00"
+    fn normalize_clock_time(time: &str) -> String {
+        let time: String = time.chars().filter(|c| !c.is_whitespace() && *c != '.').collect();
+        if time.contains(':') || time.ends_with('m') {
+            time
+        } else {
+            format!("{}:00", time)
+        }
+    }
+
     /// Format relative deadline as tascli-compatible time string
     fn format_relative_deadline(seconds: i64, time_context: &TimeContext) -> String {
         let minutes = seconds / 60;
@@ -724,34 +799,34 @@ impl DeadlineInference {
 
         // For days and longer, use +Xd format
         if days > 0 {
-            format!("+{}d", days)
-        } else if hours > 0 {
-            // For hours, calculate the actual time and return HH:MM format
-            let future_timestamp = time_context.now() + seconds;
-            let future_datetime = chrono::DateTime::from_timestamp(future_timestamp, 0)
-                .unwrap()
-                .naive_local();
-            format!("{:02}:{:02}",
+            return format!("+{}d", days);
+        }
+
+        // Less than a day ahead, which can still pass midnight
+        let future_datetime = time_context.to_datetime(time_context.now() + seconds);
+        let day = if future_datetime.date_naive() == time_context.today() {
+            "today"
+        } else {
+            "tomorrow"
+        };
+        if hours > 0 {
+            // For hours, return HH:MM format, a bare time is today
+            let time = format!("{:02}:{:02}",
                 future_datetime.hour(),
                 future_datetime.minute()
-            )
+            );
+            if day == "today" { time } else { format!("{} {}", day, time) }
         } else if minutes > 0 {
             // For minutes, return time in "today HH:MM" format
-            let future_timestamp = time_context.now() + seconds;
-            let future_datetime = chrono::DateTime::from_timestamp(future_timestamp, 0)
-                .unwrap()
-                .naive_local();
-            format!("today {:02}:{:02}",
+            format!("{} {:02}:{:02}",
+                day,
                 future_datetime.hour(),
                 future_datetime.minute()
             )
         } else {
             // For seconds, use "today HH:MM:SS" format
-            let future_timestamp = time_context.now() + seconds;
-            let future_datetime = chrono::DateTime::from_timestamp(future_timestamp, 0)
-                .unwrap()
-                .naive_local();
-            format!("today {:02}:{:02}:{:02}",
+            format!("{} {:02}:{:02}:{:02}",
+                day,
                 future_datetime.hour(),
                 future_datetime.minute(),
                 future_datetime.second()
@@ -768,20 +843,14 @@ impl DeadlineInference {
         }
     }
 
-    /// Calculate business days from now (skips weekends)
+    /// Calculate business days from now (skips weekends and configured holidays)
     pub fn add_business_days(days: u32, time_context: &TimeContext) -> String {
-        let mut current_day = time_context.day_of_week();
-        let mut days_to_add = days as i64;
-        let mut total_days = 0;
-
-        while days_to_add > 0 {
-            total_days += 1;
-            current_day = (current_day + 1) % 7;
-            // Skip weekends (0 = Sunday, 6 = Saturday)
-            if current_day != 0 && current_day != 6 {
-                days_to_add -= 1;
-            }
+        let today = time_context.today();
+        let mut date = today;
+        for _ in 0..days {
+            date = holidays::next_business_day(date);
         }
+        let total_days = (date - today).num_days();
 
         if total_days == 1 {
             "tomorrow".to_string()
@@ -792,22 +861,7 @@ impl DeadlineInference {
 
     /// Check if a deadline expression is ambiguous
     pub fn is_ambiguous_deadline(input: &str) -> bool {
-        let ambiguous_patterns = [
-            r"\blater\b",
-            r"\bsometime\b",
-            r"\beventually\b",
-            r"\bsomeday\b",
-        ];
-
-        for pattern in &ambiguous_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if re.is_match(input) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        AMBIGUOUS_DEADLINE_RE.is_match(input)
     }
 
     /// Suggest clarification for ambiguous deadlines
@@ -827,23 +881,9 @@ impl DeadlineInference {
     pub fn extract_time_phrases(input: &str) -> Vec<String> {
         let mut phrases = Vec::new();
 
-        let time_patterns = [
-            r"(?i)\bin\s+\d+\s+(?:seconds?|minutes?|hours?|days?|weeks?|months?|years?)\b",
-            r"(?i)\bby\s+(?:today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod|eow)\b",
-            r"(?i)\bdue\s+(?:today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday)\b",
-            r"(?i)\bnext\s+(?:week|month|year|monday|tuesday|wednesday|thursday|friday|saturday|sunday)\b",
-            r"(?i)\bthis\s+(?:week|weekend|month)\b",
-            r"(?i)\bat\s+\d{1,2}(?::\d{2})?\s*(?:am|pm)?\b",
-            r"(?i)\b\d{1,2}:\d{2}\s*(?:am|pm)?\b",
-        ];
-
-        for pattern in &time_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                for caps in re.captures_iter(input) {
-                    if let Some(match_str) = caps.get(0) {
-                        phrases.push(match_str.as_str().to_string());
-                    }
-                }
+        for re in TIME_PHRASE_RES.iter() {
+            for m in re.find_iter(input) {
+                phrases.push(m.as_str().to_string());
             }
         }
 
@@ -1115,7 +1155,17 @@ impl fmt::Display for Disambiguation {
 
 #[cfg(test)]
 mod tests {
+    use chrono::{
+        NaiveDateTime,
+        Weekday,
+    };
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::args::timestr::parse_timestr_at;
+
+    // Thursday 2026/10/15 at noon UTC
+    const THURSDAY_NOON: i64 = 1_792_065_600;
 
     // === CommandContext Tests ===
 
@@ -1363,6 +1413,50 @@ mod tests {
         assert_eq!(next_monday, 86400 * 4);
     }
 
+    #[test]
+    fn test_end_of_month() {
+        let at = |y, m, d| {
+            chrono::Utc
+                .with_ymd_and_hms(y, m, d, 12, 0, 0)
+                .unwrap()
+                .timestamp()
+        };
+        let last_second = |y, m, d| {
+            chrono::Utc
+                .with_ymd_and_hms(y, m, d, 23, 59, 59)
+                .unwrap()
+                .timestamp()
+        };
+        // Leap and common years
+        assert_eq!(TimeContext::with_time(at(2024, 2, 10)).end_of_month(), last_second(2024, 2, 29));
+        assert_eq!(TimeContext::with_time(at(2025, 2, 28)).end_of_month(), last_second(2025, 2, 28));
+        // Month boundaries
+        assert_eq!(TimeContext::with_time(at(2025, 1, 31)).end_of_month(), last_second(2025, 1, 31));
+        assert_eq!(TimeContext::with_time(at(2025, 4, 1)).end_of_month(), last_second(2025, 4, 30));
+        assert_eq!(TimeContext::with_time(at(2025, 12, 31)).end_of_month(), last_second(2025, 12, 31));
+    }
+
+    #[test]
+    fn test_start_of_week() {
+        // Wednesday 2025/01/01, the week started on Monday 2024/12/30
+        let context = TimeContext::with_time(chrono::Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap().timestamp());
+        assert_eq!(context.start_of_week(), chrono::Utc.with_ymd_and_hms(2024, 12, 30, 0, 0, 0).unwrap().timestamp());
+        assert_eq!(context.day_of_week(), 3);
+        assert_eq!(context.next_monday(), chrono::Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap().timestamp());
+    }
+
+    #[test]
+    fn test_timezone_offset() {
+        // 2025/03/01 02:00 UTC is still February 28 at UTC-5
+        let context = TimeContext {
+            current_time: Some(chrono::Utc.with_ymd_and_hms(2025, 3, 1, 2, 0, 0).unwrap().timestamp()),
+            timezone_offset: Some(-5 * 3600),
+        };
+        assert_eq!(context.today(), NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
+        assert_eq!(context.start_of_today(), chrono::Utc.with_ymd_and_hms(2025, 2, 28, 5, 0, 0).unwrap().timestamp());
+        assert_eq!(context.end_of_month(), chrono::Utc.with_ymd_and_hms(2025, 3, 1, 4, 59, 59).unwrap().timestamp());
+    }
+
     // === FuzzyMatcher Tests ===
 
     #[test]
@@ -1394,6 +1488,16 @@ mod tests {
         assert_eq!(match_result, Some("work".to_string()));
     }
 
+    #[test]
+    fn test_match_tag() {
+        let tags = vec!["errands".to_string(), "quick".to_string()];
+        assert_eq!(FuzzyMatcher::match_tag("#Errands", &tags), Some("errands".to_string()));
+        assert_eq!(FuzzyMatcher::match_tag("errand", &tags), Some("errands".to_string()));
+        assert_eq!(FuzzyMatcher::match_tag("qick", &tags), Some("quick".to_string()));
+        assert_eq!(FuzzyMatcher::match_tag("home", &tags), None);
+        assert_eq!(FuzzyMatcher::match_tag("+", &tags), None);
+    }
+
     #[test]
     fn test_match_category_no_match() {
         let categories = vec!["work".to_string(), "personal".to_string()];
@@ -1561,6 +1665,18 @@ mod tests {
         assert_eq!(inferred.deadline, "sunday"); // eow normalizes to sunday
     }
 
+    #[test]
+    fn test_infer_explicit_deadline_end_of_quarter() {
+        let context = TimeContext::default();
+        let deadline = |input| DeadlineInference::infer_explicit_deadline(input, &context).unwrap().deadline;
+        assert_eq!(deadline("ship the report by eoq"), "eoq");
+        assert_eq!(deadline("budget due by the end of Q3"), "q3");
+        assert_eq!(deadline("close the books before the end of the fiscal year"), "eofy");
+        assert_eq!(deadline("audit by end of quarter"), "eoq");
+        assert_eq!(deadline("invoices by end of month"), "month");
+        assert_eq!(deadline("reply to legal by next business day"), "nbd");
+    }
+
     #[test]
     fn test_infer_explicit_deadline_before_monday() {
         let context = TimeContext::default();
@@ -1832,7 +1948,7 @@ mod tests {
 
     #[test]
     fn test_format_relative_deadline_seconds() {
-        let context = TimeContext::default();
+        let context = TimeContext::with_time(THURSDAY_NOON);
         let result = DeadlineInference::format_relative_deadline(30, &context);
         // Should be in format "today HH:MM:SS"
         assert!(result.starts_with("today "));
@@ -1843,7 +1959,7 @@ mod tests {
 
     #[test]
     fn test_format_relative_deadline_minutes() {
-        let context = TimeContext::default();
+        let context = TimeContext::with_time(THURSDAY_NOON);
         let result = DeadlineInference::format_relative_deadline(300, &context);
         // Should be in format "today HH:MM"
         assert!(result.starts_with("today "));
@@ -1882,11 +1998,18 @@ mod tests {
 
     #[test]
     fn test_add_business_days_one_day() {
-        let context = TimeContext::default();
+        let context = TimeContext::with_time(THURSDAY_NOON);
         let result = DeadlineInference::add_business_days(1, &context);
         assert_eq!(result, "tomorrow");
     }
 
+    #[test]
+    fn test_add_business_days_over_weekend() {
+        let context = TimeContext::with_time(THURSDAY_NOON);
+        assert_eq!(DeadlineInference::add_business_days(1, &context), "tomorrow");
+        assert_eq!(DeadlineInference::add_business_days(2, &context), "4 days");
+    }
+
     #[test]
     fn test_add_business_days_multiple_days() {
         let context = TimeContext::default();
@@ -1948,6 +2071,12 @@ mod tests {
         assert!(phrases.iter().any(|p| p.contains("by Friday")));
     }
 
+    #[test]
+    fn test_extract_time_phrases_end_of_quarter() {
+        let phrases = DeadlineInference::extract_time_phrases("plan the offsite by the end of Q3");
+        assert!(phrases.iter().any(|p| p == "end of Q3"));
+    }
+
     #[test]
     fn test_extract_time_phrases_due_tomorrow() {
         let phrases = DeadlineInference::extract_time_phrases("task due tomorrow");
@@ -2527,4 +2656,161 @@ mod tests {
         assert!(result[0].1 > 0.0);
         assert!(result[1].1 > 0.0);
     }
+
+    // === Deadline Property Tests ===
+    // Inferred deadlines are handed to the time parser, so each one is parsed as the CLI would
+    // at the context's time and checked against chrono
+
+    fn contexts() -> impl Strategy<Value = TimeContext> {
+        // 1970 to 2100
+        (0i64..4_102_444_800).prop_map(TimeContext::with_time)
+    }
+
+    fn now_of(context: &TimeContext) -> NaiveDateTime {
+        context.to_datetime(context.now()).naive_local()
+    }
+
+    fn parse_inferred(input: &str, context: &TimeContext, category: Option<&str>) -> NaiveDateTime {
+        let inferred = DeadlineInference::infer_deadline(input, context, category)
+            .unwrap_or_else(|| panic!("No deadline inferred from '{}'", input));
+        parse_timestr_at(&inferred.deadline, now_of(context)).unwrap_or_else(|e| {
+            panic!("'{}' gave '{}' which does not parse: {}", input, inferred.deadline, e)
+        })
+    }
+
+    fn end_of(date: NaiveDate) -> NaiveDateTime {
+        date.and_hms_opt(23, 59, 59).unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_relative_times(
+            (unit, seconds, amount) in prop_oneof![
+                (Just("second"), Just(1i64), 1i64..86_400),
+                (Just("minute"), Just(60i64), 1i64..1_440),
+                (Just("hour"), Just(3_600i64), 1i64..24),
+            ],
+            context in contexts(),
+        ) {
+            let input = format!("call the bank in {} {}s", amount, unit);
+            let mut expected = now_of(&context) + Duration::seconds(amount * seconds);
+            // Only less than a minute ahead keeps the seconds
+            if amount * seconds >= 60 {
+                expected = expected.with_second(0).unwrap();
+            }
+            prop_assert_eq!(parse_inferred(&input, &context, None), expected);
+        }
+
+        #[test]
+        fn prop_relative_days(days in 1i64..1_000, weeks in any::<bool>(), context in contexts()) {
+            let (input, days) = match weeks {
+                true => (format!("water plants in {} weeks", days % 100 + 1), (days % 100 + 1) * 7),
+                false => (format!("water plants in {} days", days), days),
+            };
+            let expected = end_of(context.today() + Duration::days(days));
+            prop_assert_eq!(parse_inferred(&input, &context, None), expected);
+        }
+
+        #[test]
+        fn prop_weekdays(
+            prefix in prop::sample::select(
+                vec!["by", "due", "deadline is", "before", "on", "for", "next"],
+            ),
+            day in prop::sample::select(
+                vec!["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"],
+            ),
+            context in contexts(),
+        ) {
+            let input = format!("send the report {} {}", prefix, day);
+            let parsed = parse_inferred(&input, &context, None);
+            let ahead = (parsed.date() - context.today()).num_days();
+            prop_assert_eq!(parsed.weekday().to_string().to_lowercase(), &day[..3]);
+            prop_assert!((1..=7).contains(&ahead), "{} is {} days ahead", input, ahead);
+        }
+
+        #[test]
+        fn prop_clock_times(
+            hour in 1u32..=12,
+            minute in 0u32..60,
+            pm in any::<bool>(),
+            style in 0usize..4,
+            context in contexts(),
+        ) {
+            let suffix = if pm { "pm" } else { "am" };
+            let time = match style {
+                0 => format!("{}{}", hour, suffix),
+                1 => format!("{} {}", hour, suffix),
+                2 => format!("{}:{:02} {}.m.", hour, minute, &suffix[..1]),
+                _ => format!("{}:{:02}{}", hour, minute, suffix),
+            };
+            let minute = if style < 2 { 0 } else { minute };
+            let hour24 = hour % 12 + if pm { 12 } else { 0 };
+            let expected = context.today().and_hms_opt(hour24, minute, 0).unwrap();
+            let input = format!("finish the slides by {}", time);
+            prop_assert_eq!(parse_inferred(&input, &context, None), expected, "{}", input);
+        }
+
+        #[test]
+        fn prop_keyword_deadlines_parse(
+            phrase in prop::sample::select(vec![
+                "urgent", "asap", "right now", "soon", "this week", "overdue", "by eod", "by eow",
+                "by eom", "by eoq", "by eoy", "by eofy", "due the next business day",
+                "before the end of the quarter", "by end of q3", "due end of the fiscal year",
+                "by the end of the month", "by end of year", "next week", "next month",
+                "next year", "tidy up",
+            ]),
+            category in prop::sample::select(vec![
+                "urgent", "today", "emergency", "asap", "work", "meeting", "personal", "errand",
+                "shopping", "chore", "learning", "reading",
+            ]),
+            context in contexts(),
+        ) {
+            let input = format!("{} {}", category, phrase);
+            parse_inferred(&input, &context, Some(category));
+        }
+
+        #[test]
+        fn prop_business_days(days in 1u32..30, context in contexts()) {
+            let result = DeadlineInference::add_business_days(days, &context);
+            let total = match result.as_str() {
+                "tomorrow" => 1,
+                other => other.trim_end_matches(" days").parse::<i64>().unwrap(),
+            };
+            let today = context.today();
+            let weekdays = (1..=total)
+                .map(|d| today + Duration::days(d))
+                .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+                .count();
+            let landed = today + Duration::days(total);
+            prop_assert!(!matches!(landed.weekday(), Weekday::Sat | Weekday::Sun), "{}", landed);
+            prop_assert_eq!(weekdays, days as usize);
+        }
+    }
+
+    // Run with: cargo test --release bench_infer_deadline -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_infer_deadline() {
+        let context = TimeContext::default();
+        let inputs = [
+            "finish the report by friday",
+            "call mom in 3 hours",
+            "plan the offsite next month",
+            "urgent fix for login",
+            "buy milk",
+        ];
+        let iterations = 20_000;
+
+        let start = std::time::Instant::now();
+        for _ in 0..iterations {
+            for input in &inputs {
+                std::hint::black_box(DeadlineInference::infer_deadline(input, &context, Some("work")));
+            }
+        }
+        let elapsed = start.elapsed();
+        println!(
+            "infer_deadline: {:.2}µs per input",
+            elapsed.as_secs_f64() * 1e6 / (iterations * inputs.len()) as f64
+        );
+    }
 }
@@ -220,9 +220,9 @@ impl ErrorRecoveryEngine {
             print_yellow("For more help, try:");
             for suggestion in help_suggestions.iter().take(2) {
                 let topic_str = format!("{:?}", suggestion.topic).to_lowercase();
-                println!("  tascli nlp help {} - {}", topic_str, suggestion.reason);
+                println!("  tascli help-topics {} - {}", topic_str, suggestion.reason);
             }
-            println!("  tascli nlp help - List all help topics");
+            println!("  tascli help-topics - List all help topics");
         }
     }
 
@@ -3,8 +3,34 @@
 //! This module provides comprehensive help documentation and examples
 //! for natural language command usage in tascli.
 
+use std::cell::RefCell;
+
 use crate::nlp::{ActionType, QueryType, StatusType};
 
+// Help pages are printed line by line, `HelpSystem::text` collects the lines instead
+thread_local! {
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+macro_rules! println {
+    () => {
+        print_line(String::new())
+    };
+    ($($arg:tt)*) => {
+        print_line(format!($($arg)*))
+    };
+}
+
+fn print_line(line: String) {
+    CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(out) => {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        None => std::println!("{}", line),
+    });
+}
+
 /// Help topics available
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HelpTopic {
@@ -26,7 +52,7 @@ pub enum HelpTopic {
 
 impl HelpTopic {
     /// Parse topic from string
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "overview" | "intro" | "introduction" | "getting-started" | "start" => Some(Self::Overview),
             "queries" | "query" | "search" | "filter" => Some(Self::Queries),
@@ -39,6 +65,21 @@ impl HelpTopic {
         }
     }
 
+    /// Topics with a page of their own, all of them together make up All
+    pub const PAGES: [HelpTopic; 6] = [
+        Self::Overview,
+        Self::Queries,
+        Self::Compound,
+        Self::Conditions,
+        Self::Examples,
+        Self::Patterns,
+    ];
+
+    /// Name of the topic on the command line
+    pub fn name(&self) -> String {
+        format!("{:?}", self).to_lowercase()
+    }
+
     /// Get description of topic
     pub fn description(&self) -> &'static str {
         match self {
@@ -80,11 +121,12 @@ impl HelpSystem {
         println!();
         println!("  Getting More Help:");
         println!("  -----------------");
-        println!("    tascli nlp help queries      - Learn about query types");
-        println!("    tascli nlp help compound     - Learn about multiple commands");
-        println!("    tascli nlp help conditions   - Learn about conditional execution");
-        println!("    tascli nlp help examples     - See real-world examples");
-        println!("    tascli nlp help patterns     - See all command patterns");
+        println!("    tascli help-topics queries      - Learn about query types");
+        println!("    tascli help-topics compound     - Learn about multiple commands");
+        println!("    tascli help-topics conditions   - Learn about conditional execution");
+        println!("    tascli help-topics examples     - See real-world examples");
+        println!("    tascli help-topics patterns     - See all command patterns");
+        println!("    tascli help-topics -s <words>   - Search all topics");
         println!();
         println!("  Suggestions:");
         println!("  -----------");
@@ -105,6 +147,13 @@ impl HelpSystem {
         }
     }
 
+    /// The help page of a topic
+    pub fn text(topic: HelpTopic) -> String {
+        CAPTURED.set(Some(String::new()));
+        Self::show_help(topic);
+        CAPTURED.take().unwrap_or_default()
+    }
+
     /// Show query types help
     fn show_queries() {
         println!();
@@ -242,6 +291,11 @@ impl HelpSystem {
         println!("    tascli nlp \"if upcoming has more than 5 tasks then show upcoming\"");
         println!("    tascli nlp \"if pending has fewer than 3 tasks then list tasks\"");
         println!();
+        println!("  Simulation:");
+        println!("  ----------");
+        println!("    tascli ai --simulate \"if work is empty then add plan next sprint\"");
+        println!("    shows whether the condition holds and why, without running anything");
+        println!();
         println!("  Use Cases:");
         println!("  ---------");
         println!("  - Check for overdue tasks before adding new ones");
@@ -468,6 +522,31 @@ impl HelpSystem {
         Self::show_patterns();
     }
 
+    /// Lines of each page containing all the words of `query`, ignoring case. Pages without
+    /// any are left out.
+    pub fn search(query: &str) -> Vec<(HelpTopic, Vec<String>)> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Vec::new();
+        }
+        HelpTopic::PAGES
+            .into_iter()
+            .map(|topic| {
+                let text = Self::text(topic);
+                let lines = text
+                    .lines()
+                    .filter(|line| {
+                        let line = line.to_lowercase();
+                        words.iter().all(|word| line.contains(word.as_str()))
+                    })
+                    .map(|line| line.trim().to_string())
+                    .collect::<Vec<_>>();
+                (topic, lines)
+            })
+            .filter(|(_, lines)| !lines.is_empty())
+            .collect()
+    }
+
     /// List all available help topics
     pub fn list_topics() {
         println!();
@@ -489,7 +568,7 @@ impl HelpSystem {
             println!("    {:20} - {}", topic_str, description);
         }
         println!();
-        println!("  Usage: tascli nlp help <topic>");
+        println!("  Usage: tascli help-topics <topic>, or --search <words> to search them");
         println!();
     }
 
@@ -619,7 +698,7 @@ pub fn format_help_suggestions(suggestions: &[HelpSuggestion]) -> String {
 
     for (i, suggestion) in suggestions.iter().enumerate() {
         let topic_str = format!("{:?}", suggestion.topic).to_lowercase();
-        output.push_str(&format!("  {}. tascli nlp help {} - {}\n",
+        output.push_str(&format!("  {}. tascli help-topics {} - {}\n",
             i + 1, topic_str, suggestion.reason));
     }
 
@@ -632,16 +711,16 @@ mod tests {
 
     #[test]
     fn test_help_topic_from_str() {
-        assert_eq!(HelpTopic::from_str("overview"), Some(HelpTopic::Overview));
-        assert_eq!(HelpTopic::from_str("OVERVIEW"), Some(HelpTopic::Overview));
-        assert_eq!(HelpTopic::from_str("queries"), Some(HelpTopic::Queries));
-        assert_eq!(HelpTopic::from_str("query"), Some(HelpTopic::Queries));
-        assert_eq!(HelpTopic::from_str("compound"), Some(HelpTopic::Compound));
-        assert_eq!(HelpTopic::from_str("conditions"), Some(HelpTopic::Conditions));
-        assert_eq!(HelpTopic::from_str("examples"), Some(HelpTopic::Examples));
-        assert_eq!(HelpTopic::from_str("patterns"), Some(HelpTopic::Patterns));
-        assert_eq!(HelpTopic::from_str("all"), Some(HelpTopic::All));
-        assert_eq!(HelpTopic::from_str("invalid"), None);
+        assert_eq!(HelpTopic::parse("overview"), Some(HelpTopic::Overview));
+        assert_eq!(HelpTopic::parse("OVERVIEW"), Some(HelpTopic::Overview));
+        assert_eq!(HelpTopic::parse("queries"), Some(HelpTopic::Queries));
+        assert_eq!(HelpTopic::parse("query"), Some(HelpTopic::Queries));
+        assert_eq!(HelpTopic::parse("compound"), Some(HelpTopic::Compound));
+        assert_eq!(HelpTopic::parse("conditions"), Some(HelpTopic::Conditions));
+        assert_eq!(HelpTopic::parse("examples"), Some(HelpTopic::Examples));
+        assert_eq!(HelpTopic::parse("patterns"), Some(HelpTopic::Patterns));
+        assert_eq!(HelpTopic::parse("all"), Some(HelpTopic::All));
+        assert_eq!(HelpTopic::parse("invalid"), None);
     }
 
     #[test]
@@ -661,6 +740,27 @@ mod tests {
         assert!(suggestions.contains(&"query".to_string()));
     }
 
+    #[test]
+    fn test_search() {
+        let results = HelpSystem::search("OVERDUE tasks");
+        assert!(!results.is_empty());
+        let (topic, lines) = &results[0];
+        assert_eq!(*topic, HelpTopic::Queries);
+        assert!(lines.contains(&"tascli nlp \"show overdue tasks\"".to_string()), "{:?}", lines);
+        assert!(results.iter().all(|(topic, _)| *topic != HelpTopic::All));
+
+        assert!(HelpSystem::search("xyzzy").is_empty());
+        assert!(HelpSystem::search("  ").is_empty());
+    }
+
+    #[test]
+    fn test_text() {
+        assert!(HelpSystem::text(HelpTopic::Overview).contains("Quick Start:"));
+        let all = HelpSystem::text(HelpTopic::All);
+        assert!(HelpTopic::PAGES.iter().all(|topic| all.contains(&HelpSystem::text(*topic))));
+        assert_eq!(HelpTopic::Conditions.name(), "conditions");
+    }
+
     #[test]
     fn test_suggest_for_input() {
         let suggestions = HelpSystem::suggest_for_input("");
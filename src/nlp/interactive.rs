@@ -4,10 +4,12 @@
 //! with context persistence across queries.
 
 use super::parser::NLPParser;
+use super::suggestions::AutoCompleter;
 use super::types::*;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::io::{self, Write};
+use crate::actions::display::is_plain;
 
 /// Session state for interactive mode
 #[derive(Debug, Clone)]
@@ -58,6 +60,12 @@ impl InteractiveSession {
     }
 }
 
+impl Default for InteractiveSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Result of an interactive command
 #[derive(Debug, Clone)]
 pub enum InteractiveResult {
@@ -77,6 +85,8 @@ pub enum InteractiveResult {
     Repeat { command: NLPCommand },
     /// Ambiguous input - needs clarification
     Ambiguous { clarification: String },
+    /// User asked for completions of a partial input
+    Completions { partial: String, completions: Vec<String> },
 }
 
 /// Interactive mode configuration
@@ -97,7 +107,7 @@ pub struct InteractiveConfig {
 impl Default for InteractiveConfig {
     fn default() -> Self {
         Self {
-            prompt: "\x1b[1;36mnlp>\x1b[0m ".to_string(),
+            prompt: if is_plain() { "nlp> " } else { "\x1b[1;36mnlp>\x1b[0m " }.to_string(),
             show_interpretation: true,
             show_context_on_start: true,
             max_history: 100,
@@ -117,6 +127,8 @@ pub struct InteractiveMode {
     last_command: Option<NLPCommand>,
     /// Pending clarification response
     pending_clarification: Option<String>,
+    /// Completions from stored history and categories, plus this session's inputs
+    completer: AutoCompleter,
 }
 
 impl InteractiveMode {
@@ -129,9 +141,16 @@ impl InteractiveMode {
             history: Vec::new(),
             last_command: None,
             pending_clarification: None,
+            completer: AutoCompleter::new(),
         }
     }
 
+    /// Use a completer built from the stored history and categories
+    pub fn with_completer(mut self, completer: AutoCompleter) -> Self {
+        self.completer = completer;
+        self
+    }
+
     /// Create with default configuration
     pub fn with_parser(parser: Arc<Mutex<NLPParser>>) -> Self {
         Self::new(parser, InteractiveConfig::default())
@@ -195,11 +214,16 @@ impl InteractiveMode {
                         self.clear_context().await;
                         continue;
                     }
+                    InteractiveResult::Completions { partial, completions } => {
+                        self.show_completions(&partial, &completions);
+                        continue;
+                    }
                     _ => {}
                 }
             }
 
-            // Process as NLP command
+            // Process as NLP command, offered as a completion from then on
+            self.completer.add_to_history(input.to_string());
             match self.process_input(input).await {
                 Ok(result) => {
                     self.handle_result(result);
@@ -293,7 +317,13 @@ impl InteractiveMode {
                 self.show_history();
                 None
             }
-            _ => None,
+            _ => {
+                // "complete <partial>", the partial input keeps its case
+                let partial = input_lower.strip_prefix("complete ")?;
+                let partial = input[input.len() - partial.len()..].to_string();
+                let completions = self.completer.complete(&partial);
+                Some(InteractiveResult::Completions { partial, completions })
+            }
         }
     }
 
@@ -394,8 +424,24 @@ impl InteractiveMode {
         }
     }
 
+    /// Show completions for a partial input
+    fn show_completions(&self, partial: &str, completions: &[String]) {
+        if completions.is_empty() {
+            println!("No completions for '{}'.", partial);
+        }
+        for completion in completions {
+            println!("  {}", completion);
+        }
+    }
+
     /// Print welcome message
     fn print_welcome(&self) {
+        if is_plain() {
+            println!("Interactive Natural Language Task Management");
+            println!("Session: {}", self.session.session_id);
+            println!("Type help for available commands or exit to quit.\n");
+            return;
+        }
         println!("\x1b[1;36m╔════════════════════════════════════════════════════════╗\x1b[0m");
         println!("\x1b[1;36m║\x1b[0m  \x1b[1;37mInteractive Natural Language Task Management\x1b[0m    \x1b[1;36m║\x1b[0m");
         println!("\x1b[1;36m╚════════════════════════════════════════════════════════╝\x1b[0m");
@@ -428,6 +474,7 @@ impl InteractiveMode {
         println!("  \x1b[1;32mclear, reset\x1b[0m    Clear session context");
         println!("  \x1b[1;32mrepeat, r, !!\x1b[0m   Repeat last command");
         println!("  \x1b[1;32mhistory\x1b[0m        Show command history");
+        println!("  \x1b[1;32mcomplete <text>\x1b[0m Show completions from your history");
 
         println!("\n\x1b[1;37mFeatures:\x1b[0m");
         println!("  • Context persistence across queries");
@@ -622,4 +669,20 @@ mod tests {
         let result = mode.handle_builtin_commands("CONTEXT");
         assert!(matches!(result, Some(InteractiveResult::ContextInfo { .. })));
     }
+
+    #[test]
+    fn test_complete_builtin() {
+        let completer = AutoCompleter::from_history(
+            vec!["Work".to_string()],
+            vec!["list Work tasks".to_string()],
+        );
+        let mut mode = create_test_mode().with_completer(completer);
+
+        let result = mode.handle_builtin_commands("complete list W");
+        let Some(InteractiveResult::Completions { partial, completions }) = result else {
+            panic!("expected completions");
+        };
+        assert_eq!(partial, "list W");
+        assert_eq!(completions[0], "list Work tasks");
+    }
 }
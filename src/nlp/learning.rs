@@ -8,6 +8,9 @@ use super::types::{NLPCommand, ActionType};
 use rusqlite::params;
 use std::path::Path;
 
+/// Commands parsed with less confidence are always previewed, even with previews turned off
+pub const LOW_CONFIDENCE: f64 = 0.7;
+
 /// A correction learned from user input
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LearnedCorrection {
@@ -112,6 +115,28 @@ impl LearningDB {
             [],
         ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create patterns table: {}", e)))?;
 
+        // The last interpretation of each input, and how often each source and action was used
+        // and corrected
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS interpretations (
+                input TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                action TEXT NOT NULL
+            )",
+            [],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create interpretations table: {}", e)))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS calibration (
+                source TEXT NOT NULL,
+                action TEXT NOT NULL,
+                uses INTEGER NOT NULL DEFAULT 0,
+                corrections INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY(source, action)
+            )",
+            [],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create calibration table: {}", e)))?;
+
         // Create indexes for faster lookups
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_original_input ON corrections(original_input)",
@@ -159,8 +184,7 @@ impl LearningDB {
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => {
                 // Insert new correction
-                let command_data = serde_json::to_vec(intended_command)
-                    .map_err(|e| crate::nlp::NLPError::SerializationError(serde_json::Error::from(e)))?;
+                let command_data = crate::nlp::schema::encode_command_bytes(intended_command)?;
 
                 self.conn.execute(
                     "INSERT INTO corrections (original_input, intended_command, confirmation_count, learned_at, last_used_at, confidence)
@@ -185,7 +209,7 @@ impl LearningDB {
             params![normalized_input],
             |row| {
                 let data: Vec<u8> = row.get(0)?;
-                let command: NLPCommand = serde_json::from_slice(&data)
+                let command = crate::nlp::schema::decode_command_bytes(&data)
                     .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
                 Ok((
                     command,
@@ -221,7 +245,7 @@ impl LearningDB {
 
         let rows = stmt.query_map([], |row| {
             let data: Vec<u8> = row.get(1)?;
-            let command: NLPCommand = serde_json::from_slice(&data)
+            let command = crate::nlp::schema::decode_command_bytes(&data)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
             Ok((
                 row.get::<_, String>(0)?,
@@ -417,6 +441,50 @@ impl LearningDB {
         patterns
     }
 
+    /// Remember how an input was interpreted, so a later correction of it counts against the
+    /// source and action that got it wrong
+    pub fn record_interpretation(&self, input: &str, source: &str, action: &ActionType) -> Result<(), crate::nlp::NLPError> {
+        let action = format_action(action);
+        self.conn.execute(
+            "INSERT OR REPLACE INTO interpretations (input, source, action) VALUES (?1, ?2, ?3)",
+            params![input.trim().to_lowercase(), source, action],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to store interpretation: {}", e)))?;
+        self.conn.execute(
+            "INSERT INTO calibration (source, action, uses) VALUES (?1, ?2, 1)
+             ON CONFLICT(source, action) DO UPDATE SET uses = uses + 1",
+            params![source, action],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to update calibration: {}", e)))?;
+        Ok(())
+    }
+
+    /// Count a correction of `input` against the interpretation recorded for it. The
+    /// interpretation is forgotten, so correcting the same input again does not count twice.
+    pub fn record_miss(&self, input: &str) -> Result<(), crate::nlp::NLPError> {
+        let input = input.trim().to_lowercase();
+        self.conn.execute(
+            "UPDATE calibration SET corrections = corrections + 1
+             WHERE (source, action) = (SELECT source, action FROM interpretations WHERE input = ?1)",
+            params![input],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to update calibration: {}", e)))?;
+        self.conn.execute("DELETE FROM interpretations WHERE input = ?1", params![input])
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to forget interpretation: {}", e)))?;
+        Ok(())
+    }
+
+    /// The share of interpretations by `source` as `action` that were not corrected, with one
+    /// extra good use so a source starts out trusted
+    pub fn accuracy(&self, source: &str, action: &ActionType) -> f64 {
+        let counts = self.conn.query_row(
+            "SELECT uses, corrections FROM calibration WHERE source = ?1 AND action = ?2",
+            params![source, format_action(action)],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        );
+        match counts {
+            Ok((uses, corrections)) => ((uses - corrections + 1) as f64 / (uses + 1) as f64).clamp(0.0, 1.0),
+            Err(_) => 1.0,
+        }
+    }
+
     /// Get learning statistics
     pub fn stats(&self) -> LearningStats {
         let total_corrections = self.conn.query_row(
@@ -457,6 +525,10 @@ impl LearningDB {
             .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear corrections: {}", e)))?;
         self.conn.execute("DELETE FROM patterns", [])
             .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear patterns: {}", e)))?;
+        self.conn.execute("DELETE FROM interpretations", [])
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear interpretations: {}", e)))?;
+        self.conn.execute("DELETE FROM calibration", [])
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear calibration: {}", e)))?;
         Ok(())
     }
 }
@@ -482,6 +554,7 @@ impl LearningEngine {
     /// Learn from a user correction
     pub fn learn_from_correction(&self, original_input: &str, intended_command: &NLPCommand) -> Result<(), crate::nlp::NLPError> {
         if let Some(ref db) = self.db {
+            db.record_miss(original_input)?;
             db.store_correction(original_input, intended_command)?;
 
             // Also extract and learn patterns from the correction
@@ -493,6 +566,17 @@ impl LearningEngine {
         Ok(())
     }
 
+    /// Scale the confidence of a parsed command by how often its source and action were
+    /// corrected, and remember the interpretation in case this one is corrected too
+    pub fn calibrate(&self, input: &str, command: &mut NLPCommand) {
+        let (Some(db), Some(source)) = (&self.db, command.interpretation_source.as_deref()) else {
+            return;
+        };
+        let accuracy = db.accuracy(source, &command.action);
+        command.confidence = command.confidence.map(|confidence| confidence * accuracy);
+        let _ = db.record_interpretation(input, source, &command.action);
+    }
+
     /// Apply learned corrections to input
     pub fn apply_learning(&self, input: &str) -> Option<NLPCommand> {
         if let Some(ref db) = self.db {
@@ -788,6 +872,41 @@ mod tests {
         assert_eq!(stats.total_corrections, 1);
     }
 
+    #[test]
+    fn test_calibration_from_corrections() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let engine = LearningEngine::with_db(temp_file.path()).unwrap();
+        let parsed = |input: &str| {
+            let mut command = NLPCommand {
+                action: ActionType::Delete,
+                confidence: Some(0.95),
+                interpretation_source: Some("pattern".to_string()),
+                ..Default::default()
+            };
+            engine.calibrate(input, &mut command);
+            command.confidence.unwrap()
+        };
+        let intended = NLPCommand {
+            action: ActionType::Done,
+            content: "1".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(parsed("remove 1"), 0.95);
+        engine.learn_from_correction("remove 1", &intended).unwrap();
+        // One use out of one was corrected
+        assert!((parsed("remove 2") - 0.475).abs() < 1e-9);
+        engine.learn_from_correction("remove 2", &intended).unwrap();
+        assert!(parsed("remove 3") < LOW_CONFIDENCE);
+
+        // A correction only counts once, and other actions keep their confidence
+        engine.learn_from_correction("remove 1", &intended).unwrap();
+        let db = engine.db.as_ref().unwrap();
+        assert!((db.accuracy("pattern", &ActionType::Delete) - 0.5).abs() < 1e-9);
+        assert_eq!(db.accuracy("pattern", &ActionType::Done), 1.0);
+        assert_eq!(db.accuracy("ai", &ActionType::Delete), 1.0);
+    }
+
     #[test]
     fn test_clear_learning_data() {
         let (db, _temp) = create_test_learning_db();
@@ -28,6 +28,11 @@ impl CommandMapper {
                 } else if let Some(schedule) = &command.schedule {
                     args.push(schedule.clone());
                 }
+
+                if let Some(priority) = command.filters.get("priority") {
+                    args.push("--priority".to_string());
+                    args.push(priority.to_lowercase());
+                }
             },
 
             ActionType::Record => {
@@ -132,6 +137,12 @@ impl CommandMapper {
                 if let Some(category) = &command.category {
                     args.push("-c".to_string());
                     args.push(category.clone());
+                } else if let Some(place) = command.filters.get("place") {
+                    // Named places of config stand for their categories, tasks only
+                    if list_type == "task" {
+                        args.push("--at".to_string());
+                        args.push(place.clone());
+                    }
                 }
 
                 // Add search filter
@@ -140,6 +151,19 @@ impl CommandMapper {
                     args.push(search.clone());
                 }
 
+                // Priorities are kept on tasks only
+                if let Some(priority) = command.filters.get("priority")
+                    && list_type == "task"
+                {
+                    args.push("--priority".to_string());
+                    args.push(priority.to_lowercase());
+                }
+
+                if let Some(tag) = command.filters.get("tag") {
+                    args.push("--tag".to_string());
+                    args.push(tag.trim_start_matches(['+', '#']).to_lowercase());
+                }
+
                 // Add status filter (only if not already set by query_type)
                 if let Some(status) = &command.status {
                     if command.query_type.is_none() || !matches!(command.query_type, Some(QueryType::Overdue | QueryType::Upcoming | QueryType::Urgent)) {
@@ -272,6 +296,18 @@ impl CommandMapper {
                     filters.push(format!("category: {}", category));
                 }
 
+                if let Some(place) = command.filters.get("place") {
+                    filters.push(format!("at {}", place));
+                }
+
+                if let Some(priority) = command.filters.get("priority") {
+                    filters.push(format!("priority: {}", priority.to_lowercase()));
+                }
+
+                if let Some(tag) = command.filters.get("tag") {
+                    filters.push(format!("tag: +{}", tag.trim_start_matches(['+', '#']).to_lowercase()));
+                }
+
                 if let Some(status) = &command.status {
                     filters.push(format!("status: {:?}", status));
                 }
@@ -648,6 +684,20 @@ mod tests {
         assert_eq!(args, vec!["list", "task", "-c", "work", "--search", "meeting", "-s", "ongoing", "-d", "7", "--limit", "20"]);
     }
 
+    #[test]
+    fn test_list_mapping_place() {
+        let mut command = NLPCommand {
+            action: ActionType::List,
+            content: "".to_string(),
+            ..Default::default()
+        };
+        command.filters.insert("place".to_string(), "office".to_string());
+
+        let args = CommandMapper::to_tascli_args(&command);
+        assert_eq!(args, vec!["list", "task", "--at", "office"]);
+        assert_eq!(CommandMapper::describe_command(&command), "List tasks (at office)");
+    }
+
     #[test]
     fn test_list_mapping_status_cancelled() {
         let command = NLPCommand {
@@ -794,6 +844,47 @@ mod tests {
         assert_eq!(args, vec!["list", "task", "-s", "ongoing", "--target-time-max", "tomorrow"]);
     }
 
+    #[test]
+    fn test_list_mapping_priority() {
+        let mut command = NLPCommand {
+            action: ActionType::List,
+            content: "".to_string(),
+            ..Default::default()
+        };
+        command.filters.insert("priority".to_string(), "High".to_string());
+
+        let args = CommandMapper::to_tascli_args(&command);
+        assert_eq!(args, vec!["list", "task", "--priority", "high"]);
+        assert_eq!(CommandMapper::describe_command(&command), "List tasks (priority: high)");
+    }
+
+    #[test]
+    fn test_list_mapping_tag() {
+        let mut command = NLPCommand {
+            action: ActionType::List,
+            content: "".to_string(),
+            ..Default::default()
+        };
+        command.filters.insert("tag".to_string(), "#Errands".to_string());
+
+        let args = CommandMapper::to_tascli_args(&command);
+        assert_eq!(args, vec!["list", "task", "--tag", "errands"]);
+        assert_eq!(CommandMapper::describe_command(&command), "List tasks (tag: +errands)");
+    }
+
+    #[test]
+    fn test_task_mapping_priority() {
+        let mut command = NLPCommand {
+            action: ActionType::Task,
+            content: "file taxes".to_string(),
+            ..Default::default()
+        };
+        command.filters.insert("priority".to_string(), "urgent".to_string());
+
+        let args = CommandMapper::to_tascli_args(&command);
+        assert_eq!(args, vec!["task", "file taxes", "--priority", "urgent"]);
+    }
+
     // === Delete Mapping Tests ===
 
     #[test]
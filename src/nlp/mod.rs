@@ -4,6 +4,7 @@
 //! allowing users to interact with tascli using natural language commands.
 
 pub mod cache;
+pub mod cancel;
 pub mod client;
 pub mod parser;
 pub mod mapper;
@@ -22,6 +23,7 @@ pub mod personalization;
 pub mod transparency;
 pub mod help;
 pub mod interactive;
+pub mod schema;
 
 pub use help::{
     HelpSystem,
@@ -40,6 +42,7 @@ mod integration_tests;
 mod natural_language_patterns_tests;
 
 pub use cache::{ResponseCache, CacheStats};
+pub use schema::SCHEMA_VERSION;
 pub use client::OpenAIClient;
 pub use types::*;
 pub use parser::NLPParser;
@@ -55,7 +58,7 @@ pub use types::{
     CommandOutput,
     ExecutionSummary,
 };
-pub use sequential::SequentialExecutor;
+pub use sequential::{SequentialExecutor, prepare_parallel, MAX_PARALLEL};
 
 // Batching support
 pub use batching::{
@@ -73,6 +76,8 @@ pub use conditional::{
     ConditionalExecutor,
     ConditionBuilder,
     BranchExecutionResult,
+    ConditionTrace,
+    SimulatedCommand,
 };
 pub use types::{
     Condition,
@@ -116,6 +121,7 @@ pub use learning::{
     PatternCorrection,
     LearningStats,
     format_action,
+    LOW_CONFIDENCE,
 };
 pub use personalization::{
     UserProfile,
@@ -127,6 +133,7 @@ pub use personalization::{
     PersonalizedPatternMatcher,
     PersonalizationStats,
     get_user_id,
+    parse_clarification,
 };
 pub use transparency::{
     show_interpretation,
@@ -11,12 +11,14 @@ use super::personalization::PersonalizationEngine;
 use sha2::{Sha256, Digest};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use lru::LruCache;
 use std::num::NonZeroUsize;
 
 pub struct NLPParser {
-    client: Arc<Mutex<OpenAIClient>>,
+    client: Arc<RwLock<OpenAIClient>>,
+    /// Client of the fallback provider, asked when the main one fails
+    fallback_client: Option<Arc<RwLock<OpenAIClient>>>,
     /// Fast LRU cache for frequently accessed commands (in-memory, size-limited)
     hot_cache: Arc<Mutex<LruCache<String, NLPCommand>>>,
     /// Fallback HashMap for less frequently accessed items with timestamps
@@ -33,7 +35,8 @@ pub struct NLPParser {
 impl NLPParser {
     /// Create a new NLP parser with the given configuration
     pub fn new(config: NLPConfig) -> Self {
-        let client = Arc::new(Mutex::new(OpenAIClient::new(config.clone())));
+        let client = Arc::new(RwLock::new(OpenAIClient::new(config.clone())));
+        let fallback_client = Self::fallback_client(&config);
         // Hot cache: stores 100 most recently used commands
         let hot_cache = Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(100).unwrap())));
         let cold_cache = Arc::new(Mutex::new(HashMap::new()));
@@ -44,6 +47,7 @@ impl NLPParser {
 
         Self {
             client,
+            fallback_client,
             hot_cache,
             cold_cache,
             config,
@@ -56,7 +60,8 @@ impl NLPParser {
 
     /// Create a new NLP parser with initial categories
     pub fn with_categories(config: NLPConfig, categories: Vec<String>) -> Self {
-        let client = Arc::new(Mutex::new(OpenAIClient::new(config.clone())));
+        let client = Arc::new(RwLock::new(OpenAIClient::new(config.clone())));
+        let fallback_client = Self::fallback_client(&config);
         let hot_cache = Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(100).unwrap())));
         let cold_cache = Arc::new(Mutex::new(HashMap::new()));
         let context = Arc::new(Mutex::new(CommandContext::new(categories)));
@@ -66,6 +71,7 @@ impl NLPParser {
 
         Self {
             client,
+            fallback_client,
             hot_cache,
             cold_cache,
             config,
@@ -76,6 +82,49 @@ impl NLPParser {
         }
     }
 
+    fn fallback_client(config: &NLPConfig) -> Option<Arc<RwLock<OpenAIClient>>> {
+        let fallback = config.fallback.as_deref()?;
+        Some(Arc::new(RwLock::new(OpenAIClient::new(fallback.clone()))))
+    }
+
+    async fn ask_client(
+        client: &RwLock<OpenAIClient>,
+        input: &str,
+        context: &str,
+        conversation_summary: &[HashMap<String, String>],
+        known_categories: &[String],
+    ) -> NLPResult<NLPCommand> {
+        // Held shared while the request is in flight, so requests made at once are sent at once
+        client.write().await.reserve_request().await?;
+        client
+            .read()
+            .await
+            .request_command(input, context, conversation_summary, known_categories)
+            .await
+    }
+
+    /// Whether the error came from reaching the provider rather than from the input, so
+    /// another provider may do better
+    fn is_provider_error(error: &NLPError) -> bool {
+        matches!(
+            error,
+            NLPError::NetworkError(_)
+                | NLPError::APIError(_)
+                | NLPError::InvalidAPIKey
+                | NLPError::RateLimited
+                | NLPError::Timeout(_)
+        )
+    }
+
+    /// Replace a tag filter with the known tag it is closest to, when there is one
+    fn match_known_tag(command: &mut NLPCommand, known_tags: &[String]) {
+        if let Some(tag) = command.filters.get("tag")
+            && let Some(known) = FuzzyMatcher::match_tag(tag, known_tags)
+        {
+            command.filters.insert("tag".to_string(), known);
+        }
+    }
+
     /// Initialize the learning engine with a database path
     pub async fn init_learning(&self, db_path: &std::path::Path) -> Result<(), NLPError> {
         let engine = LearningEngine::with_db(db_path)?;
@@ -94,6 +143,13 @@ impl NLPParser {
 
     /// Parse natural language input and return a structured command
     pub async fn parse(&self, input: &str) -> NLPResult<NLPCommand> {
+        let mut command = self.interpret(input).await?;
+        // Phrases the user clarified before give tasks and records their category
+        self.personalization_engine.lock().await.apply_clarifications(&mut command);
+        Ok(command)
+    }
+
+    async fn interpret(&self, input: &str) -> NLPResult<NLPCommand> {
         // Check personalization engine first for user-specific patterns
         let personalization = self.personalization_engine.lock().await;
         if let Some(mut personalized_command) = personalization.get_personalized_command(input) {
@@ -133,13 +189,14 @@ impl NLPParser {
 
         // Try pattern matching first for simple commands (fast path)
         if self.pattern_matcher_enabled {
-            match PatternMatcher::match_input(input) {
+            let context_state = self.context.lock().await;
+            let known_categories = context_state.known_categories.clone();
+            let known_tags = context_state.known_tags.clone();
+            drop(context_state);
+
+            match PatternMatcher::match_with_categories(input, &known_categories) {
                 PatternMatch::Matched(mut command) => {
                     // Apply fuzzy matching for categories if needed
-                    let context_state = self.context.lock().await;
-                    let known_categories = context_state.known_categories.clone();
-                    drop(context_state);
-
                     if let Some(ref category) = command.category {
                         if !known_categories.is_empty() &&
                            !known_categories.contains(&category.to_lowercase()) &&
@@ -149,10 +206,12 @@ impl NLPParser {
                             }
                         }
                     }
+                    Self::match_known_tag(&mut command, &known_tags);
 
                     // Set transparency info for pattern match
                     command.confidence = Some(0.95);
                     command.interpretation_source = Some("pattern".to_string());
+                    self.learning_engine.lock().await.calibrate(input, &mut command);
 
                     // Update context and cache
                     let mut context_state = self.context.lock().await;
@@ -179,17 +238,18 @@ impl NLPParser {
         let context_str = context_state.to_context_string();
         let conversation_summary = context_state.get_conversation_summary();
         let known_categories = context_state.known_categories.clone();
+        let known_tags = context_state.known_tags.clone();
         let last_category = context_state.last_category.clone();
         drop(context_state);
 
-        // Parse using OpenAI with context
-        let mut client = self.client.lock().await;
-        let mut command = client.parse_command_with_context(
-            input,
-            &context_str,
-            &conversation_summary,
-            &known_categories,
-        ).await?;
+        // Parse using OpenAI with context, or the fallback provider when that fails
+        let ask = |client| {
+            Self::ask_client(client, input, &context_str, &conversation_summary, &known_categories)
+        };
+        let mut command = match (ask(&self.client).await, &self.fallback_client) {
+            (Err(e), Some(fallback)) if Self::is_provider_error(&e) => ask(fallback).await?,
+            (result, _) => result?,
+        };
 
         // Set transparency info for AI parsing
         if command.confidence.is_none() {
@@ -209,6 +269,7 @@ impl NLPParser {
                 }
             }
         }
+        Self::match_known_tag(&mut command, &known_tags);
 
         // Handle follow-up references (e.g., "change the category" without specifying content)
         if command.content.is_empty() || command.content == "it" || command.content == "that" {
@@ -222,6 +283,9 @@ impl NLPParser {
         // Validate the command
         CommandValidator::validate(&command)?;
 
+        // Lower the confidence of interpretations users keep correcting
+        self.learning_engine.lock().await.calibrate(input, &mut command);
+
         // Update context with the parsed command
         let mut context_state = self.context.lock().await;
         context_state.add_command(command.clone(), input.to_string());
@@ -395,8 +459,7 @@ impl NLPParser {
 
     /// Check if the parser is ready (has valid API key and is enabled)
     pub fn is_ready(&self) -> bool {
-        self.config.enabled &&
-        self.config.api_key.as_ref().map_or(false, |k| !k.is_empty())
+        self.config.enabled && self.config.has_provider()
     }
 
     /// Get configuration
@@ -410,9 +473,10 @@ impl NLPParser {
         self.clear_cache().await;
 
         // Create new client with updated config
-        let client = Arc::new(Mutex::new(OpenAIClient::new(new_config.clone())));
+        let client = Arc::new(RwLock::new(OpenAIClient::new(new_config.clone())));
 
         self.client = client;
+        self.fallback_client = Self::fallback_client(&new_config);
         self.config = new_config;
     }
 
@@ -422,6 +486,12 @@ impl NLPParser {
         context.update_categories(categories);
     }
 
+    /// Update known tags in the context, tag filters are matched against them
+    pub async fn update_tags(&self, tags: Vec<String>) {
+        let mut context = self.context.lock().await;
+        context.update_tags(tags);
+    }
+
     /// Clear old context entries
     pub async fn clear_old_context(&self, max_age_seconds: i64) {
         let mut context = self.context.lock().await;
@@ -437,6 +507,7 @@ impl NLPParser {
             last_category: context.last_category.clone(),
             last_content: context.last_content.clone(),
             known_categories: context.known_categories.clone(),
+            known_tags: context.known_tags.clone(),
             recent_tasks: context.recent_tasks.clone(),
             max_history_size: context.max_history_size,
         }
@@ -449,6 +520,7 @@ impl NLPParser {
         context.last_category = state.last_category;
         context.last_content = state.last_content;
         context.known_categories = state.known_categories;
+        context.known_tags = state.known_tags;
         context.recent_tasks = state.recent_tasks;
         context.max_history_size = state.max_history_size;
     }
@@ -474,6 +546,25 @@ impl NLPParser {
         learning.learn_from_correction(original_input, intended_command)
     }
 
+    /// The category to propose for a new task or record without one, when the last few added
+    /// shared it
+    pub async fn context_category(&self, command: &NLPCommand) -> Option<String> {
+        if command.category.is_some() || !matches!(command.action, ActionType::Task | ActionType::Record) {
+            return None;
+        }
+        self.personalization_engine.lock().await.context_category()
+    }
+
+    /// Remember the category of a task or record that was added
+    pub async fn record_category(&self, command: &NLPCommand) {
+        let _ = self.personalization_engine.lock().await.record_category(command);
+    }
+
+    /// Remember an input that ran, for completions from history
+    pub async fn record_input(&self, input: &str) {
+        let _ = self.personalization_engine.lock().await.record_input(input);
+    }
+
     /// Get learning-based suggestions for input
     pub async fn suggest_learning(&self, input: &str) -> Vec<String> {
         let learning = self.learning_engine.lock().await;
@@ -929,6 +1020,7 @@ mod tests {
             last_category: Some("work".to_string()),
             last_content: Some("meeting".to_string()),
             known_categories: vec!["work".to_string(), "personal".to_string()],
+            known_tags: vec![],
             recent_tasks: vec!["task1".to_string(), "task2".to_string()],
             max_history_size: 100,
         };
@@ -1049,4 +1141,28 @@ mod tests {
         assert_eq!(all_args[1], vec!["update", "5"]);
         assert_eq!(all_args[2], vec!["list", "task"]);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_fallback_provider() {
+        let fallback = NLPConfig {
+            enabled: true,
+            api_key: Some("local".to_string()),
+            // Nothing listens on the discard port
+            api_base_url: "http://127.0.0.1:9".to_string(),
+            timeout_seconds: 2,
+            ..Default::default()
+        };
+        let config = NLPConfig {
+            enabled: true,
+            cache_commands: false,
+            fallback: Some(Box::new(fallback)),
+            ..Default::default()
+        };
+        let parser = NLPParser::new(config);
+        assert!(parser.is_ready());
+
+        // Without a key of its own the request goes on to the fallback provider
+        let err = parser.parse("sort out the thing we talked about whenever").await.unwrap_err();
+        assert!(matches!(err, NLPError::NetworkError(_)), "{:?}", err);
+    }
+}
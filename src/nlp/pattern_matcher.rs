@@ -4,8 +4,9 @@
 //! that don't require full AI processing. This significantly reduces latency and
 //! API usage for frequently used commands.
 
-use super::types::{NLPCommand, ActionType, StatusType, QueryType, Condition, ConditionExpression, ComparisonOperator};
+use super::types::{NLPCommand, ActionType, StatusType, QueryType, Condition, ComparisonOperator};
 use super::conditional::ConditionBuilder;
+use super::context::{DeadlineInference, TimeContext};
 use regex::Regex;
 use std::sync::LazyLock;
 
@@ -29,6 +30,22 @@ static ADD_TASK_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)^(?:(add|create|new)\s+)?task\s+(.+)$").unwrap()
 });
 
+// A deadline ending a new task: "buy milk tomorrow", "pay rent by friday", "call mom in 2 days"
+static TRAILING_DEADLINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(.+?)\s+((?:(?:by|due|before|on|for)\s+)?(?:today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|eod|eow|eom|eoy)|next\s+(?:week|month|year|monday|tuesday|wednesday|thursday|friday|saturday|sunday)|in\s+\d+\s+(?:minutes?|hours?|days?|weeks?))$").unwrap()
+});
+
+// "... to work", "... to the work category"
+static TRAILING_CATEGORY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(.+?)\s+to\s+(?:the\s+|my\s+)?([\w-]+)(\s+category)?$").unwrap()
+});
+
+// === Category Prefix Pattern ===
+// "work: ...", for a known category
+static CATEGORY_PREFIX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([\w-]+):\s*(.+)$").unwrap()
+});
+
 // === Record Addition Patterns ===
 // "add record ...", "log ...", "record ..."
 static ADD_RECORD_RE: LazyLock<Regex> = LazyLock::new(|| {
@@ -71,6 +88,12 @@ static LIST_STATUS_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)^(?:list|show)?\s*(done|pending|ongoing|cancelled|all)\s+tasks?$").unwrap()
 });
 
+// === Place Patterns ===
+// "when I'm at the office", "tasks at home", "what can I do at the office"
+static PLACE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:list|show)\s+)?(?:(?:my\s+)?tasks?\s+)?(?:(?:for\s+)?when\s+i(?:'m|\s+am)\s+|while\s+|what\s+can\s+i\s+do\s+)?at\s+(?:the\s+)?(\w+)$").unwrap()
+});
+
 // === Query Type Patterns ===
 // "overdue", "upcoming", "due today", "due tomorrow", "unscheduled"
 static QUERY_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| {
@@ -107,6 +130,13 @@ static PRIORITY_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)^(high|low|medium)\s+priority\s+tasks?$").unwrap()
 });
 
+// === Tag Patterns ===
+// "tasks tagged errands", "show tasks tagged with +quick"
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:show|list)\s+)?(?:(?:my|all)\s+)?(?:tasks?\s+)?tagged\s+(?:with\s+)?[+#]?([\w-]+)$")
+        .unwrap()
+});
+
 // === Date-based Quick Patterns ===
 // "today's tasks", "tomorrow's tasks"
 static DATE_QUICK_RE: LazyLock<Regex> = LazyLock::new(|| {
@@ -120,14 +150,14 @@ static SEARCH_RE: LazyLock<Regex> = LazyLock::new(|| {
 });
 
 // === Conditional Patterns ===
-// "if <category> has tasks then ...", "if category <category> is not empty then ..."
+// "if <category> category has tasks then ...", "if <category> is not empty then ..."
 static IF_CATEGORY_HAS_TASKS_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)^if\s+(\w+)\s+category\s+(?:has\s+tasks|is\s+not\s+empty)\s+then\s+(.+)$").unwrap()
+    Regex::new(r"(?i)^if\s+([\w-]+)(\s+category)?\s+(?:has\s+tasks|is\s+not\s+empty)\s+then\s+(.+)$").unwrap()
 });
 
-// "if <category> is empty then ...", "if category <category> has no tasks then ..."
+// "if <category> category is empty then ...", "if <category> has no tasks then ..."
 static IF_CATEGORY_EMPTY_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)^if\s+(\w+)\s+category\s+(?:is\s+empty|has\s+no\s+tasks)\s+then\s+(.+)$").unwrap()
+    Regex::new(r"(?i)^if\s+([\w-]+)(\s+category)?\s+(?:is\s+empty|has\s+no\s+tasks)\s+then\s+(.+)$").unwrap()
 });
 
 // "if task count is <operator> <number> then ..."
@@ -160,6 +190,12 @@ impl PatternMatcher {
     /// Returns PatternMatch::Matched if a simple pattern is found
     /// Returns PatternMatch::NeedsAI if input requires AI processing
     pub fn match_input(input: &str) -> PatternMatch {
+        Self::match_with_categories(input, &[])
+    }
+
+    /// Like `match_input`, also reading "<category>: ..." and "add ... to <category>" for the
+    /// known categories
+    pub fn match_with_categories(input: &str, known_categories: &[String]) -> PatternMatch {
         let input = input.trim();
         let input_lower = input.to_lowercase();
 
@@ -175,9 +211,6 @@ impl PatternMatcher {
         // Check for specific complex patterns that require AI
         // These patterns indicate the user wants to do something complex that AI should handle
         if input_lower.contains("deadline") ||
-           input_lower.contains(" to work category") ||
-           input_lower.contains(" to personal category") ||
-           input_lower.ends_with(" category") && input_lower.len() > "category".len() + 5 ||
            input_lower.contains("every day") ||
            input_lower.contains("every week") ||
            input_lower.contains("recurring") ||
@@ -194,14 +227,19 @@ impl PatternMatcher {
             });
         }
 
+        // === Task in a Category ===
+        if let Some(caps) = CATEGORY_PREFIX_RE.captures(input)
+            && let Some(category) = Self::known_category(&caps[1], known_categories)
+        {
+            let mut command = Self::new_task(&caps[2], known_categories);
+            command.category = Some(category);
+            return PatternMatch::Matched(command);
+        }
+
         // === Task Addition ===
         if let Some(caps) = ADD_TASK_RE.captures(input) {
-            let content = caps.get(2).map(|m| m.as_str().to_string()).unwrap_or_default();
-            return PatternMatch::Matched(NLPCommand {
-                action: ActionType::Task,
-                content,
-                ..Default::default()
-            });
+            let content = caps.get(2).map(|m| m.as_str()).unwrap_or_default();
+            return PatternMatch::Matched(Self::new_task(content, known_categories));
         }
 
         // === Record Addition ===
@@ -259,6 +297,18 @@ impl PatternMatcher {
             });
         }
 
+        // === List by Place ===
+        if let Some(caps) = PLACE_RE.captures(input) {
+            let mut filters = std::collections::HashMap::new();
+            filters.insert("place".to_string(), caps[1].to_lowercase());
+            return PatternMatch::Matched(NLPCommand {
+                action: ActionType::List,
+                content: String::new(),
+                filters,
+                ..Default::default()
+            });
+        }
+
         // === List by Category ===
         if let Some(caps) = LIST_CATEGORY_RE.captures(input) {
             if let Some(category) = caps.get(1) {
@@ -371,6 +421,20 @@ impl PatternMatcher {
             }
         }
 
+        // === Tag Pattern ===
+        if let Some(caps) = TAG_RE.captures(input)
+            && let Some(tag) = caps.get(1)
+        {
+            let mut filters = std::collections::HashMap::new();
+            filters.insert("tag".to_string(), tag.as_str().to_lowercase());
+            return PatternMatch::Matched(NLPCommand {
+                action: ActionType::List,
+                content: String::new(),
+                filters,
+                ..Default::default()
+            });
+        }
+
         // === Date Quick Pattern ===
         if let Some(caps) = DATE_QUICK_RE.captures(input) {
             if let Some(day) = caps.get(1) {
@@ -419,43 +483,28 @@ impl PatternMatcher {
 
         // === Very simple "add <content>" pattern ===
         if let Some(caps) = Regex::new(r"^add\s+(.+)$").unwrap().captures(input) {
-            let content = caps.get(1).unwrap().as_str().to_string();
-            return PatternMatch::Matched(NLPCommand {
-                action: ActionType::Task,
-                content,
-                ..Default::default()
-            });
+            let content = caps.get(1).unwrap().as_str();
+            return PatternMatch::Matched(Self::new_task(content, known_categories));
         }
 
         // === Conditional Patterns ===
         // Note: Complex conditionals may require AI processing for full accuracy
 
-        // "if <category> has tasks then ..." or "if <category> category is not empty then ..."
-        if let Some(caps) = IF_CATEGORY_HAS_TASKS_RE.captures(input) {
-            if let (Some(category), Some(then_command)) = (caps.get(1), caps.get(2)) {
-                let condition = ConditionBuilder::category_has_tasks(category.as_str());
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
-            }
+        // "if <category> category has tasks then ..." or "if <category> is not empty then ...",
+        // without the word "category" only for known categories
+        if let Some(caps) = IF_CATEGORY_HAS_TASKS_RE.captures(input)
+            && let Some(category) = Self::condition_category(&caps, known_categories)
+        {
+            let condition = ConditionBuilder::category_has_tasks(&category);
+            return PatternMatch::Matched(Self::conditional(&caps[3], condition, known_categories));
         }
 
-        // "if <category> is empty then ..." or "if <category> category has no tasks then ..."
-        if let Some(caps) = IF_CATEGORY_EMPTY_RE.captures(input) {
-            if let (Some(category), Some(then_command)) = (caps.get(1), caps.get(2)) {
-                let condition = ConditionBuilder::category_empty(category.as_str());
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
-            }
+        // "if <category> category is empty then ..." or "if <category> has no tasks then ..."
+        if let Some(caps) = IF_CATEGORY_EMPTY_RE.captures(input)
+            && let Some(category) = Self::condition_category(&caps, known_categories)
+        {
+            let condition = ConditionBuilder::category_empty(&category);
+            return PatternMatch::Matched(Self::conditional(&caps[3], condition, known_categories));
         }
 
         // "if task count is <operator> <number> then ..."
@@ -472,13 +521,9 @@ impl PatternMatcher {
                 };
                 let value: i32 = value_str.as_str().parse().unwrap_or(0);
                 let condition = ConditionBuilder::task_count(operator, value);
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
+                return PatternMatch::Matched(
+                    Self::conditional(then_command.as_str(), condition, known_categories),
+                );
             }
         }
 
@@ -498,13 +543,9 @@ impl PatternMatcher {
                     _ => return PatternMatch::NeedsAI,
                 };
                 let condition = ConditionBuilder::day_of_week(days);
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
+                return PatternMatch::Matched(
+                    Self::conditional(then_command.as_str(), condition, known_categories),
+                );
             }
         }
 
@@ -524,13 +565,9 @@ impl PatternMatcher {
                 let hour: i32 = hour_str.as_str().parse().unwrap_or(0);
                 let minute: i32 = minute_str.as_str().parse().unwrap_or(0);
                 let condition = ConditionBuilder::time_condition(operator, Some(hour), Some(minute));
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
+                return PatternMatch::Matched(
+                    Self::conditional(then_command.as_str(), condition, known_categories),
+                );
             }
         }
 
@@ -538,13 +575,9 @@ impl PatternMatcher {
         if let Some(caps) = IF_PREVIOUS_SUCCESS_RE.captures(input) {
             if let Some(then_command) = caps.get(1) {
                 let condition = ConditionBuilder::previous_success();
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
+                return PatternMatch::Matched(
+                    Self::conditional(then_command.as_str(), condition, known_categories),
+                );
             }
         }
 
@@ -552,13 +585,9 @@ impl PatternMatcher {
         if let Some(caps) = IF_PREVIOUS_FAILED_RE.captures(input) {
             if let Some(then_command) = caps.get(1) {
                 let condition = ConditionBuilder::previous_failed();
-                let then_content = then_command.as_str().to_string();
-                return PatternMatch::Matched(NLPCommand {
-                    action: ActionType::Task,
-                    content: then_content,
-                    condition: Some(condition),
-                    ..Default::default()
-                });
+                return PatternMatch::Matched(
+                    Self::conditional(then_command.as_str(), condition, known_categories),
+                );
             }
         }
 
@@ -566,6 +595,78 @@ impl PatternMatcher {
         PatternMatch::NeedsAI
     }
 
+    /// A new task, with a deadline and a category ending the content split off so it needs no
+    /// AI call
+    fn new_task(content: &str, known_categories: &[String]) -> NLPCommand {
+        let (content, mut deadline) = Self::split_deadline(content);
+        let (mut content, category) = Self::split_category(content, known_categories);
+        // "pay rent tomorrow to home" has the deadline before the category
+        if deadline.is_none() {
+            (content, deadline) = Self::split_deadline(content);
+        }
+        NLPCommand {
+            action: ActionType::Task,
+            content: content.to_string(),
+            category,
+            deadline,
+            ..Default::default()
+        }
+    }
+
+    fn split_deadline(content: &str) -> (&str, Option<String>) {
+        let Some(caps) = TRAILING_DEADLINE_RE.captures(content) else {
+            return (content, None);
+        };
+        let phrase = caps[2].to_lowercase();
+        // A bare day such as "tomorrow" is already a time string
+        let deadline = DeadlineInference::infer_deadline(&phrase, &TimeContext::new(), None)
+            .map(|inferred| inferred.deadline)
+            .unwrap_or_else(|| phrase.split_whitespace().last().unwrap_or_default().to_string());
+        (caps.get(1).unwrap().as_str(), Some(deadline))
+    }
+
+    // "... to work category" names a category outright, "... to work" only a known one
+    fn split_category<'a>(
+        content: &'a str,
+        known_categories: &[String],
+    ) -> (&'a str, Option<String>) {
+        let Some(caps) = TRAILING_CATEGORY_RE.captures(content) else {
+            return (content, None);
+        };
+        let category = if caps.get(3).is_some() {
+            Self::known_category(&caps[2], known_categories).or_else(|| Some(caps[2].to_string()))
+        } else {
+            Self::known_category(&caps[2], known_categories)
+        };
+        match category {
+            Some(category) => (caps.get(1).unwrap().as_str(), Some(category)),
+            None => (content, None),
+        }
+    }
+
+    /// The known category `name` stands for, ignoring case
+    /// The command after "then", a new task when it is not one the patterns know
+    fn conditional(then: &str, condition: Condition, known_categories: &[String]) -> NLPCommand {
+        let mut command = match Self::match_with_categories(then, known_categories) {
+            PatternMatch::Matched(command) if command.condition.is_none() => command,
+            _ => Self::new_task(then, known_categories),
+        };
+        command.condition = Some(condition);
+        command
+    }
+
+    // The category of a condition, which needs the word "category" when it is not known
+    fn condition_category(caps: &regex::Captures, known_categories: &[String]) -> Option<String> {
+        if caps.get(2).is_some() {
+            return Some(caps[1].to_string());
+        }
+        Self::known_category(&caps[1], known_categories)
+    }
+
+    fn known_category(name: &str, known_categories: &[String]) -> Option<String> {
+        known_categories.iter().find(|c| c.eq_ignore_ascii_case(name)).cloned()
+    }
+
     /// Check if input might be matchable by patterns
     /// (useful for caching decisions)
     pub fn is_simple_input(input: &str) -> bool {
@@ -602,12 +703,12 @@ impl PatternMatcher {
     /// Get statistics about pattern matching
     pub fn stats() -> PatternMatcherStats {
         PatternMatcherStats {
-            total_patterns: 26,
+            total_patterns: 27,
             patterns_checked: vec![
                 "add_task", "add_record", "complete", "delete", "list_all",
                 "list_records", "list_category", "list_status", "query_type",
                 "update", "help", "clear", "single_number", "simple_add",
-                "search", "priority", "date_quick", "set_category",
+                "search", "priority", "tag", "date_quick", "set_category",
                 "if_category_has_tasks", "if_category_empty", "if_task_count",
                 "if_day_of_week", "if_time", "if_previous_success", "if_previous_failed",
             ],
@@ -668,6 +769,89 @@ mod tests {
         }
     }
 
+    fn matched_task(input: &str) -> (String, Option<String>) {
+        match PatternMatcher::match_input(input) {
+            PatternMatch::Matched(cmd) => {
+                assert_eq!(cmd.action, ActionType::Task);
+                (cmd.content, cmd.deadline)
+            }
+            other => panic!("{} did not match: {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn test_match_task_with_deadline() {
+        let deadline = |d: &str| Some(d.to_string());
+        assert_eq!(matched_task("task buy milk tomorrow"), ("buy milk".to_string(), deadline("tomorrow")));
+        assert_eq!(matched_task("task pay rent by Friday"), ("pay rent".to_string(), deadline("friday")));
+        assert_eq!(matched_task("add call mom on sunday"), ("call mom".to_string(), deadline("sunday")));
+        assert_eq!(matched_task("task file taxes in 3 days"), ("file taxes".to_string(), deadline("+3d")));
+        assert_eq!(matched_task("create task plan trip next month"), ("plan trip".to_string(), deadline("+30d")));
+        assert_eq!(matched_task("task read for today"), ("read".to_string(), deadline("today")));
+
+        // Only a deadline at the end is split off
+        assert_eq!(matched_task("task tomorrow"), ("tomorrow".to_string(), None));
+        assert_eq!(matched_task("task plan monday standup"), ("plan monday standup".to_string(), None));
+    }
+
+    fn matched_category(input: &str) -> (String, Option<String>, Option<String>) {
+        let known = vec!["work".to_string(), "Groceries".to_string()];
+        match PatternMatcher::match_with_categories(input, &known) {
+            PatternMatch::Matched(cmd) => {
+                assert_eq!(cmd.action, ActionType::Task);
+                (cmd.content, cmd.category, cmd.deadline)
+            }
+            other => panic!("{} did not match: {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn test_match_conditional() {
+        let known = vec!["work".to_string()];
+        let PatternMatch::Matched(command) =
+            PatternMatcher::match_with_categories("if work is empty then add plan next sprint", &known)
+        else {
+            panic!("conditional not matched");
+        };
+        assert_eq!(command.action, ActionType::Task);
+        assert_eq!(command.content, "plan next sprint");
+        assert_eq!(command.condition, Some(ConditionBuilder::category_empty("work")));
+
+        // The then clause is read as a command of its own
+        let PatternMatch::Matched(command) = PatternMatcher::match_with_categories(
+            "if home category has tasks then list tasks",
+            &known,
+        ) else {
+            panic!("conditional not matched");
+        };
+        assert_eq!(command.action, ActionType::List);
+        assert_eq!(command.condition, Some(ConditionBuilder::category_has_tasks("home")));
+
+        // Unknown categories need the word "category"
+        assert!(matches!(
+            PatternMatcher::match_with_categories("if home is empty then add x", &known),
+            PatternMatch::NeedsAI
+        ));
+    }
+
+    #[test]
+    fn test_match_task_with_category() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(matched_category("add review docs to work"), ("review docs".to_string(), some("work"), None));
+        assert_eq!(matched_category("add milk to groceries"), ("milk".to_string(), some("Groceries"), None));
+        assert_eq!(matched_category("task call bob to the side category"), ("call bob".to_string(), some("side"), None));
+        assert_eq!(matched_category("work: send report by friday"), ("send report".to_string(), some("work"), some("friday")));
+        assert_eq!(matched_category("add pay rent tomorrow to work"), ("pay rent".to_string(), some("work"), some("tomorrow")));
+        assert_eq!(matched_category("add milk to groceries tomorrow"), ("milk".to_string(), some("Groceries"), some("tomorrow")));
+
+        // Unknown categories stay part of the content
+        assert_eq!(matched_category("add talk to bob"), ("talk to bob".to_string(), None, None));
+        assert!(matches!(
+            PatternMatcher::match_with_categories("home: fix the sink", &[]),
+            PatternMatch::NeedsAI
+        ));
+    }
+
     // === Record Addition Tests ===
 
     #[test]
@@ -906,6 +1090,21 @@ mod tests {
         }
     }
 
+    // === Place Tests ===
+
+    #[test]
+    fn test_match_place() {
+        for input in ["when I'm at the office", "tasks at home", "what can I do at the Office"] {
+            let result = PatternMatcher::match_input(input);
+            let PatternMatch::Matched(cmd) = result else {
+                panic!("{} should match a place", input);
+            };
+            assert_eq!(cmd.action, ActionType::List);
+            assert!(matches!(cmd.filters.get("place").map(String::as_str), Some("office" | "home")));
+        }
+        assert!(!matches!(PatternMatcher::match_input("meet bob at noon"), PatternMatch::Matched(_)));
+    }
+
     // === Query Type Tests ===
 
     #[test]
@@ -968,6 +1167,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_match_tagged() {
+        for input in ["tasks tagged errands", "show tasks tagged with +Errands", "tagged #errands"] {
+            let result = PatternMatcher::match_input(input);
+            if let PatternMatch::Matched(cmd) = result {
+                assert_eq!(cmd.action, ActionType::List);
+                assert_eq!(cmd.filters.get("tag").map(String::as_str), Some("errands"));
+            } else {
+                panic!("'{}' did not match", input);
+            }
+        }
+    }
+
     // === Update Tests ===
 
     #[test]
@@ -1083,11 +1295,6 @@ mod tests {
         assert!(matches!(result, PatternMatch::NeedsAI));
     }
 
-    #[test]
-    fn test_needs_ai_with_category() {
-        let result = PatternMatcher::match_input("add review documentation to work category");
-        assert!(matches!(result, PatternMatch::NeedsAI));
-    }
 
     // === Edge Cases ===
 
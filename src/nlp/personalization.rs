@@ -4,9 +4,21 @@
 //! users' preferred terminology, phrasing, and command patterns over time.
 
 use super::types::{NLPCommand, ActionType};
+use regex::Regex;
 use rusqlite::params;
 use std::path::Path;
 use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Added tasks and records whose categories are kept
+const RECENT_CATEGORIES: usize = 10;
+/// A category shared by this many added tasks and records in a row is proposed for the next one
+const CONTEXT_COMMANDS: usize = 3;
+
+// "by gym I mean category health"
+static CLARIFICATION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*by\s+(.+?)\s+i\s+mean\s+(?:the\s+)?(?:category\s+)?([\w-]+)(?:\s+category)?\s*[.!]?\s*$").unwrap()
+});
 
 /// User profile tracking individual patterns
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -145,6 +157,40 @@ impl PersonalizationDB {
             [],
         ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create shortcuts table: {}", e)))?;
 
+        // Create recent_categories table, the category of each task and record added, newest last
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS recent_categories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id TEXT NOT NULL,
+                category TEXT NOT NULL
+            )",
+            [],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create recent_categories table: {}", e)))?;
+
+        // Create clarifications table, the category the user said a phrase stands for
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS clarifications (
+                user_id TEXT NOT NULL,
+                phrase TEXT NOT NULL,
+                category TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (user_id, phrase)
+            )",
+            [],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create clarifications table: {}", e)))?;
+
+        // Create command_history table, the inputs that ran and how often
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS command_history (
+                user_id TEXT NOT NULL,
+                input TEXT NOT NULL,
+                usage_count INTEGER NOT NULL DEFAULT 1,
+                last_used INTEGER NOT NULL,
+                PRIMARY KEY (user_id, input)
+            )",
+            [],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to create command_history table: {}", e)))?;
+
         // Create indexes for faster lookups
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_cmd_patterns_user ON command_patterns(user_id)",
@@ -334,8 +380,7 @@ impl PersonalizationDB {
         // Store shortcut in lowercase for case-insensitive matching
         let shortcut_lower = shortcut.to_lowercase();
 
-        let command_data = serde_json::to_vec(command)
-            .map_err(|e| crate::nlp::NLPError::SerializationError(serde_json::Error::from(e)))?;
+        let command_data = crate::nlp::schema::encode_command_bytes(command)?;
 
         self.conn.execute(
             "INSERT OR REPLACE INTO shortcuts (user_id, shortcut, command_data, usage_count, confidence, created_at, last_used_at)
@@ -362,7 +407,7 @@ impl PersonalizationDB {
             params![&self.user_id, shortcut_lower],
             |row| {
                 let data: Vec<u8> = row.get(2)?;
-                let command: NLPCommand = serde_json::from_slice(&data)
+                let command = crate::nlp::schema::decode_command_bytes(&data)
                     .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
                 Ok((
                     row.get::<_, i64>(0)?,
@@ -400,7 +445,7 @@ impl PersonalizationDB {
 
         let shortcuts = stmt.query_map(params![&self.user_id], |row| {
             let data: Vec<u8> = row.get(1)?;
-            let command: NLPCommand = serde_json::from_slice(&data)
+            let command = crate::nlp::schema::decode_command_bytes(&data)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
             Ok(PersonalizedShortcut {
                 shortcut: row.get(0)?,
@@ -466,6 +511,91 @@ impl PersonalizationDB {
         })
     }
 
+    /// Remember the category of an added task or record, empty for none. Only the last
+    /// RECENT_CATEGORIES are kept.
+    pub fn record_recent_category(&self, category: &str) -> Result<(), crate::nlp::NLPError> {
+        self.conn.execute(
+            "INSERT INTO recent_categories (user_id, category) VALUES (?1, ?2)",
+            params![&self.user_id, category],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to record category: {}", e)))?;
+        self.conn.execute(
+            "DELETE FROM recent_categories WHERE user_id = ?1 AND id NOT IN
+             (SELECT id FROM recent_categories WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![&self.user_id, RECENT_CATEGORIES as i64],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to trim categories: {}", e)))?;
+        Ok(())
+    }
+
+    /// The categories of the last `limit` added tasks and records, newest first
+    pub fn recent_categories(&self, limit: usize) -> Vec<String> {
+        let Ok(mut stmt) = self.conn.prepare(
+            "SELECT category FROM recent_categories WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2",
+        ) else {
+            return Vec::new();
+        };
+        stmt.query_map(params![&self.user_id, limit as i64], |row| row.get(0))
+            .map(|rows| rows.filter_map(Result::ok).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remember that a phrase stands for a category, replacing an earlier answer
+    pub fn remember_clarification(&self, phrase: &str, category: &str) -> Result<(), crate::nlp::NLPError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Time error: {}", e)))?
+            .as_secs() as i64;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO clarifications (user_id, phrase, category, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![&self.user_id, normalize_phrase(phrase), category, now],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to store clarification: {}", e)))?;
+        Ok(())
+    }
+
+    /// All remembered phrases with their categories, by phrase
+    pub fn clarifications(&self) -> Result<Vec<(String, String)>, crate::nlp::NLPError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT phrase, category FROM clarifications WHERE user_id = ?1 ORDER BY phrase",
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to prepare query: {}", e)))?;
+        let rows = stmt.query_map(params![&self.user_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to query clarifications: {}", e)))?;
+        Ok(rows.filter_map(Result::ok).collect())
+    }
+
+    /// Remember an input that ran, counting how often it was used
+    pub fn record_input(&self, input: &str) -> Result<(), crate::nlp::NLPError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Time error: {}", e)))?
+            .as_secs() as i64;
+        self.conn.execute(
+            "INSERT INTO command_history (user_id, input, usage_count, last_used) VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(user_id, input) DO UPDATE SET usage_count = usage_count + 1, last_used = ?3",
+            params![&self.user_id, input.trim(), now],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to store history: {}", e)))?;
+        Ok(())
+    }
+
+    /// The `limit` inputs used most, most used first, the latest first among equals
+    pub fn command_history(&self, limit: usize) -> Result<Vec<String>, crate::nlp::NLPError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT input FROM command_history WHERE user_id = ?1
+             ORDER BY usage_count DESC, last_used DESC, input LIMIT ?2",
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to prepare query: {}", e)))?;
+        let rows = stmt.query_map(params![&self.user_id, limit as i64], |row| row.get(0))
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to query history: {}", e)))?;
+        Ok(rows.filter_map(Result::ok).collect())
+    }
+
+    /// Forget a remembered phrase, false when there was none
+    pub fn forget_clarification(&self, phrase: &str) -> Result<bool, crate::nlp::NLPError> {
+        let rows = self.conn.execute(
+            "DELETE FROM clarifications WHERE user_id = ?1 AND phrase = ?2",
+            params![&self.user_id, normalize_phrase(phrase)],
+        ).map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to forget clarification: {}", e)))?;
+        Ok(rows > 0)
+    }
+
     /// Clear all personalization data for the user
     pub fn clear(&self) -> Result<(), crate::nlp::NLPError> {
         self.conn.execute("DELETE FROM command_patterns WHERE user_id = ?1", params![&self.user_id])
@@ -477,6 +607,15 @@ impl PersonalizationDB {
         self.conn.execute("DELETE FROM preferred_categories WHERE user_id = ?1", params![&self.user_id])
             .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear categories: {}", e)))?;
 
+        self.conn.execute("DELETE FROM recent_categories WHERE user_id = ?1", params![&self.user_id])
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear categories: {}", e)))?;
+
+        self.conn.execute("DELETE FROM clarifications WHERE user_id = ?1", params![&self.user_id])
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear clarifications: {}", e)))?;
+
+        self.conn.execute("DELETE FROM command_history WHERE user_id = ?1", params![&self.user_id])
+            .map_err(|e| crate::nlp::NLPError::ConfigError(format!("Failed to clear history: {}", e)))?;
+
         Ok(())
     }
 
@@ -651,6 +790,78 @@ impl PersonalizationEngine {
         Ok(())
     }
 
+    /// Remember the category of a task or record that was added
+    pub fn record_category(&self, command: &NLPCommand) -> Result<(), crate::nlp::NLPError> {
+        if let Some(ref db) = self.db
+            && matches!(command.action, ActionType::Task | ActionType::Record)
+        {
+            db.record_recent_category(command.category.as_deref().unwrap_or_default())?;
+        }
+        Ok(())
+    }
+
+    /// The category shared by the last CONTEXT_COMMANDS added tasks and records, to propose for
+    /// the next one
+    pub fn context_category(&self) -> Option<String> {
+        let recent = self.db.as_ref()?.recent_categories(CONTEXT_COMMANDS);
+        let first = recent.first()?;
+        let shared = recent.len() == CONTEXT_COMMANDS
+            && !first.is_empty()
+            && recent.iter().all(|category| category == first);
+        shared.then(|| first.clone())
+    }
+
+    /// Remember the category the user said a phrase stands for
+    pub fn remember_clarification(&self, phrase: &str, category: &str) -> Result<(), crate::nlp::NLPError> {
+        if let Some(ref db) = self.db {
+            db.remember_clarification(phrase, category)?;
+        }
+        Ok(())
+    }
+
+    /// All remembered phrases with their categories
+    pub fn clarifications(&self) -> Result<Vec<(String, String)>, crate::nlp::NLPError> {
+        match self.db {
+            Some(ref db) => db.clarifications(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Forget a remembered phrase, false when there was none
+    pub fn forget_clarification(&self, phrase: &str) -> Result<bool, crate::nlp::NLPError> {
+        match self.db {
+            Some(ref db) => db.forget_clarification(phrase),
+            None => Ok(false),
+        }
+    }
+
+    /// Remember an input that ran, for completions
+    pub fn record_input(&self, input: &str) -> Result<(), crate::nlp::NLPError> {
+        if let Some(ref db) = self.db {
+            db.record_input(input)?;
+        }
+        Ok(())
+    }
+
+    /// The inputs used most, most used first
+    pub fn command_history(&self, limit: usize) -> Result<Vec<String>, crate::nlp::NLPError> {
+        match self.db {
+            Some(ref db) => db.command_history(limit),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Give tasks and records without a category the category of a remembered phrase in their
+    /// content, including those of a compound command
+    pub fn apply_clarifications(&self, command: &mut NLPCommand) {
+        let Ok(clarifications) = self.clarifications() else {
+            return;
+        };
+        if !clarifications.is_empty() {
+            apply_clarifications(&clarifications, command);
+        }
+    }
+
     /// Get personalized command for input
     pub fn get_personalized_command(&self, input: &str) -> Option<NLPCommand> {
         self.matcher.match_pattern(input)
@@ -716,6 +927,34 @@ impl Default for PersonalizationEngine {
     }
 }
 
+/// The phrase and category of a clarification like "by gym I mean category health"
+pub fn parse_clarification(input: &str) -> Option<(String, String)> {
+    let captures = CLARIFICATION_RE.captures(input)?;
+    let phrase = normalize_phrase(&captures[1]);
+    (!phrase.is_empty()).then(|| (phrase, captures[2].to_string()))
+}
+
+// Lowercase words, so "The Gym!" and "the gym" are the same phrase
+fn normalize_phrase(phrase: &str) -> String {
+    phrase.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn apply_clarifications(clarifications: &[(String, String)], command: &mut NLPCommand) {
+    if command.category.is_none() && matches!(command.action, ActionType::Task | ActionType::Record) {
+        let content = format!(" {} ", normalize_phrase(&command.content));
+        command.category = clarifications.iter()
+            .find(|(phrase, _)| content.contains(&format!(" {} ", phrase)))
+            .map(|(_, category)| category.clone());
+    }
+    for command in command.compound_commands.iter_mut().flatten() {
+        apply_clarifications(clarifications, command);
+    }
+}
+
 /// Get current user ID for personalization
 pub fn get_user_id() -> String {
     std::env::var("USER")
@@ -983,4 +1222,96 @@ mod tests {
         assert_eq!(stats.total_patterns, 0);
         assert_eq!(stats.user_id, "test");
     }
+
+    #[test]
+    fn test_context_category() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let engine = PersonalizationEngine::with_db(temp_file.path(), "test".to_string()).unwrap();
+        let added = |category: Option<&str>| {
+            let command = NLPCommand {
+                action: ActionType::Task,
+                category: category.map(String::from),
+                ..Default::default()
+            };
+            engine.record_category(&command).unwrap();
+        };
+
+        added(Some("work"));
+        added(Some("work"));
+        assert_eq!(engine.context_category(), None);
+        added(Some("work"));
+        assert_eq!(engine.context_category(), Some("work".to_string()));
+
+        // Listing does not count, a task without a category breaks the run
+        let list = NLPCommand { action: ActionType::List, ..Default::default() };
+        engine.record_category(&list).unwrap();
+        assert_eq!(engine.context_category(), Some("work".to_string()));
+        added(None);
+        assert_eq!(engine.context_category(), None);
+    }
+
+    #[test]
+    fn test_command_history() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let engine = PersonalizationEngine::with_db(temp_file.path(), "test".to_string()).unwrap();
+        engine.record_input("list work tasks").unwrap();
+        engine.record_input("add task standup notes").unwrap();
+        engine.record_input(" list work tasks ").unwrap();
+        assert_eq!(
+            engine.command_history(10).unwrap(),
+            vec!["list work tasks".to_string(), "add task standup notes".to_string()]
+        );
+        assert_eq!(engine.command_history(1).unwrap().len(), 1);
+
+        engine.clear().unwrap();
+        assert!(engine.command_history(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clarifications() {
+        assert_eq!(
+            parse_clarification("By the Gym I mean category health"),
+            Some(("the gym".to_string(), "health".to_string()))
+        );
+        assert_eq!(
+            parse_clarification("by gym i mean the health category."),
+            Some(("gym".to_string(), "health".to_string()))
+        );
+        assert_eq!(parse_clarification("add gym session"), None);
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let engine = PersonalizationEngine::with_db(temp_file.path(), "test".to_string()).unwrap();
+        engine.remember_clarification("gym", "fitness").unwrap();
+        engine.remember_clarification("Gym", "health").unwrap();
+        assert_eq!(engine.clarifications().unwrap(), vec![("gym".to_string(), "health".to_string())]);
+
+        let mut command = NLPCommand {
+            action: ActionType::Task,
+            content: "Go to the gym".to_string(),
+            compound_commands: Some(vec![
+                NLPCommand { action: ActionType::Record, content: "gymnastics".to_string(), ..Default::default() },
+                NLPCommand { action: ActionType::List, content: "gym".to_string(), ..Default::default() },
+            ]),
+            ..Default::default()
+        };
+        engine.apply_clarifications(&mut command);
+        assert_eq!(command.category.as_deref(), Some("health"));
+        let compound = command.compound_commands.as_ref().unwrap();
+        assert_eq!(compound[0].category, None);
+        assert_eq!(compound[1].category, None);
+
+        // An explicit category wins
+        let mut command = NLPCommand {
+            action: ActionType::Task,
+            content: "gym".to_string(),
+            category: Some("work".to_string()),
+            ..Default::default()
+        };
+        engine.apply_clarifications(&mut command);
+        assert_eq!(command.category.as_deref(), Some("work"));
+
+        assert!(engine.forget_clarification("GYM").unwrap());
+        assert!(!engine.forget_clarification("gym").unwrap());
+        assert!(engine.clarifications().unwrap().is_empty());
+    }
 }
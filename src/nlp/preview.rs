@@ -6,6 +6,7 @@
 use super::types::*;
 use super::mapper::CommandMapper;
 use std::io::{self, Write};
+use crate::actions::display::is_plain;
 
 /// Result of user confirmation prompt
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +17,8 @@ pub enum ConfirmationResult {
     Cancelled,
     /// User wants to edit the command
     Edit,
+    /// User wants another category than the one proposed from context
+    ChangeCategory,
 }
 
 /// Represents a command ready for preview
@@ -29,6 +32,8 @@ pub struct PreviewCommand {
     pub args: Vec<String>,
     /// Command type
     pub command_type: String,
+    /// Category proposed from the previous commands, which the user can change
+    pub context_category: Option<String>,
 }
 
 impl PreviewCommand {
@@ -43,14 +48,24 @@ impl PreviewCommand {
             description,
             args,
             command_type,
+            context_category: None,
         }
     }
 
+    /// Mark the category of the command as proposed from context
+    pub fn with_context_category(mut self, category: &str) -> Self {
+        self.context_category = Some(category.to_string());
+        self
+    }
+
     /// Format for display
     pub fn format(&self) -> String {
         let mut output = format!("{}. {}\n", self.index + 1, self.description);
         output.push_str(&format!("   Type: {}\n", self.command_type));
         output.push_str(&format!("   Command: {}\n", self.args.join(" ")));
+        if let Some(category) = &self.context_category {
+            output.push_str(&format!("   Category: {} (from context)\n", category));
+        }
         output
     }
 }
@@ -145,7 +160,7 @@ pub struct PreviewManager {
 impl PreviewManager {
     /// Create a new preview manager
     pub fn new(enabled: bool, auto_confirm: bool) -> Self {
-        let formatter = PreviewFormatter::new(true, true);
+        let formatter = PreviewFormatter::new(true, !is_plain());
         Self {
             enabled,
             auto_confirm,
@@ -173,8 +188,8 @@ impl PreviewManager {
             return Ok(ConfirmationResult::Confirmed);
         }
 
-        // Prompt for confirmation
-        self.prompt_confirmation()
+        // Prompt for confirmation, offering to change a category proposed from context
+        self.prompt_confirmation(cmd.context_category.is_some())
     }
 
     /// Preview and confirm compound commands
@@ -197,7 +212,7 @@ impl PreviewManager {
         }
 
         // Prompt for confirmation
-        self.prompt_confirmation()
+        self.prompt_confirmation(false)
     }
 
     /// Show execution summary
@@ -206,8 +221,12 @@ impl PreviewManager {
     }
 
     /// Prompt user for confirmation
-    fn prompt_confirmation(&self) -> Result<ConfirmationResult, String> {
-        print!("Execute these commands? [Y/n/e] ");
+    fn prompt_confirmation(&self, can_change_category: bool) -> Result<ConfirmationResult, String> {
+        if can_change_category {
+            print!("Execute these commands? [Y/n/e/c to change the category] ");
+        } else {
+            print!("Execute these commands? [Y/n/e] ");
+        }
 
         io::stdout().flush()
             .map_err(|e| format!("Failed to flush stdout: {}", e))?;
@@ -219,13 +238,15 @@ impl PreviewManager {
         let input = input.trim().to_lowercase();
         println!();
 
-        match input.as_str() {
-            "" | "y" | "yes" => Ok(ConfirmationResult::Confirmed),
-            "n" | "no" => Ok(ConfirmationResult::Cancelled),
-            "e" | "edit" => Ok(ConfirmationResult::Edit),
-            _ => {
+        match parse_confirmation(&input, can_change_category) {
+            Some(result) => Ok(result),
+            None if can_change_category => {
+                println!("Invalid input. Please enter Y (yes), N (no), E (edit) or C (category).");
+                self.prompt_confirmation(can_change_category)
+            }
+            None => {
                 println!("Invalid input. Please enter Y (yes), N (no), or E (edit).");
-                self.prompt_confirmation()
+                self.prompt_confirmation(can_change_category)
             }
         }
     }
@@ -236,11 +257,22 @@ impl Default for PreviewManager {
         Self {
             enabled: true,
             auto_confirm: false,
-            formatter: PreviewFormatter::new(true, true),
+            formatter: PreviewFormatter::new(true, !is_plain()),
         }
     }
 }
 
+/// The answer to the confirmation prompt, None when it is not one
+fn parse_confirmation(input: &str, can_change_category: bool) -> Option<ConfirmationResult> {
+    match input {
+        "" | "y" | "yes" => Some(ConfirmationResult::Confirmed),
+        "n" | "no" => Some(ConfirmationResult::Cancelled),
+        "e" | "edit" => Some(ConfirmationResult::Edit),
+        "c" | "category" if can_change_category => Some(ConfirmationResult::ChangeCategory),
+        _ => None,
+    }
+}
+
 /// Convert NLPCommands to PreviewCommands
 pub fn commands_to_previews(commands: &[NLPCommand]) -> Vec<PreviewCommand> {
     commands.iter()
@@ -287,6 +319,22 @@ mod tests {
         assert!(formatted.contains("Command:"));
     }
 
+    #[test]
+    fn test_context_category() {
+        let cmd = NLPCommand {
+            action: ActionType::Task,
+            content: "test".to_string(),
+            category: Some("work".to_string()),
+            ..Default::default()
+        };
+        let preview = PreviewCommand::from_nlp_command(&cmd, 0).with_context_category("work");
+        assert!(preview.format().contains("Category: work (from context)"));
+
+        assert_eq!(parse_confirmation("c", true), Some(ConfirmationResult::ChangeCategory));
+        assert_eq!(parse_confirmation("c", false), None);
+        assert_eq!(parse_confirmation("", true), Some(ConfirmationResult::Confirmed));
+    }
+
     #[test]
     fn test_commands_to_previews() {
         let commands = vec![
@@ -356,6 +404,7 @@ mod tests {
                 description: "Test command".to_string(),
                 args: vec!["task".to_string(), "test".to_string()],
                 command_type: "Task".to_string(),
+                context_category: None,
             },
         ];
 
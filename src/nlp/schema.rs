@@ -0,0 +1,118 @@
+//! Versioning of the NLPCommand JSON
+//!
+//! Commands kept on disk (response cache, learned corrections, shortcuts) carry a
+//! `schema_version`. Entries written by older versions of tascli, and the arguments sent
+//! back by the model, are converted step by step to the current layout when read.
+
+use serde_json::{Map, Value};
+use super::types::{NLPCommand, NLPError, NLPResult};
+
+/// Version of the NLPCommand JSON written by this build
+pub const SCHEMA_VERSION: u64 = 2;
+
+const VERSION_KEY: &str = "schema_version";
+
+/// Serialize a command, tagged with the current schema version
+pub fn encode_command(command: &NLPCommand) -> NLPResult<Value> {
+    let mut value = serde_json::to_value(command)?;
+    if let Value::Object(map) = &mut value {
+        map.insert(VERSION_KEY.to_string(), SCHEMA_VERSION.into());
+    }
+    Ok(value)
+}
+
+/// Serialize a command to bytes for a BLOB column
+pub fn encode_command_bytes(command: &NLPCommand) -> NLPResult<Vec<u8>> {
+    Ok(serde_json::to_vec(&encode_command(command)?)?)
+}
+
+/// Read a command written with any schema version up to the current one
+///
+/// JSON without a version is treated as version 1.
+pub fn decode_command(mut value: Value) -> NLPResult<NLPCommand> {
+    let version = value.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(1);
+    if version > SCHEMA_VERSION {
+        return Err(NLPError::ParseError(format!(
+            "Command schema version {} is newer than the supported version {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+    let Value::Object(map) = &mut value else {
+        return Err(NLPError::ParseError("Command is not a JSON object".to_string()));
+    };
+    if version < 2 {
+        v1_to_v2(map);
+    }
+    map.remove(VERSION_KEY);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Read a command from the bytes of a BLOB column
+pub fn decode_command_bytes(data: &[u8]) -> NLPResult<NLPCommand> {
+    decode_command(serde_json::from_slice(data)?)
+}
+
+// Version 1 was unversioned and could leave out (or null) the filter and modification maps,
+// at the top level and in compound commands
+fn v1_to_v2(map: &mut Map<String, Value>) {
+    for key in ["filters", "modifications"] {
+        if !map.get(key).is_some_and(Value::is_object) {
+            map.insert(key.to_string(), Value::Object(Map::new()));
+        }
+    }
+    if let Some(Value::Array(commands)) = map.get_mut("compound_commands") {
+        for command in commands {
+            if let Value::Object(command) = command {
+                v1_to_v2(command);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::ActionType;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let command = NLPCommand {
+            action: ActionType::Task,
+            content: "review PR".to_string(),
+            category: Some("work".to_string()),
+            ..Default::default()
+        };
+        let value = encode_command(&command).unwrap();
+        assert_eq!(value[VERSION_KEY], json!(SCHEMA_VERSION));
+
+        let decoded = decode_command_bytes(&encode_command_bytes(&command).unwrap()).unwrap();
+        assert_eq!(decoded.action, ActionType::Task);
+        assert_eq!(decoded.content, "review PR");
+        assert_eq!(decoded.category.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_migrate_v1() {
+        let v1 = json!({
+            "action": "task",
+            "content": "buy milk",
+            "filters": null,
+            "compound_commands": [{"action": "list", "content": ""}],
+        });
+        let command = decode_command(v1).unwrap();
+        assert_eq!(command.content, "buy milk");
+        assert!(command.filters.is_empty());
+        assert!(command.modifications.is_empty());
+        let nested = &command.compound_commands.unwrap()[0];
+        assert_eq!(nested.action, ActionType::List);
+        assert!(nested.filters.is_empty());
+    }
+
+    #[test]
+    fn test_reject_newer_version() {
+        let newer = json!({"action": "task", "content": "x", "schema_version": SCHEMA_VERSION + 1});
+        assert!(decode_command(newer).is_err());
+        assert!(decode_command(json!("task")).is_err());
+    }
+}
@@ -1,8 +1,51 @@
 //! Sequential command execution with state management and error handling
 
 use super::types::*;
+use super::cancel;
 use super::conditional::ConditionalExecutor;
 use rusqlite::Connection;
+use std::future::Future;
+use std::panic;
+use tokio::task::JoinSet;
+
+/// Steps of a parallel compound command prepared at once
+pub const MAX_PARALLEL: usize = 4;
+
+/// Runs the steps of a parallel compound command that wait on the provider, such as
+/// interpreting each clause, up to MAX_PARALLEL at once. The outputs are in the order of
+/// `steps`. Ctrl-C drops the steps still running.
+pub async fn prepare_parallel<T, F>(steps: Vec<F>) -> NLPResult<Vec<T>>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    cancel::abortable(run_bounded(steps)).await
+}
+
+// Dropped mid-flight, the JoinSet aborts the steps still running
+async fn run_bounded<T, F>(steps: Vec<F>) -> NLPResult<Vec<T>>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut outputs: Vec<Option<T>> = steps.iter().map(|_| None).collect();
+    let mut waiting = steps.into_iter().enumerate();
+    let mut running = JoinSet::new();
+    loop {
+        while running.len() < MAX_PARALLEL {
+            let Some((index, step)) = waiting.next() else {
+                break;
+            };
+            running.spawn(async move { (index, step.await) });
+        }
+        match running.join_next().await {
+            Some(Ok((index, output))) => outputs[index] = Some(output),
+            Some(Err(e)) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+            Some(Err(_)) => return Err(NLPError::Cancelled),
+            None => return Ok(outputs.into_iter().flatten().collect()),
+        }
+    }
+}
 
 /// Executor for sequential compound commands
 pub struct SequentialExecutor {
@@ -35,6 +78,10 @@ impl SequentialExecutor {
         }
 
         match execution_mode {
+            // Plain sequential runs follow the executor's own setting
+            CompoundExecutionMode::Sequential if !self.stop_on_error => {
+                self.execute_continue_on_error(conn, commands)
+            },
             CompoundExecutionMode::Sequential | CompoundExecutionMode::StopOnError => {
                 self.execute_stop_on_error(conn, commands)
             },
@@ -63,6 +110,10 @@ impl SequentialExecutor {
         let mut results = Vec::new();
 
         for (index, command) in commands.iter().enumerate() {
+            // Ctrl-C stops before the next command
+            if cancel::requested() {
+                return Ok(ExecutionSummary::new(commands.len(), results, context).mark_cancelled());
+            }
             if self.verbose {
                 println!("Executing command {}/{}...", index + 1, commands.len());
             }
@@ -98,6 +149,10 @@ impl SequentialExecutor {
         let mut results = Vec::new();
 
         for (index, command) in commands.iter().enumerate() {
+            // Ctrl-C stops before the next command
+            if cancel::requested() {
+                return Ok(ExecutionSummary::new(commands.len(), results, context).mark_cancelled());
+            }
             if self.verbose {
                 println!("Executing command {}/{}...", index + 1, commands.len());
             }
@@ -122,7 +177,10 @@ impl SequentialExecutor {
         Ok(ExecutionSummary::new(commands.len(), results, context))
     }
 
-    /// Execute commands in parallel (simplified - executes sequentially but independently)
+    /// Execute the commands of a parallel compound command, once `prepare_parallel` has
+    /// interpreted them. Writes go through the caller's connection inside its transaction, one
+    /// at a time in command order. No context is carried between them and a failure does not
+    /// stop the others.
     fn execute_parallel(
         &self,
         conn: &Connection,
@@ -132,12 +190,16 @@ impl SequentialExecutor {
         let mut results = Vec::new();
 
         for (index, command) in commands.iter().enumerate() {
+            // Ctrl-C stops before the next command
+            if cancel::requested() {
+                return Ok(ExecutionSummary::new(commands.len(), results, context).mark_cancelled());
+            }
             if self.verbose {
                 println!("Executing command {}/{}...", index + 1, commands.len());
             }
 
             let result = match self.execute_single(conn, command, &context) {
-                Ok(result) => result,
+                Ok(result) => CommandExecutionResult { index, ..result },
                 Err(e) => CommandExecutionResult {
                     index,
                     success: false,
@@ -161,6 +223,10 @@ impl SequentialExecutor {
         let mut results = Vec::new();
 
         for (index, command) in commands.iter().enumerate() {
+            // Ctrl-C stops before the next command
+            if cancel::requested() {
+                return Ok(ExecutionSummary::new(commands.len(), results, context).mark_cancelled());
+            }
             if self.verbose {
                 println!("Executing command {}/{}...", index + 1, commands.len());
             }
@@ -200,6 +266,10 @@ impl SequentialExecutor {
         let cond_executor = ConditionalExecutor::new(self.verbose);
 
         for (index, command) in commands.iter().enumerate() {
+            // Ctrl-C stops before the next command
+            if cancel::requested() {
+                return Ok(ExecutionSummary::new(commands.len(), results, context).mark_cancelled());
+            }
             if self.verbose {
                 println!("Evaluating command {}/{}...", index + 1, commands.len());
             }
@@ -371,7 +441,7 @@ fn execute_parsed_command(conn: &Connection, args: &[String]) -> Result<(), Stri
     let parsed_args = CliArgs::try_parse_from(cmd_args)
         .map_err(|e| format!("Failed to parse generated command: {}", e))?;
 
-    crate::actions::handler::handle_commands(conn, parsed_args)
+    crate::actions::handler::handle_commands(conn, parsed_args).map_err(|e| e.to_string())
 }
 
 impl Default for SequentialExecutor {
@@ -386,6 +456,9 @@ impl Default for SequentialExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
 
     #[test]
     fn test_sequential_context_default() {
@@ -587,4 +660,96 @@ mod tests {
 
         assert_eq!(resolved.modifications.get("deadline"), Some(&"tomorrow".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_run_bounded_keeps_order() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let total = MAX_PARALLEL * 2 + 1;
+        // The first steps take the longest, so they finish last
+        let steps: Vec<_> = (0..total)
+            .map(|index| {
+                let running = running.clone();
+                let most = most.clone();
+                async move {
+                    most.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10 * (total - index) as u64)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    index
+                }
+            })
+            .collect();
+
+        let outputs = run_bounded(steps).await.unwrap();
+        assert_eq!(outputs, (0..total).collect::<Vec<_>>());
+        assert_eq!(most.load(Ordering::SeqCst), MAX_PARALLEL);
+    }
+
+    #[tokio::test]
+    async fn test_run_bounded_cancelled() {
+        // A flag of its own, the process wide one would cancel the other tests
+        let flag = Arc::new(AtomicBool::new(false));
+        let started = Arc::new(AtomicUsize::new(0));
+        let finished = Arc::new(AtomicUsize::new(0));
+        let steps: Vec<_> = (0..MAX_PARALLEL * 2)
+            .map(|_| {
+                let started = started.clone();
+                let finished = finished.clone();
+                async move {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    finished.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .collect();
+
+        let cancel_soon = {
+            let flag = flag.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                flag.store(true, Ordering::SeqCst);
+            }
+        };
+        let (result, _) =
+            tokio::join!(cancel::abortable_by(&flag, run_bounded(steps)), cancel_soon);
+        assert!(matches!(result, Err(NLPError::Cancelled)));
+
+        // The steps in flight were dropped and the others never started
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(started.load(Ordering::SeqCst), MAX_PARALLEL);
+        assert_eq!(finished.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_execute_parallel() {
+        let (conn, _temp_file) = crate::tests::get_test_conn();
+        let task = |content: &str| NLPCommand {
+            action: ActionType::Task,
+            content: content.to_string(),
+            category: Some("home".to_string()),
+            ..Default::default()
+        };
+        let missing = NLPCommand {
+            action: ActionType::Done,
+            content: "99".to_string(),
+            ..Default::default()
+        };
+        let commands = [task("water plants"), missing, task("call mom")];
+
+        let summary = SequentialExecutor::new(false, false)
+            .execute_compound(&conn, &commands, &CompoundExecutionMode::Parallel, false)
+            .unwrap();
+        // A failure does not stop the others, and results stay in command order
+        let outcomes: Vec<(usize, bool)> =
+            summary.results.iter().map(|r| (r.index, r.success)).collect();
+        assert_eq!(outcomes, [(0, true), (1, false), (2, true)]);
+        let contents: Vec<String> = conn
+            .prepare("SELECT content FROM items ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(contents, ["water plants", "call mom"]);
+    }
 }
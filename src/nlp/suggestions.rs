@@ -352,11 +352,12 @@ impl SuggestionEngine {
     }
 }
 
-/// Auto-completer for shell integration
+/// Auto-completer for shell integration and the interactive mode, ranking inputs from the
+/// user's history and categories by how much they are used
 pub struct AutoCompleter {
-    /// Available categories
+    /// Available categories, the most used first
     categories: Vec<String>,
-    /// Recent command history
+    /// Recent command history, the newest or most used last
     history: Vec<String>,
     /// Max history size
     max_history: usize,
@@ -381,6 +382,15 @@ impl AutoCompleter {
         }
     }
 
+    /// Create from stored usage: categories and past inputs, both the most used first
+    pub fn from_history(categories: Vec<String>, history: Vec<String>) -> Self {
+        let mut completer = Self::with_categories(categories);
+        for input in history.into_iter().rev() {
+            completer.add_to_history(input);
+        }
+        completer
+    }
+
     /// Update available categories
     pub fn update_categories(&mut self, categories: Vec<String>) {
         self.categories = categories;
@@ -388,6 +398,8 @@ impl AutoCompleter {
 
     /// Add a command to history
     pub fn add_to_history(&mut self, command: String) {
+        // A repeated command moves up rather than taking a second place
+        self.history.retain(|c| *c != command);
         self.history.push(command);
         if self.history.len() > self.max_history {
             self.history.remove(0);
@@ -396,15 +408,7 @@ impl AutoCompleter {
 
     /// Get completions for input
     pub fn complete(&self, input: &str) -> Vec<String> {
-        let request = SuggestionRequest {
-            input: input.to_string(),
-            cursor_position: input.len(),
-            recent_commands: self.history.clone(),
-            available_categories: self.categories.clone(),
-        };
-
-        let result = SuggestionEngine::suggest(&request);
-        result.suggestions.into_iter().map(|s| s.text).collect()
+        self.suggest(input).suggestions.into_iter().map(|s| s.text).collect()
     }
 
     /// Get detailed suggestions
@@ -416,7 +420,56 @@ impl AutoCompleter {
             available_categories: self.categories.clone(),
         };
 
-        SuggestionEngine::suggest(&request)
+        let mut result = SuggestionEngine::suggest(&request);
+        let mut suggestions = self.history_suggestions(input);
+        suggestions.extend(self.category_suggestions(input));
+        suggestions.append(&mut result.suggestions);
+        suggestions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+        let mut seen = std::collections::HashSet::new();
+        suggestions.retain(|s| seen.insert(s.text.trim().to_lowercase()));
+        suggestions.truncate(8);
+        result.suggestions = suggestions;
+        result
+    }
+
+    /// Past inputs starting with the input, the newest or most used first
+    fn history_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let input_lower = input.trim_start().to_lowercase();
+        self.history
+            .iter()
+            .rev()
+            .filter(|c| c.to_lowercase().starts_with(&input_lower) && c.len() > input_lower.len())
+            .enumerate()
+            .map(|(rank, c)| Suggestion {
+                text: c.clone(),
+                suggestion_type: SuggestionType::SimilarCommand,
+                confidence: 0.99 - rank as f64 * 0.01,
+                description: "From your history".to_string(),
+            })
+            .collect()
+    }
+
+    /// The last word completed to a category, the most used first
+    fn category_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (head, word) = match input.rfind(' ') {
+            Some(space) => input.split_at(space + 1),
+            None => ("", input),
+        };
+        if word.is_empty() {
+            return Vec::new();
+        }
+        let word_lower = word.to_lowercase();
+        self.categories
+            .iter()
+            .filter(|c| c.to_lowercase().starts_with(&word_lower) && c.len() > word.len())
+            .enumerate()
+            .map(|(rank, c)| Suggestion {
+                text: format!("{}{}", head, c),
+                suggestion_type: SuggestionType::AvailableOption,
+                confidence: 0.97 - rank as f64 * 0.01,
+                description: format!("Category {}", c),
+            })
+            .collect()
     }
 }
 
@@ -550,6 +603,24 @@ mod tests {
         assert_eq!(completer.history.len(), 3);
     }
 
+    #[test]
+    fn test_auto_completer_from_history() {
+        let completer = AutoCompleter::from_history(
+            vec!["work".to_string(), "workshop".to_string(), "home".to_string()],
+            vec!["list work tasks".to_string(), "list home tasks".to_string()],
+        );
+        let completions = completer.complete("list");
+        assert_eq!(completions[..2], ["list work tasks", "list home tasks"]);
+
+        let completions = completer.complete("add task call mom wo");
+        assert_eq!(completions[..2], ["add task call mom work", "add task call mom workshop"]);
+
+        let mut completer = completer;
+        completer.add_to_history("list home tasks".to_string());
+        assert_eq!(completer.complete("list")[0], "list home tasks");
+        assert_eq!(completer.history.len(), 2);
+    }
+
     #[test]
     fn test_auto_completer_complete() {
         let completer = AutoCompleter::new();
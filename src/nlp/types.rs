@@ -292,6 +292,9 @@ pub struct ExecutionSummary {
     pub results: Vec<CommandExecutionResult>,
     /// Final context state
     pub final_context: SequentialContext,
+    /// Whether Ctrl-C stopped it before the last command
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 impl ExecutionSummary {
@@ -305,9 +308,16 @@ impl ExecutionSummary {
             failed,
             results,
             final_context,
+            cancelled: false,
         }
     }
 
+    /// Mark the execution as stopped by Ctrl-C
+    pub fn mark_cancelled(mut self) -> Self {
+        self.cancelled = true;
+        self
+    }
+
     /// Whether all commands succeeded
     pub fn is_complete_success(&self) -> bool {
         self.failed == 0
@@ -458,6 +468,16 @@ pub struct NLPConfig {
     pub auto_confirm: bool,
     /// Whether to show NLP interpretation transparency
     pub show_transparency: bool,
+    /// Provider asked when this one fails or has no key, such as a local Ollama server
+    #[serde(default)]
+    pub fallback: Option<Box<NLPConfig>>,
+}
+
+impl NLPConfig {
+    /// Whether there is a provider to ask, either with a key or as the fallback
+    pub fn has_provider(&self) -> bool {
+        self.api_key.as_ref().is_some_and(|k| !k.is_empty()) || self.fallback.is_some()
+    }
 }
 
 impl Default for NLPConfig {
@@ -475,6 +495,7 @@ impl Default for NLPConfig {
             preview_enabled: true,
             auto_confirm: false,
             show_transparency: true,
+            fallback: None,
         }
     }
 }
@@ -511,6 +532,9 @@ pub enum NLPError {
 
     #[error("Request timeout after {0} seconds")]
     Timeout(u64),
+
+    #[error("Cancelled")]
+    Cancelled,
 }
 
 /// Disambiguation information for ambiguous inputs
@@ -790,6 +814,7 @@ mod tests {
             preview_enabled: false,
             auto_confirm: true,
             show_transparency: false,
+            fallback: None,
         };
 
         assert!(config.enabled);
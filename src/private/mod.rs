@@ -0,0 +1,278 @@
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use chacha20poly1305::{
+    aead::{
+        Aead,
+        AeadCore,
+        KeyInit,
+        OsRng,
+    },
+    ChaCha20Poly1305,
+    Key,
+    Nonce,
+};
+
+use crate::{
+    config,
+    error::TascliError,
+    tr,
+};
+
+/// Starts the content of private items, followed by the hex nonce and ciphertext.
+pub const PRIVATE_PREFIX: &str = "tascli-private:v1:";
+
+// Both live next to the config, outside the data dir that may be synced to other machines
+const KEY_FILE: &str = "private.key";
+const UNLOCK_FILE: &str = "unlocked_until";
+const NONCE_LEN: usize = 12;
+
+pub fn is_private(content: &str) -> bool {
+    content.starts_with(PRIVATE_PREFIX)
+}
+
+/// Encrypts `content` with the local key, creating the key on first use.
+pub fn encrypt(content: &str) -> Result<String, TascliError> {
+    Vault::local()?.encrypt(content)
+}
+
+/// Content to show for an item, "[private]" for private content while locked.
+pub fn reveal(content: &str) -> String {
+    if !is_private(content) {
+        return content.to_string();
+    }
+    Vault::local()
+        .ok()
+        .filter(|vault| vault.is_unlocked(now()))
+        .and_then(|vault| vault.decrypt(content).ok())
+        .unwrap_or_else(|| tr!("private-content"))
+}
+
+/// Content for messages that leave the machine, such as chat and email, never decrypted.
+pub fn conceal(content: &str) -> String {
+    if is_private(content) {
+        tr!("private-content")
+    } else {
+        content.to_string()
+    }
+}
+
+/// Applies `edit` to the plain content, private content stays private.
+pub fn edit(content: &str, edit: impl FnOnce(String) -> String) -> Result<String, TascliError> {
+    if !is_private(content) {
+        return Ok(edit(content.to_string()));
+    }
+    let vault = Vault::local()?;
+    vault.encrypt(&edit(vault.decrypt(content)?))
+}
+
+/// Shows private content for the next `minutes`.
+pub fn unlock(minutes: u64) -> Result<i64, TascliError> {
+    let until = now() + minutes as i64 * 60;
+    Vault::local()?.unlock(until)?;
+    Ok(until)
+}
+
+pub fn lock() -> Result<(), TascliError> {
+    Vault::local()?.lock()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+struct Vault {
+    dir: PathBuf,
+}
+
+impl Vault {
+    fn local() -> Result<Self, TascliError> {
+        let config_path = config::get_config_path()?;
+        let dir = config_path
+            .parent()
+            .ok_or_else(|| TascliError::Config("cannot find config directory".to_string()))?;
+        Ok(Vault {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    fn cipher(&self, create: bool) -> Result<ChaCha20Poly1305, TascliError> {
+        let path = self.dir.join(KEY_FILE);
+        if create && !path.exists() {
+            let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+            fs::create_dir_all(&self.dir)
+                .and_then(|_| write_secret(&path, to_hex(&key).as_bytes()))
+                .map_err(|e| TascliError::io(format!("Failed to write {}", path.display()), e))?;
+        }
+        let hex = fs::read_to_string(&path)
+            .map_err(|e| TascliError::io(format!("Failed to read {}", path.display()), e))?;
+        let key = from_hex(hex.trim())
+            .filter(|key| key.len() == 32)
+            .ok_or_else(|| TascliError::Config(format!("{} is not a valid key", path.display())))?;
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    fn encrypt(&self, content: &str) -> Result<String, TascliError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(true)?
+            .encrypt(&nonce, content.as_bytes())
+            .map_err(|_| TascliError::Invalid("Failed to encrypt content".to_string()))?;
+        Ok(format!("{}{}{}", PRIVATE_PREFIX, to_hex(&nonce), to_hex(&ciphertext)))
+    }
+
+    fn decrypt(&self, content: &str) -> Result<String, TascliError> {
+        let invalid = || TascliError::Invalid(tr!("private-undecryptable"));
+        let bytes = content
+            .strip_prefix(PRIVATE_PREFIX)
+            .and_then(from_hex)
+            .filter(|bytes| bytes.len() > NONCE_LEN)
+            .ok_or_else(invalid)?;
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plain = self
+            .cipher(false)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| invalid())?;
+        String::from_utf8(plain).map_err(|_| invalid())
+    }
+
+    fn is_unlocked(&self, now: i64) -> bool {
+        fs::read_to_string(self.dir.join(UNLOCK_FILE))
+            .ok()
+            .and_then(|until| until.trim().parse::<i64>().ok())
+            .is_some_and(|until| now < until)
+    }
+
+    fn unlock(&self, until: i64) -> Result<(), TascliError> {
+        // Nothing can be shown without the key, usually only missing before the first private item
+        self.cipher(false)?;
+        let path = self.dir.join(UNLOCK_FILE);
+        fs::write(&path, until.to_string())
+            .map_err(|e| TascliError::io(format!("Failed to write {}", path.display()), e))
+    }
+
+    fn lock(&self) -> Result<(), TascliError> {
+        let path = self.dir.join(UNLOCK_FILE);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(TascliError::io(format!("Failed to remove {}", path.display()), e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// The key is only readable by its owner
+#[cfg(unix)]
+fn write_secret(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_secret(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(dir: &tempfile::TempDir) -> Vault {
+        Vault {
+            dir: dir.path().join("tascli"),
+        }
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault(&dir);
+
+        let first = vault.encrypt("see the doctor about it").unwrap();
+        let second = vault.encrypt("see the doctor about it").unwrap();
+        assert!(is_private(&first));
+        assert!(!first.contains("doctor"));
+        assert_ne!(first, second);
+        assert_eq!(vault.decrypt(&first).unwrap(), "see the doctor about it");
+        assert_eq!(vault.decrypt(&second).unwrap(), "see the doctor about it");
+    }
+
+    #[test]
+    fn test_decrypt_with_other_key_fails() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let encrypted = vault(&first).encrypt("secret").unwrap();
+        vault(&second).encrypt("other").unwrap();
+
+        assert!(vault(&second).decrypt(&encrypted).is_err());
+        let tampered = encrypted.replacen(PRIVATE_PREFIX, &format!("{}00", PRIVATE_PREFIX), 1);
+        assert!(vault(&first).decrypt(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_unlock_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault(&dir);
+        assert!(vault.unlock(2000).is_err(), "no key to unlock yet");
+        vault.encrypt("secret").unwrap();
+
+        assert!(!vault.is_unlocked(1000));
+        vault.unlock(2000).unwrap();
+        assert!(vault.is_unlocked(1999));
+        assert!(!vault.is_unlocked(2000));
+        vault.lock().unwrap();
+        assert!(!vault.is_unlocked(1000));
+        vault.lock().unwrap();
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(from_hex("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("0f0"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[test]
+    fn test_public_content_is_unchanged() {
+        assert_eq!(reveal("buy milk"), "buy milk");
+        assert_eq!(conceal("buy milk"), "buy milk");
+        assert_eq!(conceal(&format!("{}00", PRIVATE_PREFIX)), "[private]");
+        assert_eq!(edit("buy milk", |c| c + " and eggs").unwrap(), "buy milk and eggs");
+    }
+}
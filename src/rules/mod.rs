@@ -0,0 +1,376 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::print_yellow,
+        list::OPEN_STATUS_CODES,
+    },
+    args::{
+        parser::parse_status,
+        timestr,
+    },
+    config::{
+        ChatConfig,
+        Config,
+    },
+    db::{
+        crud::{
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+    },
+    events,
+    integrations::chat,
+    private,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum Subject {
+    Task,
+    Record,
+    Item,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Trigger {
+    /// one of the events in crate::events
+    Event(&'static str),
+    /// open task past its deadline by at least this many seconds
+    Overdue(i64),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RuleAction {
+    SetDeadline(String),
+    SetCategory(String),
+    SetStatus(u8),
+    Notify,
+}
+
+/// A rule written as "when <subject> [in category <category>] <trigger> then <action>",
+/// e.g. "when task in category finance is created then set deadline eom".
+#[derive(Debug, PartialEq)]
+pub struct Rule {
+    pub text: String,
+    pub subject: Subject,
+    pub category: Option<String>,
+    pub trigger: Trigger,
+    pub action: RuleAction,
+}
+
+impl Rule {
+    fn matches(&self, item: &Item) -> bool {
+        let subject_matches = match self.subject {
+            Subject::Task => item.action == TASK || item.action == RECURRING_TASK,
+            Subject::Record => item.action == RECORD || item.action == RECURRING_TASK_RECORD,
+            Subject::Item => true,
+        };
+        let category_matches = self
+            .category
+            .as_ref()
+            .is_none_or(|c| c.eq_ignore_ascii_case(&item.category));
+        subject_matches && category_matches
+    }
+}
+
+pub fn parse_rule(text: &str) -> Result<Rule, String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let then = tokens
+        .iter()
+        .position(|t| t.eq_ignore_ascii_case("then"))
+        .ok_or_else(|| "expected 'then' before the action".to_string())?;
+    let (condition, action) = (&tokens[..then], &tokens[then + 1..]);
+
+    let lowered: Vec<String> = condition.iter().map(|t| t.to_lowercase()).collect();
+    let mut rest: Vec<&str> = lowered.iter().map(String::as_str).collect();
+    if rest.first() != Some(&"when") {
+        return Err("a rule starts with 'when'".to_string());
+    }
+    rest.remove(0);
+
+    let subject = match rest.first().copied() {
+        Some("task" | "tasks") => Subject::Task,
+        Some("record" | "records") => Subject::Record,
+        Some("item" | "items") => Subject::Item,
+        other => {
+            return Err(format!(
+                "expected task, record or item after 'when', found '{}'",
+                other.unwrap_or_default()
+            ))
+        }
+    };
+    rest.remove(0);
+
+    let mut category = None;
+    if rest.starts_with(&["in", "category"]) {
+        // Keep the category as written, the keywords are matched case insensitively
+        category = Some(
+            condition
+                .get(4)
+                .ok_or_else(|| "expected a category after 'in category'".to_string())?
+                .to_string(),
+        );
+        rest.drain(..3);
+    }
+    if rest.first() == Some(&"is") {
+        rest.remove(0);
+    }
+
+    let trigger = match rest.as_slice() {
+        ["created"] => Trigger::Event(events::CREATE),
+        ["done" | "completed"] => Trigger::Event(events::DONE),
+        ["deleted"] => Trigger::Event(events::DELETE),
+        ["overdue"] => Trigger::Overdue(0),
+        ["overdue", duration] | ["overdue", "by", duration] => {
+            Trigger::Overdue(timestr::parse_duration(duration)?)
+        }
+        _ => {
+            return Err(format!(
+                "unknown trigger '{}', expected created, done, deleted or overdue <duration>",
+                rest.join(" ")
+            ))
+        }
+    };
+    if matches!(trigger, Trigger::Overdue(_)) && subject != Subject::Task {
+        return Err("only tasks can be overdue".to_string());
+    }
+
+    let action = parse_action(action)?;
+    if trigger == Trigger::Event(events::DELETE) && action != RuleAction::Notify {
+        return Err("deleted items can only be notified about".to_string());
+    }
+
+    Ok(Rule {
+        text: text.trim().to_string(),
+        subject,
+        category,
+        trigger,
+        action,
+    })
+}
+
+fn parse_action(tokens: &[&str]) -> Result<RuleAction, String> {
+    let keyword = |i: usize| tokens.get(i).map(|t| t.to_lowercase());
+    match (keyword(0).as_deref(), keyword(1).as_deref()) {
+        (Some("notify"), None) => Ok(RuleAction::Notify),
+        (Some("set"), Some("deadline")) if tokens.len() > 2 => {
+            let timestr = tokens[2..].join(" ");
+            timestr::to_unix_epoch(&timestr)?;
+            Ok(RuleAction::SetDeadline(timestr))
+        }
+        (Some("set"), Some("category")) if tokens.len() == 3 => {
+            Ok(RuleAction::SetCategory(tokens[2].to_string()))
+        }
+        (Some("set"), Some("status")) if tokens.len() == 3 => {
+            let status = parse_status(tokens[2])?;
+            // Aggregates like open or closed only make sense when listing
+            if status > 6 {
+                return Err(format!("'{}' is not a single status", tokens[2]));
+            }
+            Ok(RuleAction::SetStatus(status))
+        }
+        _ => Err(format!(
+            "unknown action '{}', expected set deadline|category|status <value> or notify",
+            tokens.join(" ")
+        )),
+    }
+}
+
+// Invalid rules are reported on every run rather than silently ignored.
+fn parsed_rules(config: &Config) -> Vec<Rule> {
+    config
+        .rules
+        .iter()
+        .filter_map(|text| match parse_rule(text) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                print_yellow(&format!("Skipping rule '{}': {}", text, e));
+                None
+            }
+        })
+        .collect()
+}
+
+/// Runs the rules triggered by an event, changes made by a rule are saved
+/// but do not emit events of their own.
+pub fn apply_event_rules(conn: &Connection, config: &Config, event: &str, item: &mut Item) {
+    for rule in parsed_rules(config) {
+        if matches!(rule.trigger, Trigger::Event(e) if e == event)
+            && rule.matches(item)
+            && let Err(e) = execute(conn, &config.chat, &rule, item)
+        {
+            print_yellow(&format!("Rule '{}' failed: {}", rule.text, e));
+        }
+    }
+}
+
+/// Runs the overdue rules against every open task, returns how many times a rule fired.
+/// Rules fire again on each run for as long as the task stays overdue.
+pub fn run_overdue_rules(conn: &Connection, config: &Config, now: i64) -> Result<usize, String> {
+    let mut fired = 0;
+    for rule in parsed_rules(config) {
+        let Trigger::Overdue(by) = rule.trigger else {
+            continue;
+        };
+        let mut query = ItemQuery::new()
+            .with_action(TASK)
+            .with_statuses(OPEN_STATUS_CODES.to_vec())
+            .with_target_time_max(now - by);
+        if let Some(c) = &rule.category {
+            query = query.with_category(c);
+        }
+
+        for mut task in query_items(conn, &query).map_err(|e| e.to_string())? {
+            execute(conn, &config.chat, &rule, &mut task)
+                .map_err(|e| format!("Rule '{}' failed: {}", rule.text, e))?;
+            fired += 1;
+        }
+    }
+    Ok(fired)
+}
+
+fn execute(
+    conn: &Connection,
+    chat: &[ChatConfig],
+    rule: &Rule,
+    item: &mut Item,
+) -> Result<(), String> {
+    match &rule.action {
+        RuleAction::SetDeadline(t) if item.action == TASK => {
+            item.set_target_time(Some(timestr::to_unix_epoch(t)?));
+        }
+        RuleAction::SetCategory(c) => item.category = c.clone(),
+        RuleAction::SetStatus(s) if item.action == TASK => item.status = *s,
+        // Deadlines and statuses do not apply to records or recurring tasks
+        RuleAction::SetDeadline(_) | RuleAction::SetStatus(_) => return Ok(()),
+        RuleAction::Notify => {
+            notify(chat, rule, item);
+            return Ok(());
+        }
+    }
+    update_item(conn, item).map_err(|e| e.to_string())
+}
+
+fn notify(chat: &[ChatConfig], rule: &Rule, item: &Item) {
+    let text = format!(
+        "🔔 {}: [{}] {}",
+        rule.text,
+        item.category,
+        private::conceal(&item.content).lines().next().unwrap_or_default()
+    );
+    print_yellow(&text);
+    for channel in chat.iter().filter(|c| chat::handles_category(c, &item.category)) {
+        if let Err(e) = chat::post_message(channel, &text) {
+            print_yellow(&format!("Chat notification to {} failed: {}", channel.kind, e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    fn config_with(rules: &[&str]) -> Config {
+        Config {
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        assert_eq!(
+            parse_rule("when task in category Finance is created then set deadline eom").unwrap(),
+            Rule {
+                text: "when task in category Finance is created then set deadline eom".to_string(),
+                subject: Subject::Task,
+                category: Some("Finance".to_string()),
+                trigger: Trigger::Event(events::CREATE),
+                action: RuleAction::SetDeadline("eom".to_string()),
+            }
+        );
+        let rule = parse_rule("When tasks overdue 7d then notify").unwrap();
+        assert_eq!(rule.trigger, Trigger::Overdue(7 * 86400));
+        assert_eq!(rule.action, RuleAction::Notify);
+        let rule = parse_rule("when item is done then set category archive").unwrap();
+        assert_eq!(rule.subject, Subject::Item);
+        assert_eq!(rule.action, RuleAction::SetCategory("archive".to_string()));
+        let rule = parse_rule("when task is overdue by 2w then set status suspended").unwrap();
+        assert_eq!(rule.trigger, Trigger::Overdue(14 * 86400));
+        assert_eq!(rule.action, RuleAction::SetStatus(4));
+        assert_eq!(
+            parse_rule("when task is created then set deadline tomorrow 9am")
+                .unwrap()
+                .action,
+            RuleAction::SetDeadline("tomorrow 9am".to_string())
+        );
+
+        assert!(parse_rule("task is created then notify").is_err());
+        assert!(parse_rule("when task is created").is_err());
+        assert!(parse_rule("when note is created then notify").is_err());
+        assert!(parse_rule("when task is renamed then notify").is_err());
+        assert!(parse_rule("when record overdue 7d then notify").is_err());
+        assert!(parse_rule("when task overdue 7x then notify").is_err());
+        assert!(parse_rule("when task is deleted then set category x").is_err());
+        assert!(parse_rule("when task is created then set deadline someday").is_err());
+        assert!(parse_rule("when task is created then set status open").is_err());
+        assert!(parse_rule("when task is created then archive").is_err());
+    }
+
+    #[test]
+    fn test_apply_event_rules() {
+        let (conn, _temp_file) = get_test_conn();
+        let config = config_with(&[
+            "when task in category finance is created then set deadline eom",
+            "when record is created then set category journal",
+            "not a rule",
+        ]);
+
+        let id = insert_task(&conn, "finance", "pay invoice", "today");
+        let mut task = get_item(&conn, id).unwrap();
+        apply_event_rules(&conn, &config, events::CREATE, &mut task);
+        let eom = timestr::to_unix_epoch("eom").unwrap();
+        assert_eq!(task.target_time, Some(eom));
+        assert_eq!(get_item(&conn, id).unwrap().target_time, Some(eom));
+
+        // Other categories and events are left alone
+        let id = insert_task(&conn, "work", "write report", "today");
+        let mut task = get_item(&conn, id).unwrap();
+        apply_event_rules(&conn, &config, events::CREATE, &mut task);
+        assert_eq!(task.target_time, Some(timestr::to_unix_epoch("today").unwrap()));
+        assert_eq!(task.category, "work");
+    }
+
+    #[test]
+    fn test_run_overdue_rules() {
+        let (conn, _temp_file) = get_test_conn();
+        let config = config_with(&[
+            "when task overdue 7d then set status suspended",
+            "when task in category home is overdue then notify",
+        ]);
+        let old = insert_task(&conn, "work", "old task", "2020/01/01");
+        let recent = insert_task(&conn, "work", "recent task", "yesterday");
+        let home = insert_task(&conn, "home", "fix sink", "yesterday");
+
+        let now = chrono::Local::now().timestamp();
+        assert_eq!(run_overdue_rules(&conn, &config, now).unwrap(), 2);
+        assert_eq!(get_item(&conn, old).unwrap().status, 4);
+        assert_eq!(get_item(&conn, recent).unwrap().status, 0);
+        assert_eq!(get_item(&conn, home).unwrap().status, 0);
+    }
+}
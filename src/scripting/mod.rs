@@ -0,0 +1,276 @@
+use std::{
+    fs,
+    path::PathBuf,
+    rc::Rc,
+    time::Duration,
+};
+
+use rhai::{
+    Array,
+    Dynamic,
+    Engine,
+    EvalAltResult,
+    Map,
+    Scope,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::print_yellow,
+        list::OPEN_STATUS_CODES,
+    },
+    args::timestr,
+    config::{
+        expand_path,
+        Config,
+        ScriptConfig,
+    },
+    db::{
+        crud::{
+            insert_item,
+            query_items,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
+
+// Keeps a runaway script from hanging the command that triggered it
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const SCRIPT_QUERY_LIMIT: usize = 1000;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Runs the scripts subscribed to `event` with `event` and `item` in scope.
+/// Failures are reported but never fail the command that emitted the event.
+pub fn run_event_scripts(conn: &Connection, config: &Config, event: &str, item: &Item) {
+    for script in config.scripts.iter().filter(|s| s.events.iter().any(|e| e == event)) {
+        let mut scope = Scope::new();
+        scope.push("event", event.to_string());
+        scope.push("item", item_to_map(item));
+        if let Err(e) = run_script(conn, script, scope) {
+            print_yellow(&format!("Script {} failed: {}", script.path, e));
+        }
+    }
+}
+
+/// Finds the script registered as `tascli <command>`.
+pub fn find_command<'a>(config: &'a Config, command: &str) -> Option<&'a ScriptConfig> {
+    config
+        .scripts
+        .iter()
+        .find(|s| !s.command.is_empty() && s.command == command)
+}
+
+/// Runs a script as a command with its arguments in `args`.
+pub fn run_command(
+    conn: &Connection,
+    script: &ScriptConfig,
+    args: &[String],
+) -> Result<(), TascliError> {
+    let mut scope = Scope::new();
+    let args: Array = args.iter().cloned().map(Dynamic::from).collect();
+    scope.push("args", args);
+    run_script(conn, script, scope)
+}
+
+fn run_script(conn: &Connection, script: &ScriptConfig, mut scope: Scope) -> Result<(), TascliError> {
+    let path = resolve_path(&script.path)?;
+    let source = fs::read_to_string(&path)
+        .map_err(|e| TascliError::io(format!("Failed to read {}", path.display()), e))?;
+
+    // Registered functions must own their connection, the caller's stays borrowed
+    let db_path = conn
+        .path()
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| TascliError::Invalid("Scripts need a database file".to_string()))?;
+    let script_conn = Connection::open(db_path)?;
+    script_conn.busy_timeout(Duration::from_secs(5))?;
+
+    build_engine(Rc::new(script_conn))
+        .run_with_scope(&mut scope, &source)
+        .map_err(|e| TascliError::Invalid(format!("{}: {}", path.display(), e)))
+}
+
+fn resolve_path(path: &str) -> Result<PathBuf, TascliError> {
+    expand_path(path)?.ok_or_else(|| {
+        TascliError::Config(format!(
+            "script path must be absolute or home relative, it cannot be '{}'",
+            path
+        ))
+    })
+}
+
+// The api available to scripts. There is no file or process access, items created
+// here do not emit events so scripts cannot trigger themselves.
+fn build_engine(conn: Rc<Connection>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.disable_symbol("eval");
+
+    let db = conn.clone();
+    engine.register_fn("tasks", move || open_tasks(&db, None));
+    let db = conn.clone();
+    engine.register_fn("tasks", move |category: &str| open_tasks(&db, Some(category)));
+    let db = conn.clone();
+    engine.register_fn(
+        "create_task",
+        move |category: &str, content: &str, timestr: &str| -> ScriptResult<i64> {
+            let target_time = timestr::to_unix_epoch(timestr)?;
+            let task = Item::with_target_time(
+                TASK.to_string(),
+                category.to_string(),
+                content.to_string(),
+                Some(target_time),
+            );
+            Ok(insert_item(&db, &task).map_err(|e| e.to_string())?)
+        },
+    );
+    let db = conn;
+    engine.register_fn(
+        "create_record",
+        move |category: &str, content: &str| -> ScriptResult<i64> {
+            let record = Item::new(RECORD.to_string(), category.to_string(), content.to_string());
+            Ok(insert_item(&db, &record).map_err(|e| e.to_string())?)
+        },
+    );
+    engine
+}
+
+fn open_tasks(conn: &Connection, category: Option<&str>) -> ScriptResult<Array> {
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_limit(SCRIPT_QUERY_LIMIT);
+    if let Some(category) = category {
+        query = query.with_category(category);
+    }
+    let tasks = query_items(conn, &query).map_err(|e| e.to_string())?;
+    Ok(tasks.iter().map(|t| Dynamic::from_map(item_to_map(t))).collect())
+}
+
+fn item_to_map(item: &Item) -> Map {
+    let optional = |v: Option<i64>| v.map(Dynamic::from).unwrap_or(Dynamic::UNIT);
+    let mut map = Map::new();
+    map.insert("id".into(), optional(item.id));
+    map.insert("uuid".into(), item.uuid.clone().into());
+    map.insert("action".into(), item.action.clone().into());
+    map.insert("category".into(), item.category.clone().into());
+    map.insert("content".into(), item.content.clone().into());
+    map.insert("status".into(), (item.status as i64).into());
+    map.insert("create_time".into(), item.create_time.into());
+    map.insert("target_time".into(), optional(item.target_time));
+    map.insert(
+        "human_schedule".into(),
+        item.human_schedule.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map.insert(
+        "owner".into(),
+        item.owner.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map.insert(
+        "value".into(),
+        item.value.map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map.insert(
+        "unit".into(),
+        item.unit.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    fn script(dir: &tempfile::TempDir, source: &str) -> ScriptConfig {
+        let path = dir.path().join("script.rhai");
+        fs::write(&path, source).unwrap();
+        ScriptConfig {
+            path: path.display().to_string(),
+            events: vec!["done".to_string()],
+            command: "standup".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_run_command_queries_and_creates_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let dir = tempfile::tempdir().unwrap();
+        insert_task(&conn, "work", "first", "today");
+        insert_task(&conn, "home", "second", "today");
+        let script = script(
+            &dir,
+            r#"
+                let open = tasks("work");
+                create_record("standup", `${args[0]}: ${open.len()} open, ${tasks().len()} total`);
+                create_task("work", "follow up on " + open[0].content, "tomorrow");
+            "#,
+        );
+
+        run_command(&conn, &script, &["monday".to_string()]).unwrap();
+
+        let record = get_item(&conn, 3).unwrap();
+        assert_eq!(record.action, RECORD);
+        assert_eq!(record.content, "monday: 1 open, 2 total");
+        let task = get_item(&conn, 4).unwrap();
+        assert_eq!(task.content, "follow up on first");
+        assert!(task.target_time.is_some());
+    }
+
+    #[test]
+    fn test_run_event_scripts_only_for_subscribed_events() {
+        let (conn, _temp_file) = get_test_conn();
+        let dir = tempfile::tempdir().unwrap();
+        let id = insert_task(&conn, "work", "ship it", "today");
+        let item = get_item(&conn, id).unwrap();
+        let config = Config {
+            scripts: vec![script(&dir, r#"create_record(item.category, event + " " + item.content);"#)],
+            ..Default::default()
+        };
+
+        run_event_scripts(&conn, &config, "create", &item);
+        run_event_scripts(&conn, &config, "done", &item);
+
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].content, "done ship it");
+    }
+
+    #[test]
+    fn test_runaway_script_is_stopped() {
+        let (conn, _temp_file) = get_test_conn();
+        let dir = tempfile::tempdir().unwrap();
+        let script = script(&dir, "loop { }");
+
+        let err = run_command(&conn, &script, &[]).unwrap_err();
+        assert!(err.to_string().contains("Too many operations"), "{}", err);
+    }
+
+    #[test]
+    fn test_find_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            scripts: vec![script(&dir, "")],
+            ..Default::default()
+        };
+        assert!(find_command(&config, "standup").is_some());
+        assert!(find_command(&config, "").is_none());
+        assert!(find_command(&config, "sync").is_none());
+    }
+}
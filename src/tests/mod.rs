@@ -3,6 +3,7 @@ use tempfile::NamedTempFile;
 
 use crate::{
     args::timestr,
+    clock,
     db::{
         conn::init_table,
         crud::{
@@ -22,6 +23,12 @@ pub fn get_test_conn() -> (Connection, NamedTempFile) {
     (conn, temp_file)
 }
 
+// Pins the clock to a local time such as "2026/10/15 12:00". Every test runs on a thread of
+// its own, so the others keep the system clock.
+pub fn pin_clock(local: &str) {
+    clock::set_now(Some(timestr::to_unix_epoch(local).unwrap()));
+}
+
 pub fn insert_task(conn: &Connection, category: &str, content: &str, timestr: &str) -> i64 {
     let target_time = timestr::to_unix_epoch(timestr).unwrap();
     let new_task = Item::with_target_time(